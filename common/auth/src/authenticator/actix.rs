@@ -10,10 +10,10 @@ pub async fn openid_validator(
     auth: BearerAuth,
     authenticator: Arc<Authenticator>,
 ) -> Result<ServiceRequest, (actix_web::Error, ServiceRequest)> {
-    match authenticator.validate_token(auth.token()).await {
-        Ok(payload) => {
+    match authenticator.authenticate(auth.token()).await {
+        Ok(details) => {
             req.extensions_mut()
-                .insert(UserInformation::Authenticated(payload.into()));
+                .insert(UserInformation::Authenticated(details));
             Ok(req)
         }
 
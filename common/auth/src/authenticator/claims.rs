@@ -43,6 +43,7 @@ impl From<ValidatedAccessToken> for UserDetails {
         Self {
             id: token.access_token.sub,
             permissions: token.permissions,
+            api_token: false,
         }
     }
 }
@@ -15,6 +15,7 @@ pub const DEFAULT_SCOPE_MAPPINGS: &[(&str, &[&str])] = &[
             "create.metadata",
             "create.sbom",
             "create.weakness",
+            "import.snapshot",
             "upload.dataset",
         ],
     ),
@@ -22,7 +23,9 @@ pub const DEFAULT_SCOPE_MAPPINGS: &[(&str, &[&str])] = &[
         "read:document",
         &[
             "ai",
+            "export.snapshot",
             "read.advisory",
+            "read.audit",
             "read.importer",
             "read.metadata",
             "read.sbom",
@@ -32,6 +35,7 @@ pub const DEFAULT_SCOPE_MAPPINGS: &[(&str, &[&str])] = &[
     (
         "update:document",
         &[
+            "reprocess.document",
             "update.advisory",
             "update.importer",
             "update.metadata",
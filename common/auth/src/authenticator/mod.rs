@@ -10,10 +10,14 @@ pub use default::*;
 pub mod actix;
 pub mod config;
 pub mod error;
+pub mod token;
 pub mod user;
 
 use crate::{
-    authenticator::claims::ValidatedAccessToken, authenticator::config::AuthenticatorConfig,
+    authenticator::claims::ValidatedAccessToken,
+    authenticator::config::AuthenticatorConfig,
+    authenticator::token::{ApiTokenValidator, is_api_token},
+    authenticator::user::UserDetails,
 };
 use anyhow::anyhow;
 use biscuit::jws::Compact;
@@ -24,7 +28,7 @@ use futures_util::{StreamExt, TryStreamExt, stream};
 use jsonpath_rust::{JsonPath, JsonPathValue};
 use openid::{Client, Configurable, Discovered, Empty, Jws};
 use serde_json::Value;
-use std::{collections::HashMap, ops::Deref, str::FromStr};
+use std::{collections::HashMap, ops::Deref, str::FromStr, sync::Arc};
 use tracing::instrument;
 use trustify_common::reqwest::ClientFactory;
 
@@ -32,11 +36,21 @@ use trustify_common::reqwest::ClientFactory;
 #[derive(Clone)]
 pub struct Authenticator {
     pub clients: Vec<AuthenticatorClient>,
+    api_tokens: Option<Arc<dyn ApiTokenValidator>>,
 }
 
 impl Authenticator {
     fn from_clients(clients: Vec<AuthenticatorClient>) -> Self {
-        Self { clients }
+        Self {
+            clients,
+            api_tokens: None,
+        }
+    }
+
+    /// Accept API tokens, in addition to OIDC access tokens.
+    pub fn with_api_tokens(mut self, validator: Arc<dyn ApiTokenValidator>) -> Self {
+        self.api_tokens = Some(validator);
+        self
     }
 
     pub async fn from_config(config: Option<AuthenticatorConfig>) -> anyhow::Result<Option<Self>> {
@@ -118,6 +132,33 @@ impl Authenticator {
         Ok(client)
     }
 
+    /// Authenticate a bearer token, which may either be an API token or an OIDC access token.
+    pub async fn authenticate<S: AsRef<str>>(
+        &self,
+        token: S,
+    ) -> Result<UserDetails, AuthenticationError> {
+        let token = token.as_ref();
+
+        match &self.api_tokens {
+            Some(api_tokens) if is_api_token(token) => api_tokens
+                .validate_api_token(token)
+                .await
+                .map_err(|err| {
+                    log::warn!("Failed to validate API token: {err}");
+                    AuthenticationError::Failed
+                })?
+                .ok_or(AuthenticationError::Failed),
+            Some(api_tokens) => {
+                let user: UserDetails = self.validate_token(token).await?.into();
+                if let Err(err) = api_tokens.observe_user(&user).await {
+                    log::warn!("Failed to observe user '{}': {err}", user.id);
+                }
+                Ok(user)
+            }
+            None => Ok(self.validate_token(token).await?.into()),
+        }
+    }
+
     /// Validate a bearer token.
     #[instrument(level = "debug", skip_all, fields(token = token.as_ref()), ret)]
     pub async fn validate_token<S: AsRef<str>>(
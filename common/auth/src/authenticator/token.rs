@@ -0,0 +1,33 @@
+//! Support for API tokens, issued by the application itself.
+
+use crate::authenticator::user::UserDetails;
+use async_trait::async_trait;
+
+/// The prefix of API tokens, allowing to tell them apart from OIDC access tokens.
+pub const API_TOKEN_PREFIX: &str = "trustify_";
+
+/// Check if a bearer token is an API token (vs an OIDC access token).
+pub fn is_api_token(token: &str) -> bool {
+    token.starts_with(API_TOKEN_PREFIX)
+}
+
+/// Validates API tokens.
+///
+/// The authenticator doesn't know how API tokens are stored, this is provided by an
+/// implementation of this trait.
+#[async_trait]
+pub trait ApiTokenValidator: Send + Sync {
+    /// Validate an API token, returning the details of the user the token was issued for.
+    ///
+    /// The permissions of the user details must be those granted to the token. Returns `None`
+    /// if the token is unknown, revoked, or expired.
+    async fn validate_api_token(&self, token: &str) -> anyhow::Result<Option<UserDetails>>;
+
+    /// Observe a user which was authenticated using an OIDC access token.
+    ///
+    /// This allows an implementation to track the current permissions of a user, so that the
+    /// permissions of their tokens can be limited to those.
+    async fn observe_user(&self, _user: &UserDetails) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
@@ -31,6 +31,8 @@ use crate::authenticator::error::AuthorizationError;
 pub struct UserDetails {
     pub id: String,
     pub permissions: Vec<String>,
+    /// If the user was authenticated using an API token, rather than an OIDC access token
+    pub api_token: bool,
 }
 
 impl UserDetails {
@@ -103,11 +103,22 @@ permission! {
         #[strum(serialize = "upload.dataset")]
         UploadDataset,
 
+        #[strum(serialize = "export.snapshot")]
+        ExportSnapshot,
+        #[strum(serialize = "import.snapshot")]
+        ImportSnapshot,
+
+        #[strum(serialize = "reprocess.document")]
+        ReprocessDocument,
+
         #[strum(serialize = "ai")]
         Ai,
 
         #[strum(serialize = "delete.vulnerability")]
         DeleteVulnerability,
+
+        #[strum(serialize = "read.audit")]
+        ReadAudit,
     }
 }
 
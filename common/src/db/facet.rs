@@ -0,0 +1,245 @@
+use crate::db::query::{Columns, Error, IntoColumns};
+use crate::model::FacetCount;
+use sea_orm::{ColumnType, ConnectionTrait, DbErr, QueryTrait, sea_query};
+use sea_query::{
+    Alias, Expr, Func, Order, SelectStatement, SimpleExpr, extension::postgres::PgExpr,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use utoipa::IntoParams;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct Facets {
+    /// Form expected: `{facet}*`
+    ///
+    /// where `{facet}` is of the form `{field}[:{key}]`. Multiple facets should be
+    /// `,`-delimited.
+    ///
+    /// Each `{field}` name must correspond to one of the Columns that can be used for
+    /// filtering. Items of an array field are counted by each of their elements. The
+    /// optional `{key}` counts a JSON field by the values of that key, e.g. `labels:type`,
+    /// or by its keys if omitted. For a timestamp field, the `{key}` is one of `year`,
+    /// `month` or `day`, counting its values truncated to that precision.
+    #[serde(default)]
+    pub facets: String,
+    /// The maximum number of values to return for each facet, the most frequent first.
+    ///
+    /// Zero means: no limit
+    #[serde(default = "default::limit")]
+    pub limit: u64,
+}
+
+mod default {
+    pub(super) const fn limit() -> u64 {
+        10
+    }
+}
+
+/// Count the results of a select by the values of facets
+pub trait FacetingTrait<'db, C>
+where
+    C: ConnectionTrait,
+{
+    fn faceting<I: IntoColumns>(
+        self,
+        db: &'db C,
+        facets: &Facets,
+        context: I,
+    ) -> Result<FacetCounter<'db, C>, Error>;
+}
+
+impl<'db, C, T> FacetingTrait<'db, C> for T
+where
+    C: ConnectionTrait,
+    T: QueryTrait<QueryStatement = SelectStatement>,
+{
+    fn faceting<I: IntoColumns>(
+        self,
+        db: &'db C,
+        facets: &Facets,
+        context: I,
+    ) -> Result<FacetCounter<'db, C>, Error> {
+        let columns = context.columns();
+        let select = self.into_query();
+
+        let statements = facets
+            .facets
+            .split_terminator(',')
+            .map(|facet| {
+                let value = value(facet, &columns)?;
+                Ok((
+                    facet.to_string(),
+                    count(select.clone(), value, facets.limit),
+                ))
+            })
+            .collect::<Result<_, Error>>()?;
+
+        Ok(FacetCounter { db, statements })
+    }
+}
+
+pub struct FacetCounter<'db, C>
+where
+    C: ConnectionTrait,
+{
+    db: &'db C,
+    statements: Vec<(String, SelectStatement)>,
+}
+
+impl<C> FacetCounter<'_, C>
+where
+    C: ConnectionTrait,
+{
+    /// Fetch the counts of each facet, by the name it was requested with
+    pub async fn fetch(self) -> Result<BTreeMap<String, Vec<FacetCount>>, DbErr> {
+        let backend = self.db.get_database_backend();
+        let mut result = BTreeMap::new();
+
+        for (facet, statement) in self.statements {
+            let mut counts = vec![];
+            for row in self.db.query_all(backend.build(&statement)).await? {
+                counts.push(FacetCount {
+                    value: row.try_get("", "value")?,
+                    count: row.try_get::<i64>("", "count")? as u64,
+                });
+            }
+            result.insert(facet, counts);
+        }
+
+        Ok(result)
+    }
+}
+
+/// The expression for the value of a facet, resulting in either a text or a set of texts
+fn value(facet: &str, columns: &Columns) -> Result<SimpleExpr, Error> {
+    let (field, key) = match facet.split_once(':') {
+        Some((field, key)) => (field, Some(key)),
+        None => (facet, None),
+    };
+    let (expr, def) = columns.for_field(field)?;
+    let text = |expr: SimpleExpr| expr.cast_as(Alias::new("text"));
+    let function = |name: &str, args: Vec<SimpleExpr>| -> SimpleExpr {
+        Func::cust(Alias::new(name)).args(args).into()
+    };
+
+    Ok(match (def.get_column_type(), key) {
+        (ColumnType::Json | ColumnType::JsonBinary, Some(key)) => {
+            Expr::expr(expr).cast_json_field(key)
+        }
+        (ColumnType::Json | ColumnType::JsonBinary, None) => {
+            function("jsonb_object_keys", vec![expr.cast_as(Alias::new("jsonb"))])
+        }
+        (ColumnType::Array(_), None) => text(function("unnest", vec![expr.into()])),
+        (
+            ColumnType::Date
+            | ColumnType::DateTime
+            | ColumnType::Timestamp
+            | ColumnType::TimestampWithTimeZone,
+            Some(precision),
+        ) => {
+            let format = match precision {
+                "year" => "YYYY",
+                "month" => "YYYY-MM",
+                "day" => "YYYY-MM-DD",
+                _ => {
+                    return Err(Error::SearchSyntax(format!(
+                        "Invalid facet precision: '{precision}'"
+                    )));
+                }
+            };
+            function("to_char", vec![expr.into(), Expr::val(format).into()])
+        }
+        (_, None) => text(expr.into()),
+        (_, Some(_)) => {
+            return Err(Error::SearchSyntax(format!("Invalid facet: '{facet}'")));
+        }
+    })
+}
+
+/// Count the rows of a select by a value, the most frequent values first
+fn count(mut select: SelectStatement, value: SimpleExpr, limit: u64) -> SelectStatement {
+    select
+        .clear_selects()
+        .clear_order_by()
+        .expr_as(value, Alias::new("value"));
+
+    let mut count = sea_query::Query::select();
+    count
+        .column(Alias::new("value"))
+        .expr_as(Expr::cust("COUNT(*)"), Alias::new("count"))
+        .from_subquery(select, Alias::new("facet"))
+        .group_by_col(Alias::new("value"))
+        .order_by(Alias::new("count"), Order::Desc)
+        .order_by(Alias::new("value"), Order::Asc);
+    if limit > 0 {
+        count.limit(limit);
+    }
+    count
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::db::query::{Filtering, q};
+    use sea_orm::{EntityTrait, entity::prelude::*};
+    use test_log::test;
+    use time::OffsetDateTime;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+    #[sea_orm(table_name = "sbom")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: Uuid,
+        pub name: String,
+        pub published: Option<OffsetDateTime>,
+        pub authors: Vec<String>,
+        #[sea_orm(column_type = "JsonBinary")]
+        pub labels: serde_json::Value,
+    }
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+    impl ActiveModelBehavior for ActiveModel {}
+
+    fn sql(facet: &str) -> Result<String, Error> {
+        let columns = Entity.columns();
+        let select = Entity::find().filtering_with(q("name~foo"), columns.clone())?;
+        let value = value(facet, &columns)?;
+        Ok(count(select.into_query(), value, 10).to_string(sea_query::PostgresQueryBuilder))
+    }
+
+    #[test(tokio::test)]
+    async fn facets() -> Result<(), anyhow::Error> {
+        let from = r#"FROM (SELECT {} AS "value" FROM "sbom" WHERE "sbom"."name" ILIKE '%foo%') AS "facet" GROUP BY "value" ORDER BY "count" DESC, "value" ASC LIMIT 10"#;
+        let expected = |value: &str| {
+            format!(
+                r#"SELECT "value", COUNT(*) AS "count" {}"#,
+                from.replace("{}", value)
+            )
+        };
+
+        assert_eq!(sql("name")?, expected(r#"CAST("sbom"."name" AS text)"#));
+        assert_eq!(
+            sql("authors")?,
+            expected(r#"CAST(unnest("sbom"."authors") AS text)"#)
+        );
+        assert_eq!(
+            sql("labels")?,
+            expected(r#"jsonb_object_keys(CAST("sbom"."labels" AS jsonb))"#)
+        );
+        assert_eq!(
+            sql("labels:type")?,
+            expected(r#""sbom"."labels" ->> 'type'"#)
+        );
+        assert_eq!(
+            sql("published:year")?,
+            expected(r#"to_char("sbom"."published", 'YYYY')"#)
+        );
+
+        assert!(sql("published:week").is_err());
+        assert!(sql("name:foo").is_err());
+        assert!(sql("foo").is_err());
+
+        Ok(())
+    }
+}
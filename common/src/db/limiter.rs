@@ -1,4 +1,6 @@
 use crate::db::multi_model::{FromQueryResultMultiModel, SelectIntoMultiModel};
+use crate::db::query::{self, Keyset};
+use crate::model::Paginated;
 use sea_orm::{
     ConnectionTrait, DbErr, EntityTrait, FromQueryResult, Paginator, PaginatorTrait, QuerySelect,
     Select, SelectModel, SelectTwo, SelectTwoModel, Selector, SelectorTrait,
@@ -14,6 +16,9 @@ where
     db: &'db C,
     selector: Selector<S1>,
     paginator: Paginator<'db, C, S2>,
+    /// The keyset and page size, when paginating by cursors
+    keyset: Option<(Keyset, u64)>,
+    count: bool,
 }
 
 impl<'db, C, S1, S2> Limiter<'db, C, S1, S2>
@@ -23,11 +28,43 @@ where
     S2: SelectorTrait + 'db,
 {
     pub async fn fetch(self) -> Result<Vec<S1::Item>, DbErr> {
-        self.selector.all(self.db).await
+        Ok(self.fetch_page().await?.0)
     }
 
-    pub async fn total(&self) -> Result<u64, DbErr> {
-        self.paginator.num_items().await
+    /// Fetch the items, along with the cursor to the next page, if
+    /// there is one and the limiter paginates by a keyset.
+    pub async fn fetch_page(self) -> Result<(Vec<S1::Item>, Option<String>), DbErr> {
+        let Some((keyset, limit)) = self.keyset else {
+            return Ok((self.selector.all(self.db).await?, None));
+        };
+
+        let stmt = self.selector.into_statement(self.db.get_database_backend());
+        let mut rows = self.db.query_all(stmt).await?;
+
+        // the keyset fetches one more item than the limit, if available
+        let next = match limit > 0 && rows.len() as u64 > limit {
+            true => {
+                rows.truncate(limit as usize);
+                rows.last().map(|row| keyset.cursor(row)).transpose()?
+            }
+            false => None,
+        };
+
+        let items = rows
+            .into_iter()
+            .map(S1::from_raw_query_result)
+            .collect::<Result<_, _>>()?;
+
+        Ok((items, next))
+    }
+
+    /// Count the total number of items, or `None` if counting was
+    /// disabled by the [`Paginated::total`] flag.
+    pub async fn total(&self) -> Result<Option<u64>, DbErr> {
+        match self.count {
+            true => Ok(Some(self.paginator.num_items().await?)),
+            false => Ok(None),
+        }
     }
 }
 
@@ -38,12 +75,23 @@ where
     type FetchSelector: SelectorTrait + 'db;
     type CountSelector: SelectorTrait + 'db;
 
+    /// Limit the results to the offset and limit of the [`Paginated`],
+    /// counting the total only if it asks for it.
     fn limiting(
         self,
         db: &'db C,
-        offset: u64,
-        limit: u64,
+        paginated: &Paginated,
     ) -> Limiter<'db, C, Self::FetchSelector, Self::CountSelector>;
+
+    /// Limit the results to a page, continuing after the cursor of
+    /// the [`Paginated`] if it has one. The keyset replaces any order
+    /// of the results.
+    fn paginating(
+        self,
+        db: &'db C,
+        paginated: &Paginated,
+        keyset: Keyset,
+    ) -> Result<Limiter<'db, C, Self::FetchSelector, Self::CountSelector>, query::Error>;
 }
 
 impl<'db, C, E, M> LimiterTrait<'db, C> for Select<E>
@@ -58,21 +106,39 @@ where
     fn limiting(
         self,
         db: &'db C,
-        offset: u64,
-        limit: u64,
+        paginated: &Paginated,
     ) -> Limiter<'db, C, Self::FetchSelector, Self::CountSelector> {
         let selector = self
             .clone()
-            .limit(NonZeroU64::new(limit).map(|limit| limit.get()))
-            .offset(NonZeroU64::new(offset).map(|offset| offset.get()))
+            .limit(NonZeroU64::new(paginated.limit).map(|limit| limit.get()))
+            .offset(NonZeroU64::new(paginated.offset).map(|offset| offset.get()))
             .into_model();
 
         Limiter {
             db,
             paginator: self.paginate(db, 1),
             selector,
+            keyset: None,
+            count: paginated.total,
         }
     }
+
+    fn paginating(
+        self,
+        db: &'db C,
+        paginated: &Paginated,
+        keyset: Keyset,
+    ) -> Result<Limiter<'db, C, Self::FetchSelector, Self::CountSelector>, query::Error> {
+        let selector = keyset.apply(self.clone(), paginated)?.into_model();
+
+        Ok(Limiter {
+            db,
+            paginator: self.paginate(db, 1),
+            selector,
+            keyset: Some((keyset, paginated.limit)),
+            count: paginated.total,
+        })
+    }
 }
 
 pub trait LimiterAsModelTrait<'db, C>
@@ -82,16 +148,22 @@ where
     fn limiting_as<M: FromQueryResult + Sync + Send>(
         self,
         db: &'db C,
-        offset: u64,
-        limit: u64,
+        paginated: &Paginated,
     ) -> Limiter<'db, C, SelectModel<M>, SelectModel<M>>;
 
     fn try_limiting_as_multi_model<M: FromQueryResultMultiModel + Sync + Send>(
         self,
         db: &'db C,
-        offset: u64,
-        limit: u64,
+        paginated: &Paginated,
     ) -> Result<Limiter<'db, C, SelectModel<M>, SelectModel<M>>, DbErr>;
+
+    /// Like [`LimiterTrait::paginating`], but into a custom model
+    fn paginating_as<M: FromQueryResult + Sync + Send>(
+        self,
+        db: &'db C,
+        paginated: &Paginated,
+        keyset: Keyset,
+    ) -> Result<Limiter<'db, C, SelectModel<M>, SelectModel<M>>, query::Error>;
 }
 
 impl<'db, C, E> LimiterAsModelTrait<'db, C> for Select<E>
@@ -102,38 +174,57 @@ where
     fn limiting_as<M: FromQueryResult + Sync + Send>(
         self,
         db: &'db C,
-        offset: u64,
-        limit: u64,
+        paginated: &Paginated,
     ) -> Limiter<'db, C, SelectModel<M>, SelectModel<M>> {
         let selector = self
             .clone()
-            .limit(NonZeroU64::new(limit).map(|limit| limit.get()))
-            .offset(NonZeroU64::new(offset).map(|offset| offset.get()))
+            .limit(NonZeroU64::new(paginated.limit).map(|limit| limit.get()))
+            .offset(NonZeroU64::new(paginated.offset).map(|offset| offset.get()))
             .into_model::<M>();
 
         Limiter {
             db,
             paginator: self.into_model::<M>().paginate(db, 1),
             selector,
+            keyset: None,
+            count: paginated.total,
         }
     }
 
     fn try_limiting_as_multi_model<M: FromQueryResultMultiModel + Sync + Send>(
         self,
         db: &'db C,
-        offset: u64,
-        limit: u64,
+        paginated: &Paginated,
     ) -> Result<Limiter<'db, C, SelectModel<M>, SelectModel<M>>, DbErr> {
         let selector = self
             .clone()
-            .limit(NonZeroU64::new(limit).map(|limit| limit.get()))
-            .offset(NonZeroU64::new(offset).map(|offset| offset.get()))
+            .limit(NonZeroU64::new(paginated.limit).map(|limit| limit.get()))
+            .offset(NonZeroU64::new(paginated.offset).map(|offset| offset.get()))
             .try_into_multi_model::<M>()?;
 
         Ok(Limiter {
             db,
             paginator: self.into_model::<M>().paginate(db, 1),
             selector,
+            keyset: None,
+            count: paginated.total,
+        })
+    }
+
+    fn paginating_as<M: FromQueryResult + Sync + Send>(
+        self,
+        db: &'db C,
+        paginated: &Paginated,
+        keyset: Keyset,
+    ) -> Result<Limiter<'db, C, SelectModel<M>, SelectModel<M>>, query::Error> {
+        let selector = keyset.apply(self.clone(), paginated)?.into_model::<M>();
+
+        Ok(Limiter {
+            db,
+            paginator: self.into_model::<M>().paginate(db, 1),
+            selector,
+            keyset: Some((keyset, paginated.limit)),
+            count: paginated.total,
         })
     }
 }
@@ -141,8 +232,7 @@ where
 pub fn limit_selector<'db, C, E, EM, M>(
     db: &'db C,
     select: Select<E>,
-    offset: u64,
-    limit: u64,
+    paginated: &Paginated,
 ) -> Limiter<'db, C, SelectModel<M>, SelectModel<EM>>
 where
     C: ConnectionTrait,
@@ -152,14 +242,16 @@ where
 {
     let selector = select
         .clone()
-        .limit(NonZeroU64::new(limit).map(|limit| limit.get()))
-        .offset(NonZeroU64::new(offset).map(|offset| offset.get()))
+        .limit(NonZeroU64::new(paginated.limit).map(|limit| limit.get()))
+        .offset(NonZeroU64::new(paginated.offset).map(|offset| offset.get()))
         .into_model();
 
     Limiter {
         db,
         paginator: select.paginate(db, 1),
         selector,
+        keyset: None,
+        count: paginated.total,
     }
 }
 
@@ -177,19 +269,37 @@ where
     fn limiting(
         self,
         db: &'db C,
-        offset: u64,
-        limit: u64,
+        paginated: &Paginated,
     ) -> Limiter<'db, C, Self::FetchSelector, Self::CountSelector> {
         let selector = self
             .clone()
-            .limit(NonZeroU64::new(limit).map(|limit| limit.get()))
-            .offset(NonZeroU64::new(offset).map(|offset| offset.get()))
+            .limit(NonZeroU64::new(paginated.limit).map(|limit| limit.get()))
+            .offset(NonZeroU64::new(paginated.offset).map(|offset| offset.get()))
             .into_model();
 
         Limiter {
             db,
             paginator: self.paginate(db, 1),
             selector,
+            keyset: None,
+            count: paginated.total,
         }
     }
+
+    fn paginating(
+        self,
+        db: &'db C,
+        paginated: &Paginated,
+        keyset: Keyset,
+    ) -> Result<Limiter<'db, C, Self::FetchSelector, Self::CountSelector>, query::Error> {
+        let selector = keyset.apply(self.clone(), paginated)?.into_model();
+
+        Ok(Limiter {
+            db,
+            paginator: self.paginate(db, 1),
+            selector,
+            keyset: Some((keyset, paginated.limit)),
+            count: paginated.total,
+        })
+    }
 }
@@ -2,6 +2,7 @@ mod func;
 
 pub mod chunk;
 pub mod embedded;
+pub mod facet;
 pub mod limiter;
 pub mod multi_model;
 pub mod query;
@@ -12,10 +13,11 @@ use anyhow::{Context, ensure};
 use migration::{Migrator, MigratorTrait};
 use sea_orm::{
     ConnectOptions, ConnectionTrait, DatabaseConnection, DbBackend, DbErr, ExecResult, QueryResult,
-    RuntimeErr, Statement, prelude::async_trait,
+    QueryStream, RuntimeErr, Statement, StreamTrait, prelude::async_trait,
 };
 use sqlx::error::ErrorKind;
 use std::ops::{Deref, DerefMut};
+use std::pin::Pin;
 use std::time::Duration;
 use tracing::instrument;
 
@@ -176,6 +178,19 @@ impl ConnectionTrait for Database {
     }
 }
 
+/// Implementation of the stream trait for our database struct, streaming the rows of a query
+/// from the database, rather than fetching all of them at once.
+impl StreamTrait for Database {
+    type Stream<'a> = QueryStream;
+
+    fn stream<'a>(
+        &'a self,
+        stmt: Statement,
+    ) -> Pin<Box<dyn Future<Output = Result<Self::Stream<'a>, DbErr>> + 'a + Send>> {
+        self.db.stream(stmt)
+    }
+}
+
 /// Implementation of the connection trait for our database struct.
 ///
 /// **NOTE**: We lack the implementations for the `mock` feature. However, the mock feature would
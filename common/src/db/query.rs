@@ -1,11 +1,13 @@
 mod columns;
 mod filter;
 mod filtering;
+mod keyset;
 mod sort;
 mod value;
 
 pub use columns::{Columns, IntoColumns};
 pub use filtering::Filtering;
+pub use keyset::Keyset;
 pub use value::Value;
 
 use filter::{Filter, Operator};
@@ -13,6 +15,7 @@ use regex::Regex;
 use serde::{Deserialize, Serialize};
 use sort::Sort;
 use std::collections::HashMap;
+use std::fmt::Display;
 use std::str::FromStr;
 use std::sync::OnceLock;
 use utoipa::{IntoParams, ToSchema};
@@ -37,7 +40,9 @@ impl Query {
     /// Multiple searches and/or filters should be `&`-delimited
     ///
     /// The `{search}` text will result in an OR clause of LIKE clauses
-    /// for every [String] field in the associated Columns. Optional
+    /// for every [String] field in the associated Columns. If they
+    /// declare full-text documents, see [`Columns::full_text`], it also
+    /// matches documents containing words starting with its words. Optional
     /// filters of the form `{field}{op}{value}` may further constrain
     /// the results. Each `{field}` name must correspond to one of the
     /// selected Columns.
@@ -50,6 +55,25 @@ impl Query {
     /// `{op}` should be one of `=`, `!=`, `~`, `!~, `>=`, `>`, `<=`,
     /// or `<`.
     ///
+    /// Queries prefixed with `expr:` may combine searches and filters
+    /// into boolean expressions, e.g.
+    /// `expr:(severity=critical|score>9)&!(title~test)`. A `|`
+    /// between filters on different fields results in an OR clause,
+    /// a leading `!` negates the following search, filter or group,
+    /// and parentheses group them. The `!` binds most tightly,
+    /// followed by `|` and then `&`. Whitespace is ignored around
+    /// parentheses and before a `!`. A negation matches everything
+    /// its operand doesn't, including the results lacking a value
+    /// for the negated field.
+    ///
+    /// Within such expressions, an alternate value that is neither a
+    /// filter, a negation nor a group still belongs to the preceding
+    /// search or filter, so `location=a|b` keeps its meaning. Any
+    /// literal `(` or `!` at the start of a search or value, or
+    /// unbalanced `)` within a group, should be escaped with a
+    /// backslash as well. Without the prefix, `(`, `)` and `!` have
+    /// no special meaning, and every `|` delimits alternate values.
+    ///
     pub fn q(s: &str) -> Self {
         Self {
             q: s.into(),
@@ -66,7 +90,9 @@ impl Query {
     /// Multiple sorts should be `,`-delimited
     ///
     /// Each `{field}` name must correspond to one of the selected
-    /// Columns.
+    /// Columns. If the Columns declare full-text documents, `rank`
+    /// sorts by how well the results match the `{search}` terms,
+    /// e.g. `rank:desc` for the most relevant results first.
     ///
     pub fn sort(self, s: &str) -> Self {
         Self {
@@ -78,86 +104,72 @@ impl Query {
     /// Apply the query to a mapping of field names to values,
     /// returning true if the context is successfully matched by the
     /// query, by either a filter or a full-text search of all the
-    /// values.
+    /// values. A query with a syntax error matches nothing.
     pub fn apply(&self, context: &HashMap<&'static str, Value>) -> bool {
-        use Operator::*;
-        self.parse().iter().all(|c| match c {
-            Constraint {
-                field: Some(f),
-                op: Some(o),
-                value: vs,
-            } => context.get(f.as_str()).is_some_and(|field| match o {
-                Equal => vs.iter().any(|v| field.eq(v)),
-                NotEqual => vs.iter().all(|v| field.ne(v)),
-                Like => vs.iter().any(|v| field.contains(v)),
-                NotLike => vs.iter().all(|v| !field.contains(v)),
-                GreaterThan => vs.iter().all(|v| field.gt(v)),
-                GreaterThanOrEqual => vs.iter().all(|v| field.ge(v)),
-                LessThan => vs.iter().all(|v| field.lt(v)),
-                LessThanOrEqual => vs.iter().all(|v| field.le(v)),
-                _ => false,
-            }),
-            Constraint {
-                field: None,
-                value: vs,
-                ..
-            } => context
-                .values()
-                .any(|field| vs.iter().any(|v| field.contains(v))),
-            _ => false,
-        })
+        self.parse()
+            .is_ok_and(|expression| expression.apply(context))
     }
 
-    fn parse(&self) -> Vec<Constraint> {
-        // regex for filters: {field}{op}{value}
-        const RE: &str = r"^(?<field>[[:word:]]+)(?<op>=|!=|~|!~|>=|>|<=|<)(?<value>.*)$";
-        static LOCK: OnceLock<Regex> = OnceLock::new();
-        #[allow(clippy::unwrap_used)]
-        let regex = LOCK.get_or_init(|| (Regex::new(RE).unwrap()));
+    /// The keys the results are sorted by, as requested by the
+    /// `{sort}`, in order to continue after the last item of a page.
+    /// Append a unique tie-breaker using [`Keyset::then`].
+    pub fn keyset<C: IntoColumns>(&self, context: C) -> Result<Keyset, Error> {
+        let columns = context
+            .columns()
+            .searching(self.searches().iter().map(String::as_str));
+        Ok(Keyset::new(self.sorts(&columns)?))
+    }
 
-        fn encode(s: &str) -> String {
-            s.replace(r"\&", "\x07").replace(r"\|", "\x08")
+    /// Check that the `{search}` and `{sort}` are valid for the
+    /// columns, without running a query.
+    pub fn validate<C: IntoColumns>(&self, context: C) -> Result<(), Error> {
+        let columns = context.columns();
+        if !self.q.is_empty() {
+            self.filter_for(&columns)?;
         }
-        fn decode(s: &str) -> String {
-            s.replace('\x07', "&")
-                .replace('\x08', "|")
-                .replace(r"\\", "\x08")
-                .replace('\\', "")
-                .replace('\x08', r"\")
+        let columns = columns.searching(self.searches().iter().map(String::as_str));
+        self.sorts(&columns)?;
+        Ok(())
+    }
+
+    fn parse(&self) -> Result<Expression, Error> {
+        match self.q.starts_with(Parser::PREFIX) {
+            true => Parser::new(&self.q, Parser::PREFIX.len()).parse(),
+            false => Ok(constraints(&self.q)),
         }
-        encode(&self.q)
-            .split_terminator('&')
-            .map(|s| {
-                if let Some(capture) = regex.captures(s) {
-                    // We have a filter: {field}{op}{value}
-                    let field = Some(capture["field"].into());
-                    #[allow(clippy::unwrap_used)] // regex ensures we won't panic
-                    let op = Some(Operator::from_str(&capture["op"]).unwrap());
-                    let value = capture["value"].split('|').map(decode).collect();
-                    Constraint { field, op, value }
-                } else {
-                    // We have a full-text search
-                    Constraint {
-                        field: None,
-                        op: None,
-                        value: s.split('|').map(decode).collect(),
-                    }
-                }
-            })
-            .collect()
     }
 
     fn filter_for(&self, columns: &Columns) -> Result<Filter, Error> {
-        let constraints = self.parse();
-        Ok(match constraints.len() {
-            1 => constraints[0].filter_for(columns)?,
-            _ => Filter::all(
-                constraints
-                    .iter()
-                    .map(|constraint| constraint.filter_for(columns))
-                    .collect::<Result<Vec<_>, _>>()?,
-            ),
-        })
+        self.parse()?.filter_for(columns)
+    }
+
+    fn sorts(&self, columns: &Columns) -> Result<Vec<Sort>, Error> {
+        self.sort
+            .split_terminator(',')
+            .map(|s| Sort::parse(s, columns))
+            .collect()
+    }
+
+    /// The values of the full-text searches which aren't negated
+    fn searches(&self) -> Vec<String> {
+        fn collect(expression: Expression, result: &mut Vec<String>) {
+            match expression {
+                Expression::Constraint(Constraint {
+                    field: None, value, ..
+                }) => result.extend(value),
+                Expression::And(operands) | Expression::Or(operands) => {
+                    for operand in operands {
+                        collect(operand, result);
+                    }
+                }
+                Expression::Constraint(_) | Expression::Not(_) => {}
+            }
+        }
+        let mut result = vec![];
+        if let Ok(expression) = self.parse() {
+            collect(expression, &mut result);
+        }
+        result
     }
 }
 
@@ -174,9 +186,59 @@ pub struct Query {
 pub enum Error {
     #[error("query syntax error: {0}")]
     SearchSyntax(String),
+    #[error("invalid cursor: '{0}'")]
+    InvalidCursor(String),
+}
+
+#[derive(Debug, PartialEq)]
+enum Expression {
+    Constraint(Constraint),
+    Not(Box<Expression>),
+    And(Vec<Expression>),
+    Or(Vec<Expression>),
 }
 
-#[derive(Debug)]
+impl Expression {
+    fn all(mut operands: Vec<Expression>) -> Self {
+        match operands.len() {
+            1 => operands.remove(0),
+            _ => Self::And(operands),
+        }
+    }
+
+    fn any(mut operands: Vec<Expression>) -> Self {
+        match operands.len() {
+            1 => operands.remove(0),
+            _ => Self::Or(operands),
+        }
+    }
+
+    fn apply(&self, context: &HashMap<&'static str, Value>) -> bool {
+        match self {
+            Self::Constraint(constraint) => constraint.apply(context),
+            Self::Not(operand) => !operand.apply(context),
+            Self::And(operands) => operands.iter().all(|e| e.apply(context)),
+            Self::Or(operands) => operands.iter().any(|e| e.apply(context)),
+        }
+    }
+
+    fn filter_for(&self, columns: &Columns) -> Result<Filter, Error> {
+        let filters = |operands: &Vec<Expression>| {
+            operands
+                .iter()
+                .map(|e| e.filter_for(columns))
+                .collect::<Result<Vec<_>, _>>()
+        };
+        Ok(match self {
+            Self::Constraint(constraint) => constraint.filter_for(columns)?,
+            Self::Not(operand) => Filter::not(operand.filter_for(columns)?),
+            Self::And(operands) => Filter::all(filters(operands)?),
+            Self::Or(operands) => Filter::any(filters(operands)?),
+        })
+    }
+}
+
+#[derive(Debug, PartialEq)]
 struct Constraint {
     field: Option<String>, // None for full-text searches
     op: Option<Operator>,  // None for full-text searches
@@ -184,6 +246,62 @@ struct Constraint {
 }
 
 impl Constraint {
+    /// The regex for filters: {field}{op}{value}
+    fn regex() -> &'static Regex {
+        const RE: &str = r"^(?<field>[[:word:]]+)(?<op>=|!=|~|!~|>=|>|<=|<)(?<value>.*)$";
+        static LOCK: OnceLock<Regex> = OnceLock::new();
+        #[allow(clippy::unwrap_used)]
+        LOCK.get_or_init(|| Regex::new(RE).unwrap())
+    }
+
+    /// Parse a single, still escaped, term of a query
+    fn parse(s: &str) -> Self {
+        if let Some(capture) = Self::regex().captures(s) {
+            // We have a filter: {field}{op}{value}
+            let field = Some(capture["field"].into());
+            #[allow(clippy::unwrap_used)] // regex ensures we won't panic
+            let op = Some(Operator::from_str(&capture["op"]).unwrap());
+            let value = vec![unescape(&capture["value"])];
+            Constraint { field, op, value }
+        } else {
+            // We have a full-text search
+            Constraint {
+                field: None,
+                op: None,
+                value: vec![unescape(s)],
+            }
+        }
+    }
+
+    fn apply(&self, context: &HashMap<&'static str, Value>) -> bool {
+        use Operator::*;
+        match self {
+            Constraint {
+                field: Some(f),
+                op: Some(o),
+                value: vs,
+            } => context.get(f.as_str()).is_some_and(|field| match o {
+                Equal => vs.iter().any(|v| field.eq(v)),
+                NotEqual => vs.iter().all(|v| field.ne(v)),
+                Like => vs.iter().any(|v| field.contains(v)),
+                NotLike => vs.iter().all(|v| !field.contains(v)),
+                GreaterThan => vs.iter().all(|v| field.gt(v)),
+                GreaterThanOrEqual => vs.iter().all(|v| field.ge(v)),
+                LessThan => vs.iter().all(|v| field.lt(v)),
+                LessThanOrEqual => vs.iter().all(|v| field.le(v)),
+                _ => false,
+            }),
+            Constraint {
+                field: None,
+                value: vs,
+                ..
+            } => context
+                .values()
+                .any(|field| vs.iter().any(|v| field.contains(v))),
+            _ => false,
+        }
+    }
+
     fn filter_for(&self, columns: &Columns) -> Result<Filter, Error> {
         match (&self.field, self.op) {
             // We have a filter of the form, {field}{op}{value}
@@ -197,6 +315,232 @@ impl Constraint {
     }
 }
 
+/// Remove the backslashes escaping the characters following them
+fn unescape(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => result.extend(chars.next()),
+            c => result.push(c),
+        }
+    }
+    result
+}
+
+/// Parse a query without the [`Parser::PREFIX`]: `&`-delimited
+/// constraints, each with `|`-delimited alternate values
+fn constraints(s: &str) -> Expression {
+    let mut terms = split(s, '&');
+    // A trailing '&' is ignored
+    if terms.last().is_some_and(|term| term.is_empty()) {
+        terms.pop();
+    }
+    Expression::all(
+        terms
+            .into_iter()
+            .map(|term| {
+                let mut values = split(term, '|').into_iter();
+                let mut constraint = Constraint::parse(values.next().unwrap_or_default());
+                constraint.value.extend(values.map(unescape));
+                Expression::Constraint(constraint)
+            })
+            .collect(),
+    )
+}
+
+/// Split at every separator which isn't escaped with a backslash
+fn split(s: &str, separator: char) -> Vec<&str> {
+    let mut result = vec![];
+    let mut start = 0;
+    let mut escaped = false;
+    for (i, c) in s.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            c if c == separator => {
+                result.push(&s[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    result.push(&s[start..]);
+    result
+}
+
+/// A recursive descent parser for the `q` grammar of queries with
+/// the [`Parser::PREFIX`]:
+///
+/// ```text
+/// conjunction := disjunction [ '&' disjunction ]*
+/// disjunction := negation [ '|' negation ]*
+/// negation    := '!' negation | '(' conjunction ')' | term
+/// term        := segment [ '|' segment ]*
+/// ```
+///
+/// where the alternate segments of a term are the ones which are
+/// neither filters, negations nor groups.
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser {
+    /// The prefix of queries using boolean expressions
+    const PREFIX: &'static str = "expr:";
+
+    fn new(s: &str, pos: usize) -> Self {
+        Self {
+            chars: s.chars().collect(),
+            pos,
+            depth: 0,
+        }
+    }
+
+    fn parse(mut self) -> Result<Expression, Error> {
+        let expression = self.conjunction()?;
+        match self.peek() {
+            None => Ok(expression),
+            Some(c) => Err(self.error(format!("unexpected '{c}'"))),
+        }
+    }
+
+    fn conjunction(&mut self) -> Result<Expression, Error> {
+        let mut operands = vec![];
+        // A trailing '&' is ignored
+        while !self.at_close() {
+            operands.push(self.disjunction()?);
+            if !self.eat('&') {
+                break;
+            }
+        }
+        Ok(Expression::all(operands))
+    }
+
+    fn disjunction(&mut self) -> Result<Expression, Error> {
+        let mut operands = vec![self.negation()?];
+        while self.eat('|') {
+            operands.push(self.negation()?);
+        }
+        Ok(Expression::any(operands))
+    }
+
+    fn negation(&mut self) -> Result<Expression, Error> {
+        if self.at_structural() {
+            self.skip_whitespace();
+        }
+        if self.eat('!') {
+            if self.at_close() || matches!(self.peek(), Some('&' | '|')) {
+                return Err(self.error("expected a constraint after '!'"));
+            }
+            return Ok(Expression::Not(Box::new(self.negation()?)));
+        }
+        if self.peek() == Some('(') {
+            let open = self.pos;
+            self.pos += 1;
+            self.skip_whitespace();
+            if self.peek() == Some(')') {
+                return Err(self.error("empty group"));
+            }
+            self.depth += 1;
+            let expression = self.conjunction()?;
+            self.depth -= 1;
+            if !self.eat(')') {
+                self.pos = open;
+                return Err(self.error("unclosed '('"));
+            }
+            self.skip_whitespace();
+            return Ok(expression);
+        }
+        Ok(Expression::Constraint(self.term()))
+    }
+
+    fn term(&mut self) -> Constraint {
+        let mut constraint = Constraint::parse(&self.segment());
+        loop {
+            let start = self.pos;
+            if self.eat('|') && !self.at_structural() {
+                let segment = self.segment();
+                if !Constraint::regex().is_match(&segment) {
+                    constraint.value.push(unescape(&segment));
+                    continue;
+                }
+            }
+            self.pos = start;
+            return constraint;
+        }
+    }
+
+    /// Everything up to the next unescaped '&', '|' or closing ')'
+    fn segment(&mut self) -> String {
+        let mut result = String::new();
+        // literal parentheses within the segment
+        let mut open = 0usize;
+        while let Some(mut c) = self.peek() {
+            match c {
+                '&' | '|' => break,
+                ')' if open == 0 && self.depth > 0 => break,
+                ')' => open = open.saturating_sub(1),
+                '(' => open += 1,
+                '\\' => {
+                    // keep the escape, it's removed once the segment is parsed
+                    result.push(c);
+                    self.pos += 1;
+                    match self.peek() {
+                        Some(escaped) => c = escaped,
+                        None => break,
+                    }
+                }
+                _ => {}
+            }
+            result.push(c);
+            self.pos += 1;
+        }
+        result
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        let found = self.peek() == Some(c);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    /// At the end of the query, or of the current group
+    fn at_close(&self) -> bool {
+        match self.peek() {
+            None => true,
+            Some(')') => self.depth > 0,
+            _ => false,
+        }
+    }
+
+    /// At a (possibly whitespace prefixed) negation or group
+    fn at_structural(&self) -> bool {
+        self.chars[self.pos..]
+            .iter()
+            .find(|c| !c.is_whitespace())
+            .is_some_and(|c| matches!(c, '!' | '('))
+    }
+
+    fn error(&self, message: impl Display) -> Error {
+        Error::SearchSyntax(format!("{message} at position {}", self.pos))
+    }
+}
+
 #[cfg(test)]
 pub(crate) mod tests {
     use super::*;
@@ -219,6 +563,144 @@ pub(crate) mod tests {
         Ok(())
     }
 
+    #[test(tokio::test)]
+    async fn boolean_expressions() -> Result<(), anyhow::Error> {
+        let clause = |query: &str| -> Result<String, anyhow::Error> {
+            Ok(advisory::Entity::find()
+                .select_only()
+                .column(advisory::Column::Id)
+                .filtering(q(query))?
+                .build(sea_orm::DatabaseBackend::Postgres)
+                .to_string()
+                .split("WHERE ")
+                .last()
+                .unwrap()
+                .to_string())
+        };
+
+        assert_eq!(
+            clause("expr:location=foo|score>9")?,
+            r#""advisory"."location" = 'foo' OR "advisory"."score" > 9"#
+        );
+        assert_eq!(
+            clause("expr:(location=foo|bar|score>9)&!(title~test)")?,
+            r#"("advisory"."location" = 'foo' OR "advisory"."location" = 'bar' OR "advisory"."score" > 9) AND ("advisory"."title" ILIKE '%test%') IS NOT TRUE"#
+        );
+        assert_eq!(
+            clause("expr:(location=foo&score<1) | !(score<=9)")?,
+            r#"("advisory"."location" = 'foo' AND "advisory"."score" < 1) OR ("advisory"."score" <= 9) IS NOT TRUE"#
+        );
+        assert_eq!(
+            clause(r"expr:\(foo)|\!bar")?,
+            r#"("advisory"."location" ILIKE '%(foo)%') OR ("advisory"."title" ILIKE '%(foo)%') OR ("advisory"."location" ILIKE '%!bar%') OR ("advisory"."title" ILIKE '%!bar%')"#
+        );
+        assert_eq!(
+            clause("expr:(title~foo (bar))")?,
+            r#""advisory"."title" ILIKE '%foo (bar)%'"#
+        );
+        // NULL values don't match a filter, so they match its negation
+        assert_eq!(
+            clause("expr:!published>2024-04-20")?,
+            r#"("advisory"."published" > '2024-04-20') IS NOT TRUE"#
+        );
+
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn syntax_errors() -> Result<(), anyhow::Error> {
+        let error = |query: &str| match q(query).parse() {
+            Err(Error::SearchSyntax(message)) => message,
+            Err(err) => panic!("The query '{query}' failed with {err:?}"),
+            Ok(expression) => panic!("The query '{query}' parsed to {expression:?}"),
+        };
+
+        assert_eq!(error("expr:(a=1|b=2"), "unclosed '(' at position 5");
+        assert_eq!(error("expr:a=1&(b=2|(c=3)"), "unclosed '(' at position 9");
+        assert_eq!(error("expr:a=1&()"), "empty group at position 10");
+        assert_eq!(
+            error("expr:a=1&!"),
+            "expected a constraint after '!' at position 10"
+        );
+        assert_eq!(
+            error("expr:!&a=1"),
+            "expected a constraint after '!' at position 6"
+        );
+        assert_eq!(error("expr:(a=1)b=2"), "unexpected 'b' at position 10");
+        assert_eq!(error("expr:(a=1))"), "unexpected ')' at position 10");
+
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn validate() -> Result<(), anyhow::Error> {
+        let columns = || Columns::from_entity::<advisory::Entity>();
+
+        q("foo&published>2024-04-20")
+            .sort("location,title:desc")
+            .validate(columns())?;
+        q("").validate(columns())?;
+
+        assert!(q("expr:(a=1").validate(columns()).is_err());
+        assert!(q("nope=1").validate(columns()).is_err());
+        assert!(q("foo").sort("nope").validate(columns()).is_err());
+
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn backwards_compatibility() -> Result<(), anyhow::Error> {
+        let constraint = |field: Option<&str>, op: Option<Operator>, value: &[&str]| {
+            Expression::Constraint(Constraint {
+                field: field.map(String::from),
+                op,
+                value: value.iter().map(|s| s.to_string()).collect(),
+            })
+        };
+
+        assert_eq!(
+            q("a|b&location=x|y&").parse()?,
+            Expression::And(vec![
+                constraint(None, None, &["a", "b"]),
+                constraint(Some("location"), Some(Operator::Equal), &["x", "y"]),
+            ])
+        );
+        assert_eq!(
+            q(r"foo)&location=f\&o\|o").parse()?,
+            Expression::And(vec![
+                constraint(None, None, &["foo)"]),
+                constraint(Some("location"), Some(Operator::Equal), &["f&o|o"]),
+            ])
+        );
+        assert_eq!(q("").parse()?, Expression::And(vec![]));
+
+        // without the prefix, there are no boolean expressions
+        assert_eq!(
+            q("location=a|!b|score>9").parse()?,
+            constraint(
+                Some("location"),
+                Some(Operator::Equal),
+                &["a", "!b", "score>9"]
+            )
+        );
+        assert_eq!(
+            q("(foo&!bar|(baz)").parse()?,
+            Expression::And(vec![
+                constraint(None, None, &["(foo"]),
+                constraint(None, None, &["!bar", "(baz)"]),
+            ])
+        );
+        assert_eq!(
+            q("a|b&&").parse()?,
+            Expression::And(vec![
+                constraint(None, None, &["a", "b"]),
+                constraint(None, None, &[""]),
+            ])
+        );
+
+        Ok(())
+    }
+
     /////////////////////////////////////////////////////////////////////////
     // Dummy Entity used for multiple tests in the crate
     /////////////////////////////////////////////////////////////////////////
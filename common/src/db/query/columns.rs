@@ -4,7 +4,9 @@ use std::fmt::{Display, Formatter};
 use sea_orm::entity::ColumnDef;
 use sea_orm::{ColumnTrait, ColumnType, EntityTrait, IntoIdentity, Iterable, sea_query};
 use sea_query::extension::postgres::PgExpr;
-use sea_query::{Alias, ColumnRef, Expr, IntoColumnRef, IntoIden};
+use sea_query::{
+    Alias, ColumnRef, DynIden, Expr, Func, IntoColumnRef, IntoIden, SimpleExpr, SubQueryStatement,
+};
 
 use super::Error;
 
@@ -12,8 +14,97 @@ use super::Error;
 #[derive(Default, Debug, Clone)]
 pub struct Columns {
     columns: Vec<(ColumnRef, ColumnDef)>,
+    named: BTreeMap<&'static str, (ColumnRef, ColumnDef)>,
     translator: Option<Translator>,
     json_keys: BTreeMap<&'static str, &'static str>,
+    full_text: Vec<FullText>,
+    rank: Option<SimpleExpr>,
+}
+
+/// A `tsvector` column used for full-text searches
+#[derive(Debug, Clone, PartialEq)]
+enum FullText {
+    /// A column of the selected tables
+    Column(ColumnRef),
+    /// A column of a related table, matching its rows where `foreign_key` equals `key`
+    Related {
+        table: DynIden,
+        document: DynIden,
+        foreign_key: DynIden,
+        key: ColumnRef,
+    },
+}
+
+impl FullText {
+    fn matches(&self, query: SimpleExpr) -> SimpleExpr {
+        match self {
+            Self::Column(document) => Expr::col(document.clone()).matches(query),
+            Self::Related {
+                table,
+                document,
+                foreign_key,
+                key,
+            } => Expr::exists(
+                sea_query::Query::select()
+                    .expr(Expr::val(1))
+                    .from(table.clone())
+                    .and_where(Expr::col((table.clone(), foreign_key.clone())).equals(key.clone()))
+                    .and_where(Expr::col((table.clone(), document.clone())).matches(query))
+                    .to_owned(),
+            ),
+        }
+    }
+
+    fn rank(&self, query: SimpleExpr) -> SimpleExpr {
+        let ts_rank = |document: ColumnRef| {
+            SimpleExpr::from(
+                Func::cust(Alias::new("ts_rank"))
+                    .arg(Expr::col(document))
+                    .arg(query.clone()),
+            )
+        };
+        let rank = match self {
+            Self::Column(document) => ts_rank(document.clone()),
+            Self::Related {
+                table,
+                document,
+                foreign_key,
+                key,
+            } => SimpleExpr::SubQuery(
+                None,
+                Box::new(SubQueryStatement::SelectStatement(
+                    sea_query::Query::select()
+                        .expr(Func::max(ts_rank(
+                            (table.clone(), document.clone()).into_column_ref(),
+                        )))
+                        .from(table.clone())
+                        .and_where(
+                            Expr::col((table.clone(), foreign_key.clone())).equals(key.clone()),
+                        )
+                        .to_owned(),
+                )),
+            ),
+        };
+        Func::coalesce([rank, Expr::val(0.0f32).into()]).into()
+    }
+}
+
+/// Turn a `{search}` into a `tsquery`, matching documents containing words starting with all
+/// of its words. Everything but letters and digits separates words.
+fn full_text_query(search: &str) -> Option<String> {
+    let words = search
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| format!("{}:*", word.to_lowercase()))
+        .collect::<Vec<_>>();
+    (!words.is_empty()).then(|| words.join(" & "))
+}
+
+fn to_tsquery(query: String) -> SimpleExpr {
+    Func::cust(Alias::new("to_tsquery"))
+        .arg(Expr::cust("'simple'"))
+        .arg(query)
+        .into()
 }
 
 impl Display for Columns {
@@ -76,8 +167,11 @@ impl Columns {
             .collect();
         Self {
             columns,
+            named: BTreeMap::new(),
             translator: None,
             json_keys: BTreeMap::new(),
+            full_text: vec![],
+            rank: None,
         }
     }
 
@@ -88,6 +182,18 @@ impl Columns {
         self
     }
 
+    /// Add a column under a different field name, e.g. a column of a joined table which has
+    /// the same name as one of the selected table.
+    pub fn add_column_as<C: IntoColumnRef>(
+        mut self,
+        column: C,
+        name: &'static str,
+        def: ColumnDef,
+    ) -> Self {
+        self.named.insert(name, (column.into_column_ref(), def));
+        self
+    }
+
     /// Add columns from another column context.
     ///
     /// Any columns already existing within this context will *not* be replaced
@@ -105,6 +211,16 @@ impl Columns {
             }
         }
 
+        for (name, column) in columns.named {
+            self.named.entry(name).or_insert(column);
+        }
+
+        for full_text in columns.full_text {
+            if !self.full_text.contains(&full_text) {
+                self.full_text.push(full_text);
+            }
+        }
+
         self
     }
 
@@ -116,19 +232,78 @@ impl Columns {
 
     /// Alias a table name
     pub fn alias(mut self, from: &str, to: &str) -> Self {
+        let alias = |r: ColumnRef| match r {
+            ColumnRef::TableColumn(t, c) if t.to_string().eq_ignore_ascii_case(from) => {
+                ColumnRef::TableColumn(Alias::new(to).into_iden(), c)
+            }
+            _ => r,
+        };
         self.columns = self
             .columns
             .into_iter()
-            .map(|(r, d)| match r {
-                ColumnRef::TableColumn(t, c) if t.to_string().eq_ignore_ascii_case(from) => {
-                    (ColumnRef::TableColumn(Alias::new(to).into_iden(), c), d)
-                }
-                _ => (r, d),
+            .map(|(r, d)| (alias(r), d))
+            .collect();
+        self.named = self
+            .named
+            .into_iter()
+            .map(|(name, (r, d))| (name, (alias(r), d)))
+            .collect();
+        self.full_text = self
+            .full_text
+            .into_iter()
+            .map(|f| match f {
+                FullText::Column(document) => FullText::Column(alias(document)),
+                FullText::Related {
+                    table,
+                    document,
+                    foreign_key,
+                    key,
+                } => FullText::Related {
+                    table,
+                    document,
+                    foreign_key,
+                    key: alias(key),
+                },
             })
             .collect();
         self
     }
 
+    /// Declare a `tsvector` column of the selected tables as full-text document.
+    ///
+    /// Once a context has full-text documents, a `{search}` matches documents containing
+    /// words starting with each of its words, rather than resulting in LIKE clauses for all
+    /// string-ish columns, and results can be sorted by their `rank`.
+    ///
+    /// The documents are expected to be generated using the `simple` text search
+    /// configuration, from text having everything but letters and digits replaced by
+    /// whitespace.
+    pub fn full_text<T: IntoIden, D: IntoIden>(mut self, table: T, document: D) -> Self {
+        self.full_text.push(FullText::Column(ColumnRef::TableColumn(
+            table.into_iden(),
+            document.into_iden(),
+        )));
+        self
+    }
+
+    /// Declare a `tsvector` column of a related table as full-text document, for the rows
+    /// where `foreign_key` equals `key`. See [`Self::full_text`].
+    pub fn full_text_related<T: IntoIden, D: IntoIden, F: IntoIden, K: IntoColumnRef>(
+        mut self,
+        table: T,
+        document: D,
+        foreign_key: F,
+        key: K,
+    ) -> Self {
+        self.full_text.push(FullText::Related {
+            table: table.into_iden(),
+            document: document.into_iden(),
+            foreign_key: foreign_key.into_iden(),
+            key: key.into_column_ref(),
+        });
+        self
+    }
+
     /// Declare which query fields are the nested keys of a JSON column
     pub fn json_keys(mut self, column: &'static str, fields: &[&'static str]) -> Self {
         for each in fields {
@@ -141,6 +316,7 @@ impl Columns {
     pub(crate) fn strings(&self) -> impl Iterator<Item = Expr> + '_ {
         self.columns
             .iter()
+            .chain(self.named.values())
             .filter_map(|(col_ref, col_def)| match col_def.get_column_type() {
                 ColumnType::String(_) | ColumnType::Text => Some(Expr::col(col_ref.clone())),
                 _ => None,
@@ -161,9 +337,11 @@ impl Columns {
                          if name.to_string().eq_ignore_ascii_case(tgt))
             }
         }
-        self.columns
+        self.named
             .iter()
-            .find(name_match(field))
+            .find(|(name, _)| name.eq_ignore_ascii_case(field))
+            .map(|(_, c)| c)
+            .or_else(|| self.columns.iter().find(name_match(field)))
             .map(|(r, d)| (Expr::col(r.clone()), d.clone()))
             .or_else(|| {
                 self.columns
@@ -187,6 +365,48 @@ impl Columns {
             )))
     }
 
+    pub(crate) fn has_full_text(&self) -> bool {
+        !self.full_text.is_empty()
+    }
+
+    /// Return the condition matching a `{search}` against the full-text documents, `None` if
+    /// there are none or the search doesn't contain any words.
+    pub(crate) fn full_text_search(&self, search: &str) -> Option<SimpleExpr> {
+        if !self.has_full_text() {
+            return None;
+        }
+        let query = to_tsquery(full_text_query(search)?);
+        self.full_text
+            .iter()
+            .map(|f| f.matches(query.clone()))
+            .reduce(SimpleExpr::or)
+    }
+
+    /// Prepare ranking the results by how well they match any of the `{search}` terms
+    pub(crate) fn searching<'a>(mut self, searches: impl IntoIterator<Item = &'a str>) -> Self {
+        let queries = searches
+            .into_iter()
+            .filter_map(full_text_query)
+            .map(|query| format!("({query})"))
+            .collect::<Vec<_>>();
+        self.rank = match queries.is_empty() {
+            true => None,
+            false => {
+                let query = to_tsquery(queries.join(" | "));
+                self.full_text
+                    .iter()
+                    .map(|f| f.rank(query.clone()))
+                    .reduce(SimpleExpr::add)
+            }
+        };
+        self
+    }
+
+    /// The rank of the results, `None` without full-text documents or search terms
+    pub(crate) fn rank(&self) -> Option<&SimpleExpr> {
+        self.rank.as_ref()
+    }
+
     pub(crate) fn translate(&self, field: &str, op: &str, value: &str) -> Option<String> {
         match self.translator {
             None => None,
@@ -375,4 +595,73 @@ mod tests {
 
         Ok(())
     }
+
+    #[test(tokio::test)]
+    async fn full_text() -> Result<(), anyhow::Error> {
+        let clause = |query: Query| -> Result<String, Error> {
+            Ok(advisory::Entity::find()
+                .select_only()
+                .column(advisory::Column::Id)
+                .filtering_with(
+                    query,
+                    advisory::Entity
+                        .columns()
+                        .full_text(advisory::Entity, Alias::new("search"))
+                        .full_text_related(
+                            Alias::new("description"),
+                            Alias::new("search"),
+                            Alias::new("advisory_id"),
+                            (advisory::Entity, advisory::Column::Id),
+                        ),
+                )?
+                .build(sea_orm::DatabaseBackend::Postgres)
+                .to_string()
+                .split_once("WHERE ")
+                .unwrap()
+                .1
+                .to_string())
+        };
+
+        let matches = |query: &str| {
+            format!(
+                r#""advisory"."search" @@ to_tsquery('simple', '{query}') OR EXISTS(SELECT 1 FROM "description" WHERE "description"."advisory_id" = "advisory"."id" AND "description"."search" @@ to_tsquery('simple', '{query}'))"#
+            )
+        };
+
+        // Substrings of the string-ish columns still match, in addition to the words
+        let search = |like: &str, query: &str| {
+            format!(
+                r#"("advisory"."location" ILIKE '%{like}%') OR ("advisory"."title" ILIKE '%{like}%') OR ({})"#,
+                matches(query)
+            )
+        };
+
+        assert_eq!(
+            clause(q("Log4j-Core"))?,
+            search("Log4j-Core", "log4j:* & core:*")
+        );
+        assert_eq!(
+            clause(q("log4j&score>5").sort("rank:desc"))?,
+            format!(
+                r#"({}) AND "advisory"."score" > 5 ORDER BY COALESCE(ts_rank("advisory"."search", to_tsquery('simple', '(log4j:*)')), 0) + COALESCE((SELECT MAX(ts_rank("description"."search", to_tsquery('simple', '(log4j:*)'))) FROM "description" WHERE "description"."advisory_id" = "advisory"."id"), 0) DESC"#,
+                search("log4j", "log4j:*")
+            )
+        );
+        // Searches without any words only use the LIKE clauses
+        assert_eq!(
+            clause(q("expr:!x&%"))?,
+            format!(
+                r#"({}) IS NOT TRUE AND (("advisory"."location" ILIKE E'%\\%%') OR ("advisory"."title" ILIKE E'%\\%%'))"#,
+                search("x", "x:*")
+            )
+        );
+        // Without a search, there's nothing to rank
+        assert_eq!(
+            clause(q("score>5").sort("rank"))?,
+            r#""advisory"."score" > 5"#
+        );
+        assert!(clause(q("x").sort("rank:up")).is_err());
+
+        Ok(())
+    }
 }
@@ -23,6 +23,20 @@ impl Filter {
             operands: Operand::Composite(filters),
         }
     }
+
+    pub(crate) fn any(filters: Vec<Filter>) -> Self {
+        Filter {
+            operator: Operator::Or,
+            operands: Operand::Composite(filters),
+        }
+    }
+
+    pub(crate) fn not(filter: Filter) -> Self {
+        Filter {
+            operator: Operator::Not,
+            operands: Operand::Composite(vec![filter]),
+        }
+    }
 }
 
 // From a filter string of the form {field}{op}{value}
@@ -67,10 +81,16 @@ impl TryFrom<(&Vec<String>, &Columns)> for Filter {
                     .iter()
                     .flat_map(|s| {
                         // Create a LIKE filter for all the string-ish columns
-                        columns.strings().map(move |expr| Filter {
+                        let like = columns.strings().map(|expr| Filter {
                             operands: Operand::Simple(expr, Arg::Value(SeaValue::from(s))),
                             operator: Operator::Like,
-                        })
+                        });
+                        // And also match the words of the full-text documents, if there are any
+                        let full_text = columns.full_text_search(s).map(|condition| Filter {
+                            operands: Operand::Condition(condition),
+                            operator: Operator::Match,
+                        });
+                        like.chain(full_text).collect::<Vec<_>>()
                     })
                     .collect(),
             ),
@@ -116,9 +136,17 @@ impl IntoCondition for Filter {
                 _ => unreachable!(),
             }
             .into_condition(),
+            Operand::Condition(condition) => condition.into_condition(),
             Operand::Composite(v) => match self.operator {
                 Operator::And => v.into_iter().fold(Condition::all(), |and, f| and.add(f)),
                 Operator::Or => v.into_iter().fold(Condition::any(), |or, f| or.add(f)),
+                // Rows are negated unless they match, including those
+                // which are NULL, the same as `Query::apply` does
+                Operator::Not => Expr::expr(SimpleExpr::from(
+                    v.into_iter().fold(Condition::all(), |and, f| and.add(f)),
+                ))
+                .is_not(true)
+                .into_condition(),
                 _ => unreachable!(),
             },
         }
@@ -199,6 +227,7 @@ impl Arg {
 #[derive(Debug)]
 enum Operand {
     Simple(Expr, Arg),
+    Condition(SimpleExpr),
     Composite(Vec<Filter>),
 }
 
@@ -214,6 +243,8 @@ pub(crate) enum Operator {
     LessThanOrEqual,
     And,
     Or,
+    Not,
+    Match,
 }
 
 impl Display for Operator {
@@ -230,6 +261,8 @@ impl Display for Operator {
             LessThanOrEqual => write!(f, "<="),
             And => write!(f, "&"),
             Or => write!(f, "|"),
+            Not => write!(f, "!"),
+            Match => write!(f, "@@"),
         }
     }
 }
@@ -248,6 +281,7 @@ impl FromStr for Operator {
             "<=" => Ok(LessThanOrEqual),
             "|" => Ok(Or),
             "&" => Ok(And),
+            "!" => Ok(Not),
             _ => Err(Error::SearchSyntax(format!("Invalid operator: '{s}'"))),
         }
     }
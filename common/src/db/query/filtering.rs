@@ -1,6 +1,6 @@
 // SeaORM impls
 
-use super::{Columns, Error, IntoColumns, Query, Sort};
+use super::{Columns, Error, IntoColumns, Query};
 use sea_orm::{EntityTrait, QueryFilter, QueryOrder, Select, SelectTwo};
 
 /// Pass a Query instance for filtering
@@ -25,11 +25,10 @@ pub trait Filtering<T: EntityTrait> {
         } else {
             self.filter(search.filter_for(&columns)?)
         };
+        let columns = columns.searching(search.searches().iter().map(String::as_str));
 
-        Ok(sort
-            .split_terminator(',')
-            .map(|s| Sort::parse(s, &columns))
-            .collect::<Result<Vec<_>, _>>()?
+        Ok(search
+            .sorts(&columns)?
             .into_iter()
             .fold(stmt, |select, s| s.order_by(select)))
     }
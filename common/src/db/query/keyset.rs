@@ -0,0 +1,235 @@
+use super::{Error, Sort};
+use crate::model::Paginated;
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use sea_orm::{
+    ColumnTrait, ColumnType, Condition, DbErr, IntoSimpleExpr, Order, QueryFilter, QueryOrder,
+    QueryResult, QuerySelect, QueryTrait, sea_query,
+};
+use sea_query::{Expr, SelectStatement, SimpleExpr};
+use std::num::NonZeroU64;
+
+/// The keys that the results of a query are sorted by.
+///
+/// Continuing after the keys of the last item of a page, rather than
+/// skipping an offset of items, keeps fetching pages deep into large
+/// result sets cheap. The keys must identify an item uniquely, so a
+/// keyset usually ends with the primary key as a tie-breaker, see
+/// [`Keyset::then`].
+#[derive(Clone, Debug, Default)]
+pub struct Keyset {
+    keys: Vec<Key>,
+}
+
+#[derive(Clone, Debug)]
+struct Key {
+    expr: SimpleExpr,
+    order: Order,
+    /// The SQL type to convert the textual values of a cursor back to
+    sql_type: String,
+}
+
+impl Keyset {
+    pub(crate) fn new(sorts: Vec<Sort>) -> Self {
+        Self {
+            keys: sorts
+                .into_iter()
+                .filter_map(Sort::into_key)
+                .map(|(expr, order, column_type)| Key::new(expr.into(), order, &column_type))
+                .collect(),
+        }
+    }
+
+    /// Sort by another column after the existing keys, e.g. a default
+    /// order or a unique tie-breaker
+    pub fn then<C: ColumnTrait>(mut self, column: C, order: Order) -> Self {
+        self.keys.push(Key::new(
+            column.into_simple_expr(),
+            order,
+            column.def().get_column_type(),
+        ));
+        self
+    }
+
+    /// Order and limit the statement according to the keyset. This
+    /// replaces any order of the statement.
+    pub(crate) fn apply<T>(&self, mut stmt: T, paginated: &Paginated) -> Result<T, Error>
+    where
+        T: QueryFilter + QueryOrder + QuerySelect + QueryTrait<QueryStatement = SelectStatement>,
+    {
+        QueryTrait::query(&mut stmt).clear_order_by();
+        for (i, key) in self.keys.iter().enumerate() {
+            stmt = stmt.order_by(key.expr.clone(), key.order.clone()).expr_as(
+                Expr::cust_with_expr("CAST($1 AS text)", key.expr.clone()),
+                column(i),
+            );
+        }
+        let stmt = match &paginated.cursor {
+            Some(cursor) => stmt.filter(self.after(self.decode(cursor)?)),
+            None => stmt.offset(NonZeroU64::new(paginated.offset).map(|offset| offset.get())),
+        };
+        // fetch one more item, to know if there's a next page
+        Ok(stmt.limit(NonZeroU64::new(paginated.limit).map(|limit| limit.get() + 1)))
+    }
+
+    /// Create the cursor continuing after the given row
+    pub(crate) fn cursor(&self, row: &QueryResult) -> Result<String, DbErr> {
+        let values = (0..self.keys.len())
+            .map(|i| row.try_get::<Option<String>>("", &column(i)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let json = serde_json::to_vec(&values).map_err(|err| DbErr::Custom(err.to_string()))?;
+        Ok(URL_SAFE_NO_PAD.encode(json))
+    }
+
+    fn decode(&self, cursor: &str) -> Result<Vec<Option<String>>, Error> {
+        URL_SAFE_NO_PAD
+            .decode(cursor)
+            .ok()
+            .and_then(|json| serde_json::from_slice::<Vec<Option<String>>>(&json).ok())
+            .filter(|values| values.len() == self.keys.len())
+            .ok_or_else(|| Error::InvalidCursor(cursor.into()))
+    }
+
+    /// The condition for items sorted after the given values, taking
+    /// into account that NULL values sort last in ascending order
+    fn after(&self, values: Vec<Option<String>>) -> Condition {
+        let mut after = Condition::any();
+        let mut equal = Condition::all();
+        for (key, value) in self.keys.iter().zip(values) {
+            let value = value.map(|v| key.value(v));
+            let expr = Expr::expr(key.expr.clone());
+            let greater = match (&key.order, value.clone()) {
+                (Order::Desc, Some(v)) => expr.clone().lt(v),
+                (Order::Desc, None) => expr.clone().is_not_null(),
+                (_, Some(v)) => expr.clone().gt(v).or(expr.clone().is_null()),
+                (_, None) => Expr::value(false),
+            };
+            after = after.add(equal.clone().add(greater));
+            equal = equal.add(match value {
+                Some(v) => expr.eq(v),
+                None => expr.is_null(),
+            });
+        }
+        after
+    }
+}
+
+impl Key {
+    fn new(expr: SimpleExpr, order: Order, column_type: &ColumnType) -> Self {
+        Self {
+            expr,
+            order,
+            sql_type: sql_type(column_type),
+        }
+    }
+
+    fn value(&self, value: String) -> SimpleExpr {
+        Expr::cust_with_values(format!("CAST($1 AS {})", self.sql_type), [value])
+    }
+}
+
+fn column(i: usize) -> String {
+    format!("keyset_{i}")
+}
+
+fn sql_type(column_type: &ColumnType) -> String {
+    match column_type {
+        ColumnType::Uuid => "uuid",
+        ColumnType::TinyInteger | ColumnType::SmallInteger => "smallint",
+        ColumnType::Integer => "integer",
+        ColumnType::BigInteger => "bigint",
+        ColumnType::Float => "real",
+        ColumnType::Double => "double precision",
+        ColumnType::Decimal(_) | ColumnType::Money(_) => "numeric",
+        ColumnType::Boolean => "boolean",
+        ColumnType::Date => "date",
+        ColumnType::Time => "time",
+        ColumnType::DateTime | ColumnType::Timestamp => "timestamp",
+        ColumnType::TimestampWithTimeZone => "timestamptz",
+        ColumnType::JsonBinary => "jsonb",
+        ColumnType::Enum { name, .. } => return format!(r#""{}""#, name.to_string()),
+        _ => "text",
+    }
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::*;
+    use super::super::*;
+    use super::*;
+    use sea_orm::{DatabaseBackend, EntityTrait};
+    use test_log::test;
+
+    fn sql(query: Query, paginated: &Paginated) -> Result<String, Error> {
+        let keyset = query
+            .keyset(advisory::Entity)?
+            .then(advisory::Column::Id, Order::Asc);
+        Ok(keyset
+            .apply(
+                advisory::Entity::find()
+                    .select_only()
+                    .column(advisory::Column::Id)
+                    .filtering(query)?,
+                paginated,
+            )?
+            .build(DatabaseBackend::Postgres)
+            .to_string())
+    }
+
+    #[test(tokio::test)]
+    async fn keysets() -> Result<(), anyhow::Error> {
+        let paginated = Paginated {
+            offset: 10,
+            limit: 5,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            sql(q("").sort("published:desc"), &paginated)?,
+            r#"SELECT "advisory"."id", CAST("advisory"."published" AS text) AS "keyset_0", CAST("advisory"."id" AS text) AS "keyset_1" FROM "advisory" ORDER BY "advisory"."published" DESC, "advisory"."id" ASC LIMIT 6 OFFSET 10"#
+        );
+
+        // a cursor replaces the offset
+        let cursor = URL_SAFE_NO_PAD.encode(r#"["2024-04-20 00:00:00+00","b"]"#);
+        assert_eq!(
+            sql(
+                q("").sort("published:desc"),
+                &Paginated {
+                    cursor: Some(cursor),
+                    ..paginated.clone()
+                }
+            )?,
+            r#"SELECT "advisory"."id", CAST("advisory"."published" AS text) AS "keyset_0", CAST("advisory"."id" AS text) AS "keyset_1" FROM "advisory" WHERE "advisory"."published" < (CAST('2024-04-20 00:00:00+00' AS timestamptz)) OR ("advisory"."published" = (CAST('2024-04-20 00:00:00+00' AS timestamptz)) AND ("advisory"."id" > (CAST('b' AS uuid)) OR "advisory"."id" IS NULL)) ORDER BY "advisory"."published" DESC, "advisory"."id" ASC LIMIT 6"#
+        );
+
+        // NULL values sort last in ascending order
+        let cursor = URL_SAFE_NO_PAD.encode(r#"[null,"b"]"#);
+        assert_eq!(
+            sql(
+                q("").sort("published"),
+                &Paginated {
+                    cursor: Some(cursor),
+                    limit: 0,
+                    ..paginated.clone()
+                }
+            )?,
+            r#"SELECT "advisory"."id", CAST("advisory"."published" AS text) AS "keyset_0", CAST("advisory"."id" AS text) AS "keyset_1" FROM "advisory" WHERE FALSE OR ("advisory"."published" IS NULL AND ("advisory"."id" > (CAST('b' AS uuid)) OR "advisory"."id" IS NULL)) ORDER BY "advisory"."published" ASC, "advisory"."id" ASC"#
+        );
+
+        // cursors must match the keyset
+        for cursor in ["foo", &URL_SAFE_NO_PAD.encode(r#"["b"]"#)] {
+            assert_eq!(
+                sql(
+                    q("").sort("published"),
+                    &Paginated {
+                        cursor: Some(cursor.into()),
+                        ..paginated.clone()
+                    }
+                ),
+                Err(Error::InvalidCursor(cursor.into()))
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -1,15 +1,25 @@
 use super::{Columns, Error};
-use sea_orm::{Order, QueryOrder};
+use sea_orm::{ColumnType, Order, QueryOrder};
 use sea_query::Expr;
 
 pub(crate) struct Sort {
-    field: Expr,
+    field: Option<(Expr, ColumnType)>,
     order: Order,
 }
 
+/// The pseudo field to sort the results of a full-text search by
+const RANK: &str = "rank";
+
 impl Sort {
     pub(crate) fn order_by<T: QueryOrder>(self, stmt: T) -> T {
-        stmt.order_by(self.field, self.order)
+        match self.field {
+            Some((field, _)) => stmt.order_by(field, self.order),
+            None => stmt,
+        }
+    }
+    pub(crate) fn into_key(self) -> Option<(Expr, Order, ColumnType)> {
+        self.field
+            .map(|(field, column_type)| (field, self.order, column_type))
     }
     pub(crate) fn parse(s: &str, columns: &Columns) -> Result<Self, Error> {
         let (field, order) = match s.split(':').collect::<Vec<_>>()[..] {
@@ -22,7 +32,17 @@ impl Sort {
         match columns.translate(field, &order, "") {
             Some(s) => Sort::parse(&s, columns),
             None => Ok(Self {
-                field: columns.for_field(field)?.0,
+                field: match columns.has_full_text() && field.eq_ignore_ascii_case(RANK) {
+                    // without a search, there's nothing to rank
+                    true => columns
+                        .rank()
+                        .cloned()
+                        .map(|rank| (Expr::expr(rank), ColumnType::Float)),
+                    false => {
+                        let (expr, def) = columns.for_field(field)?;
+                        Some((expr, def.get_column_type().clone()))
+                    }
+                },
                 order: match order.as_str() {
                     "asc" => Order::Asc,
                     "desc" => Order::Desc,
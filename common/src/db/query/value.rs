@@ -159,4 +159,41 @@ pub(crate) mod tests {
 
         Ok(())
     }
+
+    #[test(tokio::test)]
+    async fn filter_boolean_expressions() -> Result<(), anyhow::Error> {
+        let context = HashMap::from([
+            ("id", Value::String("foo")),
+            ("count", Value::Int(42)),
+            ("score", Value::Float(6.66)),
+        ]);
+
+        assert!(q("expr:id=bar|count=42").apply(&context));
+        assert!(q("expr:id=bar|baz|foo").apply(&context));
+        assert!(q("expr:(id=bar|score>6)&count=42").apply(&context));
+        assert!(!q("expr:(id=bar|score>9)&count=42").apply(&context));
+        assert!(q("expr:!id=bar").apply(&context));
+        assert!(!q("expr:!id=bar|foo").apply(&context));
+        assert!(q("expr:!(id=foo&count<42)").apply(&context));
+        assert!(!q("expr:!(id=foo&count<=42)").apply(&context));
+        assert!(q("expr:(id=bar|score>9) | !(count>42)").apply(&context));
+        assert!(q("expr:((id=foo))&!!count=42").apply(&context));
+        assert!(q("expr:oo&!(bar|baz)").apply(&context));
+
+        // syntax errors never match
+        assert!(!q("expr:(id=foo").apply(&context));
+        assert!(!q("expr:!").apply(&context));
+
+        // missing values don't match a filter, so they match its negation
+        assert!(!q("detected>1993-06-12").apply(&context));
+        assert!(q("expr:!detected>1993-06-12").apply(&context));
+
+        // without the prefix, there are no boolean expressions
+        assert!(!q("id=bar|count=42").apply(&context));
+        assert!(q("id=bar|!foo|foo").apply(&context));
+        assert!(!q("!id=bar").apply(&context));
+        assert!(!q("(oo").apply(&context));
+
+        Ok(())
+    }
 }
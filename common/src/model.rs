@@ -22,9 +22,7 @@ pub struct Revisioned<T> {
     pub revision: String,
 }
 
-#[derive(
-    IntoParams, Copy, Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize,
-)]
+#[derive(IntoParams, Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Paginated {
     /// The first item to return, skipping all that come before it.
@@ -37,6 +35,83 @@ pub struct Paginated {
     /// Zero means: no limit
     #[serde(default = "default::limit")]
     pub limit: u64,
+    /// The cursor to continue after, only accepted from APIs supporting cursors.
+    ///
+    /// Using [`CursorPaginated`] as parameters, rather than [`Paginated`], accepts a cursor.
+    /// Otherwise, providing one is rejected.
+    #[serde(
+        default,
+        deserialize_with = "unsupported_cursor",
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[param(ignore)]
+    pub cursor: Option<String>,
+    /// Whether to count the total number of items.
+    ///
+    /// Counting can be expensive for large results. If disabled, the
+    /// `total` is omitted from the result.
+    #[serde(default = "default::total")]
+    pub total: bool,
+}
+
+/// The parameters of [`Paginated`], for APIs which also support cursors
+#[derive(IntoParams, Clone, Debug, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CursorPaginated {
+    /// The first item to return, skipping all that come before it.
+    ///
+    /// NOTE: The order of items is defined by the API being called.
+    #[serde(default)]
+    pub offset: u64,
+    /// The maximum number of entries to return.
+    ///
+    /// Zero means: no limit
+    #[serde(default = "default::limit")]
+    pub limit: u64,
+    /// Continue after the last item of a previous page, instead of
+    /// skipping an `offset` of items.
+    ///
+    /// An opaque value, taken from the `next` field of that page.
+    #[serde(default)]
+    pub cursor: Option<String>,
+    /// Whether to count the total number of items.
+    ///
+    /// Counting can be expensive for large results. If disabled, the
+    /// `total` is omitted from the result.
+    #[serde(default = "default::total")]
+    pub total: bool,
+}
+
+impl From<CursorPaginated> for Paginated {
+    fn from(value: CursorPaginated) -> Self {
+        Self {
+            offset: value.offset,
+            limit: value.limit,
+            cursor: value.cursor,
+            total: value.total,
+        }
+    }
+}
+
+/// Reject a cursor, for APIs not supporting cursors
+fn unsupported_cursor<'de, D>(_: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Err(serde::de::Error::custom(
+        "this API doesn't support the 'cursor' parameter",
+    ))
+}
+
+impl Default for Paginated {
+    fn default() -> Self {
+        Self {
+            offset: 0,
+            limit: 0,
+            cursor: None,
+            total: default::total(),
+        }
+    }
 }
 
 impl Paginated {
@@ -45,14 +120,16 @@ impl Paginated {
         if self.offset as usize > vec.len() {
             return PaginatedResults {
                 items: vec![],
-                total: vec.len() as u64,
+                total: self.total.then_some(vec.len() as u64),
+                next: None,
             };
         }
 
         if self.limit == 0 {
             return PaginatedResults {
                 items: Vec::from(&vec[self.offset as usize..]),
-                total: vec.len() as u64,
+                total: self.total.then_some(vec.len() as u64),
+                next: None,
             };
         }
 
@@ -60,7 +137,8 @@ impl Paginated {
 
         PaginatedResults {
             items: Vec::from(&vec[self.offset as usize..end]),
-            total: vec.len() as u64,
+            total: self.total.then_some(vec.len() as u64),
+            next: None,
         }
     }
 }
@@ -69,6 +147,10 @@ mod default {
     pub(super) const fn limit() -> u64 {
         25
     }
+
+    pub(super) const fn total() -> bool {
+        true
+    }
 }
 
 // NOTE: This struct must be aligned with the struct in the [`paginated`] macro below.
@@ -76,7 +158,12 @@ mod default {
 #[serde(rename_all = "camelCase")]
 pub struct PaginatedResults<R> {
     pub items: Vec<R>,
-    pub total: u64,
+    /// The total number of items, unless counting was disabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total: Option<u64>,
+    /// The cursor to fetch the next page with, if there is one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next: Option<String>,
 }
 
 impl<R> PaginatedResults<R> {
@@ -90,22 +177,28 @@ impl<R> PaginatedResults<R> {
         S2: SelectorTrait,
     {
         let total = limiter.total().await?;
-        let results = limiter.fetch().await?;
+        let (items, next) = limiter.fetch_page().await?;
 
-        Ok(PaginatedResults {
-            items: results,
-            total,
-        })
+        Ok(PaginatedResults { items, total, next })
     }
 
     pub fn map<O, F: Fn(R) -> O>(self, f: F) -> PaginatedResults<O> {
         PaginatedResults {
             items: self.items.into_iter().map(f).collect(),
             total: self.total,
+            next: self.next,
         }
     }
 }
 
+/// The number of items having a value of a facet
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize, ToSchema)]
+pub struct FacetCount {
+    /// The value, `null` for items not having a value
+    pub value: Option<String>,
+    pub count: u64,
+}
+
 #[derive(ToSchema)]
 #[schema(value_type = String, format = Binary)]
 pub struct BinaryData(PhantomData<Vec<u8>>);
@@ -121,37 +214,54 @@ mod test {
         let paginated = Paginated {
             offset: 0,
             limit: 0,
+            ..Default::default()
         }
         .paginate_array(&data);
 
-        assert_eq!(10, paginated.total);
+        assert_eq!(Some(10), paginated.total);
         assert_eq!(10, paginated.items.len());
 
         let paginated = Paginated {
             offset: 0,
             limit: 5,
+            ..Default::default()
         }
         .paginate_array(&data);
 
-        assert_eq!(10, paginated.total);
+        assert_eq!(Some(10), paginated.total);
         assert_eq!(5, paginated.items.len());
 
         let paginated = Paginated {
             offset: 5,
             limit: 0,
+            ..Default::default()
         }
         .paginate_array(&data);
 
-        assert_eq!(10, paginated.total);
+        assert_eq!(Some(10), paginated.total);
         assert_eq!(5, paginated.items.len());
 
         let paginated = Paginated {
             offset: 12,
             limit: 0,
+            ..Default::default()
         }
         .paginate_array(&data);
 
-        assert_eq!(10, paginated.total);
+        assert_eq!(Some(10), paginated.total);
         assert_eq!(0, paginated.items.len());
+
+        // without counting
+
+        let paginated = Paginated {
+            offset: 0,
+            limit: 5,
+            total: false,
+            ..Default::default()
+        }
+        .paginate_array(&data);
+
+        assert_eq!(None, paginated.total);
+        assert_eq!(5, paginated.items.len());
     }
 }
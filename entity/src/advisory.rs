@@ -30,6 +30,8 @@ pub struct Model {
     pub title: Option<String>,
     pub labels: Labels,
     pub source_document_id: Option<Uuid>,
+    /// The format of the document the advisory was loaded from, e.g. `csaf` or `nvd`
+    pub format: Option<String>,
 }
 
 #[ComplexObject]
@@ -0,0 +1,32 @@
+use sea_orm::entity::prelude::*;
+use time::OffsetDateTime;
+
+/// A long-lived API token, issued by a user.
+///
+/// Only the hash of the token is stored. The token itself is only returned once, when it gets
+/// created.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "api_token")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+
+    /// The ID of the user owning the token
+    pub user_id: String,
+    pub name: String,
+
+    /// The SHA-256 digest of the token, hex encoded
+    pub token_hash: String,
+
+    /// The permissions granted to the token, a subset of the user's permissions
+    pub permissions: Vec<String>,
+
+    pub created: OffsetDateTime,
+    pub expires: OffsetDateTime,
+    pub last_used: Option<OffsetDateTime>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,23 @@
+use sea_orm::entity::prelude::*;
+use time::OffsetDateTime;
+
+/// A user owning API tokens, with the permissions they had when they were last seen.
+///
+/// The permissions of a token are limited to those, so that tokens lose permissions revoked
+/// from their owner.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "api_token_owner")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub user_id: String,
+
+    /// The permissions of the user, as last seen
+    pub permissions: Vec<String>,
+
+    pub updated: OffsetDateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
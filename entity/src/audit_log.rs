@@ -0,0 +1,131 @@
+use sea_orm::{FromJsonQueryResult, entity::prelude::*};
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use utoipa::ToSchema;
+
+/// A mutating operation performed through the API.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "audit_log")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+
+    pub timestamp: OffsetDateTime,
+
+    /// The ID of the user performing the operation, `None` for anonymous access.
+    pub actor: Option<String>,
+    pub action: Action,
+
+    pub target_kind: TargetKind,
+    /// The ID of the affected resource, if there is a single one.
+    pub target_id: Option<String>,
+
+    pub request: Request,
+
+    /// The state of the resource before the operation, if tracked.
+    pub before: Option<serde_json::Value>,
+    /// The state of the resource after the operation, if tracked.
+    pub after: Option<serde_json::Value>,
+}
+
+/// The kind of operation performed
+#[derive(
+    Copy,
+    Clone,
+    Eq,
+    Hash,
+    Debug,
+    PartialEq,
+    EnumIter,
+    DeriveActiveEnum,
+    strum::Display,
+    Serialize,
+    Deserialize,
+    ToSchema,
+)]
+#[sea_orm(
+    rs_type = "String",
+    db_type = "String(StringLen::None)",
+    rename_all = "lowercase"
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+#[schema(as = AuditAction)]
+pub enum Action {
+    Create,
+    Update,
+    Delete,
+    Upload,
+    Enable,
+    Disable,
+    Force,
+    Merge,
+}
+
+/// The kind of resource an operation was performed on
+#[derive(
+    Copy,
+    Clone,
+    Eq,
+    Hash,
+    Debug,
+    PartialEq,
+    EnumIter,
+    DeriveActiveEnum,
+    strum::Display,
+    Serialize,
+    Deserialize,
+    ToSchema,
+)]
+#[sea_orm(
+    rs_type = "String",
+    db_type = "String(StringLen::None)",
+    rename_all = "snake_case"
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+#[schema(as = AuditTargetKind)]
+pub enum TargetKind {
+    Advisory,
+    AdvisoryLabels,
+    AiConversation,
+    ApiToken,
+    CpeMapping,
+    Dataset,
+    Importer,
+    Job,
+    LicensePolicy,
+    Organization,
+    OrganizationAliases,
+    OrganizationRules,
+    Product,
+    ProductVersion,
+    Reprocess,
+    RetentionPolicy,
+    SavedSearch,
+    Sbom,
+    SbomLabels,
+    Snapshot,
+    UserPreference,
+    Vulnerability,
+    Watchlist,
+}
+
+/// Metadata of the HTTP request which performed the operation
+#[derive(
+    Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, FromJsonQueryResult, ToSchema,
+)]
+#[schema(as = AuditRequest)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote_address: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_agent: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -11,6 +11,8 @@ pub struct Model {
     pub seq: i32,
     pub summary: String,
     pub updated_at: OffsetDateTime,
+    /// The events of all completions, in the order they happened
+    pub events: serde_json::Value,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
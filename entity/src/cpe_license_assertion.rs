@@ -11,6 +11,42 @@ pub struct Model {
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
-pub enum Relation {}
+pub enum Relation {
+    #[sea_orm(belongs_to="super::cpe::Entity"
+        from = "Column::CpeId",
+        to = "super::cpe::Column::Id",
+    )]
+    Cpe,
+
+    #[sea_orm(belongs_to="super::license::Entity"
+        from = "Column::LicenseId",
+        to = "super::license::Column::Id",
+    )]
+    License,
+
+    #[sea_orm(belongs_to="super::sbom::Entity"
+        from = "Column::SbomId",
+        to = "super::sbom::Column::SbomId",
+    )]
+    Sbom,
+}
+
+impl Related<super::cpe::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Cpe.def()
+    }
+}
+
+impl Related<super::license::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::License.def()
+    }
+}
+
+impl Related<super::sbom::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Sbom.def()
+    }
+}
 
 impl ActiveModelBehavior for ActiveModel {}
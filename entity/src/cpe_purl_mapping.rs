@@ -0,0 +1,59 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use utoipa::ToSchema;
+
+/// A correlation between a CPE, identified by vendor and product, and a package, identified
+/// by its base PURL.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "cpe_purl_mapping")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+
+    pub cpe_vendor: String,
+    pub cpe_product: String,
+
+    /// The ID of the base PURL, which might not (yet) exist in the `base_purl` table.
+    pub base_purl_id: Uuid,
+    pub purl_type: String,
+    pub purl_namespace: Option<String>,
+    pub purl_name: String,
+
+    pub source: MappingSource,
+    pub created: OffsetDateTime,
+}
+
+/// How a CPE to PURL mapping came into existence
+#[derive(
+    Copy,
+    Clone,
+    Eq,
+    Hash,
+    Debug,
+    PartialEq,
+    EnumIter,
+    DeriveActiveEnum,
+    strum::Display,
+    Serialize,
+    Deserialize,
+    ToSchema,
+)]
+#[sea_orm(
+    rs_type = "String",
+    db_type = "String(StringLen::None)",
+    rename_all = "lowercase"
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum MappingSource {
+    /// Learned from SBOMs carrying both a CPE and a PURL for the same package
+    Learned,
+    /// Provided by a user
+    Curated,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,20 @@
+use crate::ingestion_history::DocumentKind;
+use sea_orm::entity::prelude::*;
+use time::OffsetDateTime;
+
+/// The deletion of a source document, so that it can be replayed by incremental snapshots.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "document_tombstone")]
+pub struct Model {
+    /// The SHA256 digest of the deleted document
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub sha256: String,
+
+    pub kind: DocumentKind,
+    pub deleted: OffsetDateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
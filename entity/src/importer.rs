@@ -25,6 +25,9 @@ pub struct Model {
 
     /// Unix time in nanos, for coordinating importer replicas
     pub heartbeat: Option<Decimal>,
+
+    /// A report of which the failed documents should be retried with the next run
+    pub retry: Option<Uuid>,
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, EnumIter, DeriveActiveEnum)]
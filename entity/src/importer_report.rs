@@ -22,6 +22,8 @@ pub enum Relation {
         to = "super::importer::Column::Name"
     )]
     Importer,
+    #[sea_orm(has_many = "super::importer_report_item::Entity")]
+    Item,
 }
 
 impl Related<super::importer::Entity> for Entity {
@@ -30,4 +32,10 @@ impl Related<super::importer::Entity> for Entity {
     }
 }
 
+impl Related<super::importer_report_item::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Item.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
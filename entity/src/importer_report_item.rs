@@ -0,0 +1,65 @@
+use sea_orm::entity::prelude::*;
+
+/// The outcome of processing a single document during an importer run
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "importer_report_item")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+
+    pub report: Uuid,
+
+    /// The document, as identified by the importer
+    pub file: String,
+    pub outcome: Outcome,
+
+    pub phase: Option<Phase>,
+    pub message: Option<String>,
+
+    /// The ID of the ingested document
+    pub document: Option<String>,
+    /// The ID declared by the ingested document
+    pub document_id: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(
+    rs_type = "String",
+    db_type = "String(StringLen::None)",
+    rename_all = "snake_case"
+)]
+pub enum Outcome {
+    Ingested,
+    Skipped,
+    Failed,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(
+    rs_type = "String",
+    db_type = "String(StringLen::None)",
+    rename_all = "snake_case"
+)]
+pub enum Phase {
+    Retrieval,
+    Validation,
+    Upload,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::importer_report::Entity",
+        from = "Column::Report",
+        to = "super::importer_report::Column::Id"
+    )]
+    Report,
+}
+
+impl Related<super::importer_report::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Report.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,85 @@
+use crate::labels::Labels;
+use sea_orm::{FromJsonQueryResult, entity::prelude::*};
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use utoipa::ToSchema;
+
+/// An ingestion event of a logical document, an advisory or an SBOM.
+///
+/// Documents are identified by their `key`, which is the identifier of an advisory or the
+/// document ID (namespace) of an SBOM. Each ingestion of a document with the same key creates
+/// a new entry.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "ingestion_history")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+
+    pub kind: DocumentKind,
+    pub key: String,
+
+    /// The ID of the ingested advisory or SBOM.
+    pub document_id: Uuid,
+
+    pub ingested: OffsetDateTime,
+    pub sha256: String,
+
+    /// The name of the importer which ingested the document, if any.
+    pub importer: Option<String>,
+    /// The ID of the user which uploaded the document, if any.
+    pub user_id: Option<String>,
+    pub labels: Labels,
+
+    /// `false` if the exact same document was already ingested before.
+    pub changed: bool,
+    /// The changes compared to the previous version of the document.
+    pub delta: Delta,
+}
+
+/// The kind of document an ingestion event is recorded for
+#[derive(
+    Copy,
+    Clone,
+    Eq,
+    Hash,
+    Debug,
+    PartialEq,
+    EnumIter,
+    DeriveActiveEnum,
+    strum::Display,
+    Serialize,
+    Deserialize,
+    ToSchema,
+)]
+#[sea_orm(
+    rs_type = "String",
+    db_type = "String(StringLen::None)",
+    rename_all = "lowercase"
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum DocumentKind {
+    Advisory,
+    Sbom,
+}
+
+/// A summary of changes compared to a previous version of a document.
+///
+/// For advisories, the entries are the statuses of the advisory. For SBOMs, those are the
+/// packages.
+#[derive(
+    Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, FromJsonQueryResult, ToSchema,
+)]
+pub struct Delta {
+    /// Entries which got added
+    #[serde(default)]
+    pub added: Vec<String>,
+    /// Entries which got removed
+    #[serde(default)]
+    pub removed: Vec<String>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
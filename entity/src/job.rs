@@ -0,0 +1,32 @@
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "job")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub name: String,
+    pub description: Option<String>,
+
+    pub configuration: serde_json::Value,
+
+    pub last_run: Option<time::OffsetDateTime>,
+    pub last_success: Option<time::OffsetDateTime>,
+    pub last_error: Option<String>,
+
+    /// Unix time in nanos, for coordinating scheduler replicas
+    pub heartbeat: Option<Decimal>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::job_report::Entity")]
+    Report,
+}
+
+impl Related<super::job_report::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Report.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
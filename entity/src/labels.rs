@@ -87,6 +87,15 @@ impl Labels {
     }
 }
 
+/// The labels of a document, before and after a modification
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LabelsChange {
+    /// The ID of the modified document
+    pub id: sea_orm::prelude::Uuid,
+    pub before: Labels,
+    pub after: Labels,
+}
+
 impl<'a> FromIterator<(&'a str, &'a str)> for Labels {
     fn from_iter<T: IntoIterator<Item = (&'a str, &'a str)>>(iter: T) -> Self {
         Self(
@@ -1,26 +1,43 @@
 pub mod advisory;
 pub mod advisory_vulnerability;
+pub mod api_token;
+pub mod api_token_owner;
+pub mod audit_log;
 pub mod base_purl;
 pub mod conversation;
 pub mod cpe;
 pub mod cpe_license_assertion;
+pub mod cpe_purl_mapping;
 pub mod cvss3;
 pub mod cvss4;
+pub mod document_tombstone;
 pub mod importer;
 pub mod importer_report;
+pub mod importer_report_item;
+pub mod ingestion_history;
+pub mod job;
+pub mod job_report;
 pub mod labels;
 pub mod license;
+pub mod license_policy;
 pub mod organization;
+pub mod organization_alias;
+pub mod organization_rule;
 pub mod package_relates_to_package;
 pub mod package_version_range;
 pub mod product;
 pub mod product_status;
+pub mod product_status_platform;
 pub mod product_version;
 pub mod product_version_range;
 pub mod purl_license_assertion;
 pub mod purl_status;
 pub mod qualified_purl;
 pub mod relationship;
+pub mod reprocess;
+pub mod retention_policy;
+pub mod retention_report;
+pub mod saved_search;
 pub mod sbom;
 pub mod sbom_external_node;
 pub mod sbom_file;
@@ -29,6 +46,7 @@ pub mod sbom_node_checksum;
 pub mod sbom_package;
 pub mod sbom_package_cpe_ref;
 pub mod sbom_package_purl_ref;
+pub mod snapshot_import;
 pub mod source_document;
 pub mod status;
 pub mod user_preferences;
@@ -37,4 +55,5 @@ pub mod version_scheme;
 pub mod versioned_purl;
 pub mod vulnerability;
 pub mod vulnerability_description;
+pub mod watchlist;
 pub mod weakness;
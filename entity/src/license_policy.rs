@@ -0,0 +1,65 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use utoipa::ToSchema;
+
+/// A policy, classifying licenses as allowed, denied or in need of a review.
+///
+/// Entries are normalized SPDX license requirements, like `MIT`, `GPL-2.0-or-later` or
+/// `GPL-2.0-only WITH Classpath-exception-2.0`.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "license_policy")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+
+    pub name: String,
+    pub description: Option<String>,
+
+    pub allowed: Vec<String>,
+    pub denied: Vec<String>,
+    pub needs_review: Vec<String>,
+    /// SPDX exception identifiers, which make any license they are applied to acceptable
+    pub exceptions: Vec<String>,
+    /// The verdict for licenses which are not listed
+    pub fallback: Verdict,
+
+    pub created: OffsetDateTime,
+    pub modified: OffsetDateTime,
+}
+
+/// The verdict of a policy for a license, ordered from the most to the least acceptable one
+#[derive(
+    Copy,
+    Clone,
+    Eq,
+    Hash,
+    Debug,
+    PartialEq,
+    PartialOrd,
+    Ord,
+    EnumIter,
+    DeriveActiveEnum,
+    strum::Display,
+    Serialize,
+    Deserialize,
+    ToSchema,
+)]
+#[sea_orm(
+    rs_type = "String",
+    db_type = "String(StringLen::None)",
+    rename_all = "snake_case"
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+#[schema(as = LicensePolicyVerdict)]
+pub enum Verdict {
+    Allowed,
+    NeedsReview,
+    Denied,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -1,4 +1,4 @@
-use crate::{advisory, product};
+use crate::{advisory, organization_alias, product};
 use async_graphql::*;
 use sea_orm::entity::prelude::*;
 
@@ -22,6 +22,14 @@ impl Related<advisory::Entity> for Entity {
     }
 }
 
+impl Related<organization_alias::Entity> for Entity {
+    fn to() -> RelationDef {
+        super::organization_alias::Relation::Organization
+            .def()
+            .rev()
+    }
+}
+
 impl Related<product::Entity> for Entity {
     fn to() -> RelationDef {
         super::product::Relation::Vendor.def().rev()
@@ -0,0 +1,31 @@
+use sea_orm::entity::prelude::*;
+
+/// An alternative name of an organization.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "organization_alias")]
+pub struct Model {
+    /// The normalized form of the alias, used for matching names.
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub key: String,
+    /// The alias, as provided.
+    pub alias: String,
+    pub organization_id: Uuid,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::organization::Entity",
+        from = "Column::OrganizationId",
+        to = "super::organization::Column::Id"
+    )]
+    Organization,
+}
+
+impl Related<super::organization::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Organization.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,21 @@
+use sea_orm::entity::prelude::*;
+
+/// A rule normalizing organization names during ingestion.
+///
+/// Rules are applied in the order of their position, replacing all matches of the regular
+/// expression `pattern` with `replacement`.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "organization_rule")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub position: i32,
+    pub pattern: String,
+    pub replacement: String,
+    pub description: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,45 @@
+use sea_orm::entity::prelude::*;
+
+/// A platform a product status only applies in combination with
+///
+/// For example, a vulnerable component might only be affected when running on a specific
+/// operating system. The platform itself is not affected.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "product_status_platform")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub product_status_id: Uuid,
+
+    #[sea_orm(primary_key)]
+    pub cpe_id: Uuid,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::product_status::Entity",
+        from = "Column::ProductStatusId",
+        to = "super::product_status::Column::Id"
+    )]
+    ProductStatus,
+    #[sea_orm(
+        belongs_to = "super::cpe::Entity",
+        from = "Column::CpeId",
+        to = "super::cpe::Column::Id"
+    )]
+    Cpe,
+}
+
+impl Related<super::product_status::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ProductStatus.def()
+    }
+}
+
+impl Related<super::cpe::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Cpe.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
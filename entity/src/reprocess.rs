@@ -0,0 +1,103 @@
+use crate::labels::Labels;
+use sea_orm::{FromJsonQueryResult, entity::prelude::*};
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use utoipa::ToSchema;
+
+/// A run, reprocessing stored documents by loading them again.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "reprocess")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+
+    pub created: OffsetDateTime,
+    /// The last time the run made progress.
+    pub updated: OffsetDateTime,
+    pub state: State,
+
+    pub filter: Filter,
+
+    /// The ingestion time of the last processed document, for resuming the run.
+    pub cursor_ingested: Option<OffsetDateTime>,
+    /// The SHA256 digest of the last processed document, for resuming the run.
+    pub cursor_sha256: Option<String>,
+
+    /// The number of documents matching the filter, when the run was created.
+    pub total: i64,
+    /// The number of documents which got loaded again.
+    pub processed: i64,
+    /// The number of documents which didn't match the formats of the filter.
+    pub skipped: i64,
+    /// The number of documents which failed to load.
+    pub failed: i64,
+
+    pub report: Report,
+}
+
+/// The state of a reprocessing run
+#[derive(
+    Copy,
+    Clone,
+    Eq,
+    Hash,
+    Debug,
+    PartialEq,
+    EnumIter,
+    DeriveActiveEnum,
+    strum::Display,
+    Serialize,
+    Deserialize,
+    ToSchema,
+)]
+#[sea_orm(
+    rs_type = "String",
+    db_type = "String(StringLen::None)",
+    rename_all = "lowercase"
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+#[schema(as = ReprocessState)]
+pub enum State {
+    /// Created, or interrupted, and waiting to be run
+    Pending,
+    Running,
+    Completed,
+}
+
+/// The documents to reprocess
+#[derive(
+    Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, FromJsonQueryResult, ToSchema,
+)]
+#[schema(as = ReprocessFilter)]
+pub struct Filter {
+    /// Only reprocess documents of these formats, like `csaf` or `spdx`. All formats if empty.
+    #[serde(default)]
+    pub formats: Vec<String>,
+    /// Only reprocess documents ingested at or after this time
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub since: Option<OffsetDateTime>,
+    /// Only reprocess documents ingested before this time
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub until: Option<OffsetDateTime>,
+    /// Only reprocess documents having all of these labels
+    #[serde(default)]
+    pub labels: Labels,
+}
+
+/// The documents which failed to be reprocessed
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, FromJsonQueryResult)]
+pub struct Report(pub Vec<Failure>);
+
+/// A document which failed to be reprocessed
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[schema(as = ReprocessFailure)]
+pub struct Failure {
+    pub sha256: String,
+    pub error: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
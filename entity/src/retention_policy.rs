@@ -0,0 +1,87 @@
+use crate::labels::Labels;
+use sea_orm::{FromJsonQueryResult, entity::prelude::*};
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use utoipa::ToSchema;
+
+/// A policy, selecting SBOMs or advisories which should no longer be retained.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "retention_policy")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+
+    pub name: String,
+    pub description: Option<String>,
+    /// Disabled policies are not executed
+    pub enabled: bool,
+
+    pub target: Target,
+    pub rule: Rule,
+    /// Documents having all of these labels are never deleted
+    pub exempt: Labels,
+
+    pub created: OffsetDateTime,
+    pub modified: OffsetDateTime,
+}
+
+/// The kind of documents a retention policy applies to
+#[derive(
+    Copy,
+    Clone,
+    Eq,
+    Hash,
+    Debug,
+    PartialEq,
+    EnumIter,
+    DeriveActiveEnum,
+    strum::Display,
+    Serialize,
+    Deserialize,
+    ToSchema,
+)]
+#[sea_orm(
+    rs_type = "String",
+    db_type = "String(StringLen::None)",
+    rename_all = "snake_case"
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+#[schema(as = RetentionTarget)]
+pub enum Target {
+    Sbom,
+    Advisory,
+}
+
+/// The rule of a retention policy, selecting the documents to delete
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, FromJsonQueryResult, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[schema(as = RetentionRule)]
+pub enum Rule {
+    /// Keep the most recently ingested SBOMs of each group, deleting older ones
+    ///
+    /// SBOMs missing the value to group by are not affected.
+    KeepLast { count: u32, group_by: GroupBy },
+    /// Delete documents ingested more than a number of days ago
+    MaxAge { days: u32 },
+    /// Delete advisories withdrawn more than a number of days ago
+    Withdrawn { days: u32 },
+}
+
+/// The value to group SBOMs by, when keeping the most recent ones
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+#[schema(as = RetentionGroupBy)]
+pub enum GroupBy {
+    /// The ID declared by the document, like the SPDX document namespace
+    DocumentId,
+    /// The name of the SBOM
+    Name,
+    /// The value of a label, like `product`
+    Label(String),
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
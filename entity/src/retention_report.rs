@@ -0,0 +1,21 @@
+use sea_orm::entity::prelude::*;
+use time::OffsetDateTime;
+
+/// The outcome of executing the retention policies.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "retention_report")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+
+    pub created: OffsetDateTime,
+    /// Dry runs only report the documents which would have been deleted
+    pub dry_run: bool,
+
+    pub report: serde_json::Value,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,27 @@
+use sea_orm::entity::prelude::*;
+use time::OffsetDateTime;
+
+/// A set of SBOMs, packages and products a user follows.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "watchlist")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+
+    pub user_id: String,
+    pub name: String,
+
+    pub sboms: Vec<Uuid>,
+    /// Package URLs, without a version they match all versions
+    pub purls: Vec<String>,
+    pub products: Vec<Uuid>,
+
+    /// The last time the user checked for new vulnerabilities
+    pub last_checked: Option<OffsetDateTime>,
+    pub created: OffsetDateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -14,6 +14,9 @@ impl MigratorTrait for Migrator {
     }
 
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
-        vec![Box::new(crate::ai_m0000010_create_conversation::Migration)]
+        vec![
+            Box::new(crate::ai_m0000010_create_conversation::Migration),
+            Box::new(crate::ai_m0000020_alter_conversation_add_events::Migration),
+        ]
     }
 }
@@ -2,11 +2,31 @@ pub use sea_orm_migration::prelude::*;
 
 mod m0000010_init;
 mod m0000970_alter_importer_add_heartbeat;
+mod m0000980_create_cpe_purl_mapping;
+mod m0000990_create_ingestion_history;
+mod m0001000_create_api_token;
+mod m0001010_create_audit_log;
+mod m0001020_create_license_policy;
+mod m0001030_create_organization_alias;
+mod m0001040_create_saved_search_and_watchlist;
+mod m0001050_add_full_text_search;
+mod m0001060_create_reprocess;
+mod m0001070_create_retention_policy;
+mod m0001080_create_job;
+mod m0001090_create_importer_report_item;
+mod m0001100_create_product_status_platform;
+mod m0001110_create_api_token_owner;
+mod m0001120_create_snapshot_import;
+mod m0001130_create_document_tombstone;
+mod m0001140_add_advisory_format;
+mod m0001150_add_organization_name_key;
 
 #[cfg(feature = "ai")]
 pub mod ai;
 #[cfg(feature = "ai")]
 mod ai_m0000010_create_conversation;
+#[cfg(feature = "ai")]
+mod ai_m0000020_alter_conversation_add_events;
 
 pub struct Migrator;
 
@@ -16,6 +36,24 @@ impl MigratorTrait for Migrator {
         vec![
             Box::new(m0000010_init::Migration),
             Box::new(m0000970_alter_importer_add_heartbeat::Migration),
+            Box::new(m0000980_create_cpe_purl_mapping::Migration),
+            Box::new(m0000990_create_ingestion_history::Migration),
+            Box::new(m0001000_create_api_token::Migration),
+            Box::new(m0001010_create_audit_log::Migration),
+            Box::new(m0001020_create_license_policy::Migration),
+            Box::new(m0001030_create_organization_alias::Migration),
+            Box::new(m0001040_create_saved_search_and_watchlist::Migration),
+            Box::new(m0001050_add_full_text_search::Migration),
+            Box::new(m0001060_create_reprocess::Migration),
+            Box::new(m0001070_create_retention_policy::Migration),
+            Box::new(m0001080_create_job::Migration),
+            Box::new(m0001090_create_importer_report_item::Migration),
+            Box::new(m0001100_create_product_status_platform::Migration),
+            Box::new(m0001110_create_api_token_owner::Migration),
+            Box::new(m0001120_create_snapshot_import::Migration),
+            Box::new(m0001130_create_document_tombstone::Migration),
+            Box::new(m0001140_add_advisory_format::Migration),
+            Box::new(m0001150_add_organization_name_key::Migration),
         ]
     }
 }
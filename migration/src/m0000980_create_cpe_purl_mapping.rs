@@ -0,0 +1,123 @@
+use crate::{Now, UuidV4};
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(CpePurlMapping::Table)
+                    .col(
+                        ColumnDef::new(CpePurlMapping::Id)
+                            .uuid()
+                            .not_null()
+                            .default(Func::cust(UuidV4))
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(CpePurlMapping::CpeVendor)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(CpePurlMapping::CpeProduct)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(CpePurlMapping::BasePurlId).uuid().not_null())
+                    .col(ColumnDef::new(CpePurlMapping::PurlType).string().not_null())
+                    .col(
+                        ColumnDef::new(CpePurlMapping::PurlNamespace)
+                            .string()
+                            .null(),
+                    )
+                    .col(ColumnDef::new(CpePurlMapping::PurlName).string().not_null())
+                    .col(ColumnDef::new(CpePurlMapping::Source).string().not_null())
+                    .col(
+                        ColumnDef::new(CpePurlMapping::Created)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Func::cust(Now)),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // a mapping must only exist once, no matter if it was learned or curated
+        manager
+            .create_index(
+                Index::create()
+                    .table(CpePurlMapping::Table)
+                    .name(Indexes::CpePurlMappingCpeBasePurlIdx.to_string())
+                    .col(CpePurlMapping::CpeVendor)
+                    .col(CpePurlMapping::CpeProduct)
+                    .col(CpePurlMapping::BasePurlId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        // speed up the reverse lookup, from purl to CPE
+        manager
+            .create_index(
+                Index::create()
+                    .table(CpePurlMapping::Table)
+                    .name(Indexes::CpePurlMappingBasePurlIdIdx.to_string())
+                    .col(CpePurlMapping::BasePurlId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .if_exists()
+                    .table(CpePurlMapping::Table)
+                    .name(Indexes::CpePurlMappingBasePurlIdIdx.to_string())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .if_exists()
+                    .table(CpePurlMapping::Table)
+                    .name(Indexes::CpePurlMappingCpeBasePurlIdx.to_string())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(CpePurlMapping::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Indexes {
+    CpePurlMappingCpeBasePurlIdx,
+    CpePurlMappingBasePurlIdIdx,
+}
+
+#[derive(DeriveIden)]
+enum CpePurlMapping {
+    Table,
+    Id,
+    CpeVendor,
+    CpeProduct,
+    BasePurlId,
+    PurlType,
+    PurlNamespace,
+    PurlName,
+    Source,
+    Created,
+}
@@ -0,0 +1,110 @@
+use crate::{Now, UuidV4};
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(IngestionHistory::Table)
+                    .col(
+                        ColumnDef::new(IngestionHistory::Id)
+                            .uuid()
+                            .not_null()
+                            .default(Func::cust(UuidV4))
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(IngestionHistory::Kind).string().not_null())
+                    .col(ColumnDef::new(IngestionHistory::Key).string().not_null())
+                    .col(
+                        ColumnDef::new(IngestionHistory::DocumentId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(IngestionHistory::Ingested)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Func::cust(Now)),
+                    )
+                    .col(ColumnDef::new(IngestionHistory::Sha256).string().not_null())
+                    .col(ColumnDef::new(IngestionHistory::Importer).string().null())
+                    .col(ColumnDef::new(IngestionHistory::UserId).string().null())
+                    .col(
+                        ColumnDef::new(IngestionHistory::Labels)
+                            .json_binary()
+                            .not_null()
+                            .default(Expr::cust("'{}'::jsonb")),
+                    )
+                    .col(
+                        ColumnDef::new(IngestionHistory::Changed)
+                            .boolean()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(IngestionHistory::Delta)
+                            .json_binary()
+                            .not_null()
+                            .default(Expr::cust("'{}'::jsonb")),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // history is always requested for a logical document
+        manager
+            .create_index(
+                Index::create()
+                    .table(IngestionHistory::Table)
+                    .name(Indexes::IngestionHistoryKindKeyIdx.to_string())
+                    .col(IngestionHistory::Kind)
+                    .col(IngestionHistory::Key)
+                    .col(IngestionHistory::Ingested)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .if_exists()
+                    .table(IngestionHistory::Table)
+                    .name(Indexes::IngestionHistoryKindKeyIdx.to_string())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(IngestionHistory::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Indexes {
+    IngestionHistoryKindKeyIdx,
+}
+
+#[derive(DeriveIden)]
+enum IngestionHistory {
+    Table,
+    Id,
+    Kind,
+    Key,
+    DocumentId,
+    Ingested,
+    Sha256,
+    Importer,
+    UserId,
+    Labels,
+    Changed,
+    Delta,
+}
@@ -0,0 +1,113 @@
+use crate::{Now, UuidV4};
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(AuditLog::Table)
+                    .col(
+                        ColumnDef::new(AuditLog::Id)
+                            .uuid()
+                            .not_null()
+                            .default(Func::cust(UuidV4))
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(AuditLog::Timestamp)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Func::cust(Now)),
+                    )
+                    .col(ColumnDef::new(AuditLog::Actor).string().null())
+                    .col(ColumnDef::new(AuditLog::Action).string().not_null())
+                    .col(ColumnDef::new(AuditLog::TargetKind).string().not_null())
+                    .col(ColumnDef::new(AuditLog::TargetId).string().null())
+                    .col(
+                        ColumnDef::new(AuditLog::Request)
+                            .json_binary()
+                            .not_null()
+                            .default(Expr::cust("'{}'::jsonb")),
+                    )
+                    .col(ColumnDef::new(AuditLog::Before).json_binary().null())
+                    .col(ColumnDef::new(AuditLog::After).json_binary().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // the log is usually browsed newest first
+        manager
+            .create_index(
+                Index::create()
+                    .table(AuditLog::Table)
+                    .name(Indexes::AuditLogTimestampIdx.to_string())
+                    .col(AuditLog::Timestamp)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(AuditLog::Table)
+                    .name(Indexes::AuditLogTargetIdx.to_string())
+                    .col(AuditLog::TargetKind)
+                    .col(AuditLog::TargetId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .if_exists()
+                    .table(AuditLog::Table)
+                    .name(Indexes::AuditLogTargetIdx.to_string())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .if_exists()
+                    .table(AuditLog::Table)
+                    .name(Indexes::AuditLogTimestampIdx.to_string())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(AuditLog::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Indexes {
+    AuditLogTimestampIdx,
+    AuditLogTargetIdx,
+}
+
+#[derive(DeriveIden)]
+enum AuditLog {
+    Table,
+    Id,
+    Timestamp,
+    Actor,
+    Action,
+    TargetKind,
+    TargetId,
+    Request,
+    Before,
+    After,
+}
@@ -0,0 +1,109 @@
+use crate::{Now, UuidV4};
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(LicensePolicy::Table)
+                    .col(
+                        ColumnDef::new(LicensePolicy::Id)
+                            .uuid()
+                            .not_null()
+                            .default(Func::cust(UuidV4))
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(LicensePolicy::Name).string().not_null())
+                    .col(ColumnDef::new(LicensePolicy::Description).string().null())
+                    .col(
+                        ColumnDef::new(LicensePolicy::Allowed)
+                            .array(ColumnType::Text)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(LicensePolicy::Denied)
+                            .array(ColumnType::Text)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(LicensePolicy::NeedsReview)
+                            .array(ColumnType::Text)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(LicensePolicy::Exceptions)
+                            .array(ColumnType::Text)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(LicensePolicy::Fallback).string().not_null())
+                    .col(
+                        ColumnDef::new(LicensePolicy::Created)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Func::cust(Now)),
+                    )
+                    .col(
+                        ColumnDef::new(LicensePolicy::Modified)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Func::cust(Now)),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(LicensePolicy::Table)
+                    .name(Indexes::LicensePolicyNameIdx.to_string())
+                    .col(LicensePolicy::Name)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .if_exists()
+                    .table(LicensePolicy::Table)
+                    .name(Indexes::LicensePolicyNameIdx.to_string())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(LicensePolicy::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Indexes {
+    LicensePolicyNameIdx,
+}
+
+#[derive(DeriveIden)]
+enum LicensePolicy {
+    Table,
+    Id,
+    Name,
+    Description,
+    Allowed,
+    Denied,
+    NeedsReview,
+    Exceptions,
+    Fallback,
+    Created,
+    Modified,
+}
@@ -0,0 +1,161 @@
+use crate::UuidV4;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// The normalization rules applied to organization names by default, stripping common
+/// legal entity suffixes like `, Inc.`.
+const DEFAULT_RULES: &[(&str, &str)] = &[(
+    r"(?i)[,\s]+(inc|llc|ltd|limited|corp|corporation|gmbh|co)\.?$",
+    "",
+)];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(OrganizationAlias::Table)
+                    .col(
+                        ColumnDef::new(OrganizationAlias::Key)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(OrganizationAlias::Alias).string().not_null())
+                    .col(
+                        ColumnDef::new(OrganizationAlias::OrganizationId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from_col(OrganizationAlias::OrganizationId)
+                            .to(Organization::Table, Organization::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(OrganizationAlias::Table)
+                    .name(Indexes::OrganizationAliasOrganizationIdIdx.to_string())
+                    .col(OrganizationAlias::OrganizationId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(OrganizationRule::Table)
+                    .col(
+                        ColumnDef::new(OrganizationRule::Id)
+                            .uuid()
+                            .not_null()
+                            .default(Func::cust(UuidV4))
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(OrganizationRule::Position)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(OrganizationRule::Pattern)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(OrganizationRule::Replacement)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(OrganizationRule::Description)
+                            .string()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        for (position, (pattern, replacement)) in DEFAULT_RULES.iter().enumerate() {
+            manager
+                .exec_stmt(
+                    Query::insert()
+                        .into_table(OrganizationRule::Table)
+                        .columns([
+                            OrganizationRule::Position,
+                            OrganizationRule::Pattern,
+                            OrganizationRule::Replacement,
+                            OrganizationRule::Description,
+                        ])
+                        .values_panic([
+                            (position as i32).into(),
+                            (*pattern).into(),
+                            (*replacement).into(),
+                            "Strip legal entity suffixes".into(),
+                        ])
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(OrganizationRule::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .if_exists()
+                    .table(OrganizationAlias::Table)
+                    .name(Indexes::OrganizationAliasOrganizationIdIdx.to_string())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(OrganizationAlias::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Indexes {
+    OrganizationAliasOrganizationIdIdx,
+}
+
+#[derive(DeriveIden)]
+enum Organization {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum OrganizationAlias {
+    Table,
+    Key,
+    Alias,
+    OrganizationId,
+}
+
+#[derive(DeriveIden)]
+enum OrganizationRule {
+    Table,
+    Id,
+    Position,
+    Pattern,
+    Replacement,
+    Description,
+}
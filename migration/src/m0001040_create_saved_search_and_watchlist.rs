@@ -0,0 +1,174 @@
+use crate::{Now, UuidV4};
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(SavedSearch::Table)
+                    .col(
+                        ColumnDef::new(SavedSearch::Id)
+                            .uuid()
+                            .not_null()
+                            .default(Func::cust(UuidV4))
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(SavedSearch::UserId).string().not_null())
+                    .col(ColumnDef::new(SavedSearch::Name).string().not_null())
+                    .col(ColumnDef::new(SavedSearch::Kind).string().not_null())
+                    .col(ColumnDef::new(SavedSearch::Q).string().not_null())
+                    .col(ColumnDef::new(SavedSearch::Sort).string().null())
+                    .col(
+                        ColumnDef::new(SavedSearch::Created)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Func::cust(Now)),
+                    )
+                    .col(
+                        ColumnDef::new(SavedSearch::Modified)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Func::cust(Now)),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // names are unique per user and kind of list
+        manager
+            .create_index(
+                Index::create()
+                    .table(SavedSearch::Table)
+                    .name(Indexes::SavedSearchUserIdKindNameIdx.to_string())
+                    .col(SavedSearch::UserId)
+                    .col(SavedSearch::Kind)
+                    .col(SavedSearch::Name)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(Watchlist::Table)
+                    .col(
+                        ColumnDef::new(Watchlist::Id)
+                            .uuid()
+                            .not_null()
+                            .default(Func::cust(UuidV4))
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Watchlist::UserId).string().not_null())
+                    .col(ColumnDef::new(Watchlist::Name).string().not_null())
+                    .col(
+                        ColumnDef::new(Watchlist::Sboms)
+                            .array(ColumnType::Uuid)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Watchlist::Purls)
+                            .array(ColumnType::Text)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Watchlist::Products)
+                            .array(ColumnType::Uuid)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Watchlist::LastChecked)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(Watchlist::Created)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Func::cust(Now)),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // watchlists are always listed for a user
+        manager
+            .create_index(
+                Index::create()
+                    .table(Watchlist::Table)
+                    .name(Indexes::WatchlistUserIdIdx.to_string())
+                    .col(Watchlist::UserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .if_exists()
+                    .table(Watchlist::Table)
+                    .name(Indexes::WatchlistUserIdIdx.to_string())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Watchlist::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .if_exists()
+                    .table(SavedSearch::Table)
+                    .name(Indexes::SavedSearchUserIdKindNameIdx.to_string())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(SavedSearch::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Indexes {
+    SavedSearchUserIdKindNameIdx,
+    WatchlistUserIdIdx,
+}
+
+#[derive(DeriveIden)]
+enum SavedSearch {
+    Table,
+    Id,
+    UserId,
+    Name,
+    Kind,
+    Q,
+    Sort,
+    Created,
+    Modified,
+}
+
+#[derive(DeriveIden)]
+enum Watchlist {
+    Table,
+    Id,
+    UserId,
+    Name,
+    Sboms,
+    Purls,
+    Products,
+    LastChecked,
+    Created,
+}
@@ -0,0 +1,76 @@
+use sea_orm_migration::prelude::*;
+
+/// Adds a generated `search` column to the tables of [`DOCUMENTS`].
+///
+/// The columns are `STORED`, so adding them rewrites the whole table, holding an exclusive lock
+/// on it while doing so. For large tables, like `sbom_node` and `base_purl`, this takes a while
+/// and needs additional disk space for the copy of the table.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// The tables getting a `search` column, and the text it is generated from.
+///
+/// Everything but letters and digits is replaced by whitespace before creating the vector, so
+/// that identifiers like `CVE-2023-0044` or `org.apache` end up as individual words, instead of
+/// getting special treatment by the text search parser. Queries are created the same way, see
+/// `Columns::full_text` in the common crate.
+const DOCUMENTS: &[(&str, &str)] = &[
+    ("advisory", "identifier || ' ' || coalesce(title, '')"),
+    (
+        "advisory_vulnerability",
+        "coalesce(title, '') || ' ' || coalesce(summary, '') || ' ' || coalesce(description, '')",
+    ),
+    ("vulnerability", "id || ' ' || coalesce(title, '')"),
+    ("vulnerability_description", "description"),
+    ("sbom", "coalesce(document_id, '')"),
+    ("sbom_node", "name"),
+    (
+        "base_purl",
+        "type || ' ' || coalesce(namespace, '') || ' ' || name",
+    ),
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        for (table, text) in DOCUMENTS {
+            db.execute_unprepared(&format!(
+                r#"ALTER TABLE "{table}" ADD COLUMN "search" tsvector GENERATED ALWAYS AS (to_tsvector('simple', regexp_replace({text}, '[^[:alnum:]]+', ' ', 'g'))) STORED"#
+            ))
+            .await?;
+
+            manager
+                .create_index(
+                    Index::create()
+                        .table(Alias::new(*table))
+                        .name(format!("{table}_search_idx"))
+                        .col(Search)
+                        .index_type(IndexType::FullText)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for (table, _) in DOCUMENTS {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Alias::new(*table))
+                        .drop_column(Search)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+struct Search;
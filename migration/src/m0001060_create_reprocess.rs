@@ -0,0 +1,100 @@
+use crate::{Now, UuidV4};
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Reprocess::Table)
+                    .col(
+                        ColumnDef::new(Reprocess::Id)
+                            .uuid()
+                            .not_null()
+                            .default(Func::cust(UuidV4))
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(Reprocess::Created)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Func::cust(Now)),
+                    )
+                    .col(
+                        ColumnDef::new(Reprocess::Updated)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Func::cust(Now)),
+                    )
+                    .col(ColumnDef::new(Reprocess::State).string().not_null())
+                    .col(
+                        ColumnDef::new(Reprocess::Filter)
+                            .json_binary()
+                            .not_null()
+                            .default(Expr::cust("'{}'::jsonb")),
+                    )
+                    .col(
+                        ColumnDef::new(Reprocess::CursorIngested)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .col(ColumnDef::new(Reprocess::CursorSha256).string().null())
+                    .col(ColumnDef::new(Reprocess::Total).big_integer().not_null())
+                    .col(
+                        ColumnDef::new(Reprocess::Processed)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(Reprocess::Skipped)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(Reprocess::Failed)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(Reprocess::Report)
+                            .json_binary()
+                            .not_null()
+                            .default(Expr::cust("'[]'::jsonb")),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Reprocess::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Reprocess {
+    Table,
+    Id,
+    Created,
+    Updated,
+    State,
+    Filter,
+    CursorIngested,
+    CursorSha256,
+    Total,
+    Processed,
+    Skipped,
+    Failed,
+    Report,
+}
@@ -0,0 +1,156 @@
+use crate::{Now, UuidV4};
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(RetentionPolicy::Table)
+                    .col(
+                        ColumnDef::new(RetentionPolicy::Id)
+                            .uuid()
+                            .not_null()
+                            .default(Func::cust(UuidV4))
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(RetentionPolicy::Name).string().not_null())
+                    .col(ColumnDef::new(RetentionPolicy::Description).string().null())
+                    .col(
+                        ColumnDef::new(RetentionPolicy::Enabled)
+                            .boolean()
+                            .not_null()
+                            .default(true),
+                    )
+                    .col(ColumnDef::new(RetentionPolicy::Target).string().not_null())
+                    .col(
+                        ColumnDef::new(RetentionPolicy::Rule)
+                            .json_binary()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(RetentionPolicy::Exempt)
+                            .json_binary()
+                            .not_null()
+                            .default(Expr::cust("'{}'::jsonb")),
+                    )
+                    .col(
+                        ColumnDef::new(RetentionPolicy::Created)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Func::cust(Now)),
+                    )
+                    .col(
+                        ColumnDef::new(RetentionPolicy::Modified)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Func::cust(Now)),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(RetentionPolicy::Table)
+                    .name(Indexes::RetentionPolicyNameIdx.to_string())
+                    .col(RetentionPolicy::Name)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(RetentionReport::Table)
+                    .col(
+                        ColumnDef::new(RetentionReport::Id)
+                            .uuid()
+                            .not_null()
+                            .default(Func::cust(UuidV4))
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(RetentionReport::Created)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Func::cust(Now)),
+                    )
+                    .col(ColumnDef::new(RetentionReport::DryRun).boolean().not_null())
+                    .col(
+                        ColumnDef::new(RetentionReport::Report)
+                            .json_binary()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(RetentionReport::Table)
+                    .name(Indexes::RetentionReportCreatedIdx.to_string())
+                    .col(RetentionReport::Created)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(RetentionReport::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .if_exists()
+                    .table(RetentionPolicy::Table)
+                    .name(Indexes::RetentionPolicyNameIdx.to_string())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(RetentionPolicy::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Indexes {
+    RetentionPolicyNameIdx,
+    RetentionReportCreatedIdx,
+}
+
+#[derive(DeriveIden)]
+enum RetentionPolicy {
+    Table,
+    Id,
+    Name,
+    Description,
+    Enabled,
+    Target,
+    Rule,
+    Exempt,
+    Created,
+    Modified,
+}
+
+#[derive(DeriveIden)]
+enum RetentionReport {
+    Table,
+    Id,
+    Created,
+    DryRun,
+    Report,
+}
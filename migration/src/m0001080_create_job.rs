@@ -0,0 +1,112 @@
+use crate::UuidV4;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Job::Table)
+                    .col(ColumnDef::new(Job::Name).string().not_null().primary_key())
+                    .col(ColumnDef::new(Job::Description).string().null())
+                    .col(ColumnDef::new(Job::Configuration).json_binary().not_null())
+                    .col(
+                        ColumnDef::new(Job::LastRun)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(Job::LastSuccess)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .col(ColumnDef::new(Job::LastError).string().null())
+                    .col(ColumnDef::new(Job::Heartbeat).decimal().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(JobReport::Table)
+                    .col(
+                        ColumnDef::new(JobReport::Id)
+                            .uuid()
+                            .not_null()
+                            .default(Func::cust(UuidV4))
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(JobReport::Job).string().not_null())
+                    .col(
+                        ColumnDef::new(JobReport::Creation)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(JobReport::Error).string().null())
+                    .col(ColumnDef::new(JobReport::Report).json_binary().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from_col(JobReport::Job)
+                            .to(Job::Table, Job::Name)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(JobReport::Table)
+                    .name(Indexes::JobReportJobCreationIdx.to_string())
+                    .col(JobReport::Job)
+                    .col(JobReport::Creation)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(JobReport::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Job::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Indexes {
+    JobReportJobCreationIdx,
+}
+
+#[derive(DeriveIden)]
+enum Job {
+    Table,
+    Name,
+    Description,
+    Configuration,
+    LastRun,
+    LastSuccess,
+    LastError,
+    Heartbeat,
+}
+
+#[derive(DeriveIden)]
+enum JobReport {
+    Table,
+    Id,
+    Job,
+    Creation,
+    Error,
+    Report,
+}
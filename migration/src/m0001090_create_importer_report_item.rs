@@ -0,0 +1,113 @@
+use crate::UuidV4;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ImporterReportItem::Table)
+                    .col(
+                        ColumnDef::new(ImporterReportItem::Id)
+                            .uuid()
+                            .not_null()
+                            .default(Func::cust(UuidV4))
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ImporterReportItem::Report).uuid().not_null())
+                    .col(ColumnDef::new(ImporterReportItem::File).string().not_null())
+                    .col(
+                        ColumnDef::new(ImporterReportItem::Outcome)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ImporterReportItem::Phase).string().null())
+                    .col(ColumnDef::new(ImporterReportItem::Message).string().null())
+                    .col(ColumnDef::new(ImporterReportItem::Document).string().null())
+                    .col(
+                        ColumnDef::new(ImporterReportItem::DocumentId)
+                            .string()
+                            .null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from_col(ImporterReportItem::Report)
+                            .to(ImporterReport::Table, ImporterReport::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(ImporterReportItem::Table)
+                    .name(Indexes::ImporterReportItemReportOutcomeIdx.to_string())
+                    .col(ImporterReportItem::Report)
+                    .col(ImporterReportItem::Outcome)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Importer::Table)
+                    .add_column(ColumnDef::new(Importer::Retry).uuid().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Importer::Table)
+                    .drop_column(Importer::Retry)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(ImporterReportItem::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Indexes {
+    ImporterReportItemReportOutcomeIdx,
+}
+
+#[derive(DeriveIden)]
+enum Importer {
+    Table,
+    Retry,
+}
+
+#[derive(DeriveIden)]
+enum ImporterReport {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum ImporterReportItem {
+    Table,
+    Id,
+    Report,
+    File,
+    Outcome,
+    Phase,
+    Message,
+    Document,
+    DocumentId,
+}
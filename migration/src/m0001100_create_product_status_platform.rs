@@ -0,0 +1,69 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ProductStatusPlatform::Table)
+                    .col(
+                        ColumnDef::new(ProductStatusPlatform::ProductStatusId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ProductStatusPlatform::CpeId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(ProductStatusPlatform::ProductStatusId)
+                            .col(ProductStatusPlatform::CpeId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from_col(ProductStatusPlatform::ProductStatusId)
+                            .to(ProductStatus::Table, ProductStatus::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from_col(ProductStatusPlatform::CpeId)
+                            .to(Cpe::Table, Cpe::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ProductStatusPlatform::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ProductStatusPlatform {
+    Table,
+    ProductStatusId,
+    CpeId,
+}
+
+#[derive(DeriveIden)]
+enum ProductStatus {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Cpe {
+    Table,
+    Id,
+}
@@ -0,0 +1,66 @@
+use crate::Now;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ApiTokenOwner::Table)
+                    .col(
+                        ColumnDef::new(ApiTokenOwner::UserId)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ApiTokenOwner::Permissions)
+                            .array(ColumnType::Text)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ApiTokenOwner::Updated)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Func::cust(Now)),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // owners of existing tokens are unknown, grant them what their tokens have, until they
+        // log in again
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"
+INSERT INTO api_token_owner (user_id, permissions)
+SELECT user_id, array_agg(DISTINCT permission)
+FROM api_token, unnest(permissions) AS permission
+GROUP BY user_id
+ON CONFLICT DO NOTHING
+"#,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ApiTokenOwner::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ApiTokenOwner {
+    Table,
+    UserId,
+    Permissions,
+    Updated,
+}
@@ -0,0 +1,59 @@
+use crate::{Now, UuidV4};
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(SnapshotImport::Table)
+                    .col(
+                        ColumnDef::new(SnapshotImport::Id)
+                            .uuid()
+                            .not_null()
+                            .default(Func::cust(UuidV4))
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(SnapshotImport::Created)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Func::cust(Now)),
+                    )
+                    .col(
+                        ColumnDef::new(SnapshotImport::Updated)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Func::cust(Now)),
+                    )
+                    .col(ColumnDef::new(SnapshotImport::State).string().not_null())
+                    .col(ColumnDef::new(SnapshotImport::Result).json_binary().null())
+                    .col(ColumnDef::new(SnapshotImport::Error).string().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(SnapshotImport::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum SnapshotImport {
+    Table,
+    Id,
+    Created,
+    Updated,
+    State,
+    Result,
+    Error,
+}
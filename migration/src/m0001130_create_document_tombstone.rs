@@ -0,0 +1,73 @@
+use crate::Now;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(DocumentTombstone::Table)
+                    .col(
+                        ColumnDef::new(DocumentTombstone::Sha256)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(DocumentTombstone::Kind).string().not_null())
+                    .col(
+                        ColumnDef::new(DocumentTombstone::Deleted)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Func::cust(Now)),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // incremental snapshots look up deletions after a point in time
+        manager
+            .create_index(
+                Index::create()
+                    .table(DocumentTombstone::Table)
+                    .name(Indexes::DocumentTombstoneDeletedIdx.to_string())
+                    .col(DocumentTombstone::Deleted)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .if_exists()
+                    .table(DocumentTombstone::Table)
+                    .name(Indexes::DocumentTombstoneDeletedIdx.to_string())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(DocumentTombstone::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Indexes {
+    DocumentTombstoneDeletedIdx,
+}
+
+#[derive(DeriveIden)]
+enum DocumentTombstone {
+    Table,
+    Sha256,
+    Kind,
+    Deleted,
+}
@@ -0,0 +1,78 @@
+use sea_orm_migration::prelude::*;
+
+/// Adds the `name_key` of organizations, used to find them by names only differing in case and
+/// punctuation.
+///
+/// The key is computed by the `organization_name_key` function, for both the stored column and
+/// the names looked up, so that they can't disagree.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute_unprepared(
+            r#"CREATE OR REPLACE FUNCTION organization_name_key(name text) RETURNS text
+LANGUAGE sql IMMUTABLE STRICT PARALLEL SAFE
+AS $$ SELECT regexp_replace(lower(name), '[^[:alnum:]]+', '', 'g') $$"#,
+        )
+        .await?;
+
+        db.execute_unprepared(
+            r#"ALTER TABLE organization ADD COLUMN name_key text GENERATED ALWAYS AS (organization_name_key(name)) STORED"#,
+        )
+        .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(Organization::Table)
+                    .name(Indexes::OrganizationNameKeyIdx.to_string())
+                    .col(Organization::NameKey)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .table(Organization::Table)
+                    .name(Indexes::OrganizationNameKeyIdx.to_string())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Organization::Table)
+                    .drop_column(Organization::NameKey)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(r#"DROP FUNCTION IF EXISTS organization_name_key(text)"#)
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Organization {
+    Table,
+    NameKey,
+}
+
+#[derive(DeriveIden)]
+enum Indexes {
+    OrganizationNameKeyIdx,
+}
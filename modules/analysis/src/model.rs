@@ -139,6 +139,12 @@ impl GraphMap {
         self.map.get(key)
     }
 
+    // Remove the graph with the given key
+    pub fn remove(&self, key: &str) {
+        self.map.invalidate(key);
+        self.map.run_pending_tasks();
+    }
+
     // Clear all graphs from the map
     pub fn clear(&self) {
         self.map.invalidate_all();
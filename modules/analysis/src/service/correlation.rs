@@ -0,0 +1,196 @@
+use crate::{
+    Error,
+    model::{PackageGraph, graph},
+    service::{AnalysisService, ComponentReference, GraphQuery},
+};
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QuerySelect, QueryTrait, RelationTrait,
+};
+use sea_query::{Condition, JoinType};
+use std::sync::Arc;
+use tracing::{Level, instrument};
+use trustify_common::cpe::Component;
+use trustify_entity::{
+    cpe, cpe_purl_mapping, qualified_purl, sbom_node, sbom_package, sbom_package_cpe_ref,
+    sbom_package_purl_ref, versioned_purl,
+};
+use uuid::Uuid;
+
+/// An identity of a component, derived from a CPE/purl mapping of the original query
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Correlated {
+    /// A purl (without version) which maps to the CPE of the query
+    Purl {
+        base_purl_id: Uuid,
+        ty: String,
+        namespace: Option<String>,
+        name: String,
+        version: Option<String>,
+    },
+    /// A CPE vendor/product which maps to the purl of the query
+    Cpe {
+        vendor: String,
+        product: String,
+        version: Option<String>,
+    },
+}
+
+impl Correlated {
+    /// check if a package node matches this correlated identity
+    pub(crate) fn matches(&self, package: &graph::PackageNode) -> bool {
+        match self {
+            Self::Purl {
+                ty,
+                namespace,
+                name,
+                version,
+                ..
+            } => package.purl.iter().any(|purl| {
+                purl.ty == *ty
+                    && purl.namespace == *namespace
+                    && purl.name == *name
+                    && version
+                        .as_ref()
+                        .is_none_or(|version| purl.version.as_ref() == Some(version))
+            }),
+            Self::Cpe {
+                vendor,
+                product,
+                version,
+            } => package.cpe.iter().any(|cpe| {
+                cpe.vendor().as_ref() == vendor
+                    && cpe.product().as_ref() == product
+                    && version.as_ref().is_none_or(|version| {
+                        cpe.version().as_ref() == version || package.version == *version
+                    })
+            }),
+        }
+    }
+}
+
+/// get the value of a CPE component, if it is an actual value
+fn value(component: Component) -> Option<String> {
+    match component {
+        Component::Value(value) => Some(value),
+        Component::Any | Component::NotApplicable => None,
+    }
+}
+
+impl AnalysisService {
+    /// Find the identities a component query correlates to, using the CPE/purl mappings
+    #[instrument(skip(self, connection), err(level=Level::INFO))]
+    pub(crate) async fn correlate<C: ConnectionTrait>(
+        &self,
+        connection: &C,
+        query: GraphQuery<'_>,
+    ) -> Result<Vec<Correlated>, Error> {
+        Ok(match query {
+            GraphQuery::Component(ComponentReference::Purl(purl)) => {
+                let namespace = match &purl.namespace {
+                    Some(namespace) => {
+                        cpe_purl_mapping::Column::PurlNamespace.eq(namespace.as_str())
+                    }
+                    None => cpe_purl_mapping::Column::PurlNamespace.is_null(),
+                };
+
+                cpe_purl_mapping::Entity::find()
+                    .filter(cpe_purl_mapping::Column::PurlType.eq(purl.ty.as_str()))
+                    .filter(namespace)
+                    .filter(cpe_purl_mapping::Column::PurlName.eq(purl.name.as_str()))
+                    .all(connection)
+                    .await?
+                    .into_iter()
+                    .map(|mapping| Correlated::Cpe {
+                        vendor: mapping.cpe_vendor,
+                        product: mapping.cpe_product,
+                        version: purl.version.clone(),
+                    })
+                    .collect()
+            }
+            GraphQuery::Component(ComponentReference::Cpe(cpe)) => {
+                let (Some(vendor), Some(product)) = (value(cpe.vendor()), value(cpe.product()))
+                else {
+                    return Ok(vec![]);
+                };
+
+                cpe_purl_mapping::Entity::find()
+                    .filter(cpe_purl_mapping::Column::CpeVendor.eq(vendor))
+                    .filter(cpe_purl_mapping::Column::CpeProduct.eq(product))
+                    .all(connection)
+                    .await?
+                    .into_iter()
+                    .map(|mapping| Correlated::Purl {
+                        base_purl_id: mapping.base_purl_id,
+                        ty: mapping.purl_type,
+                        namespace: mapping.purl_namespace,
+                        name: mapping.purl_name,
+                        version: value(cpe.version()),
+                    })
+                    .collect()
+            }
+            _ => vec![],
+        })
+    }
+
+    /// Load all SBOMs containing a component with one of the correlated identities
+    #[instrument(skip(self, connection), err(level=Level::INFO))]
+    pub(crate) async fn load_graphs_correlated<C: ConnectionTrait>(
+        &self,
+        connection: &C,
+        correlated: &[Correlated],
+    ) -> Result<Vec<(String, Arc<PackageGraph>)>, Error> {
+        let mut purls = Condition::any();
+        let mut cpes = Condition::any();
+
+        for each in correlated {
+            match each {
+                Correlated::Purl { base_purl_id, .. } => {
+                    purls = purls.add(versioned_purl::Column::BasePurlId.eq(*base_purl_id));
+                }
+                Correlated::Cpe {
+                    vendor, product, ..
+                } => {
+                    cpes = cpes.add(
+                        Condition::all()
+                            .add(cpe::Column::Vendor.eq(vendor.as_str()))
+                            .add(cpe::Column::Product.eq(product.as_str())),
+                    );
+                }
+            }
+        }
+
+        let mut result = vec![];
+
+        if !purls.is_empty() {
+            let subquery = sbom_node::Entity::find()
+                .join(JoinType::Join, sbom_node::Relation::Package.def())
+                .join(JoinType::Join, sbom_package::Relation::Purl.def())
+                .join(JoinType::Join, sbom_package_purl_ref::Relation::Purl.def())
+                .join(
+                    JoinType::Join,
+                    qualified_purl::Relation::VersionedPurl.def(),
+                )
+                .filter(purls)
+                .select_only()
+                .column(sbom_node::Column::SbomId)
+                .distinct()
+                .into_query();
+            result.extend(self.load_graphs_subquery(connection, subquery).await?);
+        }
+
+        if !cpes.is_empty() {
+            let subquery = sbom_node::Entity::find()
+                .join(JoinType::Join, sbom_node::Relation::Package.def())
+                .join(JoinType::Join, sbom_package::Relation::Cpe.def())
+                .join(JoinType::Join, sbom_package_cpe_ref::Relation::Cpe.def())
+                .filter(cpes)
+                .select_only()
+                .column(sbom_node::Column::SbomId)
+                .distinct()
+                .into_query();
+            result.extend(self.load_graphs_subquery(connection, subquery).await?);
+        }
+
+        Ok(result)
+    }
+}
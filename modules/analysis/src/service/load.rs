@@ -248,7 +248,7 @@ impl AnalysisService {
     }
 
     /// Take a select for sboms, and ensure they are loaded and return their IDs.
-    async fn load_graphs_subquery<C: ConnectionTrait>(
+    pub(super) async fn load_graphs_subquery<C: ConnectionTrait>(
         &self,
         connection: &C,
         subquery: SelectStatement,
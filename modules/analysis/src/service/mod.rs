@@ -1,3 +1,4 @@
+mod correlation;
 mod load;
 mod query;
 mod walk;
@@ -15,6 +16,7 @@ use crate::{
     Error,
     config::AnalysisConfig,
     model::{AnalysisStatus, BaseSummary, GraphMap, Node, PackageGraph, graph},
+    service::correlation::Correlated,
 };
 use fixedbitset::FixedBitSet;
 use futures::{StreamExt, stream};
@@ -230,6 +232,11 @@ impl AnalysisService {
         Ok(())
     }
 
+    /// Remove the graph of an SBOM from the cache, e.g. because the SBOM got removed
+    pub fn clear_graph(&self, sbom_id: &str) {
+        self.graph_cache.remove(sbom_id);
+    }
+
     pub async fn status<C: ConnectionTrait>(
         &self,
         connection: &C,
@@ -252,6 +259,7 @@ impl AnalysisService {
     async fn collect_graph<'a, C>(
         &self,
         query: impl Into<GraphQuery<'a>> + Debug,
+        correlated: &[Correlated],
         graphs: &[(String, Arc<PackageGraph>)],
         create: C,
     ) -> Vec<Node>
@@ -269,7 +277,10 @@ impl AnalysisService {
             stream::iter(
                 graph
                     .node_indices()
-                    .filter(|&i| Self::filter(graph, &query, i))
+                    .filter(|&i| {
+                        Self::filter(graph, &query, i)
+                            || Self::filter_correlated(graph, correlated, i)
+                    })
                     .filter_map(|i| graph.node_weight(i).map(|w| (i, w))),
             )
             .then(|(node_index, package_node)| create(graph, node_index, package_node))
@@ -285,46 +296,65 @@ impl AnalysisService {
         options: QueryOptions,
         graphs: &[(String, Arc<PackageGraph>)],
         connection: &C,
+    ) -> Vec<Node> {
+        self.run_correlated_graph_query(query, &[], options, graphs, connection)
+            .await
+    }
+
+    /// Run a graph query, also matching components with one of the correlated identities
+    #[instrument(skip(self, connection))]
+    async fn run_correlated_graph_query<'a, C: ConnectionTrait>(
+        &self,
+        query: impl Into<GraphQuery<'a>> + Debug,
+        correlated: &[Correlated],
+        options: QueryOptions,
+        graphs: &[(String, Arc<PackageGraph>)],
+        connection: &C,
     ) -> Vec<Node> {
         let relationships = options.relationships;
 
-        self.collect_graph(query, graphs, async |graph, node_index, node| {
-            log::debug!(
-                "Discovered node - sbom: {}, node: {}",
-                node.sbom_id,
-                node.node_id
-            );
-            Node {
-                base: node.into(),
-                relationship: None,
-                ancestors: Box::pin(
-                    Collector::new(
-                        graphs,
-                        graph,
-                        node_index,
-                        Direction::Incoming,
-                        options.ancestors,
-                        &relationships,
-                        connection,
+        self.collect_graph(
+            query,
+            correlated,
+            graphs,
+            async |graph, node_index, node| {
+                log::debug!(
+                    "Discovered node - sbom: {}, node: {}",
+                    node.sbom_id,
+                    node.node_id
+                );
+                Node {
+                    base: node.into(),
+                    relationship: None,
+                    ancestors: Box::pin(
+                        Collector::new(
+                            graphs,
+                            graph,
+                            node_index,
+                            Direction::Incoming,
+                            options.ancestors,
+                            &relationships,
+                            connection,
+                        )
+                        .collect(),
                     )
-                    .collect(),
-                )
-                .await,
-                descendants: Box::pin(
-                    Collector::new(
-                        graphs,
-                        graph,
-                        node_index,
-                        Direction::Outgoing,
-                        options.descendants,
-                        &relationships,
-                        connection,
+                    .await,
+                    descendants: Box::pin(
+                        Collector::new(
+                            graphs,
+                            graph,
+                            node_index,
+                            Direction::Outgoing,
+                            options.descendants,
+                            &relationships,
+                            connection,
+                        )
+                        .collect(),
                     )
-                    .collect(),
-                )
-                .await,
-            }
-        })
+                    .await,
+                }
+            },
+        )
         .await
     }
 
@@ -343,9 +373,11 @@ impl AnalysisService {
         let query = query.into();
         let options = options.into();
 
+        let correlated = self.correlate(connection, query).await?;
+
         let graphs = self.load_graphs(connection, &distinct_sbom_ids).await?;
         let components = self
-            .run_graph_query(query, options, &graphs, connection)
+            .run_correlated_graph_query(query, &correlated, options, &graphs, connection)
             .await;
 
         Ok(paginated.paginate_array(&components))
@@ -363,14 +395,34 @@ impl AnalysisService {
         let query = query.into();
         let options = options.into();
 
-        let graphs = self.load_graphs_query(connection, query).await?;
+        let correlated = self.correlate(connection, query).await?;
+
+        let mut graphs = self.load_graphs_query(connection, query).await?;
+        for (id, graph) in self.load_graphs_correlated(connection, &correlated).await? {
+            if !graphs.iter().any(|(existing, _)| *existing == id) {
+                graphs.push((id, graph));
+            }
+        }
+
         let components = self
-            .run_graph_query(query, options, &graphs, connection)
+            .run_correlated_graph_query(query, &correlated, options, &graphs, connection)
             .await;
 
         Ok(paginated.paginate_array(&components))
     }
 
+    /// check if a node in the graph matches one of the correlated identities
+    fn filter_correlated(
+        graph: &Graph<graph::Node, Relationship>,
+        correlated: &[Correlated],
+        i: NodeIndex,
+    ) -> bool {
+        graph.node_weight(i).is_some_and(|node| match node {
+            graph::Node::Package(package) => correlated.iter().any(|c| c.matches(package)),
+            _ => false,
+        })
+    }
+
     /// check if a node in the graph matches the provided query
     fn filter(graph: &Graph<graph::Node, Relationship>, query: &GraphQuery, i: NodeIndex) -> bool {
         match query {
@@ -52,7 +52,7 @@ async fn test_simple_analysis_service(ctx: &TrustifyContext) -> Result<(), anyho
             "doesn't match: {traces:#?}"
         );
     });
-    assert_eq!(analysis_graph.total, 1);
+    assert_eq!(analysis_graph.total, Some(1));
 
     // ensure we set implicit relationship on components with no defined relationships
     let analysis_graph = service
@@ -68,7 +68,7 @@ async fn test_simple_analysis_service(ctx: &TrustifyContext) -> Result<(), anyho
     let analysis_graph = analysis_graph.roots();
     log::debug!("After: {analysis_graph:#?}");
 
-    assert_eq!(analysis_graph.total, 1);
+    assert_eq!(analysis_graph.total, Some(1));
 
     Ok(())
 }
@@ -113,7 +113,7 @@ async fn test_simple_analysis_cyclonedx_service(
             "doesn't match: {traces:#?}"
         );
     });
-    assert_eq!(analysis_graph.total, 1);
+    assert_eq!(analysis_graph.total, Some(1));
 
     // ensure we set implicit relationship on components with no defined relationships
     let analysis_graph = service
@@ -129,7 +129,7 @@ async fn test_simple_analysis_cyclonedx_service(
     let analysis_graph = analysis_graph.root_traces();
     log::debug!("After: {analysis_graph:#?}");
 
-    assert_eq!(analysis_graph.total, 1);
+    assert_eq!(analysis_graph.total, Some(1));
 
     Ok(())
 }
@@ -176,7 +176,7 @@ async fn test_simple_by_name_analysis_service(ctx: &TrustifyContext) -> Result<(
         );
     });
 
-    assert_eq!(analysis_graph.total, 1);
+    assert_eq!(analysis_graph.total, Some(1));
 
     Ok(())
 }
@@ -219,7 +219,7 @@ async fn simple_by_name_analysis_service_filter_rel(
         );
     });
 
-    assert_eq!(analysis_graph.total, 1);
+    assert_eq!(analysis_graph.total, Some(1));
 
     Ok(())
 }
@@ -268,7 +268,7 @@ async fn test_simple_by_purl_analysis_service(ctx: &TrustifyContext) -> Result<(
         );
     });
 
-    assert_eq!(analysis_graph.total, 1);
+    assert_eq!(analysis_graph.total, Some(1));
     Ok(())
 }
 
@@ -325,7 +325,7 @@ async fn test_quarkus_analysis_service(ctx: &TrustifyContext) -> Result<(), anyh
         );
     });
 
-    assert_eq!(analysis_graph.total, 2);
+    assert_eq!(analysis_graph.total, Some(2));
 
     Ok(())
 }
@@ -416,7 +416,7 @@ async fn test_simple_deps_service(ctx: &TrustifyContext) -> Result<(), anyhow::E
         )
         .await?;
 
-    assert_eq!(analysis_graph.total, 1);
+    assert_eq!(analysis_graph.total, Some(1));
 
     // ensure we set implicit relationship on components with no defined relationships
     let analysis_graph = service
@@ -432,7 +432,7 @@ async fn test_simple_deps_service(ctx: &TrustifyContext) -> Result<(), anyhow::E
     let analysis_graph = analysis_graph.roots();
     log::debug!("After: {analysis_graph:#?}");
 
-    assert_eq!(analysis_graph.total, 1);
+    assert_eq!(analysis_graph.total, Some(1));
 
     Ok(())
 }
@@ -453,7 +453,7 @@ async fn test_simple_deps_cyclonedx_service(ctx: &TrustifyContext) -> Result<(),
         )
         .await?;
 
-    assert_eq!(analysis_graph.total, 1);
+    assert_eq!(analysis_graph.total, Some(1));
 
     // ensure we set implicit relationship on component with no defined relationships
     let analysis_graph = service
@@ -465,7 +465,7 @@ async fn test_simple_deps_cyclonedx_service(ctx: &TrustifyContext) -> Result<(),
         )
         .await?
         .roots();
-    assert_eq!(analysis_graph.total, 1);
+    assert_eq!(analysis_graph.total, Some(1));
 
     Ok(())
 }
@@ -487,7 +487,7 @@ async fn test_simple_by_name_deps_service(ctx: &TrustifyContext) -> Result<(), a
         .await?;
 
     assert_eq!(analysis_graph.items.len(), 1);
-    assert_eq!(analysis_graph.total, 1);
+    assert_eq!(analysis_graph.total, Some(1));
 
     assert_eq!(
         analysis_graph.items[0].purl,
@@ -501,6 +501,59 @@ async fn test_simple_by_name_deps_service(ctx: &TrustifyContext) -> Result<(), a
     Ok(())
 }
 
+#[test_context(TrustifyContext)]
+#[test(tokio::test)]
+async fn test_simple_by_correlated_cpe(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    use sea_orm::{ActiveModelTrait, Set};
+    use trustify_entity::cpe_purl_mapping::{self, MappingSource};
+
+    ctx.ingest_documents(["spdx/simple.json"]).await?;
+
+    // "B" only has a purl, map a CPE to it
+    let purl = Purl::from_str("pkg:rpm/redhat/B")?;
+    cpe_purl_mapping::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        cpe_vendor: Set("acme".to_string()),
+        cpe_product: Set("b".to_string()),
+        base_purl_id: Set(purl.package_uuid()),
+        purl_type: Set(purl.ty.clone()),
+        purl_namespace: Set(purl.namespace.clone()),
+        purl_name: Set(purl.name.clone()),
+        source: Set(MappingSource::Curated),
+        created: Default::default(),
+    }
+    .insert(&ctx.db)
+    .await?;
+
+    let service = AnalysisService::new(AnalysisConfig::default());
+
+    let analysis_graph = service
+        .retrieve(
+            &Cpe::from_str("cpe:/a:acme:b")?,
+            QueryOptions::default(),
+            Paginated::default(),
+            &ctx.db,
+        )
+        .await?;
+
+    assert_eq!(analysis_graph.total, Some(1));
+    assert_eq!(analysis_graph.items[0].node_id, "SPDXRef-B");
+
+    // a version must match the version of the purl
+    let analysis_graph = service
+        .retrieve(
+            &Cpe::from_str("cpe:/a:acme:b:1.0")?,
+            QueryOptions::default(),
+            Paginated::default(),
+            &ctx.db,
+        )
+        .await?;
+
+    assert_eq!(analysis_graph.total, Some(0));
+
+    Ok(())
+}
+
 #[test_context(TrustifyContext)]
 #[test(tokio::test)]
 async fn test_simple_by_purl_deps_service(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
@@ -525,7 +578,7 @@ async fn test_simple_by_purl_deps_service(ctx: &TrustifyContext) -> Result<(), a
         vec![Purl::from_str("pkg:rpm/redhat/AA@0.0.0?arch=src")?]
     );
 
-    assert_eq!(analysis_graph.total, 1);
+    assert_eq!(analysis_graph.total, Some(1));
 
     Ok(())
 }
@@ -550,7 +603,7 @@ async fn test_quarkus_deps_service(ctx: &TrustifyContext) -> Result<(), anyhow::
         )
         .await?;
 
-    assert_eq!(analysis_graph.total, 2);
+    assert_eq!(analysis_graph.total, Some(2));
 
     Ok(())
 }
@@ -573,7 +626,7 @@ async fn test_circular_deps_cyclonedx_service(ctx: &TrustifyContext) -> Result<(
         .await?;
 
     // we should get zero, as we don't deal with circular dependencies and don't load such graphs
-    assert_eq!(analysis_graph.total, 0);
+    assert_eq!(analysis_graph.total, Some(0));
 
     Ok(())
 }
@@ -595,7 +648,7 @@ async fn test_circular_deps_spdx_service(ctx: &TrustifyContext) -> Result<(), an
         .await?;
 
     // we should get zero, as we don't deal with circular dependencies and don't load such graphs
-    assert_eq!(analysis_graph.total, 0);
+    assert_eq!(analysis_graph.total, Some(0));
 
     Ok(())
 }
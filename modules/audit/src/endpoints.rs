@@ -0,0 +1,39 @@
+use crate::{
+    model::AuditEntry,
+    service::{AuditService, Error},
+};
+use actix_web::{HttpResponse, Responder, get, web};
+use trustify_auth::{ReadAudit, authorizer::Require};
+use trustify_common::{
+    db::{Database, query::Query},
+    model::{Paginated, PaginatedResults},
+};
+
+/// mount the "audit" module
+pub fn configure(svc: &mut utoipa_actix_web::service_config::ServiceConfig, db: Database) {
+    svc.app_data(web::Data::new(AuditService::new(db)))
+        .service(list);
+}
+
+#[utoipa::path(
+    tag = "audit",
+    operation_id = "listAuditEntries",
+    params(
+        Query,
+        Paginated,
+    ),
+    responses(
+        (status = 200, description = "Matching audit log entries, newest first", body = PaginatedResults<AuditEntry>),
+        (status = 400, description = "The query was invalid"),
+    )
+)]
+#[get("/v2/audit")]
+/// Search the audit log
+async fn list(
+    service: web::Data<AuditService>,
+    web::Query(search): web::Query<Query>,
+    web::Query(paginated): web::Query<Paginated>,
+    _: Require<ReadAudit>,
+) -> Result<impl Responder, Error> {
+    Ok(HttpResponse::Ok().json(service.fetch(search, paginated).await?))
+}
@@ -0,0 +1,7 @@
+//! An audit log of the mutating operations performed through the API.
+
+pub mod endpoints;
+pub mod model;
+pub mod recorder;
+pub mod service;
+pub mod test;
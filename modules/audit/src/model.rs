@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use trustify_entity::audit_log::{self, Action, Request, TargetKind};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// An entry of the audit log
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, ToSchema)]
+pub struct AuditEntry {
+    pub id: Uuid,
+    #[serde(with = "time::serde::rfc3339")]
+    pub timestamp: OffsetDateTime,
+    /// The ID of the user performing the operation, missing for anonymous access
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actor: Option<String>,
+    pub action: Action,
+    pub target_kind: TargetKind,
+    /// The ID of the affected resource
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_id: Option<String>,
+    pub request: Request,
+    /// The state before the operation, only tracked for some operations
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub before: Option<serde_json::Value>,
+    /// The state after the operation, only tracked for some operations
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after: Option<serde_json::Value>,
+}
+
+impl From<audit_log::Model> for AuditEntry {
+    fn from(value: audit_log::Model) -> Self {
+        Self {
+            id: value.id,
+            timestamp: value.timestamp,
+            actor: value.actor,
+            action: value.action,
+            target_kind: value.target_kind,
+            target_id: value.target_id,
+            request: value.request,
+            before: value.before,
+            after: value.after,
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use crate::service::{AuditService, NewAuditEntry};
+use actix_web::{
+    FromRequest, HttpMessage, HttpRequest, dev::Payload, error::ErrorInternalServerError,
+    http::header::USER_AGENT, web,
+};
+use serde::Serialize;
+use std::future::{Ready, ready};
+use trustify_auth::authenticator::user::UserInformation;
+use trustify_entity::audit_log::Request;
+
+pub use trustify_entity::audit_log::{Action, TargetKind};
+
+/// An operation to record in the audit log
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Event {
+    pub action: Action,
+    pub target_kind: TargetKind,
+    pub target_id: Option<String>,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+}
+
+impl Event {
+    pub fn new(action: Action, target_kind: TargetKind, target_id: impl ToString) -> Self {
+        Self {
+            action,
+            target_kind,
+            target_id: Some(target_id.to_string()),
+            before: None,
+            after: None,
+        }
+    }
+
+    /// An operation which doesn't target a single, identifiable resource
+    pub fn untargeted(action: Action, target_kind: TargetKind) -> Self {
+        Self {
+            action,
+            target_kind,
+            target_id: None,
+            before: None,
+            after: None,
+        }
+    }
+
+    /// Set the state before the operation
+    pub fn before(mut self, value: impl Serialize) -> Self {
+        self.before = to_value(value);
+        self
+    }
+
+    /// Set the state after the operation
+    pub fn after(mut self, value: impl Serialize) -> Self {
+        self.after = to_value(value);
+        self
+    }
+}
+
+fn to_value(value: impl Serialize) -> Option<serde_json::Value> {
+    serde_json::to_value(value)
+        .inspect_err(|err| log::warn!("Failed to serialize audit state: {err}"))
+        .ok()
+}
+
+/// Records operations of the current request in the audit log.
+///
+/// The actor and the request metadata are captured when extracting. This requires the
+/// [`AuditService`] to be registered as application data.
+pub struct Audit {
+    service: web::Data<AuditService>,
+    actor: Option<String>,
+    request: Request,
+}
+
+impl Audit {
+    /// Record an operation.
+    ///
+    /// As the operation has already been performed at this point, a failure to record it is
+    /// logged, but not reported to the caller.
+    pub async fn record(&self, event: Event) {
+        let Event {
+            action,
+            target_kind,
+            target_id,
+            before,
+            after,
+        } = event;
+
+        let entry = NewAuditEntry {
+            actor: self.actor.clone(),
+            action,
+            target_kind,
+            target_id,
+            request: self.request.clone(),
+            before,
+            after,
+        };
+
+        if let Err(err) = self.service.record(entry.clone()).await {
+            log::error!("Failed to record audit entry {entry:?}: {err}");
+        }
+    }
+}
+
+impl FromRequest for Audit {
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let Some(service) = req.app_data::<web::Data<AuditService>>().cloned() else {
+            log::error!("Audit service is not configured");
+            return ready(Err(ErrorInternalServerError(
+                "audit service is not configured",
+            )));
+        };
+
+        let actor = match req.extensions().get::<UserInformation>() {
+            Some(UserInformation::Authenticated(details)) => Some(details.id.clone()),
+            Some(UserInformation::Anonymous) | None => None,
+        };
+
+        let request = Request {
+            method: req.method().to_string(),
+            path: req.path().to_string(),
+            remote_address: req
+                .connection_info()
+                .realip_remote_addr()
+                .map(ToString::to_string),
+            user_agent: req
+                .headers()
+                .get(USER_AGENT)
+                .and_then(|value| value.to_str().ok())
+                .map(ToString::to_string),
+        };
+
+        ready(Ok(Self {
+            service,
+            actor,
+            request,
+        }))
+    }
+}
@@ -0,0 +1,119 @@
+use crate::model::AuditEntry;
+use actix_web::{HttpResponse, ResponseError, body::BoxBody};
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, EntityTrait, QueryOrder};
+use time::OffsetDateTime;
+use trustify_common::{
+    db::{
+        Database,
+        limiter::LimiterTrait,
+        query::{self, Columns, Filtering, Query},
+    },
+    error::ErrorInformation,
+    model::{Paginated, PaginatedResults},
+};
+use trustify_entity::audit_log::{self, Action, Request, TargetKind};
+use uuid::Uuid;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    Query(#[from] query::Error),
+    #[error("database error: {0}")]
+    Database(#[from] sea_orm::DbErr),
+}
+
+impl ResponseError for Error {
+    fn error_response(&self) -> HttpResponse<BoxBody> {
+        match self {
+            Error::Query(err) => {
+                HttpResponse::BadRequest().json(ErrorInformation::new("Query error", err))
+            }
+            Error::Database(err) => {
+                HttpResponse::InternalServerError().json(ErrorInformation::new("Internal", err))
+            }
+        }
+    }
+}
+
+/// A new entry for the audit log
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NewAuditEntry {
+    pub actor: Option<String>,
+    pub action: Action,
+    pub target_kind: TargetKind,
+    pub target_id: Option<String>,
+    pub request: Request,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+}
+
+#[derive(Clone, Debug)]
+pub struct AuditService {
+    db: Database,
+}
+
+impl AuditService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Append an entry to the audit log
+    pub async fn record(&self, entry: NewAuditEntry) -> Result<AuditEntry, Error> {
+        let NewAuditEntry {
+            actor,
+            action,
+            target_kind,
+            target_id,
+            request,
+            before,
+            after,
+        } = entry;
+
+        let model = audit_log::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            timestamp: Set(OffsetDateTime::now_utc()),
+            actor: Set(actor),
+            action: Set(action),
+            target_kind: Set(target_kind),
+            target_id: Set(target_id),
+            request: Set(request),
+            before: Set(before),
+            after: Set(after),
+        }
+        .insert(&self.db)
+        .await?;
+
+        Ok(model.into())
+    }
+
+    /// Search the audit log, newest entries first unless sorted otherwise
+    pub async fn fetch(
+        &self,
+        search: Query,
+        paginated: Paginated,
+    ) -> Result<PaginatedResults<AuditEntry>, Error> {
+        let limiter = audit_log::Entity::find()
+            .filtering_with(
+                search,
+                Columns::from_entity::<audit_log::Entity>().json_keys(
+                    "request",
+                    &["method", "path", "remote_address", "user_agent"],
+                ),
+            )?
+            .order_by_desc(audit_log::Column::Timestamp)
+            .limiting(&self.db, &paginated);
+
+        let total = limiter.total().await?;
+
+        Ok(PaginatedResults {
+            total,
+            items: limiter
+                .fetch()
+                .await?
+                .into_iter()
+                .map(AuditEntry::from)
+                .collect(),
+            next: None,
+        })
+    }
+}
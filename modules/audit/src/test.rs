@@ -0,0 +1,164 @@
+#![cfg(test)]
+
+use crate::{
+    recorder::{Action, Audit, Event, TargetKind},
+    service::{AuditService, NewAuditEntry},
+};
+use actix_web::{App, HttpResponse, Responder, http::StatusCode, post, test as actix, web};
+use serde_json::{Value, json};
+use test_context::test_context;
+use test_log::test;
+use trustify_common::{db::query::Query, model::Paginated};
+use trustify_entity::audit_log::Request;
+use trustify_test_context::{TrustifyContext, app::TestApp, auth::TestAuthentication};
+use utoipa_actix_web::AppExt;
+
+fn entry(action: Action, target_kind: TargetKind, target_id: &str) -> NewAuditEntry {
+    NewAuditEntry {
+        actor: Some("user-a".into()),
+        action,
+        target_kind,
+        target_id: Some(target_id.into()),
+        request: Request {
+            method: "DELETE".into(),
+            path: format!("/api/v2/{target_kind}/{target_id}"),
+            remote_address: None,
+            user_agent: Some("curl/8.0".into()),
+        },
+        before: None,
+        after: None,
+    }
+}
+
+#[test_context(TrustifyContext, skip_teardown)]
+#[test(tokio::test)]
+async fn record_and_search(ctx: TrustifyContext) -> anyhow::Result<()> {
+    let service = AuditService::new(ctx.db.clone());
+
+    service
+        .record(entry(Action::Delete, TargetKind::Sbom, "sbom-1"))
+        .await?;
+    service
+        .record(entry(Action::Force, TargetKind::Importer, "cve"))
+        .await?;
+    service
+        .record(entry(Action::Delete, TargetKind::Advisory, "advisory-1"))
+        .await?;
+
+    // newest first
+
+    let result = service
+        .fetch(Query::default(), Paginated::default())
+        .await?;
+    assert_eq!(result.total, Some(3));
+    let ids = result
+        .items
+        .iter()
+        .map(|entry| entry.target_id.as_deref())
+        .collect::<Vec<_>>();
+    assert_eq!(ids, vec![Some("advisory-1"), Some("cve"), Some("sbom-1")]);
+
+    // filter by column
+
+    let result = service
+        .fetch(Query::q("action=delete"), Paginated::default())
+        .await?;
+    assert_eq!(result.total, Some(2));
+
+    let result = service
+        .fetch(
+            Query::q("target_kind=importer&actor=user-a"),
+            Paginated::default(),
+        )
+        .await?;
+    assert_eq!(result.total, Some(1));
+    assert_eq!(result.items[0].target_id.as_deref(), Some("cve"));
+
+    // filter by request metadata
+
+    let result = service
+        .fetch(Query::q("path~sbom-1"), Paginated::default())
+        .await?;
+    assert_eq!(result.total, Some(1));
+    assert_eq!(result.items[0].target_kind, TargetKind::Sbom);
+
+    // invalid fields are rejected
+
+    assert!(
+        service
+            .fetch(Query::q("foo=bar"), Paginated::default())
+            .await
+            .is_err()
+    );
+
+    Ok(())
+}
+
+#[post("/v2/example/{id}/labels")]
+async fn example(audit: Audit, id: web::Path<String>) -> impl Responder {
+    audit
+        .record(
+            Event::new(Action::Update, TargetKind::SbomLabels, id.into_inner())
+                .before(json!({"a": "1"}))
+                .after(json!({"a": "2"})),
+        )
+        .await;
+    HttpResponse::NoContent().finish()
+}
+
+#[test_context(TrustifyContext, skip_teardown)]
+#[test(actix_web::test)]
+async fn endpoints(ctx: TrustifyContext) -> anyhow::Result<()> {
+    let db = ctx.db;
+    let app = actix::init_service(
+        App::new()
+            .into_utoipa_app()
+            .add_test_authorizer()
+            .service(utoipa_actix_web::scope("/api").configure(|svc| {
+                crate::endpoints::configure(svc, db);
+                svc.service(example);
+            }))
+            .into_app(),
+    )
+    .await;
+
+    // perform an audited operation
+
+    let req = actix::TestRequest::post()
+        .uri("/api/v2/example/123/labels")
+        .insert_header(("user-agent", "test-client"))
+        .to_request()
+        .test_auth("user-a");
+    let resp = actix::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+    // and find it in the log
+
+    let req = actix::TestRequest::get()
+        .uri("/api/v2/audit?q=target_kind%3Dsbom_labels")
+        .to_request();
+    let resp = actix::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let result: Value = actix::read_body_json(resp).await;
+
+    assert_eq!(result["total"], json!(1));
+    let item = &result["items"][0];
+    assert_eq!(item["actor"], json!("user-a"));
+    assert_eq!(item["action"], json!("update"));
+    assert_eq!(item["target_id"], json!("123"));
+    assert_eq!(item["request"]["method"], json!("POST"));
+    assert_eq!(item["request"]["path"], json!("/api/v2/example/123/labels"));
+    assert_eq!(item["request"]["user_agent"], json!("test-client"));
+    assert_eq!(item["before"], json!({"a": "1"}));
+    assert_eq!(item["after"], json!({"a": "2"}));
+
+    // invalid queries are a bad request
+
+    let req = actix::TestRequest::get()
+        .uri("/api/v2/audit?q=foo%3Dbar")
+        .to_request();
+    let resp = actix::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
@@ -1,9 +1,9 @@
 use crate::advisory::service::AdvisoryService;
 use actix_web::{HttpResponse, Responder, patch, put, web};
 use trustify_auth::{UpdateAdvisory, authorizer::Require};
-use trustify_common::db::Database;
 use trustify_common::id::Id;
-use trustify_entity::labels::Labels;
+use trustify_entity::labels::{Labels, LabelsChange};
+use trustify_module_audit::recorder::{Action, Audit, Event, TargetKind};
 
 /// Replace the labels of an advisory
 #[utoipa::path(
@@ -21,20 +21,13 @@ use trustify_entity::labels::Labels;
 #[put("/v2/advisory/{id}/label")]
 pub async fn set(
     advisory: web::Data<AdvisoryService>,
-    db: web::Data<Database>,
     id: web::Path<Id>,
     web::Json(labels): web::Json<Labels>,
+    audit: Audit,
     _: Require<UpdateAdvisory>,
 ) -> actix_web::Result<impl Responder> {
-    Ok(
-        match advisory
-            .set_labels(id.into_inner(), labels, db.as_ref())
-            .await?
-        {
-            Some(()) => HttpResponse::NoContent(),
-            None => HttpResponse::NotFound(),
-        },
-    )
+    let change = advisory.update_labels(id.into_inner(), |_| labels).await?;
+    Ok(respond(change, audit).await)
 }
 
 /// Modify existing labels of an advisory
@@ -55,15 +48,27 @@ pub async fn update(
     advisory: web::Data<AdvisoryService>,
     id: web::Path<Id>,
     web::Json(update): web::Json<Labels>,
+    audit: Audit,
     _: Require<UpdateAdvisory>,
 ) -> actix_web::Result<impl Responder> {
-    Ok(
-        match advisory
-            .update_labels(id.into_inner(), |labels| labels.apply(update))
-            .await?
-        {
-            Some(()) => HttpResponse::NoContent(),
-            None => HttpResponse::NotFound(),
-        },
-    )
+    let change = advisory
+        .update_labels(id.into_inner(), |labels| labels.apply(update))
+        .await?;
+    Ok(respond(change, audit).await)
+}
+
+async fn respond(change: Option<LabelsChange>, audit: Audit) -> HttpResponse {
+    match change {
+        Some(LabelsChange { id, before, after }) => {
+            audit
+                .record(
+                    Event::new(Action::Update, TargetKind::AdvisoryLabels, id.urn())
+                        .before(before)
+                        .after(after),
+                )
+                .await;
+            HttpResponse::NoContent().finish()
+        }
+        None => HttpResponse::NotFound().finish(),
+    }
 }
@@ -10,23 +10,30 @@ use crate::{
         service::AdvisoryService,
     },
     endpoints::Deprecation,
+    export::{self, Export, ExportFormat},
+    history::{model::IngestionEvent, service::HistoryService},
     purl::service::PurlService,
 };
-use actix_web::{HttpResponse, Responder, delete, get, http::header, post, web};
+use actix_web::{HttpRequest, HttpResponse, Responder, delete, get, http::header, post, web};
 use config::Config;
 use futures_util::TryStreamExt;
 use sea_orm::TransactionTrait;
+use std::collections::BTreeMap;
 use std::str::FromStr;
-use trustify_auth::authorizer::Require;
 use trustify_auth::{CreateAdvisory, DeleteAdvisory, ReadAdvisory};
+use trustify_auth::{authenticator::user::UserInformation, authorizer::Require};
 use trustify_common::{
-    db::{Database, query::Query},
+    db::{Database, facet::Facets, query::Query},
     decompress::decompress_async,
     id::Id,
-    model::{BinaryData, Paginated, PaginatedResults},
+    model::{BinaryData, FacetCount, Paginated, PaginatedResults},
+};
+use trustify_entity::{ingestion_history::DocumentKind, labels::Labels};
+use trustify_module_audit::recorder::{Action, Audit, Event, TargetKind};
+use trustify_module_ingestor::{
+    graph::history::Origin,
+    service::{Format, IngestorService},
 };
-use trustify_entity::labels::Labels;
-use trustify_module_ingestor::service::{Format, IngestorService};
 use trustify_module_storage::service::StorageBackend;
 use utoipa::IntoParams;
 
@@ -37,17 +44,21 @@ pub fn configure(
 ) {
     let advisory_service = AdvisoryService::new(db.clone());
     let purl_service = PurlService::new();
+    let history_service = HistoryService::new();
 
     config
         .app_data(web::Data::new(db))
         .app_data(web::Data::new(advisory_service))
         .app_data(web::Data::new(purl_service))
+        .app_data(web::Data::new(history_service))
         .app_data(web::Data::new(Config { upload_limit }))
         .service(all)
+        .service(facets)
         .service(get)
         .service(delete)
         .service(upload)
         .service(download)
+        .service(history)
         .service(label::set)
         .service(label::update);
 }
@@ -59,21 +70,43 @@ pub fn configure(
         Query,
         Paginated,
         Deprecation,
+        Export,
     ),
     responses(
-        (status = 200, description = "Matching vulnerabilities", body = PaginatedResults<AdvisorySummary>),
+        (status = 200, description = "Matching vulnerabilities", content(
+            (PaginatedResults<AdvisorySummary> = "application/json"),
+            (String = "text/csv"),
+            (String = "application/x-ndjson"),
+        )),
     ),
 )]
 #[get("/v2/advisory")]
 /// List advisories
+///
+/// Using an `Accept` header of `text/csv` or `application/x-ndjson`, all matching
+/// advisories are streamed in that format, without pagination.
 pub async fn all(
+    req: HttpRequest,
     state: web::Data<AdvisoryService>,
     db: web::Data<Database>,
     web::Query(search): web::Query<Query>,
     web::Query(paginated): web::Query<Paginated>,
     web::Query(Deprecation { deprecated }): web::Query<Deprecation>,
+    web::Query(export): web::Query<Export>,
     _: Require<ReadAdvisory>,
 ) -> actix_web::Result<impl Responder> {
+    if let Some(format) = ExportFormat::accepted(&req) {
+        return Ok(export::respond(format, export, move |sink| async move {
+            sink.send_all(
+                state
+                    .stream_advisories(search, deprecated, db.as_ref())
+                    .await?,
+            )
+            .await
+        })
+        .await?);
+    }
+
     Ok(HttpResponse::Ok().json(
         state
             .fetch_advisories(search, paginated, deprecated, db.as_ref())
@@ -81,6 +114,35 @@ pub async fn all(
     ))
 }
 
+#[utoipa::path(
+    tag = "advisory",
+    operation_id = "listAdvisoryFacets",
+    params(
+        Query,
+        Facets,
+        Deprecation,
+    ),
+    responses(
+        (status = 200, description = "Counts of the matching advisories, by facet", body = BTreeMap<String, Vec<FacetCount>>),
+    ),
+)]
+#[get("/v2/advisory/facets")]
+/// Count advisories by the values of facets
+pub async fn facets(
+    state: web::Data<AdvisoryService>,
+    db: web::Data<Database>,
+    web::Query(search): web::Query<Query>,
+    web::Query(facets): web::Query<Facets>,
+    web::Query(Deprecation { deprecated }): web::Query<Deprecation>,
+    _: Require<ReadAdvisory>,
+) -> actix_web::Result<impl Responder> {
+    Ok(HttpResponse::Ok().json(
+        state
+            .fetch_facets(search, facets, deprecated, db.as_ref())
+            .await?,
+    ))
+}
+
 #[utoipa::path(
     tag = "advisory",
     operation_id = "getAdvisory",
@@ -128,6 +190,7 @@ pub async fn delete(
     db: web::Data<Database>,
     purl_service: web::Data<PurlService>,
     key: web::Path<String>,
+    audit: Audit,
     _: Require<DeleteAdvisory>,
 ) -> Result<impl Responder, Error> {
     let tx = db.begin().await?;
@@ -142,6 +205,13 @@ pub async fn delete(
             1 => {
                 let _ = purl_service.gc_purls(&tx).await; // ignore gc failure..
                 tx.commit().await?;
+                audit
+                    .record(Event::new(
+                        Action::Delete,
+                        TargetKind::Advisory,
+                        fetched.head.uuid.urn(),
+                    ))
+                    .await;
                 Ok(HttpResponse::Ok().json(fetched))
             }
             _ => Err(Error::Internal("Unexpected number of rows affected".into())),
@@ -183,13 +253,21 @@ pub async fn upload(
     web::Query(UploadParams { issuer, labels }): web::Query<UploadParams>,
     content_type: Option<web::Header<header::ContentType>>,
     bytes: web::Bytes,
+    user: UserInformation,
+    audit: Audit,
     _: Require<CreateAdvisory>,
 ) -> Result<impl Responder, Error> {
     let bytes = decompress_async(bytes, content_type.map(|ct| ct.0), config.upload_limit).await??;
+    let origin = Origin {
+        user_id: user.id().map(ToString::to_string),
+    };
     let result = service
-        .ingest(&bytes, Format::Advisory, labels, issuer)
+        .ingest_with_origin(&bytes, Format::Advisory, labels, issuer, origin)
         .await?;
     log::info!("Uploaded Advisory: {}", result.id);
+    audit
+        .record(Event::new(Action::Upload, TargetKind::Advisory, &result.id))
+        .await;
     Ok(HttpResponse::Created().json(result))
 }
 
@@ -239,3 +317,45 @@ pub async fn download(
         Ok(HttpResponse::NotFound().finish())
     }
 }
+
+#[utoipa::path(
+    tag = "advisory",
+    operation_id = "getAdvisoryHistory",
+    params(
+        ("key" = String, Path, description = "Digest/hash of the document, prefixed by hash type, such as 'sha256:<hash>' or 'urn:uuid:<uuid>'"),
+        Paginated,
+    ),
+    responses(
+        (status = 200, description = "The ingestion history of the advisory, most recent first", body = PaginatedResults<IngestionEvent>),
+        (status = 404, description = "Matching advisory not found"),
+    ),
+)]
+#[get("/v2/advisory/{key}/history")]
+/// Get the ingestion history of an advisory
+///
+/// This includes all versions of the advisory, sharing the same identifier.
+pub async fn history(
+    advisory: web::Data<AdvisoryService>,
+    history: web::Data<HistoryService>,
+    db: web::Data<Database>,
+    key: web::Path<String>,
+    web::Query(paginated): web::Query<Paginated>,
+    _: Require<ReadAdvisory>,
+) -> Result<impl Responder, Error> {
+    let id = Id::from_str(&key).map_err(Error::IdKey)?;
+
+    let Some(advisory) = advisory.fetch_advisory(id, db.as_ref()).await? else {
+        return Ok(HttpResponse::NotFound().finish());
+    };
+
+    Ok(HttpResponse::Ok().json(
+        history
+            .fetch_history(
+                DocumentKind::Advisory,
+                advisory.head.uuid,
+                paginated,
+                db.as_ref(),
+            )
+            .await?,
+    ))
+}
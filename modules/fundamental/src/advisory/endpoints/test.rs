@@ -3,7 +3,7 @@ use crate::{
     test::caller,
 };
 use actix_http::StatusCode;
-use actix_web::test::TestRequest;
+use actix_web::{http::header, test::TestRequest};
 use hex::ToHex;
 use jsonpath_rust::JsonPathQuery;
 use serde_json::{Value, json};
@@ -40,6 +40,7 @@ async fn all_advisories(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
                 published: Some(OffsetDateTime::now_utc()),
                 modified: None,
                 withdrawn: None,
+                format: None,
             },
             &ctx.db,
         )
@@ -78,6 +79,7 @@ async fn all_advisories(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
                 published: Some(OffsetDateTime::now_utc()),
                 modified: None,
                 withdrawn: None,
+                format: None,
             },
             &ctx.db,
         )
@@ -129,6 +131,7 @@ async fn one_advisory(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
                 published: Some(OffsetDateTime::now_utc()),
                 modified: None,
                 withdrawn: None,
+                format: None,
             },
             &ctx.db,
         )
@@ -148,6 +151,7 @@ async fn one_advisory(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
                 published: Some(OffsetDateTime::now_utc()),
                 modified: None,
                 withdrawn: None,
+                format: None,
             },
             &ctx.db,
         )
@@ -226,6 +230,7 @@ async fn one_advisory_by_uuid(ctx: &TrustifyContext) -> Result<(), anyhow::Error
                 published: Some(OffsetDateTime::now_utc()),
                 modified: None,
                 withdrawn: None,
+                format: None,
             },
             &ctx.db,
         )
@@ -245,6 +250,7 @@ async fn one_advisory_by_uuid(ctx: &TrustifyContext) -> Result<(), anyhow::Error
                 published: Some(OffsetDateTime::now_utc()),
                 modified: None,
                 withdrawn: None,
+                format: None,
             },
             &ctx.db,
         )
@@ -315,30 +321,165 @@ async fn search_advisories(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
 
     // No results before ingestion
     let result = query(&app, "").await;
-    assert_eq!(result.total, 0);
+    assert_eq!(result.total, Some(0));
 
     // ingest some advisories
     ctx.ingest_documents(["mitre/CVE-2024-27088.json", "mitre/CVE-2024-28111.json"])
         .await?;
 
     let result = query(&app, "").await;
-    assert_eq!(result.total, 2);
+    assert_eq!(result.total, Some(2));
     assert_eq!(result.items[0].head.identifier, "CVE-2024-28111");
     let result = query(&app, "ingested>last week").await;
-    assert_eq!(result.total, 2);
+    assert_eq!(result.total, Some(2));
     assert_eq!(result.items[0].head.identifier, "CVE-2024-28111");
     let result = query(&app, "csv").await;
-    assert_eq!(result.total, 1);
+    assert_eq!(result.total, Some(1));
     assert_eq!(result.items[0].head.identifier, "CVE-2024-28111");
     let result = query(&app, "function#copy").await;
-    assert_eq!(result.total, 1);
+    assert_eq!(result.total, Some(1));
     assert_eq!(result.items[0].head.identifier, "CVE-2024-27088");
     let result = query(&app, "tostringtokens").await;
-    assert_eq!(result.total, 1);
+    assert_eq!(result.total, Some(1));
     assert_eq!(result.items[0].head.identifier, "CVE-2024-27088");
     let result = query(&app, "es5-ext").await;
     assert_eq!(result.items[0].head.identifier, "CVE-2024-27088");
-    assert_eq!(result.total, 1);
+    assert_eq!(result.total, Some(1));
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn rank_advisories(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    async fn query(app: &impl CallService, q: &str) -> PaginatedResults<AdvisorySummary> {
+        let uri = format!(
+            "/api/v2/advisory?q={}&sort={}",
+            urlencoding::encode(q),
+            urlencoding::encode("rank:desc")
+        );
+        let req = TestRequest::get().uri(&uri).to_request();
+        app.call_and_read_body_json(req).await
+    }
+    let app = caller(ctx).await?;
+
+    ctx.ingest_documents(["mitre/CVE-2024-27088.json", "mitre/CVE-2024-28111.json"])
+        .await?;
+
+    // without a search, there's nothing to rank by
+    let result = query(&app, "").await;
+    assert_eq!(result.total, Some(2));
+
+    // matching words of the description, not just the title
+    let result = query(&app, "canarytokens").await;
+    assert_eq!(result.total, Some(1));
+    assert_eq!(result.items[0].head.identifier, "CVE-2024-28111");
+
+    // words are matched by prefix
+    let result = query(&app, "ECMAScr").await;
+    assert_eq!(result.total, Some(1));
+    assert_eq!(result.items[0].head.identifier, "CVE-2024-27088");
+
+    // substrings of the title still match, even if they don't start a word
+    let result = query(&app, "StringTok").await;
+    assert_eq!(result.total, Some(1));
+    assert_eq!(result.items[0].head.identifier, "CVE-2024-27088");
+
+    let result = query(&app, "csv|es5").await;
+    assert_eq!(result.total, Some(2));
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn advisory_facets(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    async fn facets(app: &impl CallService, facets: &str, q: &str) -> Value {
+        let uri = format!(
+            "/api/v2/advisory/facets?facets={}&q={}",
+            urlencoding::encode(facets),
+            urlencoding::encode(q)
+        );
+        let req = TestRequest::get().uri(&uri).to_request();
+        app.call_and_read_body_json(req).await
+    }
+    let app = caller(ctx).await?;
+
+    ctx.ingest_documents(["mitre/CVE-2024-27088.json", "mitre/CVE-2024-28111.json"])
+        .await?;
+
+    let result = facets(&app, "published:year,published:month", "").await;
+    assert_eq!(
+        result,
+        json!({
+            "published:month": [
+                { "value": "2024-02", "count": 1 },
+                { "value": "2024-03", "count": 1 },
+            ],
+            "published:year": [
+                { "value": "2024", "count": 2 },
+            ],
+        })
+    );
+
+    // both are issued by the same organization
+    let result = facets(&app, "issuer", "").await;
+    assert_eq!(result["issuer"].as_array().map(Vec::len), Some(1));
+    assert_eq!(result["issuer"][0]["count"], 2);
+
+    // advisories without a score have no severity
+    let result = facets(&app, "average_severity", "csv").await;
+    assert_eq!(
+        result,
+        json!({
+            "average_severity": [
+                { "value": null, "count": 1 },
+            ],
+        })
+    );
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn export_advisories(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let app = caller(ctx).await?;
+
+    ctx.ingest_documents(["mitre/CVE-2024-27088.json", "mitre/CVE-2024-28111.json"])
+        .await?;
+
+    let request = TestRequest::get()
+        .uri("/api/v2/advisory?columns=identifier,average_severity&sort=identifier&limit=1")
+        .insert_header((header::ACCEPT, "text/csv"))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get(header::CONTENT_TYPE),
+        Some(&header::HeaderValue::from_static("text/csv"))
+    );
+    // all advisories, regardless of the limit
+    let body = actix_web::test::read_body(response).await;
+    assert_eq!(
+        body,
+        "identifier,average_severity\nCVE-2024-27088,\nCVE-2024-28111,\n"
+    );
+
+    let request = TestRequest::get()
+        .uri("/api/v2/advisory?columns=identifier&q=csv")
+        .insert_header((header::ACCEPT, "application/x-ndjson"))
+        .to_request();
+    let body = app.call_and_read_body(request).await;
+    assert_eq!(body, "{\"identifier\":\"CVE-2024-28111\"}\n");
+
+    // invalid queries are rejected before streaming
+    let request = TestRequest::get()
+        .uri("/api/v2/advisory?q=foo%3Dbar")
+        .insert_header((header::ACCEPT, "text/csv"))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
 
     Ok(())
 }
@@ -535,6 +676,40 @@ async fn download_advisory_by_id(ctx: &TrustifyContext) -> Result<(), anyhow::Er
     Ok(())
 }
 
+/// Test fetching the ingestion history of an advisory
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn advisory_history(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let app = caller(ctx).await?;
+    let result = ctx.ingest_document(DOC).await?;
+    // re-ingesting the same document is recorded as well
+    ctx.ingest_document(DOC).await?;
+
+    let uri = format!("/api/v2/advisory/{}/history", result.id);
+    let request = TestRequest::get().uri(&uri).to_request();
+    let history: Value = app.call_and_read_body_json(request).await;
+    log::debug!("{history:#?}");
+
+    assert_eq!(history["total"], 2);
+    assert_eq!(history["items"][0]["changed"], false);
+    assert_eq!(history["items"][1]["changed"], true);
+    assert_eq!(history["items"][1]["id"], result.id.to_string());
+    assert!(
+        history["items"][1]["sha256"]
+            .as_str()
+            .is_some_and(|digest| digest.starts_with("sha256:"))
+    );
+
+    // unknown advisory
+
+    let uri = format!("/api/v2/advisory/{}/history", Id::Uuid(Uuid::now_v7()));
+    let request = TestRequest::get().uri(&uri).to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
+
 /// Test setting labels
 #[test_context(TrustifyContext)]
 #[test(actix_web::test)]
@@ -582,7 +757,7 @@ async fn delete_advisory(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
     let advisory_list: PaginatedResults<AdvisorySummary> = app
         .call_and_read_body_json(TestRequest::get().uri("/api/v2/advisory").to_request())
         .await;
-    assert_eq!(advisory_list.total, 1);
+    assert_eq!(advisory_list.total, Some(1));
 
     // first delete should succeed
     let response = app
@@ -600,7 +775,7 @@ async fn delete_advisory(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
     let advisory_list: PaginatedResults<AdvisorySummary> = app
         .call_and_read_body_json(TestRequest::get().uri("/api/v2/advisory").to_request())
         .await;
-    assert_eq!(advisory_list.total, 0);
+    assert_eq!(advisory_list.total, Some(0));
 
     // second delete should fail
     let response = app
@@ -1,30 +1,38 @@
 use crate::{
     Error,
     advisory::model::{AdvisoryDetails, AdvisorySummary},
+    export,
 };
+use futures_util::Stream;
 use sea_orm::{
     ActiveModelTrait, ActiveValue::Set, ColumnTypeTrait, ConnectionTrait, DatabaseBackend, DbErr,
     EntityTrait, FromQueryResult, IntoActiveModel, IntoIdentity, QueryResult, QuerySelect,
-    QueryTrait, RelationTrait, Select, Statement, TransactionTrait,
+    QueryTrait, RelationTrait, Select, Statement, StreamTrait, TransactionTrait,
 };
 use sea_query::{ColumnRef, ColumnType, Expr, Func, IntoColumnRef, IntoIden, JoinType, SimpleExpr};
+use std::collections::BTreeMap;
 use trustify_common::{
     db::{
         Database, UpdateDeprecatedAdvisory,
+        facet::{FacetingTrait, Facets},
         limiter::LimiterAsModelTrait,
         multi_model::{FromQueryResultMultiModel, SelectIntoMultiModel},
         query::{Columns, Filtering, Query},
     },
     id::{Id, TrySelectForId},
-    model::{Paginated, PaginatedResults},
+    model::{FacetCount, Paginated, PaginatedResults},
 };
 use trustify_entity::{
-    advisory,
+    advisory, advisory_vulnerability,
     cvss3::{self, Severity},
-    labels::Labels,
+    ingestion_history::DocumentKind,
+    labels::{Labels, LabelsChange},
     organization, source_document,
 };
-use trustify_module_ingestor::common::{Deprecation, DeprecationExt};
+use trustify_module_ingestor::{
+    common::{Deprecation, DeprecationExt},
+    graph::tombstone::record_tombstone,
+};
 use uuid::Uuid;
 
 pub struct AdvisoryService {
@@ -43,84 +51,9 @@ impl AdvisoryService {
         deprecation: Deprecation,
         connection: &C,
     ) -> Result<PaginatedResults<AdvisorySummary>, Error> {
-        // To be able to ORDER or WHERE using a synthetic column, we must first
-        // SELECT col, extra_col FROM (SELECT col, random as extra_col FROM...)
-        // which involves mucking about inside the Select<E> to re-target from
-        // the original underlying table it expects the entity to live in.
-        let inner_query = advisory::Entity::find()
-            .with_deprecation(deprecation)
-            .left_join(cvss3::Entity)
-            .expr_as_(
-                SimpleExpr::FunctionCall(Func::avg(SimpleExpr::Column(
-                    cvss3::Column::Score.into_column_ref(),
-                ))),
-                "average_score",
-            )
-            .expr_as_(
-                SimpleExpr::FunctionCall(Func::cust("cvss3_severity".into_identity()).arg(
-                    SimpleExpr::FunctionCall(Func::avg(SimpleExpr::Column(
-                        cvss3::Column::Score.into_column_ref(),
-                    ))),
-                )),
-                "average_severity",
-            )
-            .group_by(advisory::Column::Id);
-
-        let mut outer_query = advisory::Entity::find();
-
-        // Alias the inner query as exactly the table the entity is expecting
-        // so that column aliases link up correctly.
-        QueryTrait::query(&mut outer_query)
-            .from_clear()
-            .from_subquery(inner_query.into_query(), "advisory".into_identity());
-
-        // And then proceed as usual.
-        let limiter = outer_query
-            .left_join(source_document::Entity)
-            .join(JoinType::LeftJoin, advisory::Relation::Issuer.def())
-            .column_as(
-                SimpleExpr::Column(ColumnRef::Column(
-                    "average_score".into_identity().into_iden(),
-                )),
-                "average_score",
-            )
-            .column_as(
-                SimpleExpr::Column(ColumnRef::Column(
-                    "average_severity".into_identity().into_iden(),
-                ))
-                .cast_as("TEXT".into_identity()),
-                "average_severity",
-            )
-            .filtering_with(
-                search,
-                Columns::from_entity::<advisory::Entity>()
-                    .add_columns(source_document::Entity)
-                    .add_column("average_score", ColumnType::Decimal(None).def())
-                    .add_column(
-                        "average_severity",
-                        ColumnType::Enum {
-                            name: "cvss3_severity".into_identity().into_iden(),
-                            variants: vec![
-                                "none".into_identity().into_iden(),
-                                "low".into_identity().into_iden(),
-                                "medium".into_identity().into_iden(),
-                                "high".into_identity().into_iden(),
-                                "critical".into_identity().into_iden(),
-                            ],
-                        }
-                        .def(),
-                    )
-                    .translator(|f, op, v| match (f, v) {
-                        // v = "" for all sort fields
-                        ("average_severity", "") => Some(format!("average_score:{op}")),
-                        _ => None,
-                    }),
-            )?
-            .try_limiting_as_multi_model::<AdvisoryCatcher>(
-                connection,
-                paginated.offset,
-                paginated.limit,
-            )?;
+        let limiter = advisories(deprecation)
+            .filtering_with(search, columns())?
+            .try_limiting_as_multi_model::<AdvisoryCatcher>(connection, &paginated)?;
 
         let total = limiter.total().await?;
 
@@ -129,9 +62,44 @@ impl AdvisoryService {
         Ok(PaginatedResults {
             total,
             items: AdvisorySummary::from_entities(&items, connection).await?,
+            next: None,
         })
     }
 
+    /// Stream all advisories matching the query, without pagination
+    pub async fn stream_advisories<'a, C: ConnectionTrait + StreamTrait + Sync + Send>(
+        &self,
+        search: Query,
+        deprecation: Deprecation,
+        connection: &'a C,
+    ) -> Result<impl Stream<Item = Result<AdvisorySummary, Error>> + 'a, Error> {
+        let rows = advisories(deprecation)
+            .filtering_with(search, columns())?
+            .try_into_multi_model::<AdvisoryCatcher>()?
+            .stream(connection)
+            .await?;
+
+        Ok(export::chunked(rows, move |rows| async move {
+            AdvisorySummary::from_entities(&rows, connection).await
+        }))
+    }
+
+    /// Count the advisories matching the query by the values of the requested facets, e.g.
+    /// `issuer`, `average_severity` or `published:year`
+    pub async fn fetch_facets<C: ConnectionTrait + Sync + Send>(
+        &self,
+        search: Query,
+        facets: Facets,
+        deprecation: Deprecation,
+        connection: &C,
+    ) -> Result<BTreeMap<String, Vec<FacetCount>>, Error> {
+        Ok(advisories(deprecation)
+            .filtering_with(search, columns())?
+            .faceting(connection, &facets, columns())?
+            .fetch()
+            .await?)
+    }
+
     pub async fn fetch_advisory<C: ConnectionTrait + Sync + Send>(
         &self,
         id: Id,
@@ -205,7 +173,7 @@ impl AdvisoryService {
     ) -> Result<u64, Error> {
         let stmt = Statement::from_sql_and_values(
             connection.get_database_backend(),
-            r#"DELETE FROM advisory WHERE id=$1 RETURNING identifier"#,
+            r#"DELETE FROM advisory WHERE id=$1 RETURNING identifier, source_document_id"#,
             [id.into()],
         );
 
@@ -215,6 +183,9 @@ impl AdvisoryService {
         for row in result {
             let identifier = row.try_get_by_index::<String>(0)?;
             UpdateDeprecatedAdvisory::execute(connection, &identifier).await?;
+            if let Some(source_document_id) = row.try_get_by_index::<Option<Uuid>>(1)? {
+                record_tombstone(DocumentKind::Advisory, source_document_id, connection).await?;
+            }
         }
 
         Ok(rows_affected as u64)
@@ -241,11 +212,11 @@ impl AdvisoryService {
 
     /// Update the labels of an advisory
     ///
-    /// Returns `Ok(Some(()))` if a document was found and updated. If no document was found, it will
-    /// return `Ok(None)`.
+    /// Returns `Ok(Some(change))` if a document was found and updated, containing the labels before
+    /// and after the update. If no document was found, it will return `Ok(None)`.
     ///
     /// The function will handle its own transaction.
-    pub async fn update_labels<F>(&self, id: Id, mutator: F) -> Result<Option<()>, Error>
+    pub async fn update_labels<F>(&self, id: Id, mutator: F) -> Result<Option<LabelsChange>, Error>
     where
         F: FnOnce(Labels) -> Labels,
     {
@@ -272,9 +243,11 @@ impl AdvisoryService {
 
         // perform the mutation
 
-        let labels = result.labels.clone();
+        let advisory_id = result.id;
+        let before = result.labels.clone();
+        let after = mutator(before.clone());
         let mut result = result.into_active_model();
-        result.labels = Set(mutator(labels));
+        result.labels = Set(after.clone());
 
         // store
 
@@ -286,7 +259,11 @@ impl AdvisoryService {
 
         // return
 
-        Ok(Some(()))
+        Ok(Some(LabelsChange {
+            id: advisory_id,
+            before,
+            after,
+        }))
     }
 }
 
@@ -324,5 +301,99 @@ impl FromQueryResultMultiModel for AdvisoryCatcher {
     }
 }
 
+/// Select the advisories, along with their average score and severity
+fn advisories(deprecation: Deprecation) -> Select<advisory::Entity> {
+    // To be able to ORDER or WHERE using a synthetic column, we must first
+    // SELECT col, extra_col FROM (SELECT col, random as extra_col FROM...)
+    // which involves mucking about inside the Select<E> to re-target from
+    // the original underlying table it expects the entity to live in.
+    let inner_query = advisory::Entity::find()
+        .with_deprecation(deprecation)
+        .left_join(cvss3::Entity)
+        .expr_as_(
+            SimpleExpr::FunctionCall(Func::avg(SimpleExpr::Column(
+                cvss3::Column::Score.into_column_ref(),
+            ))),
+            "average_score",
+        )
+        .expr_as_(
+            SimpleExpr::FunctionCall(Func::cust("cvss3_severity".into_identity()).arg(
+                SimpleExpr::FunctionCall(Func::avg(SimpleExpr::Column(
+                    cvss3::Column::Score.into_column_ref(),
+                ))),
+            )),
+            "average_severity",
+        )
+        .expr_as_(
+            Expr::col((advisory::Entity, "search".into_identity())),
+            "search",
+        )
+        .group_by(advisory::Column::Id);
+
+    let mut outer_query = advisory::Entity::find();
+
+    // Alias the inner query as exactly the table the entity is expecting
+    // so that column aliases link up correctly.
+    QueryTrait::query(&mut outer_query)
+        .from_clear()
+        .from_subquery(inner_query.into_query(), "advisory".into_identity());
+
+    // And then proceed as usual.
+    outer_query
+        .left_join(source_document::Entity)
+        .join(JoinType::LeftJoin, advisory::Relation::Issuer.def())
+        .column_as(
+            SimpleExpr::Column(ColumnRef::Column(
+                "average_score".into_identity().into_iden(),
+            )),
+            "average_score",
+        )
+        .column_as(
+            SimpleExpr::Column(ColumnRef::Column(
+                "average_severity".into_identity().into_iden(),
+            ))
+            .cast_as("TEXT".into_identity()),
+            "average_severity",
+        )
+}
+
+/// The columns available for filtering and faceting advisories
+pub fn columns() -> Columns {
+    Columns::from_entity::<advisory::Entity>()
+        .add_columns(source_document::Entity)
+        .full_text(advisory::Entity, "search".into_identity())
+        .full_text_related(
+            advisory_vulnerability::Entity,
+            "search".into_identity(),
+            advisory_vulnerability::Column::AdvisoryId,
+            (advisory::Entity, advisory::Column::Id),
+        )
+        .add_column_as(
+            (organization::Entity, organization::Column::Name),
+            "issuer",
+            ColumnType::Text.def(),
+        )
+        .add_column("average_score", ColumnType::Decimal(None).def())
+        .add_column(
+            "average_severity",
+            ColumnType::Enum {
+                name: "cvss3_severity".into_identity().into_iden(),
+                variants: vec![
+                    "none".into_identity().into_iden(),
+                    "low".into_identity().into_iden(),
+                    "medium".into_identity().into_iden(),
+                    "high".into_identity().into_iden(),
+                    "critical".into_identity().into_iden(),
+                ],
+            }
+            .def(),
+        )
+        .translator(|f, op, v| match (f, v) {
+            // v = "" for all sort fields
+            ("average_severity", "") => Some(format!("average_score:{op}")),
+            _ => None,
+        })
+}
+
 #[cfg(test)]
 pub mod test;
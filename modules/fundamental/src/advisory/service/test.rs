@@ -35,6 +35,7 @@ pub async fn ingest_sample_advisory<'a>(
                 published: Some(OffsetDateTime::now_utc()),
                 modified: None,
                 withdrawn: None,
+                format: None,
             },
             &ctx.db,
         )
@@ -80,7 +81,7 @@ async fn all_advisories(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
         .fetch_advisories(q(""), Paginated::default(), Default::default(), &ctx.db)
         .await?;
 
-    assert_eq!(fetched.total, 2);
+    assert_eq!(fetched.total, Some(2));
     Ok(())
 }
 
@@ -103,7 +104,7 @@ async fn all_advisories_filtered_by_average_score(
         )
         .await?;
 
-    assert_eq!(fetched.total, 1);
+    assert_eq!(fetched.total, Some(1));
     Ok(())
 }
 
@@ -128,7 +129,7 @@ async fn all_advisories_filtered_by_average_severity(
 
     log::debug!("{:#?}", fetched);
 
-    assert_eq!(fetched.total, 1);
+    assert_eq!(fetched.total, Some(1));
     Ok(())
 }
 
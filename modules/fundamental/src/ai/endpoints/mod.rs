@@ -5,36 +5,55 @@ mod test;
 use crate::{
     Error,
     ai::{
-        model::{AiFlags, AiTool, ChatMessage, ChatState, Conversation, ConversationSummary},
-        service::AiService,
+        model::{
+            AiFlags, AiTool, ChatMessage, ChatState, Conversation, ConversationEvent,
+            ConversationSummary,
+        },
+        service::{AiService, events::EventSink, mcp::McpServer},
     },
 };
 use actix_web::{
-    HttpResponse, Responder, delete, get,
+    HttpRequest, HttpResponse, Responder, delete, get,
     http::header::{self, ETag, EntityTag, IfMatch},
     post, put, web,
 };
+use futures_util::{StreamExt, stream};
 use itertools::Itertools;
+use serde::Serialize;
+use serde_json::json;
+use std::future::Future;
 use time::OffsetDateTime;
+use tokio::sync::{mpsc, oneshot};
 use trustify_auth::{Ai, authenticator::user::UserDetails, authorizer::Require};
 use trustify_common::{
     db::{Database, query::Query},
     model::{Paginated, PaginatedResults},
 };
+use trustify_module_analysis::service::AnalysisService;
+use trustify_module_audit::recorder::{Action, Audit, Event, TargetKind};
 use uuid::Uuid;
 
-pub fn configure(config: &mut utoipa_actix_web::service_config::ServiceConfig, db: Database) {
-    let service = AiService::new(db.clone());
+pub fn configure(
+    config: &mut utoipa_actix_web::service_config::ServiceConfig,
+    db: Database,
+    analysis: AnalysisService,
+) {
+    let service = AiService::new(db.clone(), analysis);
+    let mcp = McpServer::new(service.local_tools.clone());
     config
         .app_data(web::Data::new(service))
+        .app_data(web::Data::new(mcp))
         .service(completions)
         .service(flags)
         .service(tools)
         .service(tool_call)
+        .service(mcp_post)
+        .service(mcp_get)
         .service(create_conversation)
         .service(update_conversation)
         .service(list_conversations)
         .service(get_conversation)
+        .service(get_conversation_events)
         .service(delete_conversation);
 }
 
@@ -49,15 +68,71 @@ pub fn configure(config: &mut utoipa_actix_web::service_config::ServiceConfig, d
     )
 )]
 #[post("/v2/ai/completions")]
+/// Run a completion.
+///
+/// If the client only accepts `text/event-stream`, intermediate events are streamed as they
+/// happen, followed by a final `done` event, carrying the resulting completion.
 pub async fn completions(
     service: web::Data<AiService>,
+    req: HttpRequest,
     request: web::Json<ChatState>,
     _: Require<Ai>,
 ) -> actix_web::Result<impl Responder> {
+    let request = request.into_inner();
+
+    if event_stream_only(&req) {
+        return Ok(stream_events(move |events| async move {
+            service.completions_with_events(&request, &events).await
+        }));
+    }
+
     let response = service.completions(&request).await?;
     Ok(HttpResponse::Ok().json(response))
 }
 
+/// Run a completion, streaming its events as server-sent events.
+///
+/// Once the completion finished, its result is sent as `done` event, or as `error` event in case
+/// it failed.
+fn stream_events<T, F>(run: impl FnOnce(EventSink) -> F) -> HttpResponse
+where
+    T: Serialize + 'static,
+    F: Future<Output = Result<T, Error>> + 'static,
+{
+    let (sender, receiver) = mpsc::unbounded_channel();
+    let (result_sender, result_receiver) = oneshot::channel();
+
+    // the sink is dropped once the completion is finished, which ends the stream of events
+    let run = run(EventSink::new(sender));
+    actix_web::rt::spawn(async move {
+        let _ = result_sender.send(run.await);
+    });
+
+    let events = stream::unfold(receiver, |mut receiver| async move {
+        let event = receiver.recv().await?;
+        Some((sse_event(event.name(), &event), receiver))
+    });
+    let done = stream::once(async move {
+        match result_receiver.await {
+            Ok(Ok(result)) => sse_event("done", &result),
+            Ok(Err(err)) => sse_event("error", &json!({ "message": err.to_string() })),
+            Err(_) => sse_event("error", &json!({ "message": "completion aborted" })),
+        }
+    });
+
+    HttpResponse::Ok()
+        .insert_header((header::CONTENT_TYPE, "text/event-stream"))
+        .insert_header((header::CACHE_CONTROL, "no-cache"))
+        .streaming(events.chain(done).map(Ok::<_, actix_web::Error>))
+}
+
+fn sse_event(name: &str, data: &impl Serialize) -> web::Bytes {
+    web::Bytes::from(format!(
+        "event: {name}\ndata: {}\n\n",
+        serde_json::to_string(data).unwrap_or_default()
+    ))
+}
+
 #[utoipa::path(
     tag = "ai",
     operation_id = "aiFlags",
@@ -139,6 +214,73 @@ pub async fn tool_call(
         .body(result))
 }
 
+#[utoipa::path(
+    tag = "ai",
+    operation_id = "mcp",
+    request_body = serde_json::Value,
+    responses(
+        (status = 200, description = "The JSON-RPC response", body = serde_json::Value),
+        (status = 202, description = "The message was a notification, which has no response"),
+        (status = 404, description = "The AI service is not enabled")
+    )
+)]
+#[post("/v2/ai/mcp")]
+/// Model Context Protocol (MCP) endpoint, using the streamable HTTP transport.
+///
+/// Accepts a single JSON-RPC message, or a batch. The response is sent as JSON, unless the client
+/// only accepts `text/event-stream`, in which case it is sent as a single server-sent event.
+pub async fn mcp_post(
+    server: web::Data<McpServer>,
+    req: HttpRequest,
+    body: web::Bytes,
+    _: Require<Ai>,
+) -> actix_web::Result<impl Responder> {
+    let body = std::str::from_utf8(&body).map_err(|err| Error::BadRequest(err.to_string()))?;
+
+    let Some(response) = server.handle_str(body).await else {
+        return Ok(HttpResponse::Accepted().finish());
+    };
+
+    if event_stream_only(&req) {
+        Ok(HttpResponse::Ok()
+            .insert_header((header::CONTENT_TYPE, "text/event-stream"))
+            .body(sse_event("message", &response)))
+    } else {
+        Ok(HttpResponse::Ok().json(response))
+    }
+}
+
+#[utoipa::path(
+    tag = "ai",
+    operation_id = "mcpStream",
+    responses(
+        (status = 405, description = "Server initiated streams are not supported"),
+    )
+)]
+#[get("/v2/ai/mcp")]
+/// Server initiated message streams are not supported, as the server has no notifications to send.
+pub async fn mcp_get(_: Require<Ai>) -> impl Responder {
+    HttpResponse::MethodNotAllowed()
+        .insert_header((header::ALLOW, "POST"))
+        .finish()
+}
+
+/// Check if the client accepts `text/event-stream`, but not `application/json`
+fn event_stream_only(req: &HttpRequest) -> bool {
+    let accept = req
+        .headers()
+        .get_all(header::ACCEPT)
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|value| value.split(';').next().unwrap_or_default().trim())
+        .collect::<Vec<_>>();
+
+    accept.contains(&"text/event-stream")
+        && !accept
+            .iter()
+            .any(|value| matches!(*value, "application/json" | "application/*" | "*/*"))
+}
+
 #[utoipa::path(
     tag = "ai",
     operation_id = "createConversation",
@@ -149,6 +291,10 @@ pub async fn tool_call(
     )
 )]
 #[post("/v2/ai/conversations")]
+/// Create a new, empty conversation.
+///
+/// This only generates the ID of the conversation, it doesn't get stored until messages get
+/// added to it. Which is why it isn't recorded in the audit log either.
 pub async fn create_conversation(_: Require<Ai>) -> actix_web::Result<impl Responder, Error> {
     // generate an assistant response
     let uuid = Uuid::now_v7();
@@ -187,13 +333,19 @@ fn to_offset_date_time(uuid: Uuid) -> Result<OffsetDateTime, Error> {
     )
 )]
 #[put("/v2/ai/conversations/{id}")]
+/// Add messages to a conversation, and run a completion.
+///
+/// If the client only accepts `text/event-stream`, intermediate events are streamed as they
+/// happen, followed by a final `done` event, carrying the resulting conversation.
 pub async fn update_conversation(
     service: web::Data<AiService>,
     db: web::Data<Database>,
     id: web::Path<Uuid>,
     web::Header(if_match): web::Header<IfMatch>,
     user: UserDetails,
+    req: HttpRequest,
     request: web::Json<Vec<ChatMessage>>,
+    audit: Audit,
     _: Require<Ai>,
 ) -> actix_web::Result<impl Responder> {
     let user_id = user.id;
@@ -204,18 +356,42 @@ pub async fn update_conversation(
             .and_then(|etag| etag.tag().parse::<i32>().ok()),
     };
     let conversation_id = id.into_inner();
+    let request = request.into_inner();
 
-    let (conversation, messages) = service
-        .upsert_conversation(conversation_id, user_id, &request, seq, db.as_ref())
-        .await?;
+    let run = move |events: EventSink| async move {
+        let (conversation, messages) = service
+            .upsert_conversation_with_events(
+                conversation_id,
+                user_id,
+                &request,
+                seq,
+                &events,
+                db.as_ref(),
+            )
+            .await?;
 
-    let conversation = Conversation {
-        id: conversation.id,
-        updated_at: conversation.updated_at,
-        messages,
-        seq: conversation.seq,
+        // the messages aren't recorded, as they may be large, and only matter to the user
+        audit
+            .record(Event::new(
+                Action::Update,
+                TargetKind::AiConversation,
+                conversation.id,
+            ))
+            .await;
+
+        Ok::<_, Error>(Conversation {
+            id: conversation.id,
+            updated_at: conversation.updated_at,
+            messages,
+            seq: conversation.seq,
+        })
     };
 
+    if event_stream_only(&req) {
+        return Ok(stream_events(run));
+    }
+
+    let conversation = run(EventSink::default()).await?;
     Ok(HttpResponse::Ok().json(conversation))
 }
 
@@ -258,6 +434,7 @@ pub async fn list_conversations(
             })
             .collect(),
         total: result.total,
+        next: None,
     };
 
     Ok(HttpResponse::Ok().json(result))
@@ -325,6 +502,40 @@ pub async fn get_conversation(
     }
 }
 
+#[utoipa::path(
+    tag = "ai",
+    operation_id = "getConversationEvents",
+    params(
+        ("id", Path, description = "Opaque ID of the conversation")
+    ),
+    responses(
+        (status = 200, description = "The recorded events of the conversation", body = Vec<ConversationEvent>),
+        (status = 404, description = "The AI service is not enabled or the conversation was not found")
+    )
+)]
+#[get("/v2/ai/conversations/{id}/events")]
+/// Get the recorded events of a conversation, to replay it step by step
+pub async fn get_conversation_events(
+    service: web::Data<AiService>,
+    db: web::Data<Database>,
+    id: web::Path<Uuid>,
+    user: UserDetails,
+    _: Require<Ai>,
+) -> actix_web::Result<impl Responder> {
+    let conversation = service
+        .fetch_conversation(id.into_inner(), db.as_ref())
+        .await?;
+
+    match conversation {
+        // make a conversation of another user look like a not found error to avoid leaking
+        // existence of the conversation
+        Some((conversation, _)) if conversation.user_id == user.id => {
+            Ok(HttpResponse::Ok().json(AiService::conversation_events(&conversation)?))
+        }
+        _ => Err(Error::NotFound("conversation not found".to_string()))?,
+    }
+}
+
 #[utoipa::path(
     tag = "ai",
     operation_id = "deleteConversation",
@@ -343,6 +554,7 @@ pub async fn delete_conversation(
     db: web::Data<Database>,
     id: web::Path<Uuid>,
     user: UserDetails,
+    audit: Audit,
     _: Require<Ai>,
 ) -> actix_web::Result<impl Responder> {
     let user_id = user.id;
@@ -370,12 +582,21 @@ pub async fn delete_conversation(
                 .await?;
             match rows_affected {
                 0 => Ok(HttpResponse::NotFound().finish()),
-                1 => Ok(HttpResponse::Ok().json(Conversation {
-                    id: conversation.id,
-                    updated_at: conversation.updated_at,
-                    messages: internal_state.chat_messages(),
-                    seq: conversation.seq,
-                })),
+                1 => {
+                    audit
+                        .record(Event::new(
+                            Action::Delete,
+                            TargetKind::AiConversation,
+                            conversation_id,
+                        ))
+                        .await;
+                    Ok(HttpResponse::Ok().json(Conversation {
+                        id: conversation.id,
+                        updated_at: conversation.updated_at,
+                        messages: internal_state.chat_messages(),
+                        seq: conversation.seq,
+                    }))
+                }
                 _ => Err(Error::Internal("Unexpected number of rows affected".into()))?,
             }
         }
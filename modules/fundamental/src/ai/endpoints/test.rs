@@ -1,6 +1,6 @@
 use crate::ai::model::{ChatMessage, ChatState, Conversation, ConversationSummary};
 use crate::ai::service::AiService;
-use crate::ai::service::test::{ingest_fixtures, sanitize_uuid_field, sanitize_uuid_urn};
+use crate::ai::service::test::{analysis, ingest_fixtures, sanitize_uuid_field, sanitize_uuid_urn};
 use crate::test::caller;
 use actix_http::StatusCode;
 use actix_web::dev::ServiceResponse;
@@ -12,11 +12,12 @@ use trustify_common::model::PaginatedResults;
 use trustify_test_context::TrustifyContext;
 use trustify_test_context::auth::TestAuthentication;
 use trustify_test_context::call::CallService;
+use uuid::Uuid;
 
 #[test_context(TrustifyContext)]
 #[test(actix_web::test)]
 async fn configure(ctx: &TrustifyContext) -> anyhow::Result<()> {
-    let service = AiService::new(ctx.db.clone());
+    let service = AiService::new(ctx.db.clone(), analysis());
     if !service.completions_enabled() {
         return Ok(()); // skip test
     }
@@ -63,7 +64,7 @@ async fn flags(ctx: &TrustifyContext) -> anyhow::Result<()> {
     let result: serde_json::Value = actix_web::test::read_body_json(response).await;
     log::info!("result: {:?}", result);
 
-    let service = AiService::new(ctx.db.clone());
+    let service = AiService::new(ctx.db.clone(), analysis());
 
     assert_eq!(
         result,
@@ -161,10 +162,148 @@ async fn tools_call(ctx: &TrustifyContext) -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn mcp(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    ctx.ingest_document("quarkus/v1/quarkus-bom-2.13.8.Final-redhat-00004.json")
+        .await?;
+
+    let app = caller(ctx).await?;
+
+    // initialize
+
+    let request = TestRequest::post()
+        .uri("/api/v2/ai/mcp")
+        .set_json(json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "initialize",
+            "params": {
+                "protocolVersion": "2025-03-26",
+                "capabilities": {},
+                "clientInfo": { "name": "test", "version": "1.0" }
+            }
+        }))
+        .to_request();
+
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let result: serde_json::Value = read_body_json(response).await;
+    assert_eq!(result["id"], json!(1));
+    assert_eq!(result["result"]["protocolVersion"], json!("2025-03-26"));
+    assert_eq!(result["result"]["serverInfo"]["name"], json!("trustify"));
+
+    // notifications are accepted, without a response
+
+    let request = TestRequest::post()
+        .uri("/api/v2/ai/mcp")
+        .set_json(json!({"jsonrpc": "2.0", "method": "notifications/initialized"}))
+        .to_request();
+
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+
+    // list tools, same as the tools endpoint
+
+    let request = TestRequest::post()
+        .uri("/api/v2/ai/mcp")
+        .set_json(json!({"jsonrpc": "2.0", "id": 2, "method": "tools/list"}))
+        .to_request();
+
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let result: serde_json::Value = read_body_json(response).await;
+
+    let expected: serde_json::Value =
+        serde_json::from_str(include_str!("expected_tools_result.json"))?;
+    let names = |value: &serde_json::Value| {
+        value
+            .as_array()
+            .map(|tools| {
+                tools
+                    .iter()
+                    .map(|tool| tool["name"].clone())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default()
+    };
+    assert_eq!(names(&result["result"]["tools"]), names(&expected));
+    assert_eq!(
+        result["result"]["tools"][0]["inputSchema"],
+        expected[0]["parameters"]
+    );
+
+    // call a tool, streaming the response
+
+    let request = TestRequest::post()
+        .uri("/api/v2/ai/mcp")
+        .insert_header(("accept", "text/event-stream"))
+        .set_json(json!({
+            "jsonrpc": "2.0",
+            "id": 3,
+            "method": "tools/call",
+            "params": { "name": "sbom-info", "arguments": { "input": "quarkus" } }
+        }))
+        .to_request();
+
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let result = read_text(response).await?;
+    let data = result
+        .strip_prefix("event: message\ndata: ")
+        .expect("must be an SSE message");
+    let result: serde_json::Value = serde_json::from_str(data.trim())?;
+    assert_eq!(result["id"], json!(3));
+    assert_eq!(result["result"]["isError"], json!(false));
+    assert!(
+        result["result"]["content"][0]["text"]
+            .as_str()
+            .unwrap_or_default()
+            .contains("quarkus-bom")
+    );
+
+    // call an unknown tool
+
+    let request = TestRequest::post()
+        .uri("/api/v2/ai/mcp")
+        .set_json(json!({
+            "jsonrpc": "2.0",
+            "id": 4,
+            "method": "tools/call",
+            "params": { "name": "unknown", "arguments": {} }
+        }))
+        .to_request();
+
+    let response = app.call_service(request).await;
+    let result: serde_json::Value = read_body_json(response).await;
+    assert_eq!(result["error"]["code"], json!(-32602));
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn conversation_events_not_found(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let app = caller(ctx).await?;
+
+    let request = TestRequest::get()
+        .uri(&format!(
+            "/api/v2/ai/conversations/{}/events",
+            Uuid::now_v7()
+        ))
+        .to_request()
+        .test_auth("user-a");
+
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
+
 #[test_context(TrustifyContext)]
 #[test(actix_web::test)]
 async fn conversation_crud(ctx: &TrustifyContext) -> anyhow::Result<()> {
-    if !AiService::new(ctx.db.clone()).completions_enabled() {
+    if !AiService::new(ctx.db.clone(), analysis()).completions_enabled() {
         return Ok(()); // skip test
     }
 
@@ -188,7 +327,7 @@ async fn conversation_crud(ctx: &TrustifyContext) -> anyhow::Result<()> {
     );
 
     let result: PaginatedResults<ConversationSummary> = read_body_json(response).await;
-    assert_eq!(result.total, 0);
+    assert_eq!(result.total, Some(0));
     assert_eq!(result.items.len(), 0);
 
     // Create a conversation
@@ -240,7 +379,7 @@ async fn conversation_crud(ctx: &TrustifyContext) -> anyhow::Result<()> {
     assert_eq!(response.status(), StatusCode::OK);
 
     let result: PaginatedResults<ConversationSummary> = read_body_json(response).await;
-    assert_eq!(result.total, 1);
+    assert_eq!(result.total, Some(1));
     assert_eq!(result.items.len(), 1);
     assert_eq!(result.items[0].id, conversation_v1.id);
 
@@ -310,7 +449,7 @@ async fn conversation_crud(ctx: &TrustifyContext) -> anyhow::Result<()> {
     assert_eq!(response.status(), StatusCode::OK);
 
     let result: PaginatedResults<ConversationSummary> = read_body_json(response).await;
-    assert_eq!(result.total, 0);
+    assert_eq!(result.total, Some(0));
     assert_eq!(result.items.len(), 0);
 
     Ok(())
@@ -1,3 +1,5 @@
+pub mod mcp;
+
 use langchain_rust::schemas::Message;
 use serde::{Deserialize, Serialize};
 use time::OffsetDateTime;
@@ -91,3 +93,43 @@ pub struct AiTool {
     pub description: String,
     pub parameters: serde_json::Value,
 }
+
+/// An intermediate event of a completion
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CompletionEvent {
+    /// The user message, which started the completion
+    Prompt { content: String },
+    /// The model invokes a tool
+    ToolCall { name: String, input: String },
+    /// The result of a tool invocation
+    ToolResult {
+        name: String,
+        output: String,
+        is_error: bool,
+    },
+    /// A chunk of the answer of the model
+    Token { delta: String },
+}
+
+impl CompletionEvent {
+    /// The name of the event, when sent as a server-sent event
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Prompt { .. } => "prompt",
+            Self::ToolCall { .. } => "tool_call",
+            Self::ToolResult { .. } => "tool_result",
+            Self::Token { .. } => "token",
+        }
+    }
+}
+
+/// An event of a conversation, as it is stored for replaying the conversation
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema, PartialEq)]
+pub struct ConversationEvent {
+    /// The sequence number of the conversation which produced this event
+    pub seq: i32,
+    #[serde(with = "time::serde::rfc3339")]
+    pub timestamp: OffsetDateTime,
+    pub event: CompletionEvent,
+}
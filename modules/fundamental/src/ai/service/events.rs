@@ -0,0 +1,235 @@
+use crate::ai::model::CompletionEvent;
+use async_trait::async_trait;
+use futures_util::StreamExt;
+use langchain_rust::{
+    language_models::{GenerateResult, LLMError, llm::LLM, options::CallOptions},
+    schemas::{Message, StreamData},
+    tools::Tool,
+};
+use serde_json::{Value, json};
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    pin::Pin,
+    sync::{Arc, Mutex},
+};
+use time::OffsetDateTime;
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Collects the events of a completion, and optionally forwards them to a listener.
+#[derive(Clone, Default)]
+pub struct EventSink {
+    sender: Option<UnboundedSender<CompletionEvent>>,
+    recorded: Arc<Mutex<Vec<(OffsetDateTime, CompletionEvent)>>>,
+}
+
+impl EventSink {
+    /// Create a new sink, forwarding events to the sender
+    pub fn new(sender: UnboundedSender<CompletionEvent>) -> Self {
+        Self {
+            sender: Some(sender),
+            recorded: Default::default(),
+        }
+    }
+
+    /// If there is someone listening for events
+    pub fn streaming(&self) -> bool {
+        self.sender.is_some()
+    }
+
+    pub fn emit(&self, event: CompletionEvent) {
+        if let Some(sender) = &self.sender {
+            // the receiver might be gone, that's ok, we still need to finish the completion
+            let _ = sender.send(event.clone());
+        }
+
+        let Ok(mut recorded) = self.recorded.lock() else {
+            return;
+        };
+
+        // we record tokens as one event, per chunk would be too much
+
+        match (recorded.last_mut(), event) {
+            (
+                Some((_, CompletionEvent::Token { delta })),
+                CompletionEvent::Token { delta: next },
+            ) => {
+                delta.push_str(&next);
+            }
+            (_, event) => recorded.push((OffsetDateTime::now_utc(), event)),
+        }
+    }
+
+    /// Take the events recorded so far
+    pub fn take(&self) -> Vec<(OffsetDateTime, CompletionEvent)> {
+        self.recorded
+            .lock()
+            .map(|mut recorded| std::mem::take(&mut *recorded))
+            .unwrap_or_default()
+    }
+
+    /// Wrap a tool, emitting events for invocations
+    pub fn tool(&self, tool: Arc<dyn Tool>) -> Arc<dyn Tool> {
+        Arc::new(EventTool {
+            tool,
+            sink: self.clone(),
+        })
+    }
+}
+
+/// A tool, emitting events when it gets called
+struct EventTool {
+    tool: Arc<dyn Tool>,
+    sink: EventSink,
+}
+
+#[async_trait]
+impl Tool for EventTool {
+    fn name(&self) -> String {
+        self.tool.name()
+    }
+
+    fn description(&self) -> String {
+        self.tool.description()
+    }
+
+    fn parameters(&self) -> Value {
+        self.tool.parameters()
+    }
+
+    async fn call(&self, input: &str) -> Result<String, Box<dyn Error>> {
+        let name = self.name();
+        self.sink.emit(CompletionEvent::ToolCall {
+            name: name.clone(),
+            input: input.to_string(),
+        });
+
+        let result = self.tool.call(input).await;
+
+        let (output, is_error) = match &result {
+            Ok(output) => (output.clone(), false),
+            Err(err) => (err.to_string(), true),
+        };
+        self.sink.emit(CompletionEvent::ToolResult {
+            name,
+            output,
+            is_error,
+        });
+
+        result
+    }
+
+    async fn run(&self, input: Value) -> Result<String, Box<dyn Error>> {
+        self.tool.run(input).await
+    }
+
+    async fn parse_input(&self, input: &str) -> Value {
+        self.tool.parse_input(input).await
+    }
+}
+
+/// A language model, streaming its response as token events when someone is listening.
+#[derive(Clone)]
+pub struct EventLLM<L: LLM + Clone> {
+    llm: L,
+    sink: EventSink,
+}
+
+impl<L: LLM + Clone> EventLLM<L> {
+    pub fn new(llm: L, sink: EventSink) -> Self {
+        Self { llm, sink }
+    }
+}
+
+/// A tool call, assembled from streamed chunks
+#[derive(Default)]
+struct ToolCall {
+    id: String,
+    name: String,
+    arguments: String,
+}
+
+#[async_trait]
+impl<L: LLM + Clone + 'static> LLM for EventLLM<L> {
+    async fn generate(&self, messages: &[Message]) -> Result<GenerateResult, LLMError> {
+        if !self.sink.streaming() {
+            return self.llm.generate(messages).await;
+        }
+
+        let mut stream = self.llm.stream(messages).await?;
+
+        let mut content = String::new();
+        let mut tool_calls = BTreeMap::<u64, ToolCall>::new();
+
+        while let Some(data) = stream.next().await {
+            let data = data?;
+
+            if !data.content.is_empty() {
+                content.push_str(&data.content);
+                self.sink.emit(CompletionEvent::Token {
+                    delta: data.content.clone(),
+                });
+            }
+
+            let chunks = data
+                .value
+                .pointer("/choices/0/delta/tool_calls")
+                .and_then(Value::as_array);
+            for chunk in chunks.into_iter().flatten() {
+                let call = tool_calls
+                    .entry(chunk["index"].as_u64().unwrap_or_default())
+                    .or_default();
+                if let Some(id) = chunk["id"].as_str() {
+                    call.id.push_str(id);
+                }
+                if let Some(name) = chunk["function"]["name"].as_str() {
+                    call.name.push_str(name);
+                }
+                if let Some(arguments) = chunk["function"]["arguments"].as_str() {
+                    call.arguments.push_str(arguments);
+                }
+            }
+        }
+
+        // tool calls are reported the same way the non-streaming response does
+
+        let generation = match tool_calls.is_empty() {
+            true => content,
+            false => serde_json::to_string(
+                &tool_calls
+                    .into_values()
+                    .map(|call| {
+                        json!({
+                            "id": call.id,
+                            "type": "function",
+                            "function": {
+                                "name": call.name,
+                                "arguments": call.arguments,
+                            }
+                        })
+                    })
+                    .collect::<Vec<_>>(),
+            )
+            .unwrap_or_default(),
+        };
+
+        Ok(GenerateResult {
+            generation,
+            ..Default::default()
+        })
+    }
+
+    async fn stream(
+        &self,
+        messages: &[Message],
+    ) -> Result<
+        Pin<Box<dyn futures_util::Stream<Item = Result<StreamData, LLMError>> + Send>>,
+        LLMError,
+    > {
+        self.llm.stream(messages).await
+    }
+
+    fn add_options(&mut self, options: CallOptions) {
+        self.llm.add_options(options);
+    }
+}
@@ -0,0 +1,245 @@
+use crate::ai::model::mcp::{
+    CallToolParams, CallToolResult, Content, Implementation, InitializeParams, InitializeResult,
+    JsonRpcError, JsonRpcRequest, JsonRpcResponse, ListToolsResult, McpTool, ServerCapabilities,
+    ToolsCapability,
+};
+use futures_util::future::join_all;
+use langchain_rust::tools::Tool;
+use serde_json::Value;
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+
+/// The protocol versions we support, the most recent one first
+pub const PROTOCOL_VERSIONS: &[&str] = &["2025-03-26", "2024-11-05"];
+
+/// A Model Context Protocol server, exposing the local AI tools.
+///
+/// The server itself is transport agnostic, it only handles JSON-RPC messages.
+pub struct McpServer {
+    tools: Vec<Arc<dyn Tool>>,
+}
+
+impl McpServer {
+    pub fn new(tools: Vec<Arc<dyn Tool>>) -> Self {
+        Self { tools }
+    }
+
+    /// Handle a JSON-RPC message, which can be a single request or a batch.
+    ///
+    /// Returns `None` if there is nothing to respond, which is the case for notifications.
+    pub async fn handle(&self, message: Value) -> Option<Value> {
+        match message {
+            Value::Array(batch) => {
+                let responses = join_all(batch.into_iter().map(|message| self.handle_one(message)))
+                    .await
+                    .into_iter()
+                    .flatten()
+                    .collect::<Vec<_>>();
+
+                match responses.is_empty() {
+                    true => None,
+                    false => serde_json::to_value(responses).ok(),
+                }
+            }
+            message => self
+                .handle_one(message)
+                .await
+                .and_then(|response| serde_json::to_value(response).ok()),
+        }
+    }
+
+    /// Handle the raw payload of a message, reporting parse errors to the client
+    pub async fn handle_str(&self, message: &str) -> Option<Value> {
+        match serde_json::from_str(message) {
+            Ok(message) => self.handle(message).await,
+            Err(err) => serde_json::to_value(JsonRpcResponse::error(
+                Value::Null,
+                JsonRpcError::parse_error(err.to_string()),
+            ))
+            .ok(),
+        }
+    }
+
+    async fn handle_one(&self, message: Value) -> Option<JsonRpcResponse> {
+        let request: JsonRpcRequest = match serde_json::from_value(message) {
+            Ok(request) => request,
+            Err(err) => {
+                return Some(JsonRpcResponse::error(
+                    Value::Null,
+                    JsonRpcError::invalid_request(err.to_string()),
+                ));
+            }
+        };
+
+        // notifications don't get a response
+        let id = request.id?;
+
+        Some(
+            match self.handle_request(&request.method, request.params).await {
+                Ok(result) => JsonRpcResponse::result(id, result),
+                Err(err) => JsonRpcResponse::error(id, err),
+            },
+        )
+    }
+
+    async fn handle_request(&self, method: &str, params: Value) -> Result<Value, JsonRpcError> {
+        match method {
+            "initialize" => to_value(self.initialize(params)),
+            "ping" => Ok(Value::Object(Default::default())),
+            "tools/list" => to_value(self.list_tools()),
+            "tools/call" => to_value(self.call_tool(params).await?),
+            method => Err(JsonRpcError::method_not_found(method)),
+        }
+    }
+
+    fn initialize(&self, params: Value) -> InitializeResult {
+        // use the client's version if we support it, otherwise propose our most recent one
+
+        let requested = serde_json::from_value::<InitializeParams>(params)
+            .ok()
+            .map(|params| params.protocol_version);
+        let protocol_version = requested
+            .filter(|version| PROTOCOL_VERSIONS.contains(&version.as_str()))
+            .unwrap_or_else(|| PROTOCOL_VERSIONS[0].to_string());
+
+        InitializeResult {
+            protocol_version,
+            capabilities: ServerCapabilities {
+                tools: ToolsCapability {
+                    list_changed: false,
+                },
+            },
+            server_info: Implementation {
+                name: "trustify".into(),
+                version: env!("CARGO_PKG_VERSION").into(),
+            },
+            instructions: None,
+        }
+    }
+
+    fn list_tools(&self) -> ListToolsResult {
+        ListToolsResult {
+            tools: self
+                .tools
+                .iter()
+                .map(|tool| McpTool {
+                    name: tool.name(),
+                    description: tool.description(),
+                    input_schema: tool.parameters(),
+                })
+                .collect(),
+        }
+    }
+
+    async fn call_tool(&self, params: Value) -> Result<CallToolResult, JsonRpcError> {
+        let params: CallToolParams = serde_json::from_value(params)
+            .map_err(|err| JsonRpcError::invalid_params(err.to_string()))?;
+
+        let tool = self
+            .tools
+            .iter()
+            .find(|tool| tool.name() == params.name)
+            .ok_or_else(|| {
+                JsonRpcError::invalid_params(format!("Unknown tool: {}", params.name))
+            })?;
+
+        let input = serde_json::to_string(&params.arguments)
+            .map_err(|err| JsonRpcError::invalid_params(err.to_string()))?;
+
+        // errors of the tool are reported as a result, so that the model can see them
+
+        let (text, is_error) = match tool.call(&input).await {
+            Ok(text) => (text, false),
+            Err(err) => (err.to_string(), true),
+        };
+
+        Ok(CallToolResult {
+            content: vec![Content::Text { text }],
+            is_error,
+        })
+    }
+
+    /// Serve the protocol using the stdio transport: newline delimited JSON-RPC messages.
+    ///
+    /// Returns when the input is closed.
+    pub async fn serve_stdio<R, W>(&self, input: R, mut output: W) -> std::io::Result<()>
+    where
+        R: AsyncRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        let mut lines = BufReader::new(input).lines();
+
+        while let Some(line) = lines.next_line().await? {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            if let Some(response) = self.handle_str(&line).await {
+                let mut response = serde_json::to_vec(&response)?;
+                response.push(b'\n');
+                output.write_all(&response).await?;
+                output.flush().await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn to_value(value: impl serde::Serialize) -> Result<Value, JsonRpcError> {
+    serde_json::to_value(value).map_err(|err| JsonRpcError {
+        code: -32603,
+        message: err.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+    use test_context::test_context;
+    use test_log::test;
+    use trustify_test_context::TrustifyContext;
+
+    #[test_context(TrustifyContext)]
+    #[test(tokio::test)]
+    async fn stdio(ctx: &TrustifyContext) -> anyhow::Result<()> {
+        let server = McpServer::new(crate::ai::service::tools::new(
+            ctx.db.clone(),
+            crate::ai::service::test::analysis(),
+        ));
+
+        let input = [
+            json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {"protocolVersion": "2024-11-05"}}),
+            json!({"jsonrpc": "2.0", "method": "notifications/initialized"}),
+            json!({"jsonrpc": "2.0", "id": 2, "method": "tools/list"}),
+            json!({"jsonrpc": "2.0", "id": 3, "method": "unknown"}),
+        ]
+        .iter()
+        .map(|message| format!("{message}\n"))
+        .collect::<String>();
+
+        let mut output = vec![];
+        server.serve_stdio(input.as_bytes(), &mut output).await?;
+
+        let responses = String::from_utf8(output)?
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<Result<Vec<Value>, _>>()?;
+
+        assert_eq!(responses.len(), 3);
+        assert_eq!(responses[0]["id"], json!(1));
+        assert_eq!(
+            responses[0]["result"]["protocolVersion"],
+            json!("2024-11-05")
+        );
+        assert_eq!(responses[1]["id"], json!(2));
+        assert_eq!(
+            responses[1]["result"]["tools"].as_array().map(Vec::len),
+            Some(8)
+        );
+        assert_eq!(responses[2]["error"]["code"], json!(-32601));
+
+        Ok(())
+    }
+}
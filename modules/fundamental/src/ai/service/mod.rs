@@ -1,8 +1,13 @@
+pub mod events;
+pub mod mcp;
 pub mod tools;
 
-use crate::ai::model::{ChatMessage, ChatState, InternalState, LLMInfo, MessageType};
+use crate::ai::model::{
+    ChatMessage, ChatState, CompletionEvent, ConversationEvent, InternalState, LLMInfo, MessageType,
+};
 
 use crate::Error;
+use crate::ai::service::events::{EventLLM, EventSink};
 use crate::ai::service::tools::remote::RemoteToolsProvider;
 use base64::engine::Engine as _;
 use base64::engine::general_purpose::STANDARD;
@@ -33,6 +38,7 @@ use trustify_common::db::Database;
 use trustify_common::db::query::{Filtering, Query};
 use trustify_common::model::{Paginated, PaginatedResults};
 use trustify_entity::conversation;
+use trustify_module_analysis::service::AnalysisService;
 
 pub const PREFIX: &str = include_str!("prefix.txt");
 
@@ -86,9 +92,7 @@ impl AiService {
     /// export OPENAI_MODEL=llama3.1:70b
     /// ```
     ///
-    pub fn new(db: Database) -> Self {
-        let local_tools = tools::new(db.clone());
-
+    pub fn new(db: Database, analysis: AnalysisService) -> Self {
         let api_key = env::var("OPENAI_API_KEY");
         let api_key = match api_key {
             Ok(api_key) => api_key,
@@ -97,7 +101,7 @@ impl AiService {
                     llm: None,
                     llm_info: None,
                     remote_tools_providers: Vec::new(),
-                    local_tools,
+                    local_tools: tools::new(db, analysis),
                     tools: OnceCell::new(),
                 };
             }
@@ -107,17 +111,7 @@ impl AiService {
             env::var("OPENAI_API_BASE").unwrap_or_else(|_| "https://api.openai.com/v1".to_string());
         let model = env::var("OPENAI_MODEL").unwrap_or_else(|_| "gpt-4o".to_string());
 
-        log::info!("LLM API: {}", api_base.clone());
-        log::info!("LLM Model: {}", model);
-
-        let llm_config = OpenAIConfig::default()
-            .with_api_base(api_base.clone())
-            .with_api_key(api_key);
-
-        let llm = OpenAI::default()
-            .with_config(llm_config.clone())
-            .with_model(model.clone())
-            .with_options(CallOptions::default().with_seed(2000));
+        let mut service = Self::with_llm(db, analysis, LLMInfo { api_base, model }, api_key);
 
         let mut remote_tools_providers = vec![];
 
@@ -133,11 +127,36 @@ impl AiService {
             });
         }
 
+        service.remote_tools_providers = remote_tools_providers;
+        service
+    }
+
+    /// Creates a new instance of the AI service, using the provided OpenAI compatible API endpoint.
+    ///
+    /// Unlike [`Self::new`], this doesn't evaluate any environment variables.
+    pub fn with_llm(
+        db: Database,
+        analysis: AnalysisService,
+        llm_info: LLMInfo,
+        api_key: String,
+    ) -> Self {
+        log::info!("LLM API: {}", llm_info.api_base);
+        log::info!("LLM Model: {}", llm_info.model);
+
+        let llm_config = OpenAIConfig::default()
+            .with_api_base(llm_info.api_base.clone())
+            .with_api_key(api_key);
+
+        let llm = OpenAI::default()
+            .with_config(llm_config)
+            .with_model(llm_info.model.clone())
+            .with_options(CallOptions::default().with_seed(2000));
+
         Self {
             llm: Some(llm),
-            llm_info: Some(LLMInfo { api_base, model }),
-            remote_tools_providers,
-            local_tools,
+            llm_info: Some(llm_info),
+            remote_tools_providers: Vec::new(),
+            local_tools: tools::new(db, analysis),
             tools: OnceCell::new(),
         }
     }
@@ -198,6 +217,16 @@ impl AiService {
         }
     }
     pub async fn completions(&self, request: &ChatState) -> Result<ChatState, Error> {
+        self.completions_with_events(request, &EventSink::default())
+            .await
+    }
+
+    /// Same as [`Self::completions`], reporting intermediate events to the sink
+    pub async fn completions_with_events(
+        &self,
+        request: &ChatState,
+        events: &EventSink,
+    ) -> Result<ChatState, Error> {
         // get the previous LLM message history
         let internal_state = match &request.internal_state {
             Some(internal_state) => match STANDARD.decode(internal_state) {
@@ -217,7 +246,7 @@ impl AiService {
         };
 
         let internal_state = self
-            .completions_decoded(&request.messages, &internal_state)
+            .completions_decoded(&request.messages, &internal_state, events)
             .await?;
 
         let messages = internal_state.chat_messages();
@@ -241,22 +270,30 @@ impl AiService {
         &self,
         request_messages: &Vec<ChatMessage>,
         internal_state: &InternalState,
+        events: &EventSink,
     ) -> Result<InternalState, Error> {
         let llm = match self.llm.clone() {
             Some(llm) => llm,
             None => return Err(Error::NotFound("AI service is not enabled".to_string())),
         };
 
+        let tools = self
+            .tools_ref()
+            .await
+            .iter()
+            .map(|tool| events.tool(tool.clone()))
+            .collect::<Vec<_>>();
+
         let agent = OpenAiToolAgentBuilder::new()
             .prefix(PREFIX)
-            .tools(self.tools_ref().await)
+            .tools(&tools)
             .options(
                 ChainCallOptions::new()
                     .with_max_tokens(1000)
                     .with_temperature(0.0)
                     .with_seed(1000),
             )
-            .build(llm)
+            .build(EventLLM::new(llm, events.clone()))
             .map_err(Error::AgentError)?;
 
         if internal_state.messages.len() != internal_state.timestamps.len() {
@@ -309,15 +346,24 @@ impl AiService {
         }
 
         // use the last user message as the prompt
+        events.emit(CompletionEvent::Prompt {
+            content: last_message.content.clone(),
+        });
+
         let memory: Arc<tokio::sync::Mutex<dyn BaseMemory>> = memory.into();
         let executor = AgentExecutor::from_agent(agent).with_memory(memory.clone());
-        _ = executor
+        let output = executor
             .invoke(prompt_args! {
                 "input" => last_message.content.clone(),
             })
             .await
             .map_err(Error::ChainError)?;
 
+        // when streaming, the answer was already reported token by token
+        if !events.streaming() {
+            events.emit(CompletionEvent::Token { delta: output });
+        }
+
         let memory = memory.lock().await;
         let history = memory.messages();
 
@@ -342,9 +388,32 @@ impl AiService {
         messages: &Vec<ChatMessage>,
         if_seq: Option<i32>,
         connection: &C,
+    ) -> Result<(conversation::Model, Vec<ChatMessage>), Error> {
+        self.upsert_conversation_with_events(
+            conversation_id,
+            user_id,
+            messages,
+            if_seq,
+            &EventSink::default(),
+            connection,
+        )
+        .await
+    }
+
+    /// Same as [`Self::upsert_conversation`], reporting intermediate events to the sink.
+    ///
+    /// The events are also stored with the conversation, so that it can be replayed later on.
+    pub async fn upsert_conversation_with_events<C: ConnectionTrait>(
+        &self,
+        conversation_id: Uuid,
+        user_id: String,
+        messages: &Vec<ChatMessage>,
+        if_seq: Option<i32>,
+        events: &EventSink,
+        connection: &C,
     ) -> Result<(conversation::Model, Vec<ChatMessage>), Error> {
         let found = self.fetch_conversation(conversation_id, connection).await?;
-        let (internal_state, current_seq) = match found {
+        let (internal_state, current_seq, mut conversation_events) = match found {
             Some((conversation, internal_state)) => {
                 // verify that the conversation belongs to the user
                 if conversation.user_id != user_id {
@@ -352,7 +421,12 @@ impl AiService {
                     // existence of the conversation
                     Err(Error::NotFound("conversation not found".to_string()))?;
                 }
-                (internal_state, if_seq.unwrap_or(conversation.seq))
+                let conversation_events = Self::conversation_events(&conversation)?;
+                (
+                    internal_state,
+                    if_seq.unwrap_or(conversation.seq),
+                    conversation_events,
+                )
             }
             None => {
                 // store the new conversation, LLM request will take a while,
@@ -367,13 +441,14 @@ impl AiService {
                     summary: Set("".to_string()),
                     seq: Set(seq),
                     updated_at: Set(OffsetDateTime::now_utc()),
+                    events: Set(serde_json::Value::Array(vec![])),
                 };
 
                 // TODO: check for duplicate conversation_id error, and retry as an update
                 // to deal with concurrent initial upsert requests.
                 log::info!("inserting conversation into db: {}", conversation_id);
                 model.insert(connection).await?;
-                (internal_state, seq)
+                (internal_state, seq, vec![])
             }
         };
 
@@ -384,7 +459,17 @@ impl AiService {
             current_seq,
             if_seq
         );
-        let internal_state = self.completions_decoded(messages, &internal_state).await?;
+        let internal_state = self
+            .completions_decoded(messages, &internal_state, events)
+            .await?;
+
+        conversation_events.extend(events.take().into_iter().map(|(timestamp, event)| {
+            ConversationEvent {
+                seq: current_seq + 1,
+                timestamp,
+                event,
+            }
+        }));
 
         let response = internal_state.chat_messages();
 
@@ -399,6 +484,8 @@ impl AiService {
             summary: Set(summary),
             seq: Set(current_seq + 1),
             updated_at: Set(OffsetDateTime::now_utc()),
+            events: Set(serde_json::to_value(&conversation_events)
+                .map_err(|e| Error::Internal(e.to_string()))?),
             ..Default::default()
         };
 
@@ -428,6 +515,14 @@ impl AiService {
         })
     }
 
+    /// Get the recorded events of a conversation
+    pub fn conversation_events(
+        conversation: &conversation::Model,
+    ) -> Result<Vec<ConversationEvent>, Error> {
+        serde_json::from_value(conversation.events.clone())
+            .map_err(|e| Error::Internal(e.to_string()))
+    }
+
     pub async fn fetch_conversations<C: ConnectionTrait + Sync + Send>(
         &self,
         user_id: String,
@@ -439,13 +534,14 @@ impl AiService {
             .order_by_desc(conversation::Column::UpdatedAt)
             .filtering(search)?
             .filter(conversation::Column::UserId.eq(user_id))
-            .limiting(connection, paginated.offset, paginated.limit);
+            .limiting(connection, &paginated);
 
         let total = limiter.total().await?;
 
         Ok(PaginatedResults {
             total,
             items: limiter.fetch().await?,
+            next: None,
         })
     }
 
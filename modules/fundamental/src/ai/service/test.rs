@@ -1,15 +1,23 @@
-use crate::ai::model::{ChatMessage, ChatState};
+use crate::ai::model::{ChatMessage, ChatState, CompletionEvent, LLMInfo};
 use crate::ai::service::AiService;
+use crate::ai::service::events::EventSink;
 
+use actix_web::{App, HttpResponse, HttpServer, dev::ServerHandle, web};
+use serde_json::{Value, json};
 use test_context::test_context;
 use test_log::test;
 use trustify_common::db::query::Query;
 use trustify_common::hashing::Digests;
 use trustify_common::model::Paginated;
+use trustify_module_analysis::{config::AnalysisConfig, service::AnalysisService};
 use trustify_module_ingestor::graph::product::ProductInformation;
 use trustify_test_context::TrustifyContext;
 use uuid::Uuid;
 
+pub fn analysis() -> AnalysisService {
+    AnalysisService::new(AnalysisConfig::default())
+}
+
 pub async fn ingest_fixtures(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
     let sbom = ctx
         .graph
@@ -67,7 +75,7 @@ pub fn sanitize_uuid_urn(value: String) -> String {
 #[test_context(TrustifyContext)]
 #[test(actix_web::test)]
 async fn test_completions_sbom_info(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
-    let service = AiService::new(ctx.db.clone());
+    let service = AiService::new(ctx.db.clone(), analysis());
     if !service.completions_enabled() {
         return Ok(()); // skip test
     }
@@ -100,7 +108,7 @@ async fn test_completions_sbom_info(ctx: &TrustifyContext) -> Result<(), anyhow:
 #[test_context(TrustifyContext)]
 #[test(actix_web::test)]
 async fn test_completions_package_info(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
-    let service = AiService::new(ctx.db.clone());
+    let service = AiService::new(ctx.db.clone(), analysis());
     if !service.completions_enabled() {
         return Ok(()); // skip test
     }
@@ -130,10 +138,57 @@ async fn test_completions_package_info(ctx: &TrustifyContext) -> Result<(), anyh
     Ok(())
 }
 
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn test_completions_affected_products(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let service = AiService::new(ctx.db.clone(), analysis());
+    if !service.completions_enabled() {
+        return Ok(()); // skip test
+    }
+
+    let results = ctx
+        .ingest_documents([
+            "csaf/cve-2023-0044.json",
+            "quarkus/v2/quarkus-bom-2.13.8.Final-redhat-00004.json",
+        ])
+        .await?;
+
+    ctx.graph
+        .ingest_product(
+            "Red Hat build of Quarkus",
+            ProductInformation {
+                vendor: Some("Red Hat".to_string()),
+                cpe: None,
+            },
+            &ctx.db,
+        )
+        .await?
+        .ingest_product_version("2.13.8".to_string(), results[1].id.try_as_uid(), &ctx.db)
+        .await?;
+
+    let mut req = ChatState::default();
+    req.messages.push(ChatMessage::human(
+        "Which of our products ship quarkus-vertx-http and are affected by CVE-2023-0044?".into(),
+    ));
+
+    let result = service.completions(&req).await?;
+
+    log::info!("result: {:#?}", result);
+    let last_message_content = result.messages.last().unwrap().content.clone();
+    println!(
+        "Test formatted output:\n\n{}\n",
+        termimad::inline(last_message_content.as_str())
+    );
+    assert!(last_message_content.contains("Red Hat build of Quarkus"));
+    assert!(last_message_content.contains("2.13.8"));
+
+    Ok(())
+}
+
 #[test_context(TrustifyContext)]
 #[test(actix_web::test)]
 async fn test_completions_cve_info(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
-    let service = AiService::new(ctx.db.clone());
+    let service = AiService::new(ctx.db.clone(), analysis());
     if !service.completions_enabled() {
         return Ok(()); // skip test
     }
@@ -163,7 +218,7 @@ async fn test_completions_cve_info(ctx: &TrustifyContext) -> Result<(), anyhow::
 #[test_context(TrustifyContext)]
 #[test(actix_web::test)]
 async fn test_completions_advisory_info(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
-    let service = AiService::new(ctx.db.clone());
+    let service = AiService::new(ctx.db.clone(), analysis());
     if !service.completions_enabled() {
         return Ok(()); // skip test
     }
@@ -195,7 +250,7 @@ async fn test_completions_advisory_info(ctx: &TrustifyContext) -> Result<(), any
 #[test_context(TrustifyContext)]
 #[test(actix_web::test)]
 async fn conversation_crud(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
-    let service = AiService::new(ctx.db.clone());
+    let service = AiService::new(ctx.db.clone(), analysis());
     if !service.completions_enabled() {
         return Ok(()); // skip test
     }
@@ -235,12 +290,13 @@ async fn conversation_crud(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
             Paginated {
                 offset: 0,
                 limit: 10,
+                ..Default::default()
             },
             &ctx.db,
         )
         .await?;
 
-    assert_eq!(1, converstations.total);
+    assert_eq!(Some(1), converstations.total);
     assert_eq!(1, converstations.items.len());
     assert_eq!(conversation, converstations.items[0]);
 
@@ -290,3 +346,235 @@ async fn conversation_crud(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
 
     Ok(())
 }
+
+/// Start a mock of an OpenAI compatible API.
+///
+/// For every prompt, the model first asks for calling the `sbom-info` tool, and answers once it
+/// got the result. It supports both the regular and the streaming mode.
+pub async fn mock_llm(ctx: &TrustifyContext) -> anyhow::Result<(AiService, ServerHandle)> {
+    let server = HttpServer::new(|| {
+        App::new().route("/chat/completions", web::post().to(mock_chat_completions))
+    })
+    .workers(1)
+    .bind(("127.0.0.1", 0))?;
+
+    let addr = server.addrs()[0];
+    let server = server.run();
+    let handle = server.handle();
+    actix_web::rt::spawn(server);
+
+    let service = AiService::with_llm(
+        ctx.db.clone(),
+        analysis(),
+        LLMInfo {
+            api_base: format!("http://{addr}"),
+            model: "mock".into(),
+        },
+        "mock".into(),
+    );
+
+    Ok((service, handle))
+}
+
+pub const MOCK_ANSWER: &str = "The SBOM is quarkus-bom.";
+
+async fn mock_chat_completions(request: web::Json<Value>) -> HttpResponse {
+    // answer if we just got the result of the tool call
+    let answer = request["messages"]
+        .as_array()
+        .and_then(|messages| messages.last())
+        .is_some_and(|message| message["role"] == "tool");
+
+    let (message, deltas, finish_reason) = match answer {
+        true => (
+            json!({ "role": "assistant", "content": MOCK_ANSWER }),
+            vec![
+                json!({ "role": "assistant", "content": "" }),
+                json!({ "content": "The SBOM " }),
+                json!({ "content": "is quarkus-bom." }),
+            ],
+            "stop",
+        ),
+        false => (
+            json!({
+                "role": "assistant",
+                "content": null,
+                "tool_calls": [{
+                    "id": "call_1",
+                    "type": "function",
+                    "function": { "name": "sbom-info", "arguments": r#"{"input":"quarkus"}"# }
+                }]
+            }),
+            vec![
+                json!({ "role": "assistant", "tool_calls": [{
+                    "index": 0,
+                    "id": "call_1",
+                    "type": "function",
+                    "function": { "name": "sbom-info", "arguments": "" }
+                }]}),
+                json!({ "tool_calls": [{ "index": 0, "function": { "arguments": r#"{"input":"# } }]}),
+                json!({ "tool_calls": [{ "index": 0, "function": { "arguments": r#""quarkus"}"# } }]}),
+            ],
+            "tool_calls",
+        ),
+    };
+
+    if !request["stream"].as_bool().unwrap_or_default() {
+        return HttpResponse::Ok().json(json!({
+            "id": "mock",
+            "object": "chat.completion",
+            "created": 0,
+            "model": "mock",
+            "choices": [{
+                "index": 0,
+                "message": message,
+                "finish_reason": finish_reason,
+                "logprobs": null
+            }],
+            "usage": { "prompt_tokens": 1, "completion_tokens": 1, "total_tokens": 2 }
+        }));
+    }
+
+    let chunk = |delta: Value, finish_reason: Option<&str>| {
+        let chunk = json!({
+            "id": "mock",
+            "object": "chat.completion.chunk",
+            "created": 0,
+            "model": "mock",
+            "choices": [{
+                "index": 0,
+                "delta": delta,
+                "finish_reason": finish_reason,
+                "logprobs": null
+            }]
+        });
+        format!("data: {chunk}\n\n")
+    };
+
+    let mut body = deltas
+        .into_iter()
+        .map(|delta| chunk(delta, None))
+        .collect::<String>();
+    body.push_str(&chunk(json!({}), Some(finish_reason)));
+    body.push_str("data: [DONE]\n\n");
+
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .body(body)
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn completions_stream_events(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    ctx.ingest_document("quarkus/v1/quarkus-bom-2.13.8.Final-redhat-00004.json")
+        .await?;
+
+    let (service, server) = mock_llm(ctx).await?;
+
+    let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+    let events = EventSink::new(sender);
+
+    let mut req = ChatState::default();
+    req.messages
+        .push(ChatMessage::human("Tell me about quarkus".into()));
+
+    let result = service.completions_with_events(&req, &events).await?;
+    drop(events);
+
+    assert_eq!(
+        result
+            .messages
+            .last()
+            .map(|message| message.content.as_str()),
+        Some(MOCK_ANSWER)
+    );
+
+    let mut streamed = vec![];
+    while let Some(event) = receiver.recv().await {
+        streamed.push(event);
+    }
+
+    assert_eq!(
+        streamed
+            .iter()
+            .map(|event| event.name())
+            .collect::<Vec<_>>(),
+        vec!["prompt", "tool_call", "tool_result", "token", "token"]
+    );
+    assert!(matches!(
+        &streamed[1],
+        CompletionEvent::ToolCall { name, input } if name == "sbom-info" && input.contains("quarkus")
+    ));
+    assert!(matches!(
+        &streamed[2],
+        CompletionEvent::ToolResult { output, is_error: false, .. } if output.contains("quarkus-bom")
+    ));
+
+    server.stop(true).await;
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn conversation_replay(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    ctx.ingest_document("quarkus/v1/quarkus-bom-2.13.8.Final-redhat-00004.json")
+        .await?;
+
+    let (service, server) = mock_llm(ctx).await?;
+
+    let conversation_id = Uuid::now_v7();
+    let messages = vec![ChatMessage::human("Tell me about quarkus".into())];
+
+    let (conversation, _) = service
+        .upsert_conversation(conversation_id, "user_a".into(), &messages, None, &ctx.db)
+        .await?;
+
+    // tokens are recorded as a single event
+
+    let events = AiService::conversation_events(&conversation)?;
+    assert_eq!(
+        events
+            .iter()
+            .map(|event| (event.seq, event.event.name()))
+            .collect::<Vec<_>>(),
+        vec![
+            (1, "prompt"),
+            (1, "tool_call"),
+            (1, "tool_result"),
+            (1, "token"),
+        ]
+    );
+    assert_eq!(
+        events[3].event,
+        CompletionEvent::Token {
+            delta: MOCK_ANSWER.into()
+        }
+    );
+
+    // events of further completions get appended
+
+    let (conversation, _) = service
+        .upsert_conversation(
+            conversation_id,
+            "user_a".into(),
+            &[messages, vec![ChatMessage::human("And again".into())]].concat(),
+            None,
+            &ctx.db,
+        )
+        .await?;
+
+    let events = AiService::conversation_events(&conversation)?;
+    assert_eq!(events.len(), 8);
+    assert_eq!(events[4].seq, 2);
+    assert_eq!(
+        events[4].event,
+        CompletionEvent::Prompt {
+            content: "And again".into()
+        }
+    );
+
+    server.stop(true).await;
+
+    Ok(())
+}
@@ -0,0 +1,191 @@
+use crate::ai::service::tools::{self, input_description};
+use async_trait::async_trait;
+use langchain_rust::tools::Tool;
+use serde::Serialize;
+use serde_json::Value;
+use std::{error::Error, fmt::Debug, str::FromStr};
+use trustify_common::{
+    cpe::Cpe, db::Database, db::query::Query, model::PaginatedResults, purl::Purl,
+};
+use trustify_entity::relationship::Relationship;
+use trustify_module_analysis::{
+    model::Node,
+    service::{AnalysisService, ComponentReference, GraphQuery, QueryOptions},
+};
+
+pub struct ComponentGraph {
+    pub db: Database,
+    pub service: AnalysisService,
+}
+
+impl ComponentGraph {
+    pub fn new(db: Database, service: AnalysisService) -> Self {
+        Self { db, service }
+    }
+}
+
+#[async_trait]
+impl Tool for ComponentGraph {
+    fn name(&self) -> String {
+        String::from("component-graph")
+    }
+
+    fn description(&self) -> String {
+        String::from(
+            r##"
+This tool can be used to find out where a component (package) is used, and what it contains.
+A component can be identified by a Package URL (purl), a CPE, or by its name. Examples:
+
+pkg:maven/org.apache.logging.log4j/log4j-core@2.14.1
+cpe:/a:redhat:quarkus:3.2
+log4j-core
+
+For every SBOM containing the component, the tool returns the chain of ancestors up to
+the top level component of the SBOM, as well as the direct dependencies of the component.
+If the SBOM is part of a product, the product name and version are returned as well.
+"##
+            .trim(),
+        )
+    }
+
+    fn parameters(&self) -> Value {
+        input_description(
+            r#"
+A Package URL, a CPE, or the name of the component to search for.
+"#,
+        )
+    }
+
+    async fn run(&self, input: Value) -> Result<String, Box<dyn Error>> {
+        let input = input
+            .as_str()
+            .ok_or("Input should be a string")?
+            .trim()
+            .to_string();
+
+        let options = QueryOptions {
+            ancestors: u64::MAX,
+            descendants: 1,
+            ..Default::default()
+        };
+
+        let results = if input.starts_with("pkg:") {
+            let purl = Purl::from_str(&input)?;
+            self.retrieve(ComponentReference::Purl(&purl), options)
+                .await?
+        } else if input.starts_with("cpe:") {
+            let cpe = Cpe::from_str(&input)?;
+            self.retrieve(ComponentReference::Cpe(&cpe), options)
+                .await?
+        } else {
+            let query = Query::q(&input);
+            self.retrieve(&query, options).await?
+        };
+
+        if results.items.is_empty() {
+            return Ok(format!("Component '{input}' not found"));
+        }
+
+        tools::paginated_to_json(results, Component::root)
+    }
+}
+
+impl ComponentGraph {
+    async fn retrieve(
+        &self,
+        query: impl Into<GraphQuery<'_>> + Debug,
+        options: QueryOptions,
+    ) -> Result<PaginatedResults<Node>, Box<dyn Error>> {
+        Ok(self
+            .service
+            .retrieve(query, options, Default::default(), &self.db)
+            .await?)
+    }
+}
+
+/// A simplified version of the analysis node, reducing the amount of tokens sent to the LLM
+#[derive(Serialize)]
+struct Component {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sbom_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    product_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    product_version: Option<String>,
+    name: String,
+    version: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    purls: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    cpes: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    relationship: Option<Relationship>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    ancestors: Vec<Component>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    descendants: Vec<Component>,
+}
+
+impl Component {
+    fn root(node: &Node) -> Self {
+        let mut result = Self::nested(node);
+        result.sbom_id = Some(node.sbom_id.clone());
+        result.product_name = Some(node.product_name.clone()).filter(|v| !v.is_empty());
+        result.product_version = Some(node.product_version.clone()).filter(|v| !v.is_empty());
+        result
+    }
+
+    fn nested(node: &Node) -> Self {
+        Self {
+            sbom_id: None,
+            product_name: None,
+            product_version: None,
+            name: node.name.clone(),
+            version: node.version.clone(),
+            purls: node.purl.iter().map(ToString::to_string).collect(),
+            cpes: node.cpe.iter().map(ToString::to_string).collect(),
+            relationship: node.relationship,
+            ancestors: node.ancestors.iter().flatten().map(Self::nested).collect(),
+            descendants: node
+                .descendants
+                .iter()
+                .flatten()
+                .map(Self::nested)
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::service::test::analysis;
+    use crate::ai::service::tools::tests::assert_tool_contains;
+    use std::rc::Rc;
+    use test_context::test_context;
+    use test_log::test;
+    use trustify_test_context::TrustifyContext;
+
+    #[test_context(TrustifyContext)]
+    #[test(actix_web::test)]
+    async fn component_graph_tool(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+        ctx.ingest_document("spdx/quarkus-bom-3.2.11.Final-redhat-00001.json")
+            .await?;
+
+        let tool = Rc::new(ComponentGraph::new(ctx.db.clone(), analysis()));
+
+        assert_tool_contains(
+            tool.clone(),
+            "spymemcached",
+            r#""name": "quarkus-bom-3.2.11.Final-redhat-00001""#,
+        )
+        .await?;
+
+        assert_tool_contains(
+            tool,
+            "does-not-exist",
+            "Component 'does-not-exist' not found",
+        )
+        .await
+    }
+}
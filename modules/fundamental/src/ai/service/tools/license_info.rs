@@ -0,0 +1,187 @@
+use crate::{
+    ai::service::tools::{self, input_description},
+    license::service::LicenseService,
+    product::service::ProductService,
+};
+use async_trait::async_trait;
+use langchain_rust::tools::Tool;
+use serde::Serialize;
+use serde_json::Value;
+use std::{error::Error, str::FromStr};
+use trustify_common::{
+    db::{Database, query::Query},
+    id::Id,
+};
+use uuid::Uuid;
+
+pub struct LicenseInfo {
+    pub db: Database,
+    pub service: LicenseService,
+}
+
+impl LicenseInfo {
+    pub fn new(db: Database) -> Self {
+        let service = LicenseService::new(db.clone());
+        Self { db, service }
+    }
+}
+
+#[async_trait]
+impl Tool for LicenseInfo {
+    fn name(&self) -> String {
+        String::from("license-info")
+    }
+
+    fn description(&self) -> String {
+        String::from(
+            r##"
+This tool can be used to get the licenses used by the packages of an SBOM, or of all versions of a product.
+SBOMs are identified by UUID URIs. Example: urn:uuid:2fd0d1b7-a908-4d63-9310-d57a7f77c6df
+Products are identified by their name. Example: Quay
+
+For each license, the tool returns the number of packages declaring that license.
+"##
+            .trim(),
+        )
+    }
+
+    fn parameters(&self) -> Value {
+        input_description(
+            r#"
+An SBOM UUID or the name of a product.
+"#,
+        )
+    }
+
+    async fn run(&self, input: Value) -> Result<String, Box<dyn Error>> {
+        let input = input
+            .as_str()
+            .ok_or("Input should be a string")?
+            .trim()
+            .to_string();
+
+        let sbom_id = match Id::from_str(&input) {
+            Ok(Id::Uuid(id)) => Some(id),
+            _ => Uuid::from_str(&input).ok(),
+        };
+
+        let sboms = match sbom_id {
+            Some(sbom_id) => vec![(None, None, sbom_id)],
+            None => {
+                let products = ProductService::new()
+                    .fetch_products(
+                        Query {
+                            q: input.clone(),
+                            ..Default::default()
+                        },
+                        Default::default(),
+                        &self.db,
+                    )
+                    .await?;
+
+                products
+                    .items
+                    .into_iter()
+                    .flat_map(|product| {
+                        let name = product.head.name;
+                        product.versions.into_iter().filter_map(move |version| {
+                            let sbom_id = version.sbom_id?;
+                            Some((Some(name.clone()), Some(version.version), sbom_id))
+                        })
+                    })
+                    .collect()
+            }
+        };
+
+        #[derive(Serialize)]
+        struct Sbom {
+            uuid: Uuid,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            product: Option<String>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            version: Option<String>,
+            licenses: Vec<License>,
+        }
+
+        #[derive(Serialize)]
+        struct License {
+            license: String,
+            packages: u64,
+        }
+
+        let mut result = vec![];
+        for (product, version, sbom_id) in sboms {
+            let licenses = self.service.fetch_sbom_licenses(sbom_id).await?;
+            result.push(Sbom {
+                uuid: sbom_id,
+                product,
+                version,
+                licenses: licenses
+                    .into_iter()
+                    .map(|license| License {
+                        license: license.license,
+                        packages: license.purls,
+                    })
+                    .collect(),
+            });
+        }
+
+        if result.iter().all(|sbom| sbom.licenses.is_empty()) {
+            return Ok(format!("No licenses found for '{input}'"));
+        }
+
+        tools::to_json(&result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::service::tools::tests::assert_tool_contains;
+    use std::rc::Rc;
+    use test_context::test_context;
+    use test_log::test;
+    use trustify_module_ingestor::graph::product::ProductInformation;
+    use trustify_test_context::TrustifyContext;
+
+    #[test_context(TrustifyContext)]
+    #[test(actix_web::test)]
+    async fn license_info_tool(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+        let result = ctx.ingest_document("ubi9-9.2-755.1697625012.json").await?;
+        let sbom_id = result.id.try_as_uid().expect("must be a UUID");
+
+        ctx.graph
+            .ingest_product(
+                "Universal Base Image",
+                ProductInformation {
+                    vendor: Some("Red Hat".to_string()),
+                    cpe: None,
+                },
+                &ctx.db,
+            )
+            .await?
+            .ingest_product_version("9.2".to_string(), Some(sbom_id), &ctx.db)
+            .await?;
+
+        let tool = Rc::new(LicenseInfo::new(ctx.db.clone()));
+
+        assert_tool_contains(
+            tool.clone(),
+            &sbom_id.urn().to_string(),
+            r#""license": "LGPLV2+","#,
+        )
+        .await?;
+
+        assert_tool_contains(
+            tool.clone(),
+            "Universal Base Image",
+            r#"
+    "product": "Universal Base Image",
+    "version": "9.2",
+"#,
+        )
+        .await?;
+
+        assert_tool_contains(tool, "Quay", "No licenses found for 'Quay'").await
+    }
+}
@@ -1,28 +1,36 @@
 use crate::ai::service::tools::{
-    advisory_info::AdvisoryInfo, cve_info::CVEInfo, logger::ToolLogger, package_info::PackageInfo,
-    sbom_info::SbomInfo,
+    advisory_info::AdvisoryInfo, component_graph::ComponentGraph, cve_info::CVEInfo,
+    license_info::LicenseInfo, logger::ToolLogger, package_info::PackageInfo,
+    product_info::ProductInfo, sbom_info::SbomInfo, vulnerable_sboms::VulnerableSboms,
 };
 use langchain_rust::tools::Tool;
 use serde::Serialize;
 use serde_json::{Value, json};
 use std::{error::Error, sync::Arc};
 use trustify_common::{db::Database, model::PaginatedResults};
+use trustify_module_analysis::service::AnalysisService;
 
 pub mod advisory_info;
+pub mod component_graph;
 pub mod cve_info;
+pub mod license_info;
 pub mod logger;
 pub mod package_info;
 pub mod product_info;
 pub mod remote;
 pub mod sbom_info;
+pub mod vulnerable_sboms;
 
-pub fn new(db: Database) -> Vec<Arc<dyn Tool>> {
+pub fn new(db: Database, analysis: AnalysisService) -> Vec<Arc<dyn Tool>> {
     vec![
-        // Arc::new(ToolLogger(ProductInfo(ProductService::new(db.clone())))),
         Arc::new(ToolLogger(CVEInfo::new(db.clone()))),
         Arc::new(ToolLogger(AdvisoryInfo::new(db.clone()))),
         Arc::new(ToolLogger(PackageInfo::new(db.clone()))),
         Arc::new(ToolLogger(SbomInfo::new(db.clone()))),
+        Arc::new(ToolLogger(ProductInfo::new(db.clone()))),
+        Arc::new(ToolLogger(ComponentGraph::new(db.clone(), analysis))),
+        Arc::new(ToolLogger(LicenseInfo::new(db.clone()))),
+        Arc::new(ToolLogger(VulnerableSboms::new(db.clone()))),
     ]
 }
 
@@ -51,6 +59,7 @@ where
     to_json(&PaginatedResults {
         items: p.items.iter().map(f).collect(),
         total: p.total,
+        next: None,
     })
 }
 
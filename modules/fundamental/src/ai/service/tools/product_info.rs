@@ -33,7 +33,7 @@ impl Tool for ProductInfo {
             r##"
 This tool can be used to get information about a product.
 
-Products have multiple versions.  Each version is defined by a SBOM.
+Products have multiple versions.  Each version is defined by a SBOM, the UUID of that SBOM is returned as well.
 Products have a UUID that uniquely identifies the product.  Example: 2fd0d1b7-a908-4d63-9310-d57a7f77c6df
 Products are names of Software Products.  Examples:
 * Red Hat Enterprise Linux
@@ -76,13 +76,27 @@ Products are names of Software Products.  Examples:
             name: String,
             uuid: Uuid,
             vendor: Option<String>,
-            versions: Vec<String>,
+            versions: Vec<Version>,
+        }
+
+        #[derive(Serialize)]
+        struct Version {
+            version: String,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            sbom_uuid: Option<Uuid>,
         }
         tools::paginated_to_json(results, |item| Product {
             name: item.head.name.clone(),
             uuid: item.head.id,
             vendor: item.vendor.clone().map(|v| v.head.name),
-            versions: item.versions.iter().map(|v| v.version.clone()).collect(),
+            versions: item
+                .versions
+                .iter()
+                .map(|v| Version {
+                    version: v.version.clone(),
+                    sbom_uuid: v.sbom_id,
+                })
+                .collect(),
         })
     }
 }
@@ -113,13 +127,9 @@ mod tests {
       "uuid": "xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx",
       "vendor": "Red Hat",
       "versions": [
-        "37.17.9"
-      ]
-    }
-  ],
-  "total": 1
-}
-"#,
+        {
+          "version": "37.17.9",
+          "sbom_uuid": ""#,
         )
         .await
     }
@@ -0,0 +1,174 @@
+use crate::{
+    ai::service::tools::{self, input_description},
+    vulnerability::service::VulnerabilityService,
+};
+use async_trait::async_trait;
+use langchain_rust::tools::Tool;
+use serde::Serialize;
+use serde_json::Value;
+use std::error::Error;
+use time::OffsetDateTime;
+use trustify_common::db::Database;
+use uuid::Uuid;
+
+pub struct VulnerableSboms {
+    pub db: Database,
+    pub service: VulnerabilityService,
+}
+
+impl VulnerableSboms {
+    pub fn new(db: Database) -> Self {
+        Self {
+            db,
+            service: VulnerabilityService::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for VulnerableSboms {
+    fn name(&self) -> String {
+        String::from("vulnerable-sboms")
+    }
+
+    fn description(&self) -> String {
+        String::from(
+            r##"
+This tool can be used to find all SBOMs, and the products shipping them, which contain packages affected by a Vulnerability.
+Vulnerabilities are identified by their CVE Identifier.
+
+For each SBOM, the tool returns the affected packages, the advisories stating that the package is affected,
+and the product versions the SBOM belongs to.
+"##
+            .trim(),
+        )
+    }
+
+    fn parameters(&self) -> Value {
+        input_description(
+            r#"
+The CVE identifier of the Vulnerability. Example:
+* CVE-2021-44228
+"#,
+        )
+    }
+
+    async fn run(&self, input: Value) -> Result<String, Box<dyn Error>> {
+        let input = input
+            .as_str()
+            .ok_or("Input should be a string")?
+            .trim()
+            .to_string();
+
+        let sboms = self.service.fetch_affected_sboms(&input, &self.db).await?;
+
+        if sboms.is_empty() {
+            return Ok(format!("No SBOMs found affected by '{input}'"));
+        }
+
+        #[derive(Serialize)]
+        struct Sbom {
+            uuid: Uuid,
+            name: String,
+            #[serde(with = "time::serde::rfc3339::option")]
+            published: Option<OffsetDateTime>,
+            products: Vec<String>,
+            packages: Vec<Package>,
+        }
+
+        #[derive(Serialize)]
+        struct Package {
+            name: String,
+            purl: String,
+            advisory: String,
+        }
+
+        tools::to_json(
+            &sboms
+                .into_iter()
+                .map(|sbom| Sbom {
+                    uuid: sbom.sbom_id,
+                    name: sbom.name,
+                    published: sbom.published,
+                    products: sbom
+                        .products
+                        .into_iter()
+                        .map(|product| format!("{} {}", product.name, product.version))
+                        .collect(),
+                    packages: sbom
+                        .packages
+                        .into_iter()
+                        .map(|package| Package {
+                            name: package.name,
+                            purl: package.purl,
+                            advisory: package.advisory,
+                        })
+                        .collect(),
+                })
+                .collect::<Vec<_>>(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::service::tools::tests::assert_tool_contains;
+    use std::rc::Rc;
+    use test_context::test_context;
+    use test_log::test;
+    use trustify_module_ingestor::graph::product::ProductInformation;
+    use trustify_test_context::TrustifyContext;
+
+    #[test_context(TrustifyContext)]
+    #[test(actix_web::test)]
+    async fn vulnerable_sboms_tool(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+        let results = ctx
+            .ingest_documents([
+                "csaf/cve-2023-0044.json",
+                "quarkus/v2/quarkus-bom-2.13.8.Final-redhat-00004.json",
+            ])
+            .await?;
+        let sbom_id = results[1].id.try_as_uid().expect("must be a UUID");
+
+        ctx.graph
+            .ingest_product(
+                "Quarkus",
+                ProductInformation {
+                    vendor: Some("Red Hat".to_string()),
+                    cpe: None,
+                },
+                &ctx.db,
+            )
+            .await?
+            .ingest_product_version("2.13".to_string(), Some(sbom_id), &ctx.db)
+            .await?;
+
+        let tool = Rc::new(VulnerableSboms::new(ctx.db.clone()));
+
+        assert_tool_contains(
+            tool.clone(),
+            "CVE-2023-0044",
+            r#"
+    "products": [
+      "Quarkus 2.13"
+    ],
+"#,
+        )
+        .await?;
+
+        assert_tool_contains(
+            tool.clone(),
+            "CVE-2023-0044",
+            r#""purl": "pkg:maven/io.quarkus/quarkus-vertx-http@2.13.8.Final-redhat-00004","#,
+        )
+        .await?;
+
+        assert_tool_contains(
+            tool,
+            "CVE-0000-0000",
+            "No SBOMs found affected by 'CVE-0000-0000'",
+        )
+        .await
+    }
+}
@@ -0,0 +1,104 @@
+#[cfg(test)]
+mod test;
+
+use crate::cpe_mapping::{
+    model::{CpeMapping, NewCpeMapping},
+    service::CpeMappingService,
+};
+use actix_web::{HttpResponse, Responder, delete, get, post, web};
+use trustify_auth::{CreateMetadata, DeleteMetadata, ReadMetadata, authorizer::Require};
+use trustify_common::{
+    db::{Database, query::Query},
+    model::{Paginated, PaginatedResults},
+};
+use trustify_module_audit::recorder::{Action, Audit, Event, TargetKind};
+use uuid::Uuid;
+
+pub fn configure(config: &mut utoipa_actix_web::service_config::ServiceConfig, db: Database) {
+    let service = CpeMappingService::new();
+    config
+        .app_data(web::Data::new(db))
+        .app_data(web::Data::new(service))
+        .service(all)
+        .service(create)
+        .service(delete);
+}
+
+#[utoipa::path(
+    tag = "cpe-mapping",
+    operation_id = "listCpeMappings",
+    params(
+        Query,
+        Paginated,
+    ),
+    responses(
+        (status = 200, description = "Matching CPE mappings", body = PaginatedResults<CpeMapping>),
+    ),
+)]
+#[get("/v2/cpe-mapping")]
+/// List CPE to PURL mappings
+pub async fn all(
+    state: web::Data<CpeMappingService>,
+    db: web::Data<Database>,
+    web::Query(search): web::Query<Query>,
+    web::Query(paginated): web::Query<Paginated>,
+    _: Require<ReadMetadata>,
+) -> actix_web::Result<impl Responder> {
+    Ok(HttpResponse::Ok().json(state.fetch_mappings(search, paginated, db.as_ref()).await?))
+}
+
+#[utoipa::path(
+    tag = "cpe-mapping",
+    operation_id = "createCpeMapping",
+    request_body = NewCpeMapping,
+    responses(
+        (status = 201, description = "Created the CPE mapping", body = CpeMapping),
+        (status = 400, description = "The CPE or PURL could not be parsed"),
+    ),
+)]
+#[post("/v2/cpe-mapping")]
+/// Create a curated CPE to PURL mapping
+pub async fn create(
+    state: web::Data<CpeMappingService>,
+    db: web::Data<Database>,
+    web::Json(mapping): web::Json<NewCpeMapping>,
+    audit: Audit,
+    _: Require<CreateMetadata>,
+) -> actix_web::Result<impl Responder> {
+    let created = state.create_mapping(mapping, db.as_ref()).await?;
+    audit
+        .record(Event::new(Action::Create, TargetKind::CpeMapping, created.id).after(&created))
+        .await;
+    Ok(HttpResponse::Created().json(created))
+}
+
+#[utoipa::path(
+    tag = "cpe-mapping",
+    operation_id = "deleteCpeMapping",
+    params(
+        ("id", Path, description = "Opaque ID of the CPE mapping")
+    ),
+    responses(
+        (status = 204, description = "Deleted the CPE mapping"),
+        (status = 404, description = "The CPE mapping could not be found"),
+    ),
+)]
+#[delete("/v2/cpe-mapping/{id}")]
+/// Delete a CPE to PURL mapping
+pub async fn delete(
+    state: web::Data<CpeMappingService>,
+    db: web::Data<Database>,
+    id: web::Path<Uuid>,
+    audit: Audit,
+    _: Require<DeleteMetadata>,
+) -> actix_web::Result<impl Responder> {
+    Ok(match state.delete_mapping(*id, db.as_ref()).await? {
+        true => {
+            audit
+                .record(Event::new(Action::Delete, TargetKind::CpeMapping, *id))
+                .await;
+            HttpResponse::NoContent().finish()
+        }
+        false => HttpResponse::NotFound().finish(),
+    })
+}
@@ -0,0 +1,88 @@
+use crate::test::caller;
+use actix_http::StatusCode;
+use actix_web::test::TestRequest;
+use serde_json::{Value, json};
+use test_context::test_context;
+use test_log::test;
+use trustify_test_context::{TrustifyContext, call::CallService};
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn create_list_delete(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let app = caller(ctx).await?;
+
+    let request = TestRequest::post()
+        .uri("/api/v2/cpe-mapping")
+        .set_json(json!({
+            "cpe": "cpe:/a:apache:log4j",
+            "purl": "pkg:maven/org.apache.logging.log4j/log4j-core@2.14.1",
+        }))
+        .to_request();
+
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let created: Value = actix_web::test::read_body_json(response).await;
+
+    assert_eq!(created["vendor"], json!("apache"));
+    assert_eq!(created["product"], json!("log4j"));
+    assert_eq!(
+        created["purl"],
+        json!("pkg:maven/org.apache.logging.log4j/log4j-core")
+    );
+    assert_eq!(created["source"], json!("curated"));
+
+    let request = TestRequest::get()
+        .uri("/api/v2/cpe-mapping?q=cpe_vendor%3Dapache")
+        .to_request();
+    let response: Value = app.call_and_read_body_json(request).await;
+    assert_eq!(response["total"], json!(1));
+    assert_eq!(response["items"][0]["id"], created["id"]);
+
+    let id = created["id"].as_str().unwrap_or_default();
+
+    let request = TestRequest::delete()
+        .uri(&format!("/api/v2/cpe-mapping/{id}"))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let request = TestRequest::delete()
+        .uri(&format!("/api/v2/cpe-mapping/{id}"))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn reject_invalid(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let app = caller(ctx).await?;
+
+    // a CPE without a product can't be correlated
+
+    let request = TestRequest::post()
+        .uri("/api/v2/cpe-mapping")
+        .set_json(json!({
+            "cpe": "cpe:/a:apache",
+            "purl": "pkg:maven/org.apache.logging.log4j/log4j-core",
+        }))
+        .to_request();
+
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let request = TestRequest::post()
+        .uri("/api/v2/cpe-mapping")
+        .set_json(json!({
+            "cpe": "cpe:/a:apache:log4j",
+            "purl": "log4j-core",
+        }))
+        .to_request();
+
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
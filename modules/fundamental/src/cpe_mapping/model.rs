@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use trustify_common::purl::Purl;
+use trustify_entity::cpe_purl_mapping::{self, MappingSource};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// A correlation between a CPE and a package.
+///
+/// CPEs are correlated by their vendor and product, packages by their PURL, ignoring
+/// version and qualifiers.
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema, PartialEq, Eq)]
+pub struct CpeMapping {
+    /// The opaque UUID of the mapping.
+    pub id: Uuid,
+
+    /// The vendor of the CPE.
+    pub vendor: String,
+
+    /// The product of the CPE.
+    pub product: String,
+
+    /// The PURL of the package, without version and qualifiers.
+    pub purl: String,
+
+    /// How the mapping came into existence.
+    pub source: MappingSource,
+
+    /// The date (in RFC3339 format) the mapping was created.
+    #[serde(with = "time::serde::rfc3339")]
+    pub created: OffsetDateTime,
+}
+
+impl From<cpe_purl_mapping::Model> for CpeMapping {
+    fn from(value: cpe_purl_mapping::Model) -> Self {
+        let purl = Purl {
+            ty: value.purl_type,
+            namespace: value.purl_namespace,
+            name: value.purl_name,
+            version: None,
+            qualifiers: Default::default(),
+        };
+
+        Self {
+            id: value.id,
+            vendor: value.cpe_vendor,
+            product: value.cpe_product,
+            purl: purl.to_string(),
+            source: value.source,
+            created: value.created,
+        }
+    }
+}
+
+/// A user provided correlation between a CPE and a package.
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema, PartialEq, Eq)]
+pub struct NewCpeMapping {
+    /// The CPE, only vendor and product are considered.
+    pub cpe: String,
+
+    /// The PURL of the package, version and qualifiers are ignored.
+    pub purl: String,
+}
@@ -0,0 +1,117 @@
+use crate::{
+    Error,
+    cpe_mapping::model::{CpeMapping, NewCpeMapping},
+};
+use sea_orm::{ActiveValue::Set, ConnectionTrait, EntityTrait};
+use sea_query::OnConflict;
+use std::str::FromStr;
+use trustify_common::{
+    cpe::{Component, Cpe},
+    db::{
+        limiter::LimiterTrait,
+        query::{Filtering, Query},
+    },
+    model::{Paginated, PaginatedResults},
+    purl::Purl,
+};
+use trustify_entity::cpe_purl_mapping::{self, MappingSource};
+use uuid::Uuid;
+
+#[derive(Default)]
+pub struct CpeMappingService {}
+
+impl CpeMappingService {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    pub async fn fetch_mappings<C: ConnectionTrait>(
+        &self,
+        search: Query,
+        paginated: Paginated,
+        connection: &C,
+    ) -> Result<PaginatedResults<CpeMapping>, Error> {
+        let limiter = cpe_purl_mapping::Entity::find()
+            .filtering(search)?
+            .limiting(connection, &paginated);
+
+        let total = limiter.total().await?;
+
+        Ok(PaginatedResults {
+            total,
+            items: limiter
+                .fetch()
+                .await?
+                .into_iter()
+                .map(CpeMapping::from)
+                .collect(),
+            next: None,
+        })
+    }
+
+    /// Create a curated mapping.
+    ///
+    /// If the same mapping was already learned before, it will be turned into a curated one.
+    pub async fn create_mapping<C: ConnectionTrait>(
+        &self,
+        mapping: NewCpeMapping,
+        connection: &C,
+    ) -> Result<CpeMapping, Error> {
+        let cpe = Cpe::from_str(&mapping.cpe)
+            .map_err(|err| Error::BadRequest(format!("Invalid CPE: {err}")))?;
+        let purl = Purl::from_str(&mapping.purl)?;
+
+        let (Component::Value(vendor), Component::Value(product)) = (cpe.vendor(), cpe.product())
+        else {
+            return Err(Error::BadRequest(
+                "The CPE must provide a vendor and a product".into(),
+            ));
+        };
+
+        let model = cpe_purl_mapping::ActiveModel {
+            id: Default::default(),
+            cpe_vendor: Set(vendor),
+            cpe_product: Set(product),
+            base_purl_id: Set(purl.package_uuid()),
+            purl_type: Set(purl.ty),
+            purl_namespace: Set(purl.namespace),
+            purl_name: Set(purl.name),
+            source: Set(MappingSource::Curated),
+            created: Default::default(),
+        };
+
+        let result = cpe_purl_mapping::Entity::insert(model)
+            .on_conflict(
+                OnConflict::columns([
+                    cpe_purl_mapping::Column::CpeVendor,
+                    cpe_purl_mapping::Column::CpeProduct,
+                    cpe_purl_mapping::Column::BasePurlId,
+                ])
+                .update_column(cpe_purl_mapping::Column::Source)
+                .to_owned(),
+            )
+            .exec_with_returning(connection)
+            .await?;
+
+        Ok(result.into())
+    }
+
+    /// Delete a mapping.
+    ///
+    /// Returns `true` if the mapping was found and deleted. Learned mappings may be learned
+    /// again, when ingesting the next SBOM providing them.
+    pub async fn delete_mapping<C: ConnectionTrait>(
+        &self,
+        id: Uuid,
+        connection: &C,
+    ) -> Result<bool, Error> {
+        let result = cpe_purl_mapping::Entity::delete_by_id(id)
+            .exec(connection)
+            .await?;
+
+        Ok(result.rows_affected > 0)
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,81 @@
+use crate::cpe_mapping::{model::NewCpeMapping, service::CpeMappingService};
+use test_context::test_context;
+use test_log::test;
+use trustify_common::{
+    db::query::{Query, q},
+    model::Paginated,
+};
+use trustify_entity::cpe_purl_mapping::MappingSource;
+use trustify_test_context::TrustifyContext;
+
+#[test_context(TrustifyContext)]
+#[test(tokio::test)]
+async fn learn_from_sbom(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    ctx.ingest_document("spdx/quarkus-bom-3.2.11.Final-redhat-00001.json")
+        .await?;
+
+    let service = CpeMappingService::new();
+
+    let mappings = service
+        .fetch_mappings(q("cpe_product=quarkus"), Paginated::default(), &ctx.db)
+        .await?;
+
+    assert_eq!(Some(1), mappings.total);
+    assert_eq!(mappings.items[0].vendor, "redhat");
+    assert_eq!(
+        mappings.items[0].purl,
+        "pkg:maven/com.redhat.quarkus.platform/quarkus-bom"
+    );
+    assert_eq!(mappings.items[0].source, MappingSource::Learned);
+
+    // ingesting a second version must not duplicate the mapping
+
+    ctx.ingest_document("spdx/quarkus-bom-3.2.12.Final-redhat-00002.json")
+        .await?;
+
+    let mappings = service
+        .fetch_mappings(q("cpe_product=quarkus"), Paginated::default(), &ctx.db)
+        .await?;
+
+    assert_eq!(Some(1), mappings.total);
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(tokio::test)]
+async fn curate_learned(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    ctx.ingest_document("spdx/quarkus-bom-3.2.11.Final-redhat-00001.json")
+        .await?;
+
+    let service = CpeMappingService::new();
+
+    let curated = service
+        .create_mapping(
+            NewCpeMapping {
+                cpe: "cpe:/a:redhat:quarkus:3.2::el8".into(),
+                purl: "pkg:maven/com.redhat.quarkus.platform/quarkus-bom@3.2.11".into(),
+            },
+            &ctx.db,
+        )
+        .await?;
+
+    assert_eq!(curated.source, MappingSource::Curated);
+    assert_eq!(
+        curated.purl,
+        "pkg:maven/com.redhat.quarkus.platform/quarkus-bom"
+    );
+
+    let mappings = service
+        .fetch_mappings(Query::default(), Paginated::default(), &ctx.db)
+        .await?;
+
+    assert_eq!(Some(1), mappings.total);
+    assert_eq!(mappings.items[0].id, curated.id);
+    assert_eq!(mappings.items[0].source, MappingSource::Curated);
+
+    assert!(service.delete_mapping(curated.id, &ctx.db).await?);
+    assert!(!service.delete_mapping(curated.id, &ctx.db).await?);
+
+    Ok(())
+}
@@ -1,6 +1,7 @@
 use actix_web::web;
 use trustify_common::db::Database;
 use trustify_module_analysis::service::AnalysisService;
+use trustify_module_audit::service::AuditService;
 use trustify_module_ingestor::graph::Graph;
 use trustify_module_ingestor::service::IngestorService;
 use trustify_module_storage::service::dispatch::DispatchBackend;
@@ -19,18 +20,24 @@ pub fn configure(
     storage: impl Into<DispatchBackend>,
     analysis: AnalysisService,
 ) {
-    let ingestor_service = IngestorService::new(Graph::new(db.clone()), storage, Some(analysis));
-    svc.app_data(web::Data::new(ingestor_service));
+    let ingestor_service =
+        IngestorService::new(Graph::new(db.clone()), storage, Some(analysis.clone()));
+    svc.app_data(web::Data::new(ingestor_service))
+        .app_data(web::Data::new(AuditService::new(db.clone())));
 
     crate::advisory::endpoints::configure(svc, db.clone(), config.advisory_upload_limit);
     crate::license::endpoints::configure(svc, db.clone());
+    crate::license_policy::endpoints::configure(svc, db.clone());
     #[cfg(feature = "ai")]
-    crate::ai::endpoints::configure(svc, db.clone());
+    crate::ai::endpoints::configure(svc, db.clone(), analysis);
+    crate::cpe_mapping::endpoints::configure(svc, db.clone());
     crate::organization::endpoints::configure(svc, db.clone());
     crate::purl::endpoints::configure(svc, db.clone());
     crate::product::endpoints::configure(svc, db.clone());
+    crate::retention::endpoints::configure(svc, db.clone());
     crate::sbom::endpoints::configure(svc, db.clone(), config.sbom_upload_limit);
     crate::vulnerability::endpoints::configure(svc, db.clone());
+    crate::watchlist::endpoints::configure(svc, db.clone());
     crate::weakness::endpoints::configure(svc, db.clone());
 }
 
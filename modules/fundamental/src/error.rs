@@ -22,6 +22,8 @@ pub enum Error {
     BadRequest(String),
     #[error("Not found: {0}")]
     NotFound(String),
+    #[error("Conflict: {0}")]
+    Conflict(String),
     #[error(transparent)]
     Any(#[from] anyhow::Error),
     #[error("Unsupported hash algorithm")]
@@ -60,6 +62,9 @@ impl ResponseError for Error {
             Self::NotFound(msg) => {
                 HttpResponse::NotFound().json(ErrorInformation::new("Not Found", msg))
             }
+            Self::Conflict(msg) => {
+                HttpResponse::Conflict().json(ErrorInformation::new("Conflict", msg))
+            }
             Self::Ingestor(inner) => inner.error_response(),
             Self::Query(err) => {
                 HttpResponse::BadRequest().json(ErrorInformation::new("Query error", err))
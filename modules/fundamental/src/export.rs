@@ -0,0 +1,331 @@
+use crate::Error;
+use actix_web::{HttpRequest, HttpResponse, http::header, web};
+use futures_util::{Stream, StreamExt, TryStreamExt, stream};
+use sea_orm::DbErr;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::pin::pin;
+use tokio::sync::mpsc;
+
+/// The number of items to convert at once, when items need additional information from the
+/// database before they can be exported
+pub const CHUNK_SIZE: usize = 1000;
+
+/// The amount of data to collect before sending it to the client
+const BUFFER_SIZE: usize = 64 * 1024;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Deserialize, utoipa::IntoParams)]
+pub struct Export {
+    /// The fields to export, `,`-delimited, when requesting CSV or NDJSON using the `Accept`
+    /// header.
+    ///
+    /// Defaults to all fields. Fields which are not a string, number or boolean are exported as
+    /// JSON.
+    #[serde(default)]
+    pub columns: Option<String>,
+}
+
+/// A format for exporting all items of a list at once, rather than paginated
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// Comma-separated values, with a header row
+    Csv,
+    /// Newline delimited JSON, one item per line
+    Ndjson,
+}
+
+impl ExportFormat {
+    /// The export format requested by the `Accept` header, or `None` if JSON is acceptable.
+    ///
+    /// Media types are considered in the order they are listed, ignoring any quality values.
+    pub fn accepted(req: &HttpRequest) -> Option<Self> {
+        req.headers()
+            .get_all(header::ACCEPT)
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(|value| value.split(';').next().unwrap_or_default().trim())
+            .find_map(|value| match value {
+                "text/csv" => Some(Some(Self::Csv)),
+                "application/x-ndjson" => Some(Some(Self::Ndjson)),
+                "application/json" | "application/*" | "*/*" => Some(None),
+                _ => None,
+            })
+            .flatten()
+    }
+
+    fn content_type(&self) -> &'static str {
+        match self {
+            Self::Csv => "text/csv",
+            Self::Ndjson => "application/x-ndjson",
+        }
+    }
+}
+
+/// Respond with all items sent to the [`ExportSink`], in the requested format.
+///
+/// The items are produced in the background, while the response is being streamed. Errors which
+/// occur before the first data is sent are reported as an error response, later errors abort the
+/// response.
+pub async fn respond<F>(
+    format: ExportFormat,
+    export: Export,
+    run: impl FnOnce(ExportSink) -> F,
+) -> Result<HttpResponse, Error>
+where
+    F: Future<Output = Result<(), Error>> + 'static,
+{
+    let (sender, mut receiver) = mpsc::channel(4);
+
+    let run = run(ExportSink::new(format, export, sender.clone()));
+    actix_web::rt::spawn(async move {
+        if let Err(err) = run.await {
+            log::warn!("Failed to export items: {err}");
+            let _ = sender.send(Err(err)).await;
+        }
+    });
+
+    let first = match receiver.recv().await {
+        Some(first) => first?,
+        None => web::Bytes::new(),
+    };
+    let rest = stream::unfold(receiver, |mut receiver| async move {
+        let next = receiver.recv().await?;
+        Some((next, receiver))
+    });
+
+    Ok(HttpResponse::Ok()
+        .content_type(format.content_type())
+        .streaming(stream::once(async { Ok(first) }).chain(rest)))
+}
+
+/// Convert a stream of rows in chunks of [`CHUNK_SIZE`], allowing to look up additional
+/// information for many items at once
+pub fn chunked<'a, T, U, F, Fut>(
+    rows: impl Stream<Item = Result<T, DbErr>> + 'a,
+    convert: F,
+) -> impl Stream<Item = Result<U, Error>> + 'a
+where
+    T: 'a,
+    U: 'a,
+    F: FnMut(Vec<T>) -> Fut + 'a,
+    Fut: Future<Output = Result<Vec<U>, Error>> + 'a,
+{
+    rows.map_err(Error::from)
+        .try_chunks(CHUNK_SIZE)
+        .map_err(|err| err.1)
+        .and_then(convert)
+        .map_ok(|items| stream::iter(items.into_iter().map(Ok)))
+        .try_flatten()
+}
+
+/// Receives the items to export, sending them to the client
+pub struct ExportSink {
+    writer: Writer,
+    sender: mpsc::Sender<Result<web::Bytes, Error>>,
+}
+
+impl ExportSink {
+    fn new(
+        format: ExportFormat,
+        export: Export,
+        sender: mpsc::Sender<Result<web::Bytes, Error>>,
+    ) -> Self {
+        let columns = export.columns.map(|columns| {
+            columns
+                .split(',')
+                .map(str::trim)
+                .filter(|column| !column.is_empty())
+                .map(ToString::to_string)
+                .collect()
+        });
+
+        Self {
+            writer: Writer::new(format, columns),
+            sender,
+        }
+    }
+
+    /// Send all items of a stream, until it ends
+    pub async fn send_all<T: Serialize>(
+        mut self,
+        items: impl Stream<Item = Result<T, Error>>,
+    ) -> Result<(), Error> {
+        let mut items = pin!(items);
+
+        while let Some(item) = items.try_next().await? {
+            self.writer.write(&item)?;
+            if self.writer.len() >= BUFFER_SIZE {
+                self.flush().await?;
+            }
+        }
+
+        self.writer.finish()?;
+        self.flush().await
+    }
+
+    async fn flush(&mut self) -> Result<(), Error> {
+        let data = self.writer.take()?;
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        self.sender
+            .send(Ok(data.into()))
+            .await
+            .map_err(|_| Error::Internal("export aborted by the client".into()))
+    }
+}
+
+enum Output {
+    Csv(Box<csv::Writer<Vec<u8>>>),
+    Ndjson(Vec<u8>),
+}
+
+struct Writer {
+    output: Output,
+    /// The requested columns, or the columns of the first item when writing CSV
+    columns: Option<Vec<String>>,
+    header: bool,
+}
+
+impl Writer {
+    fn new(format: ExportFormat, columns: Option<Vec<String>>) -> Self {
+        Self {
+            output: match format {
+                ExportFormat::Csv => Output::Csv(Box::new(csv::Writer::from_writer(vec![]))),
+                ExportFormat::Ndjson => Output::Ndjson(vec![]),
+            },
+            columns,
+            header: false,
+        }
+    }
+
+    fn write(&mut self, item: &impl Serialize) -> Result<(), anyhow::Error> {
+        let value = serde_json::to_value(item)?;
+
+        match &mut self.output {
+            Output::Csv(writer) => {
+                let columns = self.columns.get_or_insert_with(|| match &value {
+                    Value::Object(fields) => fields.keys().cloned().collect(),
+                    _ => vec![],
+                });
+                if !self.header {
+                    writer.write_record(columns.iter())?;
+                    self.header = true;
+                }
+                writer.write_record(columns.iter().map(|column| cell(value.get(column))))?;
+            }
+            Output::Ndjson(buffer) => {
+                match (&self.columns, value) {
+                    (Some(columns), Value::Object(mut fields)) => {
+                        let selected = columns
+                            .iter()
+                            .map(|column| {
+                                let value = fields.remove(column).unwrap_or_default();
+                                (column.clone(), value)
+                            })
+                            .collect::<Map<_, _>>();
+                        serde_json::to_writer(&mut *buffer, &selected)?;
+                    }
+                    (_, value) => serde_json::to_writer(&mut *buffer, &value)?,
+                }
+                buffer.push(b'\n');
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write the CSV header, in case there have been no items
+    fn finish(&mut self) -> Result<(), anyhow::Error> {
+        if let (Output::Csv(writer), Some(columns), false) =
+            (&mut self.output, &self.columns, self.header)
+        {
+            writer.write_record(columns.iter())?;
+            self.header = true;
+        }
+        Ok(())
+    }
+
+    fn len(&self) -> usize {
+        match &self.output {
+            Output::Csv(writer) => writer.get_ref().len(),
+            Output::Ndjson(buffer) => buffer.len(),
+        }
+    }
+
+    fn take(&mut self) -> Result<Vec<u8>, anyhow::Error> {
+        Ok(match &mut self.output {
+            Output::Csv(writer) => {
+                std::mem::replace(writer.as_mut(), csv::Writer::from_writer(vec![]))
+                    .into_inner()
+                    .map_err(|err| err.into_error())?
+            }
+            Output::Ndjson(buffer) => std::mem::take(buffer),
+        })
+    }
+}
+
+/// The CSV representation of a field
+fn cell(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(value)) => value.clone(),
+        Some(value) => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    fn export(format: ExportFormat, columns: Option<&str>, items: &[Value]) -> String {
+        let mut writer = Writer::new(
+            format,
+            columns.map(|columns| columns.split(',').map(ToString::to_string).collect()),
+        );
+        for item in items {
+            writer.write(item).expect("must write");
+        }
+        writer.finish().expect("must finish");
+        String::from_utf8(writer.take().expect("must flush")).expect("must be UTF-8")
+    }
+
+    #[test]
+    fn csv() {
+        let items = [
+            json!({"id": "a", "name": "foo, bar", "labels": {"type": "spdx"}, "count": 1}),
+            json!({"id": "b", "name": null, "labels": {}, "count": 2, "extra": true}),
+        ];
+
+        assert_eq!(
+            export(ExportFormat::Csv, None, &items),
+            "id,name,labels,count\na,\"foo, bar\",\"{\"\"type\"\":\"\"spdx\"\"}\",1\nb,,{},2\n"
+        );
+        assert_eq!(
+            export(ExportFormat::Csv, Some("name,id,missing"), &items),
+            "name,id,missing\n\"foo, bar\",a,\n,b,\n"
+        );
+        // the header is written even without items
+        assert_eq!(export(ExportFormat::Csv, Some("name,id"), &[]), "name,id\n");
+        assert_eq!(export(ExportFormat::Csv, None, &[]), "");
+    }
+
+    #[test]
+    fn ndjson() {
+        let items = [
+            json!({"id": "a", "name": "foo"}),
+            json!({"id": "b", "name": null}),
+        ];
+
+        assert_eq!(
+            export(ExportFormat::Ndjson, None, &items),
+            "{\"id\":\"a\",\"name\":\"foo\"}\n{\"id\":\"b\",\"name\":null}\n"
+        );
+        assert_eq!(
+            export(ExportFormat::Ndjson, Some("name,missing"), &items),
+            "{\"name\":\"foo\",\"missing\":null}\n{\"name\":null,\"missing\":null}\n"
+        );
+    }
+}
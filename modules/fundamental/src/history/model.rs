@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use trustify_entity::{
+    ingestion_history::{self, Delta},
+    labels::Labels,
+};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// An ingestion of a version of a document.
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema, PartialEq, Eq)]
+pub struct IngestionEvent {
+    /// The opaque UUID of the ingested advisory or SBOM.
+    #[serde(with = "uuid::serde::urn")]
+    #[schema(value_type=String)]
+    pub id: Uuid,
+
+    /// The date (in RFC3339 format) of the ingestion.
+    #[serde(with = "time::serde::rfc3339")]
+    pub ingested: OffsetDateTime,
+
+    /// The digest of the ingested document, prefixed by the hash type.
+    pub sha256: String,
+
+    /// The name of the importer which ingested the document, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub importer: Option<String>,
+
+    /// The ID of the user which uploaded the document, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+
+    /// The labels the document was ingested with.
+    pub labels: Labels,
+
+    /// `false` if the exact same document was already ingested before.
+    pub changed: bool,
+
+    /// The changes compared to the previous version of the document.
+    ///
+    /// For advisories, these are the statuses, for SBOMs the packages.
+    pub delta: Delta,
+}
+
+impl From<ingestion_history::Model> for IngestionEvent {
+    fn from(value: ingestion_history::Model) -> Self {
+        Self {
+            id: value.document_id,
+            ingested: value.ingested,
+            sha256: format!("sha256:{}", value.sha256),
+            importer: value.importer,
+            user: value.user_id,
+            labels: value.labels,
+            changed: value.changed,
+            delta: value.delta,
+        }
+    }
+}
@@ -0,0 +1,69 @@
+#[cfg(test)]
+mod test;
+
+use crate::{Error, history::model::IngestionEvent};
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QueryOrder, QuerySelect};
+use trustify_common::{
+    db::limiter::LimiterTrait,
+    model::{Paginated, PaginatedResults},
+};
+use trustify_entity::ingestion_history::{self, DocumentKind};
+use uuid::Uuid;
+
+#[derive(Default)]
+pub struct HistoryService {}
+
+impl HistoryService {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// Fetch the ingestion history of the logical document, the advisory or SBOM with the
+    /// provided ID belongs to.
+    ///
+    /// The most recent ingestion comes first.
+    pub async fn fetch_history<C: ConnectionTrait>(
+        &self,
+        kind: DocumentKind,
+        id: Uuid,
+        paginated: Paginated,
+        connection: &C,
+    ) -> Result<PaginatedResults<IngestionEvent>, Error> {
+        let key: Option<String> = ingestion_history::Entity::find()
+            .filter(ingestion_history::Column::Kind.eq(kind))
+            .filter(ingestion_history::Column::DocumentId.eq(id))
+            .select_only()
+            .column(ingestion_history::Column::Key)
+            .into_tuple()
+            .one(connection)
+            .await?;
+
+        let Some(key) = key else {
+            // documents ingested before tracking the history don't have one
+            return Ok(PaginatedResults {
+                items: vec![],
+                total: paginated.total.then_some(0),
+                next: None,
+            });
+        };
+
+        let limiter = ingestion_history::Entity::find()
+            .filter(ingestion_history::Column::Kind.eq(kind))
+            .filter(ingestion_history::Column::Key.eq(key))
+            .order_by_desc(ingestion_history::Column::Ingested)
+            .limiting(connection, &paginated);
+
+        let total = limiter.total().await?;
+
+        Ok(PaginatedResults {
+            total,
+            items: limiter
+                .fetch()
+                .await?
+                .into_iter()
+                .map(IngestionEvent::from)
+                .collect(),
+            next: None,
+        })
+    }
+}
@@ -0,0 +1,109 @@
+use crate::history::service::HistoryService;
+use test_context::test_context;
+use test_log::test;
+use trustify_common::{id::Id, model::Paginated};
+use trustify_entity::ingestion_history::DocumentKind;
+use trustify_test_context::{TrustifyContext, document_read};
+
+#[test_context(TrustifyContext)]
+#[test(tokio::test)]
+async fn advisory_history(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let original: serde_json::Value =
+        serde_json::from_reader(document_read("osv/GHSA-qq9f-q439-2574.json")?)?;
+
+    // a revision, moving the fixed version
+
+    let mut revision = original.clone();
+    revision["modified"] = "2025-01-03T10:00:00Z".into();
+    revision["affected"][0]["ranges"][0]["events"][1]["fixed"] = "7.1.1.Final".into();
+
+    let first = ctx.ingest_json(&original).await?;
+    let second = ctx.ingest_json(&revision).await?;
+    // ingesting the same document again
+    let third = ctx.ingest_json(&original).await?;
+
+    assert_ne!(first.id, second.id);
+    assert_eq!(first.id, third.id);
+
+    let Id::Uuid(id) = second.id else {
+        panic!("must be a UUID");
+    };
+
+    let history = HistoryService::new()
+        .fetch_history(DocumentKind::Advisory, id, Paginated::default(), &ctx.db)
+        .await?;
+
+    assert_eq!(history.total, Some(3));
+
+    // most recent first
+
+    assert!(!history.items[0].changed);
+    assert!(history.items[0].delta.added.is_empty());
+    assert!(history.items[0].delta.removed.is_empty());
+
+    assert!(history.items[1].changed);
+    assert_eq!(history.items[1].delta.added.len(), 1);
+    assert!(history.items[1].delta.added[0].contains("7.1.1.Final"));
+    assert_eq!(history.items[1].delta.removed.len(), 1);
+    assert!(history.items[1].delta.removed[0].contains("7.1.0.Final"));
+
+    assert!(history.items[2].changed);
+    assert!(history.items[2].delta.removed.is_empty());
+    assert!(!history.items[2].delta.added.is_empty());
+
+    assert_eq!(
+        history.items[2].labels.get("source").map(String::as_str),
+        Some("TrustifyContext")
+    );
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(tokio::test)]
+async fn sbom_history(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let original: serde_json::Value =
+        serde_json::from_reader(document_read("zookeeper-3.9.2-cyclonedx.json")?)?;
+
+    // a revision of the same document, upgrading a component
+
+    let mut revision = original.clone();
+    let component = &mut revision["components"][0];
+    assert_eq!(component["name"], "spotbugs-annotations");
+    component["version"] = "4.0.3".into();
+    component["purl"] = "pkg:maven/com.github.spotbugs/spotbugs-annotations@4.0.3?type=jar".into();
+
+    let first = ctx.ingest_json(&original).await?;
+    let second = ctx.ingest_json(&revision).await?;
+
+    assert_ne!(first.id, second.id);
+    assert_eq!(first.document_id, second.document_id);
+
+    let Id::Uuid(id) = second.id else {
+        panic!("must be a UUID");
+    };
+
+    let history = HistoryService::new()
+        .fetch_history(DocumentKind::Sbom, id, Paginated::default(), &ctx.db)
+        .await?;
+
+    assert_eq!(history.total, Some(2));
+
+    // most recent first
+
+    assert!(history.items[0].changed);
+    assert_eq!(
+        history.items[0].delta.added,
+        vec!["pkg:maven/com.github.spotbugs/spotbugs-annotations@4.0.3".to_string()]
+    );
+    assert_eq!(
+        history.items[0].delta.removed,
+        vec!["pkg:maven/com.github.spotbugs/spotbugs-annotations@4.0.2".to_string()]
+    );
+
+    assert!(history.items[1].changed);
+    assert!(history.items[1].delta.removed.is_empty());
+    assert!(!history.items[1].delta.added.is_empty());
+
+    Ok(())
+}
@@ -1,15 +1,21 @@
 pub mod advisory;
 #[cfg(feature = "ai")]
 pub mod ai;
+pub mod cpe_mapping;
 pub mod endpoints;
 pub mod error;
+pub mod export;
+pub mod history;
 pub mod license;
+pub mod license_policy;
 pub mod organization;
 pub mod product;
 pub mod purl;
+pub mod retention;
 pub mod sbom;
 pub mod source_document;
 pub mod vulnerability;
+pub mod watchlist;
 pub mod weakness;
 
 pub use endpoints::{Config, configure};
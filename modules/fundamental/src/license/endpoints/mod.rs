@@ -1,7 +1,10 @@
 use crate::{
     Error,
     license::{
-        endpoints::spdx::{get_spdx_license, list_spdx_licenses},
+        endpoints::{
+            report::{product_license_report, product_version_license_report, sbom_license_report},
+            spdx::{get_spdx_license, list_spdx_licenses},
+        },
         model::LicenseSummary,
         service::LicenseService,
     },
@@ -15,6 +18,7 @@ use trustify_common::{
 };
 use uuid::Uuid;
 
+pub mod report;
 pub mod spdx;
 
 pub fn configure(config: &mut utoipa_actix_web::service_config::ServiceConfig, db: Database) {
@@ -26,7 +30,10 @@ pub fn configure(config: &mut utoipa_actix_web::service_config::ServiceConfig, d
         .service(get_spdx_license)
         .service(list_licenses)
         .service(get_license)
-        .service(get_license_purls);
+        .service(get_license_purls)
+        .service(sbom_license_report)
+        .service(product_license_report)
+        .service(product_version_license_report);
 }
 
 #[utoipa::path(
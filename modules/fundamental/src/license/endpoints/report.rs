@@ -0,0 +1,120 @@
+use crate::{
+    Error,
+    license::{
+        model::report::{LicenseReport, LicenseReportFormat, SpdxLicenseList},
+        service::LicenseService,
+    },
+};
+use actix_web::{HttpResponse, Responder, get, web};
+use trustify_auth::{ReadSbom, authorizer::Require};
+use uuid::Uuid;
+
+#[derive(Clone, Debug, Default, serde::Deserialize, utoipa::IntoParams)]
+struct ReportQuery {
+    /// The format of the report
+    #[serde(default)]
+    #[param(inline)]
+    format: LicenseReportFormat,
+}
+
+#[utoipa::path(
+    tag = "license",
+    operation_id = "getSbomLicenseReport",
+    params(
+        ("id", Path, description = "ID of the SBOM"),
+        ReportQuery,
+    ),
+    responses(
+        (status = 200, description = "The license report of the SBOM. Using the `spdx` format, the SPDX licenses it uses.", content(
+            (LicenseReport = "application/json"),
+            (SpdxLicenseList = "application/json"),
+            (String = "text/csv"),
+        )),
+        (status = 404, description = "The SBOM could not be found"),
+    ),
+)]
+#[get("/v2/sbom/{id}/license-report")]
+/// Get the license report of an SBOM
+pub async fn sbom_license_report(
+    state: web::Data<LicenseService>,
+    id: web::Path<Uuid>,
+    web::Query(ReportQuery { format }): web::Query<ReportQuery>,
+    _: Require<ReadSbom>,
+) -> Result<impl Responder, Error> {
+    match state.sbom_license_report(id.into_inner()).await? {
+        Some(report) => render(report, format),
+        None => Ok(HttpResponse::NotFound().finish()),
+    }
+}
+
+#[utoipa::path(
+    tag = "license",
+    operation_id = "getProductLicenseReport",
+    params(
+        ("id", Path, description = "Opaque ID of the product"),
+        ReportQuery,
+    ),
+    responses(
+        (status = 200, description = "The license report of the SBOMs of all product versions. Using the `spdx` format, the SPDX licenses they use.", content(
+            (LicenseReport = "application/json"),
+            (SpdxLicenseList = "application/json"),
+            (String = "text/csv"),
+        )),
+        (status = 404, description = "The product could not be found"),
+    ),
+)]
+#[get("/v2/product/{id}/license-report")]
+/// Get the license report of a product, covering all its versions
+pub async fn product_license_report(
+    state: web::Data<LicenseService>,
+    id: web::Path<Uuid>,
+    web::Query(ReportQuery { format }): web::Query<ReportQuery>,
+    _: Require<ReadSbom>,
+) -> Result<impl Responder, Error> {
+    match state.product_license_report(id.into_inner()).await? {
+        Some(report) => render(report, format),
+        None => Ok(HttpResponse::NotFound().finish()),
+    }
+}
+
+#[utoipa::path(
+    tag = "license",
+    operation_id = "getProductVersionLicenseReport",
+    params(
+        ("id", Path, description = "Opaque ID of the product"),
+        ("version", Path, description = "The version of the product"),
+        ReportQuery,
+    ),
+    responses(
+        (status = 200, description = "The license report of the SBOM of the product version. Using the `spdx` format, the SPDX licenses it uses.", content(
+            (LicenseReport = "application/json"),
+            (SpdxLicenseList = "application/json"),
+            (String = "text/csv"),
+        )),
+        (status = 404, description = "The product version could not be found"),
+    ),
+)]
+#[get("/v2/product/{id}/version/{version}/license-report")]
+/// Get the license report of a product version
+pub async fn product_version_license_report(
+    state: web::Data<LicenseService>,
+    path: web::Path<(Uuid, String)>,
+    web::Query(ReportQuery { format }): web::Query<ReportQuery>,
+    _: Require<ReadSbom>,
+) -> Result<impl Responder, Error> {
+    let (id, version) = path.into_inner();
+    match state.product_version_license_report(id, &version).await? {
+        Some(report) => render(report, format),
+        None => Ok(HttpResponse::NotFound().finish()),
+    }
+}
+
+fn render(report: LicenseReport, format: LicenseReportFormat) -> Result<HttpResponse, Error> {
+    Ok(match format {
+        LicenseReportFormat::Json => HttpResponse::Ok().json(report),
+        LicenseReportFormat::Csv => HttpResponse::Ok()
+            .content_type("text/csv")
+            .body(report.to_csv()?),
+        LicenseReportFormat::Spdx => HttpResponse::Ok().json(report.to_spdx_license_list()),
+    })
+}
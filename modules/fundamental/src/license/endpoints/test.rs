@@ -1,8 +1,12 @@
 use crate::license::model::{
     LicenseDetailsPurlSummary, LicenseSummary, SpdxLicenseDetails, SpdxLicenseSummary,
+    report::LicenseReport,
 };
 use crate::test::caller;
-use actix_web::test::TestRequest;
+use actix_web::{
+    http::{StatusCode, header},
+    test::TestRequest,
+};
 use test_context::test_context;
 use test_log::test;
 use trustify_common::model::PaginatedResults;
@@ -19,7 +23,7 @@ async fn list_spdx_licenses(ctx: &TrustifyContext) -> Result<(), anyhow::Error>
 
     let response: PaginatedResults<SpdxLicenseSummary> = app.call_and_read_body_json(request).await;
 
-    assert_eq!(687, response.total);
+    assert_eq!(Some(687), response.total);
 
     Ok(())
 }
@@ -51,13 +55,13 @@ async fn list_licenses(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
 
     let request = TestRequest::get().uri(uri).to_request();
     let response: PaginatedResults<LicenseSummary> = app.call_and_read_body_json(request).await;
-    assert_eq!(0, response.total);
+    assert_eq!(Some(0), response.total);
 
     ctx.ingest_document("ubi9-9.2-755.1697625012.json").await?;
 
     let request = TestRequest::get().uri(uri).to_request();
     let response: PaginatedResults<LicenseSummary> = app.call_and_read_body_json(request).await;
-    assert_eq!(25, response.total);
+    assert_eq!(Some(25), response.total);
 
     Ok(())
 }
@@ -85,7 +89,7 @@ async fn list_license_purls(ctx: &TrustifyContext) -> Result<(), anyhow::Error>
     let response: PaginatedResults<LicenseDetailsPurlSummary> =
         app.call_and_read_body_json(request).await;
 
-    assert_eq!(29, response.total);
+    assert_eq!(Some(29), response.total);
 
     let uri = format!("/api/v2/license/{}/purl?offset=25", lgpl.id.urn());
 
@@ -94,7 +98,39 @@ async fn list_license_purls(ctx: &TrustifyContext) -> Result<(), anyhow::Error>
         app.call_and_read_body_json(request).await;
 
     assert_eq!(4, response.items.len());
-    assert_eq!(29, response.total);
+    assert_eq!(Some(29), response.total);
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn sbom_license_report(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let app = caller(ctx).await?;
+    let result = ctx.ingest_document("ubi9-9.2-755.1697625012.json").await?;
+    let id = result.id.try_as_uid().expect("must be a UUID");
+
+    let uri = format!("/api/v2/sbom/{id}/license-report");
+    let request = TestRequest::get().uri(&uri).to_request();
+    let response: LicenseReport = app.call_and_read_body_json(request).await;
+    assert_eq!(response.sboms, vec![id]);
+    assert!(!response.groups.is_empty());
+
+    let uri = format!("/api/v2/sbom/{id}/license-report?format=csv");
+    let request = TestRequest::get().uri(&uri).to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get(header::CONTENT_TYPE),
+        Some(&header::HeaderValue::from_static("text/csv"))
+    );
+    let body = String::from_utf8(actix_web::test::read_body(response).await.to_vec())?;
+    assert!(body.contains("\nunknown,LGPLV2+,,not_spdx,pkg:rpm/"));
+
+    let uri = format!("/api/v2/sbom/{}/license-report", uuid::Uuid::now_v7());
+    let request = TestRequest::get().uri(&uri).to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
 
     Ok(())
 }
@@ -1,3 +1,5 @@
+pub mod report;
+
 use crate::{Error, purl::model::VersionedPurlHead, sbom::model::SbomHead};
 use sea_orm::{ConnectionTrait, ModelTrait, PaginatorTrait};
 use serde::{Deserialize, Serialize};
@@ -0,0 +1,220 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// The category of a license, ordered from the least to the most restrictive one
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    ToSchema,
+    strum::Display,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum LicenseCategory {
+    /// OSI approved or FSF free licenses, without copyleft obligations
+    Permissive,
+    /// Copyleft licenses limited to the file or library, or copyleft licenses with an exception
+    WeakCopyleft,
+    /// Copyleft licenses
+    Copyleft,
+    /// SPDX licenses which are neither OSI approved nor FSF free
+    Other,
+    /// Texts which are no valid SPDX expression, or reference licenses outside the SPDX list
+    Unknown,
+}
+
+/// An issue with a license text
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    ToSchema,
+    strum::Display,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum LicenseFlag {
+    /// The text is not a valid SPDX expression
+    NotSpdx,
+    /// The expression references a license outside the SPDX license list (`LicenseRef-`)
+    LicenseRef,
+    /// The expression uses a deprecated SPDX identifier
+    Deprecated,
+}
+
+/// A license compliance report, grouping components by the category of their license
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct LicenseReport {
+    /// The SBOMs the report covers
+    #[schema(value_type = Vec<String>)]
+    pub sboms: Vec<Uuid>,
+    /// The groups, ordered by category
+    pub groups: Vec<LicenseReportGroup>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct LicenseReportGroup {
+    pub category: LicenseCategory,
+    pub licenses: Vec<LicenseReportEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct LicenseReportEntry {
+    /// The license as declared by the components
+    pub license: String,
+    /// The SPDX expression, missing if the license is not a valid expression
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expression: Option<String>,
+    /// The SPDX license identifiers referenced by the license
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub spdx_licenses: Vec<String>,
+    /// The SPDX exception identifiers referenced by the license
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub spdx_license_exceptions: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub flags: Vec<LicenseFlag>,
+    /// The PURLs of the components declaring the license
+    pub purls: Vec<String>,
+    /// The CPEs of the components declaring the license
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cpes: Vec<String>,
+}
+
+/// The format to render a license report in
+#[derive(
+    Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema, strum::Display,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum LicenseReportFormat {
+    #[default]
+    Json,
+    /// One row per license and component, identified by either a PURL or a CPE
+    Csv,
+    /// The SPDX licenses and exceptions used, in the format of the SPDX license list
+    Spdx,
+}
+
+#[derive(Serialize)]
+struct CsvRow<'a> {
+    category: LicenseCategory,
+    license: &'a str,
+    expression: Option<&'a str>,
+    flags: String,
+    purl: Option<&'a str>,
+    cpe: Option<&'a str>,
+}
+
+impl LicenseReport {
+    /// Render the report as CSV, one row per license and component
+    pub fn to_csv(&self) -> Result<Vec<u8>, anyhow::Error> {
+        let mut writer = csv::Writer::from_writer(vec![]);
+
+        for group in &self.groups {
+            for entry in &group.licenses {
+                let flags = entry
+                    .flags
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                let row = |purl, cpe| CsvRow {
+                    category: group.category,
+                    license: &entry.license,
+                    expression: entry.expression.as_deref(),
+                    flags: flags.clone(),
+                    purl,
+                    cpe,
+                };
+                for purl in &entry.purls {
+                    writer.serialize(row(Some(purl), None))?;
+                }
+                for cpe in &entry.cpes {
+                    writer.serialize(row(None, Some(cpe)))?;
+                }
+            }
+        }
+
+        Ok(writer.into_inner().map_err(|err| err.into_error())?)
+    }
+
+    /// The SPDX licenses and exceptions used by the report, in the format of the SPDX license list
+    pub fn to_spdx_license_list(&self) -> SpdxLicenseList {
+        let entries = || self.groups.iter().flat_map(|group| &group.licenses);
+
+        let mut licenses = entries()
+            .flat_map(|entry| &entry.spdx_licenses)
+            .filter_map(|id| spdx::license_id(id))
+            .map(|id| SpdxLicenseListEntry {
+                reference: format!("https://spdx.org/licenses/{}.html", id.name),
+                license_id: id.name.to_string(),
+                name: id.full_name.to_string(),
+                is_deprecated_license_id: id.is_deprecated(),
+                is_osi_approved: id.is_osi_approved(),
+                is_fsf_libre: id.is_fsf_free_libre(),
+            })
+            .collect::<Vec<_>>();
+        licenses.sort_by(|a, b| a.license_id.cmp(&b.license_id));
+        licenses.dedup();
+
+        let mut exceptions = entries()
+            .flat_map(|entry| &entry.spdx_license_exceptions)
+            .filter_map(|id| spdx::exception_id(id))
+            .map(|id| SpdxExceptionListEntry {
+                reference: format!("https://spdx.org/licenses/{}.html", id.name),
+                license_exception_id: id.name.to_string(),
+                is_deprecated_license_id: id.is_deprecated(),
+            })
+            .collect::<Vec<_>>();
+        exceptions.sort_by(|a, b| a.license_exception_id.cmp(&b.license_exception_id));
+        exceptions.dedup();
+
+        SpdxLicenseList {
+            licenses,
+            exceptions,
+        }
+    }
+}
+
+/// A list of licenses, following the format of the SPDX license list
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SpdxLicenseList {
+    pub licenses: Vec<SpdxLicenseListEntry>,
+    pub exceptions: Vec<SpdxExceptionListEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SpdxLicenseListEntry {
+    pub reference: String,
+    pub license_id: String,
+    pub name: String,
+    pub is_deprecated_license_id: bool,
+    pub is_osi_approved: bool,
+    pub is_fsf_libre: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SpdxExceptionListEntry {
+    pub reference: String,
+    pub license_exception_id: String,
+    pub is_deprecated_license_id: bool,
+}
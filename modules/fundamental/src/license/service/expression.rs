@@ -0,0 +1,163 @@
+use crate::license::model::report::{LicenseCategory, LicenseFlag};
+use spdx::{
+    Expression, LicenseItem, LicenseReq, ParseMode,
+    expression::{ExprNode, Operator},
+};
+use std::collections::BTreeSet;
+
+/// The result of evaluating a license text as an SPDX expression
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EvaluatedLicense {
+    /// The SPDX expression, `None` if the text could not be parsed
+    pub expression: Option<String>,
+    /// The category of the expression, respecting `AND` and `OR` operators
+    pub category: LicenseCategory,
+    /// The SPDX license identifiers referenced by the expression
+    pub licenses: BTreeSet<String>,
+    /// The SPDX exception identifiers referenced by the expression
+    pub exceptions: BTreeSet<String>,
+    pub flags: BTreeSet<LicenseFlag>,
+}
+
+/// Evaluate a license text as an SPDX expression.
+///
+/// For `OR` expressions, the least restrictive choice is used. For `AND` expressions, the most
+/// restrictive one. Licenses with an exception (`WITH`) are considered less restrictive than the
+/// license alone.
+pub fn evaluate(text: &str) -> EvaluatedLicense {
+    let Ok(expression) = Expression::parse_mode(text.trim(), ParseMode::LAX) else {
+        return EvaluatedLicense {
+            expression: None,
+            category: LicenseCategory::Unknown,
+            licenses: Default::default(),
+            exceptions: Default::default(),
+            flags: BTreeSet::from([LicenseFlag::NotSpdx]),
+        };
+    };
+
+    let mut licenses = BTreeSet::new();
+    let mut exceptions = BTreeSet::new();
+    let mut flags = BTreeSet::new();
+
+    // the expression is stored in postfix notation
+
+    let mut stack = Vec::<LicenseCategory>::new();
+    for node in expression.iter() {
+        match node {
+            ExprNode::Req(req) => {
+                let req = &req.req;
+                match &req.license {
+                    LicenseItem::Spdx { id, .. } => {
+                        licenses.insert(id.name.to_string());
+                        if id.is_deprecated() {
+                            flags.insert(LicenseFlag::Deprecated);
+                        }
+                    }
+                    LicenseItem::Other { .. } => {
+                        flags.insert(LicenseFlag::LicenseRef);
+                    }
+                }
+                if let Some(exception) = &req.exception {
+                    exceptions.insert(exception.name.to_string());
+                }
+                stack.push(category(req));
+            }
+            ExprNode::Op(op) => {
+                let (Some(rhs), Some(lhs)) = (stack.pop(), stack.pop()) else {
+                    // a parsed expression is always well-formed
+                    return EvaluatedLicense {
+                        expression: None,
+                        category: LicenseCategory::Unknown,
+                        licenses,
+                        exceptions,
+                        flags: BTreeSet::from([LicenseFlag::NotSpdx]),
+                    };
+                };
+                stack.push(match op {
+                    Operator::And => lhs.max(rhs),
+                    Operator::Or => lhs.min(rhs),
+                });
+            }
+        }
+    }
+
+    EvaluatedLicense {
+        expression: Some(expression.to_string()),
+        category: stack.pop().unwrap_or(LicenseCategory::Unknown),
+        licenses,
+        exceptions,
+        flags,
+    }
+}
+
+/// Licenses which are copyleft, but limited to the file or library
+const WEAK_COPYLEFT: &[&str] = &["CDDL-", "CPL-", "EPL-", "LGPL-", "MPL-"];
+
+/// The category of a single license requirement
+pub fn category(req: &LicenseReq) -> LicenseCategory {
+    match &req.license {
+        LicenseItem::Spdx { id, .. } if id.is_copyleft() => {
+            if req.exception.is_some()
+                || WEAK_COPYLEFT
+                    .iter()
+                    .any(|prefix| id.name.starts_with(prefix))
+            {
+                LicenseCategory::WeakCopyleft
+            } else {
+                LicenseCategory::Copyleft
+            }
+        }
+        LicenseItem::Spdx { id, .. } if id.is_osi_approved() || id.is_fsf_free_libre() => {
+            LicenseCategory::Permissive
+        }
+        LicenseItem::Spdx { .. } => LicenseCategory::Other,
+        LicenseItem::Other { .. } => LicenseCategory::Unknown,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use test_log::test;
+
+    #[test]
+    fn categories() {
+        for (text, expected) in [
+            ("MIT", LicenseCategory::Permissive),
+            ("Apache-2.0 OR GPL-2.0-only", LicenseCategory::Permissive),
+            ("Apache-2.0 AND GPL-2.0-only", LicenseCategory::Copyleft),
+            ("LGPL-2.1-or-later", LicenseCategory::WeakCopyleft),
+            (
+                "GPL-2.0-only WITH Classpath-exception-2.0",
+                LicenseCategory::WeakCopyleft,
+            ),
+            (
+                "MIT AND (GPL-3.0-only OR MPL-2.0)",
+                LicenseCategory::WeakCopyleft,
+            ),
+            ("MIT AND LicenseRef-Proprietary", LicenseCategory::Unknown),
+            ("NOASSERTION", LicenseCategory::Unknown),
+            ("Some custom license text", LicenseCategory::Unknown),
+        ] {
+            assert_eq!(evaluate(text).category, expected, "{text}");
+        }
+    }
+
+    #[test]
+    fn flags() {
+        let result = evaluate("GPL-2.0-only WITH Classpath-exception-2.0 OR LicenseRef-Foo");
+        assert_eq!(result.flags, BTreeSet::from([LicenseFlag::LicenseRef]));
+        assert_eq!(
+            result.licenses,
+            BTreeSet::from(["GPL-2.0-only".to_string()])
+        );
+        assert_eq!(
+            result.exceptions,
+            BTreeSet::from(["Classpath-exception-2.0".to_string()])
+        );
+
+        let result = evaluate("not a license");
+        assert_eq!(result.expression, None);
+        assert_eq!(result.flags, BTreeSet::from([LicenseFlag::NotSpdx]));
+    }
+}
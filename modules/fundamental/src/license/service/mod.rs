@@ -1,3 +1,8 @@
+pub mod expression;
+mod report;
+
+pub(crate) use report::PackageLicense;
+
 use crate::{
     Error,
     license::model::{
@@ -8,9 +13,9 @@ use crate::{
 };
 use sea_orm::{
     ColumnTrait, DbErr, EntityTrait, FromQueryResult, ModelTrait, PaginatorTrait, QueryFilter,
-    QueryResult, QuerySelect, RelationTrait, Select, TransactionTrait,
+    QueryOrder, QueryResult, QuerySelect, RelationTrait, Select, TransactionTrait,
 };
-use sea_query::JoinType;
+use sea_query::{Expr, JoinType};
 use trustify_common::{
     db::{
         Database,
@@ -39,17 +44,16 @@ impl LicenseService {
     ) -> Result<PaginatedResults<LicenseSummary>, Error> {
         let tx = self.db.begin().await?;
 
-        let limiter = license::Entity::find().filtering(search)?.limiting(
-            &self.db,
-            paginated.offset,
-            paginated.limit,
-        );
+        let limiter = license::Entity::find()
+            .filtering(search)?
+            .limiting(&self.db, &paginated);
 
         let total = limiter.total().await?;
 
         Ok(PaginatedResults {
             items: LicenseSummary::from_entities(&limiter.fetch().await?, &tx).await?,
             total,
+            next: None,
         })
     }
 
@@ -67,6 +71,53 @@ impl LicenseService {
         Ok(None)
     }
 
+    /// List the licenses used by the packages of an SBOM.
+    ///
+    /// The number of purls is the number of packages of the SBOM using the license.
+    pub async fn fetch_sbom_licenses(&self, sbom_id: Uuid) -> Result<Vec<LicenseSummary>, Error> {
+        #[derive(FromQueryResult)]
+        struct Row {
+            id: Uuid,
+            text: String,
+            spdx_licenses: Option<Vec<String>>,
+            spdx_license_exceptions: Option<Vec<String>>,
+            purls: i64,
+        }
+
+        let rows = license::Entity::find()
+            .join(JoinType::Join, license::Relation::PurlAssertions.def())
+            .filter(purl_license_assertion::Column::SbomId.eq(sbom_id))
+            .select_only()
+            .column(license::Column::Id)
+            .column(license::Column::Text)
+            .column(license::Column::SpdxLicenses)
+            .column(license::Column::SpdxLicenseExceptions)
+            .column_as(
+                Expr::col((
+                    purl_license_assertion::Entity,
+                    purl_license_assertion::Column::VersionedPurlId,
+                ))
+                .count_distinct(),
+                "purls",
+            )
+            .group_by(license::Column::Id)
+            .order_by_asc(license::Column::Text)
+            .into_model::<Row>()
+            .all(&self.db)
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| LicenseSummary {
+                id: row.id,
+                license: row.text,
+                spdx_licenses: row.spdx_licenses.unwrap_or_default(),
+                spdx_license_exceptions: row.spdx_license_exceptions.unwrap_or_default(),
+                purls: row.purls as u64,
+            })
+            .collect())
+    }
+
     pub async fn get_license_purls(
         &self,
         id: Uuid,
@@ -118,11 +169,7 @@ impl LicenseService {
             .join(JoinType::Join, purl_license_assertion::Relation::Sbom.def())
             .filter(license::Column::Id.eq(id))
             .filtering(query)?
-            .try_limiting_as_multi_model::<PurlLicenseCatcher>(
-                &tx,
-                pagination.offset,
-                pagination.limit,
-            )?;
+            .try_limiting_as_multi_model::<PurlLicenseCatcher>(&tx, &pagination)?;
 
         let total = licensed_purls.total().await?;
 
@@ -136,7 +183,11 @@ impl LicenseService {
             })
         }
 
-        Ok(PaginatedResults { items, total })
+        Ok(PaginatedResults {
+            items,
+            total,
+            next: None,
+        })
     }
 
     pub async fn list_spdx_licenses(
@@ -152,11 +203,13 @@ impl LicenseService {
                     || name.to_lowercase().contains(&search.q.to_lowercase())
             })
             .collect::<Vec<_>>();
+        let total = paginated.total.then_some(all_matching.len() as u64);
 
         if all_matching.len() < paginated.offset as usize {
             return Ok(PaginatedResults {
                 items: vec![],
-                total: all_matching.len() as u64,
+                total,
+                next: None,
             });
         }
 
@@ -165,12 +218,14 @@ impl LicenseService {
         if paginated.limit > 0 && matching.len() > paginated.limit as usize {
             Ok(PaginatedResults {
                 items: SpdxLicenseSummary::from_details(&matching[..paginated.limit as usize]),
-                total: all_matching.len() as u64,
+                total,
+                next: None,
             })
         } else {
             Ok(PaginatedResults {
                 items: SpdxLicenseSummary::from_details(matching),
-                total: all_matching.len() as u64,
+                total,
+                next: None,
             })
         }
     }
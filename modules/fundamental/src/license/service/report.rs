@@ -0,0 +1,286 @@
+use crate::{
+    Error,
+    license::{
+        model::report::{LicenseCategory, LicenseReport, LicenseReportEntry, LicenseReportGroup},
+        service::{LicenseService, expression},
+    },
+};
+use sea_orm::{ColumnTrait, EntityTrait, FromQueryResult, QueryFilter, QuerySelect, RelationTrait};
+use sea_query::JoinType;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use trustify_common::purl::Purl;
+use trustify_entity::{
+    base_purl, cpe, cpe_license_assertion, license, product, product_version,
+    purl_license_assertion, sbom, versioned_purl,
+};
+use uuid::Uuid;
+
+/// A license declared by a package
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct PackageLicense {
+    pub license: String,
+    /// The SPDX license identifiers, as extracted when ingesting the license
+    pub spdx_licenses: Vec<String>,
+    /// The SPDX exception identifiers, as extracted when ingesting the license
+    pub spdx_license_exceptions: Vec<String>,
+    pub purl: String,
+}
+
+/// A license declared by a package which is identified by a CPE
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct CpeLicense {
+    license: String,
+    spdx_licenses: Vec<String>,
+    spdx_license_exceptions: Vec<String>,
+    cpe: String,
+}
+
+/// The components declaring a license
+#[derive(Default)]
+struct Components {
+    spdx_licenses: BTreeSet<String>,
+    spdx_license_exceptions: BTreeSet<String>,
+    purls: BTreeSet<String>,
+    cpes: BTreeSet<String>,
+}
+
+impl LicenseService {
+    /// Create the license report of an SBOM
+    ///
+    /// Returns `Ok(None)` if the SBOM could not be found.
+    pub async fn sbom_license_report(&self, sbom_id: Uuid) -> Result<Option<LicenseReport>, Error> {
+        if sbom::Entity::find_by_id(sbom_id)
+            .one(&self.db)
+            .await?
+            .is_none()
+        {
+            return Ok(None);
+        }
+
+        Ok(Some(self.license_report(vec![sbom_id]).await?))
+    }
+
+    /// Create the license report of a product version, covering its SBOM
+    ///
+    /// Returns `Ok(None)` if the product version could not be found.
+    pub async fn product_version_license_report(
+        &self,
+        product_id: Uuid,
+        version: &str,
+    ) -> Result<Option<LicenseReport>, Error> {
+        let Some(product_version) = product_version::Entity::find()
+            .filter(product_version::Column::ProductId.eq(product_id))
+            .filter(product_version::Column::Version.eq(version))
+            .one(&self.db)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        Ok(Some(
+            self.license_report(product_version.sbom_id.into_iter().collect())
+                .await?,
+        ))
+    }
+
+    /// Create the license report of a product, covering the SBOMs of all its versions
+    ///
+    /// Returns `Ok(None)` if the product could not be found.
+    pub async fn product_license_report(
+        &self,
+        product_id: Uuid,
+    ) -> Result<Option<LicenseReport>, Error> {
+        if product::Entity::find_by_id(product_id)
+            .one(&self.db)
+            .await?
+            .is_none()
+        {
+            return Ok(None);
+        }
+
+        let mut sboms = product_version::Entity::find()
+            .filter(product_version::Column::ProductId.eq(product_id))
+            .all(&self.db)
+            .await?
+            .into_iter()
+            .filter_map(|version| version.sbom_id)
+            .collect::<Vec<_>>();
+        sboms.sort();
+        sboms.dedup();
+
+        Ok(Some(self.license_report(sboms).await?))
+    }
+
+    /// Find the license declared for each package of the SBOMs
+    ///
+    /// Returns one entry per distinct license and package PURL, ignoring qualifiers.
+    pub(crate) async fn package_licenses(
+        &self,
+        sboms: &[Uuid],
+    ) -> Result<Vec<PackageLicense>, Error> {
+        #[derive(FromQueryResult)]
+        struct Row {
+            license: String,
+            spdx_licenses: Option<Vec<String>>,
+            spdx_license_exceptions: Option<Vec<String>>,
+            ty: String,
+            namespace: Option<String>,
+            name: String,
+            version: String,
+        }
+
+        let rows = purl_license_assertion::Entity::find()
+            .join(
+                JoinType::Join,
+                purl_license_assertion::Relation::License.def(),
+            )
+            .join(
+                JoinType::Join,
+                purl_license_assertion::Relation::VersionedPurl.def(),
+            )
+            .join(JoinType::Join, versioned_purl::Relation::BasePurl.def())
+            .filter(purl_license_assertion::Column::SbomId.is_in(sboms.iter().copied()))
+            .select_only()
+            .column_as(license::Column::Text, "license")
+            .column(license::Column::SpdxLicenses)
+            .column(license::Column::SpdxLicenseExceptions)
+            .column_as(base_purl::Column::Type, "ty")
+            .column(base_purl::Column::Namespace)
+            .column(base_purl::Column::Name)
+            .column(versioned_purl::Column::Version)
+            .distinct()
+            .into_model::<Row>()
+            .all(&self.db)
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| PackageLicense {
+                license: row.license,
+                spdx_licenses: row.spdx_licenses.unwrap_or_default(),
+                spdx_license_exceptions: row.spdx_license_exceptions.unwrap_or_default(),
+                purl: Purl {
+                    ty: row.ty,
+                    namespace: row.namespace,
+                    name: row.name,
+                    version: Some(row.version),
+                    qualifiers: Default::default(),
+                }
+                .to_string(),
+            })
+            .collect())
+    }
+
+    /// Find the license declared for each package of the SBOMs which is identified by a CPE
+    ///
+    /// Returns one entry per distinct license and CPE.
+    async fn cpe_licenses(&self, sboms: &[Uuid]) -> Result<Vec<CpeLicense>, Error> {
+        #[derive(FromQueryResult)]
+        struct Row {
+            license: String,
+            spdx_licenses: Option<Vec<String>>,
+            spdx_license_exceptions: Option<Vec<String>>,
+            cpe_id: Uuid,
+        }
+
+        let rows = cpe_license_assertion::Entity::find()
+            .join(
+                JoinType::Join,
+                cpe_license_assertion::Relation::License.def(),
+            )
+            .filter(cpe_license_assertion::Column::SbomId.is_in(sboms.iter().copied()))
+            .select_only()
+            .column_as(license::Column::Text, "license")
+            .column(license::Column::SpdxLicenses)
+            .column(license::Column::SpdxLicenseExceptions)
+            .column(cpe_license_assertion::Column::CpeId)
+            .distinct()
+            .into_model::<Row>()
+            .all(&self.db)
+            .await?;
+
+        let cpes = cpe::Entity::find()
+            .filter(cpe::Column::Id.is_in(rows.iter().map(|row| row.cpe_id)))
+            .all(&self.db)
+            .await?
+            .into_iter()
+            .map(|cpe| (cpe.id, cpe.to_string()))
+            .collect::<HashMap<_, _>>();
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| {
+                Some(CpeLicense {
+                    cpe: cpes.get(&row.cpe_id)?.clone(),
+                    license: row.license,
+                    spdx_licenses: row.spdx_licenses.unwrap_or_default(),
+                    spdx_license_exceptions: row.spdx_license_exceptions.unwrap_or_default(),
+                })
+            })
+            .collect())
+    }
+
+    async fn license_report(&self, sboms: Vec<Uuid>) -> Result<LicenseReport, Error> {
+        // collect the components per license, using the SPDX identifiers extracted on ingestion
+
+        let mut licenses = BTreeMap::<String, Components>::new();
+        for PackageLicense {
+            license,
+            spdx_licenses,
+            spdx_license_exceptions,
+            purl,
+        } in self.package_licenses(&sboms).await?
+        {
+            let components = licenses.entry(license).or_default();
+            components.spdx_licenses.extend(spdx_licenses);
+            components
+                .spdx_license_exceptions
+                .extend(spdx_license_exceptions);
+            components.purls.insert(purl);
+        }
+        for CpeLicense {
+            license,
+            spdx_licenses,
+            spdx_license_exceptions,
+            cpe,
+        } in self.cpe_licenses(&sboms).await?
+        {
+            let components = licenses.entry(license).or_default();
+            components.spdx_licenses.extend(spdx_licenses);
+            components
+                .spdx_license_exceptions
+                .extend(spdx_license_exceptions);
+            components.cpes.insert(cpe);
+        }
+
+        // evaluate and group by category, which requires the structure of the expression
+
+        let mut groups = BTreeMap::<LicenseCategory, Vec<LicenseReportEntry>>::new();
+        for (license, components) in licenses {
+            let evaluated = expression::evaluate(&license);
+            groups
+                .entry(evaluated.category)
+                .or_default()
+                .push(LicenseReportEntry {
+                    license,
+                    expression: evaluated.expression,
+                    spdx_licenses: components.spdx_licenses.into_iter().collect(),
+                    spdx_license_exceptions: components
+                        .spdx_license_exceptions
+                        .into_iter()
+                        .collect(),
+                    flags: evaluated.flags.into_iter().collect(),
+                    purls: components.purls.into_iter().collect(),
+                    cpes: components.cpes.into_iter().collect(),
+                });
+        }
+
+        Ok(LicenseReport {
+            sboms,
+            groups: groups
+                .into_iter()
+                .map(|(category, licenses)| LicenseReportGroup { category, licenses })
+                .collect(),
+        })
+    }
+}
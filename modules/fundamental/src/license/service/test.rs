@@ -1,8 +1,12 @@
-use crate::license::service::LicenseService;
+use crate::license::{
+    model::report::{LicenseCategory, LicenseFlag},
+    service::LicenseService,
+};
 use test_context::test_context;
 use test_log::test;
 use trustify_common::db::query::{Query, q};
 use trustify_common::model::Paginated;
+use trustify_module_ingestor::graph::product::ProductInformation;
 use trustify_test_context::TrustifyContext;
 
 #[test_context(TrustifyContext)]
@@ -14,7 +18,7 @@ async fn list_licenses(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
         .list_licenses(q("apache"), Paginated::default())
         .await?;
 
-    assert_eq!(3, results.total);
+    assert_eq!(Some(3), results.total);
     assert_eq!(3, results.items.len());
 
     assert!(results.items.iter().any(|e| {
@@ -33,11 +37,12 @@ async fn list_licenses(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
             Paginated {
                 offset: 0,
                 limit: 1,
+                ..Default::default()
             },
         )
         .await?;
 
-    assert_eq!(3, results.total);
+    assert_eq!(Some(3), results.total);
     assert_eq!(1, results.items.len());
 
     assert!(
@@ -76,6 +81,174 @@ async fn list_license_purls(ctx: &TrustifyContext) -> Result<(), anyhow::Error>
     Ok(())
 }
 
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn list_sbom_licenses(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let result = ctx.ingest_document("ubi9-9.2-755.1697625012.json").await?;
+    let sbom_id = result.id.try_as_uid().expect("must be a UUID");
+
+    let service = LicenseService::new(ctx.db.clone());
+
+    let licenses = service.fetch_sbom_licenses(sbom_id).await?;
+
+    let lgpl = licenses.iter().find(|e| e.license == "LGPLV2+");
+    assert!(lgpl.is_some_and(|lgpl| lgpl.purls > 0));
+
+    // nothing for unknown SBOMs
+
+    assert!(
+        service
+            .fetch_sbom_licenses(uuid::Uuid::now_v7())
+            .await?
+            .is_empty()
+    );
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn sbom_license_report(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let result = ctx
+        .ingest_document("spdx/quarkus-bom-3.2.11.Final-redhat-00001.json")
+        .await?;
+    let sbom_id = result.id.try_as_uid().expect("must be a UUID");
+
+    let service = LicenseService::new(ctx.db.clone());
+
+    let report = service
+        .sbom_license_report(sbom_id)
+        .await?
+        .expect("must be found");
+    assert_eq!(report.sboms, vec![sbom_id]);
+
+    let find = |license: &str| {
+        report.groups.iter().find_map(|group| {
+            group
+                .licenses
+                .iter()
+                .find(|entry| entry.license == license)
+                .map(|entry| (group.category, entry))
+        })
+    };
+
+    let (category, apache) = find("Apache-2.0").expect("must have Apache-2.0");
+    assert_eq!(category, LicenseCategory::Permissive);
+    assert!(!apache.purls.is_empty());
+    assert!(apache.flags.is_empty());
+    assert_eq!(apache.spdx_licenses, vec!["Apache-2.0".to_string()]);
+
+    // components are reported by their CPEs as well
+
+    assert!(
+        apache
+            .cpes
+            .iter()
+            .any(|cpe| cpe.starts_with("cpe:/a:redhat:quarkus:3.2"))
+    );
+
+    // the permissive choice of an OR expression
+
+    let (category, _) = find("Apache-2.0 OR EPL-1.0").expect("must have the OR expression");
+    assert_eq!(category, LicenseCategory::Permissive);
+
+    let (category, lgpl) = find("LGPL-2.1-only").expect("must have LGPL-2.1-only");
+    assert_eq!(category, LicenseCategory::WeakCopyleft);
+    assert_eq!(lgpl.spdx_licenses, vec!["LGPL-2.1-only".to_string()]);
+
+    // groups are ordered by category
+
+    let categories = report
+        .groups
+        .iter()
+        .map(|group| group.category)
+        .collect::<Vec<_>>();
+    let mut sorted = categories.clone();
+    sorted.sort();
+    assert_eq!(categories, sorted);
+
+    // exports
+
+    let csv = String::from_utf8(report.to_csv()?)?;
+    assert!(csv.starts_with("category,license,expression,flags,purl,cpe\n"));
+    assert!(csv.contains("\npermissive,Apache-2.0,Apache-2.0,,pkg:"));
+    assert!(csv.contains("\npermissive,Apache-2.0,Apache-2.0,,,cpe:/a:redhat:quarkus:3.2"));
+
+    let list = report.to_spdx_license_list();
+    assert!(list.licenses.iter().any(|license| {
+        license.license_id == "Apache-2.0"
+            && license.is_osi_approved
+            && !license.is_deprecated_license_id
+    }));
+
+    // nothing for unknown SBOMs
+
+    assert!(
+        service
+            .sbom_license_report(uuid::Uuid::now_v7())
+            .await?
+            .is_none()
+    );
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn product_version_license_report(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let result = ctx.ingest_document("ubi9-9.2-755.1697625012.json").await?;
+    let sbom_id = result.id.try_as_uid().expect("must be a UUID");
+
+    let product = ctx
+        .graph
+        .ingest_product(
+            "Universal Base Image",
+            ProductInformation {
+                vendor: Some("Red Hat".to_string()),
+                cpe: None,
+            },
+            &ctx.db,
+        )
+        .await?;
+    product
+        .ingest_product_version("9.2".to_string(), Some(sbom_id), &ctx.db)
+        .await?;
+
+    let service = LicenseService::new(ctx.db.clone());
+
+    let report = service
+        .product_version_license_report(product.product.id, "9.2")
+        .await?
+        .expect("must be found");
+    assert_eq!(report.sboms, vec![sbom_id]);
+
+    // not a valid SPDX expression
+
+    let unknown = report
+        .groups
+        .iter()
+        .find(|group| group.category == LicenseCategory::Unknown)
+        .expect("must have unknown licenses");
+    let lgpl = unknown
+        .licenses
+        .iter()
+        .find(|entry| entry.license == "LGPLV2+")
+        .expect("must have LGPLV2+");
+    assert_eq!(lgpl.flags, vec![LicenseFlag::NotSpdx]);
+    assert_eq!(lgpl.expression, None);
+
+    // unknown versions
+
+    assert!(
+        service
+            .product_version_license_report(product.product.id, "1.0")
+            .await?
+            .is_none()
+    );
+
+    Ok(())
+}
+
 #[test_context(TrustifyContext)]
 #[test(actix_web::test)]
 async fn list_spdx_licenses(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
@@ -85,7 +258,7 @@ async fn list_spdx_licenses(ctx: &TrustifyContext) -> Result<(), anyhow::Error>
         .list_spdx_licenses(q("apache"), Paginated::default())
         .await?;
 
-    assert_eq!(3, results.total);
+    assert_eq!(Some(3), results.total);
     assert_eq!(3, results.items.len());
 
     assert!(results.items.iter().any(|e| e.id == "Apache-1.0"));
@@ -98,11 +271,12 @@ async fn list_spdx_licenses(ctx: &TrustifyContext) -> Result<(), anyhow::Error>
             Paginated {
                 offset: 0,
                 limit: 1,
+                ..Default::default()
             },
         )
         .await?;
 
-    assert_eq!(3, results.total);
+    assert_eq!(Some(3), results.total);
     assert_eq!(1, results.items.len());
 
     assert!(results.items.iter().any(|e| e.id == "Apache-1.0"));
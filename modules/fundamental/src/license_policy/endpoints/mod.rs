@@ -0,0 +1,195 @@
+#[cfg(test)]
+mod test;
+
+use crate::license_policy::{
+    model::{LicensePolicy, NewLicensePolicy, SbomLicenseCompliance},
+    service::LicensePolicyService,
+};
+use actix_web::{HttpResponse, Responder, delete, get, post, put, web};
+use trustify_auth::{
+    CreateMetadata, DeleteMetadata, ReadMetadata, ReadSbom, UpdateMetadata, authorizer::Require,
+};
+use trustify_common::{
+    db::{Database, query::Query},
+    model::{Paginated, PaginatedResults},
+};
+use trustify_module_audit::recorder::{Action, Audit, Event, TargetKind};
+use uuid::Uuid;
+
+pub fn configure(config: &mut utoipa_actix_web::service_config::ServiceConfig, db: Database) {
+    let service = LicensePolicyService::new(db);
+    config
+        .app_data(web::Data::new(service))
+        .service(all)
+        .service(create)
+        .service(get)
+        .service(update)
+        .service(delete)
+        .service(evaluate_sbom);
+}
+
+#[utoipa::path(
+    tag = "license-policy",
+    operation_id = "listLicensePolicies",
+    params(
+        Query,
+        Paginated,
+    ),
+    responses(
+        (status = 200, description = "Matching license policies", body = PaginatedResults<LicensePolicy>),
+    ),
+)]
+#[get("/v2/license-policy")]
+/// List license policies
+pub async fn all(
+    state: web::Data<LicensePolicyService>,
+    web::Query(search): web::Query<Query>,
+    web::Query(paginated): web::Query<Paginated>,
+    _: Require<ReadMetadata>,
+) -> actix_web::Result<impl Responder> {
+    Ok(HttpResponse::Ok().json(state.fetch_policies(search, paginated).await?))
+}
+
+#[utoipa::path(
+    tag = "license-policy",
+    operation_id = "createLicensePolicy",
+    request_body = NewLicensePolicy,
+    responses(
+        (status = 201, description = "Created the license policy", body = LicensePolicy),
+        (status = 400, description = "The content of the policy is invalid"),
+        (status = 409, description = "A policy with the same name already exists"),
+    ),
+)]
+#[post("/v2/license-policy")]
+/// Create a license policy
+pub async fn create(
+    state: web::Data<LicensePolicyService>,
+    web::Json(policy): web::Json<NewLicensePolicy>,
+    audit: Audit,
+    _: Require<CreateMetadata>,
+) -> actix_web::Result<impl Responder> {
+    let created = state.create_policy(policy).await?;
+    audit
+        .record(Event::new(Action::Create, TargetKind::LicensePolicy, created.id).after(&created))
+        .await;
+    Ok(HttpResponse::Created().json(created))
+}
+
+#[utoipa::path(
+    tag = "license-policy",
+    operation_id = "getLicensePolicy",
+    params(
+        ("id", Path, description = "Opaque ID of the license policy")
+    ),
+    responses(
+        (status = 200, description = "The license policy", body = LicensePolicy),
+        (status = 404, description = "The license policy could not be found"),
+    ),
+)]
+#[get("/v2/license-policy/{id}")]
+/// Get a license policy
+pub async fn get(
+    state: web::Data<LicensePolicyService>,
+    id: web::Path<Uuid>,
+    _: Require<ReadMetadata>,
+) -> actix_web::Result<impl Responder> {
+    Ok(match state.fetch_policy(*id).await? {
+        Some(policy) => HttpResponse::Ok().json(policy),
+        None => HttpResponse::NotFound().finish(),
+    })
+}
+
+#[utoipa::path(
+    tag = "license-policy",
+    operation_id = "updateLicensePolicy",
+    request_body = NewLicensePolicy,
+    params(
+        ("id", Path, description = "Opaque ID of the license policy")
+    ),
+    responses(
+        (status = 200, description = "Updated the license policy", body = LicensePolicy),
+        (status = 400, description = "The content of the policy is invalid"),
+        (status = 404, description = "The license policy could not be found"),
+        (status = 409, description = "A policy with the same name already exists"),
+    ),
+)]
+#[put("/v2/license-policy/{id}")]
+/// Replace the content of a license policy
+pub async fn update(
+    state: web::Data<LicensePolicyService>,
+    id: web::Path<Uuid>,
+    web::Json(policy): web::Json<NewLicensePolicy>,
+    audit: Audit,
+    _: Require<UpdateMetadata>,
+) -> actix_web::Result<impl Responder> {
+    let before = state.fetch_policy(*id).await?;
+    Ok(match state.update_policy(*id, policy).await? {
+        Some(updated) => {
+            audit
+                .record(
+                    Event::new(Action::Update, TargetKind::LicensePolicy, *id)
+                        .before(&before)
+                        .after(&updated),
+                )
+                .await;
+            HttpResponse::Ok().json(updated)
+        }
+        None => HttpResponse::NotFound().finish(),
+    })
+}
+
+#[utoipa::path(
+    tag = "license-policy",
+    operation_id = "deleteLicensePolicy",
+    params(
+        ("id", Path, description = "Opaque ID of the license policy")
+    ),
+    responses(
+        (status = 204, description = "Deleted the license policy"),
+        (status = 404, description = "The license policy could not be found"),
+    ),
+)]
+#[delete("/v2/license-policy/{id}")]
+/// Delete a license policy
+pub async fn delete(
+    state: web::Data<LicensePolicyService>,
+    id: web::Path<Uuid>,
+    audit: Audit,
+    _: Require<DeleteMetadata>,
+) -> actix_web::Result<impl Responder> {
+    Ok(match state.delete_policy(*id).await? {
+        true => {
+            audit
+                .record(Event::new(Action::Delete, TargetKind::LicensePolicy, *id))
+                .await;
+            HttpResponse::NoContent().finish()
+        }
+        false => HttpResponse::NotFound().finish(),
+    })
+}
+
+#[utoipa::path(
+    tag = "license-policy",
+    operation_id = "evaluateSbomLicensePolicy",
+    params(
+        ("id", Path, description = "Opaque ID of the license policy"),
+        ("sbom_id", Path, description = "ID of the SBOM"),
+    ),
+    responses(
+        (status = 200, description = "The verdicts of the policy for the packages of the SBOM", body = SbomLicenseCompliance),
+        (status = 404, description = "The license policy or the SBOM could not be found"),
+    ),
+)]
+#[get("/v2/license-policy/{id}/sbom/{sbom_id}")]
+/// Evaluate the licenses of an SBOM against a license policy
+pub async fn evaluate_sbom(
+    state: web::Data<LicensePolicyService>,
+    path: web::Path<(Uuid, Uuid)>,
+    _: Require<ReadSbom>,
+) -> actix_web::Result<impl Responder> {
+    let (id, sbom_id) = path.into_inner();
+    Ok(match state.evaluate_sbom(id, sbom_id).await? {
+        Some(compliance) => HttpResponse::Ok().json(compliance),
+        None => HttpResponse::NotFound().finish(),
+    })
+}
@@ -0,0 +1,130 @@
+use crate::test::caller;
+use actix_http::StatusCode;
+use actix_web::test::TestRequest;
+use serde_json::{Value, json};
+use test_context::test_context;
+use test_log::test;
+use trustify_test_context::{TrustifyContext, call::CallService};
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn create_update_delete(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let app = caller(ctx).await?;
+
+    let request = TestRequest::post()
+        .uri("/api/v2/license-policy")
+        .set_json(json!({
+            "name": "default",
+            "allowed": ["MIT", "Apache-2.0"],
+            "denied": ["GPL-3.0-only"],
+        }))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let created: Value = actix_web::test::read_body_json(response).await;
+
+    assert_eq!(created["allowed"], json!(["Apache-2.0", "MIT"]));
+    assert_eq!(created["needs_review"], json!([]));
+    assert_eq!(created["fallback"], json!("needs_review"));
+
+    let id = created["id"].as_str().unwrap_or_default();
+
+    // duplicate name
+
+    let request = TestRequest::post()
+        .uri("/api/v2/license-policy")
+        .set_json(json!({ "name": "default" }))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    // invalid content
+
+    let request = TestRequest::post()
+        .uri("/api/v2/license-policy")
+        .set_json(json!({ "name": "invalid", "denied": ["MIT AND"] }))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // update
+
+    let request = TestRequest::put()
+        .uri(&format!("/api/v2/license-policy/{id}"))
+        .set_json(json!({
+            "name": "default",
+            "allowed": ["MIT"],
+            "fallback": "denied",
+        }))
+        .to_request();
+    let response: Value = app.call_and_read_body_json(request).await;
+    assert_eq!(response["allowed"], json!(["MIT"]));
+    assert_eq!(response["fallback"], json!("denied"));
+
+    let request = TestRequest::get()
+        .uri("/api/v2/license-policy?q=default")
+        .to_request();
+    let response: Value = app.call_and_read_body_json(request).await;
+    assert_eq!(response["total"], json!(1));
+    assert_eq!(response["items"][0]["id"], created["id"]);
+
+    // delete
+
+    let request = TestRequest::delete()
+        .uri(&format!("/api/v2/license-policy/{id}"))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/license-policy/{id}"))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn evaluate_sbom(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let app = caller(ctx).await?;
+    let result = ctx
+        .ingest_document("spdx/quarkus-bom-3.2.11.Final-redhat-00001.json")
+        .await?;
+    let sbom_id = result.id.try_as_uid().expect("must be a UUID");
+
+    let request = TestRequest::post()
+        .uri("/api/v2/license-policy")
+        .set_json(json!({
+            "name": "permissive",
+            "allowed": ["Apache-2.0", "MIT", "BSD-3-Clause"],
+            "denied": ["EPL-1.0"],
+            "fallback": "denied",
+        }))
+        .to_request();
+    let created: Value = app.call_and_read_body_json(request).await;
+    let id = created["id"].as_str().unwrap_or_default();
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/license-policy/{id}/sbom/{sbom_id}"))
+        .to_request();
+    let response: Value = app.call_and_read_body_json(request).await;
+
+    assert_eq!(response["sbom"], json!(sbom_id));
+    assert_eq!(response["verdict"], json!("denied"));
+
+    let licenses = response["packages"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .flat_map(|package| package["licenses"].as_array().into_iter().flatten())
+        .collect::<Vec<_>>();
+    assert!(licenses.iter().any(|license| {
+        license["license"] == json!("Apache-2.0 OR EPL-1.0")
+            && license["verdict"] == json!("allowed")
+            && license["resolved"] == json!("Apache-2.0")
+    }));
+
+    Ok(())
+}
@@ -0,0 +1,130 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use trustify_entity::license_policy::{self, Verdict};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// A license policy.
+///
+/// Licenses are looked up in the lists of the policy, using their normalized SPDX form. If a
+/// license is found in more than one list, `denied` takes precedence over `needs_review`, which
+/// takes precedence over `allowed`.
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema, PartialEq, Eq)]
+pub struct LicensePolicy {
+    /// The opaque UUID of the policy.
+    pub id: Uuid,
+
+    #[serde(flatten)]
+    pub policy: NewLicensePolicy,
+
+    /// The date (in RFC3339 format) the policy was created.
+    #[serde(with = "time::serde::rfc3339")]
+    pub created: OffsetDateTime,
+
+    /// The date (in RFC3339 format) the policy was last modified.
+    #[serde(with = "time::serde::rfc3339")]
+    pub modified: OffsetDateTime,
+}
+
+impl From<license_policy::Model> for LicensePolicy {
+    fn from(value: license_policy::Model) -> Self {
+        Self {
+            id: value.id,
+            policy: NewLicensePolicy {
+                name: value.name,
+                description: value.description,
+                allowed: value.allowed,
+                denied: value.denied,
+                needs_review: value.needs_review,
+                exceptions: value.exceptions,
+                fallback: value.fallback,
+            },
+            created: value.created,
+            modified: value.modified,
+        }
+    }
+}
+
+/// The user provided content of a license policy.
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema, PartialEq, Eq)]
+pub struct NewLicensePolicy {
+    /// The unique name of the policy.
+    pub name: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Licenses which are allowed, like `MIT` or `GPL-2.0-only WITH Classpath-exception-2.0`.
+    #[serde(default)]
+    pub allowed: Vec<String>,
+
+    /// Licenses which are denied.
+    #[serde(default)]
+    pub denied: Vec<String>,
+
+    /// Licenses which require a review.
+    #[serde(default)]
+    pub needs_review: Vec<String>,
+
+    /// SPDX exception identifiers which make any license they are applied to (using `WITH`)
+    /// allowed, unless the combination is listed explicitly.
+    #[serde(default)]
+    pub exceptions: Vec<String>,
+
+    /// The verdict for licenses which are not listed.
+    #[serde(default = "default_fallback")]
+    pub fallback: Verdict,
+}
+
+fn default_fallback() -> Verdict {
+    Verdict::NeedsReview
+}
+
+/// The result of evaluating the licenses of an SBOM against a policy.
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema, PartialEq, Eq)]
+pub struct SbomLicenseCompliance {
+    /// The ID of the policy.
+    pub policy: Uuid,
+
+    /// The ID of the SBOM.
+    pub sbom: Uuid,
+
+    /// The least acceptable verdict of all packages, `allowed` if there are none.
+    pub verdict: Verdict,
+
+    /// The packages of the SBOM declaring a license, least acceptable first.
+    pub packages: Vec<PackageVerdict>,
+}
+
+impl SbomLicenseCompliance {
+    /// Check if all packages of the SBOM are allowed by the policy.
+    pub fn is_compliant(&self) -> bool {
+        self.verdict == Verdict::Allowed
+    }
+}
+
+/// The verdict of a policy for a package.
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema, PartialEq, Eq)]
+pub struct PackageVerdict {
+    /// The PURL of the package.
+    pub purl: String,
+
+    /// The least acceptable verdict of all licenses declared by the package.
+    pub verdict: Verdict,
+
+    pub licenses: Vec<LicenseVerdict>,
+}
+
+/// The verdict of a policy for a license.
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema, PartialEq, Eq)]
+pub struct LicenseVerdict {
+    /// The license, as declared by the package.
+    pub license: String,
+
+    pub verdict: Verdict,
+
+    /// The licenses chosen from the expression, resolving `OR` expressions to their most
+    /// acceptable branch. Missing if the license is not a valid SPDX expression.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolved: Option<String>,
+}
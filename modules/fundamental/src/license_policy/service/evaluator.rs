@@ -0,0 +1,261 @@
+use spdx::{
+    Expression, LicenseItem, LicenseReq, ParseMode,
+    expression::{ExprNode, Operator},
+};
+use std::collections::HashSet;
+use trustify_entity::license_policy::{self, Verdict};
+
+/// Evaluates license texts against the rules of a policy
+#[derive(Clone, Debug)]
+pub struct Evaluator {
+    allowed: HashSet<String>,
+    denied: HashSet<String>,
+    needs_review: HashSet<String>,
+    exceptions: HashSet<String>,
+    fallback: Verdict,
+}
+
+/// The verdict for a license text
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Resolution {
+    pub verdict: Verdict,
+    /// The chosen licenses, `None` if the text is not a valid SPDX expression
+    pub resolved: Option<String>,
+}
+
+impl From<&license_policy::Model> for Evaluator {
+    fn from(value: &license_policy::Model) -> Self {
+        let set = |entries: &[String]| entries.iter().cloned().collect();
+        Self {
+            allowed: set(&value.allowed),
+            denied: set(&value.denied),
+            needs_review: set(&value.needs_review),
+            exceptions: set(&value.exceptions),
+            fallback: value.fallback,
+        }
+    }
+}
+
+impl Evaluator {
+    /// Evaluate a license text.
+    ///
+    /// For `OR` expressions, the most acceptable branch is chosen, preferring the left one. For
+    /// `AND` expressions, the least acceptable verdict of both sides is used. Texts which are
+    /// not valid SPDX expressions always need a review.
+    pub fn evaluate(&self, text: &str) -> Resolution {
+        let Ok(expression) = Expression::parse_mode(text.trim(), ParseMode::LAX) else {
+            return Resolution {
+                verdict: Verdict::NeedsReview,
+                resolved: None,
+            };
+        };
+
+        // the expression is stored in postfix notation
+
+        let mut stack = Vec::<(Verdict, String)>::new();
+        for node in expression.iter() {
+            match node {
+                ExprNode::Req(req) => {
+                    stack.push((self.requirement(&req.req), req.req.to_string()));
+                }
+                ExprNode::Op(op) => {
+                    let (Some(rhs), Some(lhs)) = (stack.pop(), stack.pop()) else {
+                        // a parsed expression is always well-formed
+                        return Resolution {
+                            verdict: Verdict::NeedsReview,
+                            resolved: None,
+                        };
+                    };
+                    stack.push(match op {
+                        Operator::And => (lhs.0.max(rhs.0), format!("{} AND {}", lhs.1, rhs.1)),
+                        Operator::Or if rhs.0 < lhs.0 => rhs,
+                        Operator::Or => lhs,
+                    });
+                }
+            }
+        }
+
+        match stack.pop() {
+            Some((verdict, resolved)) => Resolution {
+                verdict,
+                resolved: Some(resolved),
+            },
+            None => Resolution {
+                verdict: Verdict::NeedsReview,
+                resolved: None,
+            },
+        }
+    }
+
+    /// The verdict for a single license requirement
+    fn requirement(&self, req: &LicenseReq) -> Verdict {
+        if let Some(exception) = &req.exception {
+            if let Some(verdict) = self.lookup(&req.to_string()) {
+                return verdict;
+            }
+            if self.exceptions.contains(exception.name) {
+                return Verdict::Allowed;
+            }
+        }
+
+        if let Some(verdict) = self.lookup(&req.license.to_string()) {
+            return verdict;
+        }
+
+        // `GPL-2.0+` is covered by rules for `GPL-2.0` too
+        if let LicenseItem::Spdx {
+            id, or_later: true, ..
+        } = &req.license
+        {
+            if let Some(verdict) = self.lookup(id.name) {
+                return verdict;
+            }
+        }
+
+        self.fallback
+    }
+
+    fn lookup(&self, key: &str) -> Option<Verdict> {
+        if self.denied.contains(key) {
+            Some(Verdict::Denied)
+        } else if self.needs_review.contains(key) {
+            Some(Verdict::NeedsReview)
+        } else if self.allowed.contains(key) {
+            Some(Verdict::Allowed)
+        } else {
+            None
+        }
+    }
+}
+
+/// Normalize a policy entry, which must be a single SPDX license requirement
+pub fn normalize_license(entry: &str) -> Result<String, String> {
+    let expression = Expression::parse_mode(entry.trim(), ParseMode::LAX)
+        .map_err(|err| format!("Invalid license '{entry}': {err}"))?;
+
+    let mut nodes = expression.iter();
+    match (nodes.next(), nodes.next()) {
+        (Some(ExprNode::Req(req)), None) => Ok(req.req.to_string()),
+        _ => Err(format!(
+            "Invalid license '{entry}': must be a single license, optionally with an exception"
+        )),
+    }
+}
+
+/// Normalize a policy exception, which must be an SPDX exception identifier
+pub fn normalize_exception(entry: &str) -> Result<String, String> {
+    spdx::exception_id(entry.trim())
+        .map(|id| id.name.to_string())
+        .ok_or_else(|| format!("Unknown SPDX exception: '{entry}'"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use test_log::test;
+
+    fn evaluator() -> Evaluator {
+        let set = |entries: &[&str]| entries.iter().map(ToString::to_string).collect();
+        Evaluator {
+            allowed: set(&["MIT", "Apache-2.0", "LGPL-2.1-only"]),
+            denied: set(&["GPL-3.0-only", "AGPL-3.0-only"]),
+            needs_review: set(&["GPL-2.0-only", "EPL-2.0"]),
+            exceptions: set(&["Classpath-exception-2.0"]),
+            fallback: Verdict::Denied,
+        }
+    }
+
+    fn assert_evaluate(text: &str, verdict: Verdict, resolved: Option<&str>) {
+        assert_eq!(
+            evaluator().evaluate(text),
+            Resolution {
+                verdict,
+                resolved: resolved.map(ToString::to_string),
+            },
+            "{text}"
+        );
+    }
+
+    #[test]
+    fn single() {
+        assert_evaluate("MIT", Verdict::Allowed, Some("MIT"));
+        assert_evaluate("GPL-3.0-only", Verdict::Denied, Some("GPL-3.0-only"));
+        assert_evaluate("EPL-2.0", Verdict::NeedsReview, Some("EPL-2.0"));
+        assert_evaluate("BSD-3-Clause", Verdict::Denied, Some("BSD-3-Clause"));
+        assert_evaluate("not a license", Verdict::NeedsReview, None);
+    }
+
+    #[test]
+    fn operators() {
+        assert_evaluate("GPL-3.0-only OR MIT", Verdict::Allowed, Some("MIT"));
+        assert_evaluate(
+            "EPL-2.0 OR GPL-3.0-only",
+            Verdict::NeedsReview,
+            Some("EPL-2.0"),
+        );
+        assert_evaluate(
+            "MIT AND EPL-2.0",
+            Verdict::NeedsReview,
+            Some("MIT AND EPL-2.0"),
+        );
+        assert_evaluate(
+            "MIT AND (GPL-3.0-only OR Apache-2.0)",
+            Verdict::Allowed,
+            Some("MIT AND Apache-2.0"),
+        );
+        assert_evaluate(
+            "(MIT OR GPL-3.0-only) AND (AGPL-3.0-only OR EPL-2.0)",
+            Verdict::NeedsReview,
+            Some("MIT AND EPL-2.0"),
+        );
+    }
+
+    #[test]
+    fn exceptions() {
+        // accepted exception
+        assert_evaluate(
+            "GPL-2.0-only WITH Classpath-exception-2.0",
+            Verdict::Allowed,
+            Some("GPL-2.0-only WITH Classpath-exception-2.0"),
+        );
+        // other exceptions fall back to the license
+        assert_evaluate(
+            "GPL-2.0-only WITH GCC-exception-2.0",
+            Verdict::NeedsReview,
+            Some("GPL-2.0-only WITH GCC-exception-2.0"),
+        );
+
+        // explicitly listed combinations win
+        let mut evaluator = evaluator();
+        evaluator
+            .denied
+            .insert("GPL-3.0-only WITH Classpath-exception-2.0".to_string());
+        assert_eq!(
+            evaluator
+                .evaluate("GPL-3.0-only WITH Classpath-exception-2.0")
+                .verdict,
+            Verdict::Denied
+        );
+    }
+
+    #[test]
+    fn normalize() {
+        assert_eq!(normalize_license(" MIT "), Ok("MIT".to_string()));
+        assert_eq!(
+            normalize_license("GPL-2.0-only WITH Classpath-exception-2.0"),
+            Ok("GPL-2.0-only WITH Classpath-exception-2.0".to_string())
+        );
+        assert_eq!(
+            normalize_license("LicenseRef-Proprietary"),
+            Ok("LicenseRef-Proprietary".to_string())
+        );
+        assert!(normalize_license("MIT OR Apache-2.0").is_err());
+        assert!(normalize_license("not a license").is_err());
+
+        assert_eq!(
+            normalize_exception("Classpath-exception-2.0"),
+            Ok("Classpath-exception-2.0".to_string())
+        );
+        assert!(normalize_exception("MIT").is_err());
+    }
+}
@@ -0,0 +1,282 @@
+pub mod evaluator;
+
+use crate::{
+    Error,
+    license::service::{LicenseService, PackageLicense},
+    license_policy::{
+        model::{
+            LicensePolicy, LicenseVerdict, NewLicensePolicy, PackageVerdict, SbomLicenseCompliance,
+        },
+        service::evaluator::{Evaluator, normalize_exception, normalize_license},
+    },
+};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter,
+    QueryOrder, TransactionTrait,
+};
+use std::collections::BTreeMap;
+use time::OffsetDateTime;
+use trustify_common::{
+    db::{
+        Database,
+        limiter::LimiterTrait,
+        query::{Filtering, Query},
+    },
+    model::{Paginated, PaginatedResults},
+};
+use trustify_entity::{
+    license_policy::{self, Verdict},
+    sbom,
+};
+use uuid::Uuid;
+
+pub struct LicensePolicyService {
+    db: Database,
+}
+
+impl LicensePolicyService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    pub async fn fetch_policies(
+        &self,
+        search: Query,
+        paginated: Paginated,
+    ) -> Result<PaginatedResults<LicensePolicy>, Error> {
+        let limiter = license_policy::Entity::find()
+            .filtering(search)?
+            .order_by_asc(license_policy::Column::Name)
+            .limiting(&self.db, &paginated);
+
+        let total = limiter.total().await?;
+
+        Ok(PaginatedResults {
+            total,
+            items: limiter
+                .fetch()
+                .await?
+                .into_iter()
+                .map(LicensePolicy::from)
+                .collect(),
+            next: None,
+        })
+    }
+
+    pub async fn fetch_policy(&self, id: Uuid) -> Result<Option<LicensePolicy>, Error> {
+        Ok(license_policy::Entity::find_by_id(id)
+            .one(&self.db)
+            .await?
+            .map(LicensePolicy::from))
+    }
+
+    /// Create a new policy.
+    ///
+    /// Fails if the content of the policy is invalid, or if a policy with the same name exists.
+    pub async fn create_policy(&self, policy: NewLicensePolicy) -> Result<LicensePolicy, Error> {
+        let policy = normalize(policy)?;
+
+        let tx = self.db.begin().await?;
+
+        ensure_unique_name(&policy.name, None, &tx).await?;
+
+        let now = OffsetDateTime::now_utc();
+        let model = license_policy::ActiveModel {
+            id: Set(Uuid::now_v7()),
+            name: Set(policy.name),
+            description: Set(policy.description),
+            allowed: Set(policy.allowed),
+            denied: Set(policy.denied),
+            needs_review: Set(policy.needs_review),
+            exceptions: Set(policy.exceptions),
+            fallback: Set(policy.fallback),
+            created: Set(now),
+            modified: Set(now),
+        }
+        .insert(&tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(model.into())
+    }
+
+    /// Replace the content of a policy.
+    ///
+    /// Returns `Ok(None)` if the policy could not be found.
+    pub async fn update_policy(
+        &self,
+        id: Uuid,
+        policy: NewLicensePolicy,
+    ) -> Result<Option<LicensePolicy>, Error> {
+        let policy = normalize(policy)?;
+
+        let tx = self.db.begin().await?;
+
+        if license_policy::Entity::find_by_id(id)
+            .one(&tx)
+            .await?
+            .is_none()
+        {
+            return Ok(None);
+        }
+
+        ensure_unique_name(&policy.name, Some(id), &tx).await?;
+
+        let model = license_policy::ActiveModel {
+            id: Set(id),
+            name: Set(policy.name),
+            description: Set(policy.description),
+            allowed: Set(policy.allowed),
+            denied: Set(policy.denied),
+            needs_review: Set(policy.needs_review),
+            exceptions: Set(policy.exceptions),
+            fallback: Set(policy.fallback),
+            created: Default::default(),
+            modified: Set(OffsetDateTime::now_utc()),
+        }
+        .update(&tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Some(model.into()))
+    }
+
+    /// Delete a policy.
+    ///
+    /// Returns `true` if the policy was found and deleted.
+    pub async fn delete_policy(&self, id: Uuid) -> Result<bool, Error> {
+        let result = license_policy::Entity::delete_by_id(id)
+            .exec(&self.db)
+            .await?;
+
+        Ok(result.rows_affected > 0)
+    }
+
+    /// Evaluate the licenses of all packages of an SBOM against a policy.
+    ///
+    /// Returns `Ok(None)` if either the policy or the SBOM could not be found.
+    pub async fn evaluate_sbom(
+        &self,
+        policy_id: Uuid,
+        sbom_id: Uuid,
+    ) -> Result<Option<SbomLicenseCompliance>, Error> {
+        let Some(policy) = license_policy::Entity::find_by_id(policy_id)
+            .one(&self.db)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        if sbom::Entity::find_by_id(sbom_id)
+            .one(&self.db)
+            .await?
+            .is_none()
+        {
+            return Ok(None);
+        }
+
+        let evaluator = Evaluator::from(&policy);
+
+        let mut packages = BTreeMap::<String, Vec<LicenseVerdict>>::new();
+        for PackageLicense { license, purl, .. } in LicenseService::new(self.db.clone())
+            .package_licenses(&[sbom_id])
+            .await?
+        {
+            let resolution = evaluator.evaluate(&license);
+            packages.entry(purl).or_default().push(LicenseVerdict {
+                license,
+                verdict: resolution.verdict,
+                resolved: resolution.resolved,
+            });
+        }
+
+        let mut packages = packages
+            .into_iter()
+            .map(|(purl, mut licenses)| {
+                licenses.sort_by(|a, b| a.license.cmp(&b.license));
+                PackageVerdict {
+                    purl,
+                    verdict: licenses
+                        .iter()
+                        .map(|license| license.verdict)
+                        .max()
+                        .unwrap_or(Verdict::Allowed),
+                    licenses,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        // least acceptable first, keeping the order of the PURLs otherwise
+        packages.sort_by(|a, b| b.verdict.cmp(&a.verdict));
+
+        Ok(Some(SbomLicenseCompliance {
+            policy: policy_id,
+            sbom: sbom_id,
+            verdict: packages
+                .first()
+                .map(|package| package.verdict)
+                .unwrap_or(Verdict::Allowed),
+            packages,
+        }))
+    }
+}
+
+/// Validate and normalize the entries of a policy
+fn normalize(mut policy: NewLicensePolicy) -> Result<NewLicensePolicy, Error> {
+    policy.name = policy.name.trim().to_string();
+    if policy.name.is_empty() {
+        return Err(Error::BadRequest(
+            "The name of a policy must not be empty".into(),
+        ));
+    }
+
+    let licenses = |entries: Vec<String>| {
+        let mut result = entries
+            .iter()
+            .map(|entry| normalize_license(entry))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(Error::BadRequest)?;
+        result.sort();
+        result.dedup();
+        Ok::<_, Error>(result)
+    };
+
+    policy.allowed = licenses(policy.allowed)?;
+    policy.denied = licenses(policy.denied)?;
+    policy.needs_review = licenses(policy.needs_review)?;
+
+    let mut exceptions = policy
+        .exceptions
+        .iter()
+        .map(|entry| normalize_exception(entry))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(Error::BadRequest)?;
+    exceptions.sort();
+    exceptions.dedup();
+    policy.exceptions = exceptions;
+
+    Ok(policy)
+}
+
+async fn ensure_unique_name(
+    name: &str,
+    id: Option<Uuid>,
+    connection: &impl ConnectionTrait,
+) -> Result<(), Error> {
+    let mut query = license_policy::Entity::find().filter(license_policy::Column::Name.eq(name));
+    if let Some(id) = id {
+        query = query.filter(license_policy::Column::Id.ne(id));
+    }
+
+    match query.one(connection).await? {
+        Some(_) => Err(Error::Conflict(format!(
+            "A license policy named '{name}' already exists"
+        ))),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,161 @@
+use crate::{
+    Error,
+    license_policy::{model::NewLicensePolicy, service::LicensePolicyService},
+};
+use test_context::test_context;
+use test_log::test;
+use trustify_common::db::query::q;
+use trustify_entity::license_policy::Verdict;
+use trustify_test_context::TrustifyContext;
+
+fn policy(name: &str) -> NewLicensePolicy {
+    NewLicensePolicy {
+        name: name.to_string(),
+        description: None,
+        allowed: vec![
+            "Apache-2.0".to_string(),
+            "MIT".to_string(),
+            "BSD-3-Clause".to_string(),
+        ],
+        denied: vec!["GPL-3.0-only".to_string()],
+        needs_review: vec!["LGPL-2.1-only".to_string(), "EPL-2.0".to_string()],
+        exceptions: vec!["Classpath-exception-2.0".to_string()],
+        fallback: Verdict::NeedsReview,
+    }
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn crud(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let service = LicensePolicyService::new(ctx.db.clone());
+
+    let created = service.create_policy(policy("default")).await?;
+    // entries are normalized and sorted
+    assert_eq!(
+        created.policy.allowed,
+        vec!["Apache-2.0", "BSD-3-Clause", "MIT"]
+    );
+
+    assert_eq!(
+        service.fetch_policy(created.id).await?,
+        Some(created.clone())
+    );
+
+    // names must be unique
+
+    let result = service.create_policy(policy("default")).await;
+    assert!(matches!(result, Err(Error::Conflict(_))), "{result:?}");
+
+    // content must be valid
+
+    let mut invalid = policy("invalid");
+    invalid.allowed.push("MIT OR Apache-2.0".to_string());
+    let result = service.create_policy(invalid).await;
+    assert!(matches!(result, Err(Error::BadRequest(_))), "{result:?}");
+
+    let mut invalid = policy("invalid");
+    invalid.exceptions.push("MIT".to_string());
+    let result = service.create_policy(invalid).await;
+    assert!(matches!(result, Err(Error::BadRequest(_))), "{result:?}");
+
+    // update
+
+    let mut update = policy("strict");
+    update.fallback = Verdict::Denied;
+    let updated = service
+        .update_policy(created.id, update)
+        .await?
+        .expect("must be found");
+    assert_eq!(updated.policy.name, "strict");
+    assert_eq!(updated.policy.fallback, Verdict::Denied);
+    assert_eq!(updated.created, created.created);
+
+    assert!(
+        service
+            .update_policy(uuid::Uuid::now_v7(), policy("other"))
+            .await?
+            .is_none()
+    );
+
+    // search
+
+    service.create_policy(policy("lenient")).await?;
+    let result = service
+        .fetch_policies(q("strict"), Default::default())
+        .await?;
+    assert_eq!(result.total, Some(1));
+    assert_eq!(result.items[0].id, created.id);
+
+    // delete
+
+    assert!(service.delete_policy(created.id).await?);
+    assert!(!service.delete_policy(created.id).await?);
+    assert_eq!(service.fetch_policy(created.id).await?, None);
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn evaluate_sbom(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let result = ctx
+        .ingest_document("spdx/quarkus-bom-3.2.11.Final-redhat-00001.json")
+        .await?;
+    let sbom_id = result.id.try_as_uid().expect("must be a UUID");
+
+    let service = LicensePolicyService::new(ctx.db.clone());
+    let policy = service.create_policy(policy("default")).await?;
+
+    let compliance = service
+        .evaluate_sbom(policy.id, sbom_id)
+        .await?
+        .expect("must be found");
+
+    assert_eq!(compliance.sbom, sbom_id);
+    assert!(!compliance.is_compliant());
+    assert_eq!(compliance.verdict, Verdict::NeedsReview);
+
+    // the compliant branch of an OR expression is chosen
+
+    let license = compliance
+        .packages
+        .iter()
+        .flat_map(|package| &package.licenses)
+        .find(|license| license.license == "Apache-2.0 OR EPL-1.0")
+        .expect("must have the OR expression");
+    assert_eq!(license.verdict, Verdict::Allowed);
+    assert_eq!(license.resolved.as_deref(), Some("Apache-2.0"));
+
+    let license = compliance
+        .packages
+        .iter()
+        .flat_map(|package| &package.licenses)
+        .find(|license| license.license == "LGPL-2.1-only")
+        .expect("must have LGPL-2.1-only");
+    assert_eq!(license.verdict, Verdict::NeedsReview);
+
+    // the least acceptable packages come first
+
+    assert_eq!(compliance.packages[0].verdict, Verdict::NeedsReview);
+    assert_eq!(
+        compliance.packages.last().map(|package| package.verdict),
+        Some(Verdict::Allowed)
+    );
+
+    // unknown policies and SBOMs
+
+    assert!(
+        service
+            .evaluate_sbom(uuid::Uuid::now_v7(), sbom_id)
+            .await?
+            .is_none()
+    );
+    assert!(
+        service
+            .evaluate_sbom(policy.id, uuid::Uuid::now_v7())
+            .await?
+            .is_none()
+    );
+
+    Ok(())
+}
@@ -1,16 +1,27 @@
 #[cfg(test)]
 mod test;
 
-use crate::organization::{
-    model::{OrganizationDetails, OrganizationSummary},
-    service::OrganizationService,
+use crate::{
+    Error,
+    organization::{
+        model::{
+            NewOrganization, OrganizationDetails, OrganizationHead, OrganizationNameRule,
+            OrganizationSummary,
+        },
+        service::OrganizationService,
+    },
+};
+use actix_web::{HttpResponse, Responder, get, post, put, web};
+use sea_orm::TransactionTrait;
+use trustify_auth::{
+    CreateMetadata, DeleteMetadata, ReadMetadata, UpdateMetadata, all, authorizer::Require,
 };
-use actix_web::{HttpResponse, Responder, get, web};
-use trustify_auth::{ReadMetadata, authorizer::Require};
 use trustify_common::{
     db::{Database, query::Query},
     model::Paginated,
 };
+use trustify_module_audit::recorder::{Action, Audit, Event, TargetKind};
+use trustify_module_ingestor::graph::organization::invalidate_name_rules;
 use uuid::Uuid;
 
 pub fn configure(config: &mut utoipa_actix_web::service_config::ServiceConfig, db: Database) {
@@ -19,7 +30,13 @@ pub fn configure(config: &mut utoipa_actix_web::service_config::ServiceConfig, d
         .app_data(web::Data::new(db))
         .app_data(web::Data::new(service))
         .service(all)
-        .service(get);
+        .service(create)
+        .service(get)
+        .service(update)
+        .service(set_aliases)
+        .service(merge)
+        .service(get_name_rules)
+        .service(set_name_rules);
 }
 
 #[utoipa::path(
@@ -76,3 +93,219 @@ pub async fn get(
         Ok(HttpResponse::NotFound().finish())
     }
 }
+
+#[utoipa::path(
+    tag = "organization",
+    operation_id = "createOrganization",
+    request_body = NewOrganization,
+    responses(
+        (status = 201, description = "Created the organization", body = OrganizationHead),
+        (status = 400, description = "The content of the organization is invalid"),
+        (status = 409, description = "An organization with the same name already exists"),
+    ),
+)]
+#[post("/v2/organization")]
+/// Create an organization
+pub async fn create(
+    state: web::Data<OrganizationService>,
+    db: web::Data<Database>,
+    web::Json(organization): web::Json<NewOrganization>,
+    audit: Audit,
+    _: Require<CreateMetadata>,
+) -> Result<impl Responder, Error> {
+    let tx = db.begin().await?;
+    let created = state.create_organization(organization, &tx).await?;
+    tx.commit().await?;
+
+    audit
+        .record(Event::new(Action::Create, TargetKind::Organization, created.id).after(&created))
+        .await;
+    Ok(HttpResponse::Created().json(created))
+}
+
+#[utoipa::path(
+    tag = "organization",
+    operation_id = "updateOrganization",
+    request_body = NewOrganization,
+    params(
+        ("id", Path, description = "Opaque ID of the organization")
+    ),
+    responses(
+        (status = 200, description = "Updated the organization", body = OrganizationHead),
+        (status = 400, description = "The content of the organization is invalid"),
+        (status = 404, description = "Matching organization not found"),
+        (status = 409, description = "An organization with the same name already exists"),
+    ),
+)]
+#[put("/v2/organization/{id}")]
+/// Replace the content of an organization
+pub async fn update(
+    state: web::Data<OrganizationService>,
+    db: web::Data<Database>,
+    id: web::Path<Uuid>,
+    web::Json(organization): web::Json<NewOrganization>,
+    audit: Audit,
+    _: Require<UpdateMetadata>,
+) -> Result<impl Responder, Error> {
+    let tx = db.begin().await?;
+    let updated = state.update_organization(*id, organization, &tx).await?;
+    tx.commit().await?;
+
+    Ok(match updated {
+        Some(updated) => {
+            audit
+                .record(Event::new(Action::Update, TargetKind::Organization, *id).after(&updated))
+                .await;
+            HttpResponse::Ok().json(updated)
+        }
+        None => HttpResponse::NotFound().finish(),
+    })
+}
+
+#[utoipa::path(
+    tag = "organization",
+    operation_id = "replaceOrganizationAliases",
+    request_body = Vec<String>,
+    params(
+        ("id", Path, description = "Opaque ID of the organization")
+    ),
+    responses(
+        (status = 204, description = "Replaced the aliases of the organization"),
+        (status = 400, description = "An alias is invalid"),
+        (status = 404, description = "Matching organization not found"),
+        (status = 409, description = "An alias already belongs to a different organization"),
+    ),
+)]
+#[put("/v2/organization/{id}/alias")]
+/// Replace the aliases of an organization
+pub async fn set_aliases(
+    state: web::Data<OrganizationService>,
+    db: web::Data<Database>,
+    id: web::Path<Uuid>,
+    web::Json(aliases): web::Json<Vec<String>>,
+    audit: Audit,
+    _: Require<UpdateMetadata>,
+) -> Result<impl Responder, Error> {
+    let tx = db.begin().await?;
+    let result = state.set_aliases(*id, aliases.clone(), &tx).await?;
+    tx.commit().await?;
+    invalidate_name_rules();
+
+    Ok(match result {
+        Some(()) => {
+            audit
+                .record(
+                    Event::new(Action::Update, TargetKind::OrganizationAliases, *id)
+                        .after(&aliases),
+                )
+                .await;
+            HttpResponse::NoContent().finish()
+        }
+        None => HttpResponse::NotFound().finish(),
+    })
+}
+
+#[utoipa::path(
+    tag = "organization",
+    operation_id = "mergeOrganization",
+    params(
+        ("id", Path, description = "Opaque ID of the organization to merge into"),
+        ("source", Path, description = "Opaque ID of the organization to merge, which gets deleted"),
+    ),
+    responses(
+        (status = 200, description = "Merged the organizations", body = OrganizationDetails),
+        (status = 400, description = "An organization cannot be merged into itself"),
+        (status = 404, description = "One of the organizations could not be found"),
+        (status = 409, description = "Both organizations have a product with the same name"),
+    ),
+)]
+#[post("/v2/organization/{id}/merge/{source}")]
+/// Merge an organization into another one
+///
+/// Advisories, products and aliases of the merged organization are moved over, and its name
+/// becomes an alias.
+pub async fn merge(
+    state: web::Data<OrganizationService>,
+    db: web::Data<Database>,
+    path: web::Path<(Uuid, Uuid)>,
+    audit: Audit,
+    _: Require<MergeOrganization>,
+) -> Result<impl Responder, Error> {
+    let (id, source) = path.into_inner();
+
+    let tx = db.begin().await?;
+    let before = state.fetch_organization(source, &tx).await?;
+    let merged = state.merge_organizations(id, source, &tx).await?;
+    tx.commit().await?;
+    invalidate_name_rules();
+
+    Ok(match merged {
+        Some(merged) => {
+            audit
+                .record(
+                    Event::new(Action::Merge, TargetKind::Organization, id)
+                        .before(&before)
+                        .after(&merged),
+                )
+                .await;
+            HttpResponse::Ok().json(merged)
+        }
+        None => HttpResponse::NotFound().finish(),
+    })
+}
+
+all!(MergeOrganization -> UpdateMetadata, DeleteMetadata);
+
+#[utoipa::path(
+    tag = "organization",
+    operation_id = "listOrganizationNameRules",
+    responses(
+        (status = 200, description = "The rules normalizing organization names, in the order they are applied", body = Vec<OrganizationNameRule>),
+    ),
+)]
+#[get("/v2/organization-rule")]
+/// Get the rules normalizing organization names during ingestion
+pub async fn get_name_rules(
+    state: web::Data<OrganizationService>,
+    db: web::Data<Database>,
+    _: Require<ReadMetadata>,
+) -> Result<impl Responder, Error> {
+    Ok(HttpResponse::Ok().json(state.fetch_name_rules(db.as_ref()).await?))
+}
+
+#[utoipa::path(
+    tag = "organization",
+    operation_id = "replaceOrganizationNameRules",
+    request_body = Vec<OrganizationNameRule>,
+    responses(
+        (status = 204, description = "Replaced the rules"),
+        (status = 400, description = "A rule is invalid"),
+    ),
+)]
+#[put("/v2/organization-rule")]
+/// Replace the rules normalizing organization names during ingestion
+///
+/// The rules are applied in the order provided, and only affect organizations ingested
+/// afterwards.
+pub async fn set_name_rules(
+    state: web::Data<OrganizationService>,
+    db: web::Data<Database>,
+    web::Json(rules): web::Json<Vec<OrganizationNameRule>>,
+    audit: Audit,
+    _: Require<UpdateMetadata>,
+) -> Result<impl Responder, Error> {
+    let tx = db.begin().await?;
+    let before = state.fetch_name_rules(&tx).await?;
+    state.set_name_rules(rules.clone(), &tx).await?;
+    tx.commit().await?;
+    invalidate_name_rules();
+
+    audit
+        .record(
+            Event::untargeted(Action::Update, TargetKind::OrganizationRules)
+                .before(&before)
+                .after(&rules),
+        )
+        .await;
+    Ok(HttpResponse::NoContent().finish())
+}
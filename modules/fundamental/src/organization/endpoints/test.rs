@@ -1,4 +1,5 @@
 use crate::test::caller;
+use actix_http::StatusCode;
 use actix_web::cookie::time::OffsetDateTime;
 use actix_web::test::TestRequest;
 use jsonpath_rust::JsonPathQuery;
@@ -29,6 +30,7 @@ async fn all_organizations(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
                 published: Some(OffsetDateTime::now_utc()),
                 modified: None,
                 withdrawn: None,
+                format: None,
             },
             &ctx.db,
         )
@@ -47,6 +49,7 @@ async fn all_organizations(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
                 published: Some(OffsetDateTime::now_utc()),
                 modified: None,
                 withdrawn: None,
+                format: None,
             },
             &ctx.db,
         )
@@ -90,6 +93,7 @@ async fn one_organization(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
                 published: Some(OffsetDateTime::now_utc()),
                 modified: None,
                 withdrawn: None,
+                format: None,
             },
             &ctx.db,
         )
@@ -105,7 +109,7 @@ async fn one_organization(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
         .fetch_organizations(Query::default(), Paginated::default(), &ctx.db)
         .await?;
 
-    assert_eq!(1, orgs.total);
+    assert_eq!(Some(1), orgs.total);
 
     let first_org = &orgs.items[0];
     let org_id = first_org.head.id;
@@ -122,3 +126,46 @@ async fn one_organization(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
 
     Ok(())
 }
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn merge_organizations(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let app = caller(ctx).await?;
+
+    let target = ctx
+        .graph
+        .ingest_organization("Red Hat", (), &ctx.db)
+        .await?;
+    let source = ctx.graph.ingest_organization("RHT", (), &ctx.db).await?;
+    let target = target.organization.id;
+    let source = source.organization.id;
+
+    let request = TestRequest::put()
+        .uri(&format!("/api/v2/organization/{target}/alias"))
+        .set_json(json!(["Red Hat Software"]))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let request = TestRequest::post()
+        .uri(&format!("/api/v2/organization/{target}/merge/{source}"))
+        .to_request();
+    let response: Value = app.call_and_read_body_json(request).await;
+    assert_eq!(response["aliases"], json!(["RHT", "Red Hat Software"]));
+
+    // the merged organization is gone
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/organization/{source}"))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let request = TestRequest::post()
+        .uri(&format!("/api/v2/organization/{target}/merge/{source}"))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
 use crate::advisory::model::AdvisoryHead;
-use trustify_entity::{advisory, organization};
+use trustify_entity::{advisory, organization, organization_alias};
 
 use crate::Error;
 use crate::organization::model::OrganizationHead;
@@ -11,10 +11,13 @@ use crate::organization::model::OrganizationHead;
 #[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
 pub struct OrganizationDetails {
     #[serde(flatten)]
-    head: OrganizationHead,
+    pub head: OrganizationHead,
+
+    /// Alternative names of the organization.
+    pub aliases: Vec<String>,
 
     /// Advisories issued by the organization, if any.
-    advisories: Vec<AdvisoryHead>,
+    pub advisories: Vec<AdvisoryHead>,
 }
 
 impl OrganizationDetails {
@@ -22,9 +25,19 @@ impl OrganizationDetails {
         org: &organization::Model,
         tx: &C,
     ) -> Result<Self, Error> {
+        let mut aliases = org
+            .find_related(organization_alias::Entity)
+            .all(tx)
+            .await?
+            .into_iter()
+            .map(|alias| alias.alias)
+            .collect::<Vec<_>>();
+        aliases.sort();
+
         let advisories = org.find_related(advisory::Entity).all(tx).await?;
         Ok(OrganizationDetails {
             head: OrganizationHead::from_entity(org).await?,
+            aliases,
             advisories: AdvisoryHead::from_entities(&advisories, tx).await?,
         })
     }
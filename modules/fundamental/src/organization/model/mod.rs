@@ -8,7 +8,7 @@ mod summary;
 use crate::Error;
 pub use details::*;
 pub use summary::*;
-use trustify_entity::organization;
+use trustify_entity::{organization, organization_rule};
 
 /// An organization who may issue advisories, product SBOMs, or
 /// otherwise be involved in supply-chain evidence.
@@ -39,3 +39,46 @@ impl OrganizationHead {
         })
     }
 }
+
+/// The user provided content of an organization.
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema, PartialEq, Eq)]
+pub struct NewOrganization {
+    /// The unique name of the organization.
+    pub name: String,
+
+    /// The `CPE` key of the organization, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpe_key: Option<String>,
+
+    /// The website of the organization, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub website: Option<String>,
+}
+
+/// A rule normalizing organization names during ingestion.
+///
+/// All matches of the regular expression `pattern` in a name are replaced with `replacement`,
+/// which may refer to capture groups like `$1`.
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema, PartialEq, Eq)]
+pub struct OrganizationNameRule {
+    /// The regular expression to match.
+    pub pattern: String,
+
+    /// The replacement of the matches.
+    #[serde(default)]
+    pub replacement: String,
+
+    /// A description of the rule.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+impl From<organization_rule::Model> for OrganizationNameRule {
+    fn from(value: organization_rule::Model) -> Self {
+        Self {
+            pattern: value.pattern,
+            replacement: value.replacement,
+            description: value.description,
+        }
+    }
+}
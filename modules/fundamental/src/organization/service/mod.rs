@@ -1,8 +1,17 @@
 use crate::{
     Error,
-    organization::model::{OrganizationDetails, OrganizationSummary},
+    organization::model::{
+        NewOrganization, OrganizationDetails, OrganizationHead, OrganizationNameRule,
+        OrganizationSummary,
+    },
+};
+use regex::Regex;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, EntityTrait, ModelTrait,
+    QueryFilter, QueryOrder,
 };
-use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter};
+use sea_query::{Expr, OnConflict};
+use std::collections::BTreeMap;
 use trustify_common::{
     db::{
         limiter::LimiterTrait,
@@ -10,7 +19,8 @@ use trustify_common::{
     },
     model::{Paginated, PaginatedResults},
 };
-use trustify_entity::organization;
+use trustify_entity::{advisory, organization, organization_alias, organization_rule, product};
+use trustify_module_ingestor::graph::organization::name_key;
 use uuid::Uuid;
 
 #[derive(Default)]
@@ -27,17 +37,16 @@ impl OrganizationService {
         paginated: Paginated,
         connection: &C,
     ) -> Result<PaginatedResults<OrganizationSummary>, Error> {
-        let limiter = organization::Entity::find().filtering(search)?.limiting(
-            connection,
-            paginated.offset,
-            paginated.limit,
-        );
+        let limiter = organization::Entity::find()
+            .filtering(search)?
+            .limiting(connection, &paginated);
 
         let total = limiter.total().await?;
 
         Ok(PaginatedResults {
             total,
             items: OrganizationSummary::from_entities(&limiter.fetch().await?).await?,
+            next: None,
         })
     }
     pub async fn fetch_organization<C: ConnectionTrait>(
@@ -57,6 +66,312 @@ impl OrganizationService {
             Ok(None)
         }
     }
+
+    /// Create an organization.
+    ///
+    /// Fails if an organization with the same name already exists.
+    pub async fn create_organization<C: ConnectionTrait>(
+        &self,
+        organization: NewOrganization,
+        connection: &C,
+    ) -> Result<OrganizationHead, Error> {
+        let organization = normalize(organization)?;
+        ensure_unique_name(&organization.name, None, connection).await?;
+
+        let model = organization::ActiveModel {
+            id: Default::default(),
+            name: Set(organization.name),
+            cpe_key: Set(organization.cpe_key),
+            website: Set(organization.website),
+        }
+        .insert(connection)
+        .await?;
+
+        OrganizationHead::from_entity(&model).await
+    }
+
+    /// Replace the content of an organization.
+    ///
+    /// Returns `Ok(None)` if the organization could not be found.
+    pub async fn update_organization<C: ConnectionTrait>(
+        &self,
+        id: Uuid,
+        organization: NewOrganization,
+        connection: &C,
+    ) -> Result<Option<OrganizationHead>, Error> {
+        let organization = normalize(organization)?;
+
+        if organization::Entity::find_by_id(id)
+            .one(connection)
+            .await?
+            .is_none()
+        {
+            return Ok(None);
+        }
+
+        ensure_unique_name(&organization.name, Some(id), connection).await?;
+
+        let model = organization::ActiveModel {
+            id: Set(id),
+            name: Set(organization.name),
+            cpe_key: Set(organization.cpe_key),
+            website: Set(organization.website),
+        }
+        .update(connection)
+        .await?;
+
+        Ok(Some(OrganizationHead::from_entity(&model).await?))
+    }
+
+    /// Replace the aliases of an organization.
+    ///
+    /// Returns `Ok(None)` if the organization could not be found. Fails if an alias already
+    /// belongs to a different organization.
+    pub async fn set_aliases<C: ConnectionTrait>(
+        &self,
+        id: Uuid,
+        aliases: Vec<String>,
+        connection: &C,
+    ) -> Result<Option<()>, Error> {
+        let Some(organization) = organization::Entity::find_by_id(id).one(connection).await? else {
+            return Ok(None);
+        };
+
+        let mut keyed = BTreeMap::new();
+        for alias in aliases {
+            let alias = alias.trim().to_string();
+            let key = name_key(&alias);
+            if key.is_empty() {
+                return Err(Error::BadRequest(format!(
+                    "The alias '{alias}' must contain at least one letter or digit"
+                )));
+            }
+            keyed.insert(key, alias);
+        }
+
+        if !keyed.is_empty() {
+            let taken = organization_alias::Entity::find()
+                .filter(organization_alias::Column::Key.is_in(keyed.keys().cloned()))
+                .filter(organization_alias::Column::OrganizationId.ne(organization.id))
+                .one(connection)
+                .await?;
+            if let Some(taken) = taken {
+                return Err(Error::Conflict(format!(
+                    "The alias '{}' already belongs to a different organization",
+                    taken.alias
+                )));
+            }
+        }
+
+        organization_alias::Entity::delete_many()
+            .filter(organization_alias::Column::OrganizationId.eq(organization.id))
+            .exec(connection)
+            .await?;
+
+        if !keyed.is_empty() {
+            organization_alias::Entity::insert_many(keyed.into_iter().map(|(key, alias)| {
+                organization_alias::ActiveModel {
+                    key: Set(key),
+                    alias: Set(alias),
+                    organization_id: Set(organization.id),
+                }
+            }))
+            .exec(connection)
+            .await?;
+        }
+
+        Ok(Some(()))
+    }
+
+    /// Merge the organization `source` into the organization `target`.
+    ///
+    /// All advisories, products and aliases of `source` are moved to `target`, the name of
+    /// `source` becomes an alias of `target`, and `source` gets deleted afterwards. Missing
+    /// information of `target` is taken from `source`.
+    ///
+    /// Returns `Ok(None)` if either organization could not be found. Fails if both
+    /// organizations have a product with the same name.
+    pub async fn merge_organizations<C: ConnectionTrait>(
+        &self,
+        target: Uuid,
+        source: Uuid,
+        connection: &C,
+    ) -> Result<Option<OrganizationDetails>, Error> {
+        if target == source {
+            return Err(Error::BadRequest(
+                "An organization cannot be merged into itself".into(),
+            ));
+        }
+
+        let Some(target) = organization::Entity::find_by_id(target)
+            .one(connection)
+            .await?
+        else {
+            return Ok(None);
+        };
+        let Some(source) = organization::Entity::find_by_id(source)
+            .one(connection)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let products = source.find_related(product::Entity).all(connection).await?;
+        if !products.is_empty() {
+            let duplicate = target
+                .find_related(product::Entity)
+                .filter(product::Column::Name.is_in(products.iter().map(|p| p.name.clone())))
+                .one(connection)
+                .await?;
+            if let Some(duplicate) = duplicate {
+                return Err(Error::Conflict(format!(
+                    "Both organizations have a product named '{}'",
+                    duplicate.name
+                )));
+            }
+        }
+
+        advisory::Entity::update_many()
+            .col_expr(advisory::Column::IssuerId, Expr::value(target.id))
+            .filter(advisory::Column::IssuerId.eq(source.id))
+            .exec(connection)
+            .await?;
+
+        product::Entity::update_many()
+            .col_expr(product::Column::VendorId, Expr::value(target.id))
+            .filter(product::Column::VendorId.eq(source.id))
+            .exec(connection)
+            .await?;
+
+        organization_alias::Entity::update_many()
+            .col_expr(
+                organization_alias::Column::OrganizationId,
+                Expr::value(target.id),
+            )
+            .filter(organization_alias::Column::OrganizationId.eq(source.id))
+            .exec(connection)
+            .await?;
+
+        let key = name_key(&source.name);
+        if !key.is_empty() && key != name_key(&target.name) {
+            organization_alias::Entity::insert(organization_alias::ActiveModel {
+                key: Set(key),
+                alias: Set(source.name.clone()),
+                organization_id: Set(target.id),
+            })
+            .on_conflict(
+                OnConflict::column(organization_alias::Column::Key)
+                    .update_column(organization_alias::Column::OrganizationId)
+                    .to_owned(),
+            )
+            .exec(connection)
+            .await?;
+        }
+
+        let merged = if (target.cpe_key.is_none() && source.cpe_key.is_some())
+            || (target.website.is_none() && source.website.is_some())
+        {
+            let mut merged = organization::ActiveModel::from(target.clone());
+            merged.cpe_key = Set(target.cpe_key.clone().or(source.cpe_key.clone()));
+            merged.website = Set(target.website.clone().or(source.website.clone()));
+            merged.update(connection).await?
+        } else {
+            target
+        };
+
+        source.delete(connection).await?;
+
+        Ok(Some(
+            OrganizationDetails::from_entity(&merged, connection).await?,
+        ))
+    }
+
+    /// Get the rules normalizing organization names during ingestion, in the order they
+    /// are applied.
+    pub async fn fetch_name_rules<C: ConnectionTrait>(
+        &self,
+        connection: &C,
+    ) -> Result<Vec<OrganizationNameRule>, Error> {
+        Ok(organization_rule::Entity::find()
+            .order_by_asc(organization_rule::Column::Position)
+            .all(connection)
+            .await?
+            .into_iter()
+            .map(OrganizationNameRule::from)
+            .collect())
+    }
+
+    /// Replace the rules normalizing organization names during ingestion.
+    ///
+    /// The rules only apply to organizations ingested afterwards.
+    pub async fn set_name_rules<C: ConnectionTrait>(
+        &self,
+        rules: Vec<OrganizationNameRule>,
+        connection: &C,
+    ) -> Result<(), Error> {
+        for rule in &rules {
+            Regex::new(&rule.pattern).map_err(|err| {
+                Error::BadRequest(format!("Invalid pattern '{}': {err}", rule.pattern))
+            })?;
+        }
+
+        organization_rule::Entity::delete_many()
+            .exec(connection)
+            .await?;
+
+        if !rules.is_empty() {
+            organization_rule::Entity::insert_many(rules.into_iter().enumerate().map(
+                |(position, rule)| organization_rule::ActiveModel {
+                    id: Default::default(),
+                    position: Set(position as i32),
+                    pattern: Set(rule.pattern),
+                    replacement: Set(rule.replacement),
+                    description: Set(rule.description),
+                },
+            ))
+            .exec(connection)
+            .await?;
+        }
+
+        Ok(())
+    }
+}
+
+fn normalize(mut organization: NewOrganization) -> Result<NewOrganization, Error> {
+    organization.name = organization.name.trim().to_string();
+    if organization.name.is_empty() {
+        return Err(Error::BadRequest(
+            "The name of an organization must not be empty".into(),
+        ));
+    }
+
+    let optional = |value: Option<String>| {
+        value
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+    organization.cpe_key = optional(organization.cpe_key);
+    organization.website = optional(organization.website);
+
+    Ok(organization)
+}
+
+async fn ensure_unique_name<C: ConnectionTrait>(
+    name: &str,
+    id: Option<Uuid>,
+    connection: &C,
+) -> Result<(), Error> {
+    let mut query = organization::Entity::find().filter(organization::Column::Name.eq(name));
+    if let Some(id) = id {
+        query = query.filter(organization::Column::Id.ne(id));
+    }
+
+    match query.one(connection).await? {
+        Some(_) => Err(Error::Conflict(format!(
+            "An organization named '{name}' already exists"
+        ))),
+        None => Ok(()),
+    }
 }
 
 #[cfg(test)]
@@ -1,10 +1,16 @@
+use crate::{
+    Error,
+    organization::{model::OrganizationNameRule, service::OrganizationService},
+};
 use actix_web::cookie::time::OffsetDateTime;
 use test_context::test_context;
 use test_log::test;
 use trustify_common::db::query::Query;
 use trustify_common::hashing::Digests;
 use trustify_common::model::Paginated;
-use trustify_module_ingestor::graph::advisory::AdvisoryInformation;
+use trustify_module_ingestor::graph::{
+    advisory::AdvisoryInformation, organization::invalidate_name_rules, product::ProductInformation,
+};
 use trustify_test_context::TrustifyContext;
 
 #[test_context(TrustifyContext)]
@@ -23,19 +29,202 @@ async fn all_organizations(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
                 published: Some(OffsetDateTime::now_utc()),
                 modified: None,
                 withdrawn: None,
+                format: None,
             },
             &ctx.db,
         )
         .await?;
 
-    let service = crate::organization::service::OrganizationService::new();
+    let service = OrganizationService::new();
 
     let orgs = service
         .fetch_organizations(Query::default(), Paginated::default(), &ctx.db)
         .await?;
 
-    assert_eq!(1, orgs.total);
+    assert_eq!(Some(1), orgs.total);
     assert_eq!(1, orgs.items.len());
 
     Ok(())
 }
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn normalize_and_merge(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let service = OrganizationService::new();
+
+    let advisory = |id: &str, issuer: &str| AdvisoryInformation {
+        id: id.to_string(),
+        title: None,
+        version: None,
+        issuer: Some(issuer.to_string()),
+        published: None,
+        modified: None,
+        withdrawn: None,
+        format: None,
+    };
+
+    ctx.graph
+        .ingest_advisory(
+            "RHSA-1",
+            ("source", "http://redhat.com/"),
+            &Digests::digest("RHSA-1"),
+            advisory("RHSA-1", "Red Hat, Inc."),
+            &ctx.db,
+        )
+        .await?;
+    ctx.graph
+        .ingest_advisory(
+            "RHSA-2",
+            ("source", "http://redhat.com/"),
+            &Digests::digest("RHSA-2"),
+            advisory("RHSA-2", "redhat"),
+            &ctx.db,
+        )
+        .await?;
+
+    // the default rules strip the suffix, and the names only differ in case and punctuation
+
+    let orgs = service
+        .fetch_organizations(Query::default(), Paginated::default(), &ctx.db)
+        .await?;
+    assert_eq!(Some(1), orgs.total);
+    let red_hat = &orgs.items[0].head;
+    assert_eq!(red_hat.name, "Red Hat");
+
+    let details = service
+        .fetch_organization(red_hat.id, &ctx.db)
+        .await?
+        .expect("must exist");
+    assert_eq!(details.aliases, vec!["Red Hat, Inc.".to_string()]);
+    assert_eq!(details.advisories.len(), 2);
+
+    // a different name, with a product
+
+    let other = ctx
+        .graph
+        .ingest_product(
+            "Quay",
+            ProductInformation {
+                vendor: Some("RHT".to_string()),
+                cpe: None,
+            },
+            &ctx.db,
+        )
+        .await?
+        .product
+        .vendor_id
+        .expect("must have a vendor");
+
+    // aliases
+
+    service
+        .set_aliases(
+            red_hat.id,
+            vec!["Red Hat, Inc.".into(), "Red Hat Software".into()],
+            &ctx.db,
+        )
+        .await?
+        .expect("must exist");
+    let found = ctx
+        .graph
+        .get_organization_by_name("red hat software", &ctx.db)
+        .await?
+        .expect("must be found");
+    assert_eq!(found.organization.id, red_hat.id);
+
+    assert!(matches!(
+        service
+            .set_aliases(other, vec!["Red Hat Software".into()], &ctx.db)
+            .await,
+        Err(Error::Conflict(_))
+    ));
+    assert!(matches!(
+        service
+            .set_aliases(other, vec!["---".into()], &ctx.db)
+            .await,
+        Err(Error::BadRequest(_))
+    ));
+
+    // merge
+
+    assert!(matches!(
+        service
+            .merge_organizations(red_hat.id, red_hat.id, &ctx.db)
+            .await,
+        Err(Error::BadRequest(_))
+    ));
+
+    let merged = service
+        .merge_organizations(red_hat.id, other, &ctx.db)
+        .await?
+        .expect("must exist");
+    assert_eq!(
+        merged.aliases,
+        vec![
+            "RHT".to_string(),
+            "Red Hat Software".to_string(),
+            "Red Hat, Inc.".to_string()
+        ]
+    );
+
+    let orgs = service
+        .fetch_organizations(Query::default(), Paginated::default(), &ctx.db)
+        .await?;
+    assert_eq!(Some(1), orgs.total);
+
+    let product = ctx
+        .graph
+        .get_product_by_organization("RHT", "Quay", &ctx.db)
+        .await?
+        .expect("must be found");
+    assert_eq!(product.product.vendor_id, Some(red_hat.id));
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn name_rules(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let service = OrganizationService::new();
+
+    assert_eq!(service.fetch_name_rules(&ctx.db).await?.len(), 1);
+
+    // load the default rules into the graph's cache
+    let org = ctx
+        .graph
+        .ingest_organization("Initech, LLC", (), &ctx.db)
+        .await?;
+    assert_eq!(org.organization.name, "Initech");
+
+    let rule = |pattern: &str, replacement: &str| OrganizationNameRule {
+        pattern: pattern.into(),
+        replacement: replacement.into(),
+        description: None,
+    };
+
+    assert!(matches!(
+        service.set_name_rules(vec![rule("(", "")], &ctx.db).await,
+        Err(Error::BadRequest(_))
+    ));
+
+    service
+        .set_name_rules(vec![rule("^RHT$", "Red Hat")], &ctx.db)
+        .await?;
+    assert_eq!(
+        service.fetch_name_rules(&ctx.db).await?,
+        vec![rule("^RHT$", "Red Hat")]
+    );
+    invalidate_name_rules();
+
+    let org = ctx.graph.ingest_organization("RHT", (), &ctx.db).await?;
+    assert_eq!(org.organization.name, "Red Hat");
+
+    // the suffix rule is gone now
+    let org = ctx
+        .graph
+        .ingest_organization("Acme, Inc.", (), &ctx.db)
+        .await?;
+    assert_eq!(org.organization.name, "Acme, Inc.");
+
+    Ok(())
+}
@@ -4,17 +4,24 @@ mod test;
 use crate::{
     Error,
     product::{
-        model::{details::ProductDetails, summary::ProductSummary},
+        model::{
+            NewProduct, NewProductVersion, ProductVersionHead, ProductVulnerability,
+            details::ProductDetails, summary::ProductSummary,
+        },
         service::ProductService,
     },
 };
-use actix_web::{HttpResponse, Responder, delete, get, web};
+use actix_web::{HttpResponse, Responder, delete, get, post, put, web};
 use sea_orm::TransactionTrait;
-use trustify_auth::{DeleteMetadata, ReadMetadata, authorizer::Require};
+use trustify_auth::{
+    CreateMetadata, DeleteMetadata, ReadAdvisory, ReadMetadata, ReadSbom, UpdateMetadata, all,
+    authorizer::Require,
+};
 use trustify_common::{
     db::{Database, query::Query},
     model::{Paginated, PaginatedResults},
 };
+use trustify_module_audit::recorder::{Action, Audit, Event, TargetKind};
 use uuid::Uuid;
 
 pub fn configure(config: &mut utoipa_actix_web::service_config::ServiceConfig, db: Database) {
@@ -23,8 +30,16 @@ pub fn configure(config: &mut utoipa_actix_web::service_config::ServiceConfig, d
         .app_data(web::Data::new(db))
         .app_data(web::Data::new(service))
         .service(all)
+        .service(create)
         .service(delete)
-        .service(get);
+        .service(get)
+        .service(update)
+        .service(create_version)
+        .service(update_version)
+        .service(delete_version)
+        .service(attach_sbom)
+        .service(detach_sbom)
+        .service(get_vulnerabilities);
 }
 
 #[utoipa::path(
@@ -91,6 +106,7 @@ pub async fn delete(
     state: web::Data<ProductService>,
     db: web::Data<Database>,
     id: web::Path<Uuid>,
+    audit: Audit,
     _: Require<DeleteMetadata>,
 ) -> Result<impl Responder, Error> {
     let tx = db.begin().await?;
@@ -102,6 +118,9 @@ pub async fn delete(
                 0 => Ok(HttpResponse::NotFound().finish()),
                 1 => {
                     tx.commit().await?;
+                    audit
+                        .record(Event::new(Action::Delete, TargetKind::Product, v.head.id))
+                        .await;
                     Ok(HttpResponse::Ok().json(v))
                 }
                 _ => Err(Error::Internal("Unexpected number of rows affected".into())),
@@ -110,3 +129,310 @@ pub async fn delete(
         None => Ok(HttpResponse::NotFound().finish()),
     }
 }
+
+#[utoipa::path(
+    tag = "product",
+    operation_id = "createProduct",
+    request_body = NewProduct,
+    responses(
+        (status = 201, description = "Created the product", body = ProductDetails),
+        (status = 400, description = "The content of the product is invalid"),
+        (status = 409, description = "The vendor already has a product with the same name"),
+    ),
+)]
+#[post("/v2/product")]
+/// Create a product
+pub async fn create(
+    state: web::Data<ProductService>,
+    db: web::Data<Database>,
+    web::Json(product): web::Json<NewProduct>,
+    audit: Audit,
+    _: Require<CreateMetadata>,
+) -> Result<impl Responder, Error> {
+    let tx = db.begin().await?;
+    let created = state.create_product(product, &tx).await?;
+    tx.commit().await?;
+
+    audit
+        .record(Event::new(Action::Create, TargetKind::Product, created.head.id).after(&created))
+        .await;
+    Ok(HttpResponse::Created().json(created))
+}
+
+#[utoipa::path(
+    tag = "product",
+    operation_id = "updateProduct",
+    request_body = NewProduct,
+    params(
+        ("id", Path, description = "Opaque ID of the product")
+    ),
+    responses(
+        (status = 200, description = "Updated the product", body = ProductDetails),
+        (status = 400, description = "The content of the product is invalid"),
+        (status = 404, description = "Matching product not found"),
+        (status = 409, description = "The name or vendor of the product would change, which identify it"),
+    ),
+)]
+#[put("/v2/product/{id}")]
+/// Replace the content of a product
+pub async fn update(
+    state: web::Data<ProductService>,
+    db: web::Data<Database>,
+    id: web::Path<Uuid>,
+    web::Json(product): web::Json<NewProduct>,
+    audit: Audit,
+    _: Require<UpdateMetadata>,
+) -> Result<impl Responder, Error> {
+    let tx = db.begin().await?;
+    let before = state.fetch_product(*id, &tx).await?;
+    let updated = state.update_product(*id, product, &tx).await?;
+    tx.commit().await?;
+
+    Ok(match updated {
+        Some(updated) => {
+            audit
+                .record(
+                    Event::new(Action::Update, TargetKind::Product, *id)
+                        .before(&before)
+                        .after(&updated),
+                )
+                .await;
+            HttpResponse::Ok().json(updated)
+        }
+        None => HttpResponse::NotFound().finish(),
+    })
+}
+
+#[utoipa::path(
+    tag = "product",
+    operation_id = "createProductVersion",
+    request_body = NewProductVersion,
+    params(
+        ("id", Path, description = "Opaque ID of the product")
+    ),
+    responses(
+        (status = 201, description = "Created the product version", body = ProductVersionHead),
+        (status = 400, description = "The content of the version is invalid"),
+        (status = 404, description = "Matching product not found"),
+        (status = 409, description = "The version already exists"),
+    ),
+)]
+#[post("/v2/product/{id}/version")]
+/// Create a version of a product
+pub async fn create_version(
+    state: web::Data<ProductService>,
+    db: web::Data<Database>,
+    id: web::Path<Uuid>,
+    web::Json(version): web::Json<NewProductVersion>,
+    audit: Audit,
+    _: Require<CreateMetadata>,
+) -> Result<impl Responder, Error> {
+    let tx = db.begin().await?;
+    let created = state.create_version(*id, version, &tx).await?;
+    tx.commit().await?;
+
+    Ok(match created {
+        Some(created) => {
+            audit
+                .record(
+                    Event::new(Action::Create, TargetKind::ProductVersion, created.id)
+                        .after(&created),
+                )
+                .await;
+            HttpResponse::Created().json(created)
+        }
+        None => HttpResponse::NotFound().finish(),
+    })
+}
+
+#[utoipa::path(
+    tag = "product",
+    operation_id = "updateProductVersion",
+    request_body = NewProductVersion,
+    params(
+        ("id", Path, description = "Opaque ID of the product"),
+        ("version", Path, description = "The version of the product"),
+    ),
+    responses(
+        (status = 200, description = "Updated the product version", body = ProductVersionHead),
+        (status = 400, description = "The content of the version is invalid"),
+        (status = 404, description = "Matching product version not found"),
+        (status = 409, description = "The new version already exists"),
+    ),
+)]
+#[put("/v2/product/{id}/version/{version}")]
+/// Replace the content of a product version
+pub async fn update_version(
+    state: web::Data<ProductService>,
+    db: web::Data<Database>,
+    path: web::Path<(Uuid, String)>,
+    web::Json(update): web::Json<NewProductVersion>,
+    audit: Audit,
+    _: Require<UpdateMetadata>,
+) -> Result<impl Responder, Error> {
+    let (id, version) = path.into_inner();
+
+    let tx = db.begin().await?;
+    let updated = state.update_version(id, &version, update, &tx).await?;
+    tx.commit().await?;
+
+    Ok(match updated {
+        Some(updated) => {
+            audit
+                .record(
+                    Event::new(Action::Update, TargetKind::ProductVersion, updated.id)
+                        .after(&updated),
+                )
+                .await;
+            HttpResponse::Ok().json(updated)
+        }
+        None => HttpResponse::NotFound().finish(),
+    })
+}
+
+#[utoipa::path(
+    tag = "product",
+    operation_id = "deleteProductVersion",
+    params(
+        ("id", Path, description = "Opaque ID of the product"),
+        ("version", Path, description = "The version of the product"),
+    ),
+    responses(
+        (status = 204, description = "Deleted the product version"),
+        (status = 404, description = "Matching product version not found"),
+    ),
+)]
+#[delete("/v2/product/{id}/version/{version}")]
+/// Delete a version of a product
+pub async fn delete_version(
+    state: web::Data<ProductService>,
+    db: web::Data<Database>,
+    path: web::Path<(Uuid, String)>,
+    audit: Audit,
+    _: Require<DeleteMetadata>,
+) -> Result<impl Responder, Error> {
+    let (id, version) = path.into_inner();
+
+    Ok(
+        match state.delete_version(id, &version, db.as_ref()).await? {
+            Some(deleted) => {
+                audit
+                    .record(
+                        Event::new(Action::Delete, TargetKind::ProductVersion, deleted.id)
+                            .before(&deleted),
+                    )
+                    .await;
+                HttpResponse::NoContent().finish()
+            }
+            None => HttpResponse::NotFound().finish(),
+        },
+    )
+}
+
+#[utoipa::path(
+    tag = "product",
+    operation_id = "attachProductVersionSbom",
+    params(
+        ("id", Path, description = "Opaque ID of the product"),
+        ("version", Path, description = "The version of the product"),
+        ("sbom_id", Path, description = "ID of the SBOM"),
+    ),
+    responses(
+        (status = 200, description = "Attached the SBOM to the product version", body = ProductVersionHead),
+        (status = 400, description = "The SBOM could not be found"),
+        (status = 404, description = "Matching product version not found"),
+    ),
+)]
+#[put("/v2/product/{id}/version/{version}/sbom/{sbom_id}")]
+/// Attach an SBOM to a product version, replacing any previously attached one
+pub async fn attach_sbom(
+    state: web::Data<ProductService>,
+    db: web::Data<Database>,
+    path: web::Path<(Uuid, String, Uuid)>,
+    audit: Audit,
+    _: Require<UpdateMetadata>,
+) -> Result<impl Responder, Error> {
+    let (id, version, sbom_id) = path.into_inner();
+    set_sbom(&state, &db, id, &version, Some(sbom_id), audit).await
+}
+
+#[utoipa::path(
+    tag = "product",
+    operation_id = "detachProductVersionSbom",
+    params(
+        ("id", Path, description = "Opaque ID of the product"),
+        ("version", Path, description = "The version of the product"),
+    ),
+    responses(
+        (status = 200, description = "Detached the SBOM from the product version", body = ProductVersionHead),
+        (status = 404, description = "Matching product version not found"),
+    ),
+)]
+#[delete("/v2/product/{id}/version/{version}/sbom")]
+/// Detach the SBOM from a product version
+pub async fn detach_sbom(
+    state: web::Data<ProductService>,
+    db: web::Data<Database>,
+    path: web::Path<(Uuid, String)>,
+    audit: Audit,
+    _: Require<UpdateMetadata>,
+) -> Result<impl Responder, Error> {
+    let (id, version) = path.into_inner();
+    set_sbom(&state, &db, id, &version, None, audit).await
+}
+
+async fn set_sbom(
+    state: &ProductService,
+    db: &Database,
+    id: Uuid,
+    version: &str,
+    sbom_id: Option<Uuid>,
+    audit: Audit,
+) -> Result<HttpResponse, Error> {
+    Ok(
+        match state.set_version_sbom(id, version, sbom_id, db).await? {
+            Some(updated) => {
+                audit
+                    .record(
+                        Event::new(Action::Update, TargetKind::ProductVersion, updated.id)
+                            .after(&updated),
+                    )
+                    .await;
+                HttpResponse::Ok().json(updated)
+            }
+            None => HttpResponse::NotFound().finish(),
+        },
+    )
+}
+
+#[utoipa::path(
+    tag = "product",
+    operation_id = "getProductVulnerabilities",
+    params(
+        ("id", Path, description = "Opaque ID of the product")
+    ),
+    responses(
+        (status = 200, description = "The vulnerabilities affecting the SBOMs of the product versions", body = Vec<ProductVulnerability>),
+        (status = 404, description = "Matching product not found"),
+    ),
+)]
+#[get("/v2/product/{id}/vulnerability")]
+/// Get the vulnerabilities affecting any version of a product
+pub async fn get_vulnerabilities(
+    state: web::Data<ProductService>,
+    db: web::Data<Database>,
+    id: web::Path<Uuid>,
+    _: Require<GetProductVulnerabilities>,
+) -> Result<impl Responder, Error> {
+    Ok(
+        match state
+            .fetch_product_vulnerabilities(*id, db.as_ref())
+            .await?
+        {
+            Some(vulnerabilities) => HttpResponse::Ok().json(vulnerabilities),
+            None => HttpResponse::NotFound().finish(),
+        },
+    )
+}
+
+all!(GetProductVulnerabilities -> ReadMetadata, ReadSbom, ReadAdvisory);
@@ -72,7 +72,7 @@ async fn one_product(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
         .fetch_products(Query::default(), Paginated::default(), &ctx.db)
         .await?;
 
-    assert_eq!(1, products.total);
+    assert_eq!(Some(1), products.total);
 
     let first_product = &products.items[0];
     let product_id = first_product.head.id;
@@ -112,7 +112,7 @@ async fn delete_product(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
         .fetch_products(Query::default(), Paginated::default(), &ctx.db)
         .await?;
 
-    assert_eq!(1, products.total);
+    assert_eq!(Some(1), products.total);
 
     let first_product = &products.items[0];
     let product_id = first_product.head.id;
@@ -129,7 +129,7 @@ async fn delete_product(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
         .fetch_products(Query::default(), Paginated::default(), &ctx.db)
         .await?;
 
-    assert_eq!(0, products.total);
+    assert_eq!(Some(0), products.total);
 
     let request = TestRequest::delete().uri(&uri).to_request();
 
@@ -139,3 +139,92 @@ async fn delete_product(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
 
     Ok(())
 }
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn curate_product(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let app = caller(ctx).await?;
+    let sbom_id = ctx
+        .ingest_document("ubi9-9.2-755.1697625012.json")
+        .await?
+        .id
+        .try_as_uid()
+        .expect("must be a UUID");
+
+    let request = TestRequest::post()
+        .uri("/api/v2/organization")
+        .set_json(json!({ "name": "Red Hat", "cpe_key": "redhat" }))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let vendor: Value = actix_web::test::read_body_json(response).await;
+
+    let request = TestRequest::post()
+        .uri("/api/v2/product")
+        .set_json(json!({
+            "name": "Universal Base Image",
+            "vendor_id": vendor["id"],
+            "cpe_key": "ubi",
+        }))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let product: Value = actix_web::test::read_body_json(response).await;
+    assert_eq!(product["cpe_key"], json!("ubi"));
+    assert_eq!(product["vendor"]["name"], json!("Red Hat"));
+
+    let id = product["id"].as_str().unwrap_or_default();
+
+    // versions
+
+    let request = TestRequest::post()
+        .uri(&format!("/api/v2/product/{id}/version"))
+        .set_json(json!({ "version": "9.2" }))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let request = TestRequest::put()
+        .uri(&format!("/api/v2/product/{id}/version/9.2/sbom/{sbom_id}"))
+        .to_request();
+    let response: Value = app.call_and_read_body_json(request).await;
+    assert_eq!(response["sbom_id"], json!(sbom_id.urn().to_string()));
+
+    // aggregated views
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/product/{id}/license-report"))
+        .to_request();
+    let response: Value = app.call_and_read_body_json(request).await;
+    assert_eq!(response["sboms"], json!([sbom_id]));
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/product/{id}/vulnerability"))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // detach
+
+    let request = TestRequest::delete()
+        .uri(&format!("/api/v2/product/{id}/version/9.2/sbom"))
+        .to_request();
+    let response: Value = app.call_and_read_body_json(request).await;
+    assert_eq!(response.get("sbom_id"), None);
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/product/{id}/license-report"))
+        .to_request();
+    let response: Value = app.call_and_read_body_json(request).await;
+    assert_eq!(response["sboms"], json!([]));
+
+    // unknown versions
+
+    let request = TestRequest::delete()
+        .uri(&format!("/api/v2/product/{id}/version/1.0"))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
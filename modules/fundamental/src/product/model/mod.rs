@@ -1,11 +1,12 @@
 use serde::{Deserialize, Serialize};
+use trustify_cvss::cvss3::severity::Severity;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
 pub mod details;
 pub mod summary;
 
-use crate::Error;
+use crate::{Error, vulnerability::model::VulnerabilityHead};
 use trustify_entity::{product, product_version};
 
 #[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
@@ -14,6 +15,9 @@ pub struct ProductHead {
     #[schema(value_type=String)]
     pub id: Uuid,
     pub name: String,
+    /// The `CPE` key of the product, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpe_key: Option<String>,
 }
 
 impl ProductHead {
@@ -21,6 +25,7 @@ impl ProductHead {
         Ok(ProductHead {
             id: product.id,
             name: product.name.clone(),
+            cpe_key: product.cpe_key.clone(),
         })
     }
 }
@@ -61,3 +66,44 @@ impl ProductVersionHead {
         Ok(heads)
     }
 }
+
+/// The user provided content of a product.
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema, PartialEq, Eq)]
+pub struct NewProduct {
+    /// The name of the product, unique for its vendor.
+    pub name: String,
+
+    /// The opaque ID of the organization which is the vendor of the product.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vendor_id: Option<Uuid>,
+
+    /// The `CPE` key of the product.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpe_key: Option<String>,
+}
+
+/// The user provided content of a product version.
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema, PartialEq, Eq)]
+pub struct NewProductVersion {
+    /// The version, unique for its product.
+    pub version: String,
+
+    /// The ID of the SBOM describing the version.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sbom_id: Option<Uuid>,
+}
+
+/// A vulnerability affecting the SBOMs of one or more versions of a product.
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct ProductVulnerability {
+    #[serde(flatten)]
+    pub head: VulnerabilityHead,
+
+    pub average_severity: Severity,
+
+    /// The affected product versions.
+    pub versions: Vec<String>,
+
+    /// The identifiers of the advisories stating the versions are affected.
+    pub advisories: Vec<String>,
+}
@@ -1,14 +1,32 @@
 use super::model::summary::ProductSummary;
-use crate::{Error, product::model::details::ProductDetails};
-use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter};
+use crate::{
+    Error,
+    product::model::{
+        NewProduct, NewProductVersion, ProductVersionHead, ProductVulnerability,
+        details::ProductDetails,
+    },
+    sbom::model::details::{advisory_info, described_cpes, status_context},
+    vulnerability::model::VulnerabilityHead,
+};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, EntityTrait, LoaderTrait,
+    ModelTrait, QueryFilter, QueryOrder,
+};
+use std::collections::{BTreeMap, BTreeSet};
 use trustify_common::{
     db::{
+        Database,
         limiter::LimiterTrait,
         query::{Filtering, Query},
     },
+    memo::Memo,
     model::{Paginated, PaginatedResults},
 };
-use trustify_entity::product;
+use trustify_cvss::cvss3::{Cvss3Base, score::Score};
+use trustify_entity::{
+    cvss3, organization, product, product_version, sbom, vulnerability, vulnerability_description,
+};
+use trustify_module_ingestor::graph::product::ProductInformation;
 use uuid::Uuid;
 
 #[derive(Default)]
@@ -25,17 +43,16 @@ impl ProductService {
         paginated: Paginated,
         connection: &C,
     ) -> Result<PaginatedResults<ProductSummary>, Error> {
-        let limiter = product::Entity::find().filtering(search)?.limiting(
-            connection,
-            paginated.offset,
-            paginated.limit,
-        );
+        let limiter = product::Entity::find()
+            .filtering(search)?
+            .limiting(connection, &paginated);
 
         let total = limiter.total().await?;
 
         Ok(PaginatedResults {
             total,
             items: ProductSummary::from_entities(&limiter.fetch().await?, connection).await?,
+            next: None,
         })
     }
 
@@ -69,6 +86,354 @@ impl ProductService {
 
         Ok(result.rows_affected)
     }
+
+    /// Create a product.
+    ///
+    /// The ID is derived from the name and the vendor, the same way it is for products created
+    /// during ingestion. Fails if such a product already exists.
+    pub async fn create_product<C: ConnectionTrait + Sync + Send>(
+        &self,
+        product: NewProduct,
+        connection: &C,
+    ) -> Result<ProductDetails, Error> {
+        let product = normalize_product(product, connection).await?;
+
+        let id = ProductInformation::create_uuid(product.vendor_id, product.name.clone());
+        if product::Entity::find_by_id(id)
+            .one(connection)
+            .await?
+            .is_some()
+        {
+            return Err(Error::Conflict(format!(
+                "A product named '{}' already exists for this vendor",
+                product.name
+            )));
+        }
+
+        product::ActiveModel {
+            id: Set(id),
+            name: Set(product.name),
+            vendor_id: Set(product.vendor_id),
+            cpe_key: Set(product.cpe_key),
+        }
+        .insert(connection)
+        .await?;
+
+        self.fetch_product(id, connection)
+            .await?
+            .ok_or_else(|| Error::Internal("Failed to fetch the created product".into()))
+    }
+
+    /// Replace the content of a product, keeping its ID and versions.
+    ///
+    /// The ID is derived from the name and the vendor, so those can't be changed. Otherwise,
+    /// ingesting the product again would create a second one.
+    ///
+    /// Returns `Ok(None)` if the product could not be found.
+    pub async fn update_product<C: ConnectionTrait + Sync + Send>(
+        &self,
+        id: Uuid,
+        product: NewProduct,
+        connection: &C,
+    ) -> Result<Option<ProductDetails>, Error> {
+        let product = normalize_product(product, connection).await?;
+
+        let Some(existing) = product::Entity::find_by_id(id).one(connection).await? else {
+            return Ok(None);
+        };
+
+        if existing.name != product.name || existing.vendor_id != product.vendor_id {
+            return Err(Error::Conflict(
+                "The name and vendor of a product can't be changed, as they identify it".into(),
+            ));
+        }
+
+        let mut model = product::ActiveModel::from(existing);
+        model.cpe_key = Set(product.cpe_key);
+        model.update(connection).await?;
+
+        self.fetch_product(id, connection).await
+    }
+
+    /// Create a version of a product.
+    ///
+    /// Returns `Ok(None)` if the product could not be found. Fails if the version already exists.
+    pub async fn create_version<C: ConnectionTrait + Sync + Send>(
+        &self,
+        product_id: Uuid,
+        version: NewProductVersion,
+        connection: &C,
+    ) -> Result<Option<ProductVersionHead>, Error> {
+        let version = normalize_version(version, connection).await?;
+
+        if product::Entity::find_by_id(product_id)
+            .one(connection)
+            .await?
+            .is_none()
+        {
+            return Ok(None);
+        }
+
+        if find_version(product_id, &version.version, connection)
+            .await?
+            .is_some()
+        {
+            return Err(Error::Conflict(format!(
+                "The version '{}' already exists",
+                version.version
+            )));
+        }
+
+        let model = product_version::ActiveModel {
+            id: Default::default(),
+            product_id: Set(product_id),
+            sbom_id: Set(version.sbom_id),
+            version: Set(version.version),
+        }
+        .insert(connection)
+        .await?;
+
+        Ok(Some(ProductVersionHead::from_entity(&model).await?))
+    }
+
+    /// Replace the content of a version of a product.
+    ///
+    /// Returns `Ok(None)` if the version could not be found.
+    pub async fn update_version<C: ConnectionTrait + Sync + Send>(
+        &self,
+        product_id: Uuid,
+        version: &str,
+        update: NewProductVersion,
+        connection: &C,
+    ) -> Result<Option<ProductVersionHead>, Error> {
+        let update = normalize_version(update, connection).await?;
+
+        let Some(existing) = find_version(product_id, version, connection).await? else {
+            return Ok(None);
+        };
+
+        if update.version != existing.version
+            && find_version(product_id, &update.version, connection)
+                .await?
+                .is_some()
+        {
+            return Err(Error::Conflict(format!(
+                "The version '{}' already exists",
+                update.version
+            )));
+        }
+
+        let mut model = product_version::ActiveModel::from(existing);
+        model.version = Set(update.version);
+        model.sbom_id = Set(update.sbom_id);
+        let model = model.update(connection).await?;
+
+        Ok(Some(ProductVersionHead::from_entity(&model).await?))
+    }
+
+    /// Attach an SBOM to a version of a product, or detach it when `sbom_id` is `None`.
+    ///
+    /// Returns `Ok(None)` if the version could not be found.
+    pub async fn set_version_sbom<C: ConnectionTrait + Sync + Send>(
+        &self,
+        product_id: Uuid,
+        version: &str,
+        sbom_id: Option<Uuid>,
+        connection: &C,
+    ) -> Result<Option<ProductVersionHead>, Error> {
+        if let Some(sbom_id) = sbom_id {
+            ensure_sbom(sbom_id, connection).await?;
+        }
+
+        let Some(existing) = find_version(product_id, version, connection).await? else {
+            return Ok(None);
+        };
+
+        let mut model = product_version::ActiveModel::from(existing);
+        model.sbom_id = Set(sbom_id);
+        let model = model.update(connection).await?;
+
+        Ok(Some(ProductVersionHead::from_entity(&model).await?))
+    }
+
+    /// Delete a version of a product.
+    ///
+    /// Returns the deleted version, `Ok(None)` if the version could not be found.
+    pub async fn delete_version<C: ConnectionTrait + Sync + Send>(
+        &self,
+        product_id: Uuid,
+        version: &str,
+        connection: &C,
+    ) -> Result<Option<ProductVersionHead>, Error> {
+        let Some(existing) = find_version(product_id, version, connection).await? else {
+            return Ok(None);
+        };
+
+        product_version::Entity::delete_by_id(existing.id)
+            .exec(connection)
+            .await?;
+
+        Ok(Some(ProductVersionHead::from_entity(&existing).await?))
+    }
+
+    /// Aggregate the vulnerabilities affecting the SBOMs of all versions of a product.
+    ///
+    /// The statuses of all SBOMs are fetched at once, and then attributed to the versions.
+    ///
+    /// Returns `Ok(None)` if the product could not be found.
+    pub async fn fetch_product_vulnerabilities(
+        &self,
+        id: Uuid,
+        db: &Database,
+    ) -> Result<Option<Vec<ProductVulnerability>>, Error> {
+        let Some(product) = product::Entity::find_by_id(id).one(db).await? else {
+            return Ok(None);
+        };
+
+        let versions = product
+            .find_related(product_version::Entity)
+            .order_by_asc(product_version::Column::Version)
+            .all(db)
+            .await?;
+
+        let mut sbom_versions = BTreeMap::<Uuid, Vec<String>>::new();
+        for version in versions {
+            if let Some(sbom_id) = version.sbom_id {
+                sbom_versions
+                    .entry(sbom_id)
+                    .or_default()
+                    .push(version.version);
+            }
+        }
+
+        let sbom_ids = sbom_versions.keys().copied().collect::<Vec<_>>();
+        let statuses = advisory_info(&sbom_ids, &["affected".to_string()], db).await?;
+        let described_cpes = described_cpes(&sbom_ids, db).await?;
+
+        let mut vulnerabilities =
+            BTreeMap::<String, (&vulnerability::Model, BTreeSet<String>, BTreeSet<String>)>::new();
+
+        for status in &statuses {
+            let sbom_id = status.sbom_package.sbom_id;
+            let sbom_cpes = described_cpes
+                .get(&sbom_id)
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            if status_context(status.context_cpe.as_ref(), sbom_cpes).is_none() {
+                continue;
+            }
+
+            let (_, versions, advisories) = vulnerabilities
+                .entry(status.vulnerability.id.clone())
+                .or_insert_with(|| {
+                    (
+                        &status.vulnerability,
+                        Default::default(),
+                        Default::default(),
+                    )
+                });
+            versions.extend(sbom_versions.get(&sbom_id).into_iter().flatten().cloned());
+            advisories.insert(status.advisory.identifier.clone());
+        }
+
+        // load the scores and descriptions of all vulnerabilities at once
+
+        let models = vulnerabilities
+            .values()
+            .map(|(vulnerability, _, _)| (*vulnerability).clone())
+            .collect::<Vec<_>>();
+        let cvss3s = models.load_many(cvss3::Entity, db).await?;
+        let descriptions = models
+            .load_many(
+                vulnerability_description::Entity::find()
+                    .filter(vulnerability_description::Column::Lang.eq("en")),
+                db,
+            )
+            .await?;
+
+        let mut result = Vec::with_capacity(vulnerabilities.len());
+        for (((vulnerability, versions, advisories), cvss3s), description) in
+            vulnerabilities.into_values().zip(cvss3s).zip(descriptions)
+        {
+            result.push(ProductVulnerability {
+                head: VulnerabilityHead::from_vulnerability_entity(
+                    vulnerability,
+                    Memo::Provided(description.into_iter().next()),
+                    db,
+                )
+                .await?,
+                average_severity: Score::from_iter(cvss3s.iter().map(Cvss3Base::from)).severity(),
+                versions: versions.into_iter().collect(),
+                advisories: advisories.into_iter().collect(),
+            });
+        }
+
+        Ok(Some(result))
+    }
+}
+
+async fn find_version<C: ConnectionTrait>(
+    product_id: Uuid,
+    version: &str,
+    connection: &C,
+) -> Result<Option<product_version::Model>, Error> {
+    Ok(product_version::Entity::find()
+        .filter(product_version::Column::ProductId.eq(product_id))
+        .filter(product_version::Column::Version.eq(version))
+        .one(connection)
+        .await?)
+}
+
+async fn ensure_sbom<C: ConnectionTrait>(sbom_id: Uuid, connection: &C) -> Result<(), Error> {
+    match sbom::Entity::find_by_id(sbom_id).one(connection).await? {
+        Some(_) => Ok(()),
+        None => Err(Error::BadRequest(format!("Unknown SBOM: {sbom_id}"))),
+    }
+}
+
+async fn normalize_product<C: ConnectionTrait>(
+    mut product: NewProduct,
+    connection: &C,
+) -> Result<NewProduct, Error> {
+    product.name = product.name.trim().to_string();
+    if product.name.is_empty() {
+        return Err(Error::BadRequest(
+            "The name of a product must not be empty".into(),
+        ));
+    }
+
+    product.cpe_key = product
+        .cpe_key
+        .map(|cpe_key| cpe_key.trim().to_string())
+        .filter(|cpe_key| !cpe_key.is_empty());
+
+    if let Some(vendor_id) = product.vendor_id {
+        if organization::Entity::find_by_id(vendor_id)
+            .one(connection)
+            .await?
+            .is_none()
+        {
+            return Err(Error::BadRequest(format!("Unknown vendor: {vendor_id}")));
+        }
+    }
+
+    Ok(product)
+}
+
+async fn normalize_version<C: ConnectionTrait>(
+    mut version: NewProductVersion,
+    connection: &C,
+) -> Result<NewProductVersion, Error> {
+    version.version = version.version.trim().to_string();
+    if version.version.is_empty() {
+        return Err(Error::BadRequest("The version must not be empty".into()));
+    }
+
+    if let Some(sbom_id) = version.sbom_id {
+        ensure_sbom(sbom_id, connection).await?;
+    }
+
+    Ok(version)
 }
 
 #[cfg(test)]
@@ -1,3 +1,11 @@
+use crate::{
+    Error,
+    organization::{model::NewOrganization, service::OrganizationService},
+    product::{
+        model::{NewProduct, NewProductVersion},
+        service::ProductService,
+    },
+};
 use std::str::FromStr;
 use test_context::test_context;
 use test_log::test;
@@ -44,7 +52,7 @@ async fn all_products(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
         .fetch_products(Query::default(), Paginated::default(), &ctx.db)
         .await?;
 
-    assert_eq!(1, prods.total);
+    assert_eq!(Some(1), prods.total);
     assert_eq!(1, prods.items.len());
 
     let ver_sbom = ver.get_sbom(&ctx.db).await?.expect("No sbom found");
@@ -135,7 +143,7 @@ async fn delete_product(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
         .fetch_products(Query::default(), Paginated::default(), &ctx.db)
         .await?;
 
-    assert_eq!(1, prods.total);
+    assert_eq!(Some(1), prods.total);
     assert_eq!(1, prods.items.len());
 
     let result = service.delete_product(pr.product.id, &ctx.db).await?;
@@ -146,3 +154,262 @@ async fn delete_product(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
 
     Ok(())
 }
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn manage_products(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let sbom_id = ctx
+        .ingest_document("ubi9-9.2-755.1697625012.json")
+        .await?
+        .id
+        .try_as_uid()
+        .expect("must be a UUID");
+
+    let vendor = OrganizationService::new()
+        .create_organization(
+            NewOrganization {
+                name: "Red Hat".to_string(),
+                cpe_key: Some("redhat".to_string()),
+                website: None,
+            },
+            &ctx.db,
+        )
+        .await?;
+
+    let service = ProductService::new();
+
+    let product = service
+        .create_product(
+            NewProduct {
+                name: " Universal Base Image ".to_string(),
+                vendor_id: Some(vendor.id),
+                cpe_key: Some("ubi".to_string()),
+            },
+            &ctx.db,
+        )
+        .await?;
+    assert_eq!(product.head.name, "Universal Base Image");
+    assert_eq!(product.head.cpe_key.as_deref(), Some("ubi"));
+    assert_eq!(
+        product
+            .vendor
+            .as_ref()
+            .map(|vendor| vendor.head.name.as_str()),
+        Some("Red Hat")
+    );
+    let id = product.head.id;
+
+    // the same ID as if created during ingestion
+
+    assert_eq!(
+        id,
+        ProductInformation::create_uuid(Some(vendor.id), "Universal Base Image".to_string())
+    );
+
+    let result = service
+        .create_product(
+            NewProduct {
+                name: "Universal Base Image".to_string(),
+                vendor_id: Some(vendor.id),
+                cpe_key: None,
+            },
+            &ctx.db,
+        )
+        .await;
+    assert!(matches!(result, Err(Error::Conflict(_))), "{result:?}");
+
+    let result = service
+        .create_product(
+            NewProduct {
+                name: "Other".to_string(),
+                vendor_id: Some(uuid::Uuid::now_v7()),
+                cpe_key: None,
+            },
+            &ctx.db,
+        )
+        .await;
+    assert!(matches!(result, Err(Error::BadRequest(_))), "{result:?}");
+
+    // update
+
+    let product = service
+        .update_product(
+            id,
+            NewProduct {
+                name: "Universal Base Image".to_string(),
+                vendor_id: Some(vendor.id),
+                cpe_key: None,
+            },
+            &ctx.db,
+        )
+        .await?
+        .expect("must be found");
+    assert_eq!(product.head.id, id);
+    assert_eq!(product.head.name, "Universal Base Image");
+    assert_eq!(product.head.cpe_key, None);
+
+    // the name identifies the product
+
+    let result = service
+        .update_product(
+            id,
+            NewProduct {
+                name: "UBI".to_string(),
+                vendor_id: Some(vendor.id),
+                cpe_key: None,
+            },
+            &ctx.db,
+        )
+        .await;
+    assert!(matches!(result, Err(Error::Conflict(_))), "{result:?}");
+
+    // versions
+
+    let version = service
+        .create_version(
+            id,
+            NewProductVersion {
+                version: "9.2".to_string(),
+                sbom_id: None,
+            },
+            &ctx.db,
+        )
+        .await?
+        .expect("must be found");
+    assert_eq!(version.sbom_id, None);
+
+    let result = service
+        .create_version(
+            id,
+            NewProductVersion {
+                version: "9.2".to_string(),
+                sbom_id: None,
+            },
+            &ctx.db,
+        )
+        .await;
+    assert!(matches!(result, Err(Error::Conflict(_))), "{result:?}");
+
+    let result = service
+        .set_version_sbom(id, "9.2", Some(uuid::Uuid::now_v7()), &ctx.db)
+        .await;
+    assert!(matches!(result, Err(Error::BadRequest(_))), "{result:?}");
+
+    let attached = service
+        .set_version_sbom(id, "9.2", Some(sbom_id), &ctx.db)
+        .await?
+        .expect("must be found");
+    assert_eq!(attached.id, version.id);
+    assert_eq!(attached.sbom_id, Some(sbom_id));
+
+    let detached = service
+        .set_version_sbom(id, "9.2", None, &ctx.db)
+        .await?
+        .expect("must be found");
+    assert_eq!(detached.sbom_id, None);
+
+    let updated = service
+        .update_version(
+            id,
+            "9.2",
+            NewProductVersion {
+                version: "9.2.0".to_string(),
+                sbom_id: Some(sbom_id),
+            },
+            &ctx.db,
+        )
+        .await?
+        .expect("must be found");
+    assert_eq!(updated.version, "9.2.0");
+    assert_eq!(updated.sbom_id, Some(sbom_id));
+
+    assert!(
+        service
+            .set_version_sbom(id, "9.2", None, &ctx.db)
+            .await?
+            .is_none()
+    );
+
+    let product = service
+        .fetch_product(id, &ctx.db)
+        .await?
+        .expect("must be found");
+    assert_eq!(product.versions.len(), 1);
+    assert!(product.versions[0].sbom.is_some());
+
+    let deleted = service.delete_version(id, "9.2.0", &ctx.db).await?;
+    assert_eq!(deleted.map(|version| version.id), Some(version.id));
+    assert!(
+        service
+            .delete_version(id, "9.2.0", &ctx.db)
+            .await?
+            .is_none()
+    );
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn product_vulnerabilities(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let sbom_id = ctx
+        .ingest_documents([
+            "quarkus-bom-2.13.8.Final-redhat-00004.json",
+            "csaf/cve-2023-0044.json",
+        ])
+        .await?[0]
+        .id
+        .try_as_uid()
+        .expect("must be a UUID");
+
+    let service = ProductService::new();
+
+    let product = service
+        .create_product(
+            NewProduct {
+                name: "Quarkus".to_string(),
+                vendor_id: None,
+                cpe_key: None,
+            },
+            &ctx.db,
+        )
+        .await?;
+    let id = product.head.id;
+
+    for (version, sbom_id) in [("2.13", Some(sbom_id)), ("3.0", None)] {
+        service
+            .create_version(
+                id,
+                NewProductVersion {
+                    version: version.to_string(),
+                    sbom_id,
+                },
+                &ctx.db,
+            )
+            .await?;
+    }
+
+    let vulnerabilities = service
+        .fetch_product_vulnerabilities(id, &ctx.db)
+        .await?
+        .expect("must be found");
+
+    let vulnerability = vulnerabilities
+        .iter()
+        .find(|vulnerability| vulnerability.head.identifier == "CVE-2023-0044")
+        .expect("must be affected by CVE-2023-0044");
+    assert_eq!(vulnerability.versions, vec!["2.13".to_string()]);
+    assert_eq!(
+        vulnerability.advisories,
+        vec!["https://www.redhat.com/#CVE-2023-0044".to_string()]
+    );
+
+    assert!(
+        service
+            .fetch_product_vulnerabilities(uuid::Uuid::now_v7(), &ctx.db)
+            .await?
+            .is_none()
+    );
+
+    Ok(())
+}
@@ -12,7 +12,7 @@ use trustify_auth::{ReadSbom, authorizer::Require};
 use trustify_common::{
     db::{Database, query::Query},
     id::IdError,
-    model::{Paginated, PaginatedResults},
+    model::{CursorPaginated, PaginatedResults},
     purl::Purl,
 };
 
@@ -48,7 +48,7 @@ pub async fn get_base_purl(
     tag = "base purl",
     params(
         Query,
-        Paginated,
+        CursorPaginated,
     ),
     responses(
         (status = 200, description = "All relevant matching versionless base PURL", body = PaginatedResults<BasePurlSummary>),
@@ -60,7 +60,11 @@ pub async fn all_base_purls(
     service: web::Data<PurlService>,
     db: web::Data<Database>,
     web::Query(search): web::Query<Query>,
-    web::Query(paginated): web::Query<Paginated>,
+    web::Query(paginated): web::Query<CursorPaginated>,
 ) -> actix_web::Result<impl Responder> {
-    Ok(HttpResponse::Ok().json(service.base_purls(search, paginated, db.as_ref()).await?))
+    Ok(HttpResponse::Ok().json(
+        service
+            .base_purls(search, paginated.into(), db.as_ref())
+            .await?,
+    ))
 }
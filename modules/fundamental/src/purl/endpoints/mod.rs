@@ -1,17 +1,18 @@
 use crate::{
     Error,
     endpoints::Deprecation,
+    export::{self, Export, ExportFormat},
     purl::{
         model::{details::purl::PurlDetails, summary::purl::PurlSummary},
         service::PurlService,
     },
 };
-use actix_web::{HttpResponse, Responder, get, web};
+use actix_web::{HttpRequest, HttpResponse, Responder, get, web};
 use sea_orm::prelude::Uuid;
 use std::str::FromStr;
 use trustify_auth::{ReadSbom, authorizer::Require};
 use trustify_common::{
-    db::Database, db::query::Query, id::IdError, model::Paginated, model::PaginatedResults,
+    db::Database, db::query::Query, id::IdError, model::CursorPaginated, model::PaginatedResults,
     purl::Purl,
 };
 
@@ -70,22 +71,40 @@ pub async fn get(
     tag = "purl",
     params(
         Query,
-        Paginated,
+        CursorPaginated,
+        Export,
     ),
     responses(
-        (status = 200, description = "All relevant matching qualified PURLs", body = PaginatedResults<PurlSummary>),
+        (status = 200, description = "All relevant matching qualified PURLs", content(
+            (PaginatedResults<PurlSummary> = "application/json"),
+            (String = "text/csv"),
+            (String = "application/x-ndjson"),
+        )),
     ),
 )]
 #[get("/v2/purl")]
 /// List fully-qualified pURLs
+///
+/// Using an `Accept` header of `text/csv` or `application/x-ndjson`, all matching
+/// pURLs are streamed in that format, without pagination.
 pub async fn all(
+    req: HttpRequest,
     service: web::Data<PurlService>,
     db: web::Data<Database>,
     web::Query(search): web::Query<Query>,
-    web::Query(paginated): web::Query<Paginated>,
+    web::Query(paginated): web::Query<CursorPaginated>,
+    web::Query(export): web::Query<Export>,
     _: Require<ReadSbom>,
 ) -> actix_web::Result<impl Responder> {
-    Ok(HttpResponse::Ok().json(service.purls(search, paginated, db.as_ref()).await?))
+    if let Some(format) = ExportFormat::accepted(&req) {
+        return Ok(export::respond(format, export, move |sink| async move {
+            sink.send_all(service.stream_purls(search, db.as_ref()).await?)
+                .await
+        })
+        .await?);
+    }
+
+    Ok(HttpResponse::Ok().json(service.purls(search, paginated.into(), db.as_ref()).await?))
 }
 
 #[cfg(test)]
@@ -5,7 +5,8 @@ use crate::purl::model::summary::base_purl::BasePurlSummary;
 use crate::purl::model::summary::purl::PurlSummary;
 use crate::purl::model::summary::r#type::TypeSummary;
 use crate::test::caller;
-use actix_web::test::TestRequest;
+use actix_http::StatusCode;
+use actix_web::{http::header, test::TestRequest};
 use serde_json::Value;
 use std::str::FromStr;
 use test_context::test_context;
@@ -350,3 +351,47 @@ async fn package_with_status(ctx: &TrustifyContext) -> Result<(), anyhow::Error>
 
     Ok(())
 }
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn export_qualified_packages(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    setup(&ctx.db, &ctx.graph).await?;
+    let app = caller(ctx).await?;
+
+    let uri = "/api/v2/purl?q=log4j&columns=uuid,purl&limit=1";
+    let request = TestRequest::get()
+        .uri(uri)
+        .insert_header((header::ACCEPT, "text/csv"))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get(header::CONTENT_TYPE),
+        Some(&header::HeaderValue::from_static("text/csv"))
+    );
+    // all PURLs, regardless of the limit
+    let body = actix_web::test::read_body(response).await;
+    let body = std::str::from_utf8(&body)?;
+    let mut lines = body.lines();
+    assert_eq!(lines.next(), Some("uuid,purl"));
+    assert_eq!(lines.count(), 3);
+
+    // the same items as the paginated result
+    let request = TestRequest::get().uri("/api/v2/purl?q=log4j").to_request();
+    let mut expected: PaginatedResults<Value> = app.call_and_read_body_json(request).await;
+    let request = TestRequest::get()
+        .uri("/api/v2/purl?q=log4j")
+        .insert_header((header::ACCEPT, "application/x-ndjson"))
+        .to_request();
+    let body = app.call_and_read_body(request).await;
+    let mut items = serde_json::Deserializer::from_slice(&body)
+        .into_iter()
+        .collect::<Result<Vec<Value>, _>>()?;
+
+    let uuid = |item: &Value| item["uuid"].as_str().map(ToString::to_string);
+    items.sort_by_key(uuid);
+    expected.items.sort_by_key(uuid);
+    assert_eq!(items, expected.items);
+
+    Ok(())
+}
@@ -9,7 +9,7 @@ use actix_web::{HttpResponse, Responder, get, web};
 use trustify_auth::{ReadSbom, authorizer::Require};
 use trustify_common::{
     db::{Database, query::Query},
-    model::{Paginated, PaginatedResults},
+    model::{CursorPaginated, PaginatedResults},
 };
 
 #[utoipa::path(
@@ -36,7 +36,7 @@ pub async fn all_purl_types(
     operation_id = "getPurlType",
     params(
         Query,
-        Paginated,
+        CursorPaginated,
         ("type" = String, Path, description = "PURL identifier of a type")
     ),
     responses(
@@ -50,12 +50,12 @@ pub async fn get_purl_type(
     db: web::Data<Database>,
     r#type: web::Path<String>,
     web::Query(search): web::Query<Query>,
-    web::Query(paginated): web::Query<Paginated>,
+    web::Query(paginated): web::Query<CursorPaginated>,
     _: Require<ReadSbom>,
 ) -> actix_web::Result<impl Responder> {
     Ok(HttpResponse::Ok().json(
         service
-            .base_purls_by_type(&r#type, search, paginated, db.as_ref())
+            .base_purls_by_type(&r#type, search, paginated.into(), db.as_ref())
             .await?,
     ))
 }
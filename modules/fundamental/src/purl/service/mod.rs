@@ -1,5 +1,5 @@
 use crate::{
-    Error,
+    Error, export,
     purl::model::{
         details::{
             base_purl::BasePurlDetails, purl::PurlDetails, versioned_purl::VersionedPurlDetails,
@@ -7,16 +7,17 @@ use crate::{
         summary::{base_purl::BasePurlSummary, purl::PurlSummary, r#type::TypeSummary},
     },
 };
+use futures_util::Stream;
 use sea_orm::{
-    ColumnTrait, ConnectionTrait, EntityTrait, FromQueryResult, QueryFilter, QueryOrder,
-    QuerySelect, prelude::Uuid,
+    ColumnTrait, ConnectionTrait, EntityTrait, FromQueryResult, IntoIdentity, QueryFilter,
+    QueryOrder, QuerySelect, StreamTrait, prelude::Uuid,
 };
 use sea_query::Order;
 use tracing::instrument;
 use trustify_common::{
     db::{
         limiter::LimiterTrait,
-        query::{Filtering, IntoColumns, Query},
+        query::{Columns, Filtering, IntoColumns, Query},
     },
     model::{Paginated, PaginatedResults},
     purl::{Purl, PurlErr},
@@ -68,16 +69,24 @@ impl PurlService {
         paginated: Paginated,
         connection: &C,
     ) -> Result<PaginatedResults<BasePurlSummary>, Error> {
+        let columns = base_purl::Entity
+            .columns()
+            .full_text(base_purl::Entity, "search".into_identity());
+        let keyset = query
+            .keyset(columns.clone())?
+            .then(base_purl::Column::Id, Order::Asc);
         let limiter = base_purl::Entity::find()
             .filter(base_purl::Column::Type.eq(r#type))
-            .filtering(query)?
-            .limiting(connection, paginated.offset, paginated.limit);
+            .filtering_with(query, columns)?
+            .paginating(connection, &paginated, keyset)?;
 
         let total = limiter.total().await?;
+        let (items, next) = limiter.fetch_page().await?;
 
         Ok(PaginatedResults {
-            items: BasePurlSummary::from_entities(&limiter.fetch().await?).await?,
+            items: BasePurlSummary::from_entities(&items).await?,
             total,
+            next,
         })
     }
 
@@ -273,17 +282,23 @@ impl PurlService {
         paginated: Paginated,
         connection: &C,
     ) -> Result<PaginatedResults<BasePurlSummary>, Error> {
-        let limiter = base_purl::Entity::find().filtering(query)?.limiting(
-            connection,
-            paginated.offset,
-            paginated.limit,
-        );
+        let columns = base_purl::Entity
+            .columns()
+            .full_text(base_purl::Entity, "search".into_identity());
+        let keyset = query
+            .keyset(columns.clone())?
+            .then(base_purl::Column::Id, Order::Asc);
+        let limiter = base_purl::Entity::find()
+            .filtering_with(query, columns)?
+            .paginating(connection, &paginated, keyset)?;
 
         let total = limiter.total().await?;
+        let (items, next) = limiter.fetch_page().await?;
 
         Ok(PaginatedResults {
-            items: BasePurlSummary::from_entities(&limiter.fetch().await?).await?,
+            items: BasePurlSummary::from_entities(&items).await?,
             total,
+            next,
         })
     }
 
@@ -294,28 +309,41 @@ impl PurlService {
         paginated: Paginated,
         connection: &C,
     ) -> Result<PaginatedResults<PurlSummary>, Error> {
+        let columns = purl_columns();
+        let keyset = query
+            .keyset(columns.clone())?
+            .then(qualified_purl::Column::Id, Order::Asc);
         let limiter = qualified_purl::Entity::find()
-            .filtering_with(
-                query,
-                qualified_purl::Entity
-                    .columns()
-                    .json_keys("purl", &["ty", "namespace", "name", "version"])
-                    .json_keys("qualifiers", &["arch", "distro", "repository_url"])
-                    .translator(|f, op, v| match f {
-                        "type" => Some(format!("ty{op}{v}")),
-                        _ => None,
-                    }),
-            )?
-            .limiting(connection, paginated.offset, paginated.limit);
+            .filtering_with(query, columns)?
+            .paginating(connection, &paginated, keyset)?;
 
         let total = limiter.total().await?;
+        let (items, next) = limiter.fetch_page().await?;
 
         Ok(PaginatedResults {
-            items: PurlSummary::from_entities(&limiter.fetch().await?, connection).await?,
+            items: PurlSummary::from_entities(&items, connection).await?,
             total,
+            next,
         })
     }
 
+    /// Stream all PURLs matching the query, without pagination
+    pub async fn stream_purls<'a, C: ConnectionTrait + StreamTrait + Send>(
+        &self,
+        query: Query,
+        connection: &'a C,
+    ) -> Result<impl Stream<Item = Result<PurlSummary, Error>> + 'a, Error> {
+        let rows = qualified_purl::Entity::find()
+            .filtering_with(query, purl_columns())?
+            .order_by_asc(qualified_purl::Column::Id)
+            .stream(connection)
+            .await?;
+
+        Ok(export::chunked(rows, move |rows| async move {
+            PurlSummary::from_entities(&rows, connection).await
+        }))
+    }
+
     #[instrument(skip(self, connection), err)]
     pub async fn gc_purls<C: ConnectionTrait>(&self, connection: &C) -> Result<u64, Error> {
         let res = connection
@@ -326,5 +354,17 @@ impl PurlService {
     }
 }
 
+/// The columns available for filtering PURLs
+fn purl_columns() -> Columns {
+    qualified_purl::Entity
+        .columns()
+        .json_keys("purl", &["ty", "namespace", "name", "version"])
+        .json_keys("qualifiers", &["arch", "distro", "repository_url"])
+        .translator(|f, op, v| match f {
+            "type" => Some(format!("ty{op}{v}")),
+            _ => None,
+        })
+}
+
 #[cfg(test)]
 mod test;
@@ -7,7 +7,7 @@ use std::str::FromStr;
 use test_context::test_context;
 use test_log::test;
 use trustify_common::{
-    db::query::{Query, q},
+    db::query::{self, Query, q},
     id::Id,
     model::Paginated,
     purl::Purl,
@@ -119,7 +119,7 @@ async fn packages_for_type(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
         .base_purls_by_type("maven", Query::default(), Paginated::default(), &ctx.db)
         .await?;
 
-    assert_eq!(packages.total, 2);
+    assert_eq!(packages.total, Some(2));
 
     assert!(
         packages
@@ -175,7 +175,7 @@ async fn packages_for_type_with_filtering(ctx: &TrustifyContext) -> Result<(), a
         .base_purls_by_type("maven", q("myspace"), Paginated::default(), &ctx.db)
         .await?;
 
-    assert_eq!(packages.total, 1);
+    assert_eq!(packages.total, Some(1));
 
     assert!(
         packages
@@ -540,6 +540,91 @@ async fn packages(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn packages_by_cursor(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let service = PurlService::new();
+
+    for purl in [
+        "pkg:maven/org.one/a",
+        "pkg:maven/org.one/b",
+        "pkg:maven/org.two/b",
+        "pkg:maven/org.one/c",
+        "pkg:maven/org.one/d",
+        "pkg:maven/org.two/d",
+        "pkg:maven/org.one/e",
+    ] {
+        ctx.graph
+            .ingest_package(&Purl::from_str(purl)?, &ctx.db)
+            .await?;
+    }
+
+    let mut names = vec![];
+    let mut paginated = Paginated {
+        limit: 3,
+        ..Default::default()
+    };
+    loop {
+        let results = service
+            .base_purls(q("").sort("name:desc"), paginated.clone(), &ctx.db)
+            .await?;
+        assert_eq!(Some(7), results.total);
+        names.extend(results.items.into_iter().map(|item| item.head.purl.name));
+        match results.next {
+            Some(next) => paginated.cursor = Some(next),
+            None => break,
+        }
+    }
+    assert_eq!(names, ["e", "d", "d", "c", "b", "b", "a"]);
+
+    // the cursor continues after the last item of the page, ignoring the offset
+    let results = service
+        .base_purls(
+            q("").sort("name:desc"),
+            Paginated {
+                offset: 1,
+                limit: 2,
+                ..Default::default()
+            },
+            &ctx.db,
+        )
+        .await?;
+    assert_eq!(2, results.items.len());
+    let results = service
+        .base_purls(
+            q("").sort("name:desc"),
+            Paginated {
+                offset: 1,
+                limit: 0,
+                cursor: results.next,
+                total: false,
+            },
+            &ctx.db,
+        )
+        .await?;
+    assert_eq!(None, results.total);
+    assert_eq!(None, results.next);
+    assert_eq!(4, results.items.len());
+
+    // cursors don't apply to a different sort
+    let results = service
+        .base_purls(
+            q("").sort("name:desc,namespace"),
+            Paginated {
+                cursor: Some("bm9wZQ".into()),
+                ..Default::default()
+            },
+            &ctx.db,
+        )
+        .await;
+    assert!(matches!(
+        results,
+        Err(crate::Error::Query(query::Error::InvalidCursor(_)))
+    ));
+
+    Ok(())
+}
+
 #[test_context(TrustifyContext)]
 #[test(actix_web::test)]
 async fn qualified_packages(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
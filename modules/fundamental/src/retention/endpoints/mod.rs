@@ -0,0 +1,256 @@
+#[cfg(test)]
+mod test;
+
+use crate::retention::{
+    model::{NewRetentionPolicy, RetentionPolicy, RetentionReport},
+    service::RetentionService,
+};
+use actix_web::{HttpResponse, Responder, delete, get, post, put, web};
+use trustify_auth::{
+    CreateMetadata, DeleteAdvisory, DeleteMetadata, DeleteSbom, ReadMetadata, UpdateMetadata,
+    authorizer::Require,
+};
+use trustify_common::{
+    db::{Database, query::Query},
+    model::{Paginated, PaginatedResults},
+};
+use trustify_module_audit::recorder::{Action, Audit, Event, TargetKind};
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+pub fn configure(config: &mut utoipa_actix_web::service_config::ServiceConfig, db: Database) {
+    let service = RetentionService::new(db);
+    config
+        .app_data(web::Data::new(service))
+        .service(all)
+        .service(create)
+        .service(get)
+        .service(update)
+        .service(delete)
+        .service(apply)
+        .service(all_reports)
+        .service(get_report);
+}
+
+#[utoipa::path(
+    tag = "retention",
+    operation_id = "listRetentionPolicies",
+    params(
+        Query,
+        Paginated,
+    ),
+    responses(
+        (status = 200, description = "Matching retention policies", body = PaginatedResults<RetentionPolicy>),
+    ),
+)]
+#[get("/v2/retention/policy")]
+/// List retention policies
+pub async fn all(
+    state: web::Data<RetentionService>,
+    web::Query(search): web::Query<Query>,
+    web::Query(paginated): web::Query<Paginated>,
+    _: Require<ReadMetadata>,
+) -> actix_web::Result<impl Responder> {
+    Ok(HttpResponse::Ok().json(state.fetch_policies(search, paginated).await?))
+}
+
+#[utoipa::path(
+    tag = "retention",
+    operation_id = "createRetentionPolicy",
+    request_body = NewRetentionPolicy,
+    responses(
+        (status = 201, description = "Created the retention policy", body = RetentionPolicy),
+        (status = 400, description = "The rule of the policy doesn't apply to its target"),
+        (status = 409, description = "A policy with the same name already exists"),
+    ),
+)]
+#[post("/v2/retention/policy")]
+/// Create a retention policy
+pub async fn create(
+    state: web::Data<RetentionService>,
+    web::Json(policy): web::Json<NewRetentionPolicy>,
+    audit: Audit,
+    _: Require<CreateMetadata>,
+) -> actix_web::Result<impl Responder> {
+    let created = state.create_policy(policy).await?;
+    audit
+        .record(Event::new(Action::Create, TargetKind::RetentionPolicy, created.id).after(&created))
+        .await;
+    Ok(HttpResponse::Created().json(created))
+}
+
+#[utoipa::path(
+    tag = "retention",
+    operation_id = "getRetentionPolicy",
+    params(
+        ("id", Path, description = "Opaque ID of the retention policy")
+    ),
+    responses(
+        (status = 200, description = "The retention policy", body = RetentionPolicy),
+        (status = 404, description = "The retention policy could not be found"),
+    ),
+)]
+#[get("/v2/retention/policy/{id}")]
+/// Get a retention policy
+pub async fn get(
+    state: web::Data<RetentionService>,
+    id: web::Path<Uuid>,
+    _: Require<ReadMetadata>,
+) -> actix_web::Result<impl Responder> {
+    Ok(match state.fetch_policy(*id).await? {
+        Some(policy) => HttpResponse::Ok().json(policy),
+        None => HttpResponse::NotFound().finish(),
+    })
+}
+
+#[utoipa::path(
+    tag = "retention",
+    operation_id = "updateRetentionPolicy",
+    request_body = NewRetentionPolicy,
+    params(
+        ("id", Path, description = "Opaque ID of the retention policy")
+    ),
+    responses(
+        (status = 200, description = "Updated the retention policy", body = RetentionPolicy),
+        (status = 400, description = "The rule of the policy doesn't apply to its target"),
+        (status = 404, description = "The retention policy could not be found"),
+        (status = 409, description = "A policy with the same name already exists"),
+    ),
+)]
+#[put("/v2/retention/policy/{id}")]
+/// Replace the content of a retention policy
+pub async fn update(
+    state: web::Data<RetentionService>,
+    id: web::Path<Uuid>,
+    web::Json(policy): web::Json<NewRetentionPolicy>,
+    audit: Audit,
+    _: Require<UpdateMetadata>,
+) -> actix_web::Result<impl Responder> {
+    let before = state.fetch_policy(*id).await?;
+    Ok(match state.update_policy(*id, policy).await? {
+        Some(updated) => {
+            audit
+                .record(
+                    Event::new(Action::Update, TargetKind::RetentionPolicy, *id)
+                        .before(&before)
+                        .after(&updated),
+                )
+                .await;
+            HttpResponse::Ok().json(updated)
+        }
+        None => HttpResponse::NotFound().finish(),
+    })
+}
+
+#[utoipa::path(
+    tag = "retention",
+    operation_id = "deleteRetentionPolicy",
+    params(
+        ("id", Path, description = "Opaque ID of the retention policy")
+    ),
+    responses(
+        (status = 204, description = "Deleted the retention policy"),
+        (status = 404, description = "The retention policy could not be found"),
+    ),
+)]
+#[delete("/v2/retention/policy/{id}")]
+/// Delete a retention policy
+pub async fn delete(
+    state: web::Data<RetentionService>,
+    id: web::Path<Uuid>,
+    audit: Audit,
+    _: Require<DeleteMetadata>,
+) -> actix_web::Result<impl Responder> {
+    Ok(match state.delete_policy(*id).await? {
+        true => {
+            audit
+                .record(Event::new(Action::Delete, TargetKind::RetentionPolicy, *id))
+                .await;
+            HttpResponse::NoContent().finish()
+        }
+        false => HttpResponse::NotFound().finish(),
+    })
+}
+
+#[derive(
+    IntoParams, Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize,
+)]
+struct ApplyParams {
+    /// Only report the documents which would be deleted
+    #[serde(default)]
+    dry_run: bool,
+}
+
+#[utoipa::path(
+    tag = "retention",
+    operation_id = "applyRetentionPolicies",
+    params(ApplyParams),
+    responses(
+        (status = 200, description = "The documents deleted by the enabled policies", body = RetentionReport),
+    ),
+)]
+#[post("/v2/retention/apply")]
+/// Execute all enabled retention policies now
+///
+/// Policies are executed regularly by the importer server. This runs them right away, or, using
+/// a dry run, reports which documents they would delete.
+pub async fn apply(
+    state: web::Data<RetentionService>,
+    web::Query(params): web::Query<ApplyParams>,
+    audit: Audit,
+    _: Require<DeleteSbom>,
+    _: Require<DeleteAdvisory>,
+) -> actix_web::Result<impl Responder> {
+    let report = state.apply(params.dry_run).await?;
+    if !report.dry_run {
+        audit
+            .record(
+                Event::untargeted(Action::Delete, TargetKind::RetentionPolicy)
+                    .after(&report.result),
+            )
+            .await;
+    }
+    Ok(HttpResponse::Ok().json(report))
+}
+
+#[utoipa::path(
+    tag = "retention",
+    operation_id = "listRetentionReports",
+    params(Paginated),
+    responses(
+        (status = 200, description = "The reports of executing the policies, the most recent first", body = PaginatedResults<RetentionReport>),
+    ),
+)]
+#[get("/v2/retention/report")]
+/// List the reports of executing the retention policies
+pub async fn all_reports(
+    state: web::Data<RetentionService>,
+    web::Query(paginated): web::Query<Paginated>,
+    _: Require<ReadMetadata>,
+) -> actix_web::Result<impl Responder> {
+    Ok(HttpResponse::Ok().json(state.fetch_reports(paginated).await?))
+}
+
+#[utoipa::path(
+    tag = "retention",
+    operation_id = "getRetentionReport",
+    params(
+        ("id", Path, description = "Opaque ID of the report")
+    ),
+    responses(
+        (status = 200, description = "The report", body = RetentionReport),
+        (status = 404, description = "The report could not be found"),
+    ),
+)]
+#[get("/v2/retention/report/{id}")]
+/// Get a report of executing the retention policies
+pub async fn get_report(
+    state: web::Data<RetentionService>,
+    id: web::Path<Uuid>,
+    _: Require<ReadMetadata>,
+) -> actix_web::Result<impl Responder> {
+    Ok(match state.fetch_report(*id).await? {
+        Some(report) => HttpResponse::Ok().json(report),
+        None => HttpResponse::NotFound().finish(),
+    })
+}
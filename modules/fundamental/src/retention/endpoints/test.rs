@@ -0,0 +1,142 @@
+use crate::test::caller;
+use actix_http::StatusCode;
+use actix_web::test::TestRequest;
+use serde_json::{Value, json};
+use test_context::test_context;
+use test_log::test;
+use trustify_test_context::{TrustifyContext, call::CallService};
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn create_update_delete(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let app = caller(ctx).await?;
+
+    let request = TestRequest::post()
+        .uri("/api/v2/retention/policy")
+        .set_json(json!({
+            "name": "old sboms",
+            "target": "sbom",
+            "rule": { "type": "max_age", "days": 90 },
+            "exempt": { "release": "true" },
+        }))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let created: Value = actix_web::test::read_body_json(response).await;
+
+    assert_eq!(created["enabled"], json!(true));
+    assert_eq!(created["exempt"], json!({ "release": "true" }));
+
+    let id = created["id"].as_str().unwrap_or_default();
+
+    // duplicate name
+
+    let request = TestRequest::post()
+        .uri("/api/v2/retention/policy")
+        .set_json(json!({
+            "name": "old sboms",
+            "target": "sbom",
+            "rule": { "type": "max_age", "days": 30 },
+        }))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    // rule not applicable to the target
+
+    let request = TestRequest::post()
+        .uri("/api/v2/retention/policy")
+        .set_json(json!({
+            "name": "invalid",
+            "target": "sbom",
+            "rule": { "type": "withdrawn", "days": 365 },
+        }))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // update
+
+    let request = TestRequest::put()
+        .uri(&format!("/api/v2/retention/policy/{id}"))
+        .set_json(json!({
+            "name": "ci builds",
+            "target": "sbom",
+            "rule": { "type": "keep_last", "count": 3, "group_by": { "label": "product" } },
+        }))
+        .to_request();
+    let response: Value = app.call_and_read_body_json(request).await;
+    assert_eq!(
+        response["rule"],
+        json!({ "type": "keep_last", "count": 3, "group_by": { "label": "product" } })
+    );
+
+    let request = TestRequest::get()
+        .uri("/api/v2/retention/policy?q=ci")
+        .to_request();
+    let response: Value = app.call_and_read_body_json(request).await;
+    assert_eq!(response["total"], json!(1));
+    assert_eq!(response["items"][0]["id"], created["id"]);
+
+    // delete
+
+    let request = TestRequest::delete()
+        .uri(&format!("/api/v2/retention/policy/{id}"))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/retention/policy/{id}"))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn dry_run(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let app = caller(ctx).await?;
+    ctx.ingest_document("csaf/cve-2023-33201.json").await?;
+
+    let request = TestRequest::post()
+        .uri("/api/v2/retention/policy")
+        .set_json(json!({
+            "name": "all advisories",
+            "target": "advisory",
+            "rule": { "type": "max_age", "days": 0 },
+        }))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let request = TestRequest::post()
+        .uri("/api/v2/retention/apply?dry_run=true")
+        .to_request();
+    let report: Value = app.call_and_read_body_json(request).await;
+    assert_eq!(report["dry_run"], json!(true));
+    assert_eq!(report["policies"][0]["name"], json!("all advisories"));
+    assert_eq!(
+        report["policies"][0]["documents"][0]["document_id"],
+        json!("CVE-2023-33201")
+    );
+
+    // the advisory still exists
+
+    let request = TestRequest::get().uri("/api/v2/advisory").to_request();
+    let response: Value = app.call_and_read_body_json(request).await;
+    assert_eq!(response["total"], json!(1));
+
+    // the report got recorded
+
+    let request = TestRequest::get()
+        .uri("/api/v2/retention/report")
+        .to_request();
+    let response: Value = app.call_and_read_body_json(request).await;
+    assert_eq!(response["total"], json!(1));
+    assert_eq!(response["items"][0], report);
+
+    Ok(())
+}
@@ -0,0 +1,147 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use trustify_entity::{
+    labels::Labels,
+    retention_policy::{self, Rule, Target},
+    retention_report,
+};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// A retention policy.
+///
+/// Enabled policies are executed regularly by the importer server, deleting the documents
+/// selected by their rule. Removing documents may leave PURLs behind which are no longer
+/// referenced, those get removed as well.
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    /// The opaque UUID of the policy.
+    pub id: Uuid,
+
+    #[serde(flatten)]
+    pub policy: NewRetentionPolicy,
+
+    /// The date (in RFC3339 format) the policy was created.
+    #[serde(with = "time::serde::rfc3339")]
+    pub created: OffsetDateTime,
+
+    /// The date (in RFC3339 format) the policy was last modified.
+    #[serde(with = "time::serde::rfc3339")]
+    pub modified: OffsetDateTime,
+}
+
+impl From<retention_policy::Model> for RetentionPolicy {
+    fn from(value: retention_policy::Model) -> Self {
+        Self {
+            id: value.id,
+            policy: NewRetentionPolicy {
+                name: value.name,
+                description: value.description,
+                enabled: value.enabled,
+                target: value.target,
+                rule: value.rule,
+                exempt: value.exempt,
+            },
+            created: value.created,
+            modified: value.modified,
+        }
+    }
+}
+
+/// The user provided content of a retention policy.
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema, PartialEq, Eq)]
+pub struct NewRetentionPolicy {
+    /// The unique name of the policy.
+    pub name: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Disabled policies are not executed.
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+
+    /// The kind of documents the policy applies to.
+    pub target: Target,
+
+    /// The rule selecting the documents to delete.
+    pub rule: Rule,
+
+    /// Documents having all of these labels are never deleted, like `release=true`.
+    #[serde(default)]
+    pub exempt: Labels,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// The outcome of executing the enabled retention policies.
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema, PartialEq, Eq)]
+pub struct RetentionReport {
+    /// The opaque UUID of the report.
+    pub id: Uuid,
+
+    /// The date (in RFC3339 format) the policies were executed.
+    #[serde(with = "time::serde::rfc3339")]
+    pub created: OffsetDateTime,
+
+    /// If the documents only would have been deleted.
+    pub dry_run: bool,
+
+    #[serde(flatten)]
+    pub result: RetentionResult,
+}
+
+impl TryFrom<retention_report::Model> for RetentionReport {
+    type Error = serde_json::Error;
+
+    fn try_from(value: retention_report::Model) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: value.id,
+            created: value.created,
+            dry_run: value.dry_run,
+            result: serde_json::from_value(value.report)?,
+        })
+    }
+}
+
+/// The documents deleted by the retention policies.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, ToSchema, PartialEq, Eq)]
+pub struct RetentionResult {
+    /// The executed policies, in the order of their execution.
+    pub policies: Vec<PolicyResult>,
+
+    /// The number of PURLs which got removed, as they were no longer referenced. Always zero for a
+    /// dry run.
+    pub purls_removed: u64,
+}
+
+/// The documents deleted by a single retention policy.
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema, PartialEq, Eq)]
+pub struct PolicyResult {
+    /// The ID of the policy.
+    pub policy: Uuid,
+
+    /// The name of the policy, at the time it was executed.
+    pub name: String,
+
+    pub target: Target,
+
+    pub documents: Vec<RemovedDocument>,
+}
+
+/// A document deleted by a retention policy.
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema, PartialEq, Eq)]
+pub struct RemovedDocument {
+    /// The ID of the SBOM or advisory.
+    pub id: Uuid,
+
+    /// The ID declared by the document.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub document_id: Option<String>,
+
+    /// The date (in RFC3339 format) the document was ingested.
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub ingested: Option<OffsetDateTime>,
+}
@@ -0,0 +1,489 @@
+use crate::{
+    Error,
+    advisory::service::AdvisoryService,
+    purl::service::PurlService,
+    retention::model::{
+        NewRetentionPolicy, PolicyResult, RemovedDocument, RetentionPolicy, RetentionReport,
+        RetentionResult,
+    },
+    sbom::service::SbomService,
+};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, Condition, ConnectionTrait, EntityTrait,
+    FromQueryResult, JoinType, QueryFilter, QueryOrder, QuerySelect, QueryTrait, RelationTrait,
+    Select, TransactionTrait,
+};
+use sea_query::{
+    Alias, Asterisk, Expr, NullOrdering, Order, Query, SimpleExpr, WindowStatement,
+    extension::postgres::PgExpr,
+};
+use std::collections::HashSet;
+use time::{Duration, OffsetDateTime};
+use tracing::instrument;
+use trustify_common::{
+    db::{
+        Database,
+        limiter::LimiterTrait,
+        query::{Filtering, Query},
+    },
+    model::{Paginated, PaginatedResults},
+};
+use trustify_entity::{
+    advisory,
+    labels::Labels,
+    retention_policy::{self, GroupBy, Rule, Target},
+    retention_report, sbom, sbom_node, source_document,
+};
+use uuid::Uuid;
+
+/// The number of documents deleted in a single transaction
+const BATCH_SIZE: usize = 100;
+
+pub struct RetentionService {
+    db: Database,
+}
+
+impl RetentionService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    pub async fn fetch_policies(
+        &self,
+        search: Query,
+        paginated: Paginated,
+    ) -> Result<PaginatedResults<RetentionPolicy>, Error> {
+        let limiter = retention_policy::Entity::find()
+            .filtering(search)?
+            .order_by_asc(retention_policy::Column::Name)
+            .limiting(&self.db, &paginated);
+
+        let total = limiter.total().await?;
+
+        Ok(PaginatedResults {
+            total,
+            items: limiter
+                .fetch()
+                .await?
+                .into_iter()
+                .map(RetentionPolicy::from)
+                .collect(),
+            next: None,
+        })
+    }
+
+    pub async fn fetch_policy(&self, id: Uuid) -> Result<Option<RetentionPolicy>, Error> {
+        Ok(retention_policy::Entity::find_by_id(id)
+            .one(&self.db)
+            .await?
+            .map(RetentionPolicy::from))
+    }
+
+    /// Create a new policy.
+    ///
+    /// Fails if the rule doesn't apply to the target of the policy, or if a policy with the same
+    /// name exists.
+    pub async fn create_policy(
+        &self,
+        policy: NewRetentionPolicy,
+    ) -> Result<RetentionPolicy, Error> {
+        let policy = normalize(policy)?;
+
+        let tx = self.db.begin().await?;
+
+        ensure_unique_name(&policy.name, None, &tx).await?;
+
+        let now = OffsetDateTime::now_utc();
+        let model = retention_policy::ActiveModel {
+            id: Set(Uuid::now_v7()),
+            name: Set(policy.name),
+            description: Set(policy.description),
+            enabled: Set(policy.enabled),
+            target: Set(policy.target),
+            rule: Set(policy.rule),
+            exempt: Set(policy.exempt),
+            created: Set(now),
+            modified: Set(now),
+        }
+        .insert(&tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(model.into())
+    }
+
+    /// Replace the content of a policy.
+    ///
+    /// Returns `Ok(None)` if the policy could not be found.
+    pub async fn update_policy(
+        &self,
+        id: Uuid,
+        policy: NewRetentionPolicy,
+    ) -> Result<Option<RetentionPolicy>, Error> {
+        let policy = normalize(policy)?;
+
+        let tx = self.db.begin().await?;
+
+        if retention_policy::Entity::find_by_id(id)
+            .one(&tx)
+            .await?
+            .is_none()
+        {
+            return Ok(None);
+        }
+
+        ensure_unique_name(&policy.name, Some(id), &tx).await?;
+
+        let model = retention_policy::ActiveModel {
+            id: Set(id),
+            name: Set(policy.name),
+            description: Set(policy.description),
+            enabled: Set(policy.enabled),
+            target: Set(policy.target),
+            rule: Set(policy.rule),
+            exempt: Set(policy.exempt),
+            created: Default::default(),
+            modified: Set(OffsetDateTime::now_utc()),
+        }
+        .update(&tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Some(model.into()))
+    }
+
+    /// Delete a policy.
+    ///
+    /// Returns `true` if the policy was found and deleted.
+    pub async fn delete_policy(&self, id: Uuid) -> Result<bool, Error> {
+        let result = retention_policy::Entity::delete_by_id(id)
+            .exec(&self.db)
+            .await?;
+
+        Ok(result.rows_affected > 0)
+    }
+
+    /// List reports, the most recent first.
+    pub async fn fetch_reports(
+        &self,
+        paginated: Paginated,
+    ) -> Result<PaginatedResults<RetentionReport>, Error> {
+        let limiter = retention_report::Entity::find()
+            .order_by_desc(retention_report::Column::Created)
+            .limiting(&self.db, &paginated);
+
+        let total = limiter.total().await?;
+
+        Ok(PaginatedResults {
+            total,
+            items: limiter
+                .fetch()
+                .await?
+                .into_iter()
+                .map(RetentionReport::try_from)
+                .collect::<Result<_, _>>()
+                .map_err(|err| Error::Data(err.to_string()))?,
+            next: None,
+        })
+    }
+
+    pub async fn fetch_report(&self, id: Uuid) -> Result<Option<RetentionReport>, Error> {
+        retention_report::Entity::find_by_id(id)
+            .one(&self.db)
+            .await?
+            .map(RetentionReport::try_from)
+            .transpose()
+            .map_err(|err| Error::Data(err.to_string()))
+    }
+
+    /// Execute all enabled policies, ordered by their name, and record a report.
+    ///
+    /// A dry run only selects the documents, without deleting anything. Otherwise, the documents
+    /// selected by a policy get deleted in batches, each batch committed on its own, followed by
+    /// a cleanup of PURLs which are no longer referenced.
+    #[instrument(skip(self), err)]
+    pub async fn apply(&self, dry_run: bool) -> Result<RetentionReport, Error> {
+        let now = OffsetDateTime::now_utc();
+
+        let policies = retention_policy::Entity::find()
+            .filter(retention_policy::Column::Enabled.eq(true))
+            .order_by_asc(retention_policy::Column::Name)
+            .all(&self.db)
+            .await?;
+
+        let mut result = RetentionResult::default();
+        // documents already selected by a previous policy
+        let mut selected = HashSet::new();
+
+        for policy in policies {
+            let mut documents = candidates(&policy, now, &self.db).await?;
+            documents.retain(|document| selected.insert(document.id));
+
+            if !dry_run {
+                self.delete(policy.target, &documents).await?;
+            }
+
+            log::info!(
+                "Retention policy '{}' selected {} documents",
+                policy.name,
+                documents.len()
+            );
+
+            result.policies.push(PolicyResult {
+                policy: policy.id,
+                name: policy.name,
+                target: policy.target,
+                documents,
+            });
+        }
+
+        if !dry_run && !selected.is_empty() {
+            let tx = self.db.begin().await?;
+            result.purls_removed = PurlService::new().gc_purls(&tx).await?;
+            tx.commit().await?;
+        }
+
+        let report = retention_report::ActiveModel {
+            id: Set(Uuid::now_v7()),
+            created: Set(now),
+            dry_run: Set(dry_run),
+            report: Set(serde_json::to_value(&result).map_err(|err| Error::Any(err.into()))?),
+        }
+        .insert(&self.db)
+        .await?;
+
+        Ok(RetentionReport {
+            id: report.id,
+            created: report.created,
+            dry_run,
+            result,
+        })
+    }
+
+    /// Delete documents of a target, committing every [`BATCH_SIZE`] documents
+    async fn delete(&self, target: Target, documents: &[RemovedDocument]) -> Result<(), Error> {
+        let sbom_service = SbomService::new(self.db.clone());
+        let advisory_service = AdvisoryService::new(self.db.clone());
+
+        for batch in documents.chunks(BATCH_SIZE) {
+            let tx = self.db.begin().await?;
+
+            for document in batch {
+                match target {
+                    Target::Sbom => sbom_service.delete_sbom(document.id, &tx).await?,
+                    Target::Advisory => advisory_service.delete_advisory(document.id, &tx).await?,
+                };
+            }
+
+            tx.commit().await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(FromQueryResult)]
+struct Candidate {
+    id: Uuid,
+    document_id: Option<String>,
+    ingested: Option<OffsetDateTime>,
+}
+
+impl From<Candidate> for RemovedDocument {
+    fn from(value: Candidate) -> Self {
+        Self {
+            id: value.id,
+            document_id: value.document_id,
+            ingested: value.ingested,
+        }
+    }
+}
+
+/// Select the documents a policy would delete
+async fn candidates<C: ConnectionTrait>(
+    policy: &retention_policy::Model,
+    now: OffsetDateTime,
+    connection: &C,
+) -> Result<Vec<RemovedDocument>, Error> {
+    let days = |days: u32| now - Duration::days(days.into());
+
+    let candidates = match (&policy.target, &policy.rule) {
+        (Target::Sbom, Rule::MaxAge { days: max_age }) => {
+            sboms(&policy.exempt)
+                .filter(source_document::Column::Ingested.lt(days(*max_age)))
+                .into_model::<Candidate>()
+                .all(connection)
+                .await?
+        }
+        (Target::Sbom, Rule::KeepLast { count, group_by }) => {
+            let key: SimpleExpr = match group_by {
+                GroupBy::DocumentId => Expr::col((sbom::Entity, sbom::Column::DocumentId)).into(),
+                GroupBy::Name => Expr::col((sbom_node::Entity, sbom_node::Column::Name)).into(),
+                GroupBy::Label(label) => {
+                    Expr::col((sbom::Entity, sbom::Column::Labels)).cast_json_field(label.as_str())
+                }
+            };
+
+            // number the SBOMs of each group, the most recent first and documents missing an
+            // ingestion time last, and select all but the first `count` of each
+            let grouped = sboms(&policy.exempt).expr_as(key, "group_key").into_query();
+            let ranked = Query::select()
+                .column(Asterisk)
+                .expr_window_as(
+                    Expr::cust("row_number()"),
+                    WindowStatement::partition_by(Alias::new("group_key"))
+                        .order_by_with_nulls(
+                            Alias::new("ingested"),
+                            Order::Desc,
+                            NullOrdering::Last,
+                        )
+                        .order_by(Alias::new("id"), Order::Asc)
+                        .to_owned(),
+                    Alias::new("rank"),
+                )
+                .from_subquery(grouped, Alias::new("grouped"))
+                .and_where(Expr::col(Alias::new("group_key")).is_not_null())
+                .to_owned();
+            let select = Query::select()
+                .columns(["id", "document_id", "ingested"].map(Alias::new))
+                .from_subquery(ranked, Alias::new("ranked"))
+                .and_where(Expr::col(Alias::new("rank")).gt(*count))
+                .to_owned();
+
+            Candidate::find_by_statement(connection.get_database_backend().build(&select))
+                .all(connection)
+                .await?
+        }
+        (Target::Advisory, Rule::MaxAge { days: max_age }) => {
+            advisories(&policy.exempt)
+                .filter(source_document::Column::Ingested.lt(days(*max_age)))
+                .into_model::<Candidate>()
+                .all(connection)
+                .await?
+        }
+        (Target::Advisory, Rule::Withdrawn { days: withdrawn }) => {
+            advisories(&policy.exempt)
+                .filter(advisory::Column::Withdrawn.lt(days(*withdrawn)))
+                .into_model::<Candidate>()
+                .all(connection)
+                .await?
+        }
+        (target, rule) => {
+            log::warn!(
+                "Retention policy '{}' has a rule not applicable to {target}: {rule:?}",
+                policy.name
+            );
+            vec![]
+        }
+    };
+
+    let mut documents = candidates
+        .into_iter()
+        .map(RemovedDocument::from)
+        .collect::<Vec<_>>();
+    documents.sort_by(|a, b| a.ingested.cmp(&b.ingested).then(a.id.cmp(&b.id)));
+
+    Ok(documents)
+}
+
+/// SBOMs, along with their ingestion time, which are not exempt
+fn sboms(exempt: &Labels) -> Select<sbom::Entity> {
+    let select = sbom::Entity::find()
+        .select_only()
+        .column_as(sbom::Column::SbomId, "id")
+        .column(sbom::Column::DocumentId)
+        .column(source_document::Column::Ingested)
+        .join(JoinType::LeftJoin, sbom::Relation::SourceDocument.def())
+        .join(JoinType::LeftJoin, sbom::Relation::SbomNode.def());
+
+    match exempt.is_empty() {
+        true => select,
+        false => select.filter(
+            Condition::all()
+                .add(Expr::col((sbom::Entity, sbom::Column::Labels)).contains(exempt.clone()))
+                .not(),
+        ),
+    }
+}
+
+/// Advisories, along with their ingestion time, which are not exempt
+fn advisories(exempt: &Labels) -> Select<advisory::Entity> {
+    let select = advisory::Entity::find()
+        .select_only()
+        .column(advisory::Column::Id)
+        .column(advisory::Column::DocumentId)
+        .column(source_document::Column::Ingested)
+        .join(JoinType::LeftJoin, advisory::Relation::SourceDocument.def());
+
+    match exempt.is_empty() {
+        true => select,
+        false => select.filter(
+            Condition::all()
+                .add(
+                    Expr::col((advisory::Entity, advisory::Column::Labels))
+                        .contains(exempt.clone()),
+                )
+                .not(),
+        ),
+    }
+}
+
+/// Validate and normalize a policy
+fn normalize(mut policy: NewRetentionPolicy) -> Result<NewRetentionPolicy, Error> {
+    policy.name = policy.name.trim().to_string();
+    if policy.name.is_empty() {
+        return Err(Error::BadRequest(
+            "The name of a policy must not be empty".into(),
+        ));
+    }
+
+    match (&policy.target, &mut policy.rule) {
+        (Target::Sbom, Rule::KeepLast { count, group_by }) => {
+            if *count == 0 {
+                return Err(Error::BadRequest(
+                    "At least one SBOM of each group must be kept".into(),
+                ));
+            }
+            if let GroupBy::Label(label) = group_by {
+                *label = label.trim().to_string();
+                if label.is_empty() {
+                    return Err(Error::BadRequest(
+                        "The label to group by must not be empty".into(),
+                    ));
+                }
+            }
+        }
+        (_, Rule::MaxAge { .. }) | (Target::Advisory, Rule::Withdrawn { .. }) => {}
+        (target, rule) => {
+            return Err(Error::BadRequest(format!(
+                "The rule {rule:?} doesn't apply to documents of type {target}"
+            )));
+        }
+    }
+
+    Ok(policy)
+}
+
+async fn ensure_unique_name(
+    name: &str,
+    id: Option<Uuid>,
+    connection: &impl ConnectionTrait,
+) -> Result<(), Error> {
+    let mut query =
+        retention_policy::Entity::find().filter(retention_policy::Column::Name.eq(name));
+    if let Some(id) = id {
+        query = query.filter(retention_policy::Column::Id.ne(id));
+    }
+
+    match query.one(connection).await? {
+        Some(_) => Err(Error::Conflict(format!(
+            "A retention policy named '{name}' already exists"
+        ))),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod test;
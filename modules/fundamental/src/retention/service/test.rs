@@ -0,0 +1,220 @@
+use crate::{
+    Error,
+    retention::{model::NewRetentionPolicy, service::RetentionService},
+};
+use sea_orm::{EntityTrait, PaginatorTrait};
+use test_context::test_context;
+use test_log::test;
+use trustify_common::{db::query::q, model::Paginated};
+use trustify_entity::{
+    advisory,
+    labels::Labels,
+    retention_policy::{GroupBy, Rule, Target},
+    sbom,
+};
+use trustify_module_ingestor::service::Format;
+use trustify_test_context::{TrustifyContext, document_bytes};
+
+fn policy(name: &str, target: Target, rule: Rule) -> NewRetentionPolicy {
+    NewRetentionPolicy {
+        name: name.to_string(),
+        description: None,
+        enabled: true,
+        target,
+        rule,
+        exempt: Labels::default(),
+    }
+}
+
+async fn ingest(ctx: &TrustifyContext, path: &str, labels: Labels) -> anyhow::Result<()> {
+    let bytes = document_bytes(path).await?;
+    ctx.ingestor
+        .ingest(&bytes, Format::Unknown, labels, None)
+        .await?;
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn crud(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let service = RetentionService::new(ctx.db.clone());
+
+    let created = service
+        .create_policy(policy(
+            " ci builds ",
+            Target::Sbom,
+            Rule::KeepLast {
+                count: 5,
+                group_by: GroupBy::Label("product".into()),
+            },
+        ))
+        .await?;
+    assert_eq!(created.policy.name, "ci builds");
+
+    assert_eq!(
+        service.fetch_policy(created.id).await?,
+        Some(created.clone())
+    );
+
+    // names must be unique
+
+    let result = service
+        .create_policy(policy("ci builds", Target::Sbom, Rule::MaxAge { days: 90 }))
+        .await;
+    assert!(matches!(result, Err(Error::Conflict(_))), "{result:?}");
+
+    // rules must apply to the target
+
+    let result = service
+        .create_policy(policy(
+            "invalid",
+            Target::Sbom,
+            Rule::Withdrawn { days: 365 },
+        ))
+        .await;
+    assert!(matches!(result, Err(Error::BadRequest(_))), "{result:?}");
+
+    let result = service
+        .create_policy(policy(
+            "invalid",
+            Target::Sbom,
+            Rule::KeepLast {
+                count: 0,
+                group_by: GroupBy::Name,
+            },
+        ))
+        .await;
+    assert!(matches!(result, Err(Error::BadRequest(_))), "{result:?}");
+
+    // update
+
+    let updated = service
+        .update_policy(
+            created.id,
+            policy("ci builds", Target::Sbom, Rule::MaxAge { days: 90 }),
+        )
+        .await?
+        .expect("must be found");
+    assert_eq!(updated.policy.rule, Rule::MaxAge { days: 90 });
+    assert_eq!(updated.created, created.created);
+
+    let result = service
+        .fetch_policies(q("ci"), Paginated::default())
+        .await?;
+    assert_eq!(result.total, Some(1));
+
+    // delete
+
+    assert!(service.delete_policy(created.id).await?);
+    assert!(!service.delete_policy(created.id).await?);
+    assert_eq!(service.fetch_policy(created.id).await?, None);
+
+    Ok(())
+}
+
+/// Keep the most recent SBOM of each product, reporting first and deleting afterwards
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn keep_last(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let service = RetentionService::new(ctx.db.clone());
+
+    let product = Labels::new().add("product", "quarkus");
+    ingest(
+        ctx,
+        "spdx/quarkus-bom-3.2.11.Final-redhat-00001.json",
+        product.clone(),
+    )
+    .await?;
+    ingest(
+        ctx,
+        "spdx/quarkus-bom-3.2.12.Final-redhat-00002.json",
+        product,
+    )
+    .await?;
+    // not part of any group
+    ctx.ingest_document("zookeeper-3.9.2-cyclonedx.json")
+        .await?;
+
+    service
+        .create_policy(policy(
+            "products",
+            Target::Sbom,
+            Rule::KeepLast {
+                count: 1,
+                group_by: GroupBy::Label("product".into()),
+            },
+        ))
+        .await?;
+
+    // dry run
+
+    let report = service.apply(true).await?;
+    assert!(report.dry_run);
+    assert_eq!(report.result.purls_removed, 0);
+    assert_eq!(report.result.policies.len(), 1);
+    let documents = &report.result.policies[0].documents;
+    assert_eq!(documents.len(), 1);
+    assert_eq!(
+        documents[0].document_id.as_deref(),
+        Some(
+            "https://access.redhat.com/security/data/sbom/spdx/quarkus-bom-3.2.11.Final-redhat-00001"
+        )
+    );
+
+    assert_eq!(sbom::Entity::find().count(&ctx.db).await?, 3);
+
+    // apply
+
+    let report = service.apply(false).await?;
+    assert!(!report.dry_run);
+    assert_eq!(report.result.policies[0].documents, *documents);
+
+    assert_eq!(sbom::Entity::find().count(&ctx.db).await?, 2);
+
+    // nothing left to do
+
+    let next = service.apply(false).await?;
+    assert!(next.result.policies[0].documents.is_empty());
+    assert_eq!(next.result.purls_removed, 0);
+
+    let reports = service.fetch_reports(Paginated::default()).await?;
+    assert_eq!(reports.total, Some(3));
+    assert_eq!(reports.items[0].id, next.id);
+    assert_eq!(service.fetch_report(report.id).await?, Some(report));
+
+    Ok(())
+}
+
+/// Delete all advisories, except for exempt ones
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn exempt(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let service = RetentionService::new(ctx.db.clone());
+
+    ingest(
+        ctx,
+        "csaf/cve-2023-33201.json",
+        Labels::new().add("release", "true"),
+    )
+    .await?;
+    ctx.ingest_document("csaf/cve-2023-0044.json").await?;
+
+    let mut all = policy("all", Target::Advisory, Rule::MaxAge { days: 0 });
+    all.exempt = Labels::new().add("release", "true");
+    service.create_policy(all).await?;
+
+    // disabled policies are not executed
+    let mut disabled = policy("disabled", Target::Advisory, Rule::MaxAge { days: 0 });
+    disabled.enabled = false;
+    service.create_policy(disabled).await?;
+
+    let report = service.apply(false).await?;
+    assert_eq!(report.result.policies.len(), 1);
+    assert_eq!(report.result.policies[0].documents.len(), 1);
+
+    let remaining = advisory::Entity::find().all(&ctx.db).await?;
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].labels, Labels::new().add("release", "true"));
+
+    Ok(())
+}
@@ -1,9 +1,9 @@
 use crate::sbom::service::SbomService;
 use actix_web::{HttpResponse, Responder, patch, put, web};
 use trustify_auth::{UpdateSbom, authorizer::Require};
-use trustify_common::db::Database;
 use trustify_common::id::Id;
-use trustify_entity::labels::Labels;
+use trustify_entity::labels::{Labels, LabelsChange};
+use trustify_module_audit::recorder::{Action, Audit, Event, TargetKind};
 
 /// Modify existing labels of an SBOM
 #[utoipa::path(
@@ -23,17 +23,13 @@ pub async fn update(
     sbom: web::Data<SbomService>,
     id: web::Path<Id>,
     web::Json(update): web::Json<Labels>,
+    audit: Audit,
     _: Require<UpdateSbom>,
 ) -> actix_web::Result<impl Responder> {
-    Ok(
-        match sbom
-            .update_labels(id.into_inner(), |labels| labels.apply(update))
-            .await?
-        {
-            Some(()) => HttpResponse::NoContent(),
-            None => HttpResponse::NotFound(),
-        },
-    )
+    let change = sbom
+        .update_labels(id.into_inner(), |labels| labels.apply(update))
+        .await?;
+    Ok(respond(change, audit).await)
 }
 
 /// Replace the labels of an SBOM
@@ -52,18 +48,27 @@ pub async fn update(
 #[put("/v2/sbom/{id}/label")]
 pub async fn set(
     sbom: web::Data<SbomService>,
-    db: web::Data<Database>,
     id: web::Path<Id>,
     web::Json(labels): web::Json<Labels>,
+    audit: Audit,
     _: Require<UpdateSbom>,
 ) -> actix_web::Result<impl Responder> {
-    Ok(
-        match sbom
-            .set_labels(id.into_inner(), labels, db.as_ref())
-            .await?
-        {
-            Some(()) => HttpResponse::NoContent(),
-            None => HttpResponse::NotFound(),
-        },
-    )
+    let change = sbom.update_labels(id.into_inner(), |_| labels).await?;
+    Ok(respond(change, audit).await)
+}
+
+async fn respond(change: Option<LabelsChange>, audit: Audit) -> HttpResponse {
+    match change {
+        Some(LabelsChange { id, before, after }) => {
+            audit
+                .record(
+                    Event::new(Action::Update, TargetKind::SbomLabels, id.urn())
+                        .before(before)
+                        .after(after),
+                )
+                .await;
+            HttpResponse::NoContent().finish()
+        }
+        None => HttpResponse::NotFound().finish(),
+    }
 }
@@ -8,6 +8,8 @@ pub use query::*;
 
 use crate::{
     Error::{self, Internal},
+    export::{self, Export, ExportFormat},
+    history::{model::IngestionEvent, service::HistoryService},
     purl::service::PurlService,
     sbom::{
         model::{
@@ -17,10 +19,11 @@ use crate::{
         service::SbomService,
     },
 };
-use actix_web::{HttpResponse, Responder, delete, get, http::header, post, web};
+use actix_web::{HttpRequest, HttpResponse, Responder, delete, get, http::header, post, web};
 use config::Config;
 use futures_util::TryStreamExt;
 use sea_orm::{TransactionTrait, prelude::Uuid};
+use std::collections::BTreeMap;
 use std::str::FromStr;
 use trustify_auth::{
     CreateSbom, DeleteSbom, Permission, ReadAdvisory, ReadSbom, all,
@@ -28,13 +31,17 @@ use trustify_auth::{
     authorizer::{Authorizer, Require},
 };
 use trustify_common::{
-    db::{Database, query::Query},
+    db::{Database, facet::Facets, query::Query},
     decompress::decompress_async,
     id::Id,
-    model::{BinaryData, Paginated, PaginatedResults},
+    model::{BinaryData, CursorPaginated, FacetCount, Paginated, PaginatedResults},
 };
-use trustify_entity::{labels::Labels, relationship::Relationship};
+use trustify_entity::{
+    ingestion_history::DocumentKind, labels::Labels, relationship::Relationship,
+};
+use trustify_module_audit::recorder::{Action, Audit, Event, TargetKind};
 use trustify_module_ingestor::{
+    graph::history::Origin,
     model::IngestResult,
     service::{Format, IngestorService},
 };
@@ -47,13 +54,16 @@ pub fn configure(
 ) {
     let sbom_service = SbomService::new(db.clone());
     let purl_service = PurlService::new();
+    let history_service = HistoryService::new();
 
     config
         .app_data(web::Data::new(db))
         .app_data(web::Data::new(sbom_service))
         .app_data(web::Data::new(purl_service))
+        .app_data(web::Data::new(history_service))
         .app_data(web::Data::new(Config { upload_limit }))
         .service(all)
+        .service(facets)
         .service(all_related)
         .service(count_related)
         .service(get)
@@ -63,6 +73,7 @@ pub fn configure(
         .service(related)
         .service(upload)
         .service(download)
+        .service(history)
         .service(label::set)
         .service(label::update);
 }
@@ -73,22 +84,41 @@ pub fn configure(
     params(
         Query,
         Paginated,
+        Export,
     ),
     responses(
-        (status = 200, description = "Matching SBOMs", body = PaginatedResults<SbomSummary>),
+        (status = 200, description = "Matching SBOMs", content(
+            (PaginatedResults<SbomSummary> = "application/json"),
+            (String = "text/csv"),
+            (String = "application/x-ndjson"),
+        )),
     ),
 )]
 #[get("/v2/sbom")]
+/// List SBOMs
+///
+/// Using an `Accept` header of `text/csv` or `application/x-ndjson`, all matching
+/// SBOMs are streamed in that format, without pagination.
 pub async fn all(
+    req: HttpRequest,
     fetch: web::Data<SbomService>,
     db: web::Data<Database>,
     web::Query(search): web::Query<Query>,
     web::Query(paginated): web::Query<Paginated>,
+    web::Query(export): web::Query<Export>,
     authorizer: web::Data<Authorizer>,
     user: UserInformation,
 ) -> actix_web::Result<impl Responder> {
     authorizer.require(&user, Permission::ReadSbom)?;
 
+    if let Some(format) = ExportFormat::accepted(&req) {
+        return Ok(export::respond(format, export, move |sink| async move {
+            sink.send_all(fetch.stream_sboms(search, (), db.as_ref()).await?)
+                .await
+        })
+        .await?);
+    }
+
     let result = fetch
         .fetch_sboms(search, paginated, (), db.as_ref())
         .await?;
@@ -96,6 +126,33 @@ pub async fn all(
     Ok(HttpResponse::Ok().json(result))
 }
 
+#[utoipa::path(
+    tag = "sbom",
+    operation_id = "listSbomFacets",
+    params(
+        Query,
+        Facets,
+    ),
+    responses(
+        (status = 200, description = "Counts of the matching SBOMs, by facet", body = BTreeMap<String, Vec<FacetCount>>),
+    ),
+)]
+#[get("/v2/sbom/facets")]
+/// Count SBOMs by the values of facets
+pub async fn facets(
+    fetch: web::Data<SbomService>,
+    db: web::Data<Database>,
+    web::Query(search): web::Query<Query>,
+    web::Query(facets): web::Query<Facets>,
+    _: Require<ReadSbom>,
+) -> actix_web::Result<impl Responder> {
+    let result = fetch
+        .fetch_sbom_facets(search, facets, (), db.as_ref())
+        .await?;
+
+    Ok(HttpResponse::Ok().json(result))
+}
+
 /// Find all SBOMs containing the provided package.
 ///
 /// The package can be provided either via a PURL or using the ID of a package as returned by
@@ -237,6 +294,7 @@ pub async fn delete(
     db: web::Data<Database>,
     purl_service: web::Data<PurlService>,
     id: web::Path<String>,
+    audit: Audit,
     _: Require<DeleteSbom>,
 ) -> Result<impl Responder, Error> {
     let tx = db.begin().await?;
@@ -250,6 +308,13 @@ pub async fn delete(
                 1 => {
                     let _ = purl_service.gc_purls(&tx).await; // ignore gc failure..
                     tx.commit().await?;
+                    audit
+                        .record(Event::new(
+                            Action::Delete,
+                            TargetKind::Sbom,
+                            v.head.id.urn(),
+                        ))
+                        .await;
                     Ok(HttpResponse::Ok().json(v))
                 }
                 _ => Err(Internal("Unexpected number of rows affected".into())),
@@ -260,29 +325,48 @@ pub async fn delete(
 }
 
 /// Search for packages of an SBOM
+///
+/// Using an `Accept` header of `text/csv` or `application/x-ndjson`, all matching
+/// packages are streamed in that format, without pagination.
 #[utoipa::path(
     tag = "sbom",
     operation_id = "listPackages",
     params(
         ("id", Path, description = "ID of the SBOM to get packages for"),
         Query,
-        Paginated,
+        CursorPaginated,
+        Export,
     ),
     responses(
-        (status = 200, description = "Packages", body = PaginatedResults<SbomPackage>),
+        (status = 200, description = "Packages", content(
+            (PaginatedResults<SbomPackage> = "application/json"),
+            (String = "text/csv"),
+            (String = "application/x-ndjson"),
+        )),
     ),
 )]
 #[get("/v2/sbom/{id}/packages")]
 pub async fn packages(
+    req: HttpRequest,
     fetch: web::Data<SbomService>,
     db: web::Data<Database>,
     id: web::Path<Uuid>,
     web::Query(search): web::Query<Query>,
-    web::Query(paginated): web::Query<Paginated>,
+    web::Query(paginated): web::Query<CursorPaginated>,
+    web::Query(export): web::Query<Export>,
     _: Require<ReadSbom>,
 ) -> actix_web::Result<impl Responder> {
+    if let Some(format) = ExportFormat::accepted(&req) {
+        let id = id.into_inner();
+        return Ok(export::respond(format, export, move |sink| async move {
+            sink.send_all(fetch.stream_sbom_packages(id, search, db.as_ref()).await?)
+                .await
+        })
+        .await?);
+    }
+
     let result = fetch
-        .fetch_sbom_packages(id.into_inner(), search, paginated, db.as_ref())
+        .fetch_sbom_packages(id.into_inner(), search, paginated.into(), db.as_ref())
         .await?;
 
     Ok(HttpResponse::Ok().json(result))
@@ -375,11 +459,21 @@ pub async fn upload(
     web::Query(UploadQuery { labels }): web::Query<UploadQuery>,
     content_type: Option<web::Header<header::ContentType>>,
     bytes: web::Bytes,
+    user: UserInformation,
+    audit: Audit,
     _: Require<CreateSbom>,
 ) -> Result<impl Responder, Error> {
     let bytes = decompress_async(bytes, content_type.map(|ct| ct.0), config.upload_limit).await??;
-    let result = service.ingest(&bytes, Format::SBOM, labels, None).await?;
+    let origin = Origin {
+        user_id: user.id().map(ToString::to_string),
+    };
+    let result = service
+        .ingest_with_origin(&bytes, Format::SBOM, labels, None, origin)
+        .await?;
     log::info!("Uploaded SBOM: {}", result.id);
+    audit
+        .record(Event::new(Action::Upload, TargetKind::Sbom, &result.id))
+        .await;
     Ok(HttpResponse::Created().json(result))
 }
 
@@ -427,3 +521,40 @@ pub async fn download(
         Ok(HttpResponse::NotFound().finish())
     }
 }
+
+#[utoipa::path(
+    tag = "sbom",
+    operation_id = "getSbomHistory",
+    params(
+        ("id" = String, Path, description = "Digest/hash of the document, prefixed by hash type, such as 'sha256:<hash>' or 'urn:uuid:<uuid>'"),
+        Paginated,
+    ),
+    responses(
+        (status = 200, description = "The ingestion history of the SBOM, most recent first", body = PaginatedResults<IngestionEvent>),
+        (status = 404, description = "Matching SBOM not found"),
+    ),
+)]
+#[get("/v2/sbom/{id}/history")]
+/// Get the ingestion history of an SBOM
+///
+/// This includes all versions of the SBOM, sharing the same document ID (namespace).
+pub async fn history(
+    sbom: web::Data<SbomService>,
+    history: web::Data<HistoryService>,
+    db: web::Data<Database>,
+    id: web::Path<String>,
+    web::Query(paginated): web::Query<Paginated>,
+    _: Require<ReadSbom>,
+) -> Result<impl Responder, Error> {
+    let id = Id::from_str(&id).map_err(Error::IdKey)?;
+
+    let Some(sbom) = sbom.fetch_sbom_summary(id, db.as_ref()).await? else {
+        return Ok(HttpResponse::NotFound().finish());
+    };
+
+    Ok(HttpResponse::Ok().json(
+        history
+            .fetch_history(DocumentKind::Sbom, sbom.head.id, paginated, db.as_ref())
+            .await?,
+    ))
+}
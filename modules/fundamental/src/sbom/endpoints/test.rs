@@ -3,12 +3,13 @@ use crate::{
     test::caller,
 };
 use actix_http::StatusCode;
-use actix_web::test::TestRequest;
+use actix_web::{http::header, test::TestRequest};
 use serde_json::{Value, json};
 use test_context::test_context;
 use test_log::test;
-use trustify_common::{id::Id, model::PaginatedResults};
+use trustify_common::{db::query::Query, id::Id, model::PaginatedResults};
 use trustify_entity::labels::Labels;
+use trustify_module_audit::service::AuditService;
 use trustify_module_ingestor::model::IngestResult;
 use trustify_test_context::{TrustifyContext, call::CallService, document_bytes};
 use uuid::Uuid;
@@ -71,17 +72,17 @@ async fn filter_packages(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
         .to_string();
 
     let result = query(&app, &id, "").await;
-    assert_eq!(result.total, 41);
+    assert_eq!(result.total, Some(41));
 
     let result = query(&app, &id, "netty-common").await;
-    assert_eq!(result.total, 1);
+    assert_eq!(result.total, Some(1));
     assert_eq!(result.items[0].name, "netty-common");
 
     let result = query(&app, &id, r"type\=jar").await;
-    assert_eq!(result.total, 41);
+    assert_eq!(result.total, Some(41));
 
     let result = query(&app, &id, "version=4.1.105.Final").await;
-    assert_eq!(result.total, 9);
+    assert_eq!(result.total, Some(9));
 
     Ok(())
 }
@@ -105,6 +106,50 @@ async fn set_labels(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+/// Test that label changes are recorded in the audit log
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn set_labels_audited(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let app = caller(ctx).await?;
+    let result = ctx
+        .ingest_document("quarkus-bom-2.13.8.Final-redhat-00004.json")
+        .await?;
+
+    let request = TestRequest::put()
+        .uri(&format!("/api/v2/sbom/{}/label", result.id))
+        .set_json(Labels::new().extend([("foo", "1"), ("bar", "2")]))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let request = TestRequest::patch()
+        .uri(&format!("/api/v2/sbom/{}/label", result.id))
+        .set_json(Labels::new().extend([("foo", ""), ("baz", "3")]))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let entries = AuditService::new(ctx.db.clone())
+        .fetch(Query::q("target_kind=sbom_labels"), Default::default())
+        .await?;
+    assert_eq!(entries.total, Some(2));
+
+    // newest first
+
+    let patch = &entries.items[0];
+    assert_eq!(patch.target_id, Some(result.id.to_string()));
+    assert_eq!(patch.request.method, "PATCH");
+    assert_eq!(patch.before, Some(json!({"foo": "1", "bar": "2"})));
+    assert_eq!(patch.after, Some(json!({"bar": "2", "baz": "3"})));
+
+    let put = &entries.items[1];
+    assert_eq!(put.request.method, "PUT");
+    assert_eq!(put.before, Some(json!({"source": "TrustifyContext"})));
+    assert_eq!(put.after, Some(json!({"foo": "1", "bar": "2"})));
+
+    Ok(())
+}
+
 /// Test setting labels, for a document that does not exists
 #[test_context(TrustifyContext)]
 #[test(actix_web::test)]
@@ -159,6 +204,16 @@ async fn delete_sbom(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
     log::debug!("Code: {}", response.status());
     assert_eq!(response.status(), StatusCode::NOT_FOUND);
 
+    // Only the successful delete got recorded
+    let entries = AuditService::new(ctx.db.clone())
+        .fetch(
+            Query::q("action=delete&target_kind=sbom"),
+            Default::default(),
+        )
+        .await?;
+    assert_eq!(entries.total, Some(1));
+    assert_eq!(entries.items[0].target_id, Some(result.id.to_string()));
+
     Ok(())
 }
 
@@ -270,3 +325,143 @@ async fn query_sboms_by_ingested_time(ctx: &TrustifyContext) -> Result<(), anyho
 
     Ok(())
 }
+
+/// Parse newline delimited JSON
+fn ndjson(body: &[u8]) -> Vec<Value> {
+    serde_json::Deserializer::from_slice(body)
+        .into_iter()
+        .collect::<Result<_, _>>()
+        .expect("must be NDJSON")
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn export_sboms(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let app = caller(ctx).await?;
+
+    ctx.ingest_documents([
+        "ubi9-9.2-755.1697625012.json",
+        "zookeeper-3.9.2-cyclonedx.json",
+    ])
+    .await?;
+
+    let request = TestRequest::get()
+        .uri("/api/v2/sbom?columns=name&sort=name&limit=1")
+        .insert_header((header::ACCEPT, "text/csv"))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get(header::CONTENT_TYPE),
+        Some(&header::HeaderValue::from_static("text/csv"))
+    );
+    // all SBOMs, regardless of the limit
+    let body = actix_web::test::read_body(response).await;
+    assert_eq!(body, "name\nubi9-container\nzookeeper\n");
+
+    // the same items as the paginated result
+    let request = TestRequest::get()
+        .uri("/api/v2/sbom?sort=name")
+        .to_request();
+    let expected: PaginatedResults<Value> = app.call_and_read_body_json(request).await;
+    let request = TestRequest::get()
+        .uri("/api/v2/sbom?sort=name")
+        .insert_header((header::ACCEPT, "application/x-ndjson"))
+        .to_request();
+    let body = app.call_and_read_body(request).await;
+    assert_eq!(ndjson(&body), expected.items);
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn export_packages(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let app = caller(ctx).await?;
+    let id = ctx
+        .ingest_document("zookeeper-3.9.2-cyclonedx.json")
+        .await?
+        .id
+        .to_string();
+
+    let request = TestRequest::get()
+        .uri(&format!(
+            "/api/v2/sbom/{id}/packages?columns=name,version&q=netty-common"
+        ))
+        .insert_header((header::ACCEPT, "text/csv"))
+        .to_request();
+    let body = app.call_and_read_body(request).await;
+    assert_eq!(body, "name,version\nnetty-common,4.1.105.Final\n");
+
+    // the same items as the paginated result
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/sbom/{id}/packages?limit=100"))
+        .to_request();
+    let expected: PaginatedResults<Value> = app.call_and_read_body_json(request).await;
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/sbom/{id}/packages"))
+        .insert_header((header::ACCEPT, "application/x-ndjson"))
+        .to_request();
+    let body = app.call_and_read_body(request).await;
+    let items = ndjson(&body);
+    assert_eq!(items.len(), 41);
+    assert_eq!(items, expected.items);
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn cursors_and_totals(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let app = caller(ctx).await?;
+    let id = ctx
+        .ingest_document("zookeeper-3.9.2-cyclonedx.json")
+        .await?
+        .id
+        .to_string();
+
+    // listing packages supports cursors
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/sbom/{id}/packages?limit=40&total=false"))
+        .to_request();
+    let page: Value = app.call_and_read_body_json(request).await;
+    assert_eq!(page["items"].as_array().map(Vec::len), Some(40));
+    // not counted
+    assert_eq!(page.get("total"), None);
+
+    let cursor = page["next"].as_str().expect("must have a next page");
+    let request = TestRequest::get()
+        .uri(&format!(
+            "/api/v2/sbom/{id}/packages?limit=40&cursor={}",
+            urlencoding::encode(cursor)
+        ))
+        .to_request();
+    let page: PaginatedResults<SbomPackage> = app.call_and_read_body_json(request).await;
+    assert_eq!(page.items.len(), 1);
+    assert_eq!(page.total, Some(41));
+    assert_eq!(page.next, None);
+
+    // listing SBOMs doesn't
+    let request = TestRequest::get()
+        .uri(&format!(
+            "/api/v2/sbom?cursor={}",
+            urlencoding::encode(cursor)
+        ))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // but can skip counting as well
+    let request = TestRequest::get()
+        .uri("/api/v2/sbom?total=false")
+        .to_request();
+    let page: Value = app.call_and_read_body_json(request).await;
+    assert_eq!(page["items"].as_array().map(Vec::len), Some(1));
+    assert_eq!(page.get("total"), None);
+
+    let request = TestRequest::get().uri("/api/v2/sbom").to_request();
+    let page: Value = app.call_and_read_body_json(request).await;
+    assert_eq!(page["total"], 1);
+
+    Ok(())
+}
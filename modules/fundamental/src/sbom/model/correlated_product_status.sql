@@ -0,0 +1,67 @@
+-- find CPE-only product statuses (no package), which apply to purl based packages
+-- through a learned or curated CPE/purl mapping
+SELECT
+    "advisory"."id" AS "advisory$id",
+    "advisory"."identifier" AS "advisory$identifier",
+    "advisory"."version" AS "advisory$version",
+    "advisory"."document_id" AS "advisory$document_id",
+    "advisory"."deprecated" AS "advisory$deprecated",
+    "advisory"."issuer_id" AS "advisory$issuer_id",
+    "advisory"."published" AS "advisory$published",
+    "advisory"."modified" AS "advisory$modified",
+    "advisory"."withdrawn" AS "advisory$withdrawn",
+    "advisory"."title" AS "advisory$title",
+    "advisory"."labels" AS "advisory$labels",
+    "advisory"."source_document_id" AS "advisory$source_document_id",
+    "advisory"."format" AS "advisory$format",
+    "vulnerability"."id" AS "vulnerability$id",
+    "vulnerability"."title" AS "vulnerability$title",
+    "vulnerability"."reserved" AS "vulnerability$reserved",
+    "vulnerability"."published" AS "vulnerability$published",
+    "vulnerability"."modified" AS "vulnerability$modified",
+    "vulnerability"."withdrawn" AS "vulnerability$withdrawn",
+    "vulnerability"."cwes" AS "vulnerability$cwes",
+    "base_purl"."id" AS "base_purl$id",
+    "base_purl"."type" AS "base_purl$type",
+    "base_purl"."namespace" AS "base_purl$namespace",
+    "base_purl"."name" AS "base_purl$name",
+    "versioned_purl"."id" AS "versioned_purl$id",
+    "versioned_purl"."base_purl_id" AS "versioned_purl$base_purl_id",
+    "versioned_purl"."version" AS "versioned_purl$version",
+    "qualified_purl"."id" AS "qualified_purl$id",
+    "qualified_purl"."versioned_purl_id" AS "qualified_purl$versioned_purl_id",
+    "qualified_purl"."qualifiers" AS "qualified_purl$qualifiers",
+    "qualified_purl"."purl" AS "qualified_purl$purl",
+    "sbom_package"."sbom_id" AS "sbom_package$sbom_id",
+    "sbom_package"."node_id" AS "sbom_package$node_id",
+    "sbom_package"."version" AS "sbom_package$version",
+    "sbom_node"."sbom_id" AS "sbom_node$sbom_id",
+    "sbom_node"."node_id" AS "sbom_node$node_id",
+    "sbom_node"."name" AS "sbom_node$name",
+    "status"."id" AS "status$id",
+    "status"."slug" AS "status$slug",
+    "status"."name" AS "status$name",
+    "status"."description" AS "status$description"
+FROM "sbom_package_purl_ref"
+JOIN "qualified_purl" ON "qualified_purl"."id" = "sbom_package_purl_ref"."qualified_purl_id"
+JOIN "versioned_purl" ON "versioned_purl"."id" = "qualified_purl"."versioned_purl_id"
+JOIN "base_purl" ON "base_purl"."id" = "versioned_purl"."base_purl_id"
+JOIN "sbom_package" ON "sbom_package"."sbom_id" = "sbom_package_purl_ref"."sbom_id" AND "sbom_package"."node_id" = "sbom_package_purl_ref"."node_id"
+JOIN "sbom_node" ON "sbom_node"."sbom_id" = "sbom_package_purl_ref"."sbom_id" AND "sbom_node"."node_id" = "sbom_package_purl_ref"."node_id"
+
+-- translate the purl into CPE identities
+JOIN "cpe_purl_mapping" ON "cpe_purl_mapping"."base_purl_id" = "base_purl"."id"
+JOIN "cpe" ON "cpe"."vendor" = "cpe_purl_mapping"."cpe_vendor" AND "cpe"."product" = "cpe_purl_mapping"."cpe_product"
+
+-- find statuses for those CPEs, matching the version of the purl
+JOIN "product_status" ON "product_status"."context_cpe_id" = "cpe"."id" AND "product_status"."package" IS NULL
+JOIN "product_version_range" ON "product_version_range"."id" = "product_status"."product_version_range_id"
+JOIN "version_range" ON "version_range"."id" = "product_version_range"."version_range_id" AND version_matches("versioned_purl"."version", "version_range".*)
+
+-- get basic status info
+JOIN "status" ON "product_status"."status_id" = "status"."id"
+JOIN "advisory" ON "product_status"."advisory_id" = "advisory"."id"
+JOIN "vulnerability" ON "product_status"."vulnerability_id" = "vulnerability"."id"
+WHERE
+"sbom_package_purl_ref"."sbom_id" = ANY($1)
+AND ($2::text[] = ARRAY[]::text[] OR "status"."slug" = ANY($2::text[]))
@@ -0,0 +1,72 @@
+-- find purl statuses, which apply to CPE-only packages through a learned or
+-- curated CPE/purl mapping
+SELECT
+    "advisory"."id" AS "advisory$id",
+    "advisory"."identifier" AS "advisory$identifier",
+    "advisory"."version" AS "advisory$version",
+    "advisory"."document_id" AS "advisory$document_id",
+    "advisory"."deprecated" AS "advisory$deprecated",
+    "advisory"."issuer_id" AS "advisory$issuer_id",
+    "advisory"."published" AS "advisory$published",
+    "advisory"."modified" AS "advisory$modified",
+    "advisory"."withdrawn" AS "advisory$withdrawn",
+    "advisory"."title" AS "advisory$title",
+    "advisory"."labels" AS "advisory$labels",
+    "advisory"."source_document_id" AS "advisory$source_document_id",
+    "advisory"."format" AS "advisory$format",
+    "vulnerability"."id" AS "vulnerability$id",
+    "vulnerability"."title" AS "vulnerability$title",
+    "vulnerability"."reserved" AS "vulnerability$reserved",
+    "vulnerability"."published" AS "vulnerability$published",
+    "vulnerability"."modified" AS "vulnerability$modified",
+    "vulnerability"."withdrawn" AS "vulnerability$withdrawn",
+    "vulnerability"."cwes" AS "vulnerability$cwes",
+    "base_purl"."id" AS "base_purl$id",
+    "base_purl"."type" AS "base_purl$type",
+    "base_purl"."namespace" AS "base_purl$namespace",
+    "base_purl"."name" AS "base_purl$name",
+    "sbom_package"."sbom_id" AS "sbom_package$sbom_id",
+    "sbom_package"."node_id" AS "sbom_package$node_id",
+    "sbom_package"."version" AS "sbom_package$version",
+    "sbom_node"."sbom_id" AS "sbom_node$sbom_id",
+    "sbom_node"."node_id" AS "sbom_node$node_id",
+    "sbom_node"."name" AS "sbom_node$name",
+    "status"."id" AS "status$id",
+    "status"."slug" AS "status$slug",
+    "status"."name" AS "status$name",
+    "status"."description" AS "status$description",
+    "context_cpe"."id" AS "cpe$id",
+    "context_cpe"."part" AS "cpe$part",
+    "context_cpe"."vendor" AS "cpe$vendor",
+    "context_cpe"."product" AS "cpe$product",
+    "context_cpe"."version" AS "cpe$version",
+    "context_cpe"."update" AS "cpe$update",
+    "context_cpe"."edition" AS "cpe$edition",
+    "context_cpe"."language" AS "cpe$language"
+FROM "sbom_package_cpe_ref"
+JOIN "cpe" AS "package_cpe" ON "package_cpe"."id" = "sbom_package_cpe_ref"."cpe_id"
+JOIN "sbom_package" ON "sbom_package"."sbom_id" = "sbom_package_cpe_ref"."sbom_id" AND "sbom_package"."node_id" = "sbom_package_cpe_ref"."node_id"
+JOIN "sbom_node" ON "sbom_node"."sbom_id" = "sbom_package_cpe_ref"."sbom_id" AND "sbom_node"."node_id" = "sbom_package_cpe_ref"."node_id"
+
+-- translate the CPE into purl identities
+JOIN "cpe_purl_mapping" ON "cpe_purl_mapping"."cpe_vendor" = "package_cpe"."vendor" AND "cpe_purl_mapping"."cpe_product" = "package_cpe"."product"
+JOIN "base_purl" ON "base_purl"."id" = "cpe_purl_mapping"."base_purl_id"
+
+-- find statuses for those purls, matching the version of the package
+JOIN "purl_status" ON "purl_status"."base_purl_id" = "base_purl"."id"
+JOIN "version_range" ON "version_range"."id" = "purl_status"."version_range_id" AND version_matches(COALESCE("sbom_package"."version", "package_cpe"."version"), "version_range".*)
+LEFT JOIN "cpe" AS "context_cpe" ON "context_cpe"."id" = "purl_status"."context_cpe_id"
+
+-- get basic status info
+JOIN "status" ON "purl_status"."status_id" = "status"."id"
+JOIN "advisory" ON "purl_status"."advisory_id" = "advisory"."id"
+JOIN "vulnerability" ON "purl_status"."vulnerability_id" = "vulnerability"."id"
+WHERE
+"sbom_package_cpe_ref"."sbom_id" = ANY($1)
+-- packages with a purl are already covered by the direct purl matching
+AND NOT EXISTS (
+    SELECT 1 FROM "sbom_package_purl_ref"
+    WHERE "sbom_package_purl_ref"."sbom_id" = "sbom_package_cpe_ref"."sbom_id"
+    AND "sbom_package_purl_ref"."node_id" = "sbom_package_cpe_ref"."node_id"
+)
+AND ($2::text[] = ARRAY[]::text[] OR "status"."slug" = ANY($2::text[]))
@@ -0,0 +1,66 @@
+-- find CPE-only product statuses (no package) of NVD advisories, which apply to packages by their CPE,
+-- matching the version of the CPE or the package against the product version range
+SELECT
+    "advisory"."id" AS "advisory$id",
+    "advisory"."identifier" AS "advisory$identifier",
+    "advisory"."version" AS "advisory$version",
+    "advisory"."document_id" AS "advisory$document_id",
+    "advisory"."deprecated" AS "advisory$deprecated",
+    "advisory"."issuer_id" AS "advisory$issuer_id",
+    "advisory"."published" AS "advisory$published",
+    "advisory"."modified" AS "advisory$modified",
+    "advisory"."withdrawn" AS "advisory$withdrawn",
+    "advisory"."title" AS "advisory$title",
+    "advisory"."labels" AS "advisory$labels",
+    "advisory"."source_document_id" AS "advisory$source_document_id",
+    "advisory"."format" AS "advisory$format",
+    "vulnerability"."id" AS "vulnerability$id",
+    "vulnerability"."title" AS "vulnerability$title",
+    "vulnerability"."reserved" AS "vulnerability$reserved",
+    "vulnerability"."published" AS "vulnerability$published",
+    "vulnerability"."modified" AS "vulnerability$modified",
+    "vulnerability"."withdrawn" AS "vulnerability$withdrawn",
+    "vulnerability"."cwes" AS "vulnerability$cwes",
+    "sbom_package"."sbom_id" AS "sbom_package$sbom_id",
+    "sbom_package"."node_id" AS "sbom_package$node_id",
+    "sbom_package"."version" AS "sbom_package$version",
+    "sbom_node"."sbom_id" AS "sbom_node$sbom_id",
+    "sbom_node"."node_id" AS "sbom_node$node_id",
+    "sbom_node"."name" AS "sbom_node$name",
+    "status"."id" AS "status$id",
+    "status"."slug" AS "status$slug",
+    "status"."name" AS "status$name",
+    "status"."description" AS "status$description"
+FROM "sbom_package_cpe_ref"
+JOIN "cpe" AS "package_cpe" ON "package_cpe"."id" = "sbom_package_cpe_ref"."cpe_id"
+JOIN "sbom_package" ON "sbom_package"."sbom_id" = "sbom_package_cpe_ref"."sbom_id" AND "sbom_package"."node_id" = "sbom_package_cpe_ref"."node_id"
+JOIN "sbom_node" ON "sbom_node"."sbom_id" = "sbom_package_cpe_ref"."sbom_id" AND "sbom_node"."node_id" = "sbom_package_cpe_ref"."node_id"
+
+-- find statuses for the same vendor and product
+JOIN "cpe" ON "cpe"."vendor" = "package_cpe"."vendor" AND "cpe"."product" = "package_cpe"."product"
+JOIN "product_status" ON "product_status"."context_cpe_id" = "cpe"."id" AND "product_status"."package" IS NULL
+JOIN "product_version_range" ON "product_version_range"."id" = "product_status"."product_version_range_id"
+JOIN "version_range" ON "version_range"."id" = "product_version_range"."version_range_id" AND version_matches(COALESCE(NULLIF(NULLIF("package_cpe"."version", '*'), ''), "sbom_package"."version"), "version_range".*)
+
+-- get basic status info
+JOIN "status" ON "product_status"."status_id" = "status"."id"
+JOIN "advisory" ON "product_status"."advisory_id" = "advisory"."id" AND "advisory"."format" = 'nvd'
+JOIN "vulnerability" ON "product_status"."vulnerability_id" = "vulnerability"."id"
+WHERE
+"sbom_package_cpe_ref"."sbom_id" = ANY($1)
+AND ($2::text[] = ARRAY[]::text[] OR "status"."slug" = ANY($2::text[]))
+
+-- statuses which only apply in combination with a platform require one of the platforms in the SBOM
+AND (
+    NOT EXISTS (
+        SELECT 1 FROM "product_status_platform"
+        WHERE "product_status_platform"."product_status_id" = "product_status"."id"
+    )
+    OR EXISTS (
+        SELECT 1 FROM "product_status_platform"
+        JOIN "cpe" AS "platform_cpe" ON "platform_cpe"."id" = "product_status_platform"."cpe_id"
+        JOIN "cpe" AS "sbom_cpe" ON "sbom_cpe"."vendor" = "platform_cpe"."vendor" AND "sbom_cpe"."product" = "platform_cpe"."product"
+        JOIN "sbom_package_cpe_ref" AS "platform_ref" ON "platform_ref"."cpe_id" = "sbom_cpe"."id" AND "platform_ref"."sbom_id" = "sbom_package_cpe_ref"."sbom_id"
+        WHERE "product_status_platform"."product_status_id" = "product_status"."id"
+    )
+)
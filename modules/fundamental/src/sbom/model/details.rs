@@ -11,8 +11,9 @@ use crate::{
 };
 use cpe::{cpe::Cpe, uri::OwnedUri};
 use sea_orm::{
-    ConnectionTrait, DbBackend, DbErr, EntityTrait, FromQueryResult, JoinType, ModelTrait,
-    QueryFilter, QueryResult, QuerySelect, RelationTrait, Select, Statement,
+    ColumnTrait, ConnectionTrait, DbBackend, DbErr, EntityTrait, FromQueryResult, JoinType,
+    ModelTrait, QueryFilter, QueryResult, QuerySelect, QueryTrait, RelationTrait, Select,
+    Statement,
 };
 use sea_query::{Asterisk, Expr, Func, SimpleExpr};
 use serde::{Deserialize, Serialize};
@@ -27,11 +28,13 @@ use trustify_common::{
 };
 use trustify_cvss::cvss3::{Cvss3Base, score::Score, severity::Severity};
 use trustify_entity::{
-    advisory, base_purl, cvss3, product_status, product_version, purl_status, qualified_purl, sbom,
-    sbom_node, sbom_package, sbom_package_purl_ref, status, version_range, versioned_purl,
-    vulnerability,
+    advisory, base_purl, cpe::CpeDto, cvss3, package_relates_to_package, product_status,
+    product_version, purl_status, qualified_purl, relationship::Relationship, sbom, sbom_node,
+    sbom_package, sbom_package_cpe_ref, sbom_package_purl_ref, status, version_range,
+    versioned_purl, vulnerability,
 };
 use utoipa::ToSchema;
+use uuid::Uuid;
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct SbomDetails {
@@ -49,135 +52,184 @@ impl SbomDetails {
         tx: &C,
         statuses: Vec<String>,
     ) -> Result<Option<SbomDetails>, Error> {
-        let mut query = sbom
-            .find_related(sbom_package::Entity)
-            .join(JoinType::Join, sbom_package::Relation::Node.def())
-            .join(JoinType::LeftJoin, sbom_package::Relation::Purl.def())
-            .join(
-                JoinType::LeftJoin,
-                sbom_package_purl_ref::Relation::Purl.def(),
-            )
-            .join(
-                JoinType::LeftJoin,
-                qualified_purl::Relation::VersionedPurl.def(),
-            )
-            .join(JoinType::LeftJoin, versioned_purl::Relation::BasePurl.def())
-            .join(JoinType::Join, base_purl::Relation::PurlStatus.def())
-            .join(JoinType::Join, purl_status::Relation::Status.def());
+        let relevant_advisory_info = advisory_info(&[sbom.sbom_id], &statuses, tx).await?;
 
-        if !statuses.is_empty() {
-            query = query
-                .filter(Expr::col((status::Entity, status::Column::Slug)).is_in(statuses.clone()));
-        }
+        let summary = SbomSummary::from_entity((sbom, node), service, tx).await?;
 
-        let mut relevant_advisory_info = query
-            .join(
-                JoinType::LeftJoin,
-                purl_status::Relation::VersionRange.def(),
-            )
-            .filter(SimpleExpr::FunctionCall(
-                Func::cust(VersionMatches)
-                    .arg(Expr::col((
-                        versioned_purl::Entity,
-                        versioned_purl::Column::Version,
-                    )))
-                    .arg(Expr::col((version_range::Entity, Asterisk))),
-            ))
-            .join(JoinType::LeftJoin, purl_status::Relation::ContextCpe.def())
-            .join(JoinType::Join, purl_status::Relation::Advisory.def())
-            .join(JoinType::Join, purl_status::Relation::Vulnerability.def())
-            .select_only()
-            .try_into_multi_model::<QueryCatcher>()?
-            .all(tx)
-            .await?;
+        Ok(match summary {
+            Some(summary) => Some(SbomDetails {
+                summary: summary.clone(),
+                advisories: SbomAdvisory::from_models(
+                    &sbom_cpes(summary.described_by.iter().flat_map(|each| each.cpe.iter())),
+                    &relevant_advisory_info,
+                    tx,
+                )
+                .await?,
+            }),
+            None => None,
+        })
+    }
+}
+
+/// Find the statuses which apply to the packages of the SBOMs
+///
+/// The SBOM of a status is the `sbom_id` of its package.
+pub(crate) async fn advisory_info<C: ConnectionTrait>(
+    sbom_ids: &[Uuid],
+    statuses: &[String],
+    tx: &C,
+) -> Result<Vec<QueryCatcher>, Error> {
+    let mut query = sbom_package::Entity::find()
+        .filter(sbom_package::Column::SbomId.is_in(sbom_ids.iter().copied()))
+        .join(JoinType::Join, sbom_package::Relation::Node.def())
+        .join(JoinType::LeftJoin, sbom_package::Relation::Purl.def())
+        .join(
+            JoinType::LeftJoin,
+            sbom_package_purl_ref::Relation::Purl.def(),
+        )
+        .join(
+            JoinType::LeftJoin,
+            qualified_purl::Relation::VersionedPurl.def(),
+        )
+        .join(JoinType::LeftJoin, versioned_purl::Relation::BasePurl.def())
+        .join(JoinType::Join, base_purl::Relation::PurlStatus.def())
+        .join(JoinType::Join, purl_status::Relation::Status.def());
+
+    if !statuses.is_empty() {
+        query = query
+            .filter(Expr::col((status::Entity, status::Column::Slug)).is_in(statuses.to_vec()));
+    }
 
-        // The query for now is in the raw form for couple of reasons
-        // First some of the join are not easily (or at all) doable using sea-orm concepts
-        // Second, it's much easier to iterate over query and work on it in this form
-        // than using the code
-        // It might be a good practice to start like this for complex query logic and
-        // turn it into a code once things stabilize
-        let product_advisory_info = r#"
-            SELECT
-                "advisory"."id" AS "advisory$id",
-                "advisory"."identifier" AS "advisory$identifier",
-                "advisory"."version" AS "advisory$version",
-                "advisory"."document_id" AS "advisory$document_id",
-                "advisory"."deprecated" AS "advisory$deprecated",
-                "advisory"."issuer_id" AS "advisory$issuer_id",
-                "advisory"."published" AS "advisory$published",
-                "advisory"."modified" AS "advisory$modified",
-                "advisory"."withdrawn" AS "advisory$withdrawn",
-                "advisory"."title" AS "advisory$title",
-                "advisory"."labels" AS "advisory$labels",
-                "advisory"."source_document_id" AS "advisory$source_document_id",
-                "vulnerability"."id" AS "vulnerability$id",
-                "vulnerability"."title" AS "vulnerability$title",
-                "vulnerability"."reserved" AS "vulnerability$reserved",
-                "vulnerability"."published" AS "vulnerability$published",
-                "vulnerability"."modified" AS "vulnerability$modified",
-                "vulnerability"."withdrawn" AS "vulnerability$withdrawn",
-                "vulnerability"."cwes" AS "vulnerability$cwes",
-                "base_purl"."id" AS "base_purl$id",
-                "base_purl"."type" AS "base_purl$type",
-                "base_purl"."namespace" AS "base_purl$namespace",
-                "base_purl"."name" AS "base_purl$name",
-                "versioned_purl"."id" AS "versioned_purl$id",
-                "versioned_purl"."base_purl_id" AS "versioned_purl$base_purl_id",
-                "versioned_purl"."version" AS "versioned_purl$version",
-                "qualified_purl"."id" AS "qualified_purl$id",
-                "qualified_purl"."versioned_purl_id" AS "qualified_purl$versioned_purl_id",
-                "qualified_purl"."qualifiers" AS "qualified_purl$qualifiers",
-                "qualified_purl"."purl" AS "qualified_purl$purl",
-                "sbom_package"."sbom_id" AS "sbom_package$sbom_id",
-                "sbom_package"."node_id" AS "sbom_package$node_id",
-                "sbom_package"."version" AS "sbom_package$version",
-                "sbom_node"."sbom_id" AS "sbom_node$sbom_id",
-                "sbom_node"."node_id" AS "sbom_node$node_id",
-                "sbom_node"."name" AS "sbom_node$name",
-                "status"."id" AS "status$id",
-                "status"."slug" AS "status$slug",
-                "status"."name" AS "status$name",
-                "status"."description" AS "status$description",
-                "cpe"."id" AS "cpe$id",
-                "cpe"."part" AS "cpe$part",
-                "cpe"."vendor" AS "cpe$vendor",
-                "cpe"."product" AS "cpe$product",
-                "cpe"."version" AS "cpe$version",
-                "cpe"."update" AS "cpe$update",
-                "cpe"."edition" AS "cpe$edition",
-                "cpe"."language" AS "cpe$language"
-            FROM "sbom"
-            -- find statuses that matches SBOMs
-            JOIN "product_version" ON "product_version"."sbom_id" = "sbom"."sbom_id"
-            JOIN "product" ON "product_version"."product_id" = "product"."id"
-            JOIN "cpe" ON "product"."cpe_key" = "cpe"."product"
-            JOIN "product_status" ON "cpe"."id" = "product_status"."context_cpe_id" AND product_status.package IS NOT NULL
-            JOIN "product_version_range" ON "product_status"."product_version_range_id" = "product_version_range"."id"
-            JOIN "version_range" ON "product_version_range"."version_range_id" = "version_range"."id" AND version_matches("product_version"."version", "version_range".*)
-
-            -- now find matching purls in these statuses
-            JOIN base_purl ON product_status.package = base_purl.name OR product_status.package LIKE CONCAT(base_purl.namespace, '/', base_purl.name)
-            JOIN "versioned_purl" ON "versioned_purl"."base_purl_id" = "base_purl"."id"
-            JOIN "qualified_purl" ON "qualified_purl"."versioned_purl_id" = "versioned_purl"."id"
-            join sbom_package_purl_ref ON sbom_package_purl_ref.qualified_purl_id = qualified_purl.id AND sbom_package_purl_ref.sbom_id = sbom.sbom_id
-            JOIN sbom_package on sbom_package.sbom_id = sbom_package_purl_ref.sbom_id AND sbom_package.node_id = sbom_package_purl_ref.node_id
-            JOIN sbom_node on sbom_node.sbom_id = sbom_package_purl_ref.sbom_id AND sbom_node.node_id = sbom_package_purl_ref.node_id
-
-            -- get basic status info
-            JOIN "status" ON "product_status"."status_id" = "status"."id"
-            JOIN "advisory" ON "product_status"."advisory_id" = "advisory"."id"
-            JOIN "vulnerability" ON "product_status"."vulnerability_id" = "vulnerability"."id"
-            WHERE
-            "sbom"."sbom_id" = $1
-            AND ($2::text[] = ARRAY[]::text[] OR "status"."slug" = ANY($2::text[]))
-            "#;
+    let mut relevant_advisory_info = query
+        .join(
+            JoinType::LeftJoin,
+            purl_status::Relation::VersionRange.def(),
+        )
+        .filter(SimpleExpr::FunctionCall(
+            Func::cust(VersionMatches)
+                .arg(Expr::col((
+                    versioned_purl::Entity,
+                    versioned_purl::Column::Version,
+                )))
+                .arg(Expr::col((version_range::Entity, Asterisk))),
+        ))
+        .join(JoinType::LeftJoin, purl_status::Relation::ContextCpe.def())
+        .join(JoinType::Join, purl_status::Relation::Advisory.def())
+        .join(JoinType::Join, purl_status::Relation::Vulnerability.def())
+        .select_only()
+        .try_into_multi_model::<QueryCatcher>()?
+        .all(tx)
+        .await?;
 
+    // The query for now is in the raw form for couple of reasons
+    // First some of the join are not easily (or at all) doable using sea-orm concepts
+    // Second, it's much easier to iterate over query and work on it in this form
+    // than using the code
+    // It might be a good practice to start like this for complex query logic and
+    // turn it into a code once things stabilize
+    let product_advisory_info = r#"
+        SELECT
+            "advisory"."id" AS "advisory$id",
+            "advisory"."identifier" AS "advisory$identifier",
+            "advisory"."version" AS "advisory$version",
+            "advisory"."document_id" AS "advisory$document_id",
+            "advisory"."deprecated" AS "advisory$deprecated",
+            "advisory"."issuer_id" AS "advisory$issuer_id",
+            "advisory"."published" AS "advisory$published",
+            "advisory"."modified" AS "advisory$modified",
+            "advisory"."withdrawn" AS "advisory$withdrawn",
+            "advisory"."title" AS "advisory$title",
+            "advisory"."labels" AS "advisory$labels",
+            "advisory"."source_document_id" AS "advisory$source_document_id",
+            "advisory"."format" AS "advisory$format",
+            "vulnerability"."id" AS "vulnerability$id",
+            "vulnerability"."title" AS "vulnerability$title",
+            "vulnerability"."reserved" AS "vulnerability$reserved",
+            "vulnerability"."published" AS "vulnerability$published",
+            "vulnerability"."modified" AS "vulnerability$modified",
+            "vulnerability"."withdrawn" AS "vulnerability$withdrawn",
+            "vulnerability"."cwes" AS "vulnerability$cwes",
+            "base_purl"."id" AS "base_purl$id",
+            "base_purl"."type" AS "base_purl$type",
+            "base_purl"."namespace" AS "base_purl$namespace",
+            "base_purl"."name" AS "base_purl$name",
+            "versioned_purl"."id" AS "versioned_purl$id",
+            "versioned_purl"."base_purl_id" AS "versioned_purl$base_purl_id",
+            "versioned_purl"."version" AS "versioned_purl$version",
+            "qualified_purl"."id" AS "qualified_purl$id",
+            "qualified_purl"."versioned_purl_id" AS "qualified_purl$versioned_purl_id",
+            "qualified_purl"."qualifiers" AS "qualified_purl$qualifiers",
+            "qualified_purl"."purl" AS "qualified_purl$purl",
+            "sbom_package"."sbom_id" AS "sbom_package$sbom_id",
+            "sbom_package"."node_id" AS "sbom_package$node_id",
+            "sbom_package"."version" AS "sbom_package$version",
+            "sbom_node"."sbom_id" AS "sbom_node$sbom_id",
+            "sbom_node"."node_id" AS "sbom_node$node_id",
+            "sbom_node"."name" AS "sbom_node$name",
+            "status"."id" AS "status$id",
+            "status"."slug" AS "status$slug",
+            "status"."name" AS "status$name",
+            "status"."description" AS "status$description",
+            "cpe"."id" AS "cpe$id",
+            "cpe"."part" AS "cpe$part",
+            "cpe"."vendor" AS "cpe$vendor",
+            "cpe"."product" AS "cpe$product",
+            "cpe"."version" AS "cpe$version",
+            "cpe"."update" AS "cpe$update",
+            "cpe"."edition" AS "cpe$edition",
+            "cpe"."language" AS "cpe$language"
+        FROM "sbom"
+        -- find statuses that matches SBOMs
+        JOIN "product_version" ON "product_version"."sbom_id" = "sbom"."sbom_id"
+        JOIN "product" ON "product_version"."product_id" = "product"."id"
+        JOIN "cpe" ON "product"."cpe_key" = "cpe"."product"
+        JOIN "product_status" ON "cpe"."id" = "product_status"."context_cpe_id" AND product_status.package IS NOT NULL
+        JOIN "product_version_range" ON "product_status"."product_version_range_id" = "product_version_range"."id"
+        JOIN "version_range" ON "product_version_range"."version_range_id" = "version_range"."id" AND version_matches("product_version"."version", "version_range".*)
+
+        -- now find matching purls in these statuses
+        JOIN base_purl ON product_status.package = base_purl.name OR product_status.package LIKE CONCAT(base_purl.namespace, '/', base_purl.name)
+        JOIN "versioned_purl" ON "versioned_purl"."base_purl_id" = "base_purl"."id"
+        JOIN "qualified_purl" ON "qualified_purl"."versioned_purl_id" = "versioned_purl"."id"
+        join sbom_package_purl_ref ON sbom_package_purl_ref.qualified_purl_id = qualified_purl.id AND sbom_package_purl_ref.sbom_id = sbom.sbom_id
+        JOIN sbom_package on sbom_package.sbom_id = sbom_package_purl_ref.sbom_id AND sbom_package.node_id = sbom_package_purl_ref.node_id
+        JOIN sbom_node on sbom_node.sbom_id = sbom_package_purl_ref.sbom_id AND sbom_node.node_id = sbom_package_purl_ref.node_id
+
+        -- get basic status info
+        JOIN "status" ON "product_status"."status_id" = "status"."id"
+        JOIN "advisory" ON "product_status"."advisory_id" = "advisory"."id"
+        JOIN "vulnerability" ON "product_status"."vulnerability_id" = "vulnerability"."id"
+        WHERE
+        "sbom"."sbom_id" = ANY($1)
+        AND ($2::text[] = ARRAY[]::text[] OR "status"."slug" = ANY($2::text[]))
+        "#;
+
+    let result: Vec<QueryResult> = tx
+        .query_all(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            product_advisory_info,
+            [sbom_ids.to_vec().into(), statuses.to_vec().into()],
+        ))
+        .await?;
+
+    relevant_advisory_info.extend(
+        result
+            .iter()
+            .map(|row| QueryCatcher::from_query_result(row, ""))
+            .collect::<Result<Vec<_>, _>>()?,
+    );
+
+    // statuses which apply through the CPE of a package, or a CPE/purl mapping
+    for correlated_advisory_info in [
+        include_str!("cpe_product_status.sql"),
+        include_str!("correlated_product_status.sql"),
+        include_str!("correlated_purl_status.sql"),
+    ] {
         let result: Vec<QueryResult> = tx
             .query_all(Statement::from_sql_and_values(
                 DbBackend::Postgres,
-                product_advisory_info,
-                [sbom.sbom_id.into(), statuses.into()],
+                correlated_advisory_info,
+                [sbom_ids.to_vec().into(), statuses.to_vec().into()],
             ))
             .await?;
 
@@ -187,22 +239,98 @@ impl SbomDetails {
                 .map(|row| QueryCatcher::from_query_result(row, ""))
                 .collect::<Result<Vec<_>, _>>()?,
         );
+    }
 
-        let summary = SbomSummary::from_entity((sbom, node), service, tx).await?;
+    Ok(relevant_advisory_info)
+}
 
-        Ok(match summary {
-            Some(summary) => Some(SbomDetails {
-                summary: summary.clone(),
-                advisories: SbomAdvisory::from_models(
-                    &summary.described_by,
-                    &relevant_advisory_info,
-                    tx,
-                )
-                .await?,
-            }),
-            None => None,
-        })
+/// Find the CPEs of the packages the SBOMs describe, by SBOM
+pub(crate) async fn described_cpes<C: ConnectionTrait>(
+    sbom_ids: &[Uuid],
+    tx: &C,
+) -> Result<HashMap<Uuid, Vec<OwnedUri>>, Error> {
+    let described = package_relates_to_package::Entity::find()
+        .select_only()
+        .column(package_relates_to_package::Column::SbomId)
+        .column(package_relates_to_package::Column::RightNodeId)
+        .filter(package_relates_to_package::Column::SbomId.is_in(sbom_ids.iter().copied()))
+        .filter(package_relates_to_package::Column::Relationship.eq(Relationship::Describes))
+        .into_query();
+
+    let rows = sbom_package_cpe_ref::Entity::find()
+        .find_also_related(trustify_entity::cpe::Entity)
+        .filter(
+            Expr::tuple([
+                Expr::col((
+                    sbom_package_cpe_ref::Entity,
+                    sbom_package_cpe_ref::Column::SbomId,
+                ))
+                .into(),
+                Expr::col((
+                    sbom_package_cpe_ref::Entity,
+                    sbom_package_cpe_ref::Column::NodeId,
+                ))
+                .into(),
+            ])
+            .in_subquery(described),
+        )
+        .all(tx)
+        .await?;
+
+    let mut result = HashMap::<_, Vec<_>>::new();
+    for (cpe_ref, cpe) in rows {
+        let Some(cpe) =
+            cpe.and_then(|cpe| trustify_common::cpe::Cpe::try_from(CpeDto::from(cpe)).ok())
+        else {
+            continue;
+        };
+        result
+            .entry(cpe_ref.sbom_id)
+            .or_default()
+            .push(cpe.to_string());
     }
+
+    Ok(result
+        .into_iter()
+        .map(|(sbom_id, cpes)| (sbom_id, sbom_cpes(&cpes)))
+        .collect())
+}
+
+/// Check the CPE context of a status against the CPEs of an SBOM
+///
+/// Returns `None` if the context excludes the SBOM. Otherwise, the context, if it has one.
+pub(crate) fn status_context(
+    context_cpe: Option<&trustify_entity::cpe::Model>,
+    sbom_cpes: &[OwnedUri],
+) -> Option<Option<OwnedUri>> {
+    let Some(Ok(status_cpe)) = context_cpe.map(TryInto::<OwnedUri>::try_into) else {
+        return Some(None);
+    };
+
+    sbom_cpes
+        .iter()
+        .any(|sbom_cpe| {
+            let status_version = status_cpe.version().to_string();
+            let sbom_version = sbom_cpe.version().to_string();
+            // This is a bit simplified logic, but it is tune with v1 parity.
+            // We need to investigate this more and apply proper version matching in the future
+            status_cpe.is_superset(sbom_cpe)
+                || status_version == "*"
+                || sbom_version.starts_with(&status_version)
+        })
+        .then_some(Some(status_cpe))
+}
+
+/// Parse the CPEs of the packages an SBOM describes, for matching them with status contexts
+pub(crate) fn sbom_cpes<'a>(cpes: impl IntoIterator<Item = &'a String>) -> Vec<OwnedUri> {
+    cpes.into_iter()
+        .flat_map(|e| {
+            let e = e.replace(":*:", "::");
+            let e = e.replace(":*", "");
+            let result = cpe::uri::Uri::parse(&e);
+            result.ok().map(|wfn| wfn.as_uri().to_owned())
+        })
+        .collect()
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
@@ -213,48 +341,20 @@ pub struct SbomAdvisory {
 }
 
 impl SbomAdvisory {
+    /// Build the advisories of an SBOM from its statuses
+    ///
+    /// Statuses with a CPE context only apply if it matches one of the CPEs of the SBOM.
     pub async fn from_models<C: ConnectionTrait>(
-        described_by: &[SbomPackage],
+        sbom_cpes: &[OwnedUri],
         statuses: &[QueryCatcher],
         tx: &C,
     ) -> Result<Vec<Self>, Error> {
         let mut advisories = HashMap::new();
 
-        let sbom_cpes = described_by
-            .iter()
-            .flat_map(|each| each.cpe.iter())
-            .flat_map(|e| {
-                let e = e.replace(":*:", "::");
-                let e = e.replace(":*", "");
-                let result = cpe::uri::Uri::parse(&e);
-                result.ok().map(|wfn| wfn.as_uri().to_owned())
-            })
-            .collect::<Vec<_>>();
-
-        'status: for each in statuses {
-            let status_cpe = if let Some(status_cpe) = &each.context_cpe {
-                let status_cpe: Result<OwnedUri, _> = status_cpe.try_into();
-                if let Ok(status_cpe) = status_cpe {
-                    if sbom_cpes.iter().any(|sbom_cpe| {
-                        let status_version = status_cpe.version().to_string();
-                        let sbom_version = sbom_cpe.version().to_string();
-                        // This is a bit simplified logic, but it is tune with v1 parity.
-                        // We need to investigate this more and apply proper version matching in the future
-                        status_cpe.is_superset(sbom_cpe)
-                            || status_version == "*"
-                            || sbom_version.starts_with(&status_version)
-                    }) {
-                        // status context is applicable, keep truckin'
-                    } else {
-                        // status context excludes this one, skip over
-                        continue 'status;
-                    }
-                    Some(status_cpe)
-                } else {
-                    None
-                }
-            } else {
-                None
+        for each in statuses {
+            let Some(status_cpe) = status_context(each.context_cpe.as_ref(), sbom_cpes) else {
+                // status context excludes this one, skip over
+                continue;
             };
 
             // if we got here, then there's either no context or the context matches this SBOM
@@ -312,15 +412,14 @@ impl SbomAdvisory {
                 id: each.sbom_package.node_id.clone(),
                 name: each.sbom_node.name.clone(),
                 version: each.sbom_package.version.clone(),
-                purl: vec![
-                    PurlSummary::from_entity(
-                        &each.base_purl,
-                        &each.versioned_purl,
-                        &each.qualified_purl,
-                        tx,
-                    )
-                    .await?,
-                ],
+                purl: match (&each.base_purl, &each.versioned_purl, &each.qualified_purl) {
+                    (Some(base_purl), Some(versioned_purl), Some(qualified_purl)) => vec![
+                        PurlSummary::from_entity(base_purl, versioned_purl, qualified_purl, tx)
+                            .await?,
+                    ],
+                    // matched through a CPE, the package has no purl
+                    _ => vec![],
+                },
                 cpe: vec![],
             });
         }
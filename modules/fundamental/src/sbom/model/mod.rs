@@ -5,8 +5,12 @@ use crate::{
     Error, purl::model::summary::purl::PurlSummary, source_document::model::SourceDocument,
 };
 use async_graphql::SimpleObject;
-use sea_orm::{ConnectionTrait, ModelTrait, PaginatorTrait, prelude::Uuid};
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, EntityTrait, LoaderTrait, ModelTrait, PaginatorTrait,
+    QueryFilter, QuerySelect, prelude::Uuid,
+};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use time::OffsetDateTime;
 use trustify_common::{cpe::Cpe, model::Paginated, purl::Purl};
 use trustify_entity::{
@@ -43,7 +47,15 @@ impl SbomHead {
         db: &C,
     ) -> Result<Self, Error> {
         let number_of_packages = sbom.find_related(sbom_package::Entity).count(db).await?;
-        Ok(Self {
+        Ok(Self::new(sbom, sbom_node, number_of_packages))
+    }
+
+    fn new(
+        sbom: &sbom::Model,
+        sbom_node: Option<sbom_node::Model>,
+        number_of_packages: u64,
+    ) -> Self {
+        Self {
             id: sbom.sbom_id,
             document_id: sbom.document_id.clone(),
             labels: sbom.labels.clone(),
@@ -54,7 +66,7 @@ impl SbomHead {
                 .unwrap_or("".to_string()),
             data_licenses: sbom.data_licenses.clone(),
             number_of_packages,
-        })
+        }
     }
 }
 
@@ -96,6 +108,52 @@ impl SbomSummary {
             None => None,
         })
     }
+
+    /// Convert many SBOMs at once, looking up the additional information for all of them in a
+    /// few queries. SBOMs without a node are skipped, like with [`Self::from_entity`].
+    pub async fn from_entities<C: ConnectionTrait>(
+        rows: Vec<(sbom::Model, Option<sbom_node::Model>)>,
+        service: &SbomService,
+        db: &C,
+    ) -> Result<Vec<SbomSummary>, Error> {
+        let (sboms, nodes): (Vec<_>, Vec<_>) =
+            rows.into_iter().filter(|(_, node)| node.is_some()).unzip();
+        let ids = sboms.iter().map(|sbom| sbom.sbom_id).collect::<Vec<_>>();
+
+        let mut described_by = service.describes_packages_of(&ids, db).await?;
+        let source_documents = sboms.load_one(source_document::Entity, db).await?;
+        let package_counts: HashMap<Uuid, i64> = sbom_package::Entity::find()
+            .filter(sbom_package::Column::SbomId.is_in(ids))
+            .select_only()
+            .column(sbom_package::Column::SbomId)
+            .column_as(sbom_package::Column::NodeId.count(), "count")
+            .group_by(sbom_package::Column::SbomId)
+            .into_tuple::<(Uuid, i64)>()
+            .all(db)
+            .await?
+            .into_iter()
+            .collect();
+
+        let mut summaries = Vec::with_capacity(sboms.len());
+
+        for ((sbom, node), source_document) in sboms.iter().zip(nodes).zip(source_documents) {
+            let number_of_packages = package_counts
+                .get(&sbom.sbom_id)
+                .copied()
+                .unwrap_or_default();
+
+            summaries.push(SbomSummary {
+                head: SbomHead::new(sbom, node, number_of_packages as u64),
+                source_document: match &source_document {
+                    Some(doc) => Some(SourceDocument::from_entity(doc).await?),
+                    None => None,
+                },
+                described_by: described_by.remove(&sbom.sbom_id).unwrap_or_default(),
+            });
+        }
+
+        Ok(summaries)
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, ToSchema, SimpleObject, Default)]
@@ -5,7 +5,10 @@ use sea_orm::{
 };
 use sea_query::Expr;
 use trustify_common::id::{Id, TrySelectForId};
-use trustify_entity::{labels::Labels, sbom};
+use trustify_entity::{
+    labels::{Labels, LabelsChange},
+    sbom,
+};
 
 impl SbomService {
     /// Set the labels of an SBOM
@@ -29,11 +32,11 @@ impl SbomService {
 
     /// Update the labels of an SBOM
     ///
-    /// Returns `Ok(Some(()))` if a document was found and updated. If no document was found, it will
-    /// return `Ok(None)`.
+    /// Returns `Ok(Some(change))` if a document was found and updated, containing the labels before
+    /// and after the update. If no document was found, it will return `Ok(None)`.
     ///
     /// The function will handle its own transaction.
-    pub async fn update_labels<F>(&self, id: Id, mutator: F) -> Result<Option<()>, Error>
+    pub async fn update_labels<F>(&self, id: Id, mutator: F) -> Result<Option<LabelsChange>, Error>
     where
         F: FnOnce(Labels) -> Labels,
     {
@@ -56,9 +59,11 @@ impl SbomService {
 
         // perform the mutation
 
-        let labels = result.labels.clone();
+        let sbom_id = result.sbom_id;
+        let before = result.labels.clone();
+        let after = mutator(before.clone());
         let mut result = result.into_active_model();
-        result.labels = Set(mutator(labels));
+        result.labels = Set(after.clone());
 
         // store
 
@@ -70,6 +75,10 @@ impl SbomService {
 
         // return
 
-        Ok(Some(()))
+        Ok(Some(LabelsChange {
+            id: sbom_id,
+            before,
+            after,
+        }))
     }
 }
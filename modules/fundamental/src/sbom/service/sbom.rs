@@ -1,36 +1,42 @@
 use super::SbomService;
 use crate::{
-    Error,
+    Error, export,
     purl::model::summary::purl::PurlSummary,
     sbom::model::{
         SbomExternalPackageReference, SbomNodeReference, SbomPackage, SbomPackageRelation,
         SbomSummary, Which, details::SbomDetails,
     },
 };
-use futures_util::{StreamExt, TryStreamExt, stream};
+use futures_util::{Stream, StreamExt, TryStreamExt, stream};
 use sea_orm::{
-    ColumnTrait, ConnectionTrait, DbErr, EntityTrait, FromQueryResult, IntoSimpleExpr, QueryFilter,
-    QueryOrder, QueryResult, QuerySelect, RelationTrait, Select, SelectColumns, prelude::Uuid,
+    ColumnTrait, ConnectionTrait, DbErr, EntityTrait, FromQueryResult, IntoIdentity,
+    IntoSimpleExpr, Order, QueryFilter, QueryOrder, QueryResult, QuerySelect, RelationTrait,
+    Select, SelectColumns, SelectTwo, Statement, StreamTrait, prelude::Uuid,
 };
 use sea_query::{Expr, Func, JoinType, SimpleExpr, extension::postgres::PgExpr};
 use serde::Deserialize;
 use serde_json::Value;
-use std::{collections::HashMap, fmt::Debug};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Debug,
+};
 use tracing::instrument;
 use trustify_common::{
     cpe::Cpe,
     db::{
         ArrayAgg, JsonBuildObject, ToJson,
-        limiter::{LimiterTrait, limit_selector},
+        facet::{FacetingTrait, Facets},
+        limiter::{LimiterAsModelTrait, LimiterTrait, limit_selector},
         multi_model::{FromQueryResultMultiModel, SelectIntoMultiModel},
         query::{Columns, Filtering, IntoColumns, Query},
     },
     id::{Id, TrySelectForId},
-    model::{Paginated, PaginatedResults},
+    model::{FacetCount, Paginated, PaginatedResults},
 };
 use trustify_entity::{
     advisory, base_purl,
     cpe::{self, CpeDto},
+    ingestion_history::DocumentKind,
     labels::Labels,
     package_relates_to_package,
     qualified_purl::{self, CanonicalPurl, Qualifiers},
@@ -39,6 +45,7 @@ use trustify_entity::{
     sbom_node, sbom_package, sbom_package_cpe_ref, sbom_package_purl_ref, source_document, status,
     versioned_purl, vulnerability,
 };
+use trustify_module_ingestor::graph::tombstone::record_tombstone;
 
 impl SbomService {
     async fn fetch_sbom<C: ConnectionTrait>(
@@ -87,11 +94,22 @@ impl SbomService {
         id: Uuid,
         connection: &C,
     ) -> Result<u64, Error> {
-        let query = sbom::Entity::delete_by_id(id);
+        let result = connection
+            .query_all(Statement::from_sql_and_values(
+                connection.get_database_backend(),
+                r#"DELETE FROM sbom WHERE sbom_id=$1 RETURNING source_document_id"#,
+                [id.into()],
+            ))
+            .await?;
+        let rows_affected = result.len();
 
-        let result = query.exec(connection).await?;
+        for row in result {
+            if let Some(source_document_id) = row.try_get_by_index::<Option<Uuid>>(0)? {
+                record_tombstone(DocumentKind::Sbom, source_document_id, connection).await?;
+            }
+        }
 
-        Ok(result.rows_affected)
+        Ok(rows_affected as u64)
     }
 
     /// fetch all SBOMs
@@ -102,24 +120,9 @@ impl SbomService {
         labels: impl Into<Labels>,
         connection: &C,
     ) -> Result<PaginatedResults<SbomSummary>, Error> {
-        let labels = labels.into();
-
-        let query = if labels.is_empty() {
-            sbom::Entity::find()
-        } else {
-            sbom::Entity::find().filter(Expr::col(sbom::Column::Labels).contains(labels))
-        };
-        let limiter = query
-            .join(JoinType::Join, sbom::Relation::SourceDocument.def())
-            .find_also_linked(SbomNodeLink)
-            .filtering_with(
-                search,
-                Columns::from_entity::<sbom::Entity>()
-                    .add_columns(sbom_node::Entity)
-                    .add_columns(source_document::Entity)
-                    .alias("sbom_node", "r0"),
-            )?
-            .limiting(connection, paginated.offset, paginated.limit);
+        let limiter = sboms(labels.into())
+            .filtering_with(search, sbom_columns())?
+            .limiting(connection, &paginated);
 
         let total = limiter.total().await?;
         let sboms = limiter.fetch().await?;
@@ -130,7 +133,44 @@ impl SbomService {
             .try_collect()
             .await?;
 
-        Ok(PaginatedResults { total, items })
+        Ok(PaginatedResults {
+            total,
+            items,
+            next: None,
+        })
+    }
+
+    /// Stream all SBOMs matching the query, without pagination
+    pub async fn stream_sboms<'a, C: ConnectionTrait + StreamTrait + Send>(
+        &'a self,
+        search: Query,
+        labels: impl Into<Labels>,
+        connection: &'a C,
+    ) -> Result<impl Stream<Item = Result<SbomSummary, Error>> + 'a, Error> {
+        let rows = sboms(labels.into())
+            .filtering_with(search, sbom_columns())?
+            .stream(connection)
+            .await?;
+
+        Ok(export::chunked(rows, move |rows| {
+            SbomSummary::from_entities(rows, self, connection)
+        }))
+    }
+
+    /// Count the SBOMs matching the query by the values of the requested facets, e.g.
+    /// `authors` or `labels:type`
+    pub async fn fetch_sbom_facets<C: ConnectionTrait>(
+        &self,
+        search: Query,
+        facets: Facets,
+        labels: impl Into<Labels>,
+        connection: &C,
+    ) -> Result<BTreeMap<String, Vec<FacetCount>>, Error> {
+        Ok(sboms(labels.into())
+            .filtering_with(search, sbom_columns())?
+            .faceting(connection, &facets, sbom_columns())?
+            .fetch()
+            .await?)
     }
 
     /// Fetch all packages from an SBOM.
@@ -145,44 +185,23 @@ impl SbomService {
         paginated: Paginated,
         connection: &C,
     ) -> Result<PaginatedResults<SbomPackage>, Error> {
-        let mut query = sbom_package::Entity::find()
-            .filter(sbom_package::Column::SbomId.eq(sbom_id))
-            .join(JoinType::Join, sbom_package::Relation::Node.def())
-            .select_only()
-            .column_as(sbom_package::Column::NodeId, "id")
-            .group_by(sbom_package::Column::NodeId)
-            .column_as(sbom_package::Column::Version, "version")
-            .group_by(sbom_package::Column::Version)
-            .column_as(sbom_node::Column::Name, "name")
-            .group_by(sbom_node::Column::Name)
-            .join(JoinType::LeftJoin, sbom_package::Relation::Purl.def())
-            .join(JoinType::LeftJoin, sbom_package::Relation::Cpe.def());
-
-        query = join_purls_and_cpes(query)
-            .filtering_with(
-                search,
-                sbom_package::Entity
-                    .columns()
-                    .add_columns(sbom_node::Entity)
-                    .add_columns(base_purl::Entity)
-                    .add_columns(sbom_package_cpe_ref::Entity)
-                    .add_columns(sbom_package_purl_ref::Entity),
-            )?
+        let columns = sbom_package_columns();
+        let keyset = search
+            .keyset(columns.clone())?
             // default order
-            .order_by_asc(sbom_node::Column::Name)
-            .order_by_asc(sbom_package::Column::Version);
+            .then(sbom_node::Column::Name, Order::Asc)
+            .then(sbom_package::Column::Version, Order::Asc)
+            // unique within the SBOM
+            .then(sbom_package::Column::NodeId, Order::Asc);
+
+        let query = sbom_packages(sbom_id).filtering_with(search, columns)?;
 
         // limit and execute
 
-        let limiter = limit_selector::<'_, _, _, _, PackageCatcher>(
-            connection,
-            query,
-            paginated.offset,
-            paginated.limit,
-        );
+        let limiter = query.paginating_as::<PackageCatcher>(connection, &paginated, keyset)?;
 
         let total = limiter.total().await?;
-        let packages = limiter.fetch().await?;
+        let (packages, next) = limiter.fetch_page().await?;
 
         // collect results
 
@@ -192,7 +211,33 @@ impl SbomService {
             items.push(package_from_row(row, connection).await?);
         }
 
-        Ok(PaginatedResults { items, total })
+        Ok(PaginatedResults { items, total, next })
+    }
+
+    /// Stream all packages of an SBOM matching the query, without pagination
+    pub async fn stream_sbom_packages<'a, C: ConnectionTrait + StreamTrait + Send>(
+        &self,
+        sbom_id: Uuid,
+        search: Query,
+        connection: &'a C,
+    ) -> Result<impl Stream<Item = Result<SbomPackage, Error>> + 'a, Error> {
+        let rows = sbom_packages(sbom_id)
+            .filtering_with(search, sbom_package_columns())?
+            // default order
+            .order_by_asc(sbom_node::Column::Name)
+            .order_by_asc(sbom_package::Column::Version)
+            .order_by_asc(sbom_package::Column::NodeId)
+            .into_model::<PackageCatcher>()
+            .stream(connection)
+            .await?;
+
+        Ok(export::chunked(rows, move |rows| async move {
+            let mut packages = Vec::with_capacity(rows.len());
+            for row in rows {
+                packages.push(package_from_row(row, connection).await?);
+            }
+            Ok(packages)
+        }))
     }
 
     /// Get all packages describing the SBOM.
@@ -216,6 +261,61 @@ impl SbomService {
         .map(|r| r.map(|rel| rel.package))
     }
 
+    /// Get all packages describing each of the SBOMs, like [`Self::describes_packages`], but
+    /// using a single query for all of them.
+    #[instrument(skip(self, db), err(level=tracing::Level::INFO))]
+    pub async fn describes_packages_of<C: ConnectionTrait>(
+        &self,
+        sbom_ids: &[Uuid],
+        db: &C,
+    ) -> Result<HashMap<Uuid, Vec<SbomPackage>>, Error> {
+        let query = package_relates_to_package::Entity::find()
+            .filter(package_relates_to_package::Column::SbomId.is_in(sbom_ids.iter().copied()))
+            .filter(package_relates_to_package::Column::Relationship.eq(Relationship::Describes))
+            .select_only()
+            .select_column(package_relates_to_package::Column::SbomId)
+            .group_by(package_relates_to_package::Column::SbomId)
+            .select_column_as(sbom_node::Column::NodeId, "id")
+            .group_by(sbom_node::Column::NodeId)
+            .select_column_as(sbom_node::Column::Name, "name")
+            .group_by(sbom_node::Column::Name)
+            .select_column_as(sbom_package::Column::Version, "version")
+            .group_by(sbom_package::Column::Version)
+            // join the described side
+            .join(
+                JoinType::Join,
+                package_relates_to_package::Relation::Right.def(),
+            )
+            .join(JoinType::Join, sbom_node::Relation::Package.def())
+            .join(JoinType::LeftJoin, sbom_package::Relation::Purl.def())
+            .join(JoinType::LeftJoin, sbom_package::Relation::Cpe.def());
+
+        let rows = join_purls_and_cpes(query)
+            .into_model::<DescribedCatcher>()
+            .all(db)
+            .await?;
+
+        let mut result = HashMap::<_, Vec<_>>::new();
+
+        for row in rows {
+            let package = package_from_row(
+                PackageCatcher {
+                    id: row.id,
+                    name: row.name,
+                    version: row.version,
+                    purls: row.purls,
+                    cpes: row.cpes,
+                    relationship: Some(Relationship::Describes),
+                },
+                db,
+            )
+            .await?;
+            result.entry(row.sbom_id).or_default().push(package);
+        }
+
+        Ok(result)
+    }
+
     #[instrument(skip(self, connection), err(level=tracing::Level::INFO))]
     pub async fn count_related_sboms<C: ConnectionTrait>(
         &self,
@@ -321,7 +421,7 @@ impl SbomService {
 
         // limit and execute
 
-        let limiter = query.limiting(connection, paginated.offset, paginated.limit);
+        let limiter = query.limiting(connection, &paginated);
 
         let total = limiter.total().await?;
         let sboms = limiter.fetch().await?;
@@ -334,7 +434,11 @@ impl SbomService {
             .try_collect()
             .await?;
 
-        Ok(PaginatedResults { items, total })
+        Ok(PaginatedResults {
+            items,
+            total,
+            next: None,
+        })
     }
 
     /// Fetch all related packages in the context of an SBOM.
@@ -416,12 +520,7 @@ impl SbomService {
 
         // limit and execute
 
-        let limiter = limit_selector::<'_, _, _, _, PackageCatcher>(
-            db,
-            query,
-            paginated.offset,
-            paginated.limit,
-        );
+        let limiter = limit_selector::<'_, _, _, _, PackageCatcher>(db, query, &paginated);
 
         let total = limiter.total().await?;
         let packages = limiter.fetch().await?;
@@ -439,7 +538,11 @@ impl SbomService {
             }
         }
 
-        Ok(PaginatedResults { items, total })
+        Ok(PaginatedResults {
+            items,
+            total,
+            next: None,
+        })
     }
 
     /// A simplified version of [`Self::fetch_related_packages`].
@@ -480,6 +583,56 @@ impl SbomService {
     }
 }
 
+/// Select the SBOMs having all the given labels, along with their describing node
+fn sboms(labels: Labels) -> SelectTwo<sbom::Entity, sbom_node::Entity> {
+    let query = if labels.is_empty() {
+        sbom::Entity::find()
+    } else {
+        sbom::Entity::find().filter(Expr::col(sbom::Column::Labels).contains(labels))
+    };
+    query
+        .join(JoinType::Join, sbom::Relation::SourceDocument.def())
+        .find_also_linked(SbomNodeLink)
+}
+
+/// The columns available for filtering and faceting SBOMs
+pub fn sbom_columns() -> Columns {
+    Columns::from_entity::<sbom::Entity>()
+        .add_columns(sbom_node::Entity)
+        .add_columns(source_document::Entity)
+        .full_text(sbom::Entity, "search".into_identity())
+        .full_text(sbom_node::Entity, "search".into_identity())
+        .alias("sbom_node", "r0")
+}
+
+/// Select the packages of an SBOM, along with their PURLs and CPEs
+fn sbom_packages(sbom_id: Uuid) -> Select<sbom_package::Entity> {
+    let query = sbom_package::Entity::find()
+        .filter(sbom_package::Column::SbomId.eq(sbom_id))
+        .join(JoinType::Join, sbom_package::Relation::Node.def())
+        .select_only()
+        .column_as(sbom_package::Column::NodeId, "id")
+        .group_by(sbom_package::Column::NodeId)
+        .column_as(sbom_package::Column::Version, "version")
+        .group_by(sbom_package::Column::Version)
+        .column_as(sbom_node::Column::Name, "name")
+        .group_by(sbom_node::Column::Name)
+        .join(JoinType::LeftJoin, sbom_package::Relation::Purl.def())
+        .join(JoinType::LeftJoin, sbom_package::Relation::Cpe.def());
+
+    join_purls_and_cpes(query)
+}
+
+/// The columns available for filtering the packages of an SBOM
+fn sbom_package_columns() -> Columns {
+    sbom_package::Entity
+        .columns()
+        .add_columns(sbom_node::Entity)
+        .add_columns(base_purl::Entity)
+        .add_columns(sbom_package_cpe_ref::Entity)
+        .add_columns(sbom_package_purl_ref::Entity)
+}
+
 /// Join CPE and PURL information.
 ///
 /// Given a select over something which already joins sbom_package_purl_ref and
@@ -564,6 +717,17 @@ struct PackageCatcher {
     relationship: Option<Relationship>,
 }
 
+/// A "package row" along with the SBOM it describes
+#[derive(FromQueryResult)]
+struct DescribedCatcher {
+    sbom_id: Uuid,
+    id: String,
+    name: String,
+    version: Option<String>,
+    purls: Vec<Value>,
+    cpes: Vec<Value>,
+}
+
 /// Convert values from a "package row" into an SBOM package
 async fn package_from_row<C: ConnectionTrait>(
     row: PackageCatcher,
@@ -655,9 +819,9 @@ struct PurlDto {
 #[derive(Debug)]
 pub struct QueryCatcher {
     pub advisory: advisory::Model,
-    pub base_purl: base_purl::Model,
-    pub versioned_purl: versioned_purl::Model,
-    pub qualified_purl: qualified_purl::Model,
+    pub base_purl: Option<base_purl::Model>,
+    pub versioned_purl: Option<versioned_purl::Model>,
+    pub qualified_purl: Option<qualified_purl::Model>,
     pub sbom_package: sbom_package::Model,
     pub sbom_node: sbom_node::Model,
     pub vulnerability: vulnerability::Model,
@@ -670,9 +834,17 @@ impl FromQueryResult for QueryCatcher {
         Ok(Self {
             advisory: Self::from_query_result_multi_model(res, "", advisory::Entity)?,
             vulnerability: Self::from_query_result_multi_model(res, "", vulnerability::Entity)?,
-            base_purl: Self::from_query_result_multi_model(res, "", base_purl::Entity)?,
-            versioned_purl: Self::from_query_result_multi_model(res, "", versioned_purl::Entity)?,
-            qualified_purl: Self::from_query_result_multi_model(res, "", qualified_purl::Entity)?,
+            base_purl: Self::from_query_result_multi_model_optional(res, "", base_purl::Entity)?,
+            versioned_purl: Self::from_query_result_multi_model_optional(
+                res,
+                "",
+                versioned_purl::Entity,
+            )?,
+            qualified_purl: Self::from_query_result_multi_model_optional(
+                res,
+                "",
+                qualified_purl::Entity,
+            )?,
             sbom_package: Self::from_query_result_multi_model(res, "", sbom_package::Entity)?,
             sbom_node: Self::from_query_result_multi_model(res, "", sbom_node::Entity)?,
             context_cpe: Self::from_query_result_multi_model_optional(res, "", cpe::Entity)?,
@@ -703,7 +875,7 @@ mod test {
     use test_log::test;
     use trustify_common::db::query::q;
     use trustify_common::hashing::Digests;
-    use trustify_entity::labels::Labels;
+    use trustify_entity::{document_tombstone, labels::Labels};
     use trustify_test_context::TrustifyContext;
 
     #[test_context(TrustifyContext)]
@@ -766,7 +938,7 @@ mod test {
             .await?;
 
         log::debug!("{:#?}", fetched.items);
-        assert_eq!(1, fetched.total);
+        assert_eq!(Some(1), fetched.total);
 
         Ok(())
     }
@@ -826,7 +998,7 @@ mod test {
                 &ctx.db,
             )
             .await?;
-        assert_eq!(1, fetched.total);
+        assert_eq!(Some(1), fetched.total);
 
         let fetched = service
             .fetch_sboms(
@@ -836,7 +1008,7 @@ mod test {
                 &ctx.db,
             )
             .await?;
-        assert_eq!(2, fetched.total);
+        assert_eq!(Some(2), fetched.total);
 
         let fetched = service
             .fetch_sboms(
@@ -846,7 +1018,7 @@ mod test {
                 &ctx.db,
             )
             .await?;
-        assert_eq!(0, fetched.total);
+        assert_eq!(Some(0), fetched.total);
 
         let fetched = service
             .fetch_sboms(
@@ -856,12 +1028,12 @@ mod test {
                 &ctx.db,
             )
             .await?;
-        assert_eq!(0, fetched.total);
+        assert_eq!(Some(0), fetched.total);
 
         let fetched = service
             .fetch_sboms(Query::default(), Paginated::default(), (), &ctx.db)
             .await?;
-        assert_eq!(3, fetched.total);
+        assert_eq!(Some(3), fetched.total);
 
         let fetched = service
             .fetch_sboms(
@@ -871,7 +1043,7 @@ mod test {
                 &ctx.db,
             )
             .await?;
-        assert_eq!(1, fetched.total);
+        assert_eq!(Some(1), fetched.total);
 
         Ok(())
     }
@@ -897,6 +1069,12 @@ mod test {
         log::debug!("{:#?}", affected);
         assert_eq!(1, affected);
 
+        // the deletion must be recorded, for incremental snapshots
+
+        let tombstones = document_tombstone::Entity::find().all(&ctx.db).await?;
+        assert_eq!(tombstones.len(), 1);
+        assert_eq!(tombstones[0].kind, DocumentKind::Sbom);
+
         let affected = service.delete_sbom(sbom_v1.sbom.sbom_id, &ctx.db).await?;
 
         log::debug!("{:#?}", affected);
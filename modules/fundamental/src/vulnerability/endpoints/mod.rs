@@ -5,18 +5,21 @@ use crate::{
     Error,
     Error::Internal,
     endpoints::Deprecation,
+    export::{self, Export, ExportFormat},
     vulnerability::{
         model::{VulnerabilityDetails, VulnerabilitySummary},
         service::VulnerabilityService,
     },
 };
-use actix_web::{HttpResponse, Responder, delete, get, web};
+use actix_web::{HttpRequest, HttpResponse, Responder, delete, get, web};
 use sea_orm::TransactionTrait;
+use std::collections::BTreeMap;
 use trustify_auth::{DeleteVulnerability, ReadAdvisory, authorizer::Require};
 use trustify_common::{
-    db::{Database, query::Query},
-    model::{Paginated, PaginatedResults},
+    db::{Database, facet::Facets, query::Query},
+    model::{FacetCount, Paginated, PaginatedResults},
 };
+use trustify_module_audit::recorder::{Action, Audit, Event, TargetKind};
 
 pub fn configure(config: &mut utoipa_actix_web::service_config::ServiceConfig, db: Database) {
     let service = VulnerabilityService::new();
@@ -24,6 +27,7 @@ pub fn configure(config: &mut utoipa_actix_web::service_config::ServiceConfig, d
         .app_data(web::Data::new(service))
         .app_data(web::Data::new(db))
         .service(all)
+        .service(facets)
         .service(delete)
         .service(get);
 }
@@ -34,21 +38,43 @@ pub fn configure(config: &mut utoipa_actix_web::service_config::ServiceConfig, d
     params(
         Query,
         Paginated,
+        Export,
     ),
     responses(
-        (status = 200, description = "Matching vulnerabilities", body = PaginatedResults<VulnerabilitySummary>),
+        (status = 200, description = "Matching vulnerabilities", content(
+            (PaginatedResults<VulnerabilitySummary> = "application/json"),
+            (String = "text/csv"),
+            (String = "application/x-ndjson"),
+        )),
     ),
 )]
 #[get("/v2/vulnerability")]
 /// List vulnerabilities
+///
+/// Using an `Accept` header of `text/csv` or `application/x-ndjson`, all matching
+/// vulnerabilities are streamed in that format, without pagination.
 pub async fn all(
+    req: HttpRequest,
     state: web::Data<VulnerabilityService>,
     db: web::Data<Database>,
     web::Query(search): web::Query<Query>,
     web::Query(paginated): web::Query<Paginated>,
     web::Query(Deprecation { deprecated }): web::Query<Deprecation>,
+    web::Query(export): web::Query<Export>,
     _: Require<ReadAdvisory>,
 ) -> actix_web::Result<impl Responder> {
+    if let Some(format) = ExportFormat::accepted(&req) {
+        return Ok(export::respond(format, export, move |sink| async move {
+            sink.send_all(
+                state
+                    .stream_vulnerabilities(search, deprecated, db.as_ref())
+                    .await?,
+            )
+            .await
+        })
+        .await?);
+    }
+
     Ok(HttpResponse::Ok().json(
         state
             .fetch_vulnerabilities(search, paginated, deprecated, db.as_ref())
@@ -56,6 +82,29 @@ pub async fn all(
     ))
 }
 
+#[utoipa::path(
+    tag = "vulnerability",
+    operation_id = "listVulnerabilityFacets",
+    params(
+        Query,
+        Facets,
+    ),
+    responses(
+        (status = 200, description = "Counts of the matching vulnerabilities, by facet", body = BTreeMap<String, Vec<FacetCount>>),
+    ),
+)]
+#[get("/v2/vulnerability/facets")]
+/// Count vulnerabilities by the values of facets
+pub async fn facets(
+    state: web::Data<VulnerabilityService>,
+    db: web::Data<Database>,
+    web::Query(search): web::Query<Query>,
+    web::Query(facets): web::Query<Facets>,
+    _: Require<ReadAdvisory>,
+) -> actix_web::Result<impl Responder> {
+    Ok(HttpResponse::Ok().json(state.fetch_facets(search, facets, db.as_ref()).await?))
+}
+
 #[utoipa::path(
     tag = "vulnerability",
     operation_id = "getVulnerability",
@@ -103,6 +152,7 @@ pub async fn delete(
     state: web::Data<VulnerabilityService>,
     db: web::Data<Database>,
     id: web::Path<String>,
+    audit: Audit,
     _: Require<DeleteVulnerability>,
 ) -> Result<impl Responder, Error> {
     let tx = db.begin().await?;
@@ -124,6 +174,13 @@ pub async fn delete(
             0 => Ok(HttpResponse::NotFound().finish()),
             1 => {
                 tx.commit().await?;
+                audit
+                    .record(Event::new(
+                        Action::Delete,
+                        TargetKind::Vulnerability,
+                        &vuln.head.identifier,
+                    ))
+                    .await;
                 Ok(HttpResponse::Ok().json(vuln))
             }
             _ => Err(Internal("Unexpected number of rows affected".into())),
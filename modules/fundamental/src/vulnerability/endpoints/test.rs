@@ -1,8 +1,8 @@
 use crate::test::caller;
 use crate::vulnerability::model::VulnerabilitySummary;
 use actix_http::StatusCode;
-use actix_web::test::TestRequest;
-use serde_json::Value;
+use actix_web::{http::header, test::TestRequest};
+use serde_json::{Value, json};
 use test_context::test_context;
 use test_log::test;
 use time::{OffsetDateTime, macros::datetime};
@@ -35,6 +35,7 @@ async fn all_vulnerabilities(ctx: &TrustifyContext) -> Result<(), anyhow::Error>
                 published: Some(OffsetDateTime::now_utc()),
                 modified: None,
                 withdrawn: None,
+                format: None,
             },
             &ctx.db,
         )
@@ -74,6 +75,7 @@ async fn all_vulnerabilities(ctx: &TrustifyContext) -> Result<(), anyhow::Error>
                 published: Some(OffsetDateTime::now_utc()),
                 modified: None,
                 withdrawn: None,
+                format: None,
             },
             &ctx.db,
         )
@@ -123,6 +125,7 @@ async fn one_vulnerability(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
                 published: Some(OffsetDateTime::now_utc()),
                 modified: None,
                 withdrawn: None,
+                format: None,
             },
             &ctx.db,
         )
@@ -163,6 +166,7 @@ async fn one_vulnerability(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
                 published: Some(OffsetDateTime::now_utc()),
                 modified: None,
                 withdrawn: None,
+                format: None,
             },
             &ctx.db,
         )
@@ -218,6 +222,7 @@ async fn delete_vulnerability(ctx: &TrustifyContext) -> Result<(), anyhow::Error
                 published: Some(OffsetDateTime::now_utc()),
                 modified: None,
                 withdrawn: None,
+                format: None,
             },
             &ctx.db,
         )
@@ -258,6 +263,7 @@ async fn delete_vulnerability(ctx: &TrustifyContext) -> Result<(), anyhow::Error
                 published: Some(OffsetDateTime::now_utc()),
                 modified: None,
                 withdrawn: None,
+                format: None,
             },
             &ctx.db,
         )
@@ -340,3 +346,107 @@ async fn get_vulnerability(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
 
     Ok(())
 }
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn vulnerability_facets(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    ctx.ingest_documents(["mitre/CVE-2024-27088.json", "mitre/CVE-2024-28111.json"])
+        .await?;
+
+    let app = caller(ctx).await?;
+    let facets: Value = app
+        .call_and_read_body_json(
+            TestRequest::get()
+                .uri("/api/v2/vulnerability/facets?facets=cwes,published:month")
+                .to_request(),
+        )
+        .await;
+
+    log::debug!("{facets:#?}");
+
+    assert_eq!(
+        facets,
+        json!({
+            "cwes": [
+                { "value": "CWE-1236", "count": 1 },
+                { "value": "CWE-1333", "count": 1 },
+                { "value": "CWE-400", "count": 1 },
+            ],
+            "published:month": [
+                { "value": "2024-02", "count": 1 },
+                { "value": "2024-03", "count": 1 },
+            ],
+        })
+    );
+
+    // only counting the matching vulnerabilities
+    let facets: Value = app
+        .call_and_read_body_json(
+            TestRequest::get()
+                .uri("/api/v2/vulnerability/facets?facets=cwes&q=id%3DCVE-2024-27088")
+                .to_request(),
+        )
+        .await;
+
+    assert_eq!(
+        facets,
+        json!({
+            "cwes": [
+                { "value": "CWE-1333", "count": 1 },
+                { "value": "CWE-400", "count": 1 },
+            ],
+        })
+    );
+
+    // unknown fields are rejected
+    let response = app
+        .call_service(
+            TestRequest::get()
+                .uri("/api/v2/vulnerability/facets?facets=foo")
+                .to_request(),
+        )
+        .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn export_vulnerabilities(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    ctx.ingest_documents(["mitre/CVE-2024-27088.json", "mitre/CVE-2024-28111.json"])
+        .await?;
+
+    let app = caller(ctx).await?;
+
+    let request = TestRequest::get()
+        .uri("/api/v2/vulnerability?columns=identifier&sort=identifier&limit=1")
+        .insert_header((header::ACCEPT, "text/csv"))
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get(header::CONTENT_TYPE),
+        Some(&header::HeaderValue::from_static("text/csv"))
+    );
+    // all vulnerabilities, regardless of the limit
+    let body = actix_web::test::read_body(response).await;
+    assert_eq!(body, "identifier\nCVE-2024-27088\nCVE-2024-28111\n");
+
+    // the same items as the paginated result
+    let request = TestRequest::get()
+        .uri("/api/v2/vulnerability?sort=identifier")
+        .to_request();
+    let expected: PaginatedResults<Value> = app.call_and_read_body_json(request).await;
+    let request = TestRequest::get()
+        .uri("/api/v2/vulnerability?sort=identifier")
+        .insert_header((header::ACCEPT, "application/x-ndjson"))
+        .to_request();
+    let body = app.call_and_read_body(request).await;
+    let items = serde_json::Deserializer::from_slice(&body)
+        .into_iter()
+        .collect::<Result<Vec<Value>, _>>()?;
+    assert_eq!(items, expected.items);
+
+    Ok(())
+}
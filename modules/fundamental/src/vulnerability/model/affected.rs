@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// An SBOM, containing packages affected by a vulnerability
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema, PartialEq, Eq)]
+pub struct AffectedSbom {
+    #[serde(with = "uuid::serde::urn")]
+    #[schema(value_type=String)]
+    pub sbom_id: Uuid,
+    pub name: String,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub published: Option<OffsetDateTime>,
+    /// The product versions shipping this SBOM
+    pub products: Vec<AffectedProduct>,
+    /// The affected packages
+    pub packages: Vec<AffectedPackage>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema, PartialEq, Eq, PartialOrd, Ord)]
+pub struct AffectedProduct {
+    pub name: String,
+    pub version: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema, PartialEq, Eq, PartialOrd, Ord)]
+pub struct AffectedPackage {
+    pub name: String,
+    pub purl: String,
+    /// The advisory stating the package is affected
+    pub advisory: String,
+}
@@ -1,6 +1,8 @@
+mod affected;
 mod details;
 mod summary;
 
+pub use affected::*;
 use async_graphql::SimpleObject;
 pub use details::*;
 use sea_orm::{ColumnTrait, ConnectionTrait, ModelTrait, QueryFilter};
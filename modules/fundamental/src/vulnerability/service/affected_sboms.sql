@@ -0,0 +1,29 @@
+-- find all SBOMs containing packages affected by a vulnerability, along with the products
+-- shipping those SBOMs
+SELECT DISTINCT
+    "sbom"."sbom_id" AS "sbom_id",
+    "sbom_root"."name" AS "sbom_name",
+    "sbom"."published" AS "published",
+    "package"."name" AS "package_name",
+    "base_purl"."type" AS "purl_type",
+    "base_purl"."namespace" AS "purl_namespace",
+    "base_purl"."name" AS "purl_name",
+    "versioned_purl"."version" AS "purl_version",
+    "advisory"."identifier" AS "advisory",
+    "product"."name" AS "product_name",
+    "product_version"."version" AS "product_version"
+FROM "purl_status"
+JOIN "status" ON "status"."id" = "purl_status"."status_id" AND "status"."slug" = 'affected'
+JOIN "advisory" ON "advisory"."id" = "purl_status"."advisory_id" AND NOT "advisory"."deprecated"
+JOIN "version_range" ON "version_range"."id" = "purl_status"."version_range_id"
+JOIN "base_purl" ON "base_purl"."id" = "purl_status"."base_purl_id"
+JOIN "versioned_purl" ON "versioned_purl"."base_purl_id" = "base_purl"."id" AND version_matches("versioned_purl"."version", "version_range".*)
+JOIN "qualified_purl" ON "qualified_purl"."versioned_purl_id" = "versioned_purl"."id"
+JOIN "sbom_package_purl_ref" ON "sbom_package_purl_ref"."qualified_purl_id" = "qualified_purl"."id"
+JOIN "sbom_node" AS "package" ON "package"."sbom_id" = "sbom_package_purl_ref"."sbom_id" AND "package"."node_id" = "sbom_package_purl_ref"."node_id"
+JOIN "sbom" ON "sbom"."sbom_id" = "sbom_package_purl_ref"."sbom_id"
+LEFT JOIN "sbom_node" AS "sbom_root" ON "sbom_root"."sbom_id" = "sbom"."sbom_id" AND "sbom_root"."node_id" = "sbom"."node_id"
+LEFT JOIN "product_version" ON "product_version"."sbom_id" = "sbom"."sbom_id"
+LEFT JOIN "product" ON "product"."id" = "product_version"."product_id"
+WHERE "purl_status"."vulnerability_id" = $1
+ORDER BY "sbom"."sbom_id", "package_name", "advisory"
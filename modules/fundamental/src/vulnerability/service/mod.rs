@@ -1,20 +1,30 @@
 use crate::{
-    Error,
-    vulnerability::model::{VulnerabilityDetails, VulnerabilitySummary},
+    Error, export,
+    vulnerability::model::{
+        AffectedPackage, AffectedProduct, AffectedSbom, VulnerabilityDetails, VulnerabilitySummary,
+    },
+};
+use futures_util::Stream;
+use sea_orm::{
+    DbBackend, EntityTrait, FromQueryResult, IntoIdentity, QuerySelect, QueryTrait, Statement,
+    StreamTrait, prelude::*,
 };
-use sea_orm::{EntityTrait, FromQueryResult, IntoIdentity, QuerySelect, QueryTrait, prelude::*};
-use sea_query::{ColumnRef, Func, IntoColumnRef, IntoIden, SimpleExpr};
+use sea_query::{ColumnRef, Expr, Func, IntoColumnRef, IntoIden, SimpleExpr};
+use std::collections::BTreeMap;
+use time::OffsetDateTime;
 use trustify_common::{
     db::{
+        facet::{FacetingTrait, Facets},
         limiter::LimiterAsModelTrait,
         multi_model::{FromQueryResultMultiModel, SelectIntoMultiModel},
         query::{Columns, Filtering, Query},
     },
-    model::{Paginated, PaginatedResults},
+    model::{FacetCount, Paginated, PaginatedResults},
+    purl::Purl,
 };
 use trustify_entity::{
     cvss3::{self, Severity},
-    vulnerability,
+    vulnerability, vulnerability_description,
 };
 use trustify_module_ingestor::common::Deprecation;
 
@@ -33,75 +43,9 @@ impl VulnerabilityService {
         deprecation: Deprecation,
         connection: &C,
     ) -> Result<PaginatedResults<VulnerabilitySummary>, Error> {
-        let inner_query = vulnerability::Entity::find()
-            .left_join(cvss3::Entity)
-            .expr_as_(
-                SimpleExpr::FunctionCall(Func::avg(SimpleExpr::Column(
-                    trustify_entity::cvss3::Column::Score.into_column_ref(),
-                ))),
-                "average_score",
-            )
-            .expr_as_(
-                SimpleExpr::FunctionCall(Func::cust("cvss3_severity".into_identity()).arg(
-                    SimpleExpr::FunctionCall(Func::avg(SimpleExpr::Column(
-                        trustify_entity::cvss3::Column::Score.into_column_ref(),
-                    ))),
-                )),
-                "average_severity",
-            )
-            .group_by(vulnerability::Column::Id);
-
-        let mut outer_query = vulnerability::Entity::find();
-
-        // Alias the inner query as exactly the table the entity is expecting
-        // so that column aliases link up correctly.
-        QueryTrait::query(&mut outer_query)
-            .from_clear()
-            .from_subquery(inner_query.into_query(), "vulnerability".into_identity());
-
-        let limiter = outer_query
-            .column_as(
-                SimpleExpr::Column(ColumnRef::Column(
-                    "average_score".into_identity().into_iden(),
-                )),
-                "average_score",
-            )
-            .column_as(
-                SimpleExpr::Column(ColumnRef::Column(
-                    "average_severity".into_identity().into_iden(),
-                ))
-                .cast_as("TEXT".into_identity()),
-                "average_severity",
-            )
-            .filtering_with(
-                search,
-                Columns::from_entity::<vulnerability::Entity>()
-                    .add_column("average_score", ColumnType::Decimal(None).def())
-                    .add_column(
-                        "average_severity",
-                        ColumnType::Enum {
-                            name: "cvss3_severity".into_identity().into_iden(),
-                            variants: vec![
-                                "none".into_identity().into_iden(),
-                                "low".into_identity().into_iden(),
-                                "medium".into_identity().into_iden(),
-                                "high".into_identity().into_iden(),
-                                "critical".into_identity().into_iden(),
-                            ],
-                        }
-                        .def(),
-                    )
-                    .translator(|f, op, v| match (f, v) {
-                        // v = "" for all sort fields
-                        ("average_severity", "") => Some(format!("average_score:{op}")),
-                        _ => None,
-                    }),
-            )?
-            .try_limiting_as_multi_model::<VulnerabilityCatcher>(
-                connection,
-                paginated.offset,
-                paginated.limit,
-            )?;
+        let limiter = vulnerabilities()
+            .filtering_with(search, columns())?
+            .try_limiting_as_multi_model::<VulnerabilityCatcher>(connection, &paginated)?;
 
         let total = limiter.total().await?;
         let caught = limiter.fetch().await?;
@@ -123,9 +67,58 @@ impl VulnerabilityService {
                 connection,
             )
             .await?,
+            next: None,
         })
     }
 
+    /// Stream all vulnerabilities matching the query, without pagination
+    pub async fn stream_vulnerabilities<'a, C: ConnectionTrait + StreamTrait + Sync + Send>(
+        &self,
+        search: Query,
+        deprecation: Deprecation,
+        connection: &'a C,
+    ) -> Result<impl Stream<Item = Result<VulnerabilitySummary, Error>> + 'a, Error> {
+        let rows = vulnerabilities()
+            .filtering_with(search, columns())?
+            .try_into_multi_model::<VulnerabilityCatcher>()?
+            .stream(connection)
+            .await?;
+
+        Ok(export::chunked(rows, move |caught| async move {
+            let vulnerabilities = caught
+                .iter()
+                .map(|e| e.vulnerability.clone())
+                .collect::<Vec<_>>();
+            let averages = caught
+                .iter()
+                .map(|e| (e.average_score, e.average_severity.map(|s| s.into())))
+                .collect::<Vec<_>>();
+
+            VulnerabilitySummary::from_entities(
+                &vulnerabilities,
+                &averages,
+                deprecation,
+                connection,
+            )
+            .await
+        }))
+    }
+
+    /// Count the vulnerabilities matching the query by the values of the requested facets, e.g.
+    /// `cwes`, `average_severity` or `published:year`
+    pub async fn fetch_facets<C: ConnectionTrait + Sync + Send>(
+        &self,
+        search: Query,
+        facets: Facets,
+        connection: &C,
+    ) -> Result<BTreeMap<String, Vec<FacetCount>>, Error> {
+        Ok(vulnerabilities()
+            .filtering_with(search, columns())?
+            .faceting(connection, &facets, columns())?
+            .fetch()
+            .await?)
+    }
+
     pub async fn fetch_vulnerability<C: ConnectionTrait + Sync + Send>(
         &self,
         identifier: &str,
@@ -144,6 +137,73 @@ impl VulnerabilityService {
         }
     }
 
+    /// Find all SBOMs containing packages which are affected by a vulnerability
+    pub async fn fetch_affected_sboms<C: ConnectionTrait + Sync + Send>(
+        &self,
+        identifier: &str,
+        connection: &C,
+    ) -> Result<Vec<AffectedSbom>, Error> {
+        #[derive(FromQueryResult)]
+        struct Row {
+            sbom_id: Uuid,
+            sbom_name: Option<String>,
+            published: Option<OffsetDateTime>,
+            package_name: String,
+            purl_type: String,
+            purl_namespace: Option<String>,
+            purl_name: String,
+            purl_version: String,
+            advisory: String,
+            product_name: Option<String>,
+            product_version: Option<String>,
+        }
+
+        let rows = Row::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            include_str!("affected_sboms.sql"),
+            [identifier.into()],
+        ))
+        .all(connection)
+        .await?;
+
+        let mut result = BTreeMap::<Uuid, AffectedSbom>::new();
+
+        for row in rows {
+            let sbom = result.entry(row.sbom_id).or_insert_with(|| AffectedSbom {
+                sbom_id: row.sbom_id,
+                name: row.sbom_name.clone().unwrap_or_default(),
+                published: row.published,
+                products: vec![],
+                packages: vec![],
+            });
+
+            if let (Some(name), Some(version)) = (row.product_name, row.product_version) {
+                let product = AffectedProduct { name, version };
+                if !sbom.products.contains(&product) {
+                    sbom.products.push(product);
+                }
+            }
+
+            let package = AffectedPackage {
+                name: row.package_name,
+                purl: Purl {
+                    ty: row.purl_type,
+                    namespace: row.purl_namespace,
+                    name: row.purl_name,
+                    version: Some(row.purl_version),
+                    qualifiers: Default::default(),
+                }
+                .to_string(),
+                advisory: row.advisory,
+            };
+            if !sbom.packages.contains(&package) {
+                sbom.packages.push(package);
+            }
+        }
+
+        Ok(result.into_values().collect())
+    }
+
     pub async fn delete_vulnerability<C: ConnectionTrait + Sync + Send>(
         &self,
         id: &str,
@@ -180,5 +240,85 @@ impl FromQueryResultMultiModel for VulnerabilityCatcher {
     }
 }
 
+/// Select the vulnerabilities, along with their average score and severity
+fn vulnerabilities() -> Select<vulnerability::Entity> {
+    let inner_query = vulnerability::Entity::find()
+        .left_join(cvss3::Entity)
+        .expr_as_(
+            SimpleExpr::FunctionCall(Func::avg(SimpleExpr::Column(
+                trustify_entity::cvss3::Column::Score.into_column_ref(),
+            ))),
+            "average_score",
+        )
+        .expr_as_(
+            SimpleExpr::FunctionCall(Func::cust("cvss3_severity".into_identity()).arg(
+                SimpleExpr::FunctionCall(Func::avg(SimpleExpr::Column(
+                    trustify_entity::cvss3::Column::Score.into_column_ref(),
+                ))),
+            )),
+            "average_severity",
+        )
+        .expr_as_(
+            Expr::col((vulnerability::Entity, "search".into_identity())),
+            "search",
+        )
+        .group_by(vulnerability::Column::Id);
+
+    let mut outer_query = vulnerability::Entity::find();
+
+    // Alias the inner query as exactly the table the entity is expecting
+    // so that column aliases link up correctly.
+    QueryTrait::query(&mut outer_query)
+        .from_clear()
+        .from_subquery(inner_query.into_query(), "vulnerability".into_identity());
+
+    outer_query
+        .column_as(
+            SimpleExpr::Column(ColumnRef::Column(
+                "average_score".into_identity().into_iden(),
+            )),
+            "average_score",
+        )
+        .column_as(
+            SimpleExpr::Column(ColumnRef::Column(
+                "average_severity".into_identity().into_iden(),
+            ))
+            .cast_as("TEXT".into_identity()),
+            "average_severity",
+        )
+}
+
+/// The columns available for filtering and faceting vulnerabilities
+pub fn columns() -> Columns {
+    Columns::from_entity::<vulnerability::Entity>()
+        .full_text(vulnerability::Entity, "search".into_identity())
+        .full_text_related(
+            vulnerability_description::Entity,
+            "search".into_identity(),
+            vulnerability_description::Column::VulnerabilityId,
+            (vulnerability::Entity, vulnerability::Column::Id),
+        )
+        .add_column("average_score", ColumnType::Decimal(None).def())
+        .add_column(
+            "average_severity",
+            ColumnType::Enum {
+                name: "cvss3_severity".into_identity().into_iden(),
+                variants: vec![
+                    "none".into_identity().into_iden(),
+                    "low".into_identity().into_iden(),
+                    "medium".into_identity().into_iden(),
+                    "high".into_identity().into_iden(),
+                    "critical".into_identity().into_iden(),
+                ],
+            }
+            .def(),
+        )
+        .translator(|f, op, v| match (f, v) {
+            // v = "" for all sort fields
+            ("average_severity", "") => Some(format!("average_score:{op}")),
+            _ => None,
+        })
+}
+
 #[cfg(test)]
 mod test;
@@ -422,3 +422,42 @@ async fn vulnerability_queries(ctx: &TrustifyContext) -> Result<(), anyhow::Erro
 
     Ok(())
 }
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn affected_sboms(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let service = VulnerabilityService::new();
+
+    let results = ctx
+        .ingest_documents([
+            "csaf/cve-2023-0044.json",
+            "quarkus/v2/quarkus-bom-2.13.8.Final-redhat-00004.json",
+        ])
+        .await?;
+    let sbom_id = results[1].id.try_as_uid().expect("must be a UUID");
+
+    let sboms = service
+        .fetch_affected_sboms("CVE-2023-0044", &ctx.db)
+        .await?;
+
+    log::debug!("{sboms:#?}");
+
+    assert_eq!(sboms.len(), 1);
+    assert_eq!(sboms[0].sbom_id, sbom_id);
+    assert!(sboms[0].products.is_empty());
+    assert!(sboms[0].packages.iter().any(|package| {
+        package.purl == "pkg:maven/io.quarkus/quarkus-vertx-http@2.13.8.Final-redhat-00004"
+            && package.advisory == "https://www.redhat.com/#CVE-2023-0044"
+    }));
+
+    // nothing for unknown vulnerabilities
+
+    assert!(
+        service
+            .fetch_affected_sboms("CVE-0000-0000", &ctx.db)
+            .await?
+            .is_empty()
+    );
+
+    Ok(())
+}
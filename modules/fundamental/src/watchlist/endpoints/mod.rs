@@ -0,0 +1,223 @@
+#[cfg(test)]
+mod test;
+
+use crate::{
+    Error,
+    watchlist::{
+        model::{NewWatchlist, VulnerabilityQuery, Watchlist, WatchlistVulnerability},
+        service::WatchlistService,
+    },
+};
+use actix_web::{HttpResponse, Responder, delete, get, post, put, web};
+use trustify_auth::{
+    ReadAdvisory, ReadSbom, all, authenticator::user::UserDetails, authorizer::Require,
+};
+use trustify_common::db::Database;
+use trustify_module_audit::recorder::{Action, Audit, Event, TargetKind};
+use uuid::Uuid;
+
+pub fn configure(config: &mut utoipa_actix_web::service_config::ServiceConfig, db: Database) {
+    let service = WatchlistService::new(db);
+    config
+        .app_data(web::Data::new(service))
+        .service(list)
+        .service(create)
+        .service(get)
+        .service(update)
+        .service(delete)
+        .service(get_vulnerabilities)
+        .service(check);
+}
+
+#[utoipa::path(
+    tag = "watchlist",
+    operation_id = "listWatchlists",
+    responses(
+        (status = 200, description = "The watchlists of the current user", body = Vec<Watchlist>),
+    ),
+)]
+#[get("/v2/watchlist")]
+/// List the watchlists of the current user
+pub async fn list(
+    state: web::Data<WatchlistService>,
+    user: UserDetails,
+) -> Result<impl Responder, Error> {
+    Ok(HttpResponse::Ok().json(state.list(&user.id).await?))
+}
+
+#[utoipa::path(
+    tag = "watchlist",
+    operation_id = "createWatchlist",
+    request_body = NewWatchlist,
+    responses(
+        (status = 201, description = "Created the watchlist", body = Watchlist),
+        (status = 400, description = "The content of the watchlist is invalid"),
+    ),
+)]
+#[post("/v2/watchlist")]
+/// Create a watchlist for the current user
+pub async fn create(
+    state: web::Data<WatchlistService>,
+    user: UserDetails,
+    web::Json(watchlist): web::Json<NewWatchlist>,
+    audit: Audit,
+) -> Result<impl Responder, Error> {
+    let created = state.create(&user.id, watchlist).await?;
+    audit
+        .record(Event::new(Action::Create, TargetKind::Watchlist, created.id).after(&created))
+        .await;
+    Ok(HttpResponse::Created().json(created))
+}
+
+#[utoipa::path(
+    tag = "watchlist",
+    operation_id = "getWatchlist",
+    params(
+        ("id", Path, description = "Opaque ID of the watchlist")
+    ),
+    responses(
+        (status = 200, description = "The watchlist", body = Watchlist),
+        (status = 404, description = "The user doesn't have a watchlist with this ID"),
+    ),
+)]
+#[get("/v2/watchlist/{id}")]
+/// Get a watchlist of the current user
+pub async fn get(
+    state: web::Data<WatchlistService>,
+    id: web::Path<Uuid>,
+    user: UserDetails,
+) -> Result<impl Responder, Error> {
+    Ok(match state.get(&user.id, *id).await? {
+        Some(watchlist) => HttpResponse::Ok().json(watchlist),
+        None => HttpResponse::NotFound().finish(),
+    })
+}
+
+#[utoipa::path(
+    tag = "watchlist",
+    operation_id = "updateWatchlist",
+    request_body = NewWatchlist,
+    params(
+        ("id", Path, description = "Opaque ID of the watchlist")
+    ),
+    responses(
+        (status = 200, description = "Updated the watchlist", body = Watchlist),
+        (status = 400, description = "The content of the watchlist is invalid"),
+        (status = 404, description = "The user doesn't have a watchlist with this ID"),
+    ),
+)]
+#[put("/v2/watchlist/{id}")]
+/// Replace the content of a watchlist of the current user
+pub async fn update(
+    state: web::Data<WatchlistService>,
+    id: web::Path<Uuid>,
+    user: UserDetails,
+    web::Json(watchlist): web::Json<NewWatchlist>,
+    audit: Audit,
+) -> Result<impl Responder, Error> {
+    Ok(match state.update(&user.id, *id, watchlist).await? {
+        Some(updated) => {
+            audit
+                .record(Event::new(Action::Update, TargetKind::Watchlist, *id).after(&updated))
+                .await;
+            HttpResponse::Ok().json(updated)
+        }
+        None => HttpResponse::NotFound().finish(),
+    })
+}
+
+#[utoipa::path(
+    tag = "watchlist",
+    operation_id = "deleteWatchlist",
+    params(
+        ("id", Path, description = "Opaque ID of the watchlist")
+    ),
+    responses(
+        (status = 204, description = "Deleted the watchlist"),
+        (status = 404, description = "The user doesn't have a watchlist with this ID"),
+    ),
+)]
+#[delete("/v2/watchlist/{id}")]
+/// Delete a watchlist of the current user
+pub async fn delete(
+    state: web::Data<WatchlistService>,
+    id: web::Path<Uuid>,
+    user: UserDetails,
+    audit: Audit,
+) -> Result<impl Responder, Error> {
+    let id = id.into_inner();
+    Ok(match state.delete(&user.id, id).await? {
+        true => {
+            audit
+                .record(Event::new(Action::Delete, TargetKind::Watchlist, id))
+                .await;
+            HttpResponse::NoContent().finish()
+        }
+        false => HttpResponse::NotFound().finish(),
+    })
+}
+
+#[utoipa::path(
+    tag = "watchlist",
+    operation_id = "getWatchlistVulnerabilities",
+    params(
+        ("id", Path, description = "Opaque ID of the watchlist"),
+        VulnerabilityQuery,
+    ),
+    responses(
+        (status = 200, description = "The vulnerabilities affecting the items of the watchlist", body = Vec<WatchlistVulnerability>),
+        (status = 404, description = "The user doesn't have a watchlist with this ID"),
+    ),
+)]
+#[get("/v2/watchlist/{id}/vulnerability")]
+/// Get the vulnerabilities affecting the items of a watchlist
+///
+/// This doesn't mark the watchlist as checked.
+pub async fn get_vulnerabilities(
+    state: web::Data<WatchlistService>,
+    id: web::Path<Uuid>,
+    web::Query(VulnerabilityQuery { since }): web::Query<VulnerabilityQuery>,
+    user: UserDetails,
+    _: Require<ReadWatchlistVulnerabilities>,
+) -> Result<impl Responder, Error> {
+    Ok(
+        match state.fetch_vulnerabilities(&user.id, *id, since).await? {
+            Some(vulnerabilities) => HttpResponse::Ok().json(vulnerabilities),
+            None => HttpResponse::NotFound().finish(),
+        },
+    )
+}
+
+#[utoipa::path(
+    tag = "watchlist",
+    operation_id = "checkWatchlist",
+    params(
+        ("id", Path, description = "Opaque ID of the watchlist")
+    ),
+    responses(
+        (status = 200, description = "The vulnerabilities reported since the watchlist was last checked", body = Vec<WatchlistVulnerability>),
+        (status = 404, description = "The user doesn't have a watchlist with this ID"),
+    ),
+)]
+#[post("/v2/watchlist/{id}/check")]
+/// Get the vulnerabilities reported since the watchlist was last checked, and mark it as checked
+pub async fn check(
+    state: web::Data<WatchlistService>,
+    id: web::Path<Uuid>,
+    user: UserDetails,
+    audit: Audit,
+    _: Require<ReadWatchlistVulnerabilities>,
+) -> Result<impl Responder, Error> {
+    Ok(match state.check(&user.id, *id).await? {
+        Some(vulnerabilities) => {
+            // checking moves the point in time the next check starts from
+            audit
+                .record(Event::new(Action::Update, TargetKind::Watchlist, *id))
+                .await;
+            HttpResponse::Ok().json(vulnerabilities)
+        }
+        None => HttpResponse::NotFound().finish(),
+    })
+}
+
+all!(ReadWatchlistVulnerabilities -> ReadSbom, ReadAdvisory);
@@ -0,0 +1,95 @@
+use crate::test::caller;
+use actix_http::StatusCode;
+use actix_web::test::TestRequest;
+use serde_json::{Value, json};
+use test_context::test_context;
+use test_log::test;
+use trustify_test_context::{TrustifyContext, auth::TestAuthentication, call::CallService};
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn watch_sbom(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let app = caller(ctx).await?;
+
+    let results = ctx
+        .ingest_documents([
+            "quarkus/v2/quarkus-bom-2.13.8.Final-redhat-00004.json",
+            "csaf/cve-2023-0044.json",
+        ])
+        .await?;
+    let sbom_id = results[0].id.try_as_uid().expect("must be a UUID");
+
+    let request = TestRequest::post()
+        .uri("/api/v2/watchlist")
+        .set_json(json!({
+            "name": "quarkus",
+            "sboms": [sbom_id],
+        }))
+        .to_request()
+        .test_auth("user-a");
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let created: Value = actix_web::test::read_body_json(response).await;
+    assert_eq!(created["purls"], json!([]));
+    let id = created["id"].as_str().unwrap_or_default();
+
+    // the watchlists of the user
+
+    let request = TestRequest::get()
+        .uri("/api/v2/watchlist")
+        .to_request()
+        .test_auth("user-a");
+    let response: Value = app.call_and_read_body_json(request).await;
+    assert_eq!(response.as_array().map(Vec::len), Some(1));
+
+    let request = TestRequest::get()
+        .uri("/api/v2/watchlist")
+        .to_request()
+        .test_auth("user-b");
+    let response: Value = app.call_and_read_body_json(request).await;
+    assert_eq!(response, json!([]));
+
+    // check it
+
+    let request = TestRequest::post()
+        .uri(&format!("/api/v2/watchlist/{id}/check"))
+        .to_request()
+        .test_auth("user-a");
+    let response: Value = app.call_and_read_body_json(request).await;
+    assert_eq!(response[0]["identifier"], json!("CVE-2023-0044"));
+    assert_eq!(response[0]["affected"]["sboms"], json!([sbom_id]));
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/watchlist/{id}/vulnerability"))
+        .to_request()
+        .test_auth("user-a");
+    let response: Value = app.call_and_read_body_json(request).await;
+    assert_eq!(response, json!([]));
+
+    let request = TestRequest::get()
+        .uri(&format!(
+            "/api/v2/watchlist/{id}/vulnerability?since=2000-01-01T00:00:00Z"
+        ))
+        .to_request()
+        .test_auth("user-a");
+    let response: Value = app.call_and_read_body_json(request).await;
+    assert_eq!(response.as_array().map(Vec::len), Some(1));
+
+    // delete it
+
+    let request = TestRequest::delete()
+        .uri(&format!("/api/v2/watchlist/{id}"))
+        .to_request()
+        .test_auth("user-a");
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let request = TestRequest::get()
+        .uri(&format!("/api/v2/watchlist/{id}"))
+        .to_request()
+        .test_auth("user-a");
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
@@ -0,0 +1,4 @@
+pub(crate) mod endpoints;
+
+pub mod model;
+pub mod service;
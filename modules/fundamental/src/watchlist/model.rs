@@ -0,0 +1,85 @@
+use crate::vulnerability::model::VulnerabilityHead;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use trustify_entity::watchlist;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+/// A watchlist of the current user.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, ToSchema)]
+pub struct Watchlist {
+    pub id: Uuid,
+    #[serde(flatten)]
+    pub watchlist: NewWatchlist,
+    /// The last time the user checked for new vulnerabilities.
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub last_checked: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created: OffsetDateTime,
+}
+
+impl From<watchlist::Model> for Watchlist {
+    fn from(value: watchlist::Model) -> Self {
+        Self {
+            id: value.id,
+            watchlist: NewWatchlist {
+                name: value.name,
+                items: WatchedItems {
+                    sboms: value.sboms,
+                    purls: value.purls,
+                    products: value.products,
+                },
+            },
+            last_checked: value.last_checked,
+            created: value.created,
+        }
+    }
+}
+
+/// The user provided content of a watchlist.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, ToSchema)]
+pub struct NewWatchlist {
+    pub name: String,
+    #[serde(flatten)]
+    pub items: WatchedItems,
+}
+
+/// The items a watchlist follows.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, ToSchema)]
+pub struct WatchedItems {
+    /// IDs of SBOMs.
+    #[serde(default)]
+    pub sboms: Vec<Uuid>,
+    /// Package URLs. Without a version, they match all versions of the package.
+    #[serde(default)]
+    pub purls: Vec<String>,
+    /// IDs of products, matching the SBOMs of all their versions.
+    #[serde(default)]
+    pub products: Vec<Uuid>,
+}
+
+/// A vulnerability affecting items of a watchlist.
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct WatchlistVulnerability {
+    #[serde(flatten)]
+    pub head: VulnerabilityHead,
+
+    /// When the first advisory stating an item is affected was ingested.
+    #[serde(with = "time::serde::rfc3339")]
+    pub reported: OffsetDateTime,
+
+    /// The identifiers of the advisories stating the items are affected.
+    pub advisories: Vec<String>,
+
+    /// The affected items of the watchlist.
+    pub affected: WatchedItems,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, IntoParams)]
+pub struct VulnerabilityQuery {
+    /// Only return vulnerabilities reported after this point in time. Defaults to the last
+    /// time the user checked the watchlist.
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    #[param(value_type = Option<String>, format = DateTime)]
+    pub since: Option<OffsetDateTime>,
+}
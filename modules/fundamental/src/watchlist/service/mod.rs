@@ -0,0 +1,406 @@
+use crate::{
+    Error,
+    sbom::service::SbomService,
+    vulnerability::model::VulnerabilityHead,
+    watchlist::model::{NewWatchlist, WatchedItems, Watchlist, WatchlistVulnerability},
+};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, EntityTrait, LoaderTrait,
+    QueryFilter, QueryOrder, QuerySelect, RelationTrait,
+};
+use sea_query::{Asterisk, Expr, Func, JoinType, SimpleExpr};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    str::FromStr,
+};
+use time::OffsetDateTime;
+use trustify_common::{
+    db::{Database, VersionMatches},
+    id::Id,
+    memo::Memo,
+    purl::Purl,
+};
+use trustify_entity::{
+    advisory, base_purl, product_version, purl_status, source_document, status, version_range,
+    versioned_purl, vulnerability, watchlist,
+};
+use uuid::Uuid;
+
+pub struct WatchlistService {
+    db: Database,
+}
+
+impl WatchlistService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// List the watchlists of a user.
+    pub async fn list(&self, user_id: &str) -> Result<Vec<Watchlist>, Error> {
+        Ok(watchlist::Entity::find()
+            .filter(watchlist::Column::UserId.eq(user_id))
+            .order_by_asc(watchlist::Column::Name)
+            .all(&self.db)
+            .await?
+            .into_iter()
+            .map(Watchlist::from)
+            .collect())
+    }
+
+    pub async fn get(&self, user_id: &str, id: Uuid) -> Result<Option<Watchlist>, Error> {
+        Ok(find(user_id, id, &self.db).await?.map(Watchlist::from))
+    }
+
+    /// Create a watchlist for a user.
+    pub async fn create(&self, user_id: &str, watchlist: NewWatchlist) -> Result<Watchlist, Error> {
+        let watchlist = normalize(watchlist)?;
+
+        let model = watchlist::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            user_id: Set(user_id.to_string()),
+            name: Set(watchlist.name),
+            sboms: Set(watchlist.items.sboms),
+            purls: Set(watchlist.items.purls),
+            products: Set(watchlist.items.products),
+            last_checked: Set(None),
+            created: Set(OffsetDateTime::now_utc()),
+        }
+        .insert(&self.db)
+        .await?;
+
+        Ok(model.into())
+    }
+
+    /// Replace the content of a watchlist of a user.
+    ///
+    /// Returns `Ok(None)` if the user doesn't have a watchlist with this ID.
+    pub async fn update(
+        &self,
+        user_id: &str,
+        id: Uuid,
+        watchlist: NewWatchlist,
+    ) -> Result<Option<Watchlist>, Error> {
+        let watchlist = normalize(watchlist)?;
+
+        let Some(existing) = find(user_id, id, &self.db).await? else {
+            return Ok(None);
+        };
+
+        let mut model = watchlist::ActiveModel::from(existing);
+        model.name = Set(watchlist.name);
+        model.sboms = Set(watchlist.items.sboms);
+        model.purls = Set(watchlist.items.purls);
+        model.products = Set(watchlist.items.products);
+
+        Ok(Some(model.update(&self.db).await?.into()))
+    }
+
+    /// Delete a watchlist of a user.
+    ///
+    /// Returns `false` if the user doesn't have a watchlist with this ID.
+    pub async fn delete(&self, user_id: &str, id: Uuid) -> Result<bool, Error> {
+        let result = watchlist::Entity::delete_many()
+            .filter(watchlist::Column::UserId.eq(user_id))
+            .filter(watchlist::Column::Id.eq(id))
+            .exec(&self.db)
+            .await?;
+
+        Ok(result.rows_affected > 0)
+    }
+
+    /// Get the vulnerabilities affecting the items of a watchlist, which got reported after
+    /// `since`.
+    ///
+    /// If `since` is not provided, the last time the user checked the watchlist is used.
+    /// Returns `Ok(None)` if the user doesn't have a watchlist with this ID.
+    pub async fn fetch_vulnerabilities(
+        &self,
+        user_id: &str,
+        id: Uuid,
+        since: Option<OffsetDateTime>,
+    ) -> Result<Option<Vec<WatchlistVulnerability>>, Error> {
+        let Some(watchlist) = find(user_id, id, &self.db).await? else {
+            return Ok(None);
+        };
+
+        let since = since.or(watchlist.last_checked);
+        Ok(Some(self.vulnerabilities(&watchlist, since).await?))
+    }
+
+    /// Get the vulnerabilities reported since the user last checked the watchlist, and mark
+    /// the watchlist as checked.
+    ///
+    /// Returns `Ok(None)` if the user doesn't have a watchlist with this ID.
+    pub async fn check(
+        &self,
+        user_id: &str,
+        id: Uuid,
+    ) -> Result<Option<Vec<WatchlistVulnerability>>, Error> {
+        let Some(watchlist) = find(user_id, id, &self.db).await? else {
+            return Ok(None);
+        };
+
+        let now = OffsetDateTime::now_utc();
+        let result = self
+            .vulnerabilities(&watchlist, watchlist.last_checked)
+            .await?;
+
+        let mut model = watchlist::ActiveModel::from(watchlist);
+        model.last_checked = Set(Some(now));
+        model.update(&self.db).await?;
+
+        Ok(Some(result))
+    }
+
+    async fn vulnerabilities(
+        &self,
+        watchlist: &watchlist::Model,
+        since: Option<OffsetDateTime>,
+    ) -> Result<Vec<WatchlistVulnerability>, Error> {
+        let mut collector = Collector::default();
+
+        // SBOMs, directly or through product versions
+
+        let mut sboms = BTreeMap::<Uuid, WatchedItems>::new();
+        for sbom_id in &watchlist.sboms {
+            sboms.entry(*sbom_id).or_default().sboms.push(*sbom_id);
+        }
+
+        if !watchlist.products.is_empty() {
+            let versions = product_version::Entity::find()
+                .filter(product_version::Column::ProductId.is_in(watchlist.products.clone()))
+                .all(&self.db)
+                .await?;
+            for version in versions {
+                if let Some(sbom_id) = version.sbom_id {
+                    sboms
+                        .entry(sbom_id)
+                        .or_default()
+                        .products
+                        .push(version.product_id);
+                }
+            }
+        }
+
+        let sbom_service = SbomService::new(self.db.clone());
+        for (sbom_id, items) in sboms {
+            let Some(details) = sbom_service
+                .fetch_sbom_details(Id::Uuid(sbom_id), vec!["affected".to_string()], &self.db)
+                .await?
+            else {
+                continue;
+            };
+
+            for advisory in details.advisories {
+                for status in advisory.status {
+                    collector.add(&status.vulnerability, advisory.head.uuid, &items);
+                }
+            }
+        }
+
+        // packages, all versions if the purl has none
+
+        for purl in &watchlist.purls {
+            let items = WatchedItems {
+                purls: vec![purl.clone()],
+                ..Default::default()
+            };
+
+            for (vulnerability, advisory) in affected(&Purl::from_str(purl)?, &self.db).await? {
+                collector.add(&vulnerability, advisory, &items);
+            }
+        }
+
+        collector.finish(since, &self.db).await
+    }
+}
+
+/// Collects vulnerabilities, the advisories reporting them, and the affected items.
+#[derive(Default)]
+struct Collector {
+    vulnerabilities: BTreeMap<String, (VulnerabilityHead, BTreeSet<Uuid>, Affected)>,
+}
+
+#[derive(Default)]
+struct Affected {
+    sboms: BTreeSet<Uuid>,
+    purls: BTreeSet<String>,
+    products: BTreeSet<Uuid>,
+}
+
+impl Collector {
+    fn add(&mut self, vulnerability: &VulnerabilityHead, advisory: Uuid, items: &WatchedItems) {
+        let (_, advisories, affected) = self
+            .vulnerabilities
+            .entry(vulnerability.identifier.clone())
+            .or_insert_with(|| {
+                (
+                    vulnerability.clone(),
+                    Default::default(),
+                    Default::default(),
+                )
+            });
+
+        advisories.insert(advisory);
+        affected.sboms.extend(items.sboms.iter().copied());
+        affected.purls.extend(items.purls.iter().cloned());
+        affected.products.extend(items.products.iter().copied());
+    }
+
+    /// Look up when the advisories were ingested, and only keep the vulnerabilities reported
+    /// after `since`.
+    async fn finish<C: ConnectionTrait>(
+        self,
+        since: Option<OffsetDateTime>,
+        connection: &C,
+    ) -> Result<Vec<WatchlistVulnerability>, Error> {
+        let ids = self
+            .vulnerabilities
+            .values()
+            .flat_map(|(_, advisories, _)| advisories.iter().copied())
+            .collect::<BTreeSet<_>>();
+
+        let advisories = advisory::Entity::find()
+            .filter(advisory::Column::Id.is_in(ids))
+            .find_also_related(source_document::Entity)
+            .all(connection)
+            .await?
+            .into_iter()
+            .map(|(advisory, document)| (advisory.id, (advisory, document)))
+            .collect::<HashMap<_, _>>();
+
+        let mut result = vec![];
+        for (_, (head, ids, affected)) in self.vulnerabilities {
+            let mut reported = None::<OffsetDateTime>;
+            let mut identifiers = BTreeSet::new();
+
+            for id in ids {
+                let Some((advisory, document)) = advisories.get(&id) else {
+                    continue;
+                };
+                identifiers.insert(advisory.identifier.clone());
+                if let Some(document) = document {
+                    reported = Some(match reported {
+                        Some(reported) => reported.min(document.ingested),
+                        None => document.ingested,
+                    });
+                }
+            }
+
+            let Some(reported) = reported else {
+                continue;
+            };
+            if since.is_some_and(|since| reported <= since) {
+                continue;
+            }
+
+            result.push(WatchlistVulnerability {
+                head,
+                reported,
+                advisories: identifiers.into_iter().collect(),
+                affected: WatchedItems {
+                    sboms: affected.sboms.into_iter().collect(),
+                    purls: affected.purls.into_iter().collect(),
+                    products: affected.products.into_iter().collect(),
+                },
+            });
+        }
+
+        Ok(result)
+    }
+}
+
+/// Find the vulnerabilities, and the advisories stating the package is affected by them. Without
+/// a version, any known version of the package is considered.
+async fn affected<C: ConnectionTrait>(
+    purl: &Purl,
+    connection: &C,
+) -> Result<Vec<(VulnerabilityHead, Uuid)>, Error> {
+    let mut query = purl_status::Entity::find()
+        .join(JoinType::Join, purl_status::Relation::BasePurl.def())
+        .join(JoinType::Join, base_purl::Relation::VersionedPurls.def())
+        .join(JoinType::Join, purl_status::Relation::VersionRange.def())
+        .join(JoinType::Join, purl_status::Relation::Status.def())
+        .filter(base_purl::Column::Type.eq(&purl.ty))
+        .filter(base_purl::Column::Name.eq(&purl.name))
+        .filter(status::Column::Slug.eq("affected"))
+        .filter(SimpleExpr::FunctionCall(
+            Func::cust(VersionMatches)
+                .arg(Expr::col((
+                    versioned_purl::Entity,
+                    versioned_purl::Column::Version,
+                )))
+                .arg(Expr::col((version_range::Entity, Asterisk))),
+        ));
+
+    query = match &purl.namespace {
+        Some(namespace) => query.filter(base_purl::Column::Namespace.eq(namespace)),
+        None => query.filter(base_purl::Column::Namespace.is_null()),
+    };
+    if let Some(version) = &purl.version {
+        query = query.filter(versioned_purl::Column::Version.eq(version));
+    }
+
+    let statuses = query.distinct().all(connection).await?;
+    let vulnerabilities = statuses.load_one(vulnerability::Entity, connection).await?;
+
+    let mut heads = HashMap::<String, VulnerabilityHead>::new();
+    let mut result = vec![];
+    for (status, vulnerability) in statuses.into_iter().zip(vulnerabilities) {
+        let Some(vulnerability) = vulnerability else {
+            continue;
+        };
+        let head = match heads.get(&vulnerability.id) {
+            Some(head) => head.clone(),
+            None => {
+                let head = VulnerabilityHead::from_vulnerability_entity(
+                    &vulnerability,
+                    Memo::NotProvided,
+                    connection,
+                )
+                .await?;
+                heads.insert(vulnerability.id, head.clone());
+                head
+            }
+        };
+        result.push((head, status.advisory_id));
+    }
+
+    Ok(result)
+}
+
+async fn find<C: ConnectionTrait>(
+    user_id: &str,
+    id: Uuid,
+    connection: &C,
+) -> Result<Option<watchlist::Model>, Error> {
+    Ok(watchlist::Entity::find_by_id(id)
+        .filter(watchlist::Column::UserId.eq(user_id))
+        .one(connection)
+        .await?)
+}
+
+fn normalize(mut watchlist: NewWatchlist) -> Result<NewWatchlist, Error> {
+    watchlist.name = watchlist.name.trim().to_string();
+    if watchlist.name.is_empty() {
+        return Err(Error::BadRequest("The name must not be empty".into()));
+    }
+
+    let mut purls = BTreeSet::new();
+    for purl in watchlist.items.purls {
+        let purl = Purl::from_str(purl.trim())
+            .map_err(|err| Error::BadRequest(format!("Invalid purl '{purl}': {err}")))?;
+        purls.insert(purl.to_string());
+    }
+    watchlist.items.purls = purls.into_iter().collect();
+
+    watchlist.items.sboms.sort_unstable();
+    watchlist.items.sboms.dedup();
+    watchlist.items.products.sort_unstable();
+    watchlist.items.products.dedup();
+
+    Ok(watchlist)
+}
+
+#[cfg(test)]
+mod test;
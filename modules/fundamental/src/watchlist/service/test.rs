@@ -0,0 +1,150 @@
+use crate::{
+    Error,
+    watchlist::{
+        model::{NewWatchlist, WatchedItems},
+        service::WatchlistService,
+    },
+};
+use test_context::test_context;
+use test_log::test;
+use time::{Duration, OffsetDateTime};
+use trustify_module_ingestor::graph::product::ProductInformation;
+use trustify_test_context::TrustifyContext;
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn new_vulnerabilities(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let service = WatchlistService::new(ctx.db.clone());
+
+    let results = ctx
+        .ingest_documents([
+            "quarkus/v2/quarkus-bom-2.13.8.Final-redhat-00004.json",
+            "csaf/cve-2023-0044.json",
+        ])
+        .await?;
+    let sbom_id = results[0].id.try_as_uid().expect("must be a UUID");
+
+    let product = ctx
+        .graph
+        .ingest_product(
+            "Quarkus",
+            ProductInformation {
+                vendor: Some("Red Hat".to_string()),
+                cpe: None,
+            },
+            &ctx.db,
+        )
+        .await?;
+    product
+        .ingest_product_version("2.13".to_string(), Some(sbom_id), &ctx.db)
+        .await?;
+    let product_id = product.product.id;
+
+    let watchlist = service
+        .create(
+            "user-a",
+            NewWatchlist {
+                name: "quarkus".into(),
+                items: WatchedItems {
+                    sboms: vec![sbom_id],
+                    purls: vec!["pkg:maven/org.example/unknown".into()],
+                    products: vec![product_id],
+                },
+            },
+        )
+        .await?;
+    assert_eq!(watchlist.last_checked, None);
+
+    // everything is new
+
+    let vulnerabilities = service
+        .fetch_vulnerabilities("user-a", watchlist.id, None)
+        .await?
+        .expect("must exist");
+    assert_eq!(vulnerabilities.len(), 1);
+    assert_eq!(vulnerabilities[0].head.identifier, "CVE-2023-0044");
+    assert_eq!(
+        vulnerabilities[0].advisories,
+        vec!["https://www.redhat.com/#CVE-2023-0044".to_string()]
+    );
+    assert_eq!(vulnerabilities[0].affected.sboms, vec![sbom_id]);
+    assert_eq!(vulnerabilities[0].affected.products, vec![product_id]);
+    assert!(vulnerabilities[0].affected.purls.is_empty());
+
+    // checking returns the same, but only once
+
+    let checked = service
+        .check("user-a", watchlist.id)
+        .await?
+        .expect("must exist");
+    assert_eq!(checked.len(), 1);
+
+    let checked = service
+        .check("user-a", watchlist.id)
+        .await?
+        .expect("must exist");
+    assert!(checked.is_empty());
+
+    let watchlist = service
+        .get("user-a", watchlist.id)
+        .await?
+        .expect("must exist");
+    assert!(watchlist.last_checked.is_some());
+
+    // an explicit point in time overrides the last check
+
+    let since = OffsetDateTime::now_utc() - Duration::days(1);
+    let vulnerabilities = service
+        .fetch_vulnerabilities("user-a", watchlist.id, Some(since))
+        .await?
+        .expect("must exist");
+    assert_eq!(vulnerabilities.len(), 1);
+
+    // other users don't have access
+
+    assert!(
+        service
+            .fetch_vulnerabilities("user-b", watchlist.id, None)
+            .await?
+            .is_none()
+    );
+    assert!(service.check("user-b", watchlist.id).await?.is_none());
+    assert!(!service.delete("user-b", watchlist.id).await?);
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn invalid_watchlists(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let service = WatchlistService::new(ctx.db.clone());
+
+    let result = service
+        .create(
+            "user-a",
+            NewWatchlist {
+                name: " ".into(),
+                items: Default::default(),
+            },
+        )
+        .await;
+    assert!(matches!(result, Err(Error::BadRequest(_))));
+
+    let result = service
+        .create(
+            "user-a",
+            NewWatchlist {
+                name: "invalid".into(),
+                items: WatchedItems {
+                    purls: vec!["not-a-purl".into()],
+                    ..Default::default()
+                },
+            },
+        )
+        .await;
+    assert!(matches!(result, Err(Error::BadRequest(_))));
+
+    assert!(service.list("user-a").await?.is_empty());
+
+    Ok(())
+}
@@ -27,11 +27,9 @@ impl WeaknessService {
         query: Query,
         paginated: Paginated,
     ) -> Result<PaginatedResults<WeaknessSummary>, Error> {
-        let limiter = weakness::Entity::find().filtering(query)?.limiting(
-            &self.db,
-            paginated.offset,
-            paginated.limit,
-        );
+        let limiter = weakness::Entity::find()
+            .filtering(query)?
+            .limiting(&self.db, &paginated);
 
         let total = limiter.total().await?;
         let items = limiter.fetch().await?;
@@ -39,6 +37,7 @@ impl WeaknessService {
         Ok(PaginatedResults {
             items: WeaknessSummary::from_entities(&items).await?,
             total,
+            next: None,
         })
     }
 
@@ -19,7 +19,7 @@ async fn simple(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
         )
         .await?;
 
-    assert_eq!(packages.total, 1);
+    assert_eq!(packages.total, Some(1));
     assert_eq!(packages.items.len(), 1);
 
     let package = &packages.items[0];
@@ -44,7 +44,7 @@ async fn simple_ref(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
         )
         .await?;
 
-    assert_eq!(packages.total, 1);
+    assert_eq!(packages.total, Some(1));
     assert_eq!(packages.items.len(), 1);
 
     let package = &packages.items[0];
@@ -69,7 +69,7 @@ async fn simple_comp(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
         )
         .await?;
 
-    assert_eq!(packages.total, 1);
+    assert_eq!(packages.total, Some(1));
     assert_eq!(packages.items.len(), 1);
 
     let package = &packages.items[0];
@@ -86,7 +86,7 @@ async fn simple_comp(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
         )
         .await?;
 
-    assert_eq!(packages.total, 3);
+    assert_eq!(packages.total, Some(3));
     assert_eq!(packages.items.len(), 3);
 
     // ensure the cpe one is present
@@ -71,6 +71,7 @@ async fn test_parse_cyclonedx(ctx: &TrustifyContext) -> Result<(), anyhow::Error
                     Paginated {
                         offset: 0,
                         limit: 1,
+                        ..Default::default()
                     },
                     &ctx.db,
                 )
@@ -78,7 +79,7 @@ async fn test_parse_cyclonedx(ctx: &TrustifyContext) -> Result<(), anyhow::Error
 
             log::debug!("{:?}", packages);
 
-            assert_eq!(41, packages.total);
+            assert_eq!(Some(41), packages.total);
 
             Ok(())
         },
@@ -114,6 +115,7 @@ async fn parse_cyclonedx_1dot6(ctx: &TrustifyContext) -> Result<(), anyhow::Erro
                     Paginated {
                         offset: 0,
                         limit: 1,
+                        ..Default::default()
                     },
                     &ctx.db,
                 )
@@ -121,7 +123,7 @@ async fn parse_cyclonedx_1dot6(ctx: &TrustifyContext) -> Result<(), anyhow::Erro
 
             log::debug!("{:?}", packages);
 
-            assert_eq!(9, packages.total);
+            assert_eq!(Some(9), packages.total);
 
             Ok(())
         },
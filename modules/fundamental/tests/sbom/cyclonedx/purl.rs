@@ -34,7 +34,7 @@ async fn simple_ref(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
         .describes_packages(sbom_id, Default::default(), &ctx.db)
         .await?;
 
-    assert_eq!(packages.total, 1);
+    assert_eq!(packages.total, Some(1));
     assert_eq!(packages.items.len(), 1);
 
     let package = &packages.items[0];
@@ -61,7 +61,7 @@ async fn simple_ref(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
         )
         .await?;
 
-    assert_eq!(result.total, 1);
+    assert_eq!(result.total, Some(1));
     let relation = &result.items[0];
     assert_eq!(
         to_strings(&relation.package.purl),
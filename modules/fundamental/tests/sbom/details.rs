@@ -3,6 +3,8 @@ use test_context::test_context;
 use test_log::test;
 use tracing::instrument;
 use trustify_cvss::cvss3::severity::Severity;
+use trustify_entity::labels::Labels;
+use trustify_module_fundamental::advisory::service::AdvisoryService;
 use trustify_module_fundamental::sbom::model::details::SbomDetails;
 use trustify_module_fundamental::sbom::service::SbomService;
 use trustify_test_context::TrustifyContext;
@@ -184,3 +186,43 @@ fn check_advisory(
     assert_eq!(severity, advisory.status[0].average_severity);
     assert_eq!("affected", advisory.status[0].status);
 }
+
+/// Packages without a purl match NVD statuses through their CPE and version.
+#[test_context(TrustifyContext)]
+#[test(tokio::test)]
+async fn sbom_details_cpe_nvd(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+    let sbom = SbomService::new(ctx.db.clone());
+
+    let result = ctx.ingest_document("cyclonedx/log4j_cpe.json").await?;
+    let nvd = ctx.ingest_document("nvd/CVE-2021-44228.json").await?;
+
+    // replacing the labels must not change how the statuses apply
+    AdvisoryService::new(ctx.db.clone())
+        .set_labels(nvd.id, Labels::new().add("team", "a"), &ctx.db)
+        .await?
+        .expect("advisory must be found");
+
+    let details = sbom
+        .fetch_sbom_details(result.id, vec![], &ctx.db)
+        .await?
+        .expect("SBOM details must be found");
+
+    let advisories = details
+        .advisories
+        .iter()
+        .filter(|advisory| advisory.head.document_id == "NVD-CVE-2021-44228")
+        .collect::<Vec<_>>();
+    assert_eq!(1, advisories.len());
+
+    let status = &advisories[0].status;
+    assert_eq!(1, status.len());
+    assert_eq!("CVE-2021-44228", status[0].vulnerability.identifier);
+    assert_eq!("affected", status[0].status);
+    assert_eq!(Severity::Critical, status[0].average_severity);
+
+    // only the vulnerable version matches
+    assert_eq!(1, status[0].packages.len());
+    assert_eq!(Some("2.14.1"), status[0].packages[0].version.as_deref());
+
+    Ok(())
+}
@@ -46,11 +46,12 @@ async fn simple(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
             Paginated {
                 offset: 0,
                 limit: 10,
+                ..Default::default()
             },
         )
         .await?;
 
-    assert_eq!(TOTAL_ITEMS_FOUND, all.total);
+    assert_eq!(Some(TOTAL_ITEMS_FOUND), all.total);
 
     let w = service
         .get_weakness("CWE-1004")
@@ -81,13 +82,14 @@ async fn simple(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
             Paginated {
                 offset: 0,
                 limit: 10,
+                ..Default::default()
             },
         )
         .await?;
 
     // must be the same number of items
 
-    assert_eq!(964, all.total);
+    assert_eq!(Some(964), all.total);
 
     let w = service
         .get_weakness("CWE-1004")
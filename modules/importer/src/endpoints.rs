@@ -1,5 +1,8 @@
 use super::service::{Error, ImporterService, PatchError};
-use crate::model::{Importer, ImporterConfiguration, ImporterReport};
+use crate::{
+    model::{Importer, ImporterConfiguration, ImporterReport},
+    runner::report::ReportItem,
+};
 use actix_web::{
     HttpResponse, Responder, delete, get,
     guard::{self, Guard, GuardContext},
@@ -10,13 +13,19 @@ use std::convert::Infallible;
 use trustify_auth::authorizer::Require;
 use trustify_auth::{CreateImporter, DeleteImporter, ReadImporter, UpdateImporter};
 use trustify_common::{
-    db::Database,
+    db::{Database, query::Query},
     model::{Paginated, PaginatedResults, Revisioned},
 };
+use trustify_module_audit::{
+    recorder::{Action, Audit, Event, TargetKind},
+    service::AuditService,
+};
+use uuid::Uuid;
 
 /// mount the "importer" module
 pub fn configure(svc: &mut utoipa_actix_web::service_config::ServiceConfig, db: Database) {
-    svc.app_data(web::Data::new(ImporterService::new(db)))
+    svc.app_data(web::Data::new(ImporterService::new(db.clone())))
+        .app_data(web::Data::new(AuditService::new(db.clone())))
         .service(list)
         .service(create)
         .service(read)
@@ -24,8 +33,12 @@ pub fn configure(svc: &mut utoipa_actix_web::service_config::ServiceConfig, db:
         .service(patch_json_merge)
         .service(delete)
         .service(get_reports)
+        .service(get_report_items)
+        .service(retry)
         .service(set_enabled)
         .service(force);
+
+    crate::job::endpoints::configure(svc, db);
 }
 
 #[utoipa::path(
@@ -62,9 +75,12 @@ async fn create(
     service: web::Data<ImporterService>,
     name: web::Path<String>,
     web::Json(configuration): web::Json<ImporterConfiguration>,
+    audit: Audit,
     _: Require<CreateImporter>,
 ) -> Result<impl Responder, Error> {
+    let event = Event::new(Action::Create, TargetKind::Importer, &name).after(&configuration);
     service.create(name.into_inner(), configuration).await?;
+    audit.record(event).await;
     Ok(HttpResponse::Created().finish())
 }
 
@@ -122,6 +138,7 @@ async fn update(
     name: web::Path<String>,
     web::Header(if_match): web::Header<IfMatch>,
     web::Json(configuration): web::Json<ImporterConfiguration>,
+    audit: Audit,
     _: Require<UpdateImporter>,
 ) -> Result<impl Responder, Error> {
     let revision = match &if_match {
@@ -129,9 +146,11 @@ async fn update(
         IfMatch::Items(items) => items.first().map(|etag| etag.tag()),
     };
 
+    let event = Event::new(Action::Update, TargetKind::Importer, &name).after(&configuration);
     service
         .update_configuration(&name, revision, configuration)
         .await?;
+    audit.record(event).await;
 
     Ok(HttpResponse::NoContent().finish())
 }
@@ -160,6 +179,7 @@ async fn patch_json_merge(
     name: web::Path<String>,
     web::Header(if_match): web::Header<IfMatch>,
     web::Json(patch): web::Json<serde_json::Value>,
+    audit: Audit,
     _: Require<UpdateImporter>,
 ) -> Result<impl Responder, PatchError<serde_json::Error>> {
     let revision = match &if_match {
@@ -167,13 +187,17 @@ async fn patch_json_merge(
         IfMatch::Items(items) => items.first().map(|etag| etag.tag()),
     };
 
+    let mut event = Event::new(Action::Update, TargetKind::Importer, &name);
     service
         .patch_configuration(&name, revision, |config| {
             let mut json = serde_json::to_value(&config)?;
+            event.before = Some(json.clone());
             json_merge_patch::json_merge_patch(&mut json, &patch);
+            event.after = Some(json.clone());
             serde_json::from_value(json)
         })
         .await?;
+    audit.record(event).await;
 
     Ok(HttpResponse::NoContent().finish())
 }
@@ -199,6 +223,7 @@ async fn set_enabled(
     name: web::Path<String>,
     web::Header(if_match): web::Header<IfMatch>,
     web::Json(state): web::Json<bool>,
+    audit: Audit,
     _: Require<UpdateImporter>,
 ) -> Result<impl Responder, PatchError<Infallible>> {
     let revision = match &if_match {
@@ -213,6 +238,14 @@ async fn set_enabled(
         })
         .await?;
 
+    let action = match state {
+        true => Action::Enable,
+        false => Action::Disable,
+    };
+    audit
+        .record(Event::new(action, TargetKind::Importer, &name))
+        .await;
+
     Ok(HttpResponse::NoContent().finish())
 }
 
@@ -236,6 +269,7 @@ async fn force(
     service: web::Data<ImporterService>,
     name: web::Path<String>,
     web::Header(if_match): web::Header<IfMatch>,
+    audit: Audit,
     _: Require<UpdateImporter>,
 ) -> Result<impl Responder, Error> {
     let revision = match &if_match {
@@ -244,6 +278,9 @@ async fn force(
     };
 
     service.reset(&name, revision).await?;
+    audit
+        .record(Event::new(Action::Force, TargetKind::Importer, &name))
+        .await;
 
     Ok(HttpResponse::NoContent().finish())
 }
@@ -265,6 +302,7 @@ async fn delete(
     service: web::Data<ImporterService>,
     name: web::Path<String>,
     web::Header(if_match): web::Header<IfMatch>,
+    audit: Audit,
     _: Require<DeleteImporter>,
 ) -> Result<impl Responder, Error> {
     let revision = match &if_match {
@@ -273,7 +311,12 @@ async fn delete(
     };
 
     Ok(match service.delete(&name, revision).await? {
-        true => HttpResponse::NoContent().finish(),
+        true => {
+            audit
+                .record(Event::new(Action::Delete, TargetKind::Importer, &name))
+                .await;
+            HttpResponse::NoContent().finish()
+        }
         false => HttpResponse::NoContent().finish(),
     })
 }
@@ -296,6 +339,89 @@ async fn get_reports(
     Ok(web::Json(service.get_reports(&name, paginated).await?))
 }
 
+#[utoipa::path(
+    tag = "importer",
+    operation_id = "listImporterReportItems",
+    params(
+        ("name", Path, description = "The name of the importer"),
+        ("id", Path, description = "The ID of the report"),
+        Query,
+        Paginated,
+    ),
+    responses(
+        (status = 200, description = "Retrieved the outcome of the documents of the report", body = PaginatedResults<ReportItem>),
+        (status = 400, description = "The query was invalid"),
+        (status = 404, description = "The importer or report could not be found"),
+    )
+)]
+#[get("/v2/importer/{name}/report/{id}/items")]
+/// Get the outcome of the documents processed during an importer run
+async fn get_report_items(
+    service: web::Data<ImporterService>,
+    path: web::Path<(String, Uuid)>,
+    web::Query(search): web::Query<Query>,
+    web::Query(paginated): web::Query<Paginated>,
+    _: Require<ReadImporter>,
+) -> Result<impl Responder, Error> {
+    let (name, id) = path.into_inner();
+    Ok(
+        match service
+            .get_report_items(&name, id, search, paginated)
+            .await?
+        {
+            Some(items) => HttpResponse::Ok().json(items),
+            None => HttpResponse::NotFound().finish(),
+        },
+    )
+}
+
+#[utoipa::path(
+    tag = "importer",
+    operation_id = "retryImporterReport",
+    params(
+        ("name", Path, description = "The name of the importer"),
+        ("id", Path, description = "The ID of the report"),
+        ("if-match"=Option<String>, Header, description = "The revision to update"),
+    ),
+    responses(
+        (status = 204, description = "Scheduled the failed documents to be retried"),
+        (status = 400, description = "The importer doesn't support retrying, or the report has no failed documents"),
+        (status = 404, description = "The importer or report could not be found"),
+        (status = 412, description = "The provided if-match header did not match the stored revision"),
+    )
+)]
+#[post("/v2/importer/{name}/report/{id}/retry")]
+/// Retry the failed documents of an importer run with the next run
+///
+/// This only processes the documents which failed, without resetting the continuation of the
+/// importer.
+async fn retry(
+    service: web::Data<ImporterService>,
+    path: web::Path<(String, Uuid)>,
+    web::Header(if_match): web::Header<IfMatch>,
+    audit: Audit,
+    _: Require<UpdateImporter>,
+) -> Result<impl Responder, Error> {
+    let (name, id) = path.into_inner();
+    let revision = match &if_match {
+        IfMatch::Any => None,
+        IfMatch::Items(items) => items.first().map(|etag| etag.tag()),
+    };
+
+    Ok(match service.retry(&name, id, revision).await? {
+        true => {
+            audit
+                .record(
+                    Event::new(Action::Force, TargetKind::Importer, &name)
+                        .after(&serde_json::json!({ "retry": id })),
+                )
+                .await;
+            HttpResponse::NoContent().finish()
+        }
+        false => HttpResponse::NotFound().finish(),
+    })
+}
+
 mod guards {
     use super::*;
 
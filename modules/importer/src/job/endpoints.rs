@@ -0,0 +1,135 @@
+use super::{
+    model::{JobConfiguration, JobReport, ScheduledJob},
+    service::{Error, JobService},
+};
+use actix_web::{HttpResponse, Responder, get, post, put, web};
+use trustify_auth::{ReadImporter, UpdateImporter, authorizer::Require};
+use trustify_common::{
+    db::Database,
+    model::{Paginated, PaginatedResults},
+};
+use trustify_module_audit::recorder::{Action, Audit, Event, TargetKind};
+
+/// mount the "job" endpoints
+pub fn configure(svc: &mut utoipa_actix_web::service_config::ServiceConfig, db: Database) {
+    svc.app_data(web::Data::new(JobService::new(db)))
+        .service(list)
+        .service(read)
+        .service(update)
+        .service(force)
+        .service(get_reports);
+}
+
+#[utoipa::path(
+    tag = "job",
+    operation_id = "listJobs",
+    responses(
+        (status = 200, description = "List the scheduled jobs", body = [ScheduledJob])
+    )
+)]
+#[get("/v2/job")]
+/// List the scheduled maintenance jobs
+async fn list(
+    service: web::Data<JobService>,
+    _: Require<ReadImporter>,
+) -> Result<impl Responder, Error> {
+    Ok(web::Json(service.list().await?))
+}
+
+#[utoipa::path(
+    tag = "job",
+    operation_id = "getJob",
+    params(
+        ("name", Path, description = "The name of the job"),
+    ),
+    responses(
+        (status = 200, description = "Retrieved the job", body = ScheduledJob),
+        (status = 404, description = "A job with that name could not be found")
+    )
+)]
+#[get("/v2/job/{name}")]
+/// Get a scheduled maintenance job
+async fn read(
+    service: web::Data<JobService>,
+    name: web::Path<String>,
+    _: Require<ReadImporter>,
+) -> Result<Option<impl Responder>, Error> {
+    Ok(service.read(&name).await?.map(web::Json))
+}
+
+#[utoipa::path(
+    tag = "job",
+    operation_id = "updateJob",
+    request_body = JobConfiguration,
+    params(
+        ("name", Path, description = "The name of the job"),
+    ),
+    responses(
+        (status = 204, description = "Updated the job configuration"),
+        (status = 404, description = "A job with that name does not exist"),
+    )
+)]
+#[put("/v2/job/{name}")]
+/// Update the configuration of a job
+async fn update(
+    service: web::Data<JobService>,
+    name: web::Path<String>,
+    web::Json(configuration): web::Json<JobConfiguration>,
+    audit: Audit,
+    _: Require<UpdateImporter>,
+) -> Result<impl Responder, Error> {
+    let event = Event::new(Action::Update, TargetKind::Job, &name).after(&configuration);
+    service.update_configuration(&name, configuration).await?;
+    audit.record(event).await;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[utoipa::path(
+    tag = "job",
+    operation_id = "forceRunJob",
+    params(
+        ("name", Path, description = "The name of the job"),
+    ),
+    responses(
+        (status = 204, description = "Updated the state"),
+        (status = 404, description = "A job with that name does not exist"),
+    )
+)]
+#[post("/v2/job/{name}/force")]
+/// Force a job to run as soon as possible
+async fn force(
+    service: web::Data<JobService>,
+    name: web::Path<String>,
+    audit: Audit,
+    _: Require<UpdateImporter>,
+) -> Result<impl Responder, Error> {
+    service.reset(&name).await?;
+    audit
+        .record(Event::new(Action::Force, TargetKind::Job, &name))
+        .await;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[utoipa::path(
+    tag = "job",
+    operation_id = "listJobReports",
+    params(
+        ("name", Path, description = "The name of the job"),
+        Paginated,
+    ),
+    responses(
+        (status = 200, description = "Retrieved the job reports, the most recent first", body = PaginatedResults<JobReport>),
+    )
+)]
+#[get("/v2/job/{name}/report")]
+/// Get the reports of a job
+async fn get_reports(
+    service: web::Data<JobService>,
+    name: web::Path<String>,
+    web::Query(paginated): web::Query<Paginated>,
+    _: Require<ReadImporter>,
+) -> Result<impl Responder, Error> {
+    Ok(web::Json(service.get_reports(&name, paginated).await?))
+}
@@ -0,0 +1,30 @@
+use super::model::ScheduledJob;
+use crate::runner::common::{Error, heartbeat::Lock};
+use sea_orm::{QueryFilter, entity::*, prelude::*};
+use time::OffsetDateTime;
+use trustify_common::db::Database;
+use trustify_entity::job;
+
+impl Lock for ScheduledJob {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn beat(&self, db: &Database, hold: bool) -> Result<Self, Error> {
+        let t = OffsetDateTime::now_utc().unix_timestamp_nanos();
+        let model = job::ActiveModel {
+            name: Set(self.name.to_owned()),
+            heartbeat: Set(hold.then(|| t.into())),
+            ..Default::default()
+        };
+        use job::Column::Heartbeat;
+        let lock = match self.heartbeat {
+            Some(t) => Expr::col(Heartbeat).eq(Decimal::from_i128_with_scale(t, 0)),
+            None => Expr::col(Heartbeat).is_null(),
+        };
+        // We rely on the fact that `update` will return an error if
+        // no row is affected, as opposed to how `update_many` works
+        let model = job::Entity::update(model).filter(lock).exec(db).await?;
+        Ok(ScheduledJob::try_from(model)?)
+    }
+}
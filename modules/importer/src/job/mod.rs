@@ -0,0 +1,86 @@
+//! Scheduled maintenance jobs.
+//!
+//! Jobs are run by the importer server, next to the importers. Like importers, they get run
+//! periodically, and only by one instance at a time. However, jobs are not created through the
+//! API, but provided by the process running the scheduler.
+
+mod heartbeat;
+
+pub mod endpoints;
+pub mod model;
+pub mod server;
+pub mod service;
+
+#[cfg(test)]
+mod test;
+
+use std::{fmt::Debug, future::Future, pin::Pin, sync::Arc, time::Duration};
+use trustify_common::db::Database;
+
+pub type JobFuture = Pin<Box<dyn Future<Output = anyhow::Result<serde_json::Value>>>>;
+
+/// The definition of a job.
+#[derive(Clone)]
+pub struct Job {
+    /// The unique name of the job
+    pub name: String,
+    /// A description for users
+    pub description: String,
+    /// The period the job runs in, until configured otherwise
+    pub period: Duration,
+    /// Whether the period replaces the configured one when registering the job
+    pub override_period: bool,
+    run: Arc<dyn Fn(Database) -> JobFuture + Send + Sync>,
+}
+
+impl Debug for Job {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Job")
+            .field("name", &self.name)
+            .field("description", &self.description)
+            .field("period", &self.period)
+            .field("override_period", &self.override_period)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Job {
+    /// Create a new job, the outcome of a run gets stored as the report of that run.
+    pub fn new<F, Fut, T>(
+        name: impl Into<String>,
+        description: impl Into<String>,
+        period: Duration,
+        f: F,
+    ) -> Self
+    where
+        F: Fn(Database) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = anyhow::Result<T>> + 'static,
+        T: serde::Serialize,
+    {
+        Self {
+            name: name.into(),
+            description: description.into(),
+            period,
+            override_period: false,
+            run: Arc::new(move |db| {
+                let run = f(db);
+                Box::pin(async move { Ok(serde_json::to_value(run.await?)?) })
+            }),
+        }
+    }
+
+    /// Run the job in the provided period, overriding the configured one. Keeps the default period
+    /// if none is provided.
+    pub fn with_period(mut self, period: Option<Duration>) -> Self {
+        if let Some(period) = period {
+            self.period = period;
+            self.override_period = true;
+        }
+        self
+    }
+
+    /// Run the job once
+    pub fn run(&self, db: Database) -> JobFuture {
+        (self.run)(db)
+    }
+}
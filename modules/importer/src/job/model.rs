@@ -0,0 +1,144 @@
+use crate::{model::State, runner::common::heartbeat};
+use num_traits::ToPrimitive;
+use std::time::Duration;
+use time::OffsetDateTime;
+use trustify_entity::{job, job_report};
+use utoipa::ToSchema;
+
+/// A maintenance job, as scheduled by the importer server
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledJob {
+    /// The name of the job
+    pub name: String,
+    #[schema(ignore)]
+    pub heartbeat: Option<i128>,
+
+    /// A description of what the job does
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    pub configuration: JobConfiguration,
+
+    /// The current state of the job
+    pub state: State,
+
+    /// The last successful run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub last_success: Option<OffsetDateTime>,
+
+    /// The last run (successful or not)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub last_run: Option<OffsetDateTime>,
+
+    /// The error of the last run (empty if successful)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+impl ScheduledJob {
+    /// check if we need to run or skip the job
+    pub fn is_due(&self) -> bool {
+        match self.last_run {
+            Some(t) => (OffsetDateTime::now_utc() - t) > self.configuration.period,
+            None => true,
+        }
+    }
+
+    /// check if any instance is running this job
+    pub fn is_running(&self) -> bool {
+        self.heartbeat
+            .and_then(|t| OffsetDateTime::from_unix_timestamp_nanos(t).ok())
+            .is_some_and(|t| (OffsetDateTime::now_utc() - t) < (2 * heartbeat::RATE))
+    }
+
+    /// check if enabled to run
+    pub fn is_enabled(&self) -> bool {
+        !self.configuration.disabled
+    }
+}
+
+impl TryFrom<job::Model> for ScheduledJob {
+    type Error = serde_json::Error;
+
+    fn try_from(
+        job::Model {
+            name,
+            description,
+            configuration,
+            last_run,
+            last_success,
+            last_error,
+            heartbeat,
+        }: job::Model,
+    ) -> Result<Self, Self::Error> {
+        let mut result = Self {
+            name,
+            heartbeat: heartbeat.and_then(|t| t.to_i128()),
+            description,
+            configuration: serde_json::from_value(configuration)?,
+            state: State::Waiting,
+            last_success,
+            last_run,
+            last_error,
+        };
+        if result.is_running() {
+            result.state = State::Running;
+        }
+        Ok(result)
+    }
+}
+
+/// The user configurable settings of a job
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct JobConfiguration {
+    /// A flag to disable the job, without removing it.
+    #[serde(default)]
+    pub disabled: bool,
+
+    /// The period the job should be run.
+    #[serde(with = "humantime_serde")]
+    pub period: Duration,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct JobReport {
+    /// The ID of the report
+    pub id: String,
+
+    /// The name of the job this report belongs to
+    pub job: String,
+    /// The time the report was created
+    #[serde(with = "time::serde::rfc3339")]
+    pub creation: OffsetDateTime,
+
+    /// The error of the run (empty if successful)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// The job specific outcome of the run
+    #[serde(default, skip_serializing_if = "serde_json::Value::is_null")]
+    pub report: serde_json::Value,
+}
+
+impl From<job_report::Model> for JobReport {
+    fn from(value: job_report::Model) -> Self {
+        let job_report::Model {
+            id,
+            job,
+            creation,
+            error,
+            report,
+        } = value;
+        Self {
+            id: id.to_string(),
+            job,
+            creation,
+            error,
+            report,
+        }
+    }
+}
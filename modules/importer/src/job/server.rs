@@ -0,0 +1,93 @@
+use super::{Job, service::JobService};
+use crate::runner::common::heartbeat::Heart;
+use std::{collections::HashMap, time::Duration};
+use time::OffsetDateTime;
+use tokio::{task::LocalSet, time::MissedTickBehavior};
+use tokio_util::sync::CancellationToken;
+use tracing::instrument;
+use trustify_common::db::Database;
+
+/// run the job scheduler loop
+#[instrument(skip_all, ret)]
+pub async fn scheduler(db: Database, jobs: Vec<Job>) -> anyhow::Result<()> {
+    // The Heart struct spawns locally, so we need a LocalSet
+    LocalSet::new().run_until(run(db, jobs)).await
+}
+
+async fn run(db: Database, jobs: Vec<Job>) -> anyhow::Result<()> {
+    let service = JobService::new(db.clone());
+    service.register(&jobs).await?;
+
+    let jobs: HashMap<_, _> = jobs
+        .into_iter()
+        .map(|job| (job.name.clone(), job))
+        .collect();
+
+    let mut interval = tokio::time::interval(Duration::from_secs(1));
+    interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+    // Maintain a list of currently running jobs
+    let mut runs: HashMap<String, Heart> = HashMap::new();
+
+    loop {
+        interval.tick().await;
+
+        runs.retain(|_, heart| heart.is_beating());
+
+        for scheduled in service.list().await? {
+            if !scheduled.is_enabled()
+                || !scheduled.is_due()
+                || scheduled.is_running()
+                || runs.contains_key(&scheduled.name)
+            {
+                continue;
+            }
+
+            // jobs might be registered by a different version of the scheduler
+            let Some(job) = jobs.get(&scheduled.name) else {
+                continue;
+            };
+
+            // jobs can't be asked to stop, so losing the lock aborts the job
+            let name = scheduled.name.clone();
+            let token = CancellationToken::new();
+            let work = execute(service.clone(), job.clone(), db.clone());
+            let heart = Heart::new(
+                scheduled,
+                db.clone(),
+                {
+                    let token = token.clone();
+                    async move {
+                        tokio::select! {
+                            _ = work => {}
+                            _ = token.cancelled() => {}
+                        }
+                    }
+                },
+                token,
+            );
+            runs.insert(name, heart);
+        }
+    }
+}
+
+async fn execute(service: JobService, job: Job, db: Database) {
+    // record timestamp before processing, so that the period doesn't include the runtime
+    let start = OffsetDateTime::now_utc();
+
+    log::info!("Starting job: {}", job.name);
+
+    let (last_error, report) = match job.run(db).await {
+        Ok(report) => (None, report),
+        Err(err) => (Some(err.to_string()), serde_json::Value::Null),
+    };
+
+    log::info!("Job run complete: {}: {last_error:?}", job.name);
+
+    if let Err(err) = service
+        .update_finish(&job.name, start, last_error, report)
+        .await
+    {
+        log::warn!("Failed to record the outcome of job '{}': {err}", job.name);
+    }
+}
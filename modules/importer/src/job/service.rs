@@ -0,0 +1,224 @@
+use super::{
+    Job,
+    model::{JobConfiguration, JobReport, ScheduledJob},
+};
+use actix_web::{HttpResponse, ResponseError, body::BoxBody};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter,
+    QueryOrder, TransactionTrait,
+};
+use sea_query::{Expr, OnConflict, SimpleExpr};
+use time::OffsetDateTime;
+use tracing::instrument;
+use trustify_common::{
+    db::{Database, limiter::LimiterTrait},
+    error::ErrorInformation,
+    model::{Paginated, PaginatedResults},
+};
+use trustify_entity::{job, job_report};
+use uuid::Uuid;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("job '{0}' not found")]
+    NotFound(String),
+    #[error("database error: {0}")]
+    Database(#[from] sea_orm::DbErr),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+impl ResponseError for Error {
+    fn error_response(&self) -> HttpResponse<BoxBody> {
+        match self {
+            Error::NotFound(_) => HttpResponse::NotFound().json(ErrorInformation {
+                error: "NotFound".into(),
+                message: self.to_string(),
+                details: None,
+            }),
+            _ => HttpResponse::InternalServerError().json(ErrorInformation {
+                error: "Internal".into(),
+                message: self.to_string(),
+                details: None,
+            }),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct JobService {
+    db: Database,
+}
+
+impl JobService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Make jobs known, keeping the configuration of already known jobs. Unless the job overrides
+    /// its period, which then replaces the configured one.
+    #[instrument(skip_all, err)]
+    pub async fn register(&self, jobs: &[Job]) -> Result<(), Error> {
+        for definition in jobs {
+            let configuration = JobConfiguration {
+                disabled: false,
+                period: definition.period,
+            };
+            let entity = job::ActiveModel {
+                name: Set(definition.name.clone()),
+                description: Set(Some(definition.description.clone())),
+                configuration: Set(serde_json::to_value(configuration)?),
+                last_run: Set(None),
+                last_success: Set(None),
+                last_error: Set(None),
+                heartbeat: Set(None),
+            };
+            job::Entity::insert(entity)
+                .on_conflict(
+                    OnConflict::column(job::Column::Name)
+                        .update_column(job::Column::Description)
+                        .to_owned(),
+                )
+                .exec(&self.db)
+                .await?;
+
+            if definition.override_period {
+                if let Some(job) = self.read(&definition.name).await? {
+                    if job.configuration.period != definition.period {
+                        let configuration = JobConfiguration {
+                            period: definition.period,
+                            ..job.configuration
+                        };
+                        self.update_configuration(&definition.name, configuration)
+                            .await?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn list(&self) -> Result<Vec<ScheduledJob>, Error> {
+        Ok(job::Entity::find()
+            .order_by_asc(job::Column::Name)
+            .all(&self.db)
+            .await?
+            .into_iter()
+            .map(ScheduledJob::try_from)
+            .collect::<Result<_, _>>()?)
+    }
+
+    pub async fn read(&self, name: &str) -> Result<Option<ScheduledJob>, Error> {
+        Ok(job::Entity::find_by_id(name)
+            .one(&self.db)
+            .await?
+            .map(ScheduledJob::try_from)
+            .transpose()?)
+    }
+
+    pub async fn update_configuration(
+        &self,
+        name: &str,
+        configuration: JobConfiguration,
+    ) -> Result<(), Error> {
+        self.update(
+            &self.db,
+            name,
+            vec![(
+                job::Column::Configuration,
+                Expr::value(serde_json::to_value(configuration)?),
+            )],
+        )
+        .await
+    }
+
+    /// Reset the last-run timestamp to force a new run
+    #[instrument(skip(self))]
+    pub async fn reset(&self, name: &str) -> Result<(), Error> {
+        self.update(
+            &self.db,
+            name,
+            vec![(job::Column::LastRun, Expr::value(None::<OffsetDateTime>))],
+        )
+        .await
+    }
+
+    #[instrument(skip(self, report), err)]
+    pub async fn update_finish(
+        &self,
+        name: &str,
+        start: OffsetDateTime,
+        last_error: Option<String>,
+        report: serde_json::Value,
+    ) -> Result<(), Error> {
+        let tx = self.db.begin().await?;
+
+        let mut updates = vec![
+            (job::Column::LastError, Expr::value(last_error.clone())),
+            (job::Column::LastRun, Expr::value(start)),
+        ];
+        if last_error.is_none() {
+            updates.push((job::Column::LastSuccess, Expr::value(start)));
+        }
+
+        self.update(&tx, name, updates).await?;
+
+        let entity = job_report::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            job: Set(name.to_string()),
+            creation: Set(OffsetDateTime::now_utc()),
+            error: Set(last_error),
+            report: Set(report),
+        };
+        entity.insert(&tx).await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn update<C>(
+        &self,
+        db: &C,
+        name: &str,
+        updates: Vec<(job::Column, SimpleExpr)>,
+    ) -> Result<(), Error>
+    where
+        C: ConnectionTrait,
+    {
+        let mut update = job::Entity::update_many().filter(job::Column::Name.eq(name));
+
+        for (col, expr) in updates {
+            update = update.col_expr(col, expr);
+        }
+
+        match update.exec(db).await?.rows_affected {
+            0 => Err(Error::NotFound(name.to_string())),
+            _ => Ok(()),
+        }
+    }
+
+    #[instrument(skip(self))]
+    pub async fn get_reports(
+        &self,
+        name: &str,
+        paginated: Paginated,
+    ) -> Result<PaginatedResults<JobReport>, Error> {
+        let limiting = job_report::Entity::find()
+            .filter(job_report::Column::Job.eq(name))
+            .order_by_desc(job_report::Column::Creation)
+            .limiting(&self.db, &paginated);
+
+        Ok(PaginatedResults {
+            total: limiting.total().await?,
+            items: limiting
+                .fetch()
+                .await?
+                .into_iter()
+                .map(JobReport::from)
+                .collect(),
+            next: None,
+        })
+    }
+}
@@ -0,0 +1,226 @@
+use super::{
+    Job,
+    model::{JobConfiguration, JobReport, ScheduledJob},
+    server::scheduler,
+    service::JobService,
+};
+use actix_web::{App, http::StatusCode, test as actix};
+use serde_json::json;
+use std::time::Duration;
+use test_context::test_context;
+use test_log::test;
+use trustify_common::{
+    db::Database,
+    model::{Paginated, PaginatedResults},
+};
+use trustify_test_context::{TrustifyContext, app::TestApp};
+use utoipa_actix_web::AppExt;
+
+fn succeeding() -> Job {
+    Job::new(
+        "count",
+        "Report a value",
+        Duration::from_secs(3600),
+        |_| async { Ok::<_, anyhow::Error>(json!({ "count": 42 })) },
+    )
+}
+
+fn failing() -> Job {
+    Job::new(
+        "fail",
+        "Always fail",
+        Duration::from_secs(3600),
+        |_| async { Err::<(), _>(anyhow::anyhow!("out of luck")) },
+    )
+}
+
+/// Wait until each job has at least one report, or time out.
+async fn wait_for_reports(service: &JobService, names: &[&str]) -> anyhow::Result<()> {
+    loop {
+        let mut done = true;
+        for name in names {
+            done &= service.get_reports(name, Paginated::default()).await?.total > Some(0);
+        }
+        if done {
+            return Ok(());
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+}
+
+async fn run_until_reported(db: Database, jobs: Vec<Job>) -> anyhow::Result<()> {
+    let service = JobService::new(db.clone());
+    let names: Vec<_> = jobs.iter().map(|job| job.name.clone()).collect();
+    let names: Vec<_> = names.iter().map(String::as_str).collect();
+
+    tokio::time::timeout(Duration::from_secs(30), async {
+        tokio::select! {
+            result = scheduler(db, jobs) => result,
+            result = wait_for_reports(&service, &names) => result,
+        }
+    })
+    .await?
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn run_jobs(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let service = JobService::new(ctx.db.clone());
+
+    run_until_reported(ctx.db.clone(), vec![succeeding(), failing()]).await?;
+
+    let jobs = service.list().await?;
+    assert_eq!(jobs.len(), 2);
+
+    let count = &jobs[0];
+    assert_eq!(count.name, "count");
+    assert_eq!(count.description.as_deref(), Some("Report a value"));
+    assert!(count.last_success.is_some());
+    assert_eq!(count.last_error, None);
+    assert!(!count.is_due());
+
+    let fail = &jobs[1];
+    assert_eq!(fail.name, "fail");
+    assert_eq!(fail.last_success, None);
+    assert_eq!(fail.last_error.as_deref(), Some("out of luck"));
+
+    let reports = service.get_reports("count", Paginated::default()).await?;
+    assert_eq!(reports.total, Some(1));
+    assert_eq!(reports.items[0].report, json!({ "count": 42 }));
+
+    let reports = service.get_reports("fail", Paginated::default()).await?;
+    assert_eq!(reports.items[0].error.as_deref(), Some("out of luck"));
+    assert_eq!(reports.items[0].report, serde_json::Value::Null);
+
+    Ok(())
+}
+
+/// Registering a job again must keep its configuration
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn register(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let service = JobService::new(ctx.db.clone());
+
+    service.register(&[succeeding()]).await?;
+    service
+        .update_configuration(
+            "count",
+            JobConfiguration {
+                disabled: true,
+                period: Duration::from_secs(60),
+            },
+        )
+        .await?;
+
+    service.register(&[succeeding()]).await?;
+
+    let job = service.read("count").await?.expect("must be found");
+    assert_eq!(
+        job.configuration,
+        JobConfiguration {
+            disabled: true,
+            period: Duration::from_secs(60),
+        }
+    );
+    assert!(!job.is_enabled());
+
+    // overriding the period must only replace the period
+
+    service
+        .register(&[succeeding().with_period(Some(Duration::from_secs(120)))])
+        .await?;
+
+    let job = service.read("count").await?.expect("must be found");
+    assert_eq!(
+        job.configuration,
+        JobConfiguration {
+            disabled: true,
+            period: Duration::from_secs(120),
+        }
+    );
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn endpoints(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let db = ctx.db.clone();
+    let app = actix::init_service(
+        App::new()
+            .into_utoipa_app()
+            .add_test_authorizer()
+            .service(
+                utoipa_actix_web::scope("/api")
+                    .configure(|svc| crate::endpoints::configure(svc, db)),
+            )
+            .into_app(),
+    )
+    .await;
+
+    run_until_reported(ctx.db.clone(), vec![succeeding()]).await?;
+
+    // list
+
+    let req = actix::TestRequest::get().uri("/api/v2/job").to_request();
+    let result: Vec<ScheduledJob> = actix::call_and_read_body_json(&app, req).await;
+    assert_eq!(result.len(), 1);
+    assert_eq!(result[0].name, "count");
+    assert!(result[0].last_run.is_some());
+
+    // reports
+
+    let req = actix::TestRequest::get()
+        .uri("/api/v2/job/count/report")
+        .to_request();
+    let result: PaginatedResults<JobReport> = actix::call_and_read_body_json(&app, req).await;
+    assert_eq!(result.total, Some(1));
+    assert_eq!(result.items[0].job, "count");
+
+    // update the configuration
+
+    let req = actix::TestRequest::put()
+        .uri("/api/v2/job/count")
+        .set_json(json!({ "disabled": true, "period": "5m" }))
+        .to_request();
+    let resp = actix::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+    // force a new run
+
+    let req = actix::TestRequest::post()
+        .uri("/api/v2/job/count/force")
+        .to_request();
+    let resp = actix::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+    let req = actix::TestRequest::get()
+        .uri("/api/v2/job/count")
+        .to_request();
+    let result: ScheduledJob = actix::call_and_read_body_json(&app, req).await;
+    assert_eq!(result.last_run, None);
+    assert!(result.is_due());
+    assert_eq!(
+        result.configuration,
+        JobConfiguration {
+            disabled: true,
+            period: Duration::from_secs(300),
+        }
+    );
+
+    // unknown jobs
+
+    let req = actix::TestRequest::get()
+        .uri("/api/v2/job/unknown")
+        .to_request();
+    let resp = actix::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    let req = actix::TestRequest::post()
+        .uri("/api/v2/job/unknown/force")
+        .to_request();
+    let resp = actix::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
@@ -1,6 +1,7 @@
 #![recursion_limit = "256"]
 
 pub mod endpoints;
+pub mod job;
 pub mod model;
 pub mod runner;
 pub mod server;
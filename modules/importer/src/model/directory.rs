@@ -0,0 +1,43 @@
+use super::*;
+use trustify_common::model::BinaryByteSize;
+
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    ToSchema,
+    schemars::JsonSchema,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct DirectoryImporter {
+    #[serde(flatten)]
+    pub common: CommonImporter,
+
+    /// The local path to scan. Either a directory, or a dataset archive (zip or tar) file.
+    pub source: String,
+
+    /// Only import files with a name matching one of the patterns (regular expressions).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub only_patterns: Vec<String>,
+
+    /// The maximum size of a (decompressed) document.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size_limit: Option<BinaryByteSize>,
+}
+
+impl Deref for DirectoryImporter {
+    type Target = CommonImporter;
+
+    fn deref(&self) -> &Self::Target {
+        &self.common
+    }
+}
+
+impl DerefMut for DirectoryImporter {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.common
+    }
+}
@@ -0,0 +1,86 @@
+use super::*;
+use std::collections::HashSet;
+
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    ToSchema,
+    schemars::JsonSchema,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct GhsaImporter {
+    #[serde(flatten)]
+    pub common: CommonImporter,
+
+    /// The URL to the git repository of the GitHub Advisory Database
+    #[serde(default = "default::source")]
+    pub source: String,
+
+    /// An optional branch. Will use the default branch otherwise.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+
+    /// The path to start searching for documents. Defaults to the reviewed advisories.
+    #[serde(default = "default::path")]
+    pub path: String,
+
+    #[serde(default, skip_serializing_if = "HashSet::is_empty")]
+    pub years: HashSet<u16>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_year: Option<u16>,
+}
+
+pub const DEFAULT_SOURCE_GHSA: &str = "https://github.com/github/advisory-database";
+pub const DEFAULT_PATH_GHSA: &str = "advisories/github-reviewed";
+
+mod default {
+    pub fn source() -> String {
+        super::DEFAULT_SOURCE_GHSA.into()
+    }
+
+    pub fn path() -> String {
+        super::DEFAULT_PATH_GHSA.into()
+    }
+}
+
+/// The GitHub Advisory Database is stored in the OSV format
+impl From<GhsaImporter> for OsvImporter {
+    fn from(value: GhsaImporter) -> Self {
+        let GhsaImporter {
+            common,
+            source,
+            branch,
+            path,
+            years,
+            start_year,
+        } = value;
+
+        Self {
+            common,
+            source,
+            branch,
+            path: Some(path),
+            years,
+            start_year,
+        }
+    }
+}
+
+impl Deref for GhsaImporter {
+    type Target = CommonImporter;
+
+    fn deref(&self) -> &Self::Target {
+        &self.common
+    }
+}
+
+impl DerefMut for GhsaImporter {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.common
+    }
+}
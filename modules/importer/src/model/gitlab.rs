@@ -0,0 +1,51 @@
+use super::*;
+
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    ToSchema,
+    schemars::JsonSchema,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct GitlabImporter {
+    #[serde(flatten)]
+    pub common: CommonImporter,
+
+    /// The URL to the git repository of the GitLab advisory database (gemnasium format)
+    #[serde(default = "default::source")]
+    pub source: String,
+
+    /// An optional branch. Will use the default branch otherwise.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+
+    /// An optional path to start searching for documents. Will use the root of the repository otherwise.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+}
+
+pub const DEFAULT_SOURCE_GITLAB: &str = "https://gitlab.com/gitlab-org/advisories-community";
+
+mod default {
+    pub fn source() -> String {
+        super::DEFAULT_SOURCE_GITLAB.into()
+    }
+}
+
+impl Deref for GitlabImporter {
+    type Target = CommonImporter;
+
+    fn deref(&self) -> &Self::Target {
+        &self.common
+    }
+}
+
+impl DerefMut for GitlabImporter {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.common
+    }
+}
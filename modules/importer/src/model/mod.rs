@@ -4,16 +4,27 @@ mod clearly_defined;
 mod csaf;
 mod cve;
 mod cwe;
+mod directory;
+mod ghsa;
+mod gitlab;
 mod osv;
+mod rustsec;
 mod sbom;
 
-use crate::runner::{common::heartbeat::Heart, report::Report};
+use crate::runner::{
+    common::heartbeat,
+    report::{Outcome, Phase, Report, ReportItem},
+};
 pub use clearly_defined::*;
 pub use clearly_defined_curation::*;
 pub use csaf::*;
 pub use cve::*;
 pub use cwe::*;
+pub use directory::*;
+pub use ghsa::*;
+pub use gitlab::*;
 pub use osv::*;
+pub use rustsec::*;
 pub use sbom::*;
 
 use num_traits::cast::ToPrimitive;
@@ -25,7 +36,7 @@ use time::OffsetDateTime;
 use trustify_common::model::Revisioned;
 use trustify_entity::{
     importer::{self, Model},
-    importer_report,
+    importer_report, importer_report_item,
     labels::Labels,
 };
 use url::Url;
@@ -51,6 +62,10 @@ impl Importer {
 
     /// check if we need to run or skip the importer
     pub fn is_due(&self) -> bool {
+        if self.data.retry.is_some() {
+            return true;
+        }
+
         match self.data.last_run {
             Some(t) => (OffsetDateTime::now_utc() - t) > self.data.configuration.period,
             None => true,
@@ -61,7 +76,7 @@ impl Importer {
     pub fn is_running(&self) -> bool {
         self.heartbeat
             .and_then(|t| OffsetDateTime::from_unix_timestamp_nanos(t).ok())
-            .is_some_and(|t| (OffsetDateTime::now_utc() - t) < (2 * Heart::RATE))
+            .is_some_and(|t| (OffsetDateTime::now_utc() - t) < (2 * heartbeat::RATE))
     }
 
     /// check if enabled to run
@@ -128,6 +143,10 @@ pub struct ImporterData {
     /// The continuation token of the importer.
     #[serde(default, skip_serializing_if = "serde_json::Value::is_null")]
     pub continuation: serde_json::Value,
+
+    /// The ID of a report, of which the failed documents will be retried with the next run.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry: Option<String>,
 }
 
 #[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, ToSchema)]
@@ -180,6 +199,10 @@ pub enum ImporterConfiguration {
     ClearlyDefined(ClearlyDefinedImporter),
     ClearlyDefinedCuration(ClearlyDefinedCurationImporter),
     Cwe(CweImporter),
+    Ghsa(GhsaImporter),
+    Gitlab(GitlabImporter),
+    Rustsec(RustsecImporter),
+    Directory(DirectoryImporter),
 }
 
 impl Deref for ImporterConfiguration {
@@ -194,6 +217,10 @@ impl Deref for ImporterConfiguration {
             Self::ClearlyDefined(importer) => &importer.common,
             Self::ClearlyDefinedCuration(importer) => &importer.common,
             Self::Cwe(importer) => &importer.common,
+            Self::Ghsa(importer) => &importer.common,
+            Self::Gitlab(importer) => &importer.common,
+            Self::Rustsec(importer) => &importer.common,
+            Self::Directory(importer) => &importer.common,
         }
     }
 }
@@ -208,6 +235,10 @@ impl DerefMut for ImporterConfiguration {
             Self::ClearlyDefined(importer) => &mut importer.common,
             Self::ClearlyDefinedCuration(importer) => &mut importer.common,
             Self::Cwe(importer) => &mut importer.common,
+            Self::Ghsa(importer) => &mut importer.common,
+            Self::Gitlab(importer) => &mut importer.common,
+            Self::Rustsec(importer) => &mut importer.common,
+            Self::Directory(importer) => &mut importer.common,
         }
     }
 }
@@ -242,6 +273,13 @@ pub struct CommonImporter {
     pub labels: Labels,
 }
 
+impl ImporterConfiguration {
+    /// Check if the importer can process only a selection of documents
+    pub fn supports_retry(&self) -> bool {
+        !matches!(self, Self::ClearlyDefined(_) | Self::Cwe(_))
+    }
+}
+
 // Just here to create a schema for humantime_serde.
 #[derive(schemars::JsonSchema)]
 struct HumantimeSerde(#[allow(unused)] String);
@@ -263,6 +301,7 @@ impl TryFrom<Model> for Importer {
             progress_message,
             continuation,
             heartbeat,
+            retry,
             revision: _,
         }: Model,
     ) -> Result<Self, Self::Error> {
@@ -283,6 +322,7 @@ impl TryFrom<Model> for Importer {
                     progress_message,
                 ),
                 continuation: continuation.unwrap_or_default(),
+                retry: retry.map(|retry| retry.to_string()),
                 configuration: serde_json::from_value(configuration)?,
             },
         })
@@ -327,6 +367,69 @@ impl From<importer_report::Model> for ImporterReport {
     }
 }
 
+impl From<importer_report_item::Model> for ReportItem {
+    fn from(value: importer_report_item::Model) -> Self {
+        let importer_report_item::Model {
+            id: _,
+            report: _,
+            file,
+            outcome,
+            phase,
+            message,
+            document,
+            document_id,
+        } = value;
+        Self {
+            file,
+            outcome: outcome.into(),
+            phase: phase.map(Into::into),
+            message,
+            document,
+            document_id,
+        }
+    }
+}
+
+impl From<importer_report_item::Outcome> for Outcome {
+    fn from(value: importer_report_item::Outcome) -> Self {
+        match value {
+            importer_report_item::Outcome::Ingested => Self::Ingested,
+            importer_report_item::Outcome::Skipped => Self::Skipped,
+            importer_report_item::Outcome::Failed => Self::Failed,
+        }
+    }
+}
+
+impl From<Outcome> for importer_report_item::Outcome {
+    fn from(value: Outcome) -> Self {
+        match value {
+            Outcome::Ingested => Self::Ingested,
+            Outcome::Skipped => Self::Skipped,
+            Outcome::Failed => Self::Failed,
+        }
+    }
+}
+
+impl From<importer_report_item::Phase> for Phase {
+    fn from(value: importer_report_item::Phase) -> Self {
+        match value {
+            importer_report_item::Phase::Retrieval => Self::Retrieval,
+            importer_report_item::Phase::Validation => Self::Validation,
+            importer_report_item::Phase::Upload => Self::Upload,
+        }
+    }
+}
+
+impl From<Phase> for importer_report_item::Phase {
+    fn from(value: Phase) -> Self {
+        match value {
+            Phase::Retrieval => Self::Retrieval,
+            Phase::Validation => Self::Validation,
+            Phase::Upload => Self::Upload,
+        }
+    }
+}
+
 /// Create the progress from the progress state
 fn into_progress(
     start: OffsetDateTime,
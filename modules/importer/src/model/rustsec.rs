@@ -0,0 +1,56 @@
+use super::*;
+
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    ToSchema,
+    schemars::JsonSchema,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct RustsecImporter {
+    #[serde(flatten)]
+    pub common: CommonImporter,
+
+    /// The URL to the git repository of the RustSec advisory database
+    #[serde(default = "default::source")]
+    pub source: String,
+
+    /// An optional branch. Will use the default branch otherwise.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+
+    /// The path to start searching for documents. Defaults to the crate advisories.
+    #[serde(default = "default::path")]
+    pub path: String,
+}
+
+pub const DEFAULT_SOURCE_RUSTSEC: &str = "https://github.com/rustsec/advisory-db";
+pub const DEFAULT_PATH_RUSTSEC: &str = "crates";
+
+mod default {
+    pub fn source() -> String {
+        super::DEFAULT_SOURCE_RUSTSEC.into()
+    }
+
+    pub fn path() -> String {
+        super::DEFAULT_PATH_RUSTSEC.into()
+    }
+}
+
+impl Deref for RustsecImporter {
+    type Target = CommonImporter;
+
+    fn deref(&self) -> &Self::Target {
+        &self.common
+    }
+}
+
+impl DerefMut for RustsecImporter {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.common
+    }
+}
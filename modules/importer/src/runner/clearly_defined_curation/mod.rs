@@ -32,7 +32,7 @@ impl<C: RunContext> Context<C> {
     fn store(&self, path: &Path, data: Vec<u8>) -> anyhow::Result<()> {
         self.report.lock().tick();
 
-        Handle::current().block_on(async {
+        let result = Handle::current().block_on(async {
             self.ingestor
                 .ingest(
                     &data,
@@ -47,6 +47,10 @@ impl<C: RunContext> Context<C> {
                 .await
         })?;
 
+        self.report
+            .lock()
+            .add_ingested(path.to_string_lossy(), &result);
+
         Ok(())
     }
 }
@@ -103,6 +107,12 @@ impl super::ImportRunner {
             clearly_defined.source
         ));
 
+        // files to retry, if any
+
+        let only = context
+            .retry()
+            .map(|retry| retry.iter().map(PathBuf::from).collect());
+
         // run the walker
 
         let walker = GitWalker::new(
@@ -120,6 +130,7 @@ impl super::ImportRunner {
         )
         .path(Some("curations"))
         .continuation(continuation)
+        .only(only)
         .progress(progress);
 
         let continuation = match working_dir {
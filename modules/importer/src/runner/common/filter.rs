@@ -1,6 +1,6 @@
 use crate::runner::report::ScannerError;
 use regex::Regex;
-use std::str::FromStr;
+use std::{collections::HashSet, str::FromStr};
 use walker_common::utils::url::Urlify;
 
 mod csaf {
@@ -12,6 +12,8 @@ mod sbom {
 
 pub struct Filter<T> {
     pub only_patterns: Vec<Regex>,
+    /// If present, only process documents with one of those URLs
+    pub only: Option<HashSet<String>>,
     pub next: T,
 }
 
@@ -23,14 +25,27 @@ impl<T> Filter<T> {
                 .map(|r| Regex::from_str(&r))
                 .collect::<Result<_, _>>()
                 .map_err(|err| ScannerError::Critical(err.into()))?,
+            only: None,
             next,
         })
     }
 
+    /// Only process documents with one of the provided URLs
+    pub fn only(mut self, only: Option<HashSet<String>>) -> Self {
+        self.only = only;
+        self
+    }
+
     /// check if the document should be skipped
     ///
     /// return `true` of the document should be skipped, `false` otherwise
     fn skip(&self, document: &impl Urlify) -> bool {
+        if let Some(only) = &self.only {
+            if !only.contains(document.url().as_str()) {
+                return true;
+            }
+        }
+
         if self.only_patterns.is_empty() {
             false
         } else {
@@ -12,19 +12,35 @@ use tokio_util::sync::CancellationToken;
 use trustify_common::db::Database;
 use trustify_entity::importer;
 
+/// The rate at which a [`Heart`] refreshes its lock.
+pub const RATE: Duration = Duration::from_secs(10);
+
+/// A record which can be locked by a [`Heart`].
+pub trait Lock: Sized + 'static {
+    /// The name of the record, for logging.
+    fn name(&self) -> &str;
+
+    /// Employs an optimistic locking strategy to update the record
+    /// with the current time (or clear it, when releasing the lock),
+    /// but only if the db matches the state of `self`, otherwise an
+    /// error is returned. Upon success, the updated record is
+    /// returned.
+    fn beat(&self, db: &Database, hold: bool) -> impl Future<Output = Result<Self, Error>>;
+}
+
+/// Holds the lock of a [`Lock`] record while a future runs.
 pub struct Heart {
     handle: JoinHandle<()>,
     token: CancellationToken,
 }
 
 impl Heart {
-    pub const RATE: Duration = Duration::from_secs(10);
-
-    pub fn new<F>(importer: Importer, db: Database, future: F, token: CancellationToken) -> Self
+    pub fn new<T, F>(lock: T, db: Database, future: F, token: CancellationToken) -> Self
     where
+        T: Lock,
         F: Future + 'static,
     {
-        let handle = spawn_local(Heart::pump(importer, db, future, token.clone()));
+        let handle = spawn_local(Heart::pump(lock, db, future, token.clone()));
         Self { handle, token }
     }
 
@@ -34,69 +50,84 @@ impl Heart {
 
     // Attempts to acquire exclusive optimistic lock. Upon success,
     // spawns the future, and regularly updates the lock until the
-    // future completes.
-    async fn pump<F>(importer: Importer, db: Database, future: F, token: CancellationToken)
+    // future completes. Losing the lock cancels the token.
+    async fn pump<T, F>(lock: T, db: Database, future: F, token: CancellationToken)
     where
+        T: Lock,
         F: Future + 'static,
     {
-        let name = importer.name.clone();
-        if let Ok(importer) = Heart::beat(&importer, &db).await {
+        let name = lock.name().to_string();
+        if let Ok(lock) = lock.beat(&db, true).await {
             log::debug!("Acquired lock; running '{name}'");
-            let work = spawn_local(future);
-            let mut interval = interval(Heart::RATE);
-            let mut importer = importer;
+            let mut work = spawn_local(future);
+            let mut interval = interval(RATE);
+            let mut lock = lock;
             loop {
-                interval.tick().await;
-                match Self::beat(&importer, &db).await {
-                    Ok(i) => {
-                        log::debug!("{name}: {:#?}", i.data.progress);
-                        importer = i;
+                tokio::select! {
+                    _ = &mut work => {
+                        log::debug!("Releasing lock; finished '{name}'");
+                        // once canceled, we don't hold the lock anymore
+                        if !token.is_cancelled() {
+                            if let Err(e) = lock.beat(&db, false).await {
+                                log::warn!("Failed to release lock of '{name}': {e}");
+                            }
+                        }
+                        break;
                     }
-                    Err(e) => {
-                        log::error!("Aborting '{name}': {e}");
-                        token.cancel();
+                    _ = interval.tick() => {
+                        if token.is_cancelled() {
+                            log::warn!("'{name}' not responding to cancel");
+                            continue;
+                        }
+                        match lock.beat(&db, true).await {
+                            Ok(l) => lock = l,
+                            Err(e) => {
+                                log::error!("Aborting '{name}': {e}");
+                                token.cancel();
+                            }
+                        }
                     }
                 }
-                if work.is_finished() {
-                    log::debug!("Releasing lock; finished '{name}'");
-                    break;
-                } else if token.is_cancelled() {
-                    log::warn!("Importer '{name}' not responding to cancel");
-                }
             }
         } else {
             log::debug!("Unable to acquire lock to run '{name}'");
         }
     }
+}
+
+impl Drop for Heart {
+    fn drop(&mut self) {
+        self.token.cancel();
+        self.handle.abort();
+    }
+}
+
+impl Lock for Importer {
+    fn name(&self) -> &str {
+        &self.name
+    }
 
-    // Employs an optimistic locking strategy to update the importer
-    // record with the current time, but only if the db matches the
-    // state of the &Importer parameter, otherwise an error is
-    // returned. Upon success, the updated Importer is returned.
-    async fn beat(importer: &Importer, db: &Database) -> Result<Importer, Error> {
+    async fn beat(&self, db: &Database, hold: bool) -> Result<Self, Error> {
         let t = OffsetDateTime::now_utc().unix_timestamp_nanos();
         let model = importer::ActiveModel {
-            name: Set(importer.name.to_owned()),
-            heartbeat: Set(Some(t.into())),
+            name: Set(self.name.to_owned()),
+            heartbeat: Set(hold.then(|| t.into())),
             ..Default::default()
         };
         use importer::Column::Heartbeat;
-        let lock = match importer.heartbeat {
+        let lock = match self.heartbeat {
             Some(t) => Expr::col(Heartbeat).eq(Decimal::from_i128_with_scale(t, 0)),
             None => Expr::col(Heartbeat).is_null(),
         };
         // We rely on the fact that `update` will return an error if
         // no row is affected, as opposed to how `update_many` works
         match importer::Entity::update(model).filter(lock).exec(db).await {
-            Ok(model) => Importer::try_from(model).map_err(Error::Json),
+            Ok(model) => {
+                let importer = Importer::try_from(model).map_err(Error::Json)?;
+                log::debug!("{}: {:#?}", importer.name, importer.data.progress);
+                Ok(importer)
+            }
             Err(e) => Err(Error::Heartbeat(e)),
         }
     }
 }
-
-impl Drop for Heart {
-    fn drop(&mut self) {
-        self.token.cancel();
-        self.handle.abort();
-    }
-}
@@ -70,6 +70,9 @@ where
 
     /// Fetch depth, <=0 means get everything
     pub depth: i32,
+
+    /// If present, only process those files, ignoring the continuation
+    pub only: Option<HashSet<PathBuf>>,
 }
 
 impl<H> GitWalker<H, (), ()>
@@ -89,6 +92,7 @@ where
             handler,
             progress: (),
             depth: 1, // shallow clone, by default
+            only: None,
         }
     }
 }
@@ -109,6 +113,7 @@ where
             handler,
             progress: self.progress,
             depth: self.depth,
+            only: self.only,
         }
     }
 
@@ -122,6 +127,7 @@ where
             handler: self.handler,
             progress,
             depth: self.depth,
+            only: self.only,
         }
     }
 
@@ -146,6 +152,7 @@ where
             handler: self.handler,
             progress: self.progress,
             depth: self.depth,
+            only: self.only,
         }
     }
 
@@ -164,6 +171,12 @@ where
         self
     }
 
+    /// Only process the provided files, either relative to the base or absolute.
+    pub fn only(mut self, only: Option<HashSet<PathBuf>>) -> Self {
+        self.only = only;
+        self
+    }
+
     /// Set a continuation token from a previous run.
     pub fn continuation(mut self, continuation: Continuation) -> Self {
         self.continuation = continuation;
@@ -298,7 +311,7 @@ where
         }
 
         let mut fo = self.create_fetch_options();
-        if self.continuation.0.is_none() {
+        if self.continuation.0.is_none() && !self.is_local() {
             fo.depth(self.depth);
         }
 
@@ -403,10 +416,20 @@ where
 
         let mut fo = FetchOptions::new();
         fo.remote_callbacks(cb);
-        fo.depth(i32::MAX);
+        if !self.is_local() {
+            fo.depth(i32::MAX);
+        }
         fo
     }
 
+    /// Check if the source is a local repository, which doesn't support shallow clones
+    ///
+    /// Local repositories must be given as `file://` URLs. Whatever exists on the filesystem
+    /// of the server must not change how a source is treated.
+    fn is_local(&self) -> bool {
+        self.source.starts_with("file://")
+    }
+
     #[instrument(skip(self, changes), err)]
     fn walk(&self, base: &Path, changes: &Option<HashSet<PathBuf>>) -> Result<(), Error> {
         let mut collected = vec![];
@@ -428,7 +451,12 @@ where
             // the path, relative to the base (plus repo) dir
             let path = path.strip_prefix(base).unwrap_or(path);
 
-            if let Some(changes) = changes {
+            if let Some(only) = &self.only {
+                if !only.contains(path) && !only.contains(entry.path()) {
+                    log::trace!("Skipping {}, as file was not requested", path.display());
+                    continue;
+                }
+            } else if let Some(changes) = changes {
                 if !changes.contains(path) {
                     log::trace!("Skipping {}, as file did not change", path.display());
                     continue;
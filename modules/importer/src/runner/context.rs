@@ -1,5 +1,5 @@
 use crate::runner::progress::Progress;
-use std::{fmt::Debug, future::Future};
+use std::{collections::HashSet, fmt::Debug, future::Future};
 use tokio::runtime::Handle;
 
 pub trait RunContext: Debug + Send {
@@ -41,4 +41,11 @@ pub trait RunContext: Debug + Send {
     }
 
     fn progress(&self, #[allow(unused)] message: String) -> impl Progress + Send + 'static {}
+
+    /// The documents to retry, by the key they were reported with.
+    ///
+    /// If present, runners only process those documents, and no others.
+    fn retry(&self) -> Option<&HashSet<String>> {
+        None
+    }
 }
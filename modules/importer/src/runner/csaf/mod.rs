@@ -36,6 +36,10 @@ impl super::ImportRunner {
         importer: CsafImporter,
         last_success: Option<SystemTime>,
     ) -> Result<RunOutput, ScannerError> {
+        // documents to retry, if any
+
+        let retry = context.retry().cloned();
+
         // progress reporting
 
         let progress = context.progress(format!("Import CSAF: {}", importer.source));
@@ -97,7 +101,7 @@ impl super::ImportRunner {
 
         // filter
 
-        let filter = Filter::from_config(visitor, only_patterns)?;
+        let filter = Filter::from_config(visitor, only_patterns)?.only(retry);
 
         // walker
 
@@ -45,7 +45,7 @@ impl<C: RunContext> ValidatedVisitor<HttpSource> for CsafReportVisitor<C> {
                 StorageError::Validation(ValidationError::Retrieval(err)) => {
                     self.next.report.lock().add_error(
                         Phase::Retrieval,
-                        file,
+                        file.clone(),
                         format!("retrieval of document failed: {err}"),
                     );
 
@@ -58,6 +58,10 @@ impl<C: RunContext> ValidatedVisitor<HttpSource> for CsafReportVisitor<C> {
                     {
                         if let Some(status) = err.status() {
                             if self.ignore_errors.contains(&status) {
+                                self.next
+                                    .report
+                                    .lock()
+                                    .add_skipped(file, format!("ignoring status: {status}"));
                                 return Ok(());
                             }
                         }
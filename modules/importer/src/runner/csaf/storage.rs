@@ -34,7 +34,8 @@ impl<C: RunContext, S: Source> ValidatedVisitor<S> for StorageVisitor<C> {
         let location = doc.context.url().to_string();
         let file = doc.possibly_relative_url();
 
-        self.ingestor
+        let result = self
+            .ingestor
             .ingest(
                 &doc.data,
                 Format::CSAF,
@@ -48,6 +49,10 @@ impl<C: RunContext, S: Source> ValidatedVisitor<S> for StorageVisitor<C> {
             .await
             .map_err(StorageError::Storage)?;
 
+        self.report
+            .lock()
+            .add_ingested(doc.url().to_string(), &result);
+
         self.context.check_canceled(|| StorageError::Canceled).await
     }
 }
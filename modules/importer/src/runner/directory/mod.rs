@@ -0,0 +1,570 @@
+use crate::{
+    model::DirectoryImporter,
+    runner::{
+        RunOutput,
+        context::RunContext,
+        progress::{Progress, ProgressInstance},
+        report::{Phase, ReportBuilder, ScannerError},
+    },
+};
+use anyhow::anyhow;
+use bytes::Bytes;
+use hex::ToHex;
+use regex::Regex;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::{self, Cursor, Read},
+    path::{Component, Path, PathBuf},
+    str::FromStr,
+};
+use tracing::instrument;
+use trustify_common::{
+    decompress::{decompress, decompress_async},
+    hashing::Digests,
+};
+use trustify_entity::labels::Labels;
+use trustify_module_ingestor::{
+    graph::Graph,
+    service::{Format, IngestorService},
+};
+use walkdir::WalkDir;
+
+/// The state of the directory importer, carried from one run to the next
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Continuation {
+    /// The SHA-256 digests of the files processed so far, by their path relative to the source
+    #[serde(default)]
+    files: BTreeMap<String, String>,
+}
+
+impl super::ImportRunner {
+    #[instrument(skip(self, context), err)]
+    pub async fn run_once_directory(
+        &self,
+        context: impl RunContext + 'static,
+        directory: DirectoryImporter,
+        continuation: serde_json::Value,
+    ) -> Result<RunOutput, ScannerError> {
+        let ingestor = IngestorService::new(
+            Graph::new(self.db.clone()),
+            self.storage.clone(),
+            self.analysis.clone(),
+        );
+
+        let continuation: Continuation = serde_json::from_value(continuation).unwrap_or_default();
+
+        let source = PathBuf::from(&directory.source);
+        let metadata = tokio::fs::metadata(&source).await.map_err(|err| {
+            ScannerError::Critical(anyhow!(
+                "unable to access source '{}': {err}",
+                source.display()
+            ))
+        })?;
+
+        let labels = Labels::new()
+            .add("source", &directory.source)
+            .add("importer", context.name())
+            .extend(&directory.common.labels.0);
+        let limit = directory
+            .size_limit
+            .map(|limit| limit.as_u64() as usize)
+            .unwrap_or_default();
+
+        let mut run = Run {
+            ingestor,
+            labels,
+            limit,
+            report: ReportBuilder::new(),
+            files: continuation.files,
+        };
+
+        let only_patterns = directory
+            .only_patterns
+            .iter()
+            .map(|pattern| Regex::from_str(pattern))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| ScannerError::Critical(err.into()))?;
+
+        if metadata.is_dir() {
+            run.directory(&context, source, only_patterns).await?;
+        } else {
+            run.archive(&context, source, only_patterns).await?;
+        }
+
+        Ok(run.into_output())
+    }
+}
+
+struct Run {
+    ingestor: IngestorService,
+    labels: Labels,
+    limit: usize,
+    report: ReportBuilder,
+    files: BTreeMap<String, String>,
+}
+
+impl Run {
+    fn into_output(self) -> RunOutput {
+        RunOutput {
+            report: self.report.build(),
+            continuation: serde_json::to_value(Continuation { files: self.files }).ok(),
+        }
+    }
+
+    /// Capture the current state, in case the run was canceled
+    fn canceled(&self) -> ScannerError {
+        ScannerError::Normal {
+            err: anyhow!("canceled"),
+            output: RunOutput {
+                report: self.report.clone().build(),
+                continuation: serde_json::to_value(Continuation {
+                    files: self.files.clone(),
+                })
+                .ok(),
+            },
+        }
+    }
+
+    /// Check if the file is new or changed, returns the new digest in that case
+    fn changed(&self, name: &str, data: &[u8]) -> Option<String> {
+        let digest = Digests::digest(data).sha256.encode_hex::<String>();
+        match self.files.get(name) {
+            Some(previous) if *previous == digest => None,
+            _ => Some(digest),
+        }
+    }
+
+    /// Ingest a file, if it is new or changed, reporting any error for its name
+    async fn ingest(
+        &mut self,
+        name: String,
+        format: Format,
+        data: Vec<u8>,
+    ) -> Result<(), ScannerError> {
+        let Some(digest) = self.changed(&name, &data) else {
+            return Ok(());
+        };
+
+        self.report.tick();
+
+        let data = match decompress_async(Bytes::from(data), None, self.limit).await {
+            Ok(Ok(data)) => data,
+            Ok(Err(err)) => {
+                self.report
+                    .add_error(Phase::Validation, name, err.to_string());
+                return Ok(());
+            }
+            Err(err) => return Err(ScannerError::Critical(err.into())),
+        };
+
+        let labels = self.labels.clone().add("file", &name);
+        match self.ingestor.ingest(&data, format, labels, None).await {
+            Ok(result) => {
+                self.report.add_ingested(name.clone(), &result);
+                self.files.insert(name, digest);
+            }
+            Err(err) => {
+                self.report.add_error(Phase::Upload, name, err.to_string());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Forget about files which are gone, and return the names to process
+    fn select<T>(
+        &mut self,
+        context: &impl RunContext,
+        mut files: Vec<(String, T)>,
+    ) -> Vec<(String, T)> {
+        let names = files.iter().map(|(name, _)| name).collect::<BTreeSet<_>>();
+        self.files.retain(|name, _| names.contains(name));
+
+        // when retrying, only process the files which failed before
+
+        if let Some(retry) = context.retry() {
+            files.retain(|(name, _)| retry.contains(name));
+        }
+
+        files
+    }
+
+    /// Scan a directory, ingesting all new or changed files
+    async fn directory(
+        &mut self,
+        context: &impl RunContext,
+        source: PathBuf,
+        only_patterns: Vec<Regex>,
+    ) -> Result<(), ScannerError> {
+        let root = source.clone();
+        let paths = tokio::task::spawn_blocking(move || collect(&root, &only_patterns))
+            .await
+            .map_err(|err| ScannerError::Critical(err.into()))?
+            .map_err(|err| ScannerError::Critical(err.into()))?;
+        let paths = self.select(context, paths);
+
+        let progress = context.progress(format!("Import directory: {}", source.display()));
+        let mut instance = progress.start(paths.len());
+
+        for (name, path) in paths {
+            context.check_canceled(|| self.canceled()).await?;
+            instance.tick().await;
+
+            let data = match tokio::fs::read(&path).await {
+                Ok(data) => data,
+                Err(err) => {
+                    self.report
+                        .add_error(Phase::Retrieval, name, err.to_string());
+                    continue;
+                }
+            };
+
+            self.ingest(name, Format::Unknown, data).await?;
+        }
+
+        instance.finish().await;
+
+        Ok(())
+    }
+
+    /// Scan a dataset archive, ingesting all new or changed entries
+    ///
+    /// Like a dataset, the first directory of an entry may name the format of its documents.
+    /// Otherwise, the format gets detected.
+    async fn archive(
+        &mut self,
+        context: &impl RunContext,
+        source: PathBuf,
+        only_patterns: Vec<Regex>,
+    ) -> Result<(), ScannerError> {
+        let data = tokio::fs::read(&source)
+            .await
+            .map_err(|err| ScannerError::Critical(err.into()))?;
+
+        let entries = tokio::task::spawn_blocking(move || unpack(data, &only_patterns))
+            .await
+            .map_err(|err| ScannerError::Critical(err.into()))?
+            .map_err(ScannerError::Critical)?;
+        let entries = self.select(context, entries);
+
+        let progress = context.progress(format!("Import archive: {}", source.display()));
+        let mut instance = progress.start(entries.len());
+
+        for (name, entry) in entries {
+            context.check_canceled(|| self.canceled()).await?;
+            instance.tick().await;
+
+            let data = match entry {
+                Ok(data) => data,
+                Err(err) => {
+                    self.report
+                        .add_error(Phase::Retrieval, name, err.to_string());
+                    continue;
+                }
+            };
+
+            let format = name
+                .split_once('/')
+                .and_then(|(format, _)| Format::from_str(format).ok())
+                .unwrap_or(Format::Unknown);
+
+            self.ingest(name, format, data).await?;
+        }
+
+        instance.finish().await;
+
+        Ok(())
+    }
+}
+
+/// Read the regular, non-hidden entries of a zip or (possibly compressed) tar archive whose name
+/// matches one of the patterns.
+///
+/// Returns the name of each entry, and its content or the error reading it.
+fn unpack(
+    data: Vec<u8>,
+    only_patterns: &[Regex],
+) -> anyhow::Result<Vec<(String, io::Result<Vec<u8>>)>> {
+    let selected = |path: &Path| {
+        let name = path
+            .components()
+            .filter_map(|c| match c {
+                Component::Normal(c) => Some(c.to_string_lossy()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        if name.iter().any(|c| c.starts_with('.')) {
+            return None;
+        }
+        let file_name = name.last()?;
+        if !only_patterns.is_empty()
+            && !only_patterns
+                .iter()
+                .any(|pattern| pattern.is_match(file_name))
+        {
+            return None;
+        }
+        Some(name.join("/"))
+    };
+
+    let mut result = vec![];
+
+    if data.starts_with(b"PK\x03\x04") {
+        let mut zip = zip::ZipArchive::new(Cursor::new(data))?;
+        for i in 0..zip.len() {
+            let mut file = zip.by_index(i)?;
+            if !file.is_file() {
+                continue;
+            }
+            let Some(name) = file.enclosed_name().as_deref().and_then(&selected) else {
+                continue;
+            };
+            let mut content = Vec::with_capacity(file.size() as _);
+            let content = file.read_to_end(&mut content).map(|_| content);
+            result.push((name, content));
+        }
+    } else {
+        let data = decompress(Bytes::from(data), None, 0)?;
+        let mut tar = tar::Archive::new(&*data);
+        for entry in tar.entries()? {
+            let mut entry = entry?;
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+            let Some(name) = selected(entry.path()?.as_ref()) else {
+                continue;
+            };
+            let mut content = Vec::with_capacity(entry.size() as _);
+            let content = entry.read_to_end(&mut content).map(|_| content);
+            result.push((name, content));
+        }
+    }
+
+    result.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    Ok(result)
+}
+
+/// Collect all regular, non-hidden files below `root` whose name matches one of the patterns.
+///
+/// Returns the path relative to the root, and the full path.
+fn collect(root: &Path, only_patterns: &[Regex]) -> walkdir::Result<Vec<(String, PathBuf)>> {
+    let mut result = vec![];
+
+    let walker = WalkDir::new(root)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| entry.depth() == 0 || !is_hidden(entry.file_name()));
+
+    for entry in walker {
+        let entry = entry?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        let file_name = entry.file_name().to_string_lossy();
+        if !only_patterns.is_empty()
+            && !only_patterns
+                .iter()
+                .any(|pattern| pattern.is_match(&file_name))
+        {
+            continue;
+        }
+
+        let name = entry
+            .path()
+            .strip_prefix(root)
+            .unwrap_or(entry.path())
+            .to_string_lossy()
+            .to_string();
+        result.push((name, entry.into_path()));
+    }
+
+    Ok(result)
+}
+
+fn is_hidden(name: &std::ffi::OsStr) -> bool {
+    name.to_string_lossy().starts_with('.')
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        model::CommonImporter,
+        runner::test::{TestContext, runner},
+    };
+    use std::time::Duration;
+    use test_context::test_context;
+    use test_log::test;
+    use trustify_test_context::{TrustifyContext, document_bytes_raw};
+
+    fn importer(source: &Path) -> DirectoryImporter {
+        DirectoryImporter {
+            common: CommonImporter {
+                disabled: false,
+                period: Duration::from_secs(300),
+                description: None,
+                labels: Default::default(),
+            },
+            source: source.to_string_lossy().into(),
+            only_patterns: vec![],
+            size_limit: None,
+        }
+    }
+
+    #[test_context(TrustifyContext)]
+    #[test(tokio::test(flavor = "multi_thread"))]
+    async fn import_changed(ctx: &TrustifyContext) -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        for (target, file) in [
+            ("advisory.json", "osv/GHSA-qq9f-q439-2574.json"),
+            ("sboms/simple.json", "zookeeper-3.9.2-cyclonedx.json"),
+            (".hidden/ignored.json", "osv/GHSA-qq9f-q439-2574.json"),
+        ] {
+            let path = dir.path().join(target);
+            if let Some(parent) = path.parent() {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+            tokio::fs::write(&path, document_bytes_raw(file).await?).await?;
+        }
+
+        // first run, ingests everything
+
+        let output = runner(ctx)
+            .run_once_directory(TestContext, importer(dir.path()), serde_json::Value::Null)
+            .await?;
+
+        assert_eq!(output.report.number_of_items, 2);
+        assert!(output.report.messages.is_empty());
+
+        // second run, nothing changed
+
+        let output = runner(ctx)
+            .run_once_directory(
+                TestContext,
+                importer(dir.path()),
+                output.continuation.unwrap_or_default(),
+            )
+            .await?;
+
+        assert_eq!(output.report.number_of_items, 0);
+
+        // third run, one file changed, one file is broken
+
+        tokio::fs::write(
+            dir.path().join("advisory.json"),
+            document_bytes_raw("osv/RUSTSEC-2021-0079.json").await?,
+        )
+        .await?;
+        tokio::fs::write(dir.path().join("broken.json"), b"{}").await?;
+
+        let output = runner(ctx)
+            .run_once_directory(
+                TestContext,
+                importer(dir.path()),
+                output.continuation.unwrap_or_default(),
+            )
+            .await?;
+
+        assert_eq!(output.report.number_of_items, 2);
+        assert_eq!(
+            output
+                .report
+                .messages
+                .get(&Phase::Upload)
+                .map(|messages| messages.keys().cloned().collect::<Vec<_>>()),
+            Some(vec!["broken.json".to_string()])
+        );
+
+        Ok(())
+    }
+
+    fn tar(entries: &[(&str, Vec<u8>)]) -> anyhow::Result<Vec<u8>> {
+        let mut builder = tar::Builder::new(vec![]);
+        for (name, data) in entries {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(data.len() as _);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, name, data.as_slice())?;
+        }
+        Ok(builder.into_inner()?)
+    }
+
+    fn zip(entries: &[(&str, Vec<u8>)]) -> anyhow::Result<Vec<u8>> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(vec![]));
+        for (name, data) in entries {
+            writer.start_file(*name, zip::write::SimpleFileOptions::default())?;
+            std::io::Write::write_all(&mut writer, data)?;
+        }
+        Ok(writer.finish()?.into_inner())
+    }
+
+    #[test_context(TrustifyContext)]
+    #[test(tokio::test(flavor = "multi_thread"))]
+    async fn import_archive(ctx: &TrustifyContext) -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let source = dir.path().join("dataset");
+
+        let advisory = document_bytes_raw("osv/GHSA-qq9f-q439-2574.json").await?;
+        let sbom = document_bytes_raw("zookeeper-3.9.2-cyclonedx.json").await?;
+
+        // first run, a tar archive, reporting the broken entry
+
+        tokio::fs::write(
+            &source,
+            tar(&[
+                ("osv/advisory.json", advisory.to_vec()),
+                ("sboms/simple.json", sbom.to_vec()),
+                (".hidden/ignored.json", advisory.to_vec()),
+                ("broken.json", b"{}".to_vec()),
+            ])?,
+        )
+        .await?;
+
+        let output = runner(ctx)
+            .run_once_directory(TestContext, importer(&source), serde_json::Value::Null)
+            .await?;
+
+        assert_eq!(output.report.number_of_items, 3);
+        assert_eq!(
+            output
+                .report
+                .messages
+                .get(&Phase::Upload)
+                .map(|messages| messages.keys().cloned().collect::<Vec<_>>()),
+            Some(vec!["broken.json".to_string()])
+        );
+
+        // second run, a zip archive, only the changed entry gets ingested
+
+        tokio::fs::write(
+            &source,
+            zip(&[
+                (
+                    "osv/advisory.json",
+                    document_bytes_raw("osv/RUSTSEC-2021-0079.json")
+                        .await?
+                        .to_vec(),
+                ),
+                ("sboms/simple.json", sbom.to_vec()),
+            ])?,
+        )
+        .await?;
+
+        let output = runner(ctx)
+            .run_once_directory(
+                TestContext,
+                importer(&source),
+                output.continuation.unwrap_or_default(),
+            )
+            .await?;
+
+        assert_eq!(output.report.number_of_items, 1);
+        assert!(output.report.messages.is_empty());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,75 @@
+use crate::{
+    model::GhsaImporter,
+    runner::{RunOutput, context::RunContext, report::ScannerError},
+};
+use tracing::instrument;
+
+const ISSUER: &str = "GitHub Advisory Database";
+
+impl super::ImportRunner {
+    /// The GitHub Advisory Database is stored as OSV, so we re-use the OSV importer.
+    #[instrument(skip(self, context), err)]
+    pub async fn run_once_ghsa(
+        &self,
+        context: impl RunContext + 'static,
+        ghsa: GhsaImporter,
+        continuation: serde_json::Value,
+    ) -> Result<RunOutput, ScannerError> {
+        self.run_once_osv_with_issuer(context, ghsa.into(), Some(ISSUER.into()), continuation)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        model::CommonImporter,
+        runner::test::{GitRepository, TestContext, runner},
+    };
+    use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+    use std::time::Duration;
+    use test_context::test_context;
+    use test_log::test;
+    use trustify_entity::{advisory, organization};
+    use trustify_test_context::TrustifyContext;
+
+    #[test_context(TrustifyContext)]
+    #[test(tokio::test(flavor = "multi_thread"))]
+    async fn import(ctx: &TrustifyContext) -> anyhow::Result<()> {
+        let repo = GitRepository::new()?;
+        repo.commit(&["osv/GHSA-qq9f-q439-2574.json"]).await?;
+
+        let output = runner(ctx)
+            .run_once_ghsa(
+                TestContext,
+                GhsaImporter {
+                    common: CommonImporter {
+                        disabled: false,
+                        period: Duration::from_secs(300),
+                        description: None,
+                        labels: Default::default(),
+                    },
+                    source: repo.source(),
+                    branch: None,
+                    path: "osv".into(),
+                    years: Default::default(),
+                    start_year: None,
+                },
+                serde_json::Value::Null,
+            )
+            .await?;
+
+        assert_eq!(output.report.number_of_items, 1);
+
+        let (_, issuer) = advisory::Entity::find()
+            .filter(advisory::Column::Identifier.eq("GHSA-qq9f-q439-2574"))
+            .find_also_related(organization::Entity)
+            .one(&ctx.db)
+            .await?
+            .expect("advisory must be ingested");
+        assert_eq!(issuer.map(|issuer| issuer.name).as_deref(), Some(ISSUER));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,96 @@
+mod translate;
+
+use crate::{
+    model::GitlabImporter,
+    runner::{
+        RunOutput, context::RunContext, report::ScannerError, translated::TranslatedImporter,
+    },
+};
+use tracing::instrument;
+
+const ISSUER: &str = "GitLab Advisory Database";
+
+impl super::ImportRunner {
+    /// The GitLab advisory database is stored in the gemnasium format, which we translate into OSV.
+    #[instrument(skip(self, context), err)]
+    pub async fn run_once_gitlab(
+        &self,
+        context: impl RunContext + 'static,
+        gitlab: GitlabImporter,
+        continuation: serde_json::Value,
+    ) -> Result<RunOutput, ScannerError> {
+        self.run_once_translated(
+            context,
+            TranslatedImporter {
+                name: "gitlab",
+                issuer: ISSUER,
+                extensions: &["yml", "yaml"],
+                translate: translate::translate,
+                source: gitlab.source,
+                branch: gitlab.branch,
+                path: gitlab.path,
+                labels: gitlab.common.labels,
+            },
+            continuation,
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        model::CommonImporter,
+        runner::test::{GitRepository, TestContext, runner},
+    };
+    use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter};
+    use std::time::Duration;
+    use test_context::test_context;
+    use test_log::test;
+    use trustify_entity::advisory;
+    use trustify_test_context::TrustifyContext;
+
+    #[test_context(TrustifyContext)]
+    #[test(tokio::test(flavor = "multi_thread"))]
+    async fn import(ctx: &TrustifyContext) -> anyhow::Result<()> {
+        let repo = GitRepository::new()?;
+        repo.commit(&[
+            "gitlab/maven/org.apache.logging.log4j/log4j-core/CVE-2021-44228.yml",
+            "gitlab/npm/lodash/CVE-2021-23337.yml",
+        ])
+        .await?;
+
+        let output = runner(ctx)
+            .run_once_gitlab(
+                TestContext,
+                GitlabImporter {
+                    common: CommonImporter {
+                        disabled: false,
+                        period: Duration::from_secs(300),
+                        description: None,
+                        labels: Default::default(),
+                    },
+                    source: repo.source(),
+                    branch: None,
+                    path: Some("gitlab".into()),
+                },
+                serde_json::Value::Null,
+            )
+            .await?;
+
+        assert_eq!(output.report.number_of_items, 2);
+        assert!(output.report.messages.is_empty());
+
+        let advisories = advisory::Entity::find()
+            .filter(advisory::Column::Identifier.is_in([
+                "GMS-maven/org.apache.logging.log4j/log4j-core-CVE-2021-44228",
+                "GMS-npm/lodash-CVE-2021-23337",
+            ]))
+            .count(&ctx.db)
+            .await?;
+        assert_eq!(advisories, 2);
+
+        Ok(())
+    }
+}
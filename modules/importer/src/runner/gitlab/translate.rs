@@ -0,0 +1,412 @@
+//! Translate advisories of the GitLab advisory database (gemnasium format) into OSV.
+//!
+//! See: <https://gitlab.com/gitlab-org/advisories-community>
+
+use crate::runner::translated::timestamp;
+use anyhow::{anyhow, bail};
+use serde_json::{Value, json};
+
+/// A gemnasium advisory, one per package and vulnerability
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct GitlabAdvisory {
+    pub identifier: String,
+    #[serde(default)]
+    pub identifiers: Vec<String>,
+    pub package_slug: String,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// The date of the last modification
+    #[serde(default)]
+    pub date: Option<String>,
+    /// The date of publication
+    #[serde(default)]
+    pub pubdate: Option<String>,
+    #[serde(default)]
+    pub affected_range: Option<String>,
+    #[serde(default)]
+    pub urls: Vec<String>,
+    #[serde(default)]
+    pub cvss_v3: Option<String>,
+    #[serde(default)]
+    pub cwe_ids: Vec<String>,
+}
+
+/// Parse a gemnasium YAML document and translate it into an OSV JSON document.
+pub fn translate(data: &[u8], warnings: &mut Vec<String>) -> anyhow::Result<Value> {
+    let advisory: GitlabAdvisory = serde_yml::from_slice(data)?;
+    to_osv(advisory, warnings)
+}
+
+/// Translate a gemnasium advisory into an OSV JSON document.
+///
+/// Gemnasium advisories are identified by the vulnerability they describe, but there is one
+/// for each affected package. So the OSV ID is `GMS-<package slug>-<identifier>`, to prevent
+/// advisories of different packages from deprecating each other, or those of other sources.
+///
+/// All identifiers (including the primary one) are used as aliases, as the OSV loader links
+/// vulnerabilities using the aliases.
+///
+/// Ranges which can't be expressed in OSV are skipped, adding a warning.
+pub fn to_osv(advisory: GitlabAdvisory, warnings: &mut Vec<String>) -> anyhow::Result<Value> {
+    let (ecosystem, name) = package(&advisory.package_slug)
+        .ok_or_else(|| anyhow!("unsupported package: {}", advisory.package_slug))?;
+
+    let mut aliases = advisory.identifiers.clone();
+    if !aliases.contains(&advisory.identifier) {
+        aliases.insert(0, advisory.identifier.clone());
+    }
+
+    let modified = advisory
+        .date
+        .as_deref()
+        .or(advisory.pubdate.as_deref())
+        .ok_or_else(|| anyhow!("missing date"))?;
+
+    let mut ranges = vec![];
+    let mut versions = vec![];
+    if let Some(affected_range) = &advisory.affected_range {
+        for interval in parse_range(ecosystem, affected_range)? {
+            match interval {
+                Interval::Exact(version) => versions.push(version),
+                Interval::Range { lower, upper } => match interval_to_range(lower, upper) {
+                    Ok(range) => ranges.push(range),
+                    Err(err) => warnings.push(format!(
+                        "Skipping unsupported range of {}: {err}",
+                        advisory.package_slug
+                    )),
+                },
+            }
+        }
+    }
+
+    let mut osv = json!({
+        "id": format!("GMS-{}-{}", advisory.package_slug, advisory.identifier),
+        "modified": timestamp(modified),
+        "aliases": aliases,
+        "affected": [{
+            "package": {
+                "ecosystem": ecosystem,
+                "name": name,
+            },
+            "ranges": ranges,
+            "versions": versions,
+        }],
+        "references": advisory.urls.iter().map(|url| json!({
+            "type": "WEB",
+            "url": url,
+        })).collect::<Vec<_>>(),
+        "database_specific": {
+            "cwe_ids": advisory.cwe_ids,
+        },
+    });
+
+    if let Some(pubdate) = &advisory.pubdate {
+        osv["published"] = timestamp(pubdate).into();
+    }
+    if let Some(title) = advisory.title {
+        osv["summary"] = title.into();
+    }
+    if let Some(description) = advisory.description {
+        osv["details"] = description.into();
+    }
+    if let Some(cvss_v3) = advisory.cvss_v3 {
+        osv["severity"] = json!([{ "type": "CVSS_V3", "score": cvss_v3 }]);
+    }
+
+    Ok(osv)
+}
+
+/// Split a package slug (`type/name`) into an OSV ecosystem and package name
+fn package(slug: &str) -> Option<(&'static str, String)> {
+    let (ty, name) = slug.split_once('/')?;
+    Some(match ty {
+        // maven uses `group/artifact`, OSV uses `group:artifact`
+        "maven" => ("Maven", name.replacen('/', ":", 1)),
+        "npm" => ("npm", name.to_string()),
+        "pypi" => ("PyPI", name.to_string()),
+        "gem" => ("RubyGems", name.to_string()),
+        "go" => ("Go", name.to_string()),
+        "nuget" => ("NuGet", name.to_string()),
+        "packagist" => ("Packagist", name.to_string()),
+        _ => return None,
+    })
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Bound {
+    Inclusive(String),
+    Exclusive(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Interval {
+    Exact(String),
+    Range {
+        lower: Option<Bound>,
+        upper: Option<Bound>,
+    },
+}
+
+/// Convert an interval into an OSV range.
+///
+/// OSV has no exclusive lower bound. Considering it inclusive would mark the bound itself as
+/// affected, so such ranges are rejected, and must be skipped by the caller.
+fn interval_to_range(lower: Option<Bound>, upper: Option<Bound>) -> anyhow::Result<Value> {
+    let mut events = vec![match lower {
+        Some(Bound::Inclusive(version)) => json!({ "introduced": version }),
+        Some(Bound::Exclusive(version)) => {
+            bail!("exclusive lower bound is not supported: >{version}")
+        }
+        None => json!({ "introduced": "0" }),
+    }];
+
+    match upper {
+        Some(Bound::Exclusive(version)) => events.push(json!({ "fixed": version })),
+        Some(Bound::Inclusive(version)) => events.push(json!({ "last_affected": version })),
+        None => {}
+    }
+
+    Ok(json!({
+        "type": "ECOSYSTEM",
+        "events": events,
+    }))
+}
+
+/// Parse the `affected_range` of an advisory.
+///
+/// Maven and NuGet use the Maven version range syntax (`[1.0,2.0),[3.0]`), all others use
+/// a list of constraints, separated by `||` (`>=1.0 <2.0||=3.0`).
+fn parse_range(ecosystem: &str, range: &str) -> anyhow::Result<Vec<Interval>> {
+    match ecosystem {
+        "Maven" | "NuGet" => parse_maven_range(range),
+        _ => {
+            let mut result = vec![];
+            for constraints in range.split("||") {
+                result.extend(parse_constraints(constraints)?);
+            }
+            Ok(result)
+        }
+    }
+}
+
+fn parse_maven_range(range: &str) -> anyhow::Result<Vec<Interval>> {
+    let mut result = vec![];
+    let mut rest = range.trim();
+
+    while !rest.is_empty() {
+        let lower_inclusive = match rest.chars().next() {
+            Some('[') => true,
+            Some('(') => false,
+            _ => bail!("invalid range: {range}"),
+        };
+        let Some(end) = rest.find([']', ')']) else {
+            bail!("unterminated range: {range}");
+        };
+        let upper_inclusive = &rest[end..=end] == "]";
+        let content = &rest[1..end];
+
+        result.push(match content.split_once(',') {
+            None => Interval::Exact(content.trim().to_string()),
+            Some((lower, upper)) => {
+                let (lower, upper) = (lower.trim(), upper.trim());
+                Interval::Range {
+                    lower: (!lower.is_empty()).then(|| match lower_inclusive {
+                        true => Bound::Inclusive(lower.to_string()),
+                        false => Bound::Exclusive(lower.to_string()),
+                    }),
+                    upper: (!upper.is_empty()).then(|| match upper_inclusive {
+                        true => Bound::Inclusive(upper.to_string()),
+                        false => Bound::Exclusive(upper.to_string()),
+                    }),
+                }
+            }
+        });
+
+        rest = rest[end + 1..].trim_start_matches([',', ' ']);
+    }
+
+    Ok(result)
+}
+
+/// Parse a list of constraints (like `>=1.0 <2.0` or `>=1.0,<2.0`) into an interval.
+///
+/// An exact version (like `=1.0`) must be the only constraint of the list.
+fn parse_constraints(constraints: &str) -> anyhow::Result<Option<Interval>> {
+    let mut exact = None;
+    let mut lower = None;
+    let mut upper = None;
+    let mut rest = constraints.trim();
+
+    if rest.is_empty() {
+        return Ok(None);
+    }
+
+    while !rest.is_empty() {
+        let op_len = rest
+            .find(|c: char| !matches!(c, '<' | '>' | '=' | '!' | '~' | '^'))
+            .unwrap_or(rest.len());
+        let op = &rest[..op_len];
+        rest = rest[op_len..].trim_start();
+
+        let version_len = rest
+            .find(|c: char| c.is_whitespace() || c == ',')
+            .unwrap_or(rest.len());
+        let version = rest[..version_len].to_string();
+        rest = rest[version_len..].trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+
+        if version.is_empty() {
+            bail!("missing version: {constraints}");
+        }
+
+        match op {
+            "" | "=" | "==" if exact.is_none() => exact = Some(version),
+            "" | "=" | "==" => bail!("multiple exact versions: {constraints}"),
+            ">=" => lower = Some(Bound::Inclusive(version)),
+            ">" => lower = Some(Bound::Exclusive(version)),
+            "<=" => upper = Some(Bound::Inclusive(version)),
+            "<" => upper = Some(Bound::Exclusive(version)),
+            op => bail!("unsupported operator '{op}': {constraints}"),
+        }
+    }
+
+    Ok(Some(match (exact, lower, upper) {
+        (Some(version), None, None) => Interval::Exact(version),
+        (Some(_), _, _) => bail!("exact version combined with other constraints: {constraints}"),
+        (None, lower, upper) => Interval::Range { lower, upper },
+    }))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use trustify_test_context::document_bytes;
+
+    #[test]
+    fn maven_range() {
+        assert_eq!(
+            parse_range("Maven", "[2.0-beta9,2.3.1),(,1.0],[3.0]").unwrap(),
+            vec![
+                Interval::Range {
+                    lower: Some(Bound::Inclusive("2.0-beta9".into())),
+                    upper: Some(Bound::Exclusive("2.3.1".into())),
+                },
+                Interval::Range {
+                    lower: None,
+                    upper: Some(Bound::Inclusive("1.0".into())),
+                },
+                Interval::Exact("3.0".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn constraint_range() {
+        assert_eq!(
+            parse_range("npm", ">=1.0.0 <1.2.3||=2.0.0||>= 3.0.0, < 3.1.0").unwrap(),
+            vec![
+                Interval::Range {
+                    lower: Some(Bound::Inclusive("1.0.0".into())),
+                    upper: Some(Bound::Exclusive("1.2.3".into())),
+                },
+                Interval::Exact("2.0.0".into()),
+                Interval::Range {
+                    lower: Some(Bound::Inclusive("3.0.0".into())),
+                    upper: Some(Bound::Exclusive("3.1.0".into())),
+                },
+            ]
+        );
+
+        assert!(parse_range("npm", "^1.0.0").is_err());
+        assert!(parse_range("npm", "=1.0.0 <2.0.0").is_err());
+        assert!(parse_range("npm", "=1.0.0 =2.0.0").is_err());
+    }
+
+    #[test]
+    fn exclusive_lower_bound() {
+        let intervals = parse_range("npm", ">1.0 <2.0").unwrap();
+        assert_eq!(
+            intervals,
+            vec![Interval::Range {
+                lower: Some(Bound::Exclusive("1.0".into())),
+                upper: Some(Bound::Exclusive("2.0".into())),
+            }]
+        );
+
+        // OSV can't express an exclusive lower bound
+        let Interval::Range { lower, upper } = intervals[0].clone() else {
+            unreachable!()
+        };
+        assert!(interval_to_range(lower, upper).is_err());
+
+        assert_eq!(
+            interval_to_range(Some(Bound::Inclusive("1.0".into())), None).unwrap(),
+            json!({ "type": "ECOSYSTEM", "events": [{ "introduced": "1.0" }] })
+        );
+    }
+
+    #[test]
+    fn skip_unsupported_range() -> anyhow::Result<()> {
+        let mut warnings = vec![];
+        let osv = to_osv(
+            GitlabAdvisory {
+                identifier: "CVE-2024-0001".into(),
+                identifiers: vec![],
+                package_slug: "npm/example".into(),
+                title: None,
+                description: None,
+                date: Some("2024-01-01".into()),
+                pubdate: None,
+                affected_range: Some(">1.0 <2.0||>=3.0 <4.0".into()),
+                urls: vec![],
+                cvss_v3: None,
+                cwe_ids: vec![],
+            },
+            &mut warnings,
+        )?;
+
+        assert_eq!(
+            osv["affected"][0]["ranges"],
+            json!([{ "type": "ECOSYSTEM", "events": [{ "introduced": "3.0" }, { "fixed": "4.0" }] }])
+        );
+        assert_eq!(warnings.len(), 1);
+
+        Ok(())
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn translate_advisory() -> anyhow::Result<()> {
+        let data =
+            document_bytes("gitlab/maven/org.apache.logging.log4j/log4j-core/CVE-2021-44228.yml")
+                .await?;
+        let mut warnings = vec![];
+        let osv = translate(&data, &mut warnings)?;
+
+        assert_eq!(
+            osv["id"],
+            "GMS-maven/org.apache.logging.log4j/log4j-core-CVE-2021-44228"
+        );
+        assert!(
+            osv["aliases"]
+                .as_array()
+                .is_some_and(|aliases| aliases.contains(&json!("CVE-2021-44228")))
+        );
+        assert_eq!(osv["modified"], "2021-12-20T00:00:00Z");
+        assert_eq!(
+            osv["affected"][0]["package"],
+            json!({ "ecosystem": "Maven", "name": "org.apache.logging.log4j:log4j-core" })
+        );
+        assert_eq!(
+            osv["affected"][0]["ranges"].as_array().map(Vec::len),
+            Some(3)
+        );
+        assert_eq!(osv["severity"][0]["type"], "CVSS_V3");
+        assert!(warnings.is_empty());
+
+        // must be a valid OSV document
+        trustify_module_ingestor::service::advisory::osv::parse(&serde_json::to_vec(&osv)?)?;
+
+        Ok(())
+    }
+}
@@ -6,10 +6,17 @@ pub mod context;
 pub mod csaf;
 pub mod cve;
 pub mod cwe;
+pub mod directory;
+pub mod ghsa;
+pub mod gitlab;
 pub mod osv;
 pub mod progress;
 pub mod report;
+pub mod rustsec;
 pub mod sbom;
+pub mod translated;
+
+mod test;
 
 use crate::{
     model::ImporterConfiguration,
@@ -62,6 +69,19 @@ impl ImportRunner {
             ImporterConfiguration::Cwe(cwe) => {
                 self.run_once_cwe_catalog(context, cwe, continuation).await
             }
+            ImporterConfiguration::Ghsa(ghsa) => {
+                self.run_once_ghsa(context, ghsa, continuation).await
+            }
+            ImporterConfiguration::Gitlab(gitlab) => {
+                self.run_once_gitlab(context, gitlab, continuation).await
+            }
+            ImporterConfiguration::Rustsec(rustsec) => {
+                self.run_once_rustsec(context, rustsec, continuation).await
+            }
+            ImporterConfiguration::Directory(directory) => {
+                self.run_once_directory(context, directory, continuation)
+                    .await
+            }
         }
     }
 
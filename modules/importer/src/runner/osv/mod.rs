@@ -28,6 +28,7 @@ struct Context<C: RunContext + 'static> {
     labels: Labels,
     years: HashSet<u16>,
     start_year: Option<u16>,
+    issuer: Option<String>,
     report: Arc<Mutex<ReportBuilder>>,
     ingestor: IngestorService,
 }
@@ -59,7 +60,7 @@ impl<C: RunContext> Context<C> {
             }
         }
 
-        Handle::current().block_on(async {
+        let result = Handle::current().block_on(async {
             self.ingestor
                 .ingest(
                     &data,
@@ -69,11 +70,15 @@ impl<C: RunContext> Context<C> {
                         .add("importer", self.context.name())
                         .add("file", path.to_string_lossy())
                         .extend(&self.labels.0),
-                    None,
+                    self.issuer.clone(),
                 )
                 .await
         })?;
 
+        self.report
+            .lock()
+            .add_ingested(path.to_string_lossy(), &result);
+
         Ok(())
     }
 }
@@ -107,6 +112,18 @@ impl super::ImportRunner {
         context: impl RunContext + 'static,
         osv: OsvImporter,
         continuation: serde_json::Value,
+    ) -> Result<RunOutput, ScannerError> {
+        self.run_once_osv_with_issuer(context, osv, None, continuation)
+            .await
+    }
+
+    /// Run an OSV import, using a fixed issuer for all documents
+    pub(crate) async fn run_once_osv_with_issuer(
+        &self,
+        context: impl RunContext + 'static,
+        osv: OsvImporter,
+        issuer: Option<String>,
+        continuation: serde_json::Value,
     ) -> Result<RunOutput, ScannerError> {
         let ingestor = IngestorService::new(
             Graph::new(self.db.clone()),
@@ -125,6 +142,12 @@ impl super::ImportRunner {
 
         let progress = context.progress(format!("Import OSV: {}", osv.source));
 
+        // files to retry, if any
+
+        let only = context
+            .retry()
+            .map(|retry| retry.iter().map(PathBuf::from).collect());
+
         // run the walker
 
         let walker = GitWalker::new(
@@ -135,11 +158,13 @@ impl super::ImportRunner {
                 labels: osv.common.labels,
                 years: osv.years,
                 start_year: osv.start_year,
+                issuer,
                 report: report.clone(),
                 ingestor,
             }),
         )
         .continuation(continuation)
+        .only(only)
         .branch(osv.branch)
         .path(osv.path)
         .progress(progress);
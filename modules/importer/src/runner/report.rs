@@ -1,8 +1,9 @@
 use crate::server::RunOutput;
 use parking_lot::Mutex;
 use schemars::JsonSchema;
-use std::{collections::BTreeMap, iter, sync::Arc};
+use std::{collections::BTreeMap, sync::Arc};
 use time::OffsetDateTime;
+use trustify_module_ingestor::model::IngestResult;
 
 /// The phase of processing
 #[derive(
@@ -62,6 +63,60 @@ pub struct Report {
     /// Messages emitted during processing
     #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     pub messages: BTreeMap<Phase, BTreeMap<String, Vec<Message>>>,
+
+    /// The outcome of the individual documents, stored separately from the report
+    #[serde(skip)]
+    pub items: Vec<ReportItem>,
+}
+
+/// The outcome of processing a single document
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize, utoipa::ToSchema,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum Outcome {
+    /// The document was ingested
+    Ingested,
+    /// The document was deliberately not ingested
+    Skipped,
+    /// Processing the document failed
+    Failed,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportItem {
+    /// The document, as used as a key in the messages of the report
+    pub file: String,
+    /// The outcome of processing the document
+    pub outcome: Outcome,
+
+    /// The phase in which processing failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub phase: Option<Phase>,
+    /// The reason for failing or skipping the document
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+
+    /// The ID of the ingested document
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub document: Option<String>,
+    /// The ID declared by the ingested document
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub document_id: Option<String>,
+}
+
+impl ReportItem {
+    fn new(file: String, outcome: Outcome) -> Self {
+        Self {
+            file,
+            outcome,
+            phase: None,
+            message: None,
+            document: None,
+            document_id: None,
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize, utoipa::ToSchema)]
@@ -91,6 +146,7 @@ impl Message {
 #[derive(Clone, Debug)]
 pub struct ReportBuilder {
     report: Report,
+    items: BTreeMap<String, ReportItem>,
 }
 
 impl ReportBuilder {
@@ -101,7 +157,9 @@ impl ReportBuilder {
                 end_date: OffsetDateTime::now_utc(),
                 number_of_items: 0,
                 messages: Default::default(),
+                items: Default::default(),
             },
+            items: Default::default(),
         }
     }
 
@@ -139,17 +197,30 @@ impl ReportBuilder {
         messages: impl IntoIterator<Item = Message>,
     ) {
         let file = file.into();
-        let mut messages = messages.into_iter();
+        let messages = messages.into_iter().collect::<Vec<_>>();
 
-        // check if we have at least one item
+        // check if we have at least one item, if not, return without creating any phase or file
 
-        let first = messages.next();
-        let Some(first) = first else {
-            // if not, return without creating any phase or file
+        if messages.is_empty() {
             return;
-        };
+        }
+
+        // the first error fails the document
+
+        if let Some(error) = messages.iter().find(|m| m.severity == Severity::Error) {
+            let failed = self
+                .items
+                .get(&file)
+                .is_some_and(|item| item.outcome == Outcome::Failed);
+            if !failed {
+                let mut item = ReportItem::new(file.clone(), Outcome::Failed);
+                item.phase = Some(phase);
+                item.message = Some(error.message.clone());
+                self.items.insert(file.clone(), item);
+            }
+        }
 
-        // now add the first, and all remaining messages
+        // now add all messages
 
         self.report
             .messages
@@ -157,11 +228,29 @@ impl ReportBuilder {
             .or_default()
             .entry(file)
             .or_default()
-            .extend(iter::once(first).chain(messages));
+            .extend(messages);
+    }
+
+    /// Record a document as ingested
+    pub fn add_ingested(&mut self, file: impl Into<String>, result: &IngestResult) {
+        let file = file.into();
+        let mut item = ReportItem::new(file.clone(), Outcome::Ingested);
+        item.document = Some(result.id.to_string());
+        item.document_id = result.document_id.clone();
+        self.items.insert(file, item);
+    }
+
+    /// Record a document as skipped, even if processing it failed before
+    pub fn add_skipped(&mut self, file: impl Into<String>, message: impl Into<String>) {
+        let file = file.into();
+        let mut item = ReportItem::new(file.clone(), Outcome::Skipped);
+        item.message = Some(message.into());
+        self.items.insert(file, item);
     }
 
     pub fn build(mut self) -> Report {
         self.report.end_date = OffsetDateTime::now_utc();
+        self.report.items = self.items.into_values().collect();
         self.report
     }
 }
@@ -0,0 +1,162 @@
+mod translate;
+
+use crate::{
+    model::RustsecImporter,
+    runner::{
+        RunOutput, context::RunContext, report::ScannerError, translated::TranslatedImporter,
+    },
+};
+use tracing::instrument;
+
+const ISSUER: &str = "Rust Security Advisory Database";
+
+impl super::ImportRunner {
+    /// The RustSec advisory database is stored as Markdown, which we translate into OSV.
+    #[instrument(skip(self, context), err)]
+    pub async fn run_once_rustsec(
+        &self,
+        context: impl RunContext + 'static,
+        rustsec: RustsecImporter,
+        continuation: serde_json::Value,
+    ) -> Result<RunOutput, ScannerError> {
+        self.run_once_translated(
+            context,
+            TranslatedImporter {
+                name: "rustsec",
+                issuer: ISSUER,
+                extensions: &["md"],
+                translate: translate::translate,
+                source: rustsec.source,
+                branch: rustsec.branch,
+                path: Some(rustsec.path),
+                labels: rustsec.common.labels,
+            },
+            continuation,
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        model::CommonImporter,
+        runner::{
+            report::Outcome,
+            test::{GitRepository, RetryContext, TestContext, runner},
+        },
+    };
+    use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+    use std::{collections::HashSet, time::Duration};
+    use test_context::test_context;
+    use test_log::test;
+    use trustify_entity::advisory;
+    use trustify_test_context::TrustifyContext;
+
+    fn importer(source: String) -> RustsecImporter {
+        RustsecImporter {
+            common: CommonImporter {
+                disabled: false,
+                period: Duration::from_secs(300),
+                description: None,
+                labels: Default::default(),
+            },
+            source,
+            branch: None,
+            path: "rustsec/crates".into(),
+        }
+    }
+
+    /// ensure that a second run only processes the files changed since the first one
+    #[test_context(TrustifyContext)]
+    #[test(tokio::test(flavor = "multi_thread"))]
+    async fn import_continuation(ctx: &TrustifyContext) -> anyhow::Result<()> {
+        let repo = GitRepository::new()?;
+        repo.commit(&["rustsec/crates/hyper/RUSTSEC-2021-0078.md"])
+            .await?;
+
+        let output = runner(ctx)
+            .run_once_rustsec(
+                TestContext,
+                importer(repo.source()),
+                serde_json::Value::Null,
+            )
+            .await?;
+
+        assert_eq!(output.report.number_of_items, 1);
+        assert!(output.report.messages.is_empty());
+
+        repo.commit(&["rustsec/crates/hyper/RUSTSEC-2021-0079.md"])
+            .await?;
+
+        let output = runner(ctx)
+            .run_once_rustsec(
+                TestContext,
+                importer(repo.source()),
+                output.continuation.unwrap_or_default(),
+            )
+            .await?;
+
+        assert_eq!(output.report.number_of_items, 1);
+        assert!(output.report.messages.is_empty());
+
+        let advisory = advisory::Entity::find()
+            .filter(advisory::Column::Identifier.eq("RUSTSEC-2021-0079"))
+            .one(&ctx.db)
+            .await?
+            .expect("advisory must be ingested");
+        assert!(advisory.issuer_id.is_some());
+
+        Ok(())
+    }
+
+    /// ensure that a retry only processes the requested files, ignoring the continuation
+    #[test_context(TrustifyContext)]
+    #[test(tokio::test(flavor = "multi_thread"))]
+    async fn import_retry(ctx: &TrustifyContext) -> anyhow::Result<()> {
+        let repo = GitRepository::new()?;
+        repo.commit(&[
+            "rustsec/crates/hyper/RUSTSEC-2021-0078.md",
+            "rustsec/crates/hyper/RUSTSEC-2021-0079.md",
+        ])
+        .await?;
+
+        let output = runner(ctx)
+            .run_once_rustsec(
+                TestContext,
+                importer(repo.source()),
+                serde_json::Value::Null,
+            )
+            .await?;
+
+        assert_eq!(output.report.number_of_items, 2);
+        assert_eq!(output.report.items.len(), 2);
+        assert!(
+            output
+                .report
+                .items
+                .iter()
+                .all(|item| item.outcome == Outcome::Ingested && item.document.is_some())
+        );
+
+        let file = "hyper/RUSTSEC-2021-0079.md".to_string();
+        assert!(output.report.items.iter().any(|item| item.file == file));
+
+        // the continuation is at the most recent commit, but the file must be processed anyway
+
+        let output = runner(ctx)
+            .run_once_rustsec(
+                RetryContext(HashSet::from([file.clone()])),
+                importer(repo.source()),
+                output.continuation.unwrap_or_default(),
+            )
+            .await?;
+
+        assert_eq!(output.report.number_of_items, 1);
+        assert_eq!(output.report.items.len(), 1);
+        assert_eq!(output.report.items[0].file, file);
+
+        Ok(())
+    }
+}
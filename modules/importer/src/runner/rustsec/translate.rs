@@ -0,0 +1,306 @@
+//! Translate advisories of the RustSec advisory database into OSV.
+//!
+//! Advisories are Markdown files, starting with a TOML front-matter (in a code block), followed
+//! by the title (as first heading) and the description.
+//!
+//! See: <https://github.com/rustsec/advisory-db>
+
+use crate::runner::translated::timestamp;
+use anyhow::{anyhow, bail};
+use semver::{Comparator, Op, Version, VersionReq};
+use serde_json::{Value, json};
+
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct RustsecAdvisory {
+    pub advisory: Metadata,
+    #[serde(default)]
+    pub versions: Versions,
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct Metadata {
+    pub id: String,
+    pub package: String,
+    pub date: String,
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    #[serde(default)]
+    pub related: Vec<String>,
+    #[serde(default)]
+    pub cvss: Option<String>,
+    #[serde(default)]
+    pub withdrawn: Option<String>,
+    #[serde(default)]
+    pub references: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, serde::Deserialize)]
+pub struct Versions {
+    #[serde(default)]
+    pub patched: Vec<String>,
+    #[serde(default)]
+    pub unaffected: Vec<String>,
+}
+
+/// Parse a RustSec Markdown document and translate it into an OSV JSON document.
+pub fn translate(data: &[u8], _warnings: &mut Vec<String>) -> anyhow::Result<Value> {
+    let data = std::str::from_utf8(data)?;
+
+    let Some(rest) = data.trim_start().strip_prefix("```toml") else {
+        bail!("missing TOML front-matter");
+    };
+    let Some((front_matter, markdown)) = rest.split_once("\n```") else {
+        bail!("unterminated TOML front-matter");
+    };
+
+    let advisory: RustsecAdvisory = toml::from_str(front_matter)?;
+
+    let markdown = markdown.trim();
+    let (title, details) = match markdown.strip_prefix("# ") {
+        Some(markdown) => match markdown.split_once('\n') {
+            Some((title, details)) => (Some(title.trim()), details.trim()),
+            None => (Some(markdown.trim()), ""),
+        },
+        None => (None, markdown),
+    };
+
+    to_osv(advisory, title, details)
+}
+
+/// Translate a RustSec advisory into an OSV JSON document.
+pub fn to_osv(
+    RustsecAdvisory { advisory, versions }: RustsecAdvisory,
+    title: Option<&str>,
+    details: &str,
+) -> anyhow::Result<Value> {
+    let ranges = affected(&versions)?
+        .into_iter()
+        .map(|(introduced, fixed)| {
+            let mut events = vec![json!({ "introduced": introduced })];
+            events.extend(fixed.map(|fixed| json!({ "fixed": fixed })));
+            json!({
+                "type": "SEMVER",
+                "events": events,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let mut references = vec![
+        json!({
+            "type": "PACKAGE",
+            "url": format!("https://crates.io/crates/{}", advisory.package),
+        }),
+        // the OSV loader detects the issuer from this reference
+        json!({
+            "type": "ADVISORY",
+            "url": format!("https://rustsec.org/advisories/{}.html", advisory.id),
+        }),
+    ];
+    references.extend(
+        advisory
+            .url
+            .iter()
+            .chain(&advisory.references)
+            .map(|url| json!({ "type": "WEB", "url": url })),
+    );
+
+    let mut osv = json!({
+        "id": advisory.id,
+        "modified": timestamp(&advisory.date),
+        "published": timestamp(&advisory.date),
+        "aliases": advisory.aliases,
+        "related": advisory.related,
+        "details": details,
+        "affected": [{
+            "package": {
+                "ecosystem": "crates.io",
+                "name": advisory.package,
+                "purl": format!("pkg:cargo/{}", advisory.package),
+            },
+            "ranges": ranges,
+        }],
+        "references": references,
+    });
+
+    if let Some(title) = title {
+        osv["summary"] = title.into();
+    }
+    if let Some(withdrawn) = &advisory.withdrawn {
+        osv["withdrawn"] = timestamp(withdrawn).into();
+    }
+    if let Some(cvss) = advisory.cvss {
+        osv["severity"] = json!([{ "type": "CVSS_V3", "score": cvss }]);
+    }
+
+    Ok(osv)
+}
+
+/// The lowest possible version, as used by RustSec in OSV
+const INTRODUCED_ZERO: &str = "0.0.0-0";
+
+/// Compute the affected ranges (introduced, fixed) from the patched and unaffected requirements.
+///
+/// All versions which are neither patched nor unaffected are considered affected.
+fn affected(versions: &Versions) -> anyhow::Result<Vec<(String, Option<String>)>> {
+    let mut safe = versions
+        .patched
+        .iter()
+        .chain(&versions.unaffected)
+        .map(|req| {
+            let req = VersionReq::parse(req)?;
+            to_interval(&req).ok_or_else(|| anyhow!("unsupported version requirement: {req}"))
+        })
+        .collect::<Result<Vec<_>, anyhow::Error>>()?;
+
+    safe.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let mut result = vec![];
+    let mut current = Some(Version::new(0, 0, 0));
+
+    for (lower, upper) in safe {
+        let Some(start) = &current else {
+            break;
+        };
+
+        if lower > *start {
+            result.push((introduced(start), Some(lower.to_string())));
+        }
+
+        current = match upper {
+            Some(upper) if upper > *start => Some(upper),
+            Some(_) => current,
+            None => None,
+        };
+    }
+
+    if let Some(start) = current {
+        result.push((introduced(&start), None));
+    }
+
+    Ok(result)
+}
+
+fn introduced(version: &Version) -> String {
+    match *version == Version::new(0, 0, 0) {
+        true => INTRODUCED_ZERO.to_string(),
+        false => version.to_string(),
+    }
+}
+
+/// Convert a version requirement into a half-open interval `[lower, upper)`.
+///
+/// Returns `None` for requirements which can't be expressed this way.
+fn to_interval(req: &VersionReq) -> Option<(Version, Option<Version>)> {
+    let mut lower = Version::new(0, 0, 0);
+    let mut upper: Option<Version> = None;
+
+    for comparator in &req.comparators {
+        let (from, to) = comparator_interval(comparator)?;
+        lower = lower.max(from);
+        upper = match (upper, to) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+    }
+
+    Some((lower, upper))
+}
+
+fn comparator_interval(comparator: &Comparator) -> Option<(Version, Option<Version>)> {
+    let version = Version {
+        major: comparator.major,
+        minor: comparator.minor.unwrap_or(0),
+        patch: comparator.patch.unwrap_or(0),
+        pre: comparator.pre.clone(),
+        build: Default::default(),
+    };
+    let next_patch = Version::new(version.major, version.minor, version.patch + 1);
+    let next_minor = Version::new(version.major, version.minor + 1, 0);
+    let next_major = Version::new(version.major + 1, 0, 0);
+
+    Some(match comparator.op {
+        Op::GreaterEq => (version, None),
+        Op::Greater => (next_patch, None),
+        Op::Less => (Version::new(0, 0, 0), Some(version)),
+        Op::LessEq => (Version::new(0, 0, 0), Some(next_patch)),
+        Op::Exact => (version, Some(next_patch)),
+        Op::Tilde => match comparator.minor {
+            Some(_) => (version, Some(next_minor)),
+            None => (version, Some(next_major)),
+        },
+        Op::Caret => {
+            let upper = match (version.major, comparator.minor, comparator.patch) {
+                (0, Some(0), Some(_)) => next_patch,
+                (0, Some(_), _) => next_minor,
+                _ => next_major,
+            };
+            (version, Some(upper))
+        }
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use trustify_test_context::document_bytes;
+
+    fn versions(patched: &[&str], unaffected: &[&str]) -> Versions {
+        Versions {
+            patched: patched.iter().map(ToString::to_string).collect(),
+            unaffected: unaffected.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    #[test]
+    fn affected_ranges() {
+        assert_eq!(
+            affected(&versions(&[">= 0.14.10"], &[])).unwrap(),
+            vec![(INTRODUCED_ZERO.into(), Some("0.14.10".into()))]
+        );
+
+        assert_eq!(
+            affected(&versions(&[">= 0.14.10", "^0.13.10"], &["< 0.12.0"])).unwrap(),
+            vec![
+                ("0.12.0".into(), Some("0.13.10".into())),
+                ("0.14.0".into(), Some("0.14.10".into())),
+            ]
+        );
+
+        assert_eq!(
+            affected(&versions(&[], &[])).unwrap(),
+            vec![(INTRODUCED_ZERO.into(), None)]
+        );
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn translate_advisory() -> anyhow::Result<()> {
+        let data = document_bytes("rustsec/crates/hyper/RUSTSEC-2021-0078.md").await?;
+        let osv = translate(&data, &mut vec![])?;
+
+        assert_eq!(osv["id"], "RUSTSEC-2021-0078");
+        assert_eq!(osv["modified"], "2021-07-07T00:00:00Z");
+        assert_eq!(
+            osv["summary"],
+            "Lenient `hyper` header parsing of `Content-Length` could allow request smuggling"
+        );
+        assert_eq!(
+            osv["aliases"],
+            json!(["CVE-2021-32715", "GHSA-f3pg-qwvg-p99c"])
+        );
+        assert_eq!(
+            osv["affected"][0]["ranges"],
+            json!([{
+                "type": "SEMVER",
+                "events": [{ "introduced": "0.12.0" }, { "fixed": "0.14.10" }],
+            }])
+        );
+
+        // must be a valid OSV document
+        trustify_module_ingestor::service::advisory::osv::parse(&serde_json::to_vec(&osv)?)?;
+
+        Ok(())
+    }
+}
@@ -37,6 +37,7 @@ impl super::ImportRunner {
     ) -> Result<RunOutput, ScannerError> {
         // progress
 
+        let retry = context.retry().cloned();
         let progress = context.progress(format!("Import SBOM: {}", importer.source));
 
         // report
@@ -101,7 +102,7 @@ impl super::ImportRunner {
 
         // filter
 
-        let filter = Filter::from_config(visitor, only_patterns)?;
+        let filter = Filter::from_config(visitor, only_patterns)?.only(retry);
 
         // walker
 
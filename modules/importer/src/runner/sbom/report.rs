@@ -47,7 +47,7 @@ impl<C: RunContext> ValidatedVisitor<HttpSource> for SbomReportVisitor<C> {
                 StorageError::Validation(ValidationError::Retrieval(err)) => {
                     self.next.report.lock().add_error(
                         Phase::Retrieval,
-                        file,
+                        file.clone(),
                         format!("retrieval of document failed: {err}"),
                     );
 
@@ -60,6 +60,10 @@ impl<C: RunContext> ValidatedVisitor<HttpSource> for SbomReportVisitor<C> {
                     {
                         if let Some(status) = err.status() {
                             if self.ignore_errors.contains(&status) {
+                                self.next
+                                    .report
+                                    .lock()
+                                    .add_skipped(file, format!("ignoring status: {status}"));
                                 return Ok(());
                             }
                         }
@@ -51,9 +51,9 @@ impl<C: RunContext> ValidatedVisitor<HttpSource> for StorageVisitor<C> {
                     format!("Skipping document due to size restriction - this: {len}, max: {max}");
                 log::info!("{msg}");
 
-                self.report
-                    .lock()
-                    .add_message(Phase::Upload, file, Severity::Warning, msg);
+                let mut report = self.report.lock();
+                report.add_message(Phase::Upload, file, Severity::Warning, msg.clone());
+                report.add_skipped(doc.url.to_string(), msg);
 
                 return Ok(());
             }
@@ -82,11 +82,15 @@ impl<C: RunContext> ValidatedVisitor<HttpSource> for StorageVisitor<C> {
             .await
             .map_err(StorageError::Storage)?;
 
-        self.report.lock().extend_messages(
-            Phase::Upload,
-            file,
-            result.warnings.into_iter().map(Message::warning),
-        );
+        {
+            let mut report = self.report.lock();
+            report.add_ingested(doc.url.to_string(), &result);
+            report.extend_messages(
+                Phase::Upload,
+                file,
+                result.warnings.into_iter().map(Message::warning),
+            );
+        }
 
         self.context.check_canceled(|| StorageError::Canceled).await
     }
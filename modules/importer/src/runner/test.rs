@@ -0,0 +1,100 @@
+#![cfg(test)]
+
+use crate::runner::{ImportRunner, context::RunContext};
+use git2::{Repository, Signature};
+use std::{collections::HashSet, path::Path};
+use tempfile::TempDir;
+use trustify_test_context::{TrustifyContext, document_bytes_raw};
+
+/// A run context which is never canceled
+#[derive(Debug, Default)]
+pub struct TestContext;
+
+impl RunContext for TestContext {
+    fn name(&self) -> &str {
+        "test"
+    }
+
+    async fn is_canceled(&self) -> bool {
+        false
+    }
+}
+
+/// A run context, retrying only the provided documents
+#[derive(Debug, Default)]
+pub struct RetryContext(pub HashSet<String>);
+
+impl RunContext for RetryContext {
+    fn name(&self) -> &str {
+        "test"
+    }
+
+    async fn is_canceled(&self) -> bool {
+        false
+    }
+
+    fn retry(&self) -> Option<&HashSet<String>> {
+        Some(&self.0)
+    }
+}
+
+/// Create an import runner using the test database and storage
+pub fn runner(ctx: &TrustifyContext) -> ImportRunner {
+    ImportRunner {
+        db: ctx.db.clone(),
+        storage: ctx.storage.clone().into(),
+        working_dir: None,
+        analysis: None,
+    }
+}
+
+/// A local git repository, filled with documents from the test data
+pub struct GitRepository {
+    dir: TempDir,
+}
+
+impl GitRepository {
+    pub fn new() -> anyhow::Result<Self> {
+        let dir = tempfile::tempdir()?;
+        Repository::init(dir.path())?;
+        Ok(Self { dir })
+    }
+
+    /// The source to use for cloning this repository
+    pub fn source(&self) -> String {
+        format!("file://{}", self.dir.path().display())
+    }
+
+    /// Add test data files (keeping their path) and commit them
+    pub async fn commit(&self, files: &[&str]) -> anyhow::Result<()> {
+        let repo = Repository::open(self.dir.path())?;
+        let mut index = repo.index()?;
+
+        for file in files {
+            let path = self.dir.path().join(file);
+            if let Some(parent) = path.parent() {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+            tokio::fs::write(&path, document_bytes_raw(file).await?).await?;
+            index.add_path(Path::new(file))?;
+        }
+
+        index.write()?;
+        let tree = repo.find_tree(index.write_tree()?)?;
+
+        let signature = Signature::now("test", "test@example.com")?;
+        let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+        let parents = parent.iter().collect::<Vec<_>>();
+
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            &format!("add {} files", files.len()),
+            &tree,
+            &parents,
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,72 @@
+use crate::runner::common::Error;
+use crate::runner::common::{
+    processing_error::ProcessingError,
+    walker::{CallbackError, Callbacks, Handler, HandlerError},
+};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// Handles files with one of the given extensions, ignoring all others
+pub struct TranslatedHandler<C>
+where
+    C: Callbacks<Vec<u8>> + Send + 'static,
+{
+    pub callbacks: C,
+    pub extensions: &'static [&'static str],
+}
+
+impl<C> Handler for TranslatedHandler<C>
+where
+    C: Callbacks<Vec<u8>> + Send + 'static,
+{
+    type Error = Error;
+
+    fn is_canceled(&self) -> bool {
+        self.callbacks.is_canceled()
+    }
+
+    fn process(&self, path: &Path, relative_path: &Path) -> Result<(), HandlerError<Self::Error>> {
+        match self.process_file(path, relative_path) {
+            Ok(()) => Ok(()),
+            Err(ProcessingError::Critical(err)) => {
+                Err(HandlerError::Processing(Error::Processing(err)))
+            }
+            Err(ProcessingError::Canceled) => Err(HandlerError::Canceled),
+            Err(err) => {
+                log::warn!("Failed to process file ({}): {err}", path.display());
+                self.callbacks
+                    .loading_error(path.to_path_buf(), err.to_string());
+                Ok(())
+            }
+        }
+    }
+}
+
+impl<C> TranslatedHandler<C>
+where
+    C: Callbacks<Vec<u8>> + Send + 'static,
+{
+    fn process_file(&self, path: &Path, rel_path: &Path) -> Result<(), ProcessingError> {
+        let document = match path.extension().map(|s| s.to_string_lossy()) {
+            Some(e) if self.extensions.contains(&e.as_ref()) => {
+                let mut data = Vec::new();
+                File::open(path)?.read_to_end(&mut data)?;
+                data
+            }
+            e => {
+                log::debug!("Skipping unknown extension: {e:?}");
+                return Ok(());
+            }
+        };
+
+        self.callbacks
+            .process(rel_path, document)
+            .map_err(|err| match err {
+                CallbackError::Processing(err) => ProcessingError::Critical(err),
+                CallbackError::Canceled => ProcessingError::Canceled,
+            })?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,208 @@
+mod handler;
+
+use crate::runner::{
+    RunOutput,
+    common::walker::{CallbackError, Callbacks, GitWalker},
+    context::RunContext,
+    report::{Message, Phase, ReportBuilder, ScannerError, Severity},
+};
+use handler::TranslatedHandler;
+use parking_lot::Mutex;
+use std::{path::Path, path::PathBuf, sync::Arc};
+use tokio::runtime::Handle;
+use trustify_entity::labels::Labels;
+use trustify_module_ingestor::{
+    graph::Graph,
+    service::{Format, IngestorService},
+};
+
+/// A function translating a document into an OSV JSON document
+///
+/// Parts of the document which can't be translated, but don't prevent translating the rest,
+/// are skipped and reported by adding a warning.
+pub type Translate = fn(&[u8], &mut Vec<String>) -> anyhow::Result<serde_json::Value>;
+
+/// A git repository of advisories, which get translated into OSV before they are ingested
+pub(crate) struct TranslatedImporter {
+    /// The name of the importer type, used for the working directory
+    pub name: &'static str,
+    /// The issuer of all documents
+    pub issuer: &'static str,
+    /// The extensions of the files to process, all others are ignored
+    pub extensions: &'static [&'static str],
+    pub translate: Translate,
+
+    pub source: String,
+    pub branch: Option<String>,
+    pub path: Option<String>,
+    pub labels: Labels,
+}
+
+/// Convert a date into an OSV timestamp (at midnight UTC), keeping full timestamps as they are
+pub fn timestamp(date: &str) -> String {
+    match date.contains('T') {
+        true => date.to_string(),
+        false => format!("{date}T00:00:00Z"),
+    }
+}
+
+struct Context<C: RunContext + 'static> {
+    context: C,
+    source: String,
+    labels: Labels,
+    issuer: &'static str,
+    translate: Translate,
+    report: Arc<Mutex<ReportBuilder>>,
+    ingestor: IngestorService,
+}
+
+impl<C: RunContext> Context<C> {
+    fn store(&self, path: &Path, data: Vec<u8>) -> anyhow::Result<()> {
+        self.report.lock().tick();
+
+        let result = Handle::current().block_on(async {
+            self.ingestor
+                .ingest(
+                    &data,
+                    Format::OSV,
+                    Labels::new()
+                        .add("source", &self.source)
+                        .add("importer", self.context.name())
+                        .add("file", path.to_string_lossy())
+                        .extend(&self.labels.0),
+                    Some(self.issuer.into()),
+                )
+                .await
+        })?;
+
+        self.report
+            .lock()
+            .add_ingested(path.to_string_lossy(), &result);
+
+        Ok(())
+    }
+}
+
+impl<C: RunContext> Callbacks<Vec<u8>> for Context<C> {
+    fn loading_error(&self, path: PathBuf, message: String) {
+        self.report
+            .lock()
+            .add_error(Phase::Validation, path.to_string_lossy(), message);
+    }
+
+    fn process(&self, path: &Path, data: Vec<u8>) -> Result<(), CallbackError> {
+        // translate into OSV, which we store and ingest
+
+        let mut warnings = vec![];
+        let osv =
+            (self.translate)(&data, &mut warnings).and_then(|osv| Ok(serde_json::to_vec(&osv)?));
+
+        self.report.lock().extend_messages(
+            Phase::Validation,
+            path.to_string_lossy(),
+            warnings.into_iter().map(|message| Message {
+                severity: Severity::Warning,
+                message,
+            }),
+        );
+
+        let osv = match osv {
+            Ok(osv) => osv,
+            Err(err) => {
+                self.loading_error(path.to_path_buf(), err.to_string());
+                return self.context.check_canceled_sync(|| CallbackError::Canceled);
+            }
+        };
+
+        if let Err(err) = self.store(path, osv) {
+            self.report
+                .lock()
+                .add_error(Phase::Upload, path.to_string_lossy(), err.to_string());
+        }
+
+        self.context.check_canceled_sync(|| CallbackError::Canceled)
+    }
+
+    fn is_canceled(&self) -> bool {
+        self.context.is_canceled_sync()
+    }
+}
+
+impl super::ImportRunner {
+    /// Run an import of a git repository, translating all documents into OSV.
+    pub(crate) async fn run_once_translated(
+        &self,
+        context: impl RunContext + 'static,
+        importer: TranslatedImporter,
+        continuation: serde_json::Value,
+    ) -> Result<RunOutput, ScannerError> {
+        let ingestor = IngestorService::new(
+            Graph::new(self.db.clone()),
+            self.storage.clone(),
+            self.analysis.clone(),
+        );
+
+        let report = Arc::new(Mutex::new(ReportBuilder::new()));
+        let continuation = serde_json::from_value(continuation).unwrap_or_default();
+
+        // working dir
+
+        let working_dir = self
+            .create_working_dir(importer.name, &importer.source)
+            .await?;
+
+        // progress reporting
+
+        let progress = context.progress(format!("Import {}: {}", importer.issuer, importer.source));
+
+        // files to retry, if any
+
+        let only = context
+            .retry()
+            .map(|retry| retry.iter().map(PathBuf::from).collect());
+
+        // run the walker
+
+        let walker = GitWalker::new(
+            importer.source.clone(),
+            TranslatedHandler {
+                callbacks: Context {
+                    context,
+                    source: importer.source,
+                    labels: importer.labels,
+                    issuer: importer.issuer,
+                    translate: importer.translate,
+                    report: report.clone(),
+                    ingestor,
+                },
+                extensions: importer.extensions,
+            },
+        )
+        .continuation(continuation)
+        .only(only)
+        .branch(importer.branch)
+        .path(importer.path)
+        .progress(progress);
+
+        let continuation = match working_dir {
+            Some(working_dir) => walker.working_dir(working_dir).run().await,
+            None => walker.run().await,
+        }
+        .map_err(|err| ScannerError::Critical(err.into()))?;
+
+        // extract the report
+
+        let report = match Arc::try_unwrap(report) {
+            Ok(report) => report.into_inner(),
+            Err(report) => report.lock().clone(),
+        }
+        .build();
+
+        // return
+
+        Ok(RunOutput {
+            report,
+            continuation: serde_json::to_value(continuation).ok(),
+        })
+    }
+}
@@ -7,6 +7,7 @@ use crate::{
     service::ImporterService,
 };
 use std::{
+    collections::HashSet,
     fmt::Debug,
     time::{Duration, Instant},
 };
@@ -21,6 +22,8 @@ pub struct ServiceRunContext {
     name: String,
     state: Mutex<CheckCancellation>,
     service: ImporterService,
+    /// The documents to retry, if this is a retry run
+    retry: Option<HashSet<String>>,
 }
 
 impl ServiceRunContext {
@@ -34,8 +37,15 @@ impl ServiceRunContext {
                 token,
             )),
             service,
+            retry: None,
         }
     }
+
+    /// Only process the provided documents
+    pub fn with_retry(mut self, retry: HashSet<String>) -> Self {
+        self.retry = Some(retry);
+        self
+    }
 }
 
 impl RunContext for ServiceRunContext {
@@ -50,6 +60,10 @@ impl RunContext for ServiceRunContext {
     fn progress(&self, _message: String) -> impl Progress + Send + 'static {
         ServiceProgress::new(self.name.clone(), self.service.clone())
     }
+
+    fn retry(&self) -> Option<&HashSet<String>> {
+        self.retry.as_ref()
+    }
 }
 
 #[derive(Debug)]
@@ -19,6 +19,7 @@ use tracing::instrument;
 use trustify_common::db::Database;
 use trustify_module_analysis::service::AnalysisService;
 use trustify_module_storage::service::dispatch::DispatchBackend;
+use uuid::Uuid;
 
 /// run the importer loop
 pub async fn importer(
@@ -129,13 +130,27 @@ async fn import(
 
     log::info!("Starting run: {}", importer.name);
 
-    let context = ServiceRunContext::new(service.clone(), importer.name.clone(), cancel);
+    let mut context = ServiceRunContext::new(service.clone(), importer.name.clone(), cancel);
+    let mut last_success = importer.data.last_success;
+
+    // when retrying, only process the failed documents of the report, regardless of their age
+
+    let retry = importer
+        .data
+        .retry
+        .as_deref()
+        .and_then(|id| Uuid::parse_str(id).ok());
+    if let Some(retry) = retry {
+        log::info!("Retrying failed documents of report: {retry}");
+        context = context.with_retry(service.failed_documents(retry).await?);
+        last_success = None;
+    }
 
     let (last_error, report, continuation) = match runner
         .run_once(
             context,
             importer.data.configuration,
-            importer.data.last_success,
+            last_success,
             importer.data.continuation,
         )
         .await
@@ -156,16 +171,36 @@ async fn import(
 
     log::info!("Import run complete: {last_error:?}");
 
-    service
-        .update_finish(
-            &importer.name,
-            None,
-            last_run,
-            last_error,
-            continuation,
-            report.and_then(|report| serde_json::to_value(report).ok()),
-        )
-        .await?;
+    // the outcome of the documents is stored separately from the report
+
+    let (report, items) = match report {
+        Some(mut report) => {
+            let items = std::mem::take(&mut report.items);
+            (serde_json::to_value(report).ok(), items)
+        }
+        None => (None, vec![]),
+    };
+
+    match retry {
+        Some(_) => {
+            service
+                .finish_retry(&importer.name, None, last_error, report, items)
+                .await?
+        }
+        None => {
+            service
+                .update_finish(
+                    &importer.name,
+                    None,
+                    last_run,
+                    last_error,
+                    continuation,
+                    report,
+                    items,
+                )
+                .await?
+        }
+    }
 
     Ok(())
 }
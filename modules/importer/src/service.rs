@@ -1,19 +1,30 @@
-use crate::model::{Importer, ImporterConfiguration, ImporterReport};
+use crate::{
+    model::{Importer, ImporterConfiguration, ImporterReport},
+    runner::report::ReportItem,
+};
 use actix_web::{HttpResponse, ResponseError, body::BoxBody};
 use sea_orm::{
     ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, EntityTrait, PaginatorTrait,
     QueryFilter, QueryOrder, TransactionTrait,
 };
 use sea_query::{Alias, Expr, Nullable, SimpleExpr};
-use std::fmt::{Debug, Display};
+use std::{
+    collections::HashSet,
+    fmt::{Debug, Display},
+};
 use time::OffsetDateTime;
 use tracing::instrument;
 use trustify_common::{
-    db::{Database, DatabaseErrors, limiter::LimiterTrait},
+    db::{
+        Database, DatabaseErrors,
+        chunk::EntityChunkedIter,
+        limiter::LimiterTrait,
+        query::{self, Filtering, Query},
+    },
     error::ErrorInformation,
     model::{Paginated, PaginatedResults, Revisioned},
 };
-use trustify_entity::{importer, importer_report};
+use trustify_entity::{importer, importer_report, importer_report_item};
 use uuid::Uuid;
 
 #[derive(Debug, thiserror::Error)]
@@ -24,10 +35,14 @@ pub enum Error {
     NotFound(String),
     #[error("mid air collision")]
     MidAirCollision,
+    #[error("unable to retry: {0}")]
+    Retry(String),
     #[error("database error: {0}")]
     Database(#[from] sea_orm::DbErr),
     #[error(transparent)]
     Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Query(#[from] query::Error),
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -68,6 +83,16 @@ impl ResponseError for Error {
                 message: self.to_string(),
                 details: None,
             }),
+            Error::Retry(_) => HttpResponse::BadRequest().json(ErrorInformation {
+                error: "Retry".into(),
+                message: self.to_string(),
+                details: None,
+            }),
+            Error::Query(_) => HttpResponse::BadRequest().json(ErrorInformation {
+                error: "Query".into(),
+                message: self.to_string(),
+                details: None,
+            }),
             _ => HttpResponse::InternalServerError().json(ErrorInformation {
                 error: "Internal".into(),
                 message: self.to_string(),
@@ -139,6 +164,7 @@ impl ImporterService {
             configuration: Set(serde_json::to_value(configuration)?),
 
             heartbeat: Set(None),
+            retry: Set(None),
         };
 
         match entity.insert(&self.db).await {
@@ -249,7 +275,8 @@ impl ImporterService {
         .await
     }
 
-    #[instrument(skip(self, report, continuation), err)]
+    #[instrument(skip(self, report, items, continuation), err)]
+    #[allow(clippy::too_many_arguments)]
     pub async fn update_finish(
         &self,
         name: &str,
@@ -258,6 +285,7 @@ impl ImporterService {
         last_error: Option<String>,
         continuation: Option<serde_json::Value>,
         report: Option<serde_json::Value>,
+        items: Vec<ReportItem>,
     ) -> Result<(), Error> {
         let tx = self.db.begin().await?;
 
@@ -289,14 +317,7 @@ impl ImporterService {
         // add report
 
         if let Some(report) = report {
-            let entity = importer_report::ActiveModel {
-                id: Set(Uuid::new_v4()),
-                importer: Set(name.to_string()),
-                creation: Set(OffsetDateTime::now_utc()),
-                error: Set(last_error),
-                report: Set(report),
-            };
-            entity.insert(&tx).await?;
+            Self::insert_report(&tx, name, last_error, report, items).await?;
         }
 
         // commit
@@ -306,6 +327,95 @@ impl ImporterService {
         Ok(())
     }
 
+    /// Update state to indicate the end of a run, retrying the failed documents of a report
+    ///
+    /// Compared to [`Self::update_finish`], this doesn't record the run as a regular run, keeping
+    /// the continuation and the timestamps of the last run.
+    #[instrument(skip(self, report, items), err)]
+    pub async fn finish_retry(
+        &self,
+        name: &str,
+        expected_revision: Option<&str>,
+        last_error: Option<String>,
+        report: Option<serde_json::Value>,
+        items: Vec<ReportItem>,
+    ) -> Result<(), Error> {
+        let tx = self.db.begin().await?;
+
+        let updates = vec![
+            (importer::Column::LastError, Expr::value(last_error.clone())),
+            (
+                importer::Column::State,
+                Expr::value(importer::State::Waiting),
+            ),
+            (importer::Column::ProgressCurrent, Expr::value(i32::null())),
+            (importer::Column::ProgressTotal, Expr::value(i32::null())),
+            (
+                importer::Column::ProgressMessage,
+                Expr::value(String::null()),
+            ),
+            (
+                importer::Column::LastChange,
+                Expr::value(OffsetDateTime::now_utc()),
+            ),
+            (importer::Column::Retry, Expr::value(None::<Uuid>)),
+        ];
+
+        self.update(&tx, name, expected_revision, updates).await?;
+
+        if let Some(report) = report {
+            Self::insert_report(&tx, name, last_error, report, items).await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Store the report of a run, along with the outcome of its documents
+    async fn insert_report<C>(
+        db: &C,
+        name: &str,
+        last_error: Option<String>,
+        report: serde_json::Value,
+        items: Vec<ReportItem>,
+    ) -> Result<(), Error>
+    where
+        C: ConnectionTrait,
+    {
+        let id = Uuid::new_v4();
+
+        let entity = importer_report::ActiveModel {
+            id: Set(id),
+            importer: Set(name.to_string()),
+            creation: Set(OffsetDateTime::now_utc()),
+            error: Set(last_error),
+            report: Set(report),
+        };
+        entity.insert(db).await?;
+
+        let items = items
+            .into_iter()
+            .map(|item| importer_report_item::ActiveModel {
+                id: Set(Uuid::new_v4()),
+                report: Set(id),
+                file: Set(item.file),
+                outcome: Set(item.outcome.into()),
+                phase: Set(item.phase.map(Into::into)),
+                message: Set(item.message),
+                document: Set(item.document),
+                document_id: Set(item.document_id),
+            });
+
+        for batch in &items.chunked() {
+            importer_report_item::Entity::insert_many(batch)
+                .exec(db)
+                .await?;
+        }
+
+        Ok(())
+    }
+
     async fn update<C>(
         &self,
         db: &C,
@@ -441,7 +551,7 @@ impl ImporterService {
         let limiting = importer_report::Entity::find()
             .filter(importer_report::Column::Importer.eq(name))
             .order_by_desc(importer_report::Column::Creation)
-            .limiting(&self.db, paginated.offset, paginated.limit);
+            .limiting(&self.db, &paginated);
 
         Ok(PaginatedResults {
             total: limiting.total().await?,
@@ -451,6 +561,107 @@ impl ImporterService {
                 .into_iter()
                 .map(ImporterReport::from)
                 .collect(),
+            next: None,
         })
     }
+
+    /// Get the outcome of the documents of a report
+    ///
+    /// Returns `None` if the report doesn't exist, or doesn't belong to the importer.
+    #[instrument(skip(self))]
+    pub async fn get_report_items(
+        &self,
+        name: &str,
+        report: Uuid,
+        search: Query,
+        paginated: Paginated,
+    ) -> Result<Option<PaginatedResults<ReportItem>>, Error> {
+        if !self.has_report(name, report).await? {
+            return Ok(None);
+        }
+
+        let limiting = importer_report_item::Entity::find()
+            .filter(importer_report_item::Column::Report.eq(report))
+            .filtering(search)?
+            .order_by_asc(importer_report_item::Column::File)
+            .limiting(&self.db, &paginated);
+
+        Ok(Some(PaginatedResults {
+            total: limiting.total().await?,
+            items: limiting
+                .fetch()
+                .await?
+                .into_iter()
+                .map(ReportItem::from)
+                .collect(),
+            next: None,
+        }))
+    }
+
+    /// Get the documents which failed during the run of a report
+    #[instrument(skip(self), err)]
+    pub async fn failed_documents(&self, report: Uuid) -> Result<HashSet<String>, Error> {
+        Ok(importer_report_item::Entity::find()
+            .filter(importer_report_item::Column::Report.eq(report))
+            .filter(importer_report_item::Column::Outcome.eq(importer_report_item::Outcome::Failed))
+            .all(&self.db)
+            .await?
+            .into_iter()
+            .map(|item| item.file)
+            .collect())
+    }
+
+    /// Schedule the failed documents of a report to be retried with the next run
+    ///
+    /// Returns `false` if the report doesn't exist, or doesn't belong to the importer.
+    #[instrument(skip(self), err)]
+    pub async fn retry(
+        &self,
+        name: &str,
+        report: Uuid,
+        expected_revision: Option<&str>,
+    ) -> Result<bool, Error> {
+        let Some(importer) = self.read(name).await? else {
+            return Err(Error::NotFound(name.into()));
+        };
+
+        if !importer.value.data.configuration.supports_retry() {
+            return Err(Error::Retry(
+                "importer doesn't support retrying individual documents".into(),
+            ));
+        }
+
+        if !self.has_report(name, report).await? {
+            return Ok(false);
+        }
+
+        let failed = importer_report_item::Entity::find()
+            .filter(importer_report_item::Column::Report.eq(report))
+            .filter(importer_report_item::Column::Outcome.eq(importer_report_item::Outcome::Failed))
+            .count(&self.db)
+            .await?;
+
+        if failed == 0 {
+            return Err(Error::Retry("report has no failed documents".into()));
+        }
+
+        self.update(
+            &self.db,
+            name,
+            expected_revision,
+            vec![(importer::Column::Retry, Expr::value(report))],
+        )
+        .await?;
+
+        Ok(true)
+    }
+
+    /// Check if the report exists and belongs to the importer
+    async fn has_report(&self, name: &str, report: Uuid) -> Result<bool, Error> {
+        Ok(importer_report::Entity::find_by_id(report)
+            .filter(importer_report::Column::Importer.eq(name))
+            .count(&self.db)
+            .await?
+            > 0)
+    }
 }
@@ -1,14 +1,16 @@
 #![cfg(test)]
 
-use super::model::{
-    CommonImporter, Importer, ImporterConfiguration, ImporterData, SbomImporter, State,
+use super::{
+    model::{CommonImporter, Importer, ImporterConfiguration, ImporterData, SbomImporter, State},
+    runner::report::{Outcome, Phase, ReportItem},
+    service::ImporterService,
 };
 use actix_web::{
     App,
     http::{StatusCode, header},
     test as actix,
 };
-use serde_json::json;
+use serde_json::{Value, json};
 use std::time::Duration;
 use test_context::test_context;
 use test_log::test;
@@ -48,6 +50,7 @@ fn mock_importer(result: &Importer, source: impl Into<String>) -> Importer {
             last_run: None,
             progress: Default::default(),
             continuation: serde_json::Value::Null,
+            retry: None,
         },
     }
 }
@@ -102,6 +105,7 @@ async fn default(ctx: TrustifyContext) {
                 last_error: None,
                 progress: Default::default(),
                 continuation: serde_json::Value::Null,
+                retry: None,
             }
         }]
     );
@@ -392,3 +396,172 @@ async fn patch(ctx: TrustifyContext) {
     let resp = actix::call_service(&app, req).await;
     assert_eq!(resp.status(), StatusCode::NOT_FOUND);
 }
+
+fn mock_item(file: &str, outcome: Outcome) -> ReportItem {
+    ReportItem {
+        file: file.into(),
+        outcome,
+        phase: (outcome == Outcome::Failed).then_some(Phase::Upload),
+        message: None,
+        document: None,
+        document_id: None,
+    }
+}
+
+#[test_context(TrustifyContext, skip_teardown)]
+#[test(actix_web::test)]
+async fn retry(ctx: TrustifyContext) {
+    let db = ctx.db;
+    let service = ImporterService::new(db.clone());
+    let app = actix::init_service(
+        App::new()
+            .into_utoipa_app()
+            .add_test_authorizer()
+            .service(
+                utoipa_actix_web::scope("/api")
+                    .configure(|svc| super::endpoints::configure(svc, db)),
+            )
+            .into_app(),
+    )
+    .await;
+
+    // create one
+
+    let req = actix::TestRequest::post()
+        .uri("/api/v2/importer/foo")
+        .set_json(mock_configuration("bar"))
+        .to_request();
+
+    let resp = actix::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+
+    // record a run, with one failed document
+
+    service
+        .update_finish(
+            "foo",
+            None,
+            time::OffsetDateTime::now_utc(),
+            None,
+            None,
+            Some(json!({})),
+            vec![
+                mock_item("a.json", Outcome::Ingested),
+                mock_item("b.json", Outcome::Failed),
+                mock_item("c.json", Outcome::Skipped),
+            ],
+        )
+        .await
+        .expect("must record the run");
+
+    let req = actix::TestRequest::get()
+        .uri("/api/v2/importer/foo/report")
+        .to_request();
+
+    let resp: Value = actix::call_and_read_body_json(&app, req).await;
+    let id = resp["items"][0]["id"]
+        .as_str()
+        .unwrap_or_default()
+        .to_string();
+
+    // get the failed documents
+
+    let req = actix::TestRequest::get()
+        .uri(&format!("/api/v2/importer/foo/report/{id}/items"))
+        .to_request();
+
+    let resp: Value = actix::call_and_read_body_json(&app, req).await;
+    assert_eq!(resp["total"], json!(3));
+
+    let req = actix::TestRequest::get()
+        .uri(&format!(
+            "/api/v2/importer/foo/report/{id}/items?q=outcome%3Dfailed"
+        ))
+        .to_request();
+
+    let resp: Value = actix::call_and_read_body_json(&app, req).await;
+    assert_eq!(resp["total"], json!(1));
+    assert_eq!(
+        resp["items"][0],
+        json!({ "file": "b.json", "outcome": "failed", "phase": "upload" })
+    );
+
+    // the report must belong to the importer
+
+    let req = actix::TestRequest::get()
+        .uri(&format!("/api/v2/importer/foo2/report/{id}/items"))
+        .to_request();
+
+    let resp = actix::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    // retry them
+
+    let req = actix::TestRequest::post()
+        .uri(&format!("/api/v2/importer/foo/report/{id}/retry"))
+        .to_request();
+
+    let resp = actix::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+    let importer = service
+        .read("foo")
+        .await
+        .expect("must load")
+        .expect("must exist");
+    assert_eq!(importer.value.data.retry.as_deref(), Some(id.as_str()));
+    assert!(importer.value.is_due());
+
+    // finishing the retry keeps the continuation and the last run
+
+    service
+        .finish_retry(
+            "foo",
+            None,
+            None,
+            Some(json!({})),
+            vec![mock_item("b.json", Outcome::Ingested)],
+        )
+        .await
+        .expect("must record the retry");
+
+    let importer = service
+        .read("foo")
+        .await
+        .expect("must load")
+        .expect("must exist");
+    assert_eq!(importer.value.data.retry, None);
+    assert!(importer.value.data.last_run.is_some());
+
+    // nothing left to retry
+
+    let req = actix::TestRequest::get()
+        .uri("/api/v2/importer/foo/report")
+        .to_request();
+
+    let resp: Value = actix::call_and_read_body_json(&app, req).await;
+    assert_eq!(resp["total"], json!(2));
+    let id = resp["items"][0]["id"]
+        .as_str()
+        .unwrap_or_default()
+        .to_string();
+
+    let req = actix::TestRequest::post()
+        .uri(&format!("/api/v2/importer/foo/report/{id}/retry"))
+        .to_request();
+
+    let resp = actix::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    // unknown report
+
+    let req = actix::TestRequest::post()
+        .uri(&format!(
+            "/api/v2/importer/foo/report/{}/retry",
+            uuid::Uuid::new_v4()
+        ))
+        .to_request();
+
+    let resp = actix::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+}
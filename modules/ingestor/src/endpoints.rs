@@ -1,14 +1,33 @@
 use crate::{
     graph::Graph,
-    service::{Error, IngestorService},
+    service::{Error, IngestorService, reprocess::ReprocessRun, snapshot::SnapshotImport},
 };
-use actix_web::{HttpResponse, Responder, post, web};
-use trustify_auth::{UploadDataset, authorizer::Require};
-use trustify_common::{db::Database, model::BinaryData};
-use trustify_entity::labels::Labels;
+use actix_web::{HttpResponse, Responder, get, post, web};
+use futures_util::StreamExt;
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{Seek, SeekFrom},
+};
+use time::OffsetDateTime;
+use tokio::io::AsyncWriteExt;
+use tokio_util::io::ReaderStream;
+use trustify_auth::{
+    ExportSnapshot, ImportSnapshot, ReprocessDocument, UploadDataset, authorizer::Require,
+};
+use trustify_common::{
+    db::Database,
+    model::{BinaryData, Paginated, PaginatedResults},
+};
+use trustify_entity::{labels::Labels, reprocess::Filter};
 use trustify_module_analysis::service::AnalysisService;
+use trustify_module_audit::{
+    recorder::{Action, Audit, Event, TargetKind},
+    service::AuditService,
+};
 use trustify_module_storage::service::dispatch::DispatchBackend;
 use utoipa::IntoParams;
+use uuid::Uuid;
 
 /// mount the "ingestor" module
 pub fn configure(
@@ -18,11 +37,19 @@ pub fn configure(
     storage: impl Into<DispatchBackend>,
     analysis: Option<AnalysisService>,
 ) {
-    let ingestor_service = IngestorService::new(Graph::new(db), storage, analysis);
+    let ingestor_service = IngestorService::new(Graph::new(db.clone()), storage, analysis);
 
     svc.app_data(web::Data::new(ingestor_service))
+        .app_data(web::Data::new(AuditService::new(db)))
         .app_data(web::Data::new(config))
-        .service(upload_dataset);
+        .service(upload_dataset)
+        .service(export_snapshot)
+        .service(import_snapshot)
+        .service(get_snapshot_import)
+        .service(list_reprocess_runs)
+        .service(create_reprocess_run)
+        .service(get_reprocess_run)
+        .service(resume_reprocess_run);
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Default)]
@@ -59,10 +86,254 @@ pub async fn upload_dataset(
     config: web::Data<Config>,
     web::Query(UploadParams { labels }): web::Query<UploadParams>,
     bytes: web::Bytes,
+    audit: Audit,
     _: Require<UploadDataset>,
 ) -> Result<impl Responder, Error> {
     let result = service
         .ingest_dataset(&bytes, labels, config.dataset_entry_limit)
         .await?;
+
+    // record the IDs of the ingested documents, by file name
+    let documents = result
+        .files
+        .iter()
+        .map(|(file, result)| (file, result.id.to_string()))
+        .collect::<BTreeMap<_, _>>();
+    audit
+        .record(Event::untargeted(Action::Upload, TargetKind::Dataset).after(documents))
+        .await;
+
     Ok(HttpResponse::Created().json(result))
 }
+
+#[derive(IntoParams, Clone, Debug, Default, PartialEq, Eq, serde::Deserialize)]
+struct SnapshotParams {
+    /// Only export documents ingested after this time, like the creation time of a previous
+    /// snapshot
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    since: Option<OffsetDateTime>,
+}
+
+#[utoipa::path(
+    tag = "snapshot",
+    operation_id = "exportSnapshot",
+    params(SnapshotParams),
+    responses(
+        (status = 200, description = "A snapshot archive of the documents", content_type = "application/zip", body = inline(BinaryData)),
+    )
+)]
+#[get("/v2/snapshot")]
+/// Export a snapshot of all documents
+///
+/// The snapshot is a zip archive of the documents, along with their labels and metadata, which
+/// can be imported into another instance.
+pub async fn export_snapshot(
+    service: web::Data<IngestorService>,
+    web::Query(SnapshotParams { since }): web::Query<SnapshotParams>,
+    _: Require<ExportSnapshot>,
+) -> Result<impl Responder, Error> {
+    // the archive can be large, so it gets written to a temporary file, which is then streamed
+    let mut file = tempfile::tempfile()?;
+    service.export_snapshot(since, &mut file).await?;
+    file.seek(SeekFrom::Start(0))?;
+
+    Ok(HttpResponse::Ok()
+        .content_type("application/zip")
+        .streaming(ReaderStream::new(tokio::fs::File::from_std(file))))
+}
+
+#[utoipa::path(
+    tag = "snapshot",
+    operation_id = "importSnapshot",
+    request_body = inline(BinaryData),
+    responses(
+        (status = 202, description = "Started importing the snapshot", body = SnapshotImport),
+        (status = 400, description = "The file could not be parsed as a snapshot"),
+    )
+)]
+#[post("/v2/snapshot")]
+/// Import a snapshot, ingesting all documents which don't exist yet
+///
+/// The upload is stored in a temporary file, and imported in the background. The returned
+/// import reports the outcome, once completed.
+pub async fn import_snapshot(
+    service: web::Data<IngestorService>,
+    config: web::Data<Config>,
+    mut payload: web::Payload,
+    audit: Audit,
+    _: Require<ImportSnapshot>,
+) -> Result<impl Responder, Error> {
+    let mut file = tokio::fs::File::from_std(tempfile::tempfile()?);
+    while let Some(chunk) = payload.next().await {
+        let chunk = chunk.map_err(|err| Error::Generic(err.into()))?;
+        file.write_all(&chunk).await?;
+    }
+
+    let mut file = file.into_std().await;
+    file.seek(SeekFrom::Start(0))?;
+    let limit = config.dataset_entry_limit;
+    let import = service.snapshot_importer(limit).create(&mut file).await?;
+    file.seek(SeekFrom::Start(0))?;
+
+    audit
+        .record(Event::new(Action::Upload, TargetKind::Snapshot, import.id))
+        .await;
+
+    spawn_snapshot_import(service.get_ref().clone(), limit, import.id, file);
+
+    Ok(HttpResponse::Accepted().json(import))
+}
+
+/// Run a created snapshot import in the background
+fn spawn_snapshot_import(service: IngestorService, limit: usize, id: Uuid, file: File) {
+    actix_web::rt::spawn(async move {
+        if let Err(err) = service.snapshot_importer(limit).run(id, file).await {
+            log::warn!("Failed to import snapshot {id}: {err}");
+        }
+    });
+}
+
+#[utoipa::path(
+    tag = "snapshot",
+    operation_id = "getSnapshotImport",
+    params(
+        ("id", Path, description = "The ID of the import"),
+    ),
+    responses(
+        (status = 200, description = "The snapshot import, including its outcome once completed", body = SnapshotImport),
+        (status = 404, description = "The import could not be found"),
+    )
+)]
+#[get("/v2/snapshot/import/{id}")]
+/// Get a snapshot import
+pub async fn get_snapshot_import(
+    service: web::Data<IngestorService>,
+    config: web::Data<Config>,
+    id: web::Path<Uuid>,
+    _: Require<ImportSnapshot>,
+) -> Result<impl Responder, Error> {
+    Ok(
+        match service
+            .snapshot_importer(config.dataset_entry_limit)
+            .fetch(id.into_inner())
+            .await?
+        {
+            Some(import) => HttpResponse::Ok().json(import),
+            None => HttpResponse::NotFound().finish(),
+        },
+    )
+}
+
+/// Run a claimed reprocessing run in the background
+fn spawn_reprocess_run(service: IngestorService, id: Uuid) {
+    actix_web::rt::spawn(async move {
+        if let Err(err) = service.reprocessor().run(id, |_| {}).await {
+            log::warn!("Failed to reprocess documents of run {id}: {err}");
+        }
+    });
+}
+
+#[utoipa::path(
+    tag = "reprocess",
+    operation_id = "listReprocessRuns",
+    params(Paginated),
+    responses(
+        (status = 200, description = "The reprocessing runs, the most recent first", body = PaginatedResults<ReprocessRun>),
+    )
+)]
+#[get("/v2/reprocess")]
+/// List reprocessing runs
+pub async fn list_reprocess_runs(
+    service: web::Data<IngestorService>,
+    web::Query(paginated): web::Query<Paginated>,
+    _: Require<ReprocessDocument>,
+) -> Result<impl Responder, Error> {
+    Ok(HttpResponse::Ok().json(service.reprocessor().list(paginated).await?))
+}
+
+#[utoipa::path(
+    tag = "reprocess",
+    operation_id = "createReprocessRun",
+    request_body = Filter,
+    responses(
+        (status = 202, description = "Started reprocessing the documents", body = ReprocessRun),
+    )
+)]
+#[post("/v2/reprocess")]
+/// Reprocess stored documents, loading them again
+///
+/// The documents are processed in the background, the returned run reports the progress.
+pub async fn create_reprocess_run(
+    service: web::Data<IngestorService>,
+    web::Json(filter): web::Json<Filter>,
+    audit: Audit,
+    _: Require<ReprocessDocument>,
+) -> Result<impl Responder, Error> {
+    let reprocessor = service.reprocessor();
+    let run = reprocessor.create(filter).await?;
+    reprocessor.claim(run.id).await?;
+
+    audit
+        .record(Event::new(Action::Create, TargetKind::Reprocess, run.id).after(&run.filter))
+        .await;
+
+    spawn_reprocess_run(service.get_ref().clone(), run.id);
+
+    Ok(HttpResponse::Accepted().json(run))
+}
+
+#[utoipa::path(
+    tag = "reprocess",
+    operation_id = "getReprocessRun",
+    params(
+        ("id", Path, description = "The ID of the run"),
+    ),
+    responses(
+        (status = 200, description = "The reprocessing run, including its progress and failures", body = ReprocessRun),
+        (status = 404, description = "The run could not be found"),
+    )
+)]
+#[get("/v2/reprocess/{id}")]
+/// Get a reprocessing run
+pub async fn get_reprocess_run(
+    service: web::Data<IngestorService>,
+    id: web::Path<Uuid>,
+    _: Require<ReprocessDocument>,
+) -> Result<impl Responder, Error> {
+    Ok(match service.reprocessor().fetch(id.into_inner()).await? {
+        Some(run) => HttpResponse::Ok().json(run),
+        None => HttpResponse::NotFound().finish(),
+    })
+}
+
+#[utoipa::path(
+    tag = "reprocess",
+    operation_id = "resumeReprocessRun",
+    params(
+        ("id", Path, description = "The ID of the run"),
+    ),
+    responses(
+        (status = 202, description = "Resumed reprocessing the documents"),
+        (status = 404, description = "The run could not be found"),
+        (status = 409, description = "The run is already running, or completed"),
+    )
+)]
+#[post("/v2/reprocess/{id}/resume")]
+/// Resume an interrupted reprocessing run, continuing after the last processed document
+pub async fn resume_reprocess_run(
+    service: web::Data<IngestorService>,
+    id: web::Path<Uuid>,
+    audit: Audit,
+    _: Require<ReprocessDocument>,
+) -> Result<impl Responder, Error> {
+    let id = id.into_inner();
+    service.reprocessor().claim(id).await?;
+
+    audit
+        .record(Event::new(Action::Update, TargetKind::Reprocess, id))
+        .await;
+
+    spawn_reprocess_run(service.get_ref().clone(), id);
+
+    Ok(HttpResponse::Accepted().finish())
+}
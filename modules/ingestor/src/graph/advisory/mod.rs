@@ -35,6 +35,8 @@ pub struct AdvisoryInformation {
     pub modified: Option<OffsetDateTime>,
     pub withdrawn: Option<OffsetDateTime>,
     pub version: Option<Version>,
+    /// The format of the document, as written by its loader
+    pub format: Option<String>,
 }
 
 pub struct AdvisoryVulnerabilityInformation {
@@ -118,40 +120,106 @@ impl Graph {
         information: impl Into<AdvisoryInformation>,
         connection: &C,
     ) -> Result<Outcome<AdvisoryContext>, Error> {
-        let identifier = identifier.into();
-        let labels = labels.into();
         let sha256 = digests.sha256.encode_hex::<String>();
-        let AdvisoryInformation {
-            id,
-            title,
-            issuer,
-            published,
-            modified,
-            withdrawn,
-            version,
-        } = information.into();
 
         if let Some(found) = self.get_advisory_by_digest(&sha256, connection).await? {
             // we already have the exact same document.
             return Ok(Outcome::Existed(found));
         }
 
-        let organization = if let Some(issuer) = issuer {
-            Some(self.ingest_organization(issuer, (), connection).await?)
-        } else {
-            None
+        let doc = Self::insert_source_document(digests, connection).await?;
+
+        self.insert_advisory(
+            identifier.into(),
+            labels.into(),
+            doc.id,
+            information,
+            connection,
+        )
+        .await
+        .map(Outcome::Added)
+    }
+
+    /// Ingest one of the advisories of a source document which contains more than one
+    ///
+    /// The source document is shared by all of its advisories. An advisory is only added if the
+    /// document doesn't have one with the same identifier yet.
+    #[instrument(skip(self, labels, information, connection), err(level=tracing::Level::INFO))]
+    pub async fn ingest_advisory_of_document<C: ConnectionTrait>(
+        &self,
+        identifier: impl Into<String> + Debug,
+        labels: impl Into<Labels>,
+        digests: &Digests,
+        information: impl Into<AdvisoryInformation>,
+        connection: &C,
+    ) -> Result<Outcome<AdvisoryContext>, Error> {
+        let identifier = identifier.into();
+        let sha256 = digests.sha256.encode_hex::<String>();
+
+        let doc = match source_document::Entity::find()
+            .filter(source_document::Column::Sha256.eq(&sha256))
+            .one(connection)
+            .await?
+        {
+            Some(doc) => {
+                if let Some(found) = advisory::Entity::find()
+                    .filter(advisory::Column::SourceDocumentId.eq(doc.id))
+                    .filter(advisory::Column::Identifier.eq(&identifier))
+                    .one(connection)
+                    .await?
+                {
+                    return Ok(Outcome::Existed(AdvisoryContext::new(self, found)));
+                }
+                doc
+            }
+            None => Self::insert_source_document(digests, connection).await?,
         };
 
+        self.insert_advisory(identifier, labels.into(), doc.id, information, connection)
+            .await
+            .map(Outcome::Added)
+    }
+
+    async fn insert_source_document<C: ConnectionTrait>(
+        digests: &Digests,
+        connection: &C,
+    ) -> Result<source_document::Model, Error> {
         let doc_model = source_document::ActiveModel {
             id: Default::default(),
-            sha256: Set(sha256),
+            sha256: Set(digests.sha256.encode_hex()),
             sha384: Set(digests.sha384.encode_hex()),
             sha512: Set(digests.sha512.encode_hex()),
             size: Set(digests.size as i64),
             ingested: sea_orm::Set(OffsetDateTime::now_utc()),
         };
 
-        let doc = doc_model.insert(connection).await?;
+        Ok(doc_model.insert(connection).await?)
+    }
+
+    async fn insert_advisory<C: ConnectionTrait>(
+        &self,
+        identifier: String,
+        labels: Labels,
+        source_document_id: Uuid,
+        information: impl Into<AdvisoryInformation>,
+        connection: &C,
+    ) -> Result<AdvisoryContext, Error> {
+        let AdvisoryInformation {
+            id,
+            title,
+            issuer,
+            published,
+            modified,
+            withdrawn,
+            version,
+            format,
+        } = information.into();
+
+        let organization = if let Some(issuer) = issuer {
+            Some(self.ingest_organization(issuer, (), connection).await?)
+        } else {
+            None
+        };
 
         // insert
 
@@ -168,7 +236,8 @@ impl Graph {
             modified: Set(modified),
             withdrawn: Set(withdrawn),
             labels: Set(labels),
-            source_document_id: Set(Some(doc.id)),
+            source_document_id: Set(Some(source_document_id)),
+            format: Set(format),
         };
 
         let result = model.insert(connection).await?;
@@ -179,7 +248,7 @@ impl Graph {
 
         // done
 
-        Ok(Outcome::Added(AdvisoryContext::new(self, result)))
+        Ok(AdvisoryContext::new(self, result))
     }
 }
 
@@ -426,6 +495,7 @@ mod test {
                     modified: Some(value.1),
                     withdrawn: None,
                     version: None,
+                    format: None,
                 }
             }
         }
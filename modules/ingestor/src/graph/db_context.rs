@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use crate::graph::error::Error;
+use crate::graph::{error::Error, organization::CachedNameRules};
 use sea_orm::ConnectionTrait;
 use sea_orm::EntityTrait;
 use trustify_entity::status;
@@ -9,12 +9,14 @@ use uuid::Uuid;
 #[derive(Debug, Clone)]
 pub struct DbContext {
     pub status_cache: HashMap<String, Uuid>,
+    pub name_rules: Option<CachedNameRules>,
 }
 
 impl DbContext {
     pub fn new() -> Self {
         Self {
             status_cache: HashMap::new(),
+            name_rules: None,
         }
     }
 
@@ -0,0 +1,34 @@
+-- the statuses of an advisory, summarized as text
+SELECT
+    purl_status.vulnerability_id || ' ' || status.slug || ' '
+        || 'pkg:' || base_purl.type || '/' || COALESCE(base_purl.namespace || '/', '') || base_purl.name
+        || ' ' || version_range.version_scheme_id || ':'
+        || CASE WHEN version_range.low_inclusive THEN '[' ELSE '(' END
+        || COALESCE(version_range.low_version, '') || ',' || COALESCE(version_range.high_version, '')
+        || CASE WHEN version_range.high_inclusive THEN ']' ELSE ')' END
+        AS summary
+FROM purl_status
+    JOIN status ON status.id = purl_status.status_id
+    JOIN base_purl ON base_purl.id = purl_status.base_purl_id
+    JOIN version_range ON version_range.id = purl_status.version_range_id
+WHERE
+    purl_status.advisory_id = $1
+
+UNION
+
+SELECT
+    product_status.vulnerability_id || ' ' || status.slug || ' '
+        || COALESCE(product_version_range.cpe_key, product.cpe_key, product.name)
+        || COALESCE(' ' || product_status.package, '')
+        || ' ' || version_range.version_scheme_id || ':'
+        || CASE WHEN version_range.low_inclusive THEN '[' ELSE '(' END
+        || COALESCE(version_range.low_version, '') || ',' || COALESCE(version_range.high_version, '')
+        || CASE WHEN version_range.high_inclusive THEN ']' ELSE ')' END
+        AS summary
+FROM product_status
+    JOIN status ON status.id = product_status.status_id
+    JOIN product_version_range ON product_version_range.id = product_status.product_version_range_id
+    JOIN product ON product.id = product_version_range.product_id
+    JOIN version_range ON version_range.id = product_version_range.version_range_id
+WHERE
+    product_status.advisory_id = $1
@@ -0,0 +1,191 @@
+use crate::graph::{Graph, error::Error};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DbBackend, EntityTrait, FromQueryResult,
+    QueryFilter, QueryOrder, QuerySelect, Set, Statement,
+};
+use std::collections::BTreeSet;
+use time::OffsetDateTime;
+use tracing::instrument;
+use trustify_entity::{
+    advisory,
+    ingestion_history::{self, Delta, DocumentKind},
+    labels::Labels,
+    sbom, source_document,
+};
+use uuid::Uuid;
+
+/// The origin of an ingestion
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Origin {
+    /// The ID of the user which uploaded the document
+    pub user_id: Option<String>,
+}
+
+#[derive(FromQueryResult)]
+struct Summary {
+    summary: String,
+}
+
+/// The state of a logical document
+struct Document {
+    key: String,
+    ingested: Option<OffsetDateTime>,
+}
+
+impl Graph {
+    /// Record the ingestion of an advisory or SBOM.
+    ///
+    /// This also records the changes compared to the previous version of the same logical
+    /// document. The importer is taken from the `importer` label, if present.
+    #[instrument(skip(self, labels, connection), err(level=tracing::Level::INFO))]
+    pub async fn record_ingestion<C: ConnectionTrait>(
+        &self,
+        kind: DocumentKind,
+        id: Uuid,
+        sha256: String,
+        labels: &Labels,
+        origin: Origin,
+        connection: &C,
+    ) -> Result<(), Error> {
+        let Some(document) = Self::history_document(kind, id, connection).await? else {
+            return Ok(());
+        };
+
+        // if we already recorded this document, then it is a re-ingestion of the same content
+
+        let changed = ingestion_history::Entity::find()
+            .filter(ingestion_history::Column::Kind.eq(kind))
+            .filter(ingestion_history::Column::DocumentId.eq(id))
+            .one(connection)
+            .await?
+            .is_none();
+
+        let delta = match changed {
+            true => {
+                let previous = Self::previous_document(kind, id, &document, connection).await?;
+                let current = Self::summaries(kind, Some(id), connection).await?;
+                let previous = Self::summaries(kind, previous, connection).await?;
+
+                Delta {
+                    added: current.difference(&previous).cloned().collect(),
+                    removed: previous.difference(&current).cloned().collect(),
+                }
+            }
+            false => Delta::default(),
+        };
+
+        ingestion_history::ActiveModel {
+            id: Default::default(),
+            kind: Set(kind),
+            key: Set(document.key),
+            document_id: Set(id),
+            ingested: Set(OffsetDateTime::now_utc()),
+            sha256: Set(sha256),
+            importer: Set(labels.get("importer").cloned()),
+            user_id: Set(origin.user_id),
+            labels: Set(labels.clone()),
+            changed: Set(changed),
+            delta: Set(delta),
+        }
+        .insert(connection)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Look up the key of the logical document, and when the document was stored
+    async fn history_document<C: ConnectionTrait>(
+        kind: DocumentKind,
+        id: Uuid,
+        connection: &C,
+    ) -> Result<Option<Document>, Error> {
+        Ok(match kind {
+            DocumentKind::Advisory => advisory::Entity::find_by_id(id)
+                .find_also_related(source_document::Entity)
+                .one(connection)
+                .await?
+                .map(|(advisory, doc)| Document {
+                    key: advisory.identifier,
+                    ingested: doc.map(|doc| doc.ingested),
+                }),
+            DocumentKind::Sbom => sbom::Entity::find_by_id(id)
+                .find_also_related(source_document::Entity)
+                .one(connection)
+                .await?
+                .map(|(sbom, doc)| Document {
+                    key: sbom
+                        .document_id
+                        .unwrap_or_else(|| sbom.sbom_id.urn().to_string()),
+                    ingested: doc.map(|doc| doc.ingested),
+                }),
+        })
+    }
+
+    /// Find the most recent version of the same logical document, stored before this one
+    async fn previous_document<C: ConnectionTrait>(
+        kind: DocumentKind,
+        id: Uuid,
+        document: &Document,
+        connection: &C,
+    ) -> Result<Option<Uuid>, Error> {
+        let Some(ingested) = document.ingested else {
+            return Ok(None);
+        };
+
+        Ok(match kind {
+            DocumentKind::Advisory => {
+                advisory::Entity::find()
+                    .inner_join(source_document::Entity)
+                    .filter(advisory::Column::Identifier.eq(&document.key))
+                    .filter(advisory::Column::Id.ne(id))
+                    .filter(source_document::Column::Ingested.lt(ingested))
+                    .order_by_desc(source_document::Column::Ingested)
+                    .select_only()
+                    .column(advisory::Column::Id)
+                    .into_tuple()
+                    .one(connection)
+                    .await?
+            }
+            DocumentKind::Sbom => {
+                sbom::Entity::find()
+                    .inner_join(source_document::Entity)
+                    .filter(sbom::Column::DocumentId.eq(&document.key))
+                    .filter(sbom::Column::SbomId.ne(id))
+                    .filter(source_document::Column::Ingested.lt(ingested))
+                    .order_by_desc(source_document::Column::Ingested)
+                    .select_only()
+                    .column(sbom::Column::SbomId)
+                    .into_tuple()
+                    .one(connection)
+                    .await?
+            }
+        })
+    }
+
+    /// Summarize the content of a document, used to compute the delta between versions
+    async fn summaries<C: ConnectionTrait>(
+        kind: DocumentKind,
+        id: Option<Uuid>,
+        connection: &C,
+    ) -> Result<BTreeSet<String>, Error> {
+        let Some(id) = id else {
+            return Ok(Default::default());
+        };
+
+        let sql = match kind {
+            DocumentKind::Advisory => include_str!("advisory_statuses.sql"),
+            DocumentKind::Sbom => include_str!("sbom_packages.sql"),
+        };
+
+        Ok(Summary::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            sql,
+            [id.into()],
+        ))
+        .all(connection)
+        .await?
+        .into_iter()
+        .map(|summary| summary.summary)
+        .collect())
+    }
+}
@@ -0,0 +1,11 @@
+-- the packages of an SBOM, summarized as their PURL (without qualifiers)
+SELECT DISTINCT
+    'pkg:' || base_purl.type || '/' || COALESCE(base_purl.namespace || '/', '') || base_purl.name
+        || '@' || versioned_purl.version
+        AS summary
+FROM sbom_package_purl_ref
+    JOIN qualified_purl ON qualified_purl.id = sbom_package_purl_ref.qualified_purl_id
+    JOIN versioned_purl ON versioned_purl.id = qualified_purl.versioned_purl_id
+    JOIN base_purl ON base_purl.id = versioned_purl.base_purl_id
+WHERE
+    sbom_package_purl_ref.sbom_id = $1
@@ -2,10 +2,12 @@ pub mod advisory;
 pub mod cpe;
 pub mod db_context;
 pub mod error;
+pub mod history;
 pub mod organization;
 pub mod product;
 pub mod purl;
 pub mod sbom;
+pub mod tombstone;
 pub mod vulnerability;
 
 use db_context::DbContext;
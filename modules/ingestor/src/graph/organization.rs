@@ -1,7 +1,18 @@
-use sea_orm::{ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, Set};
-use std::fmt::Debug;
+use regex::Regex;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QueryOrder, Set,
+};
+use sea_query::Expr;
+use std::{
+    fmt::Debug,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
 use tracing::instrument;
-use trustify_entity::organization;
+use trustify_entity::{organization, organization_alias, organization_rule};
 
 use crate::graph::{Graph, error::Error};
 
@@ -26,6 +37,76 @@ impl From<()> for OrganizationInformation {
     }
 }
 
+/// Create the key used for matching organization aliases.
+///
+/// The key only consists of the lowercase alphanumeric characters of the name, so that
+/// `Red Hat` and `redhat` end up with the same key. Names of organizations are matched by the
+/// key the database computes instead, see [`Graph::get_organization_by_name`].
+pub fn name_key(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Incremented whenever organization rules or aliases change, invalidating the rules cached by
+/// all graphs.
+static NAME_RULES_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// The time after which cached rules get reloaded anyway, picking up changes made by other
+/// instances.
+const NAME_RULES_TTL: Duration = Duration::from_secs(60);
+
+/// Invalidate the cached name rules of all graphs, after the rules or aliases got changed.
+pub fn invalidate_name_rules() {
+    NAME_RULES_GENERATION.fetch_add(1, Ordering::AcqRel);
+}
+
+/// Name rules, as cached by a graph
+#[derive(Clone, Debug)]
+pub struct CachedNameRules {
+    generation: u64,
+    loaded: Instant,
+    rules: Arc<NameRules>,
+}
+
+/// The rules normalizing organization names during ingestion.
+#[derive(Clone, Debug, Default)]
+pub struct NameRules(Vec<(Regex, String)>);
+
+impl NameRules {
+    /// Compile the rules, in the order they are provided.
+    pub fn compile<'a>(
+        rules: impl IntoIterator<Item = &'a organization_rule::Model>,
+    ) -> Result<Self, regex::Error> {
+        Ok(Self(
+            rules
+                .into_iter()
+                .map(|rule| Ok((Regex::new(&rule.pattern)?, rule.replacement.clone())))
+                .collect::<Result<_, regex::Error>>()?,
+        ))
+    }
+
+    /// Apply all rules to a name.
+    ///
+    /// If the rules would remove the whole name, the original (trimmed) name is returned.
+    pub fn apply(&self, name: &str) -> String {
+        let mut result = name.trim().to_string();
+        for (pattern, replacement) in &self.0 {
+            result = pattern
+                .replace_all(&result, replacement.as_str())
+                .trim()
+                .to_string();
+        }
+
+        if result.is_empty() {
+            name.trim().to_string()
+        } else {
+            result
+        }
+    }
+}
+
 pub struct OrganizationContext<'g> {
     graph: &'g Graph,
     pub organization: organization::Model,
@@ -54,19 +135,116 @@ impl Graph {
             .collect())
     }
 
+    /// Look up an organization by its name.
+    ///
+    /// The name may either be the exact name of the organization, one of its aliases, or
+    /// a name which only differs in case and punctuation.
     #[instrument(skip(self, connection), err(level=tracing::Level::INFO))]
     pub async fn get_organization_by_name<C: ConnectionTrait>(
         &self,
         name: impl Into<String> + Debug,
         connection: &C,
     ) -> Result<Option<OrganizationContext>, Error> {
+        let name = name.into();
+
+        if let Some(found) = organization::Entity::find()
+            .filter(organization::Column::Name.eq(name.as_str()))
+            .one(connection)
+            .await?
+        {
+            return Ok(Some(OrganizationContext::new(self, found)));
+        }
+
+        let key = name_key(&name);
+        if key.is_empty() {
+            return Ok(None);
+        }
+
+        if let Some(alias) = organization_alias::Entity::find_by_id(&key)
+            .one(connection)
+            .await?
+        {
+            return Ok(organization::Entity::find_by_id(alias.organization_id)
+                .one(connection)
+                .await?
+                .map(|organization| OrganizationContext::new(self, organization)));
+        }
+
+        // the stored key is computed by the same function, see the migration adding it
         Ok(organization::Entity::find()
-            .filter(organization::Column::Name.eq(name.into()))
+            .filter(Expr::cust_with_values(
+                r#""organization"."name_key" = organization_name_key($1) AND "organization"."name_key" <> ''"#,
+                [name],
+            ))
+            .order_by_asc(organization::Column::Name)
             .one(connection)
             .await?
             .map(|organization| OrganizationContext::new(self, organization)))
     }
 
+    /// Load the rules normalizing organization names.
+    ///
+    /// Rules which fail to compile are skipped.
+    #[instrument(skip(self, connection), err(level=tracing::Level::INFO))]
+    pub async fn get_organization_name_rules<C: ConnectionTrait>(
+        &self,
+        connection: &C,
+    ) -> Result<NameRules, Error> {
+        let rules = organization_rule::Entity::find()
+            .order_by_asc(organization_rule::Column::Position)
+            .all(connection)
+            .await?
+            .into_iter()
+            .filter(|rule| match Regex::new(&rule.pattern) {
+                Ok(_) => true,
+                Err(err) => {
+                    log::warn!("Skipping invalid organization rule {}: {err}", rule.id);
+                    false
+                }
+            })
+            .collect::<Vec<_>>();
+
+        // all remaining rules are known to compile
+        Ok(NameRules::compile(&rules).unwrap_or_default())
+    }
+
+    /// The rules normalizing organization names, loading them only if the cached rules got
+    /// invalidated or expired.
+    async fn name_rules<C: ConnectionTrait>(
+        &self,
+        connection: &C,
+    ) -> Result<Arc<NameRules>, Error> {
+        // read before loading, so that an invalidation while loading triggers another load
+        let generation = NAME_RULES_GENERATION.load(Ordering::Acquire);
+
+        let fresh = |cached: &CachedNameRules| {
+            cached.generation >= generation && cached.loaded.elapsed() < NAME_RULES_TTL
+        };
+
+        if let Some(cached) = &self.db_context.lock().await.name_rules {
+            if fresh(cached) {
+                return Ok(cached.rules.clone());
+            }
+        }
+
+        // don't hold the lock while loading, as that would block all other users of the context
+        let rules = Arc::new(self.get_organization_name_rules(connection).await?);
+
+        let mut context = self.db_context.lock().await;
+        match &context.name_rules {
+            // someone else loaded them in the meantime
+            Some(cached) if fresh(cached) => Ok(cached.rules.clone()),
+            _ => {
+                context.name_rules = Some(CachedNameRules {
+                    generation,
+                    loaded: Instant::now(),
+                    rules: rules.clone(),
+                });
+                Ok(rules)
+            }
+        }
+    }
+
     #[instrument(skip(self, connection), err(level=tracing::Level::INFO))]
     pub async fn ingest_organization<C: ConnectionTrait>(
         &self,
@@ -74,9 +252,12 @@ impl Graph {
         information: impl Into<OrganizationInformation> + Debug,
         connection: &C,
     ) -> Result<OrganizationContext, Error> {
-        let name = name.into();
+        let original = name.into();
         let information = information.into();
 
+        let rules = self.name_rules(connection).await?;
+        let name = rules.apply(&original);
+
         if let Some(found) = self.get_organization_by_name(&name, connection).await? {
             if information.has_data() {
                 let mut entity = organization::ActiveModel::from(found.organization);
@@ -90,15 +271,70 @@ impl Graph {
         } else {
             let entity = organization::ActiveModel {
                 id: Default::default(),
-                name: Set(name),
+                name: Set(name.clone()),
                 cpe_key: Set(information.cpe_key),
                 website: Set(information.website),
             };
+            let organization = entity.insert(connection).await?;
 
-            Ok(OrganizationContext::new(
-                self,
-                entity.insert(connection).await?,
-            ))
+            // remember the name we received, so that it can be found when searching for it
+            let key = name_key(&original);
+            if !key.is_empty() && key != name_key(&name) {
+                organization_alias::Entity::insert(organization_alias::ActiveModel {
+                    key: Set(key),
+                    alias: Set(original.trim().to_string()),
+                    organization_id: Set(organization.id),
+                })
+                .on_conflict_do_nothing()
+                .exec(connection)
+                .await?;
+            }
+
+            Ok(OrganizationContext::new(self, organization))
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn rule(pattern: &str, replacement: &str) -> organization_rule::Model {
+        organization_rule::Model {
+            id: Default::default(),
+            position: 0,
+            pattern: pattern.into(),
+            replacement: replacement.into(),
+            description: None,
+        }
+    }
+
+    #[test]
+    fn keys() {
+        assert_eq!(name_key("Red Hat"), "redhat");
+        assert_eq!(name_key("redhat"), "redhat");
+        assert_eq!(name_key(" Red-Hat, "), "redhat");
+        assert_eq!(name_key("---"), "");
+    }
+
+    #[test]
+    fn rules() {
+        let rules = NameRules::compile(&[
+            rule(r"(?i)[,\s]+(inc|llc|ltd)\.?$", ""),
+            rule(r"^redhat$", "Red Hat"),
+        ])
+        .expect("must compile");
+
+        assert_eq!(rules.apply("Red Hat, Inc."), "Red Hat");
+        assert_eq!(rules.apply("Red Hat Inc"), "Red Hat");
+        assert_eq!(rules.apply("redhat"), "Red Hat");
+        assert_eq!(rules.apply(" Acme "), "Acme");
+        assert_eq!(rules.apply("Incorporated"), "Incorporated");
+    }
+
+    #[test]
+    fn empty_result() {
+        let rules = NameRules::compile(&[rule(".*", "")]).expect("must compile");
+        assert_eq!(rules.apply(" Acme "), "Acme");
+    }
+}
@@ -313,7 +313,7 @@ impl<'g> PackageContext<'g> {
     ) -> Result<PaginatedResults<PackageVersionContext>, Error> {
         let limiter = entity::versioned_purl::Entity::find()
             .filter(entity::versioned_purl::Column::BasePurlId.eq(self.base_purl.id))
-            .limiting(connection, paginated.limit, paginated.offset);
+            .limiting(connection, &paginated);
 
         Ok(PaginatedResults {
             total: limiter.total().await?,
@@ -323,6 +323,7 @@ impl<'g> PackageContext<'g> {
                 .drain(0..)
                 .map(|each| PackageVersionContext::new(self, each))
                 .collect(),
+            next: None,
         })
     }
 }
@@ -454,12 +455,13 @@ mod tests {
                 Paginated {
                     offset: 50,
                     limit: 50,
+                    ..Default::default()
                 },
                 &ctx.db,
             )
             .await?;
 
-        assert_eq!(TOTAL_ITEMS, paginated.total);
+        assert_eq!(Some(TOTAL_ITEMS), paginated.total);
         assert_eq!(50, paginated.items.len());
 
         let _next_paginated = pkg
@@ -467,12 +469,13 @@ mod tests {
                 Paginated {
                     offset: 100,
                     limit: 50,
+                    ..Default::default()
                 },
                 &ctx.db,
             )
             .await?;
 
-        assert_eq!(TOTAL_ITEMS, paginated.total);
+        assert_eq!(Some(TOTAL_ITEMS), paginated.total);
         assert_eq!(50, paginated.items.len());
 
         Ok(())
@@ -0,0 +1,26 @@
+use super::SbomContext;
+use crate::graph::error::Error;
+use sea_orm::{ConnectionTrait, DbBackend, Statement};
+use tracing::instrument;
+
+impl SbomContext {
+    /// Learn CPE to PURL mappings from packages of this SBOM which carry both identifiers.
+    ///
+    /// Existing mappings (learned or curated) are kept as they are. Returns the number of newly
+    /// learned mappings.
+    #[instrument(skip(self, connection), err(level=tracing::Level::INFO))]
+    pub async fn learn_cpe_purl_mappings<C: ConnectionTrait>(
+        &self,
+        connection: &C,
+    ) -> Result<u64, Error> {
+        let result = connection
+            .execute(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                include_str!("learn_cpe_purl_mappings.sql"),
+                [self.sbom.sbom_id.into()],
+            ))
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
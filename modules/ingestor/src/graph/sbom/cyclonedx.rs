@@ -200,6 +200,10 @@ impl SbomContext {
 
         creator.create(connection, &mut processors).await?;
 
+        // correlate CPEs and PURLs
+
+        self.learn_cpe_purl_mappings(connection).await?;
+
         // done
 
         Ok(())
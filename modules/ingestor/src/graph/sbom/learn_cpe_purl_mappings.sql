@@ -0,0 +1,23 @@
+INSERT INTO cpe_purl_mapping (cpe_vendor, cpe_product, base_purl_id, purl_type, purl_namespace, purl_name, source)
+SELECT DISTINCT
+    cpe.vendor,
+    cpe.product,
+    base_purl.id,
+    base_purl.type,
+    base_purl.namespace,
+    base_purl.name,
+    'learned'
+FROM sbom_package_cpe_ref
+    JOIN cpe ON cpe.id = sbom_package_cpe_ref.cpe_id
+    JOIN sbom_package_purl_ref
+        ON sbom_package_purl_ref.sbom_id = sbom_package_cpe_ref.sbom_id
+        AND sbom_package_purl_ref.node_id = sbom_package_cpe_ref.node_id
+    JOIN qualified_purl ON qualified_purl.id = sbom_package_purl_ref.qualified_purl_id
+    JOIN versioned_purl ON versioned_purl.id = qualified_purl.versioned_purl_id
+    JOIN base_purl ON base_purl.id = versioned_purl.base_purl_id
+WHERE
+    sbom_package_cpe_ref.sbom_id = $1
+    -- wildcards don't identify anything
+    AND cpe.vendor IS NOT NULL AND cpe.vendor <> '*'
+    AND cpe.product IS NOT NULL AND cpe.product <> '*'
+ON CONFLICT DO NOTHING
@@ -6,6 +6,7 @@ pub mod processor;
 pub mod spdx;
 
 mod common;
+mod correlation;
 
 pub use common::*;
 
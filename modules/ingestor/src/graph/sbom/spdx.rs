@@ -278,6 +278,10 @@ impl SbomContext {
         files.create(db).await?;
         relationships.create(db).await?;
 
+        // correlate CPEs and PURLs
+
+        self.learn_cpe_purl_mappings(db).await?;
+
         // done
 
         Ok(())
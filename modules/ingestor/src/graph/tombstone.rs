@@ -0,0 +1,35 @@
+//! Tombstones of deleted documents
+//!
+//! Deleting an SBOM or advisory records a tombstone for its source document, so that incremental
+//! snapshots can replay the deletion in another instance.
+
+use sea_orm::{ConnectionTrait, DbBackend, DbErr, Statement};
+use trustify_entity::ingestion_history::DocumentKind;
+use uuid::Uuid;
+
+/// Record the deletion of a document, after its SBOM or advisory got deleted.
+///
+/// A source document may be the source of multiple advisories, like an NVD feed. The tombstone is
+/// only recorded once no document refers to the source document anymore.
+pub async fn record_tombstone<C: ConnectionTrait>(
+    kind: DocumentKind,
+    source_document_id: Uuid,
+    connection: &C,
+) -> Result<(), DbErr> {
+    connection
+        .execute(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            r#"
+INSERT INTO document_tombstone (sha256, kind, deleted)
+SELECT sha256, $2, now() FROM source_document
+WHERE id = $1
+    AND NOT EXISTS (SELECT 1 FROM advisory WHERE source_document_id = $1)
+    AND NOT EXISTS (SELECT 1 FROM sbom WHERE source_document_id = $1)
+ON CONFLICT (sha256) DO UPDATE SET kind = EXCLUDED.kind, deleted = EXCLUDED.deleted
+"#,
+            [source_document_id.into(), kind.to_string().into()],
+        ))
+        .await?;
+
+    Ok(())
+}
@@ -47,6 +47,7 @@ impl<'a> From<Information<'a>> for AdvisoryInformation {
             )
             .ok(),
             withdrawn: None,
+            format: Some("csaf".to_string()),
         }
     }
 }
@@ -73,6 +73,7 @@ impl<'g> CveLoader<'g> {
             published: information.published,
             modified: information.modified,
             withdrawn: information.withdrawn,
+            format: Some("cve".to_string()),
         };
 
         let vulnerability = self
@@ -1,3 +1,4 @@
 pub mod csaf;
 pub mod cve;
+pub mod nvd;
 pub mod osv;
@@ -0,0 +1,531 @@
+use crate::{
+    graph::{
+        Graph,
+        advisory::{
+            AdvisoryInformation, AdvisoryVulnerabilityInformation,
+            advisory_vulnerability::AdvisoryVulnerabilityContext,
+            product_status::{ProductStatus, ProductVersionRange},
+            version::{Version, VersionInfo, VersionSpec},
+        },
+        product::ProductInformation,
+        vulnerability::VulnerabilityInformation,
+    },
+    model::IngestResult,
+    service::{
+        Error, Warnings,
+        advisory::nvd::{CpeMatch, Node, NvdCve, NvdFeed, Operator, parse_cpe23},
+    },
+};
+use anyhow::anyhow;
+use sea_orm::{ConnectionTrait, EntityTrait, Set, TransactionTrait};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Debug,
+    str::FromStr,
+};
+use tracing::instrument;
+use trustify_common::{
+    cpe::{Component, Cpe},
+    hashing::Digests,
+    id::Id,
+};
+use trustify_cvss::cvss3::Cvss3Base;
+use trustify_entity::{
+    labels::Labels, product_status, product_status_platform, product_version_range, version_range,
+    version_scheme::VersionScheme,
+};
+
+/// Loader capable of parsing an NVD CVE API 2.0 JSON file
+/// and manipulating the Graph to integrate it into
+/// the knowledge base.
+///
+/// A single document may contain more than one CVE. Each of them is ingested as an advisory of
+/// its own, sharing the source document. The advisory uses an NVD specific identifier, `NVD-`
+/// followed by the CVE ID. This way, ingesting an updated feed deprecates the advisories of the
+/// previous one, but it doesn't deprecate, or get deprecated by, advisories of other sources for
+/// the same CVE. The result of the ingestion refers to the advisory of the first CVE.
+///
+/// The CPE match criteria of the configurations are stored as product statuses, with the
+/// version range of the criteria. Only criteria which are flagged as vulnerable are stored.
+/// Criteria which are only vulnerable in combination with others (`AND` nodes or
+/// configurations) record the non-vulnerable criteria of the combination as the platforms
+/// of the status, instead of as affected products. Negated nodes are skipped.
+pub struct NvdLoader<'g> {
+    graph: &'g Graph,
+}
+
+const DESCRIPTION_EN: &str = "en";
+
+const ISSUER: &str = "NVD";
+
+/// The format of the advisories, matching their product statuses by CPE version ranges
+const FORMAT: &str = "nvd";
+
+impl<'g> NvdLoader<'g> {
+    pub fn new(graph: &'g Graph) -> Self {
+        Self { graph }
+    }
+
+    #[instrument(skip(self, feed), err(level=tracing::Level::INFO))]
+    pub async fn load(
+        &self,
+        labels: impl Into<Labels> + Debug,
+        feed: NvdFeed,
+        digests: &Digests,
+    ) -> Result<IngestResult, Error> {
+        let warnings = Warnings::new();
+
+        let labels = labels.into().add("type", "nvd");
+
+        let tx = self.graph.db.begin().await?;
+
+        let mut first = None;
+
+        for item in &feed.vulnerabilities {
+            let cve = &item.cve;
+            let id = format!("NVD-{}", cve.id);
+
+            let advisory = self
+                .graph
+                .ingest_advisory_of_document(
+                    &id,
+                    labels.clone(),
+                    digests,
+                    AdvisoryInformation {
+                        id: id.clone(),
+                        title: english_description(cve).map(ToString::to_string),
+                        version: None,
+                        issuer: Some(ISSUER.to_string()),
+                        published: cve.published(),
+                        modified: cve.modified(),
+                        withdrawn: cve.withdrawn(),
+                        format: Some(FORMAT.to_string()),
+                    },
+                    &tx,
+                )
+                .await?;
+
+            let vulnerability = self
+                .graph
+                .ingest_vulnerability(
+                    &cve.id,
+                    VulnerabilityInformation {
+                        title: None,
+                        reserved: None,
+                        published: cve.published(),
+                        modified: cve.modified(),
+                        withdrawn: cve.withdrawn(),
+                        cwes: cve.cwes(),
+                    },
+                    &tx,
+                )
+                .await?;
+
+            let advisory_vuln = advisory
+                .link_to_vulnerability(
+                    &cve.id,
+                    Some(AdvisoryVulnerabilityInformation {
+                        title: None,
+                        summary: None,
+                        description: english_description(cve).map(ToString::to_string),
+                        reserved_date: None,
+                        discovery_date: None,
+                        release_date: cve.published(),
+                        cwes: cve.cwes(),
+                    }),
+                    &tx,
+                )
+                .await?;
+
+            for metric in cve
+                .metrics
+                .cvss_metric_v31
+                .iter()
+                .chain(&cve.metrics.cvss_metric_v30)
+            {
+                match Cvss3Base::from_str(&metric.cvss_data.vector_string) {
+                    Ok(cvss3) => {
+                        advisory_vuln.ingest_cvss3_score(cvss3, &tx).await?;
+                    }
+                    Err(err) => {
+                        warnings.add(format!("Unable to parse CVSS3: {err}"));
+                    }
+                }
+            }
+
+            self.ingest_configurations(cve, &advisory_vuln, &warnings, &tx)
+                .await?;
+
+            let entries = cve
+                .descriptions
+                .iter()
+                .map(|description| (description.lang.as_str(), description.value.as_str()))
+                .collect::<Vec<_>>();
+
+            vulnerability
+                .drop_descriptions_for_advisory(advisory.advisory.id, &tx)
+                .await?;
+            vulnerability
+                .add_descriptions(advisory.advisory.id, entries, &tx)
+                .await?;
+
+            first.get_or_insert((advisory.advisory.id, id));
+        }
+
+        tx.commit().await?;
+
+        let Some((uuid, id)) = first else {
+            return Err(Error::Generic(anyhow!("NVD document contains no CVE")));
+        };
+
+        Ok(IngestResult {
+            id: Id::Uuid(uuid),
+            document_id: Some(id),
+            warnings: warnings.into(),
+        })
+    }
+
+    /// Store the vulnerable CPE match criteria of all configurations as product statuses
+    async fn ingest_configurations<C: ConnectionTrait>(
+        &self,
+        cve: &NvdCve,
+        advisory_vuln: &AdvisoryVulnerabilityContext<'_>,
+        warnings: &Warnings,
+        connection: &C,
+    ) -> Result<(), Error> {
+        let status_id = self
+            .graph
+            .db_context
+            .lock()
+            .await
+            .get_status_id("affected", connection)
+            .await?;
+
+        for (cpe_match, platforms) in affected(cve) {
+            let Some(cpe) = parse_cpe23(&cpe_match.criteria) else {
+                warnings.add(format!(
+                    "Unable to parse CPE match criteria: {}",
+                    cpe_match.criteria
+                ));
+                continue;
+            };
+
+            let product = self
+                .graph
+                .ingest_product(
+                    cpe.product().as_ref(),
+                    ProductInformation {
+                        vendor: Some(cpe.vendor().as_ref().to_string()),
+                        cpe: Some(cpe.clone()),
+                    },
+                    connection,
+                )
+                .await?;
+
+            let range = ProductVersionRange {
+                cpe: Some(cpe.clone()),
+                product_id: product.product.id,
+                info: version_info(cpe_match, &cpe),
+            };
+            let (version_range, product_version_range) = range.clone().into_active_model();
+
+            version_range::Entity::insert(version_range)
+                .on_conflict_do_nothing()
+                .exec(connection)
+                .await?;
+            product_version_range::Entity::insert(product_version_range)
+                .on_conflict_do_nothing()
+                .exec(connection)
+                .await?;
+
+            self.graph.ingest_cpe22(cpe.clone(), connection).await?;
+
+            let advisory_id = advisory_vuln.advisory_vulnerability.advisory_id;
+            let vulnerability_id = advisory_vuln
+                .advisory_vulnerability
+                .vulnerability_id
+                .clone();
+
+            let product_status = ProductStatus {
+                cpe: Some(cpe),
+                package: None,
+                status: status_id,
+                product_version_range_id: range.uuid(),
+            };
+            let product_status_id = product_status.uuid(advisory_id, vulnerability_id.clone());
+
+            product_status::Entity::insert(
+                product_status.into_active_model(advisory_id, vulnerability_id),
+            )
+            .on_conflict_do_nothing()
+            .exec(connection)
+            .await?;
+
+            // the platforms are only recorded as CPEs, they are not affected themselves
+
+            for platform in platforms.into_iter().flatten() {
+                let Some(cpe) = parse_cpe23(platform) else {
+                    warnings.add(format!("Unable to parse CPE match criteria: {platform}"));
+                    continue;
+                };
+
+                self.graph.ingest_cpe22(cpe.clone(), connection).await?;
+
+                product_status_platform::Entity::insert(product_status_platform::ActiveModel {
+                    product_status_id: Set(product_status_id),
+                    cpe_id: Set(cpe.uuid()),
+                })
+                .on_conflict_do_nothing()
+                .exec(connection)
+                .await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Collect the vulnerable CPE match criteria of all configurations, along with their platforms.
+///
+/// A criteria which is vulnerable on its own has no platforms (`None`). A criteria which is only
+/// vulnerable in combination with others carries the non-vulnerable criteria it is combined with.
+/// If a criteria is part of several combinations, the platforms are merged.
+fn affected(cve: &NvdCve) -> BTreeMap<&CpeMatch, Option<BTreeSet<&str>>> {
+    let mut result = BTreeMap::<_, Option<BTreeSet<_>>>::new();
+
+    for configuration in cve.configurations.iter().filter(|c| !c.negate) {
+        let nodes = configuration
+            .nodes
+            .iter()
+            .filter(|n| !n.negate)
+            .collect::<Vec<_>>();
+
+        for (i, node) in nodes.iter().enumerate() {
+            let mut platforms = BTreeSet::new();
+
+            if node.operator == Operator::And {
+                platforms.extend(platforms_of(node));
+            }
+
+            if configuration.operator == Operator::And {
+                for (_, other) in nodes.iter().enumerate().filter(|(j, _)| *j != i) {
+                    platforms.extend(platforms_of(other));
+                }
+            }
+
+            for cpe_match in node.cpe_match.iter().filter(|m| m.vulnerable) {
+                let entry = result
+                    .entry(cpe_match)
+                    .or_insert_with(|| Some(BTreeSet::new()));
+
+                match entry {
+                    // already vulnerable on its own
+                    None => {}
+                    Some(_) if platforms.is_empty() => *entry = None,
+                    Some(existing) => existing.extend(platforms.iter().copied()),
+                }
+            }
+        }
+    }
+
+    result
+}
+
+/// The non-vulnerable criteria of a node, describing a platform
+fn platforms_of(node: &Node) -> impl Iterator<Item = &str> {
+    node.cpe_match
+        .iter()
+        .filter(|m| !m.vulnerable)
+        .map(|m| m.criteria.as_str())
+}
+
+fn english_description(cve: &NvdCve) -> Option<&str> {
+    cve.descriptions
+        .iter()
+        .find(|description| description.lang == DESCRIPTION_EN)
+        .map(|description| description.value.as_str())
+}
+
+/// Convert the version constraints of a CPE match into a version range.
+///
+/// NVD doesn't define a version scheme, so we use the RPM version comparison, which
+/// works reasonably well with arbitrary, dot separated versions. It is used for exact
+/// versions too, so that all versions of a CPE get compared the same way. The generic
+/// scheme only supports checking for equality, not ranges.
+fn version_info(cpe_match: &CpeMatch, cpe: &Cpe) -> VersionInfo {
+    let start = match (
+        &cpe_match.version_start_including,
+        &cpe_match.version_start_excluding,
+    ) {
+        (Some(version), _) => Version::Inclusive(version.clone()),
+        (None, Some(version)) => Version::Exclusive(version.clone()),
+        (None, None) => Version::Unbounded,
+    };
+    let end = match (
+        &cpe_match.version_end_including,
+        &cpe_match.version_end_excluding,
+    ) {
+        (Some(version), _) => Version::Inclusive(version.clone()),
+        (None, Some(version)) => Version::Exclusive(version.clone()),
+        (None, None) => Version::Unbounded,
+    };
+
+    match (start, end, cpe.version()) {
+        // no range, but the criteria names an exact version
+        (Version::Unbounded, Version::Unbounded, Component::Value(version)) => VersionInfo {
+            scheme: VersionScheme::Rpm,
+            spec: VersionSpec::Exact(version),
+        },
+        (start, end, _) => VersionInfo {
+            scheme: VersionScheme::Rpm,
+            spec: VersionSpec::Range(start, end),
+        },
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use sea_orm::{ColumnTrait, QueryFilter};
+    use test_context::test_context;
+    use test_log::test;
+    use trustify_entity::{advisory, cpe, product};
+    use trustify_test_context::{TrustifyContext, document};
+
+    #[test_context(TrustifyContext)]
+    #[test(tokio::test)]
+    async fn nvd_loader(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+        let graph = Graph::new(ctx.db.clone());
+
+        let (feed, digests): (NvdFeed, _) = document("nvd/CVE-2021-44228.json").await?;
+
+        let loader = NvdLoader::new(&graph);
+        let result = loader
+            .load(("file", "CVE-2021-44228.json"), feed, &digests)
+            .await?;
+
+        assert_eq!(result.document_id, Some("NVD-CVE-2021-44228".to_string()));
+
+        let vulnerability = graph.get_vulnerability("CVE-2021-44228", &ctx.db).await?;
+        assert!(vulnerability.is_some());
+
+        // only the vulnerable criteria get a status
+        let statuses = product_status::Entity::find()
+            .filter(product_status::Column::VulnerabilityId.eq("CVE-2021-44228"))
+            .all(&ctx.db)
+            .await?;
+        assert_eq!(statuses.len(), 4);
+
+        // beta9 is also vulnerable on its own, so the AND configuration adds no platform
+        let cpes = cpe::Entity::find()
+            .filter(cpe::Column::Vendor.eq("cisco"))
+            .all(&ctx.db)
+            .await?;
+        assert!(cpes.is_empty());
+        assert!(
+            product_status_platform::Entity::find()
+                .all(&ctx.db)
+                .await?
+                .is_empty()
+        );
+
+        let advisory_vuln = graph
+            .get_advisory_by_id(
+                match result.id {
+                    Id::Uuid(id) => id,
+                    _ => unreachable!(),
+                },
+                &ctx.db,
+            )
+            .await?
+            .expect("advisory must exist")
+            .get_vulnerability("CVE-2021-44228", &ctx.db)
+            .await?
+            .expect("vulnerability must be linked");
+        assert_eq!(advisory_vuln.cvss3_scores(&ctx.db).await?.len(), 1);
+
+        Ok(())
+    }
+
+    #[test_context(TrustifyContext)]
+    #[test(tokio::test)]
+    async fn nvd_loader_and_configuration(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+        let graph = Graph::new(ctx.db.clone());
+
+        let (feed, digests): (NvdFeed, _) = document("nvd/CVE-2022-22965.json").await?;
+
+        let loader = NvdLoader::new(&graph);
+        loader
+            .load(("file", "CVE-2022-22965.json"), feed, &digests)
+            .await?;
+
+        // only the vulnerable criteria get a status
+        let statuses = product_status::Entity::find()
+            .filter(product_status::Column::VulnerabilityId.eq("CVE-2022-22965"))
+            .all(&ctx.db)
+            .await?;
+        assert_eq!(statuses.len(), 1);
+
+        // the platform is no affected product
+        let products = product::Entity::find()
+            .filter(product::Column::Name.eq("jdk"))
+            .all(&ctx.db)
+            .await?;
+        assert!(products.is_empty());
+
+        // but it is recorded as platform of the status
+        let platforms = product_status_platform::Entity::find()
+            .find_also_related(cpe::Entity)
+            .all(&ctx.db)
+            .await?;
+        assert_eq!(platforms.len(), 1);
+
+        let (platform, cpe) = &platforms[0];
+        assert_eq!(platform.product_status_id, statuses[0].id);
+        let cpe = cpe.as_ref().expect("platform CPE must exist");
+        assert_eq!(cpe.vendor.as_deref(), Some("oracle"));
+        assert_eq!(cpe.product.as_deref(), Some("jdk"));
+
+        Ok(())
+    }
+
+    #[test_context(TrustifyContext)]
+    #[test(tokio::test)]
+    async fn nvd_loader_feed(ctx: &TrustifyContext) -> Result<(), anyhow::Error> {
+        let graph = Graph::new(ctx.db.clone());
+
+        let (mut feed, _): (NvdFeed, _) = document("nvd/CVE-2021-44228.json").await?;
+        let (other, _): (NvdFeed, _) = document("nvd/CVE-2022-22965.json").await?;
+        feed.vulnerabilities.extend(other.vulnerabilities);
+
+        let loader = NvdLoader::new(&graph);
+
+        // the same feed, ingested twice, with an update in between
+        for content in ["feed-1", "feed-1", "feed-2"] {
+            let result = loader
+                .load(
+                    ("file", "feed.json"),
+                    feed.clone(),
+                    &Digests::digest(content),
+                )
+                .await?;
+            assert_eq!(result.document_id, Some("NVD-CVE-2021-44228".to_string()));
+        }
+
+        let advisories = advisory::Entity::find().all(&ctx.db).await?;
+        assert_eq!(advisories.len(), 4);
+
+        // each CVE has its own advisory, the one of the updated feed deprecating the previous one
+        for identifier in ["NVD-CVE-2021-44228", "NVD-CVE-2022-22965"] {
+            let mut advisories = advisories
+                .iter()
+                .filter(|advisory| advisory.identifier == identifier)
+                .map(|advisory| advisory.deprecated)
+                .collect::<Vec<_>>();
+            advisories.sort();
+            assert_eq!(advisories, vec![false, true]);
+        }
+
+        Ok(())
+    }
+}
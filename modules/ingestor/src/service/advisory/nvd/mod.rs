@@ -0,0 +1,244 @@
+//! Support for the NVD CVE API 2.0 JSON format.
+//!
+//! See: <https://nvd.nist.gov/developers/vulnerabilities>
+
+pub mod loader;
+
+use serde::Deserialize;
+use std::str::FromStr;
+use time::{OffsetDateTime, PrimitiveDateTime, format_description::well_known::Iso8601};
+use trustify_common::cpe::Cpe;
+
+/// A response of the NVD CVE API, or a feed file in the same format
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NvdFeed {
+    pub format: String,
+    pub version: String,
+    #[serde(default)]
+    pub timestamp: Option<String>,
+    #[serde(default)]
+    pub vulnerabilities: Vec<NvdItem>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NvdItem {
+    pub cve: NvdCve,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NvdCve {
+    pub id: String,
+    #[serde(default)]
+    pub source_identifier: Option<String>,
+    #[serde(default)]
+    pub published: Option<String>,
+    #[serde(default)]
+    pub last_modified: Option<String>,
+    #[serde(default)]
+    pub vuln_status: Option<String>,
+    #[serde(default)]
+    pub descriptions: Vec<LangString>,
+    #[serde(default)]
+    pub metrics: Metrics,
+    #[serde(default)]
+    pub weaknesses: Vec<Weakness>,
+    #[serde(default)]
+    pub configurations: Vec<Configuration>,
+}
+
+impl NvdCve {
+    pub fn published(&self) -> Option<OffsetDateTime> {
+        self.published.as_deref().and_then(timestamp)
+    }
+
+    pub fn modified(&self) -> Option<OffsetDateTime> {
+        self.last_modified.as_deref().and_then(timestamp)
+    }
+
+    /// Rejected CVEs are considered withdrawn with their last modification
+    pub fn withdrawn(&self) -> Option<OffsetDateTime> {
+        match self.vuln_status.as_deref() {
+            Some("Rejected") => self.modified(),
+            _ => None,
+        }
+    }
+
+    pub fn cwes(&self) -> Option<Vec<String>> {
+        let cwes = self
+            .weaknesses
+            .iter()
+            .flat_map(|weakness| weakness.description.iter())
+            .map(|description| description.value.clone())
+            .filter(|value| value.starts_with("CWE-"))
+            .collect::<Vec<_>>();
+
+        (!cwes.is_empty()).then_some(cwes)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LangString {
+    pub lang: String,
+    pub value: String,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Metrics {
+    #[serde(default)]
+    pub cvss_metric_v31: Vec<CvssMetric>,
+    #[serde(default)]
+    pub cvss_metric_v30: Vec<CvssMetric>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CvssMetric {
+    pub cvss_data: CvssData,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CvssData {
+    pub vector_string: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Weakness {
+    #[serde(default)]
+    pub description: Vec<LangString>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Operator {
+    #[default]
+    Or,
+    And,
+}
+
+/// A configuration, a tree of nodes of CPE match criteria
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Configuration {
+    #[serde(default)]
+    pub operator: Operator,
+    #[serde(default)]
+    pub negate: bool,
+    #[serde(default)]
+    pub nodes: Vec<Node>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Node {
+    #[serde(default)]
+    pub operator: Operator,
+    #[serde(default)]
+    pub negate: bool,
+    #[serde(default)]
+    pub cpe_match: Vec<CpeMatch>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CpeMatch {
+    pub vulnerable: bool,
+    pub criteria: String,
+    #[serde(default)]
+    pub version_start_including: Option<String>,
+    #[serde(default)]
+    pub version_start_excluding: Option<String>,
+    #[serde(default)]
+    pub version_end_including: Option<String>,
+    #[serde(default)]
+    pub version_end_excluding: Option<String>,
+}
+
+/// Parse an NVD timestamp, which doesn't carry an offset, but is UTC
+fn timestamp(value: &str) -> Option<OffsetDateTime> {
+    PrimitiveDateTime::parse(value, &Iso8601::DEFAULT)
+        .ok()
+        .map(PrimitiveDateTime::assume_utc)
+}
+
+/// Parse a CPE 2.3 formatted string into a [`Cpe`].
+///
+/// Only the attributes which are part of the CPE 2.2 URI binding (part, vendor, product,
+/// version, update, edition, language) are kept.
+pub fn parse_cpe23(value: &str) -> Option<Cpe> {
+    let mut components = vec![];
+    let mut current = String::new();
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => current.extend(chars.next()),
+            ':' => components.push(std::mem::take(&mut current)),
+            c => current.push(c),
+        }
+    }
+    components.push(current);
+
+    let components = components.iter().map(String::as_str).collect::<Vec<_>>();
+    let ["cpe", "2.3", attributes @ ..] = components.as_slice() else {
+        return None;
+    };
+
+    if attributes.len() < 7 {
+        return None;
+    }
+
+    let mut uri = String::from("cpe:/");
+    for (i, attribute) in attributes[..7].iter().enumerate() {
+        if i > 0 {
+            uri.push(':');
+        }
+        match *attribute {
+            "*" => {}
+            "-" => uri.push('-'),
+            attribute => {
+                for c in attribute.chars() {
+                    match c {
+                        'a'..='z' | 'A'..='Z' | '0'..='9' | '.' | '_' | '-' => uri.push(c),
+                        c => {
+                            let mut buf = [0; 4];
+                            for b in c.encode_utf8(&mut buf).bytes() {
+                                uri.push_str(&format!("%{b:02x}"));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    Cpe::from_str(uri.trim_end_matches(':')).ok()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn cpe23() {
+        let cpe = parse_cpe23("cpe:2.3:a:apache:log4j:*:*:*:*:*:*:*:*").expect("must parse");
+        assert_eq!(cpe.vendor().as_ref(), "apache");
+        assert_eq!(cpe.product().as_ref(), "log4j");
+        assert_eq!(cpe.version().as_ref(), "*");
+
+        let cpe = parse_cpe23("cpe:2.3:a:apache:log4j:2.0:beta9:*:*:*:*:*:*").expect("must parse");
+        assert_eq!(cpe.version().as_ref(), "2.0");
+        assert_eq!(cpe.update().as_ref(), "beta9");
+
+        assert!(parse_cpe23(r"cpe:2.3:a:foo\:bar:baz:1.0:*:*:*:*:*:*:*").is_some());
+
+        assert!(parse_cpe23("cpe:/a:apache:log4j").is_none());
+        assert!(parse_cpe23("cpe:2.3:a:apache").is_none());
+    }
+}
@@ -65,6 +65,7 @@ impl<'g> OsvLoader<'g> {
             published: osv.published.map(ChronoExt::into_time),
             modified: Some(osv.modified.into_time()),
             withdrawn: osv.withdrawn.map(ChronoExt::into_time),
+            format: Some("osv".to_string()),
         };
         let advisory = self
             .graph
@@ -1,9 +1,9 @@
 //! Loader for a full dataset (archive) file
 
 use crate::{
-    graph::Graph,
+    graph::{Graph, history::Origin},
     model::IngestResult,
-    service::{Error, Format, Warnings},
+    service::{Error, Format, Warnings, record_history},
 };
 use anyhow::anyhow;
 use bytes::Bytes;
@@ -106,16 +106,32 @@ impl<'g> DatasetLoader<'g> {
                             .await
                             .map_err(|err| Error::Storage(anyhow!("{err}")))?;
 
+                        let digests = Digests::digest(&data);
+
                         // We need to box it, to work around async recursion limits
-                        let result = Box::pin({
-                            async move {
-                                format
-                                    .load(self.graph, labels, None, &Digests::digest(&data), &data)
-                                    .await
-                            }
-                        })
+                        let result = Box::pin(format.load(
+                            self.graph,
+                            labels.clone(),
+                            None,
+                            &digests,
+                            &data,
+                        ))
                         .await;
 
+                        let result = match result {
+                            Ok(result) => record_history(
+                                self.graph,
+                                format,
+                                &result,
+                                &digests,
+                                &labels,
+                                Origin::default(),
+                            )
+                            .await
+                            .map(|()| result),
+                            Err(err) => Err(err),
+                        };
+
                         match result {
                             Ok(result) => {
                                 results.insert(full_name, result);
@@ -4,7 +4,12 @@ use crate::{
     model::IngestResult,
     service::{
         Error,
-        advisory::{csaf::loader::CsafLoader, cve::loader::CveLoader, osv::loader::OsvLoader},
+        advisory::{
+            csaf::loader::CsafLoader,
+            cve::loader::CveLoader,
+            nvd::{NvdFeed, loader::NvdLoader},
+            osv::loader::OsvLoader,
+        },
         sbom::{
             clearly_defined_curation::ClearlyDefinedCurationLoader, cyclonedx::CyclonedxLoader,
             spdx::SpdxLoader,
@@ -20,14 +25,15 @@ use serde_json::Value;
 use std::io::Cursor;
 use tracing::instrument;
 use trustify_common::hashing::Digests;
-use trustify_entity::labels::Labels;
+use trustify_entity::{ingestion_history::DocumentKind, labels::Labels};
 
-#[derive(Clone, Copy, Debug, strum::EnumString)]
+#[derive(Clone, Copy, Debug, strum::Display, strum::EnumString)]
 #[strum(serialize_all = "camelCase")]
 pub enum Format {
     OSV,
     CSAF,
     CVE,
+    NVD,
     SPDX,
     CycloneDX,
     ClearlyDefinedCuration,
@@ -68,6 +74,12 @@ impl Format {
                 let cve: Cve = serde_json::from_slice(buffer)?;
                 loader.load(labels, cve, digests).await
             }
+            Format::NVD => {
+                // issuer is always the NVD
+                let loader = NvdLoader::new(graph);
+                let feed: NvdFeed = serde_json::from_slice(buffer)?;
+                loader.load(labels, feed, digests).await
+            }
             Format::SPDX => {
                 let loader = SpdxLoader::new(graph);
                 let v: Value = serde_json::from_slice(buffer)?;
@@ -97,6 +109,29 @@ impl Format {
         }
     }
 
+    /// The kind of document loading this format creates, if it is tracked in the ingestion history.
+    pub fn document_kind(&self) -> Option<DocumentKind> {
+        match self {
+            Self::OSV | Self::CSAF | Self::CVE | Self::NVD => Some(DocumentKind::Advisory),
+            Self::SPDX | Self::CycloneDX | Self::ClearlyDefined | Self::ClearlyDefinedCuration => {
+                Some(DocumentKind::Sbom)
+            }
+            _ => None,
+        }
+    }
+
+    /// Detect the format of a stored document of a kind, so that it can be loaded the same way
+    /// again
+    pub fn detect(kind: DocumentKind, bytes: &[u8]) -> Option<Self> {
+        match kind {
+            DocumentKind::Advisory => Self::advisory_from_bytes(bytes).ok(),
+            // ClearlyDefined definitions can't be detected, they are only ingested explicitly
+            DocumentKind::Sbom => {
+                Some(Self::sbom_from_bytes(bytes).unwrap_or(Self::ClearlyDefined))
+            }
+        }
+    }
+
     #[instrument(skip_all, ret)]
     pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
         match Self::advisory_from_bytes(bytes) {
@@ -115,13 +150,16 @@ impl Format {
     pub fn advisory_from_bytes(bytes: &[u8]) -> Result<Self, Error> {
         if Self::is_csaf(bytes)? {
             Ok(Format::CSAF)
+        } else if Self::is_nvd(bytes)? {
+            Ok(Format::NVD)
         } else if Self::is_cve(bytes)? {
             Ok(Format::CVE)
         } else if Self::is_osv(bytes)? {
             Ok(Format::OSV)
         } else {
             Err(Error::UnsupportedFormat(
-                "Unable to detect advisory format; only CSAF, CVE, and OSV are supported".into(),
+                "Unable to detect advisory format; only CSAF, CVE, NVD, and OSV are supported"
+                    .into(),
             ))
         }
     }
@@ -158,6 +196,13 @@ impl Format {
         }
     }
 
+    pub fn is_nvd(bytes: &[u8]) -> Result<bool, Error> {
+        match masked(depth(1).and(key("format")), bytes) {
+            Ok(Some(x)) if x == "NVD_CVE" => Ok(true),
+            Err(_) | Ok(_) => Ok(false),
+        }
+    }
+
     pub fn is_osv(bytes: &[u8]) -> Result<bool, Error> {
         Ok(Self::is_osv_json(bytes)? || Self::is_osv_yaml(bytes)?)
     }
@@ -282,6 +327,9 @@ mod test {
         let cve = document_bytes("mitre/CVE-2024-27088.json").await?;
         assert!(matches!(Format::from_bytes(&cve), Ok(Format::CVE)));
 
+        let nvd = document_bytes("nvd/CVE-2021-44228.json").await?;
+        assert!(matches!(Format::from_bytes(&nvd), Ok(Format::NVD)));
+
         let cyclone = document_bytes("zookeeper-3.9.2-cyclonedx.json").await?;
         assert!(matches!(
             Format::from_bytes(&cyclone),
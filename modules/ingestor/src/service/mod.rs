@@ -1,27 +1,49 @@
 pub mod advisory;
 pub mod dataset;
+pub mod reprocess;
 pub mod sbom;
+pub mod snapshot;
 pub mod weakness;
 
 mod format;
 pub use format::Format;
 
-use crate::service::dataset::{DatasetIngestResult, DatasetLoader};
-use crate::{graph::Graph, model::IngestResult};
+use crate::service::{
+    dataset::{DatasetIngestResult, DatasetLoader},
+    reprocess::Reprocessor,
+    snapshot::{Manifest, SnapshotExporter, SnapshotImportResult, SnapshotImporter},
+};
+use crate::{
+    graph::{Graph, history::Origin},
+    model::IngestResult,
+};
 use actix_web::{HttpResponse, ResponseError, body::BoxBody};
 use anyhow::anyhow;
+use futures_util::TryStreamExt;
+use hex::ToHex;
 use parking_lot::Mutex;
 use sbom_walker::report::ReportSink;
-use sea_orm::error::DbErr;
+use sea_orm::{ConnectionTrait, Statement, error::DbErr};
 use std::sync::Arc;
-use std::{fmt::Debug, time::Instant};
+use std::{
+    fmt::Debug,
+    io::{Read, Seek, Write},
+    time::Instant,
+};
+use time::OffsetDateTime;
 use tokio::task::JoinError;
 use tokio_util::io::ReaderStream;
 use tracing::instrument;
-use trustify_common::{error::ErrorInformation, id::IdError};
+use trustify_common::{
+    db::UpdateDeprecatedAdvisory,
+    error::ErrorInformation,
+    hashing::Digests,
+    id::{Id, IdError},
+};
 use trustify_entity::labels::Labels;
 use trustify_module_analysis::service::AnalysisService;
-use trustify_module_storage::service::{StorageBackend, dispatch::DispatchBackend};
+use trustify_module_storage::service::{StorageBackend, StorageKey, dispatch::DispatchBackend};
+use uuid::Uuid;
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -55,6 +77,10 @@ pub enum Error {
     Zip(#[from] zip::result::ZipError),
     #[error("payload too large")]
     PayloadTooLarge,
+    #[error("not found: {0}")]
+    NotFound(String),
+    #[error("conflict: {0}")]
+    Conflict(String),
 }
 
 impl ResponseError for Error {
@@ -135,6 +161,16 @@ impl ResponseError for Error {
                 message: self.to_string(),
                 details: None,
             }),
+            Self::NotFound(msg) => HttpResponse::NotFound().json(ErrorInformation {
+                error: "NotFound".into(),
+                message: msg.clone(),
+                details: None,
+            }),
+            Self::Conflict(msg) => HttpResponse::Conflict().json(ErrorInformation {
+                error: "Conflict".into(),
+                message: msg.clone(),
+                details: None,
+            }),
         }
     }
 }
@@ -178,8 +214,23 @@ impl IngestorService {
         format: Format,
         labels: impl Into<Labels> + Debug,
         issuer: Option<String>,
+    ) -> Result<IngestResult, Error> {
+        self.ingest_with_origin(bytes, format, labels, issuer, Origin::default())
+            .await
+    }
+
+    /// Ingest a document, recording the origin (like the user) in the ingestion history
+    #[instrument(skip(self, bytes), err)]
+    pub async fn ingest_with_origin(
+        &self,
+        bytes: &[u8],
+        format: Format,
+        labels: impl Into<Labels> + Debug,
+        issuer: Option<String>,
+        origin: Origin,
     ) -> Result<IngestResult, Error> {
         let start = Instant::now();
+        let labels = labels.into();
 
         // We want to resolve the format first to avoid storing a
         // document that we can't subsequently retrieve and load into
@@ -198,10 +249,40 @@ impl IngestorService {
             .await
             .map_err(|err| Error::Storage(anyhow!("{err}")))?;
 
+        let digests = result.digests;
+        let result = self
+            .load(fmt, labels, issuer, &digests, bytes, origin)
+            .await?;
+
+        let duration = Instant::now() - start;
+        log::debug!(
+            "Ingested: {} ({:?}): took {}",
+            result.id,
+            result.document_id,
+            humantime::Duration::from(duration),
+        );
+
+        Ok(result)
+    }
+
+    /// Load a stored document into the graph
+    ///
+    /// This records the ingestion history and loads SBOMs into the analysis graph.
+    pub(crate) async fn load(
+        &self,
+        fmt: Format,
+        labels: Labels,
+        issuer: Option<String>,
+        digests: &Digests,
+        bytes: &[u8],
+        origin: Origin,
+    ) -> Result<IngestResult, Error> {
         let result = fmt
-            .load(&self.graph, labels.into(), issuer, &result.digests, bytes)
+            .load(&self.graph, labels.clone(), issuer, digests, bytes)
             .await?;
 
+        record_history(&self.graph, fmt, &result, digests, &labels, origin).await?;
+
         if let Some(analysis) = &self.analysis {
             match fmt {
                 Format::SPDX | Format::CycloneDX => {
@@ -230,14 +311,6 @@ impl IngestorService {
             };
         }
 
-        let duration = Instant::now() - start;
-        log::debug!(
-            "Ingested: {} ({:?}): took {}",
-            result.id,
-            result.document_id,
-            humantime::Duration::from(duration),
-        );
-
         Ok(result)
     }
 
@@ -252,6 +325,125 @@ impl IngestorService {
         let loader = DatasetLoader::new(self.graph(), self.storage(), limit);
         loader.load(labels.into(), bytes).await
     }
+
+    /// Reprocess stored documents, loading them again
+    pub fn reprocessor(&self) -> Reprocessor<'_> {
+        Reprocessor::new(self)
+    }
+
+    /// Import snapshots in the background, limiting the size of their documents
+    pub fn snapshot_importer(&self, limit: usize) -> SnapshotImporter<'_> {
+        SnapshotImporter::new(self, limit)
+    }
+
+    /// Export a snapshot of all documents, or only those ingested after `since`
+    pub async fn export_snapshot<W: Write + Seek>(
+        &self,
+        since: Option<OffsetDateTime>,
+        writer: W,
+    ) -> Result<Manifest, Error> {
+        let exporter = SnapshotExporter::new(self.graph(), self.storage());
+        exporter.export(since, writer).await
+    }
+
+    /// Import a snapshot, ingesting all documents which don't exist yet
+    ///
+    /// Documents larger than `limit` are not ingested.
+    pub async fn import_snapshot<R: Read + Seek>(
+        &self,
+        reader: R,
+        limit: usize,
+    ) -> Result<SnapshotImportResult, Error> {
+        let importer = SnapshotImporter::new(self, limit);
+        importer.import(reader).await
+    }
+}
+
+/// Record the ingestion of a document in its history.
+///
+/// The document has already been loaded at this point. Failing to record its history still fails
+/// the ingestion, so that the caller can retry it, recording the history of the existing
+/// document.
+pub(crate) async fn record_history(
+    graph: &Graph,
+    format: Format,
+    result: &IngestResult,
+    digests: &Digests,
+    labels: &Labels,
+    origin: Origin,
+) -> Result<(), Error> {
+    let (Some(kind), Id::Uuid(id)) = (format.document_kind(), &result.id) else {
+        return Ok(());
+    };
+
+    graph
+        .record_ingestion(
+            kind,
+            *id,
+            digests.sha256.encode_hex(),
+            labels,
+            origin,
+            &graph.db,
+        )
+        .await
+        .inspect_err(|err| {
+            log::warn!("Failed to record ingestion history of {}: {err}", result.id)
+        })?;
+
+    Ok(())
+}
+
+/// Delete the SBOMs and advisories loaded from a source document
+///
+/// Returns the IDs of the deleted SBOMs, and the number of deleted advisories.
+pub(crate) async fn delete_documents(
+    source_document_id: Uuid,
+    connection: &impl ConnectionTrait,
+) -> Result<(Vec<Uuid>, usize), DbErr> {
+    let sbom_ids = connection
+        .query_all(Statement::from_sql_and_values(
+            connection.get_database_backend(),
+            r#"DELETE FROM sbom WHERE source_document_id=$1 RETURNING sbom_id"#,
+            [source_document_id.into()],
+        ))
+        .await?
+        .into_iter()
+        .map(|row| row.try_get_by_index::<Uuid>(0))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let identifiers = connection
+        .query_all(Statement::from_sql_and_values(
+            connection.get_database_backend(),
+            r#"DELETE FROM advisory WHERE source_document_id=$1 RETURNING identifier"#,
+            [source_document_id.into()],
+        ))
+        .await?;
+    for row in &identifiers {
+        let identifier = row.try_get_by_index::<String>(0)?;
+        UpdateDeprecatedAdvisory::execute(connection, &identifier).await?;
+    }
+
+    Ok((sbom_ids, identifiers.len()))
+}
+
+/// Retrieve the content of a stored document
+pub(crate) async fn retrieve(storage: &DispatchBackend, sha256: &str) -> Result<Vec<u8>, Error> {
+    let key = StorageKey::try_from(Id::Sha256(sha256.to_string()))
+        .map_err(|err| Error::Storage(anyhow!("{err}")))?;
+
+    let stream = storage
+        .retrieve(key)
+        .await
+        .map_err(|err| Error::Storage(anyhow!("{err}")))?
+        .ok_or_else(|| Error::Storage(anyhow!("missing document: {sha256}")))?;
+
+    stream
+        .try_fold(vec![], |mut data, chunk| async move {
+            data.extend_from_slice(&chunk);
+            Ok(data)
+        })
+        .await
+        .map_err(|err| Error::Storage(anyhow!("{err}")))
 }
 
 /// Capture warnings from the import process
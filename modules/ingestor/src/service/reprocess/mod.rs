@@ -0,0 +1,472 @@
+//! Reprocessing of stored documents, loading them again
+//!
+//! After fixing a loader, existing documents still carry the graph data of the previous version.
+//! Reprocessing loads a document from the storage again, keeping its labels and ingestion time,
+//! and only then removes the data of the previous load. The document gets new IDs in the process,
+//! and the analysis graph of a previous SBOM gets removed from the cache.
+//!
+//! While loading, the previous version of the document is hidden, by prefixing its digest with
+//! the ID of the run. Loading and removing the previous version are separate transactions, so
+//! if a run gets interrupted in between, the hidden documents are recovered when the run gets
+//! claimed again.
+
+use crate::{
+    graph::history::Origin,
+    service::{Error, Format, IngestorService, delete_documents, retrieve},
+};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, EntityTrait, IntoActiveModel, PaginatorTrait,
+    QueryFilter, QueryOrder, QuerySelect, QueryTrait, Select, Set, TransactionTrait,
+};
+use sea_query::{Expr, extension::postgres::PgExpr};
+use std::{future::Future, pin::pin, time::Duration};
+use time::OffsetDateTime;
+use tokio::time::MissedTickBehavior;
+use tracing::instrument;
+use trustify_common::{
+    db::limiter::LimiterTrait,
+    hashing::Digests,
+    model::{Paginated, PaginatedResults},
+};
+use trustify_entity::{
+    advisory,
+    ingestion_history::DocumentKind,
+    organization,
+    reprocess::{self, Failure, Filter, Report, State},
+    sbom, source_document,
+};
+use uuid::Uuid;
+
+/// The number of documents to fetch at once
+const BATCH_SIZE: u64 = 100;
+
+/// The time after which a running run, which didn't make progress, may be resumed
+const STALLED: Duration = Duration::from_secs(15 * 60);
+
+/// The interval in which a run signals progress while loading a single document
+const HEARTBEAT: Duration = Duration::from_secs(60);
+
+/// A run, reprocessing stored documents
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct ReprocessRun {
+    pub id: Uuid,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created: OffsetDateTime,
+    /// The last time the run made progress
+    #[serde(with = "time::serde::rfc3339")]
+    pub updated: OffsetDateTime,
+    pub state: State,
+    pub filter: Filter,
+    /// The number of documents matching the filter, when the run was created
+    pub total: u64,
+    /// The number of documents which got loaded again
+    pub processed: u64,
+    /// The number of documents which didn't match the formats of the filter
+    pub skipped: u64,
+    /// The number of documents which failed to load
+    pub failed: u64,
+    /// The documents which failed to load, and why
+    pub failures: Vec<Failure>,
+}
+
+impl From<reprocess::Model> for ReprocessRun {
+    fn from(value: reprocess::Model) -> Self {
+        Self {
+            id: value.id,
+            created: value.created,
+            updated: value.updated,
+            state: value.state,
+            filter: value.filter,
+            total: value.total as u64,
+            processed: value.processed as u64,
+            skipped: value.skipped as u64,
+            failed: value.failed as u64,
+            failures: value.report.0,
+        }
+    }
+}
+
+pub struct Reprocessor<'g> {
+    ingestor: &'g IngestorService,
+}
+
+impl<'g> Reprocessor<'g> {
+    pub fn new(ingestor: &'g IngestorService) -> Self {
+        Self { ingestor }
+    }
+
+    /// Create a new run, which still needs to be started using [`Self::run`]
+    #[instrument(skip(self), err(level=tracing::Level::INFO))]
+    pub async fn create(&self, filter: Filter) -> Result<ReprocessRun, Error> {
+        let total = documents(&filter, None)
+            .count(&self.ingestor.graph.db)
+            .await?;
+
+        let now = OffsetDateTime::now_utc();
+        let run = reprocess::ActiveModel {
+            id: Set(Uuid::now_v7()),
+            created: Set(now),
+            updated: Set(now),
+            state: Set(State::Pending),
+            filter: Set(filter),
+            cursor_ingested: Set(None),
+            cursor_sha256: Set(None),
+            total: Set(total as i64),
+            processed: Set(0),
+            skipped: Set(0),
+            failed: Set(0),
+            report: Set(Report::default()),
+        }
+        .insert(&self.ingestor.graph.db)
+        .await?;
+
+        Ok(run.into())
+    }
+
+    pub async fn fetch(&self, id: Uuid) -> Result<Option<ReprocessRun>, Error> {
+        Ok(reprocess::Entity::find_by_id(id)
+            .one(&self.ingestor.graph.db)
+            .await?
+            .map(ReprocessRun::from))
+    }
+
+    /// List all runs, the most recent first
+    pub async fn list(
+        &self,
+        paginated: Paginated,
+    ) -> Result<PaginatedResults<ReprocessRun>, Error> {
+        let limiter = reprocess::Entity::find()
+            .order_by_desc(reprocess::Column::Created)
+            .limiting(&self.ingestor.graph.db, &paginated);
+
+        Ok(PaginatedResults {
+            total: limiter.total().await?,
+            items: limiter
+                .fetch()
+                .await?
+                .into_iter()
+                .map(ReprocessRun::from)
+                .collect(),
+            next: None,
+        })
+    }
+
+    /// Claim a run, ensuring it isn't processed twice at the same time
+    ///
+    /// A run can be claimed if it is pending, or if it is running without making progress for a
+    /// while, e.g. because the process running it was stopped. In the latter case, the documents
+    /// of the interrupted run get recovered.
+    pub async fn claim(&self, id: Uuid) -> Result<(), Error> {
+        let stalled = OffsetDateTime::now_utc() - STALLED;
+
+        let result = reprocess::Entity::update_many()
+            .col_expr(reprocess::Column::State, Expr::value(State::Running))
+            .col_expr(reprocess::Column::Updated, Expr::current_timestamp().into())
+            .filter(reprocess::Column::Id.eq(id))
+            .filter(
+                Condition::any()
+                    .add(reprocess::Column::State.eq(State::Pending))
+                    .add(
+                        Condition::all()
+                            .add(reprocess::Column::State.eq(State::Running))
+                            .add(reprocess::Column::Updated.lt(stalled)),
+                    ),
+            )
+            .exec(&self.ingestor.graph.db)
+            .await?;
+
+        if result.rows_affected > 0 {
+            return self.recover(id).await;
+        }
+
+        match self.fetch(id).await? {
+            None => Err(Error::NotFound(format!("reprocess run {id}"))),
+            Some(run) if run.state == State::Completed => Err(Error::Conflict(format!(
+                "reprocess run {id} is already completed"
+            ))),
+            Some(_) => Err(Error::Conflict(format!(
+                "reprocess run {id} is already running"
+            ))),
+        }
+    }
+
+    /// Process the documents of a claimed run, continuing after the last processed document
+    ///
+    /// The `progress` callback is called after each document.
+    #[instrument(skip(self, progress), err(level=tracing::Level::INFO))]
+    pub async fn run(
+        &self,
+        id: Uuid,
+        mut progress: impl FnMut(&ReprocessRun),
+    ) -> Result<ReprocessRun, Error> {
+        let mut run = reprocess::Entity::find_by_id(id)
+            .one(&self.ingestor.graph.db)
+            .await?
+            .ok_or_else(|| Error::NotFound(format!("reprocess run {id}")))?;
+
+        loop {
+            let cursor = run.cursor_ingested.zip(run.cursor_sha256.clone());
+            let batch = documents(&run.filter, cursor)
+                .order_by_asc(source_document::Column::Ingested)
+                .order_by_asc(source_document::Column::Sha256)
+                .limit(BATCH_SIZE)
+                .all(&self.ingestor.graph.db)
+                .await?;
+
+            if batch.is_empty() {
+                break;
+            }
+
+            for document in batch {
+                match self.reprocess(id, &run.filter, &document).await {
+                    Ok(true) => run.processed += 1,
+                    Ok(false) => run.skipped += 1,
+                    Err(err) => {
+                        log::warn!("Failed to reprocess document {}: {err}", document.sha256);
+                        run.failed += 1;
+                        run.report.0.push(Failure {
+                            sha256: document.sha256.clone(),
+                            error: err.to_string(),
+                        });
+                    }
+                }
+
+                run.cursor_ingested = Some(document.ingested);
+                run.cursor_sha256 = Some(document.sha256);
+                run = self.save(run).await?;
+
+                progress(&ReprocessRun::from(run.clone()));
+            }
+        }
+
+        run.state = State::Completed;
+        Ok(self.save(run).await?.into())
+    }
+
+    async fn save(&self, mut run: reprocess::Model) -> Result<reprocess::Model, Error> {
+        run.updated = OffsetDateTime::now_utc();
+        Ok(run
+            .into_active_model()
+            .reset_all()
+            .update(&self.ingestor.graph.db)
+            .await?)
+    }
+
+    /// Reprocess a single document
+    ///
+    /// Returns `false` if the document was skipped, as it didn't match the formats of the filter.
+    async fn reprocess(
+        &self,
+        run_id: Uuid,
+        filter: &Filter,
+        document: &source_document::Model,
+    ) -> Result<bool, Error> {
+        let db = &self.ingestor.graph.db;
+
+        let sbom = sbom::Entity::find()
+            .filter(sbom::Column::SourceDocumentId.eq(document.id))
+            .one(db)
+            .await?;
+        let advisory = advisory::Entity::find()
+            .filter(advisory::Column::SourceDocumentId.eq(document.id))
+            .find_also_related(organization::Entity)
+            .one(db)
+            .await?;
+
+        let (kind, labels, issuer) = match (sbom, advisory) {
+            (Some(sbom), _) => (DocumentKind::Sbom, sbom.labels, None),
+            (None, Some((advisory, issuer))) => (
+                DocumentKind::Advisory,
+                advisory.labels,
+                issuer.map(|issuer| issuer.name),
+            ),
+            (None, None) => return Ok(false),
+        };
+
+        let data = retrieve(&self.ingestor.storage, &document.sha256).await?;
+        let format = Format::detect(kind, &data).ok_or_else(|| {
+            Error::UnsupportedFormat(format!("Unable to detect the format of the {kind}"))
+        })?;
+        if !filter.formats.is_empty()
+            && !filter
+                .formats
+                .iter()
+                .any(|f| f.eq_ignore_ascii_case(&format.to_string()))
+        {
+            return Ok(false);
+        }
+
+        // hide the current version of the document, so that it gets loaded again
+        self.set_sha256(document.id, &hidden(run_id, &document.sha256))
+            .await?;
+
+        let digests = Digests::digest(&data);
+        let load = Box::pin(self.ingestor.load(
+            format,
+            labels,
+            issuer,
+            &digests,
+            &data,
+            Origin::default(),
+        ));
+        if let Err(err) = self.keep_alive(run_id, load).await {
+            self.set_sha256(document.id, &document.sha256).await?;
+            return Err(err);
+        }
+
+        self.remove_previous(document.id, &document.sha256, document.ingested)
+            .await?;
+
+        Ok(true)
+    }
+
+    /// Remove the previous version of a document, after its current version got loaded
+    async fn remove_previous(
+        &self,
+        id: Uuid,
+        sha256: &str,
+        ingested: OffsetDateTime,
+    ) -> Result<(), Error> {
+        let tx = self.ingestor.graph.db.begin().await?;
+
+        let (sbom_ids, _) = delete_documents(id, &tx).await?;
+
+        source_document::Entity::delete_by_id(id).exec(&tx).await?;
+
+        // keep the original ingestion time, which also keeps the position of the run
+        source_document::Entity::update_many()
+            .col_expr(source_document::Column::Ingested, Expr::value(ingested))
+            .filter(source_document::Column::Sha256.eq(sha256))
+            .exec(&tx)
+            .await?;
+
+        tx.commit().await?;
+
+        if let Some(analysis) = &self.ingestor.analysis {
+            for sbom_id in sbom_ids {
+                analysis.clear_graph(&sbom_id.to_string());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Await a future, signaling that the run makes progress in the meantime
+    async fn keep_alive<T>(&self, run_id: Uuid, future: impl Future<Output = T>) -> T {
+        let mut future = pin!(future);
+
+        let mut interval = tokio::time::interval(HEARTBEAT);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        // the first tick completes immediately
+        interval.tick().await;
+
+        loop {
+            tokio::select! {
+                result = &mut future => return result,
+                _ = interval.tick() => {
+                    if let Err(err) = self.touch(run_id).await {
+                        log::warn!("Failed to update reprocess run {run_id}: {err}");
+                    }
+                }
+            }
+        }
+    }
+
+    /// Mark a run as making progress
+    async fn touch(&self, id: Uuid) -> Result<(), Error> {
+        reprocess::Entity::update_many()
+            .col_expr(reprocess::Column::Updated, Expr::current_timestamp().into())
+            .filter(reprocess::Column::Id.eq(id))
+            .exec(&self.ingestor.graph.db)
+            .await?;
+        Ok(())
+    }
+
+    /// Recover the documents hidden by an interrupted run
+    ///
+    /// If the current version of a document got loaded, the previous version gets removed.
+    /// Otherwise, the previous version is restored.
+    async fn recover(&self, run_id: Uuid) -> Result<(), Error> {
+        let prefix = hidden(run_id, "");
+        let documents = source_document::Entity::find()
+            .filter(source_document::Column::Sha256.starts_with(&prefix))
+            .all(&self.ingestor.graph.db)
+            .await?;
+
+        for document in documents {
+            let sha256 = &document.sha256[prefix.len()..];
+
+            let loaded = source_document::Entity::find()
+                .filter(source_document::Column::Sha256.eq(sha256))
+                .count(&self.ingestor.graph.db)
+                .await?
+                > 0;
+
+            log::info!("Recovering document {sha256} of reprocess run {run_id} (loaded: {loaded})");
+
+            match loaded {
+                true => {
+                    self.remove_previous(document.id, sha256, document.ingested)
+                        .await?
+                }
+                false => self.set_sha256(document.id, sha256).await?,
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn set_sha256(&self, id: Uuid, sha256: &str) -> Result<(), Error> {
+        source_document::Entity::update_many()
+            .col_expr(source_document::Column::Sha256, Expr::value(sha256))
+            .filter(source_document::Column::Id.eq(id))
+            .exec(&self.ingestor.graph.db)
+            .await?;
+        Ok(())
+    }
+}
+
+/// The digest of a document, hidden while a run loads it again
+fn hidden(run_id: Uuid, sha256: &str) -> String {
+    format!("reprocess:{run_id}:{sha256}")
+}
+
+/// The source documents of advisories and SBOMs matching the filter, after the cursor
+fn documents(
+    filter: &Filter,
+    cursor: Option<(OffsetDateTime, String)>,
+) -> Select<source_document::Entity> {
+    let mut sboms = sbom::Entity::find()
+        .select_only()
+        .column(sbom::Column::SourceDocumentId);
+    let mut advisories = advisory::Entity::find()
+        .select_only()
+        .column(advisory::Column::SourceDocumentId);
+    if !filter.labels.is_empty() {
+        sboms = sboms.filter(Expr::col(sbom::Column::Labels).contains(filter.labels.clone()));
+        advisories =
+            advisories.filter(Expr::col(advisory::Column::Labels).contains(filter.labels.clone()));
+    }
+
+    source_document::Entity::find()
+        .filter(
+            Condition::any()
+                .add(source_document::Column::Id.in_subquery(sboms.into_query()))
+                .add(source_document::Column::Id.in_subquery(advisories.into_query())),
+        )
+        .apply_if(filter.since, |select, since| {
+            select.filter(source_document::Column::Ingested.gte(since))
+        })
+        .apply_if(filter.until, |select, until| {
+            select.filter(source_document::Column::Ingested.lt(until))
+        })
+        .apply_if(cursor, |select, (ingested, sha256)| {
+            select.filter(
+                Expr::tuple([
+                    Expr::col(source_document::Column::Ingested).into(),
+                    Expr::col(source_document::Column::Sha256).into(),
+                ])
+                .gt(Expr::tuple([Expr::value(ingested), Expr::value(sha256)])),
+            )
+        })
+}
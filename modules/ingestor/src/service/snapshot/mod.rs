@@ -0,0 +1,634 @@
+//! Export and import of snapshots, archives of all source documents
+//!
+//! A snapshot is a zip archive, containing the raw documents as `documents/{sha256}`, and a
+//! manifest (`snapshot.json`) with the metadata required to ingest them again.
+//!
+//! Incremental snapshots only contain the documents ingested after a point in time. To keep a
+//! mirror in sync, they also carry the documents deleted since then, and the current labels of
+//! all documents which were ingested before.
+//!
+//! Uploaded snapshots are imported in the background, tracked by a [`SnapshotImport`]. An import
+//! interrupted by stopping the process stays `running`, and needs to be uploaded again. As
+//! existing documents are skipped, this only ingests the remaining ones.
+
+use crate::{
+    graph::{Graph, tombstone::record_tombstone},
+    model::IngestResult,
+    service::{Error, Format, IngestorService, delete_documents, retrieve},
+};
+use hex::ToHex;
+use sea_orm::{
+    AccessMode, ActiveModelTrait, ColumnTrait, DatabaseTransaction, EntityTrait, FromQueryResult,
+    IsolationLevel, JoinType, PaginatorTrait, QueryFilter, QuerySelect, QueryTrait, RelationTrait,
+    Set, TransactionTrait,
+};
+use sea_query::{Expr, SimpleExpr};
+use std::{
+    collections::{BTreeMap, HashSet},
+    io::{Read, Seek, Write},
+    str::FromStr,
+};
+use time::OffsetDateTime;
+use tracing::instrument;
+use trustify_common::hashing::Digests;
+use trustify_entity::{
+    advisory, document_tombstone,
+    ingestion_history::DocumentKind,
+    labels::Labels,
+    organization, sbom,
+    snapshot_import::{self, State},
+    source_document,
+};
+use trustify_module_storage::service::dispatch::DispatchBackend;
+use uuid::Uuid;
+use zip::{CompressionMethod, ZipArchive, ZipWriter, write::SimpleFileOptions};
+
+/// The version of the snapshot format
+///
+/// Version 2 added deletions and labels, snapshots of version 1 can still be imported.
+pub const VERSION: u32 = 2;
+
+const MANIFEST: &str = "snapshot.json";
+
+/// The metadata of a snapshot
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Manifest {
+    /// The version of the snapshot format
+    pub version: u32,
+    /// The version of trustify which created the snapshot
+    pub created_by: String,
+    /// The time the snapshot was created
+    ///
+    /// Can be used as `since` for the next, incremental, snapshot.
+    #[serde(with = "time::serde::rfc3339")]
+    pub created: OffsetDateTime,
+    /// If present, only documents ingested after this time are part of the snapshot
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub since: Option<OffsetDateTime>,
+    /// The documents of the snapshot, in the order they were ingested
+    pub documents: Vec<SnapshotDocument>,
+    /// The documents which got deleted (after `since`), and don't exist anymore
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deleted: Vec<SnapshotTombstone>,
+    /// For incremental snapshots, the current labels of the documents ingested before `since`,
+    /// by their SHA256 digest
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub labels: BTreeMap<String, Labels>,
+}
+
+/// A document which got deleted
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotTombstone {
+    /// The SHA256 digest of the deleted document
+    pub sha256: String,
+    pub kind: DocumentKind,
+    /// The time the document was deleted
+    #[serde(with = "time::serde::rfc3339")]
+    pub deleted: OffsetDateTime,
+}
+
+impl From<document_tombstone::Model> for SnapshotTombstone {
+    fn from(value: document_tombstone::Model) -> Self {
+        Self {
+            sha256: value.sha256,
+            kind: value.kind,
+            deleted: value.deleted,
+        }
+    }
+}
+
+/// A document of a snapshot
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotDocument {
+    /// The SHA256 digest of the document, also its file name in the archive
+    pub sha256: String,
+    pub kind: DocumentKind,
+    /// The format of the document, if it could be detected
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    /// The time the document was ingested
+    #[serde(with = "time::serde::rfc3339")]
+    pub ingested: OffsetDateTime,
+    pub labels: Labels,
+    /// The issuer of an advisory
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issuer: Option<String>,
+}
+
+impl SnapshotDocument {
+    fn path(&self) -> String {
+        format!("documents/{}", self.sha256)
+    }
+
+    /// The format to ingest the document with
+    fn format(&self) -> Result<Format, Error> {
+        match &self.format {
+            Some(format) => Format::from_str(format)
+                .map_err(|_| Error::UnsupportedFormat(format!("Unknown format: {format}"))),
+            None => Ok(match self.kind {
+                DocumentKind::Advisory => Format::Advisory,
+                DocumentKind::Sbom => Format::SBOM,
+            }),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct SnapshotImportResult {
+    pub warnings: Vec<String>,
+    /// The ingested documents, by their SHA256 digest
+    pub imported: BTreeMap<String, IngestResult>,
+    /// The documents which already existed, by their SHA256 digest
+    pub skipped: Vec<String>,
+    /// The documents which got deleted, by their SHA256 digest
+    #[serde(default)]
+    pub deleted: Vec<String>,
+    /// The existing documents which got their labels updated, by their SHA256 digest
+    #[serde(default)]
+    pub relabeled: Vec<String>,
+}
+
+/// An import of a snapshot, running in the background
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct SnapshotImport {
+    pub id: Uuid,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created: OffsetDateTime,
+    /// The last time the state of the import changed
+    #[serde(with = "time::serde::rfc3339")]
+    pub updated: OffsetDateTime,
+    pub state: State,
+    /// The outcome of the import, once it completed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<SnapshotImportResult>,
+    /// The error of the import, if it failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl TryFrom<snapshot_import::Model> for SnapshotImport {
+    type Error = serde_json::Error;
+
+    fn try_from(value: snapshot_import::Model) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: value.id,
+            created: value.created,
+            updated: value.updated,
+            state: value.state,
+            result: value.result.map(serde_json::from_value).transpose()?,
+            error: value.error,
+        })
+    }
+}
+
+#[derive(FromQueryResult)]
+struct Row {
+    id: Uuid,
+    sha256: String,
+    ingested: OffsetDateTime,
+    labels: Labels,
+    issuer: Option<String>,
+}
+
+pub struct SnapshotExporter<'g> {
+    graph: &'g Graph,
+    storage: &'g DispatchBackend,
+}
+
+impl<'g> SnapshotExporter<'g> {
+    pub fn new(graph: &'g Graph, storage: &'g DispatchBackend) -> Self {
+        Self { graph, storage }
+    }
+
+    /// Write a snapshot of all documents, or only those ingested after `since`
+    #[instrument(skip(self, writer), err(level=tracing::Level::INFO))]
+    pub async fn export<W: Write + Seek>(
+        &self,
+        since: Option<OffsetDateTime>,
+        writer: W,
+    ) -> Result<Manifest, Error> {
+        let created = OffsetDateTime::now_utc();
+        let Contents {
+            mut documents,
+            deleted,
+            labels,
+        } = self.contents(since).await?;
+
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        let mut zip = ZipWriter::new(writer);
+
+        for document in &mut documents {
+            let data = retrieve(self.storage, &document.sha256).await?;
+            document.format = Format::detect(document.kind, &data).map(|format| format.to_string());
+            zip.start_file(document.path(), options)?;
+            zip.write_all(&data)?;
+        }
+
+        let manifest = Manifest {
+            version: VERSION,
+            created_by: env!("CARGO_PKG_VERSION").to_string(),
+            created,
+            since,
+            documents,
+            deleted,
+            labels,
+        };
+
+        zip.start_file(MANIFEST, options)?;
+        serde_json::to_writer_pretty(&mut zip, &manifest)?;
+        zip.finish()?;
+
+        Ok(manifest)
+    }
+
+    /// Collect the contents of the snapshot, from a consistent view of the database
+    async fn contents(&self, since: Option<OffsetDateTime>) -> Result<Contents, Error> {
+        let tx = self
+            .graph
+            .db
+            .begin_with_config(
+                Some(IsolationLevel::RepeatableRead),
+                Some(AccessMode::ReadOnly),
+            )
+            .await?;
+
+        let ingested = since.map(|since| source_document::Column::Ingested.gt(since));
+        let documents = documents(ingested, &tx)
+            .await?
+            .into_iter()
+            .map(|(kind, row)| SnapshotDocument {
+                sha256: row.sha256,
+                kind,
+                format: None,
+                ingested: row.ingested,
+                labels: row.labels,
+                issuer: row.issuer,
+            })
+            .collect();
+
+        // documents which got deleted, and weren't ingested again
+
+        let deleted = document_tombstone::Entity::find()
+            .apply_if(since, |select, since| {
+                select.filter(document_tombstone::Column::Deleted.gt(since))
+            })
+            .filter(Expr::cust(
+                r#"NOT EXISTS (
+    SELECT 1 FROM source_document
+    WHERE source_document.sha256 = document_tombstone.sha256
+        AND (
+            EXISTS (SELECT 1 FROM sbom WHERE sbom.source_document_id = source_document.id)
+            OR EXISTS (SELECT 1 FROM advisory WHERE advisory.source_document_id = source_document.id)
+        )
+)"#,
+            ))
+            .all(&tx)
+            .await?
+            .into_iter()
+            .map(SnapshotTombstone::from)
+            .collect();
+
+        // the labels of documents ingested before, as those may have changed in the meantime
+
+        let labels = match since {
+            Some(since) => {
+                let ingested = source_document::Column::Ingested.lte(since);
+                documents(Some(ingested), &tx)
+                    .await?
+                    .into_iter()
+                    .map(|(_, row)| (row.sha256, row.labels))
+                    .collect()
+            }
+            None => BTreeMap::new(),
+        };
+
+        tx.commit().await?;
+
+        Ok(Contents {
+            documents,
+            deleted,
+            labels,
+        })
+    }
+}
+
+/// The contents of a snapshot
+struct Contents {
+    documents: Vec<SnapshotDocument>,
+    deleted: Vec<SnapshotTombstone>,
+    labels: BTreeMap<String, Labels>,
+}
+
+/// Find the source documents of advisories and SBOMs, in the order they were ingested
+async fn documents(
+    ingested: Option<SimpleExpr>,
+    tx: &DatabaseTransaction,
+) -> Result<Vec<(DocumentKind, Row)>, Error> {
+    let advisories = advisory::Entity::find()
+        .select_only()
+        .column(source_document::Column::Id)
+        .column(source_document::Column::Sha256)
+        .column(source_document::Column::Ingested)
+        .column(advisory::Column::Labels)
+        .column_as(organization::Column::Name, "issuer")
+        .join(JoinType::Join, advisory::Relation::SourceDocument.def())
+        .join(JoinType::LeftJoin, advisory::Relation::Issuer.def())
+        .apply_if(ingested.clone(), |select, ingested| select.filter(ingested))
+        .into_model::<Row>()
+        .all(tx)
+        .await?;
+
+    let sboms = sbom::Entity::find()
+        .select_only()
+        .column(source_document::Column::Id)
+        .column(source_document::Column::Sha256)
+        .column(source_document::Column::Ingested)
+        .column(sbom::Column::Labels)
+        .column_as(Expr::val(Option::<String>::None), "issuer")
+        .join(JoinType::Join, sbom::Relation::SourceDocument.def())
+        .apply_if(ingested, |select, ingested| select.filter(ingested))
+        .into_model::<Row>()
+        .all(tx)
+        .await?;
+
+    let mut rows = advisories
+        .into_iter()
+        .map(|row| (DocumentKind::Advisory, row))
+        .chain(sboms.into_iter().map(|row| (DocumentKind::Sbom, row)))
+        .collect::<Vec<_>>();
+    rows.sort_by_key(|(_, row)| row.ingested);
+
+    // a single document may create multiple advisories, like an NVD feed
+    let mut seen = HashSet::new();
+    rows.retain(|(_, row)| seen.insert(row.id));
+
+    Ok(rows)
+}
+
+pub struct SnapshotImporter<'s> {
+    service: &'s IngestorService,
+    /// The size limit of a single document
+    limit: usize,
+}
+
+impl<'s> SnapshotImporter<'s> {
+    pub fn new(service: &'s IngestorService, limit: usize) -> Self {
+        Self { service, limit }
+    }
+
+    /// Validate a snapshot, and create an import for it, which still needs to be run using
+    /// [`Self::run`]
+    #[instrument(skip(self, reader), err(level=tracing::Level::INFO))]
+    pub async fn create<R: Read + Seek>(&self, reader: R) -> Result<SnapshotImport, Error> {
+        manifest(&mut ZipArchive::new(reader)?)?;
+
+        let now = OffsetDateTime::now_utc();
+        let import = snapshot_import::ActiveModel {
+            id: Set(Uuid::now_v7()),
+            created: Set(now),
+            updated: Set(now),
+            state: Set(State::Running),
+            result: Set(None),
+            error: Set(None),
+        }
+        .insert(&self.service.graph().db)
+        .await?;
+
+        Ok(import.try_into()?)
+    }
+
+    pub async fn fetch(&self, id: Uuid) -> Result<Option<SnapshotImport>, Error> {
+        Ok(snapshot_import::Entity::find_by_id(id)
+            .one(&self.service.graph().db)
+            .await?
+            .map(SnapshotImport::try_from)
+            .transpose()?)
+    }
+
+    /// Run a created import, recording its outcome
+    #[instrument(skip(self, reader), err(level=tracing::Level::INFO))]
+    pub async fn run<R: Read + Seek>(&self, id: Uuid, reader: R) -> Result<(), Error> {
+        let (state, result, error) = match self.import(reader).await {
+            Ok(result) => (State::Completed, Some(serde_json::to_value(result)?), None),
+            Err(err) => (State::Failed, None, Some(err.to_string())),
+        };
+
+        snapshot_import::ActiveModel {
+            id: Set(id),
+            updated: Set(OffsetDateTime::now_utc()),
+            state: Set(state),
+            result: Set(result),
+            error: Set(error),
+            ..Default::default()
+        }
+        .update(&self.service.graph().db)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Ingest all documents of a snapshot, which don't exist yet
+    ///
+    /// Deletions are applied first, as a deleted document may have been ingested again. Labels
+    /// are applied to existing documents.
+    #[instrument(skip(self, reader), err(level=tracing::Level::INFO))]
+    pub async fn import<R: Read + Seek>(&self, reader: R) -> Result<SnapshotImportResult, Error> {
+        let mut zip = ZipArchive::new(reader)?;
+        let manifest = manifest(&mut zip)?;
+
+        let mut result = SnapshotImportResult::default();
+
+        for tombstone in manifest.deleted {
+            match self.delete(&tombstone).await {
+                Ok(true) => result.deleted.push(tombstone.sha256),
+                Ok(false) => {}
+                Err(err) => result.warnings.push(format!(
+                    "Failed to delete document ({}): {err}",
+                    tombstone.sha256
+                )),
+            }
+        }
+
+        for document in manifest.documents {
+            let sha256 = document.sha256.clone();
+
+            if self.exists(&sha256).await? {
+                self.relabel(&sha256, document.labels, &mut result).await;
+                result.skipped.push(sha256);
+                continue;
+            }
+
+            let data = match read(&mut zip, &document.path(), self.limit) {
+                Ok(data) => data,
+                Err(err) => {
+                    result
+                        .warnings
+                        .push(format!("Failed to read document ({sha256}): {err}"));
+                    continue;
+                }
+            };
+
+            if Digests::digest(&data).sha256.encode_hex::<String>() != sha256 {
+                result
+                    .warnings
+                    .push(format!("Digest mismatch of document ({sha256})"));
+                continue;
+            }
+
+            let ingested = match document.format() {
+                Ok(format) => {
+                    self.service
+                        .ingest(&data, format, document.labels, document.issuer)
+                        .await
+                }
+                Err(err) => Err(err),
+            };
+
+            match ingested {
+                Ok(ingested) => {
+                    result.imported.insert(sha256, ingested);
+                }
+                Err(err) => {
+                    result
+                        .warnings
+                        .push(format!("Error loading document ({sha256}): {err}"));
+                }
+            }
+        }
+
+        for (sha256, labels) in manifest.labels {
+            self.relabel(&sha256, labels, &mut result).await;
+        }
+
+        Ok(result)
+    }
+
+    /// Check if a document exists, as an SBOM or advisory
+    async fn exists(&self, sha256: &str) -> Result<bool, Error> {
+        let count = source_document::Entity::find()
+            .filter(source_document::Column::Sha256.eq(sha256))
+            .filter(Expr::cust(
+                r#"(
+    EXISTS (SELECT 1 FROM sbom WHERE sbom.source_document_id = source_document.id)
+    OR EXISTS (SELECT 1 FROM advisory WHERE advisory.source_document_id = source_document.id)
+)"#,
+            ))
+            .count(&self.service.graph().db)
+            .await?;
+
+        Ok(count > 0)
+    }
+
+    /// Delete the documents of a tombstone, returns `false` if there were none
+    async fn delete(&self, tombstone: &SnapshotTombstone) -> Result<bool, Error> {
+        let tx = self.service.graph().db.begin().await?;
+
+        let ids = source_document::Entity::find()
+            .select_only()
+            .column(source_document::Column::Id)
+            .filter(source_document::Column::Sha256.eq(&tombstone.sha256))
+            .into_tuple::<Uuid>()
+            .all(&tx)
+            .await?;
+
+        let mut deleted = false;
+        let mut sbom_ids = vec![];
+        for id in ids {
+            let (sboms, advisories) = delete_documents(id, &tx).await?;
+            if sboms.is_empty() && advisories == 0 {
+                continue;
+            }
+
+            deleted = true;
+            sbom_ids.extend(sboms);
+            record_tombstone(tombstone.kind, id, &tx).await?;
+        }
+
+        tx.commit().await?;
+
+        if let Some(analysis) = &self.service.analysis {
+            for sbom_id in sbom_ids {
+                analysis.clear_graph(&sbom_id.to_string());
+            }
+        }
+
+        Ok(deleted)
+    }
+
+    /// Apply the labels of a snapshot to an existing document, recording a failure as a warning
+    async fn relabel(&self, sha256: &str, labels: Labels, result: &mut SnapshotImportResult) {
+        match self.update_labels(sha256, labels).await {
+            Ok(true) => result.relabeled.push(sha256.to_string()),
+            Ok(false) => {}
+            Err(err) => result.warnings.push(format!(
+                "Failed to update labels of document ({sha256}): {err}"
+            )),
+        }
+    }
+
+    /// Update the labels of an existing document, returns `false` if they didn't change
+    async fn update_labels(&self, sha256: &str, labels: Labels) -> Result<bool, Error> {
+        let source_documents = source_document::Entity::find()
+            .select_only()
+            .column(source_document::Column::Id)
+            .filter(source_document::Column::Sha256.eq(sha256))
+            .into_query();
+
+        let tx = self.service.graph().db.begin().await?;
+
+        let sboms = sbom::Entity::update_many()
+            .col_expr(sbom::Column::Labels, Expr::value(labels.clone()))
+            .filter(sbom::Column::SourceDocumentId.in_subquery(source_documents.clone()))
+            .filter(sbom::Column::Labels.ne(labels.clone()))
+            .exec(&tx)
+            .await?;
+        let advisories = advisory::Entity::update_many()
+            .col_expr(advisory::Column::Labels, Expr::value(labels.clone()))
+            .filter(advisory::Column::SourceDocumentId.in_subquery(source_documents))
+            .filter(advisory::Column::Labels.ne(labels))
+            .exec(&tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(sboms.rows_affected + advisories.rows_affected > 0)
+    }
+}
+
+/// Read a document from the archive, failing if it exceeds the size limit
+fn read<R: Read + Seek>(
+    zip: &mut ZipArchive<R>,
+    path: &str,
+    limit: usize,
+) -> Result<Vec<u8>, Error> {
+    let file = zip.by_name(path)?;
+    if file.size() > limit as u64 {
+        return Err(Error::PayloadTooLarge);
+    }
+
+    // the size of the entry is declared by the archive, and can't be trusted
+    let mut data = Vec::with_capacity(file.size() as usize);
+    file.take(limit as u64 + 1).read_to_end(&mut data)?;
+    if data.len() > limit {
+        return Err(Error::PayloadTooLarge);
+    }
+
+    Ok(data)
+}
+
+/// Read the manifest of a snapshot, ensuring it is of a supported version
+fn manifest<R: Read + Seek>(zip: &mut ZipArchive<R>) -> Result<Manifest, Error> {
+    let manifest: Manifest = serde_json::from_reader(zip.by_name(MANIFEST)?)?;
+
+    if !(1..=VERSION).contains(&manifest.version) {
+        return Err(Error::UnsupportedFormat(format!(
+            "Unsupported snapshot version: {}",
+            manifest.version
+        )));
+    }
+
+    Ok(manifest)
+}
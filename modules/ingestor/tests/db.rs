@@ -139,6 +139,7 @@ async fn create_set(ctx: &TrustifyContext) -> anyhow::Result<()> {
                 modified: Some(modified),
                 withdrawn: None,
                 version: None,
+                format: None,
             };
             graph
                 .ingest_advisory(d, (), &digests, info, &ctx.db)
@@ -0,0 +1,164 @@
+use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter};
+use sea_query::Expr;
+use std::time::Duration;
+use test_context::test_context;
+use test_log::test;
+use time::OffsetDateTime;
+use trustify_entity::{
+    advisory,
+    labels::Labels,
+    reprocess::{self, Filter, State},
+    sbom, sbom_package, source_document,
+};
+use trustify_module_ingestor::service::Error;
+use trustify_test_context::TrustifyContext;
+
+/// Reprocess all documents, and ensure they got loaded again
+#[test_context(TrustifyContext)]
+#[test(tokio::test)]
+async fn reprocess_all(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    ctx.ingest_documents(["csaf/cve-2023-33201.json", "zookeeper-3.9.2-cyclonedx.json"])
+        .await?;
+
+    let before = sbom::Entity::find()
+        .one(&ctx.db)
+        .await?
+        .expect("must exist");
+    let documents = source_document::Entity::find().all(&ctx.db).await?;
+
+    let reprocessor = ctx.ingestor.reprocessor();
+    let run = reprocessor.create(Filter::default()).await?;
+    assert_eq!(run.state, State::Pending);
+    assert_eq!(run.total, 2);
+
+    reprocessor.claim(run.id).await?;
+    let run = reprocessor.run(run.id, |_| {}).await?;
+    assert_eq!(run.state, State::Completed);
+    assert_eq!(run.processed, 2);
+    assert_eq!(run.skipped, 0);
+    assert_eq!(run.failed, 0);
+    assert!(run.failures.is_empty());
+
+    // the SBOM got loaded again, keeping its labels
+
+    let after = sbom::Entity::find().all(&ctx.db).await?;
+    assert_eq!(after.len(), 1);
+    let after = &after[0];
+    assert_ne!(after.sbom_id, before.sbom_id);
+    assert_eq!(after.document_id, before.document_id);
+    assert_eq!(after.labels, Labels::new().add("source", "TrustifyContext"));
+    let packages = sbom_package::Entity::find()
+        .filter(sbom_package::Column::SbomId.eq(after.sbom_id))
+        .count(&ctx.db)
+        .await?;
+    assert!(packages > 0);
+
+    assert_eq!(advisory::Entity::find().count(&ctx.db).await?, 1);
+
+    // the documents kept their ingestion time
+
+    for document in documents {
+        let reprocessed = source_document::Entity::find()
+            .filter(source_document::Column::Sha256.eq(&document.sha256))
+            .one(&ctx.db)
+            .await?
+            .expect("must exist");
+        assert_ne!(reprocessed.id, document.id);
+        assert_eq!(reprocessed.ingested, document.ingested);
+    }
+
+    // a completed run can't be claimed again
+
+    let result = reprocessor.claim(run.id).await;
+    assert!(matches!(result, Err(Error::Conflict(_))), "{result:?}");
+
+    Ok(())
+}
+
+/// Skip documents not matching the formats of the filter
+#[test_context(TrustifyContext)]
+#[test(tokio::test)]
+async fn reprocess_format(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    ctx.ingest_documents(["csaf/cve-2023-33201.json", "zookeeper-3.9.2-cyclonedx.json"])
+        .await?;
+
+    let before = sbom::Entity::find()
+        .one(&ctx.db)
+        .await?
+        .expect("must exist");
+
+    let reprocessor = ctx.ingestor.reprocessor();
+    let run = reprocessor
+        .create(Filter {
+            formats: vec!["spdx".into()],
+            ..Default::default()
+        })
+        .await?;
+    reprocessor.claim(run.id).await?;
+    let run = reprocessor.run(run.id, |_| {}).await?;
+
+    assert_eq!(run.total, 2);
+    assert_eq!(run.processed, 0);
+    assert_eq!(run.skipped, 2);
+    assert_eq!(run.failed, 0);
+
+    let after = sbom::Entity::find()
+        .one(&ctx.db)
+        .await?
+        .expect("must exist");
+    assert_eq!(after.sbom_id, before.sbom_id);
+
+    Ok(())
+}
+
+/// Recover the documents of an interrupted run, when claiming it again
+#[test_context(TrustifyContext)]
+#[test(tokio::test)]
+async fn reprocess_recover(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    ctx.ingest_document("zookeeper-3.9.2-cyclonedx.json")
+        .await?;
+
+    let document = source_document::Entity::find()
+        .one(&ctx.db)
+        .await?
+        .expect("must exist");
+
+    let reprocessor = ctx.ingestor.reprocessor();
+    let run = reprocessor.create(Filter::default()).await?;
+    reprocessor.claim(run.id).await?;
+
+    // simulate a run which stopped while loading the document
+
+    source_document::Entity::update_many()
+        .col_expr(
+            source_document::Column::Sha256,
+            Expr::value(format!("reprocess:{}:{}", run.id, document.sha256)),
+        )
+        .filter(source_document::Column::Id.eq(document.id))
+        .exec(&ctx.db)
+        .await?;
+    reprocess::Entity::update_many()
+        .col_expr(
+            reprocess::Column::Updated,
+            Expr::value(OffsetDateTime::now_utc() - Duration::from_secs(60 * 60)),
+        )
+        .filter(reprocess::Column::Id.eq(run.id))
+        .exec(&ctx.db)
+        .await?;
+
+    // claiming the stalled run restores the document
+
+    reprocessor.claim(run.id).await?;
+
+    let recovered = source_document::Entity::find_by_id(document.id)
+        .one(&ctx.db)
+        .await?
+        .expect("must exist");
+    assert_eq!(recovered.sha256, document.sha256);
+
+    let run = reprocessor.run(run.id, |_| {}).await?;
+    assert_eq!(run.processed, 1);
+    assert_eq!(source_document::Entity::find().count(&ctx.db).await?, 1);
+
+    Ok(())
+}
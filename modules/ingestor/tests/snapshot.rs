@@ -0,0 +1,259 @@
+#[path = "common.rs"]
+mod common;
+
+use actix_http::StatusCode;
+use actix_web::test::TestRequest;
+use common::caller_with;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use sea_query::Expr;
+use std::{io::Cursor, time::Duration};
+use test_context::{AsyncTestContext, test_context};
+use test_log::test;
+use trustify_entity::{
+    advisory, ingestion_history::DocumentKind, labels::Labels, sbom, snapshot_import::State,
+};
+use trustify_module_ingestor::{
+    endpoints::Config, graph::tombstone::record_tombstone, service::snapshot::SnapshotImport,
+};
+use trustify_test_context::{TrustifyContext, call::CallService};
+
+/// The size limit of documents when importing
+const LIMIT: usize = 16 * 1024 * 1024;
+
+/// Export a snapshot, and import it into another instance
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn export_import(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    ctx.ingest_documents(["csaf/cve-2023-33201.json", "zookeeper-3.9.2-cyclonedx.json"])
+        .await?;
+
+    let mut data = Cursor::new(vec![]);
+    let manifest = ctx.ingestor.export_snapshot(None, &mut data).await?;
+    let data = data.into_inner();
+
+    let kinds = manifest
+        .documents
+        .iter()
+        .map(|document| (document.kind, document.format.as_deref()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        [
+            (DocumentKind::Advisory, Some("csaf")),
+            (DocumentKind::Sbom, Some("cycloneDx")),
+        ]
+    );
+    assert!(
+        manifest
+            .documents
+            .iter()
+            .all(|document| document.labels == Labels::new().add("source", "TrustifyContext"))
+    );
+
+    // nothing got ingested since the snapshot
+
+    let incremental = ctx
+        .ingestor
+        .export_snapshot(Some(manifest.created), Cursor::new(vec![]))
+        .await?;
+    assert!(incremental.documents.is_empty());
+
+    // import into an empty instance
+
+    let other = TrustifyContext::setup().await;
+
+    let result = other
+        .ingestor
+        .import_snapshot(Cursor::new(&data), LIMIT)
+        .await?;
+    assert!(result.warnings.is_empty());
+    assert_eq!(result.imported.len(), 2);
+    assert!(result.skipped.is_empty());
+
+    // importing again skips existing documents
+
+    let result = other
+        .ingestor
+        .import_snapshot(Cursor::new(&data), LIMIT)
+        .await?;
+    assert!(result.imported.is_empty());
+    assert_eq!(result.skipped.len(), 2);
+
+    other.teardown().await;
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn import_invalid(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let app = caller_with(ctx, Config::default()).await?;
+
+    let request = TestRequest::post()
+        .uri("/api/v2/snapshot")
+        .set_payload("not a snapshot")
+        .to_request();
+
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
+
+/// Export and import a snapshot through the API, the import runs in the background
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn export_import_endpoints(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    ctx.ingest_documents(["csaf/cve-2023-33201.json", "zookeeper-3.9.2-cyclonedx.json"])
+        .await?;
+
+    let app = caller_with(
+        ctx,
+        Config {
+            dataset_entry_limit: LIMIT,
+        },
+    )
+    .await?;
+    let request = TestRequest::get().uri("/api/v2/snapshot").to_request();
+    let data = app.call_and_read_body(request).await;
+
+    // import into an empty instance
+
+    let other = TrustifyContext::setup().await;
+    let app = caller_with(
+        &other,
+        Config {
+            dataset_entry_limit: LIMIT,
+        },
+    )
+    .await?;
+
+    let request = TestRequest::post()
+        .uri("/api/v2/snapshot")
+        .set_payload(data)
+        .to_request();
+    let response = app.call_service(request).await;
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+    let import: SnapshotImport = actix_web::test::read_body_json(response).await;
+
+    // wait for it to complete
+
+    let mut import = import;
+    for _ in 0..300 {
+        if import.state != State::Running {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let request = TestRequest::get()
+            .uri(&format!("/api/v2/snapshot/import/{}", import.id))
+            .to_request();
+        import = app.call_and_read_body_json(request).await;
+    }
+
+    assert_eq!(import.state, State::Completed);
+    assert_eq!(import.error, None);
+    let result = import.result.expect("must have a result");
+    assert!(result.warnings.is_empty());
+    assert_eq!(result.imported.len(), 2);
+
+    drop(app);
+    other.teardown().await;
+
+    Ok(())
+}
+
+/// Incremental snapshots carry deletions and label changes of previous documents
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn incremental(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    let results = ctx
+        .ingest_documents(["csaf/cve-2023-33201.json", "zookeeper-3.9.2-cyclonedx.json"])
+        .await?;
+    let (csaf, cyclonedx) = (&results[0], &results[1]);
+
+    let mut data = Cursor::new(vec![]);
+    let manifest = ctx.ingestor.export_snapshot(None, &mut data).await?;
+
+    let other = TrustifyContext::setup().await;
+    other
+        .ingestor
+        .import_snapshot(Cursor::new(data.into_inner()), LIMIT)
+        .await?;
+
+    // delete the SBOM, like the SBOM service does, and change the labels of the advisory
+
+    let sbom_id = cyclonedx.id.try_as_uid().expect("must be a UUID");
+    let source_document_id = sbom::Entity::find_by_id(sbom_id)
+        .one(&ctx.db)
+        .await?
+        .and_then(|sbom| sbom.source_document_id)
+        .expect("must have a source document");
+    sbom::Entity::delete_by_id(sbom_id).exec(&ctx.db).await?;
+    record_tombstone(DocumentKind::Sbom, source_document_id, &ctx.db).await?;
+
+    let labels = Labels::new()
+        .add("source", "TrustifyContext")
+        .add("foo", "bar");
+    advisory::Entity::update_many()
+        .col_expr(advisory::Column::Labels, Expr::value(labels.clone()))
+        .filter(advisory::Column::Id.eq(csaf.id.try_as_uid().expect("must be a UUID")))
+        .exec(&ctx.db)
+        .await?;
+
+    // the incremental snapshot must carry both
+
+    let mut data = Cursor::new(vec![]);
+    let incremental = ctx
+        .ingestor
+        .export_snapshot(Some(manifest.created), &mut data)
+        .await?;
+    assert!(incremental.documents.is_empty());
+    assert_eq!(incremental.deleted.len(), 1);
+    assert_eq!(incremental.deleted[0].kind, DocumentKind::Sbom);
+    assert_eq!(
+        incremental.labels.values().collect::<Vec<_>>(),
+        vec![&labels]
+    );
+
+    // and apply them to the mirror
+
+    let result = other
+        .ingestor
+        .import_snapshot(Cursor::new(data.into_inner()), LIMIT)
+        .await?;
+    assert!(result.warnings.is_empty());
+    assert_eq!(result.deleted, vec![incremental.deleted[0].sha256.clone()]);
+    assert_eq!(result.relabeled.len(), 1);
+
+    assert_eq!(sbom::Entity::find().all(&other.db).await?.len(), 0);
+    let advisories = advisory::Entity::find().all(&other.db).await?;
+    assert_eq!(advisories.len(), 1);
+    assert_eq!(advisories[0].labels, labels);
+
+    other.teardown().await;
+
+    Ok(())
+}
+
+/// Documents exceeding the size limit are not imported
+#[test_context(TrustifyContext)]
+#[test(actix_web::test)]
+async fn import_limit(ctx: &TrustifyContext) -> anyhow::Result<()> {
+    ctx.ingest_documents(["csaf/cve-2023-33201.json"]).await?;
+
+    let mut data = Cursor::new(vec![]);
+    ctx.ingestor.export_snapshot(None, &mut data).await?;
+
+    let other = TrustifyContext::setup().await;
+    let result = other
+        .ingestor
+        .import_snapshot(Cursor::new(data.into_inner()), 16)
+        .await?;
+    assert_eq!(result.warnings.len(), 1);
+    assert!(result.imported.is_empty());
+
+    other.teardown().await;
+
+    Ok(())
+}
@@ -1,18 +1,48 @@
-use crate::service::{Error, UserPreferenceService};
+use crate::{
+    service::{Error, UserPreferenceService},
+    token::service::DEFAULT_MAX_LIFETIME,
+};
 use actix_web::{
     HttpResponse, Responder, delete, get,
     http::header::{self, ETag, EntityTag, IfMatch},
     put, web,
 };
+use std::time::Duration;
 use trustify_auth::authenticator::user::UserDetails;
 use trustify_common::{db::Database, model::Revisioned};
+use trustify_module_audit::{
+    recorder::{Action, Audit, Event, TargetKind},
+    service::AuditService,
+};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Config {
+    /// The maximum time a new API token can be valid for.
+    pub api_token_max_lifetime: Duration,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            api_token_max_lifetime: Duration::from_secs(DEFAULT_MAX_LIFETIME.whole_seconds() as u64),
+        }
+    }
+}
 
 /// mount the "user" module
-pub fn configure(svc: &mut utoipa_actix_web::service_config::ServiceConfig, db: Database) {
-    svc.app_data(web::Data::new(UserPreferenceService::new(db)))
+pub fn configure(
+    svc: &mut utoipa_actix_web::service_config::ServiceConfig,
+    config: Config,
+    db: Database,
+) {
+    svc.app_data(web::Data::new(UserPreferenceService::new(db.clone())))
+        .app_data(web::Data::new(AuditService::new(db.clone())))
         .service(set)
         .service(get)
         .service(delete);
+
+    crate::search::endpoints::configure(svc, db.clone());
+    crate::token::endpoints::configure(svc, db, config.api_token_max_lifetime);
 }
 
 #[utoipa::path(
@@ -75,18 +105,21 @@ async fn set(
     user: UserDetails,
     web::Header(if_match): web::Header<IfMatch>,
     web::Json(data): web::Json<serde_json::Value>,
+    audit: Audit,
 ) -> Result<impl Responder, Error> {
     let revision = match &if_match {
         IfMatch::Any => None,
         IfMatch::Items(items) => items.first().map(|etag| etag.tag()),
     };
 
+    let event = Event::new(Action::Update, TargetKind::UserPreference, &key);
     let Revisioned {
         value: (),
         revision,
     } = service
         .set(user.id, key.into_inner(), revision, data)
         .await?;
+    audit.record(event).await;
 
     Ok(HttpResponse::NoContent()
         .append_header((header::ETAG, ETag(EntityTag::new_strong(revision))))
@@ -113,12 +146,15 @@ async fn delete(
     key: web::Path<String>,
     user: UserDetails,
     web::Header(if_match): web::Header<IfMatch>,
+    audit: Audit,
 ) -> Result<impl Responder, Error> {
     let revision = match &if_match {
         IfMatch::Any => None,
         IfMatch::Items(items) => items.first().map(|etag| etag.tag()),
     };
 
+    let event = Event::new(Action::Delete, TargetKind::UserPreference, &key);
     service.delete(user.id, key.into_inner(), revision).await?;
+    audit.record(event).await;
     Ok(HttpResponse::NoContent().finish())
 }
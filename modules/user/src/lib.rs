@@ -1,5 +1,7 @@
 #![recursion_limit = "256"]
 
 pub mod endpoints;
+pub mod search;
 pub mod service;
 pub mod test;
+pub mod token;
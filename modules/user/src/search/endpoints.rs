@@ -0,0 +1,154 @@
+use crate::{
+    search::{
+        model::{NewSavedSearch, SavedSearch, SavedSearchQuery},
+        service::SavedSearchService,
+    },
+    service::Error,
+};
+use actix_web::{HttpResponse, Responder, delete, get, post, put, web};
+use trustify_auth::authenticator::user::UserDetails;
+use trustify_common::db::Database;
+use trustify_module_audit::recorder::{Action, Audit, Event, TargetKind};
+use uuid::Uuid;
+
+/// mount the "saved search" endpoints
+pub fn configure(svc: &mut utoipa_actix_web::service_config::ServiceConfig, db: Database) {
+    svc.app_data(web::Data::new(SavedSearchService::new(db)))
+        .service(list)
+        .service(create)
+        .service(get)
+        .service(update)
+        .service(delete);
+}
+
+#[utoipa::path(
+    tag = "savedSearch",
+    operation_id = "listSavedSearches",
+    params(SavedSearchQuery),
+    responses(
+        (status = 200, description = "The saved searches of the current user", body = Vec<SavedSearch>),
+    )
+)]
+#[get("/v2/savedSearch")]
+/// List the saved searches of the current user
+async fn list(
+    service: web::Data<SavedSearchService>,
+    web::Query(SavedSearchQuery { kind }): web::Query<SavedSearchQuery>,
+    user: UserDetails,
+) -> Result<impl Responder, Error> {
+    Ok(HttpResponse::Ok().json(service.list(&user.id, kind).await?))
+}
+
+#[utoipa::path(
+    tag = "savedSearch",
+    operation_id = "createSavedSearch",
+    request_body = NewSavedSearch,
+    responses(
+        (status = 201, description = "The created search", body = SavedSearch),
+        (status = 400, description = "The search is invalid"),
+        (status = 409, description = "A search with the same name already exists for this kind of list"),
+    )
+)]
+#[post("/v2/savedSearch")]
+/// Save a search for the current user
+async fn create(
+    service: web::Data<SavedSearchService>,
+    user: UserDetails,
+    web::Json(search): web::Json<NewSavedSearch>,
+    audit: Audit,
+) -> Result<impl Responder, Error> {
+    let created = service.create(&user.id, search).await?;
+    audit
+        .record(Event::new(Action::Create, TargetKind::SavedSearch, created.id).after(&created))
+        .await;
+    Ok(HttpResponse::Created().json(created))
+}
+
+#[utoipa::path(
+    tag = "savedSearch",
+    operation_id = "getSavedSearch",
+    params(
+        ("id", Path, description = "The ID of the search"),
+    ),
+    responses(
+        (status = 200, description = "The saved search", body = SavedSearch),
+        (status = 404, description = "The user doesn't have a search with this ID"),
+    )
+)]
+#[get("/v2/savedSearch/{id}")]
+/// Get a saved search of the current user
+async fn get(
+    service: web::Data<SavedSearchService>,
+    id: web::Path<Uuid>,
+    user: UserDetails,
+) -> Result<impl Responder, Error> {
+    Ok(match service.get(&user.id, *id).await? {
+        Some(search) => HttpResponse::Ok().json(search),
+        None => HttpResponse::NotFound().finish(),
+    })
+}
+
+#[utoipa::path(
+    tag = "savedSearch",
+    operation_id = "updateSavedSearch",
+    request_body = NewSavedSearch,
+    params(
+        ("id", Path, description = "The ID of the search"),
+    ),
+    responses(
+        (status = 200, description = "The updated search", body = SavedSearch),
+        (status = 400, description = "The search is invalid"),
+        (status = 404, description = "The user doesn't have a search with this ID"),
+        (status = 409, description = "A search with the same name already exists for this kind of list"),
+    )
+)]
+#[put("/v2/savedSearch/{id}")]
+/// Replace a saved search of the current user
+async fn update(
+    service: web::Data<SavedSearchService>,
+    id: web::Path<Uuid>,
+    user: UserDetails,
+    web::Json(search): web::Json<NewSavedSearch>,
+    audit: Audit,
+) -> Result<impl Responder, Error> {
+    Ok(match service.update(&user.id, *id, search).await? {
+        Some(updated) => {
+            audit
+                .record(Event::new(Action::Update, TargetKind::SavedSearch, *id).after(&updated))
+                .await;
+            HttpResponse::Ok().json(updated)
+        }
+        None => HttpResponse::NotFound().finish(),
+    })
+}
+
+#[utoipa::path(
+    tag = "savedSearch",
+    operation_id = "deleteSavedSearch",
+    params(
+        ("id", Path, description = "The ID of the search"),
+    ),
+    responses(
+        (status = 204, description = "The search was deleted"),
+        (status = 404, description = "The user doesn't have a search with this ID"),
+    )
+)]
+#[delete("/v2/savedSearch/{id}")]
+/// Delete a saved search of the current user
+async fn delete(
+    service: web::Data<SavedSearchService>,
+    id: web::Path<Uuid>,
+    user: UserDetails,
+    audit: Audit,
+) -> Result<impl Responder, Error> {
+    let id = id.into_inner();
+    Ok(match service.delete(&user.id, id).await? {
+        true => {
+            audit
+                .record(Event::new(Action::Delete, TargetKind::SavedSearch, id))
+                .await;
+            HttpResponse::NoContent().finish()
+        }
+        false => HttpResponse::NotFound().finish(),
+    })
+}
@@ -0,0 +1,6 @@
+//! Named searches of a user, for the SBOM, advisory and vulnerability lists.
+
+pub mod endpoints;
+pub mod model;
+pub mod service;
+pub mod test;
@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use trustify_entity::saved_search::{self, Kind};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+/// A saved search of the current user
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, ToSchema)]
+pub struct SavedSearch {
+    pub id: Uuid,
+    #[serde(flatten)]
+    pub search: NewSavedSearch,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub modified: OffsetDateTime,
+}
+
+impl From<saved_search::Model> for SavedSearch {
+    fn from(value: saved_search::Model) -> Self {
+        Self {
+            id: value.id,
+            search: NewSavedSearch {
+                name: value.name,
+                kind: value.kind,
+                q: value.q,
+                sort: value.sort,
+            },
+            created: value.created,
+            modified: value.modified,
+        }
+    }
+}
+
+/// The content of a saved search
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, ToSchema)]
+pub struct NewSavedSearch {
+    /// A name, unique for the user and kind of list
+    pub name: String,
+    /// The list the search applies to
+    pub kind: Kind,
+    /// The search, as used for the `q` query parameter of the list, which must accept it
+    #[serde(default)]
+    pub q: String,
+    /// The sort order, as used for the `sort` query parameter of the list, which must accept it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sort: Option<String>,
+}
+
+/// Query parameters for listing saved searches
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, IntoParams)]
+pub struct SavedSearchQuery {
+    /// Only return searches for this kind of list
+    #[serde(default)]
+    pub kind: Option<Kind>,
+}
@@ -0,0 +1,178 @@
+use crate::{
+    search::model::{NewSavedSearch, SavedSearch},
+    service::Error,
+};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter,
+    QueryOrder, prelude::Uuid,
+};
+use time::OffsetDateTime;
+use trustify_common::db::{
+    Database,
+    query::{Columns, Query},
+};
+use trustify_entity::saved_search::{self, Kind};
+use trustify_module_fundamental::{advisory, sbom, vulnerability};
+
+#[derive(Clone, Debug)]
+pub struct SavedSearchService {
+    db: Database,
+}
+
+impl SavedSearchService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// List the searches of a user, optionally only for one kind of list.
+    pub async fn list(&self, user_id: &str, kind: Option<Kind>) -> Result<Vec<SavedSearch>, Error> {
+        let mut query = saved_search::Entity::find()
+            .filter(saved_search::Column::UserId.eq(user_id))
+            .order_by_asc(saved_search::Column::Kind)
+            .order_by_asc(saved_search::Column::Name);
+
+        if let Some(kind) = kind {
+            query = query.filter(saved_search::Column::Kind.eq(kind));
+        }
+
+        Ok(query
+            .all(&self.db)
+            .await?
+            .into_iter()
+            .map(SavedSearch::from)
+            .collect())
+    }
+
+    pub async fn get(&self, user_id: &str, id: Uuid) -> Result<Option<SavedSearch>, Error> {
+        Ok(find(user_id, id, &self.db).await?.map(SavedSearch::from))
+    }
+
+    /// Save a new search for a user.
+    pub async fn create(
+        &self,
+        user_id: &str,
+        search: NewSavedSearch,
+    ) -> Result<SavedSearch, Error> {
+        let search = normalize(search)?;
+        ensure_unique_name(user_id, &search, None, &self.db).await?;
+
+        let now = OffsetDateTime::now_utc();
+        let model = saved_search::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            user_id: Set(user_id.to_string()),
+            name: Set(search.name),
+            kind: Set(search.kind),
+            q: Set(search.q),
+            sort: Set(search.sort),
+            created: Set(now),
+            modified: Set(now),
+        }
+        .insert(&self.db)
+        .await?;
+
+        Ok(model.into())
+    }
+
+    /// Replace the content of a search of a user.
+    ///
+    /// Returns `Ok(None)` if the user doesn't have a search with this ID.
+    pub async fn update(
+        &self,
+        user_id: &str,
+        id: Uuid,
+        search: NewSavedSearch,
+    ) -> Result<Option<SavedSearch>, Error> {
+        let search = normalize(search)?;
+
+        let Some(existing) = find(user_id, id, &self.db).await? else {
+            return Ok(None);
+        };
+
+        ensure_unique_name(user_id, &search, Some(id), &self.db).await?;
+
+        let mut model = saved_search::ActiveModel::from(existing);
+        model.name = Set(search.name);
+        model.kind = Set(search.kind);
+        model.q = Set(search.q);
+        model.sort = Set(search.sort);
+        model.modified = Set(OffsetDateTime::now_utc());
+
+        Ok(Some(model.update(&self.db).await?.into()))
+    }
+
+    /// Delete a search of a user.
+    ///
+    /// Returns `false` if the user doesn't have a search with this ID.
+    pub async fn delete(&self, user_id: &str, id: Uuid) -> Result<bool, Error> {
+        let result = saved_search::Entity::delete_many()
+            .filter(saved_search::Column::UserId.eq(user_id))
+            .filter(saved_search::Column::Id.eq(id))
+            .exec(&self.db)
+            .await?;
+
+        Ok(result.rows_affected > 0)
+    }
+}
+
+async fn find<C: ConnectionTrait>(
+    user_id: &str,
+    id: Uuid,
+    connection: &C,
+) -> Result<Option<saved_search::Model>, Error> {
+    Ok(saved_search::Entity::find_by_id(id)
+        .filter(saved_search::Column::UserId.eq(user_id))
+        .one(connection)
+        .await?)
+}
+
+fn normalize(mut search: NewSavedSearch) -> Result<NewSavedSearch, Error> {
+    search.name = search.name.trim().to_string();
+    if search.name.is_empty() {
+        return Err(Error::BadRequest("The name must not be empty".into()));
+    }
+
+    search.sort = search.sort.filter(|sort| !sort.trim().is_empty());
+
+    // the search must be usable with the list it applies to
+
+    Query {
+        q: search.q.clone(),
+        sort: search.sort.clone().unwrap_or_default(),
+    }
+    .validate(columns(search.kind))
+    .map_err(|err| Error::BadRequest(err.to_string()))?;
+
+    Ok(search)
+}
+
+/// The columns of the list a kind of search applies to
+fn columns(kind: Kind) -> Columns {
+    match kind {
+        Kind::Sbom => sbom::service::sbom::sbom_columns(),
+        Kind::Advisory => advisory::service::columns(),
+        Kind::Vulnerability => vulnerability::service::columns(),
+    }
+}
+
+async fn ensure_unique_name<C: ConnectionTrait>(
+    user_id: &str,
+    search: &NewSavedSearch,
+    id: Option<Uuid>,
+    connection: &C,
+) -> Result<(), Error> {
+    let mut query = saved_search::Entity::find()
+        .filter(saved_search::Column::UserId.eq(user_id))
+        .filter(saved_search::Column::Kind.eq(search.kind))
+        .filter(saved_search::Column::Name.eq(search.name.as_str()));
+    if let Some(id) = id {
+        query = query.filter(saved_search::Column::Id.ne(id));
+    }
+
+    match query.one(connection).await? {
+        Some(_) => Err(Error::Conflict(format!(
+            "A {} search named '{}' already exists",
+            search.kind, search.name
+        ))),
+        None => Ok(()),
+    }
+}
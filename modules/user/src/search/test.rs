@@ -0,0 +1,221 @@
+#![cfg(test)]
+
+use crate::{
+    search::{model::NewSavedSearch, service::SavedSearchService},
+    service::Error,
+};
+use actix_web::{App, http::StatusCode, test as actix};
+use serde_json::{Value, json};
+use test_context::test_context;
+use test_log::test;
+use trustify_entity::saved_search::Kind;
+use trustify_test_context::TrustifyContext;
+use trustify_test_context::auth::TestAuthentication;
+use utoipa_actix_web::AppExt;
+
+fn search(name: &str, kind: Kind, q: &str) -> NewSavedSearch {
+    NewSavedSearch {
+        name: name.into(),
+        kind,
+        q: q.into(),
+        sort: None,
+    }
+}
+
+#[test_context(TrustifyContext, skip_teardown)]
+#[test(tokio::test)]
+async fn lifecycle(ctx: TrustifyContext) -> anyhow::Result<()> {
+    let service = SavedSearchService::new(ctx.db.clone());
+
+    let critical = service
+        .create(
+            "user-a",
+            search("critical", Kind::Vulnerability, "average_severity=critical"),
+        )
+        .await?;
+    service
+        .create(
+            "user-a",
+            search("critical", Kind::Advisory, "title~critical"),
+        )
+        .await?;
+
+    // the same name for the same kind of list is a conflict, for other users it's fine
+
+    let result = service
+        .create("user-a", search(" critical ", Kind::Vulnerability, ""))
+        .await;
+    assert!(matches!(result, Err(Error::Conflict(_))));
+    service
+        .create("user-b", search("critical", Kind::Vulnerability, ""))
+        .await?;
+
+    // list
+
+    let searches = service.list("user-a", None).await?;
+    assert_eq!(searches.len(), 2);
+    let searches = service.list("user-a", Some(Kind::Vulnerability)).await?;
+    assert_eq!(searches, vec![critical.clone()]);
+
+    // update
+
+    let mut changed = critical.search.clone();
+    changed.q = "average_severity>=high".into();
+    changed.sort = Some("published:desc".into());
+    let updated = service
+        .update("user-a", critical.id, changed.clone())
+        .await?
+        .expect("must exist");
+    assert_eq!(updated.search, changed);
+
+    // other users can't see, change or delete it
+
+    assert_eq!(service.get("user-b", critical.id).await?, None);
+    assert_eq!(
+        service
+            .update("user-b", critical.id, changed.clone())
+            .await?,
+        None
+    );
+    assert!(!service.delete("user-b", critical.id).await?);
+
+    // delete
+
+    assert!(service.delete("user-a", critical.id).await?);
+    assert_eq!(service.get("user-a", critical.id).await?, None);
+
+    // no name
+
+    let result = service.create("user-a", search(" ", Kind::Sbom, "")).await;
+    assert!(matches!(result, Err(Error::BadRequest(_))));
+
+    // invalid for the kind of list
+
+    let result = service
+        .create(
+            "user-a",
+            search("invalid", Kind::Sbom, "average_severity=critical"),
+        )
+        .await;
+    assert!(matches!(result, Err(Error::BadRequest(_))));
+
+    let result = service
+        .create(
+            "user-a",
+            search("invalid", Kind::Advisory, "(title~critical"),
+        )
+        .await;
+    assert!(matches!(result, Err(Error::BadRequest(_))));
+
+    let mut unsortable = search("invalid", Kind::Vulnerability, "");
+    unsortable.sort = Some("nope:desc".into());
+    let result = service.create("user-a", unsortable).await;
+    assert!(matches!(result, Err(Error::BadRequest(_))));
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext, skip_teardown)]
+#[test(actix_web::test)]
+async fn endpoints(ctx: TrustifyContext) -> anyhow::Result<()> {
+    let db = ctx.db;
+    let app = actix::init_service(
+        App::new()
+            .into_utoipa_app()
+            .service(
+                utoipa_actix_web::scope("/api")
+                    .configure(|svc| crate::endpoints::configure(svc, Default::default(), db)),
+            )
+            .into_app(),
+    )
+    .await;
+
+    // create one
+
+    let req = actix::TestRequest::post()
+        .uri("/api/v2/savedSearch")
+        .set_json(json!({
+            "name": "mine",
+            "kind": "sbom",
+            "q": "name~quarkus",
+        }))
+        .to_request()
+        .test_auth("user-a");
+
+    let resp = actix::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let created: Value = actix::read_body_json(resp).await;
+    let id = created["id"].as_str().unwrap_or_default().to_string();
+
+    // again, same name
+
+    let req = actix::TestRequest::post()
+        .uri("/api/v2/savedSearch")
+        .set_json(json!({
+            "name": "mine",
+            "kind": "sbom",
+        }))
+        .to_request()
+        .test_auth("user-a");
+
+    let resp = actix::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::CONFLICT);
+
+    // invalid search
+
+    let req = actix::TestRequest::post()
+        .uri("/api/v2/savedSearch")
+        .set_json(json!({
+            "name": "broken",
+            "kind": "sbom",
+            "q": "nope=1",
+        }))
+        .to_request()
+        .test_auth("user-a");
+
+    let resp = actix::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    // list
+
+    let req = actix::TestRequest::get()
+        .uri("/api/v2/savedSearch?kind=sbom")
+        .to_request()
+        .test_auth("user-a");
+
+    let resp = actix::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let searches: Value = actix::read_body_json(resp).await;
+    assert_eq!(searches.as_array().map(Vec::len), Some(1));
+    assert_eq!(searches[0]["q"], json!("name~quarkus"));
+
+    let req = actix::TestRequest::get()
+        .uri("/api/v2/savedSearch?kind=advisory")
+        .to_request()
+        .test_auth("user-a");
+
+    let searches: Value = actix::call_and_read_body_json(&app, req).await;
+    assert_eq!(searches, json!([]));
+
+    // other users don't see it
+
+    let req = actix::TestRequest::get()
+        .uri(&format!("/api/v2/savedSearch/{id}"))
+        .to_request()
+        .test_auth("user-b");
+
+    let resp = actix::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    // delete
+
+    let req = actix::TestRequest::delete()
+        .uri(&format!("/api/v2/savedSearch/{id}"))
+        .to_request()
+        .test_auth("user-a");
+
+    let resp = actix::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+    Ok(())
+}
@@ -11,6 +11,12 @@ use trustify_entity::user_preferences;
 pub enum Error {
     #[error("mid air collision")]
     MidAirCollision,
+    #[error("bad request: {0}")]
+    BadRequest(String),
+    #[error("conflict: {0}")]
+    Conflict(String),
+    #[error("forbidden: {0}")]
+    Forbidden(String),
     #[error("database error: {0}")]
     Database(#[from] sea_orm::DbErr),
     #[error(transparent)]
@@ -25,6 +31,21 @@ impl ResponseError for Error {
                 message: self.to_string(),
                 details: None,
             }),
+            Error::BadRequest(_) => HttpResponse::BadRequest().json(ErrorInformation {
+                error: "BadRequest".into(),
+                message: self.to_string(),
+                details: None,
+            }),
+            Error::Conflict(_) => HttpResponse::Conflict().json(ErrorInformation {
+                error: "Conflict".into(),
+                message: self.to_string(),
+                details: None,
+            }),
+            Error::Forbidden(_) => HttpResponse::Forbidden().json(ErrorInformation {
+                error: "Forbidden".into(),
+                message: self.to_string(),
+                details: None,
+            }),
             _ => HttpResponse::InternalServerError().json(ErrorInformation {
                 error: "Internal".into(),
                 message: self.to_string(),
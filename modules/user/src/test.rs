@@ -138,7 +138,7 @@ async fn wrong_rev(ctx: TrustifyContext) {
             .into_utoipa_app()
             .service(
                 utoipa_actix_web::scope("/api")
-                    .configure(|svc| super::endpoints::configure(svc, db)),
+                    .configure(|svc| super::endpoints::configure(svc, Default::default(), db)),
             )
             .into_app(),
     )
@@ -0,0 +1,101 @@
+use crate::{
+    service::Error,
+    token::{
+        model::{ApiToken, CreateApiToken, CreatedApiToken},
+        service::ApiTokenService,
+    },
+};
+use actix_web::{HttpResponse, Responder, delete, get, post, web};
+use trustify_auth::authenticator::user::UserDetails;
+use trustify_common::db::Database;
+use trustify_module_audit::recorder::{Action, Audit, Event, TargetKind};
+use uuid::Uuid;
+
+/// mount the "token" endpoints
+pub fn configure(
+    svc: &mut utoipa_actix_web::service_config::ServiceConfig,
+    db: Database,
+    max_lifetime: std::time::Duration,
+) {
+    let max_lifetime = time::Duration::try_from(max_lifetime).unwrap_or(time::Duration::MAX);
+    svc.app_data(web::Data::new(
+        ApiTokenService::new(db).with_max_lifetime(max_lifetime),
+    ))
+    .service(create)
+    .service(list)
+    .service(revoke);
+}
+
+#[utoipa::path(
+    tag = "apiToken",
+    operation_id = "createApiToken",
+    request_body = CreateApiToken,
+    responses(
+        (status = 201, description = "The created token, the token value is only returned once", body = CreatedApiToken),
+        (status = 400, description = "The request was invalid, e.g. requesting permissions not granted to the user, or expiring after the maximum lifetime"),
+        (status = 403, description = "The request was authenticated using an API token"),
+    )
+)]
+#[post("/v2/token")]
+/// Create a new API token for the current user
+async fn create(
+    service: web::Data<ApiTokenService>,
+    user: UserDetails,
+    web::Json(request): web::Json<CreateApiToken>,
+    audit: Audit,
+) -> Result<impl Responder, Error> {
+    let created = service.create(&user, request).await?;
+    audit
+        .record(
+            Event::new(Action::Create, TargetKind::ApiToken, created.info.id).after(&created.info),
+        )
+        .await;
+    Ok(HttpResponse::Created().json(created))
+}
+
+#[utoipa::path(
+    tag = "apiToken",
+    operation_id = "listApiTokens",
+    responses(
+        (status = 200, description = "The API tokens of the current user", body = Vec<ApiToken>),
+    )
+)]
+#[get("/v2/token")]
+/// List the API tokens of the current user
+async fn list(
+    service: web::Data<ApiTokenService>,
+    user: UserDetails,
+) -> Result<impl Responder, Error> {
+    Ok(HttpResponse::Ok().json(service.list(&user.id).await?))
+}
+
+#[utoipa::path(
+    tag = "apiToken",
+    operation_id = "revokeApiToken",
+    params(
+        ("id", Path, description = "The ID of the token to revoke"),
+    ),
+    responses(
+        (status = 204, description = "The token was revoked"),
+        (status = 404, description = "The user doesn't have a token with this ID"),
+    )
+)]
+#[delete("/v2/token/{id}")]
+/// Revoke an API token of the current user
+async fn revoke(
+    service: web::Data<ApiTokenService>,
+    id: web::Path<Uuid>,
+    user: UserDetails,
+    audit: Audit,
+) -> Result<impl Responder, Error> {
+    let id = id.into_inner();
+    Ok(match service.revoke(&user.id, id).await? {
+        true => {
+            audit
+                .record(Event::new(Action::Delete, TargetKind::ApiToken, id))
+                .await;
+            HttpResponse::NoContent().finish()
+        }
+        false => HttpResponse::NotFound().finish(),
+    })
+}
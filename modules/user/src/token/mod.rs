@@ -0,0 +1,6 @@
+//! Personal API tokens, as an alternative to OIDC access tokens for scripts and pipelines.
+
+pub mod endpoints;
+pub mod model;
+pub mod service;
+pub mod test;
@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use trustify_entity::api_token;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Information about an API token. This never contains the token itself.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, ToSchema)]
+pub struct ApiToken {
+    pub id: Uuid,
+    /// A name, helping the user to identify the token
+    pub name: String,
+    /// The permissions granted to the token
+    pub permissions: Vec<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub expires: OffsetDateTime,
+    /// The last time the token was used to authenticate a request
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub last_used: Option<OffsetDateTime>,
+}
+
+impl From<api_token::Model> for ApiToken {
+    fn from(value: api_token::Model) -> Self {
+        Self {
+            id: value.id,
+            name: value.name,
+            permissions: value.permissions,
+            created: value.created,
+            expires: value.expires,
+            last_used: value.last_used,
+        }
+    }
+}
+
+/// A request to create a new API token
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, ToSchema)]
+pub struct CreateApiToken {
+    pub name: String,
+    /// The permissions to grant, must be a subset of the user's permissions
+    #[serde(default)]
+    pub permissions: Vec<String>,
+    /// When the token expires, must be in the future, and within the maximum lifetime of tokens
+    #[serde(with = "time::serde::rfc3339")]
+    pub expires: OffsetDateTime,
+}
+
+/// A newly created API token
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, ToSchema)]
+pub struct CreatedApiToken {
+    #[serde(flatten)]
+    pub info: ApiToken,
+    /// The token, to be used as bearer token. It is only returned once, and cannot be
+    /// retrieved later on.
+    pub token: String,
+}
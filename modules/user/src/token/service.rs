@@ -0,0 +1,288 @@
+use crate::{
+    service::Error,
+    token::model::{ApiToken, CreateApiToken, CreatedApiToken},
+};
+use async_trait::async_trait;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter,
+    QueryOrder, TransactionTrait, prelude::Uuid,
+};
+use sea_query::{Expr, OnConflict};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::HashMap,
+    str::FromStr,
+    sync::{Arc, Mutex},
+};
+use time::{Duration, OffsetDateTime};
+use trustify_auth::{
+    Permission,
+    authenticator::{
+        token::{API_TOKEN_PREFIX, ApiTokenValidator},
+        user::UserDetails,
+    },
+};
+use trustify_common::db::Database;
+use trustify_entity::{api_token, api_token_owner};
+
+/// The minimum time between two updates of the last use of a token
+const LAST_USED_INTERVAL: Duration = Duration::minutes(1);
+
+/// The default of the maximum time a token can be valid for
+pub const DEFAULT_MAX_LIFETIME: Duration = Duration::days(90);
+
+#[derive(Clone, Debug)]
+pub struct ApiTokenService {
+    db: Database,
+    /// The permissions of users, as last stored, to only write them when they change
+    owners: Arc<Mutex<HashMap<String, Vec<String>>>>,
+    /// The maximum time a new token can be valid for
+    max_lifetime: Duration,
+}
+
+impl ApiTokenService {
+    pub fn new(db: Database) -> Self {
+        Self {
+            db,
+            owners: Default::default(),
+            max_lifetime: DEFAULT_MAX_LIFETIME,
+        }
+    }
+
+    /// Set the maximum time a new token can be valid for
+    pub fn with_max_lifetime(mut self, max_lifetime: Duration) -> Self {
+        self.max_lifetime = max_lifetime;
+        self
+    }
+
+    /// Issue a new token for a user.
+    ///
+    /// The token will only carry the requested permissions, which must be granted to the user.
+    /// Tokens can't be used to create new tokens, as that would allow extending their lifetime.
+    pub async fn create(
+        &self,
+        user: &UserDetails,
+        request: CreateApiToken,
+    ) -> Result<CreatedApiToken, Error> {
+        if user.api_token {
+            return Err(Error::Forbidden(
+                "API tokens can't be used to create API tokens".into(),
+            ));
+        }
+
+        if request.name.trim().is_empty() {
+            return Err(Error::BadRequest("The name must not be empty".into()));
+        }
+
+        let now = OffsetDateTime::now_utc();
+
+        if request.expires <= now {
+            return Err(Error::BadRequest(
+                "The expiration must be in the future".into(),
+            ));
+        }
+
+        if request.expires > now + self.max_lifetime {
+            return Err(Error::BadRequest(format!(
+                "The expiration must not be more than {} days in the future",
+                self.max_lifetime.whole_days()
+            )));
+        }
+
+        for permission in &request.permissions {
+            if Permission::from_str(permission).is_err() {
+                return Err(Error::BadRequest(format!(
+                    "Unknown permission: {permission}"
+                )));
+            }
+            if !user.permissions.contains(permission) {
+                return Err(Error::BadRequest(format!(
+                    "Permission not granted to the user: {permission}"
+                )));
+            }
+        }
+
+        let token = format!(
+            "{API_TOKEN_PREFIX}{}",
+            hex::encode(rand::random::<[u8; 32]>())
+        );
+
+        let permissions = normalize(&user.permissions);
+        let tx = self.db.begin().await?;
+
+        store_owner(&user.id, &permissions, &tx).await?;
+
+        let model = api_token::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            user_id: Set(user.id.clone()),
+            name: Set(request.name),
+            token_hash: Set(hash(&token)),
+            permissions: Set(request.permissions),
+            created: Set(now),
+            expires: Set(request.expires),
+            last_used: Set(None),
+        }
+        .insert(&tx)
+        .await?;
+
+        tx.commit().await?;
+        self.remember_owner(&user.id, permissions);
+
+        Ok(CreatedApiToken {
+            info: model.into(),
+            token,
+        })
+    }
+
+    /// List all tokens of a user, including expired ones
+    pub async fn list(&self, user_id: &str) -> Result<Vec<ApiToken>, Error> {
+        Ok(api_token::Entity::find()
+            .filter(api_token::Column::UserId.eq(user_id))
+            .order_by_asc(api_token::Column::Created)
+            .all(&self.db)
+            .await?
+            .into_iter()
+            .map(ApiToken::from)
+            .collect())
+    }
+
+    /// Revoke a token of a user.
+    ///
+    /// Returns `false` if the user didn't have such a token.
+    pub async fn revoke(&self, user_id: &str, id: Uuid) -> Result<bool, Error> {
+        let result = api_token::Entity::delete_many()
+            .filter(api_token::Column::UserId.eq(user_id))
+            .filter(api_token::Column::Id.eq(id))
+            .exec(&self.db)
+            .await?;
+
+        Ok(result.rows_affected > 0)
+    }
+
+    /// Check if the permissions of a user are the ones last stored
+    fn is_known_owner(&self, user_id: &str, permissions: &[String]) -> bool {
+        self.owners
+            .lock()
+            .map(|owners| {
+                owners
+                    .get(user_id)
+                    .is_some_and(|known| known == permissions)
+            })
+            .unwrap_or_default()
+    }
+
+    fn remember_owner(&self, user_id: &str, permissions: Vec<String>) {
+        if let Ok(mut owners) = self.owners.lock() {
+            owners.insert(user_id.to_string(), permissions);
+        }
+    }
+}
+
+#[async_trait]
+impl ApiTokenValidator for ApiTokenService {
+    async fn validate_api_token(&self, token: &str) -> anyhow::Result<Option<UserDetails>> {
+        let now = OffsetDateTime::now_utc();
+
+        let Some(model) = api_token::Entity::find()
+            .filter(api_token::Column::TokenHash.eq(hash(token)))
+            .filter(api_token::Column::Expires.gt(now))
+            .one(&self.db)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        // the token can't have more permissions than its owner currently has
+
+        let owner = api_token_owner::Entity::find_by_id(&model.user_id)
+            .one(&self.db)
+            .await?
+            .map(|owner| owner.permissions)
+            .unwrap_or_default();
+
+        if model
+            .last_used
+            .is_none_or(|last_used| now - last_used >= LAST_USED_INTERVAL)
+        {
+            api_token::Entity::update_many()
+                .col_expr(api_token::Column::LastUsed, Expr::value(now))
+                .filter(api_token::Column::Id.eq(model.id))
+                .exec(&self.db)
+                .await?;
+        }
+
+        Ok(Some(UserDetails {
+            id: model.user_id,
+            permissions: model
+                .permissions
+                .into_iter()
+                .filter(|permission| owner.contains(permission))
+                .collect(),
+            api_token: true,
+        }))
+    }
+
+    async fn observe_user(&self, user: &UserDetails) -> anyhow::Result<()> {
+        let permissions = normalize(&user.permissions);
+        if self.is_known_owner(&user.id, &permissions) {
+            return Ok(());
+        }
+
+        // only track users who own tokens, those got stored when creating the first one
+
+        api_token_owner::Entity::update_many()
+            .col_expr(
+                api_token_owner::Column::Permissions,
+                Expr::value(permissions.clone()),
+            )
+            .col_expr(
+                api_token_owner::Column::Updated,
+                Expr::value(OffsetDateTime::now_utc()),
+            )
+            .filter(api_token_owner::Column::UserId.eq(&user.id))
+            .exec(&self.db)
+            .await?;
+
+        self.remember_owner(&user.id, permissions);
+
+        Ok(())
+    }
+}
+
+/// Store the current permissions of a token owner
+async fn store_owner(
+    user_id: &str,
+    permissions: &[String],
+    connection: &impl ConnectionTrait,
+) -> Result<(), Error> {
+    api_token_owner::Entity::insert(api_token_owner::ActiveModel {
+        user_id: Set(user_id.to_string()),
+        permissions: Set(permissions.to_vec()),
+        updated: Set(OffsetDateTime::now_utc()),
+    })
+    .on_conflict(
+        OnConflict::column(api_token_owner::Column::UserId)
+            .update_columns([
+                api_token_owner::Column::Permissions,
+                api_token_owner::Column::Updated,
+            ])
+            .to_owned(),
+    )
+    .exec(connection)
+    .await?;
+
+    Ok(())
+}
+
+/// Sort and de-duplicate permissions, so that they can be compared
+fn normalize(permissions: &[String]) -> Vec<String> {
+    let mut permissions = permissions.to_vec();
+    permissions.sort_unstable();
+    permissions.dedup();
+    permissions
+}
+
+/// Tokens are only stored hashed. As they are random, and long, there's no need for a salt.
+fn hash(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
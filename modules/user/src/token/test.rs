@@ -0,0 +1,306 @@
+#![cfg(test)]
+
+use crate::{
+    service::Error,
+    token::{model::CreateApiToken, service::ApiTokenService},
+};
+use actix_web::{App, http::StatusCode, test as actix};
+use serde_json::{Value, json};
+use test_context::test_context;
+use test_log::test;
+use time::{Duration, OffsetDateTime};
+use trustify_auth::authenticator::{
+    token::{ApiTokenValidator, is_api_token},
+    user::UserDetails,
+};
+use trustify_test_context::TrustifyContext;
+use trustify_test_context::auth::TestAuthentication;
+use utoipa_actix_web::AppExt;
+
+fn user(id: &str) -> UserDetails {
+    UserDetails {
+        id: id.into(),
+        permissions: vec!["read.sbom".into(), "create.sbom".into()],
+        api_token: false,
+    }
+}
+
+fn request(permissions: &[&str], expires: OffsetDateTime) -> CreateApiToken {
+    CreateApiToken {
+        name: "ci".into(),
+        permissions: permissions.iter().map(ToString::to_string).collect(),
+        expires,
+    }
+}
+
+#[test_context(TrustifyContext, skip_teardown)]
+#[test(tokio::test)]
+async fn lifecycle(ctx: TrustifyContext) -> anyhow::Result<()> {
+    let service = ApiTokenService::new(ctx.db.clone());
+    let tomorrow = OffsetDateTime::now_utc() + Duration::days(1);
+
+    // create a token with a subset of the permissions
+
+    let created = service
+        .create(&user("user-a"), request(&["read.sbom"], tomorrow))
+        .await?;
+    assert!(is_api_token(&created.token));
+
+    // it must authenticate as the user, with the permissions of the token
+
+    let details = service.validate_api_token(&created.token).await?;
+    assert_eq!(
+        details,
+        Some(UserDetails {
+            id: "user-a".into(),
+            permissions: vec!["read.sbom".into()],
+            api_token: true,
+        })
+    );
+
+    // using it gets recorded
+
+    let tokens = service.list("user-a").await?;
+    assert_eq!(tokens.len(), 1);
+    assert_eq!(tokens[0].id, created.info.id);
+    assert!(tokens[0].last_used.is_some());
+
+    // other users don't see it, and can't revoke it
+
+    assert!(service.list("user-b").await?.is_empty());
+    assert!(!service.revoke("user-b", created.info.id).await?);
+
+    // revoke it, after that it must no longer be accepted
+
+    assert!(service.revoke("user-a", created.info.id).await?);
+    assert_eq!(service.validate_api_token(&created.token).await?, None);
+    assert!(service.list("user-a").await?.is_empty());
+
+    // unknown tokens are not accepted
+
+    assert_eq!(service.validate_api_token("trustify_foo").await?, None);
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext, skip_teardown)]
+#[test(tokio::test)]
+async fn invalid_requests(ctx: TrustifyContext) -> anyhow::Result<()> {
+    let service = ApiTokenService::new(ctx.db.clone());
+    let tomorrow = OffsetDateTime::now_utc() + Duration::days(1);
+    let yesterday = OffsetDateTime::now_utc() - Duration::days(1);
+
+    // permission not granted to the user
+
+    let result = service
+        .create(&user("user-a"), request(&["delete.sbom"], tomorrow))
+        .await;
+    assert!(matches!(result, Err(Error::BadRequest(_))));
+
+    // not a permission at all
+
+    let result = service
+        .create(&user("user-a"), request(&["foo"], tomorrow))
+        .await;
+    assert!(matches!(result, Err(Error::BadRequest(_))));
+
+    // already expired
+
+    let result = service
+        .create(&user("user-a"), request(&["read.sbom"], yesterday))
+        .await;
+    assert!(matches!(result, Err(Error::BadRequest(_))));
+
+    // valid for longer than allowed
+
+    let next_year = OffsetDateTime::now_utc() + Duration::days(365);
+    let result = service
+        .create(&user("user-a"), request(&["read.sbom"], next_year))
+        .await;
+    assert!(matches!(result, Err(Error::BadRequest(_))));
+
+    let result = service
+        .clone()
+        .with_max_lifetime(Duration::hours(1))
+        .create(&user("user-a"), request(&["read.sbom"], tomorrow))
+        .await;
+    assert!(matches!(result, Err(Error::BadRequest(_))));
+
+    assert!(service.list("user-a").await?.is_empty());
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext, skip_teardown)]
+#[test(tokio::test)]
+async fn no_tokens_from_tokens(ctx: TrustifyContext) -> anyhow::Result<()> {
+    let service = ApiTokenService::new(ctx.db.clone());
+    let tomorrow = OffsetDateTime::now_utc() + Duration::days(1);
+
+    let created = service
+        .create(&user("user-a"), request(&["read.sbom"], tomorrow))
+        .await?;
+    let details = service
+        .validate_api_token(&created.token)
+        .await?
+        .expect("must be valid");
+
+    // a token must not be able to issue a new one
+
+    let result = service
+        .create(&details, request(&["read.sbom"], tomorrow))
+        .await;
+    assert!(matches!(result, Err(Error::Forbidden(_))));
+    assert_eq!(service.list("user-a").await?.len(), 1);
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext, skip_teardown)]
+#[test(tokio::test)]
+async fn limited_to_owner(ctx: TrustifyContext) -> anyhow::Result<()> {
+    let service = ApiTokenService::new(ctx.db.clone());
+    let tomorrow = OffsetDateTime::now_utc() + Duration::days(1);
+
+    let created = service
+        .create(
+            &user("user-a"),
+            request(&["read.sbom", "create.sbom"], tomorrow),
+        )
+        .await?;
+
+    // the owner loses a permission
+
+    service
+        .observe_user(&UserDetails {
+            id: "user-a".into(),
+            permissions: vec!["read.sbom".into()],
+            api_token: false,
+        })
+        .await?;
+
+    let details = service.validate_api_token(&created.token).await?;
+    assert_eq!(
+        details.map(|details| details.permissions),
+        Some(vec!["read.sbom".into()])
+    );
+
+    // and gets it back
+
+    service.observe_user(&user("user-a")).await?;
+
+    let details = service.validate_api_token(&created.token).await?;
+    assert_eq!(
+        details.map(|details| details.permissions),
+        Some(vec!["read.sbom".into(), "create.sbom".into()])
+    );
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext, skip_teardown)]
+#[test(tokio::test)]
+async fn last_used_throttled(ctx: TrustifyContext) -> anyhow::Result<()> {
+    let service = ApiTokenService::new(ctx.db.clone());
+    let tomorrow = OffsetDateTime::now_utc() + Duration::days(1);
+
+    let created = service
+        .create(&user("user-a"), request(&["read.sbom"], tomorrow))
+        .await?;
+
+    service.validate_api_token(&created.token).await?;
+    let first = service.list("user-a").await?[0].last_used;
+    assert!(first.is_some());
+
+    // using it again right away must not update it
+
+    service.validate_api_token(&created.token).await?;
+    assert_eq!(service.list("user-a").await?[0].last_used, first);
+
+    Ok(())
+}
+
+#[test_context(TrustifyContext, skip_teardown)]
+#[test(actix_web::test)]
+async fn endpoints(ctx: TrustifyContext) -> anyhow::Result<()> {
+    let db = ctx.db;
+    let app = actix::init_service(
+        App::new()
+            .into_utoipa_app()
+            .service(
+                utoipa_actix_web::scope("/api")
+                    .configure(|svc| crate::endpoints::configure(svc, Default::default(), db)),
+            )
+            .into_app(),
+    )
+    .await;
+
+    // create one
+
+    let req = actix::TestRequest::post()
+        .uri("/api/v2/token")
+        .set_json(json!({
+            "name": "ci",
+            "permissions": ["read.sbom"],
+            "expires": "2999-01-01T00:00:00Z",
+        }))
+        .to_request()
+        .test_auth_details(user("user-a"));
+
+    let resp = actix::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let created: Value = actix::read_body_json(resp).await;
+    assert!(created["token"].as_str().is_some_and(is_api_token));
+    let id = created["id"].as_str().unwrap_or_default().to_string();
+
+    // list, must not contain the token itself
+
+    let req = actix::TestRequest::get()
+        .uri("/api/v2/token")
+        .to_request()
+        .test_auth("user-a");
+
+    let resp = actix::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let tokens: Value = actix::read_body_json(resp).await;
+    assert_eq!(tokens.as_array().map(Vec::len), Some(1));
+    assert_eq!(tokens[0]["id"], json!(id));
+    assert_eq!(tokens[0]["token"], Value::Null);
+
+    // request permissions the user doesn't have
+
+    let req = actix::TestRequest::post()
+        .uri("/api/v2/token")
+        .set_json(json!({
+            "name": "ci",
+            "permissions": ["read.sbom"],
+            "expires": "2999-01-01T00:00:00Z",
+        }))
+        .to_request()
+        .test_auth("user-a");
+
+    let resp = actix::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    // revoke
+
+    let req = actix::TestRequest::delete()
+        .uri(&format!("/api/v2/token/{id}"))
+        .to_request()
+        .test_auth("user-a");
+
+    let resp = actix::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+    // again, must be gone
+
+    let req = actix::TestRequest::delete()
+        .uri(&format!("/api/v2/token/{id}"))
+        .to_request()
+        .test_auth("user-a");
+
+    let resp = actix::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
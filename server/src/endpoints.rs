@@ -52,7 +52,7 @@ pub async fn info(req: HttpRequest, auth: Option<web::Data<Authenticator>>) -> H
                 .and_then(|auth| auth.strip_prefix("Bearer "))
             {
                 // enable details if we have a valid token
-                auth.validate_token(&bearer).await.is_ok()
+                auth.authenticate(&bearer).await.is_ok()
             } else {
                 // no token that we can use, disable details
                 false
@@ -0,0 +1,36 @@
+use std::time::Duration;
+use trustify_module_fundamental::{
+    purl::service::PurlService, retention::service::RetentionService,
+};
+use trustify_module_importer::job::Job;
+
+#[derive(Clone, Debug, serde::Serialize)]
+struct GcReport {
+    purls_removed: u64,
+}
+
+/// The maintenance jobs run by the importer server, optionally overriding their configured periods
+pub fn builtin(gc_interval: Option<Duration>, retention_interval: Option<Duration>) -> Vec<Job> {
+    vec![
+        Job::new(
+            "gc",
+            "Remove PURLs which are no longer referenced by any SBOM or advisory",
+            Duration::from_secs(60 * 60),
+            |db| async move {
+                let purls_removed = PurlService::new().gc_purls(&db).await?;
+                Ok::<_, anyhow::Error>(GcReport { purls_removed })
+            },
+        )
+        .with_period(gc_interval),
+        Job::new(
+            "retention",
+            "Execute the enabled retention policies",
+            Duration::from_secs(24 * 60 * 60),
+            |db| async move {
+                let report = RetentionService::new(db).apply(false).await?;
+                Ok::<_, anyhow::Error>(report)
+            },
+        )
+        .with_period(retention_interval),
+    ]
+}
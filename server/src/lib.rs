@@ -4,6 +4,7 @@
 #[cfg(feature = "garage-door")]
 mod embedded_oidc;
 mod endpoints;
+mod jobs;
 mod sample_data;
 
 pub use sample_data::sample_data;
@@ -44,6 +44,7 @@ use trustify_module_storage::{
     service::{dispatch::DispatchBackend, fs::FileSystemBackend, s3::S3Backend},
 };
 use trustify_module_ui::{UI, endpoints::UiResources};
+use trustify_module_user::token::service::ApiTokenService;
 use utoipa::{
     OpenApi,
     openapi::{Info, License},
@@ -93,6 +94,10 @@ pub struct Run {
     )]
     pub dataset_entry_limit: BinaryByteSize,
 
+    /// The maximum time a new API token can be valid for.
+    #[arg(long, env = "TRUSTD_API_TOKEN_MAX_LIFETIME", default_value = "90d")]
+    pub api_token_max_lifetime: humantime::Duration,
+
     // flattened commands must go last
     //
     /// Analysis configuration
@@ -182,6 +187,7 @@ struct InitData {
 pub(crate) struct ModuleConfig {
     fundamental: trustify_module_fundamental::endpoints::Config,
     ingestor: trustify_module_ingestor::endpoints::Config,
+    user: trustify_module_user::endpoints::Config,
 }
 
 impl Run {
@@ -216,8 +222,7 @@ impl InitData {
         };
 
         let (authn, authz) = run.auth.split(auth_devmode)?.unzip();
-        let authenticator: Option<Arc<Authenticator>> =
-            Authenticator::from_config(authn).await?.map(Arc::new);
+        let authenticator = Authenticator::from_config(authn).await?;
         let authorizer = Authorizer::new(authz);
 
         if authenticator.is_none() {
@@ -237,6 +242,11 @@ impl InitData {
             db.migrate().await?;
         }
 
+        // accept API tokens, issued by the user module, in addition to OIDC tokens
+        let authenticator: Option<Arc<Authenticator>> = authenticator.map(|authenticator| {
+            Arc::new(authenticator.with_api_tokens(Arc::new(ApiTokenService::new(db.clone()))))
+        });
+
         if run.devmode || run.sample_data {
             sample_data(db.clone()).await?;
         }
@@ -292,6 +302,9 @@ impl InitData {
             ingestor: trustify_module_ingestor::endpoints::Config {
                 dataset_entry_limit: run.dataset_entry_limit.into(),
             },
+            user: trustify_module_user::endpoints::Config {
+                api_token_max_lifetime: run.api_token_max_lifetime.into(),
+            },
         };
 
         Ok(InitData {
@@ -386,10 +399,12 @@ pub(crate) struct Config {
 
 pub(crate) fn configure(svc: &mut utoipa_actix_web::service_config::ServiceConfig, config: Config) {
     let Config {
-        config: ModuleConfig {
-            ingestor,
-            fundamental,
-        },
+        config:
+            ModuleConfig {
+                ingestor,
+                fundamental,
+                user,
+            },
         db,
         storage,
         auth,
@@ -449,7 +464,8 @@ pub(crate) fn configure(svc: &mut utoipa_actix_web::service_config::ServiceConfi
                         analysis.clone(),
                     );
                     trustify_module_analysis::endpoints::configure(svc, db.clone(), analysis);
-                    trustify_module_user::endpoints::configure(svc, db.clone());
+                    trustify_module_audit::endpoints::configure(svc, db.clone());
+                    trustify_module_user::endpoints::configure(svc, user, db.clone());
                 }),
         );
 }
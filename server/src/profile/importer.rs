@@ -1,4 +1,4 @@
-use crate::{endpoints, sample_data};
+use crate::{endpoints, jobs, sample_data};
 use actix_web::{
     HttpRequest, HttpResponse, Responder, Result,
     body::MessageBody,
@@ -33,7 +33,7 @@ use trustify_infrastructure::{
     otel::Tracing,
 };
 use trustify_module_graphql::RootQuery;
-use trustify_module_importer::server::importer;
+use trustify_module_importer::{job::server::scheduler, server::importer};
 use trustify_module_ingestor::graph::Graph;
 use trustify_module_storage::{
     config::{StorageConfig, StorageStrategy},
@@ -61,6 +61,14 @@ pub struct Run {
     )]
     pub concurrency: usize,
 
+    /// The interval in which the unreferenced PURLs get removed, overriding the configured one
+    #[arg(long, id = "gc_interval", env = "GC_INTERVAL")]
+    pub gc_interval: Option<humantime::Duration>,
+
+    /// The interval in which the retention policies get executed, overriding the configured one
+    #[arg(long, id = "retention_interval", env = "RETENTION_INTERVAL")]
+    pub retention_interval: Option<humantime::Duration>,
+
     // flattened commands must go last
     //
     /// Database configuration
@@ -83,6 +91,8 @@ struct InitData {
     tracing: Tracing,
     working_dir: Option<PathBuf>,
     concurrency: usize,
+    gc_interval: Option<Duration>,
+    retention_interval: Option<Duration>,
 }
 
 impl Run {
@@ -137,6 +147,8 @@ impl InitData {
             storage,
             working_dir: run.working_dir,
             concurrency: run.concurrency,
+            gc_interval: run.gc_interval.map(Into::into),
+            retention_interval: run.retention_interval.map(Into::into),
         })
     }
 
@@ -144,6 +156,12 @@ impl InitData {
         let db = self.db;
         let storage = self.storage;
 
+        let scheduler = scheduler(
+            db.clone(),
+            jobs::builtin(self.gc_interval, self.retention_interval),
+        )
+        .boxed_local();
+
         let importer = async {
             importer(
                 db,
@@ -156,7 +174,7 @@ impl InitData {
         }
         .boxed_local();
 
-        let tasks = vec![importer];
+        let tasks = vec![importer, scheduler];
 
         let (result, _, _) = futures::future::select_all(tasks).await;
 
@@ -1,8 +1,10 @@
 use std::{collections::HashSet, time::Duration};
 use trustify_common::config::Database;
 use trustify_module_importer::model::{
-    ClearlyDefinedImporter, ClearlyDefinedPackageType, CveImporter, CweImporter,
-    DEFAULT_SOURCE_CLEARLY_DEFINED_CURATION, DEFAULT_SOURCE_CVEPROJECT, DEFAULT_SOURCE_CWE_CATALOG,
+    ClearlyDefinedImporter, ClearlyDefinedPackageType, CveImporter, CweImporter, DEFAULT_PATH_GHSA,
+    DEFAULT_PATH_RUSTSEC, DEFAULT_SOURCE_CLEARLY_DEFINED_CURATION, DEFAULT_SOURCE_CVEPROJECT,
+    DEFAULT_SOURCE_CWE_CATALOG, DEFAULT_SOURCE_GHSA, DEFAULT_SOURCE_GITLAB, DEFAULT_SOURCE_RUSTSEC,
+    GhsaImporter, GitlabImporter, RustsecImporter,
 };
 use trustify_module_importer::{
     model::{
@@ -144,6 +146,55 @@ async fn add_cwe(importer: &ImporterService, name: &str, description: &str) -> a
     .await
 }
 
+fn common(description: &str) -> CommonImporter {
+    CommonImporter {
+        disabled: true,
+        period: Duration::from_secs(300),
+        description: Some(description.into()),
+        labels: Default::default(),
+    }
+}
+
+async fn add_advisory_databases(importer: &ImporterService) -> anyhow::Result<()> {
+    add(
+        importer,
+        "ghsa",
+        ImporterConfiguration::Ghsa(GhsaImporter {
+            common: common("GitHub Advisory Database (reviewed)"),
+            source: DEFAULT_SOURCE_GHSA.into(),
+            branch: None,
+            path: DEFAULT_PATH_GHSA.into(),
+            years: Default::default(),
+            start_year: None,
+        }),
+    )
+    .await?;
+
+    add(
+        importer,
+        "gitlab",
+        ImporterConfiguration::Gitlab(GitlabImporter {
+            common: common("GitLab Advisory Database (community)"),
+            source: DEFAULT_SOURCE_GITLAB.into(),
+            branch: None,
+            path: None,
+        }),
+    )
+    .await?;
+
+    add(
+        importer,
+        "rustsec",
+        ImporterConfiguration::Rustsec(RustsecImporter {
+            common: common("RustSec Advisory Database"),
+            source: DEFAULT_SOURCE_RUSTSEC.into(),
+            branch: None,
+            path: DEFAULT_PATH_RUSTSEC.into(),
+        }),
+    )
+    .await
+}
+
 pub async fn sample_data(db: trustify_common::db::Database) -> anyhow::Result<()> {
     let importer = ImporterService::new(db);
 
@@ -300,5 +351,7 @@ pub async fn sample_data(db: trustify_common::db::Database) -> anyhow::Result<()
     )
     .await?;
 
+    add_advisory_databases(&importer).await?;
+
     Ok(())
 }
@@ -7,7 +7,12 @@ use tokio::select;
 use tokio::task::{LocalSet, spawn_local};
 
 mod db;
+#[cfg(feature = "ai")]
+mod mcp;
 mod openapi;
+mod reprocess;
+mod snapshot;
+mod storage;
 
 #[allow(clippy::large_enum_variant)]
 #[derive(clap::Subcommand, Debug)]
@@ -20,6 +25,13 @@ pub enum Command {
     Db(db::Run),
     /// Access OpenAPI related information of the API server
     Openapi(openapi::Run),
+    /// Export or import snapshots of all documents
+    Snapshot(snapshot::Run),
+    /// Reprocess stored documents, loading them again
+    Reprocess(reprocess::Run),
+    /// Serve the AI tools as an MCP server on stdio
+    #[cfg(feature = "ai")]
+    Mcp(mcp::Run),
 }
 
 #[derive(clap::Parser, Debug)]
@@ -41,6 +53,10 @@ impl Trustd {
             Some(Command::Importer(run)) => run.run().await,
             Some(Command::Db(run)) => run.run().await,
             Some(Command::Openapi(run)) => run.run().await,
+            Some(Command::Snapshot(run)) => run.run().await,
+            Some(Command::Reprocess(run)) => run.run().await,
+            #[cfg(feature = "ai")]
+            Some(Command::Mcp(run)) => run.run().await,
             None => pm_mode().await,
         }
     }
@@ -0,0 +1,39 @@
+use std::process::ExitCode;
+use tracing_subscriber::EnvFilter;
+use trustify_common::{config::Database, db};
+use trustify_module_analysis::{config::AnalysisConfig, service::AnalysisService};
+use trustify_module_fundamental::ai::service::{mcp::McpServer, tools};
+
+/// Serve the AI tools as a Model Context Protocol (MCP) server, using the stdio transport.
+///
+/// This is intended to be launched by an MCP client, which talks to the server using stdin and
+/// stdout. All logging goes to stderr.
+#[derive(clap::Args, Debug)]
+pub struct Run {
+    #[command(flatten)]
+    pub(crate) database: Database,
+
+    #[command(flatten)]
+    pub(crate) analysis: AnalysisConfig,
+}
+
+impl Run {
+    pub async fn run(self) -> anyhow::Result<ExitCode> {
+        // stdout is reserved for the protocol, so we can't use the default tracing setup
+        let _ = tracing_subscriber::fmt()
+            .with_env_filter(
+                EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("warn")),
+            )
+            .with_writer(std::io::stderr)
+            .try_init();
+
+        let db = db::Database::new(&self.database).await?;
+        let server = McpServer::new(tools::new(db, AnalysisService::new(self.analysis)));
+
+        server
+            .serve_stdio(tokio::io::stdin(), tokio::io::stdout())
+            .await?;
+
+        Ok(ExitCode::SUCCESS)
+    }
+}
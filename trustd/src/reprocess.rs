@@ -0,0 +1,102 @@
+use crate::{snapshot::parse_rfc3339, storage::storage};
+use std::process::ExitCode;
+use time::OffsetDateTime;
+use trustify_common::{config::Database, db};
+use trustify_entity::{labels::Labels, reprocess::Filter};
+use trustify_infrastructure::otel::{Tracing, init_tracing};
+use trustify_module_ingestor::{graph::Graph, service::IngestorService};
+use trustify_module_storage::config::StorageConfig;
+use uuid::Uuid;
+
+/// Reprocess stored documents, loading them again, e.g. after a loader got fixed
+#[derive(clap::Args, Debug)]
+pub struct Run {
+    /// Resume an interrupted run, instead of creating a new one
+    #[arg(long, conflicts_with_all = ["format", "since", "until", "label"])]
+    pub(crate) resume: Option<Uuid>,
+
+    /// Only reprocess documents of this format, like `csaf` or `spdx`
+    #[arg(long)]
+    pub(crate) format: Vec<String>,
+
+    /// Only reprocess documents ingested at or after this time (RFC 3339)
+    #[arg(long, value_parser = parse_rfc3339)]
+    pub(crate) since: Option<OffsetDateTime>,
+
+    /// Only reprocess documents ingested before this time (RFC 3339)
+    #[arg(long, value_parser = parse_rfc3339)]
+    pub(crate) until: Option<OffsetDateTime>,
+
+    /// Only reprocess documents having this label, in the form of `key=value`
+    #[arg(long, value_parser = parse_label)]
+    pub(crate) label: Vec<(String, String)>,
+
+    // flattened commands must go last
+    //
+    /// Database configuration
+    #[command(flatten)]
+    pub(crate) database: Database,
+
+    /// Location of the storage
+    #[command(flatten)]
+    pub(crate) storage: StorageConfig,
+}
+
+fn parse_label(value: &str) -> Result<(String, String), String> {
+    match value.split_once('=') {
+        Some((key, value)) => Ok((key.to_string(), value.to_string())),
+        None => Err(format!("label must be in the form of `key=value`: {value}")),
+    }
+}
+
+impl Run {
+    pub async fn run(self) -> anyhow::Result<ExitCode> {
+        init_tracing("reprocess-run", Tracing::Disabled);
+
+        let db = db::Database::new(&self.database).await?;
+        let storage = storage(self.storage).await?;
+        let service = IngestorService::new(Graph::new(db), storage, None);
+        let reprocessor = service.reprocessor();
+
+        let id = match self.resume {
+            Some(id) => id,
+            None => {
+                let filter = Filter {
+                    formats: self.format,
+                    since: self.since,
+                    until: self.until,
+                    labels: Labels(self.label.into_iter().collect()),
+                };
+                reprocessor.create(filter).await?.id
+            }
+        };
+        reprocessor.claim(id).await?;
+
+        log::info!("Reprocessing documents, run: {id}");
+
+        let run = reprocessor
+            .run(id, |run| {
+                let done = run.processed + run.skipped + run.failed;
+                if done % 100 == 0 {
+                    log::info!("Reprocessed {done} of {} documents", run.total);
+                }
+            })
+            .await?;
+
+        for failure in &run.failures {
+            log::warn!("Failed to reprocess {}: {}", failure.sha256, failure.error);
+        }
+        log::info!(
+            "Reprocessed {} documents, skipped {}, failed {}",
+            run.processed,
+            run.skipped,
+            run.failed
+        );
+
+        Ok(if run.failed == 0 {
+            ExitCode::SUCCESS
+        } else {
+            ExitCode::FAILURE
+        })
+    }
+}
@@ -0,0 +1,119 @@
+use crate::storage::storage;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use time::{OffsetDateTime, format_description::well_known::Rfc3339};
+use trustify_common::{config::Database, db, model::BinaryByteSize};
+use trustify_infrastructure::otel::{Tracing, init_tracing};
+use trustify_module_ingestor::{graph::Graph, service::IngestorService};
+use trustify_module_storage::config::StorageConfig;
+
+/// Export and import snapshots of all documents, e.g. to mirror an instance into a
+/// disconnected environment
+#[derive(clap::Args, Debug)]
+pub struct Run {
+    #[command(subcommand)]
+    pub(crate) command: Command,
+
+    // flattened commands must go last
+    //
+    /// Database configuration
+    #[command(flatten)]
+    pub(crate) database: Database,
+
+    /// Location of the storage
+    #[command(flatten)]
+    pub(crate) storage: StorageConfig,
+}
+
+#[derive(clap::Subcommand, Debug)]
+pub enum Command {
+    /// Export a snapshot into a file
+    Export(Export),
+    /// Import a snapshot from a file
+    Import(Import),
+}
+
+#[derive(clap::Args, Debug)]
+pub struct Export {
+    /// The file the snapshot should be written to
+    pub file: PathBuf,
+
+    /// Only export documents ingested after this time (RFC 3339), like the creation time of a
+    /// previous snapshot
+    #[arg(long, value_parser = parse_rfc3339)]
+    pub since: Option<OffsetDateTime>,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct Import {
+    /// The snapshot file to import
+    pub file: PathBuf,
+
+    /// The size limit of a document in the snapshot
+    #[arg(long, env = "TRUSTD_DATASET_ENTRY_LIMIT", default_value = "1GiB")]
+    pub entry_limit: BinaryByteSize,
+}
+
+pub(crate) fn parse_rfc3339(value: &str) -> Result<OffsetDateTime, time::error::Parse> {
+    OffsetDateTime::parse(value, &Rfc3339)
+}
+
+impl Run {
+    pub async fn run(self) -> anyhow::Result<ExitCode> {
+        init_tracing("snapshot-run", Tracing::Disabled);
+
+        let db = db::Database::new(&self.database).await?;
+        let storage = storage(self.storage).await?;
+        let service = IngestorService::new(Graph::new(db), storage, None);
+
+        use Command::*;
+        match self.command {
+            Export(export) => export.run(service).await,
+            Import(import) => import.run(service).await,
+        }
+    }
+}
+
+impl Export {
+    async fn run(self, service: IngestorService) -> anyhow::Result<ExitCode> {
+        let file = BufWriter::new(File::create(&self.file)?);
+        let manifest = service.export_snapshot(self.since, file).await?;
+
+        log::info!(
+            "Exported {} documents to {}, created: {}",
+            manifest.documents.len(),
+            self.file.display(),
+            manifest.created.format(&Rfc3339)?
+        );
+
+        Ok(ExitCode::SUCCESS)
+    }
+}
+
+impl Import {
+    async fn run(self, service: IngestorService) -> anyhow::Result<ExitCode> {
+        let file = BufReader::new(File::open(&self.file)?);
+        let result = service
+            .import_snapshot(file, self.entry_limit.into())
+            .await?;
+
+        for warning in &result.warnings {
+            log::warn!("{warning}");
+        }
+        log::info!(
+            "Imported {} documents, skipped {} existing documents, deleted {}, relabeled {}",
+            result.imported.len(),
+            result.skipped.len(),
+            result.deleted.len(),
+            result.relabeled.len()
+        );
+
+        Ok(if result.warnings.is_empty() {
+            ExitCode::SUCCESS
+        } else {
+            ExitCode::FAILURE
+        })
+    }
+}
@@ -0,0 +1,20 @@
+use std::path::PathBuf;
+use trustify_module_storage::{
+    config::{StorageConfig, StorageStrategy},
+    service::{dispatch::DispatchBackend, fs::FileSystemBackend, s3::S3Backend},
+};
+
+/// Create the storage backend from its configuration
+pub async fn storage(config: StorageConfig) -> anyhow::Result<DispatchBackend> {
+    Ok(match config.storage_strategy {
+        StorageStrategy::Fs => {
+            let storage = config
+                .fs_path
+                .unwrap_or_else(|| PathBuf::from("./.trustify/storage"));
+            DispatchBackend::Filesystem(FileSystemBackend::new(storage, config.compression).await?)
+        }
+        StorageStrategy::S3 => {
+            DispatchBackend::S3(S3Backend::new(config.s3_config, config.compression).await?)
+        }
+    })
+}